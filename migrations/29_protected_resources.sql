@@ -0,0 +1,36 @@
+--
+-- OpenWorkers Database Schema - Protected Resources
+--
+-- Workers and environments can be marked as protected. Deploying to a protected
+-- worker (or a worker linked to a protected environment) requires either an
+-- interactive confirmation or a single-use approval token.
+--
+
+BEGIN;
+
+-- ============================================================================
+-- PROTECTED FLAGS
+-- ============================================================================
+
+ALTER TABLE workers ADD COLUMN protected boolean NOT NULL DEFAULT false;
+ALTER TABLE environments ADD COLUMN protected boolean NOT NULL DEFAULT false;
+
+-- ============================================================================
+-- DEPLOY APPROVALS
+-- ============================================================================
+
+CREATE TABLE deploy_approvals (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    worker_id uuid NOT NULL REFERENCES workers(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    token_hash varchar(64) NOT NULL UNIQUE,
+    created_by uuid REFERENCES users(id) ON UPDATE CASCADE ON DELETE SET NULL,
+    expires_at timestamp with time zone NOT NULL,
+    used_at timestamp with time zone,
+    created_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_deploy_approvals_worker_id ON deploy_approvals(worker_id);
+
+COMMENT ON TABLE deploy_approvals IS 'Single-use tokens authorizing a deploy to a protected worker. Tokens are SHA-256 hashed.';
+
+COMMIT;
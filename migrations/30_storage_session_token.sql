@@ -0,0 +1,14 @@
+--
+-- OpenWorkers Database Schema - Storage Session Token
+--
+-- Optional session token so storage configs can use temporary credentials
+-- (STS, R2 temporary access) in addition to long-lived access keys.
+--
+
+BEGIN;
+
+ALTER TABLE storage_configs ADD COLUMN session_token text;
+
+COMMENT ON COLUMN storage_configs.session_token IS 'Session token for temporary credentials, sent as x-amz-security-token';
+
+COMMIT;
@@ -0,0 +1,16 @@
+--
+-- OpenWorkers Database Schema - API Key Scopes
+--
+-- API keys carry a scope so CI systems can hold deploy-only (or read-only)
+-- credentials instead of full account access. Existing keys keep full access.
+--
+
+BEGIN;
+
+ALTER TABLE api_keys
+    ADD COLUMN scope varchar(16) NOT NULL DEFAULT 'admin'
+    CHECK (scope IN ('read', 'deploy', 'admin'));
+
+COMMENT ON COLUMN api_keys.scope IS 'read: read-only, deploy: read + deploys/uploads, admin: full access.';
+
+COMMIT;
@@ -0,0 +1,24 @@
+--
+-- OpenWorkers Database Schema - CLI Operations
+--
+-- Mutating CLI operations run against a DB alias are recorded with their
+-- request ID, so `ow trace <id>` can look up what happened.
+--
+
+BEGIN;
+
+CREATE TABLE cli_operations (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id varchar(64) NOT NULL UNIQUE,
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    operation varchar(100) NOT NULL,
+    status varchar(16) NOT NULL CHECK (status IN ('ok', 'error')),
+    error text,
+    created_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_cli_operations_user_id ON cli_operations(user_id);
+
+COMMENT ON TABLE cli_operations IS 'Outcome of mutating CLI operations, keyed by the request ID printed on failure.';
+
+COMMIT;
@@ -0,0 +1,20 @@
+--
+-- OpenWorkers Database Schema - Deployment Retention
+--
+-- Every deploy stores a full copy of the code. Users can cap how many
+-- deployments are kept per worker (NULL keeps all), and tagged deployments
+-- are never pruned.
+--
+
+BEGIN;
+
+ALTER TABLE worker_deployments ADD COLUMN tag varchar(64);
+
+ALTER TABLE users
+    ADD COLUMN deployment_retention integer
+    CHECK (deployment_retention IS NULL OR deployment_retention > 0);
+
+COMMENT ON COLUMN worker_deployments.tag IS 'Optional label (e.g. stable). Tagged deployments are never pruned.';
+COMMENT ON COLUMN users.deployment_retention IS 'Deployments kept per worker after each deploy, NULL keeps all.';
+
+COMMIT;
@@ -0,0 +1,63 @@
+--
+-- OpenWorkers Database Schema - Account Secrets
+--
+-- Secrets shared across environments. An environment value referencing one
+-- (`@account/KEY`) keeps a copy of its value, refreshed whenever the secret
+-- is rotated, so workers read it like any other secret.
+--
+
+BEGIN;
+
+CREATE TABLE account_secrets (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    key character varying(255) NOT NULL,
+    value character varying(255) NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    updated_at timestamp with time zone NOT NULL DEFAULT now(),
+    UNIQUE (user_id, key)
+);
+
+ALTER TABLE environment_values
+    ADD COLUMN account_secret_id uuid REFERENCES account_secrets(id) ON UPDATE CASCADE ON DELETE RESTRICT;
+
+CREATE INDEX idx_environment_values_account_secret_id ON environment_values(account_secret_id);
+
+-- Referencing values always carry the secret's current value
+CREATE OR REPLACE FUNCTION copy_account_secret_value()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.account_secret_id IS NOT NULL THEN
+        SELECT value INTO NEW.value FROM account_secrets WHERE id = NEW.account_secret_id;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER copy_account_secret_value
+    BEFORE INSERT OR UPDATE ON environment_values
+    FOR EACH ROW
+    EXECUTE FUNCTION copy_account_secret_value();
+
+-- Rotating a secret updates every environment referencing it
+CREATE OR REPLACE FUNCTION propagate_account_secret()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE environment_values
+    SET value = NEW.value, updated_at = now()
+    WHERE account_secret_id = NEW.id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER propagate_account_secret
+    AFTER UPDATE OF value ON account_secrets
+    FOR EACH ROW
+    EXECUTE FUNCTION propagate_account_secret();
+
+COMMENT ON TABLE account_secrets IS 'Account-scoped secrets, referenced from environments as @account/KEY.';
+COMMENT ON COLUMN environment_values.account_secret_id IS 'Account secret this value mirrors, NULL for regular values.';
+
+COMMIT;
@@ -0,0 +1,14 @@
+--
+-- OpenWorkers Database Schema - Secret Rotation
+--
+-- Records when a secret value was last rotated (`ow env rotate`), as opposed
+-- to renamed or retyped, which only touches updated_at.
+--
+
+BEGIN;
+
+ALTER TABLE environment_values ADD COLUMN rotated_at timestamp with time zone;
+
+COMMENT ON COLUMN environment_values.rotated_at IS 'Last time the secret value was rotated, NULL if never.';
+
+COMMIT;
@@ -0,0 +1,41 @@
+--
+-- OpenWorkers Database Schema - Worker Values
+--
+-- Variables and secrets set on a single worker, overriding the values of its
+-- linked environment with the same key. Runners read worker_runtime_values,
+-- which applies that precedence.
+--
+
+BEGIN;
+
+CREATE TABLE worker_values (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    worker_id uuid NOT NULL REFERENCES workers(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    key character varying(255) NOT NULL,
+    value character varying(255) NOT NULL,
+    type enum_binding_type NOT NULL DEFAULT 'var',
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    updated_at timestamp with time zone NOT NULL DEFAULT now(),
+    UNIQUE (worker_id, key),
+    CONSTRAINT worker_values_plain_types CHECK (type IN ('var', 'secret'))
+);
+
+CREATE INDEX idx_worker_values_worker_id ON worker_values(worker_id);
+
+-- Effective values per worker: worker values win over environment values
+CREATE OR REPLACE VIEW worker_runtime_values AS
+SELECT wv.worker_id, wv.key, wv.value, wv.type, 'worker'::text AS source
+FROM worker_values wv
+UNION ALL
+SELECT w.id AS worker_id, ev.key, ev.value, ev.type, 'environment'::text AS source
+FROM workers w
+JOIN environment_values ev ON ev.environment_id = w.environment_id
+WHERE NOT EXISTS (
+    SELECT 1 FROM worker_values wv WHERE wv.worker_id = w.id AND wv.key = ev.key
+);
+
+COMMENT ON TABLE worker_values IS 'Per-worker vars and secrets overriding the linked environment.';
+COMMENT ON VIEW worker_runtime_values IS 'Values a worker sees at runtime, after applying worker overrides.';
+
+COMMIT;
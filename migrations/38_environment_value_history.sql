@@ -0,0 +1,68 @@
+--
+-- OpenWorkers Database Schema - Environment Value History
+--
+-- Every change to an environment value, recorded by trigger so edits from the
+-- dashboard, the API and the CLI are all covered. A NULL value means the key
+-- was removed. Rows go away with their environment.
+--
+
+BEGIN;
+
+CREATE TABLE environment_value_history (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    environment_id uuid NOT NULL REFERENCES environments(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    key character varying(255) NOT NULL,
+    value character varying(255),
+    type enum_binding_type,
+    changed_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_environment_value_history_key
+    ON environment_value_history(environment_id, key, changed_at);
+
+CREATE OR REPLACE FUNCTION record_environment_value_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO environment_value_history (environment_id, user_id, key, value, type)
+        VALUES (NEW.environment_id, NEW.user_id, NEW.key, NEW.value, NEW.type);
+        RETURN NEW;
+    END IF;
+
+    -- Skip values removed along with their environment
+    IF NOT EXISTS (SELECT 1 FROM environments WHERE id = OLD.environment_id) THEN
+        RETURN OLD;
+    END IF;
+
+    IF TG_OP = 'DELETE' OR NEW.key <> OLD.key THEN
+        INSERT INTO environment_value_history (environment_id, user_id, key, value, type)
+        VALUES (OLD.environment_id, OLD.user_id, OLD.key, NULL, NULL);
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+
+    IF NEW.key <> OLD.key
+        OR NEW.value IS DISTINCT FROM OLD.value
+        OR NEW.type IS DISTINCT FROM OLD.type THEN
+        INSERT INTO environment_value_history (environment_id, user_id, key, value, type)
+        VALUES (NEW.environment_id, NEW.user_id, NEW.key, NEW.value, NEW.type);
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER environment_values_history
+    AFTER INSERT OR UPDATE OR DELETE ON environment_values
+    FOR EACH ROW EXECUTE FUNCTION record_environment_value_change();
+
+-- Values present before this migration are their own first entry
+INSERT INTO environment_value_history (environment_id, user_id, key, value, type, changed_at)
+SELECT environment_id, user_id, key, value, type, updated_at
+FROM environment_values;
+
+COMMENT ON TABLE environment_value_history IS 'Past values of environment keys, NULL value when the key was removed.';
+
+COMMIT;
@@ -0,0 +1,35 @@
+--
+-- OpenWorkers Database Schema - Organizations
+--
+-- An organization owns resources through its own account row in users, so
+-- every table keeps its user_id scoping: a member acting for an organization
+-- operates as the organization's account. Membership decides who may do so.
+--
+
+BEGIN;
+
+CREATE TYPE enum_organization_role AS ENUM ('owner', 'member');
+
+CREATE TABLE organizations (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    name character varying(255) UNIQUE NOT NULL,
+    -- Account owning the organization's workers, environments and resources
+    account_id uuid UNIQUE NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    updated_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE TABLE organization_members (
+    organization_id uuid NOT NULL REFERENCES organizations(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    role enum_organization_role NOT NULL DEFAULT 'member',
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX idx_organization_members_user_id ON organization_members(user_id);
+
+COMMENT ON TABLE organizations IS 'Teams sharing resources through a dedicated account.';
+COMMENT ON TABLE organization_members IS 'Users allowed to act as an organization account.';
+
+COMMIT;
@@ -0,0 +1,25 @@
+--
+-- OpenWorkers Database Schema - Worker Shares
+--
+-- Grants another account access to a single worker without sharing a login.
+-- Viewers can read the worker and its deployments, editors can also deploy
+-- and change its values and URLs. Deleting and sharing stay with the owner.
+--
+
+BEGIN;
+
+CREATE TYPE enum_share_role AS ENUM ('viewer', 'editor');
+
+CREATE TABLE worker_shares (
+    worker_id uuid NOT NULL REFERENCES workers(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    role enum_share_role NOT NULL DEFAULT 'viewer',
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (worker_id, user_id)
+);
+
+CREATE INDEX idx_worker_shares_user_id ON worker_shares(user_id);
+
+COMMENT ON TABLE worker_shares IS 'Per-worker access granted to accounts other than the owner.';
+
+COMMIT;
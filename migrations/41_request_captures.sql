@@ -0,0 +1,27 @@
+--
+-- OpenWorkers Database Schema - Request Captures
+--
+-- Requests recorded by the runtime alongside the logs, so a failing request
+-- can be re-sent with `ow workers replay <name> --log-id <id>` after a fix.
+--
+
+BEGIN;
+
+CREATE TABLE request_captures (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    worker_id uuid NOT NULL REFERENCES workers(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    method character varying(16) NOT NULL,
+    -- Path and query string, without the host
+    path text NOT NULL,
+    headers jsonb NOT NULL DEFAULT '{}'::jsonb,
+    body text,
+    -- Status the worker answered with, when the response completed
+    status smallint,
+    captured_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_request_captures_worker_id ON request_captures(worker_id, captured_at DESC);
+
+COMMENT ON TABLE request_captures IS 'Requests recorded by the runtime for replay.';
+
+COMMIT;
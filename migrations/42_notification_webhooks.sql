@@ -0,0 +1,23 @@
+--
+-- OpenWorkers Database Schema - Notification Webhooks
+--
+-- Per-user webhooks POSTed a JSON payload after deploys, rollbacks and
+-- deletions. The CLI fires them today; the platform may later do the same.
+--
+
+BEGIN;
+
+CREATE TABLE notification_webhooks (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id uuid NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    url text NOT NULL,
+    -- Subset of 'deploy', 'rollback', 'delete'
+    events text[] NOT NULL DEFAULT '{deploy,rollback,delete}',
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    updated_at timestamp with time zone NOT NULL DEFAULT now(),
+    UNIQUE (user_id, url)
+);
+
+COMMENT ON TABLE notification_webhooks IS 'Webhooks notified after deploys, rollbacks and deletions.';
+
+COMMIT;
@@ -0,0 +1,14 @@
+--
+-- OpenWorkers Database Schema - Worker Metadata
+--
+-- Freeform fields (owner, repo, runbook, tags...) set with `ow workers meta set`,
+-- so on-call engineers can find where a worker comes from and who owns it.
+--
+
+BEGIN;
+
+ALTER TABLE workers ADD COLUMN metadata jsonb NOT NULL DEFAULT '{}'::jsonb;
+
+COMMENT ON COLUMN workers.metadata IS 'Freeform string fields such as owner, repo, runbook and tags.';
+
+COMMIT;
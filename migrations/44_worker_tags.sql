@@ -0,0 +1,16 @@
+--
+-- OpenWorkers Database Schema - Worker Tags
+--
+-- `key=value` labels set with `ow workers create/update --tag team=payments`
+-- and matched by `ow workers list --tag team=payments`.
+--
+
+BEGIN;
+
+ALTER TABLE workers ADD COLUMN tags jsonb NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX idx_workers_tags ON workers USING gin (tags);
+
+COMMENT ON COLUMN workers.tags IS 'key=value labels used to group and filter workers.';
+
+COMMIT;
@@ -1,52 +1,366 @@
+use super::cache::ResponseCache;
 use super::{
-    AssetManifestEntry, Backend, BackendError, CreateDatabaseInput, CreateEnvironmentInput,
-    CreateKvInput, CreateStorageInput, CreateWorkerInput, Database, DeployInput, Deployment,
-    Environment, KvNamespace, Project, StorageConfig, UpdateEnvironmentInput, UpdateWorkerInput,
-    UploadResult, Worker,
+    AccountSecret, ApiToken, AssetManifestEntry, Backend, BackendError, CapturedRequest,
+    CreateDatabaseInput, CreateEnvironmentInput, CreateKvInput, CreateStorageInput,
+    CreateTokenInput, CreateWorkerInput, Database, DeletePreview, DeployApproval,
+    DeployAuthorization, DeployInput, Deployment, DirectUploadConfig, Environment,
+    EnvironmentValue, EnvironmentValueChange, ExecResult, IssuedToken, KvEntry, KvNamespace,
+    NotificationEvent, NotificationWebhook, Organization, PlatformStorage, Project, RequestContext,
+    ResourceKind, ResourceUsage, SearchResult, StorageConfig, TraceRecord, UpdateDatabaseInput,
+    UpdateEnvironmentInput, UpdateKvInput, UpdateStorageInput, UpdateWorkerInput, UploadResult,
+    Worker, WorkerEvent, WorkerRuntime, WorkerShare, WorkerUrl,
 };
-use crate::config::DEFAULT_API_URL;
+use crate::config::{DEFAULT_API_URL, PlatformStorageConfig};
+use crate::timings::{Phase, TimedSend};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use reqwest::Client;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Archives above this size are sent in resumable chunks
+const CHUNKED_UPLOAD_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Size of each chunk in a resumable upload
+const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Concurrent requests when looking up the deployments of a listing
+const LIST_CONCURRENCY: usize = 16;
+
+/// Consecutive failed chunks before giving up
+const MAX_CHUNK_FAILURES: u32 = 5;
+
+/// `If-Match` value pinning a deploy to the worker version it was based on,
+/// so the server rejects it with 412 if another deploy landed in between
+fn version_tag(current_version: Option<i32>) -> String {
+    format!("\"v{}\"", current_version.unwrap_or(0))
+}
+
+fn deploy_conflict(name: &str) -> BackendError {
+    BackendError::Api(format!(
+        "Another deploy to '{}' landed first. Re-run to deploy on top of it.",
+        name
+    ))
+}
+
+/// Error for an unsuccessful response. Gateway errors mean the API is down or
+/// overloaded; anything else carries the message the API sent.
+async fn error_response(response: reqwest::Response) -> BackendError {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+
+    match status {
+        reqwest::StatusCode::BAD_GATEWAY
+        | reqwest::StatusCode::SERVICE_UNAVAILABLE
+        | reqwest::StatusCode::GATEWAY_TIMEOUT => BackendError::Unavailable(if text.is_empty() {
+            status.to_string()
+        } else {
+            text
+        }),
+        _ => BackendError::Api(text),
+    }
+}
+
+/// Percent-encode a user-supplied path segment such as a KV key, which may
+/// contain `/`, `?` or spaces
+fn path_segment(segment: &str) -> String {
+    // Form encoding writes spaces as `+`; a literal `+` is already `%2B`
+    url::form_urlencoded::byte_serialize(segment.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// Resumable upload session (`POST /workers/{id}/uploads`).
+/// `offset` is how many bytes the server already has.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadSession {
+    upload_id: String,
+    offset: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadOffset {
+    offset: usize,
+}
 
 pub struct ApiBackend {
     client: Client,
     base_url: String,
+    /// Where requests are sent: `base_url`, or its `ow daemon` counterpart
+    request_url: String,
     token: Option<String>,
+    /// Organization acted for, sent as `X-Organization`
+    organization: Option<String>,
+    cache: Option<ResponseCache>,
 }
 
 impl ApiBackend {
     pub fn new(base_url: String, token: Option<String>, insecure: bool) -> Self {
-        let mut builder = Client::builder().danger_accept_invalid_certs(insecure);
-
-        // Resolve *.localhost domains to 127.0.0.1 (RFC 6761)
-        if let Ok(url) = reqwest::Url::parse(&base_url) {
-            if let Some(host) = url.host_str() {
-                if host.ends_with(".localhost") {
-                    let port = url.port_or_known_default().unwrap_or(443);
-                    let addr: std::net::SocketAddr = ([127, 0, 0, 1], port).into();
-                    builder = builder.resolve(host, addr);
-                }
-            }
-        }
+        #[cfg(unix)]
+        let proxied = crate::commands::daemon::proxy_client(&base_url, insecure);
+        #[cfg(not(unix))]
+        let proxied = None;
 
-        let client = builder.build().expect("Failed to build HTTP client");
+        let (client, request_url) =
+            proxied.unwrap_or_else(|| (Self::http_client(&base_url, insecure), base_url.clone()));
+        let cache = ResponseCache::new(&base_url, token.as_deref(), None);
 
         Self {
             client,
             base_url,
+            request_url,
             token,
+            organization: None,
+            cache,
+        }
+    }
+
+    /// Act for an organization instead of the token's personal account
+    pub fn with_organization(mut self, organization: Option<String>) -> Self {
+        self.cache = ResponseCache::new(
+            &self.base_url,
+            self.token.as_deref(),
+            organization.as_deref(),
+        );
+        self.organization = organization;
+        self
+    }
+
+    /// Direct HTTP client for an API URL
+    pub fn http_client(base_url: &str, insecure: bool) -> Client {
+        let mut builder = Client::builder().danger_accept_invalid_certs(insecure);
+
+        // Resolve *.localhost domains to 127.0.0.1 (RFC 6761)
+        if let Ok(url) = reqwest::Url::parse(base_url)
+            && let Some(host) = url.host_str()
+            && host.ends_with(".localhost")
+        {
+            let port = url.port_or_known_default().unwrap_or(443);
+            let addr: std::net::SocketAddr = ([127, 0, 0, 1], port).into();
+            builder = builder.resolve(host, addr);
         }
+
+        builder.build().expect("Failed to build HTTP client")
     }
 
     fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
+        let url = format!("{}{}", self.request_url, path);
+        let mutating = method != reqwest::Method::GET;
         let mut req = self.client.request(method, &url);
 
+        // Cached listings may be stale once anything changes
+        if let Some(cache) = self.cache.as_ref().filter(|_| mutating) {
+            cache.invalidate();
+        }
+
         if let Some(token) = &self.token {
             req = req.bearer_auth(token);
         }
 
+        if let Some(organization) = &self.organization {
+            req = req.header("X-Organization", organization);
+        }
+
+        if let Some(context) = super::request_context().filter(|_| mutating) {
+            req = req.header("X-Request-Id", &context.id);
+            context.mark_sent();
+        }
+
         req
     }
+
+    /// Live deployment of a worker already looked up
+    async fn current_deployment(
+        &self,
+        worker: &Worker,
+    ) -> Result<Option<Deployment>, BackendError> {
+        if worker.current_version.is_none() {
+            return Ok(None);
+        }
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/deployments/current", worker.id),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let deployment: Deployment = response.json().await?;
+        Ok(Some(deployment))
+    }
+
+    /// Map the common error statuses, leaving successful responses untouched.
+    async fn check_upload_response(
+        response: reqwest::Response,
+        name: &str,
+    ) -> Result<reqwest::Response, BackendError> {
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Err(deploy_conflict(name));
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(response)
+    }
+
+    /// Upload a large archive in ranged chunks. The session is keyed by the
+    /// archive hash, so re-running the same upload resumes where the server
+    /// left off, and a failed chunk is retried from the server's offset.
+    ///
+    /// Returns None when the server has no upload sessions (404 or 405 on an
+    /// existing worker), so the caller falls back to a single multipart upload.
+    async fn upload_worker_chunked(
+        &self,
+        name: &str,
+        worker_id: &str,
+        if_match: &str,
+        zip_data: &[u8],
+        assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
+    ) -> Result<Option<UploadResult>, BackendError> {
+        use sha2::{Digest, Sha256};
+
+        let total = zip_data.len();
+        let hash = hex::encode(Sha256::digest(zip_data));
+
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/uploads", worker_id),
+            )
+            .json(&serde_json::json!({ "size": total, "sha256": hash }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED
+        ) {
+            return Ok(None);
+        }
+
+        let session: UploadSession = Self::check_upload_response(response, name)
+            .await?
+            .json()
+            .await?;
+
+        let session_path = format!("/workers/{}/uploads/{}", worker_id, session.upload_id);
+        let mut offset = session.offset.min(total);
+        let mut failures = 0;
+
+        if offset > 0 {
+            crate::steps::note(&format!("  Resuming upload at {} KB", offset / 1024));
+        }
+
+        while offset < total {
+            let end = (offset + UPLOAD_CHUNK_SIZE).min(total);
+
+            let result = self
+                .request(reqwest::Method::PUT, &session_path)
+                .header(
+                    "Content-Range",
+                    format!("bytes {}-{}/{}", offset, end - 1, total),
+                )
+                .header("Content-Type", "application/octet-stream")
+                .body(zip_data[offset..end].to_vec())
+                .timed_send(Phase::Upload)
+                .await;
+
+            let chunk = match result {
+                Ok(response) => match Self::check_upload_response(response, name).await {
+                    Ok(response) => response.json::<UploadOffset>().await.map_err(Into::into),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e.into()),
+            };
+
+            match chunk {
+                // An acknowledgement that does not move forward would resend
+                // the same chunk forever
+                Ok(ack) if ack.offset.min(total) <= offset => {
+                    return Err(BackendError::Api(format!(
+                        "Upload of '{}' stalled: the server acknowledged offset {} for a chunk sent at {}",
+                        name, ack.offset, offset
+                    )));
+                }
+                Ok(ack) => {
+                    offset = ack.offset.min(total);
+                    failures = 0;
+                }
+                Err(BackendError::Unauthorized) => return Err(BackendError::Unauthorized),
+                Err(e) => {
+                    failures += 1;
+
+                    if failures >= MAX_CHUNK_FAILURES {
+                        return Err(e);
+                    }
+
+                    crate::steps::note(&format!("  Chunk upload failed ({}), resuming...", e));
+
+                    // Ask the server how much it actually received. An error
+                    // body is no offset, the chunk is then resent as is.
+                    let ack = match self
+                        .request(reqwest::Method::GET, &session_path)
+                        .timed_send(Phase::Request)
+                        .await
+                    {
+                        Ok(response) if response.status().is_success() => {
+                            response.json::<UploadOffset>().await.ok()
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(ack) = ack {
+                        offset = ack.offset.min(total);
+                    }
+                }
+            }
+        }
+
+        let response = self
+            .request(reqwest::Method::POST, &format!("{}/complete", session_path))
+            .header("If-Match", if_match)
+            .json(&serde_json::json!({
+                "assets": assets_manifest,
+                "approveToken": authorization.approve_token,
+                "confirmed": authorization.confirmed,
+            }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        let result: UploadResult = Self::check_upload_response(response, name)
+            .await?
+            .json()
+            .await?;
+
+        Ok(Some(result))
+    }
 }
 
 impl Backend for ApiBackend {
@@ -55,9 +369,13 @@ impl Backend for ApiBackend {
     }
 
     async fn list_workers(&self) -> Result<Vec<Worker>, BackendError> {
+        if let Some(workers) = self.cache.as_ref().and_then(|c| c.get("/workers")) {
+            return Ok(workers);
+        }
+
         let response = self
             .request(reqwest::Method::GET, "/workers")
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -65,18 +383,22 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let workers: Vec<Worker> = response.json().await?;
+
+        if let Some(cache) = &self.cache {
+            cache.put("/workers", &workers);
+        }
+
         Ok(workers)
     }
 
     async fn get_worker(&self, name: &str) -> Result<Worker, BackendError> {
         let response = self
             .request(reqwest::Method::GET, &format!("/workers/{}", name))
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -91,8 +413,7 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let worker: Worker = response.json().await?;
@@ -103,7 +424,7 @@ impl Backend for ApiBackend {
         let response = self
             .request(reqwest::Method::POST, "/workers")
             .json(&input)
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -111,8 +432,7 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let worker: Worker = response.json().await?;
@@ -122,7 +442,7 @@ impl Backend for ApiBackend {
     async fn delete_worker(&self, name: &str) -> Result<(), BackendError> {
         let response = self
             .request(reqwest::Method::DELETE, &format!("/workers/{}", name))
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -137,13 +457,40 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         Ok(())
     }
 
+    async fn preview_delete_worker(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/dependents", name),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let preview: DeletePreview = response.json().await?;
+        Ok(preview)
+    }
+
     async fn update_worker(
         &self,
         name: &str,
@@ -152,7 +499,7 @@ impl Backend for ApiBackend {
         let response = self
             .request(reqwest::Method::PATCH, &format!("/workers/{}", name))
             .json(&input)
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -167,8 +514,7 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let worker: Worker = response.json().await?;
@@ -183,7 +529,7 @@ impl Backend for ApiBackend {
         let response = self
             .request(reqwest::Method::PATCH, &format!("/workers/{}", worker_id))
             .json(&serde_json::json!({ "environment": environment_id }))
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -198,22 +544,71 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         Ok(())
     }
 
+    async fn set_worker_metadata(
+        &self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Worker, BackendError> {
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("/workers/{}", name))
+            .json(&serde_json::json!({ "metadata": metadata }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let worker: Worker = response.json().await?;
+        Ok(worker)
+    }
+
+    async fn get_current_deployment(&self, name: &str) -> Result<Option<Deployment>, BackendError> {
+        let worker = self.get_worker(name).await?;
+        self.current_deployment(&worker).await
+    }
+
+    async fn list_current_deployments(
+        &self,
+        workers: &[Worker],
+    ) -> Result<Vec<Option<Deployment>>, BackendError> {
+        let lookups: Vec<_> = workers.iter().map(|w| self.current_deployment(w)).collect();
+
+        stream::iter(lookups)
+            .buffered(LIST_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
     async fn deploy_worker(
         &self,
         name: &str,
         input: DeployInput,
     ) -> Result<Deployment, BackendError> {
+        let worker = self.get_worker(name).await?;
+
         let response = self
             .request(reqwest::Method::POST, &format!("/workers/{}/deploy", name))
+            .header("If-Match", version_tag(worker.current_version))
             .json(&input)
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -227,9 +622,12 @@ impl Backend for ApiBackend {
             return Err(BackendError::Unauthorized);
         }
 
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Err(deploy_conflict(name));
+        }
+
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let deployment: Deployment = response.json().await?;
@@ -242,11 +640,33 @@ impl Backend for ApiBackend {
         _path: &std::path::Path,
         zip_data: Vec<u8>,
         assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
     ) -> Result<UploadResult, BackendError> {
         use reqwest::multipart::{Form, Part};
 
         // First resolve worker name to ID
         let worker = self.get_worker(name).await?;
+        let if_match = version_tag(worker.current_version);
+
+        if zip_data.len() > CHUNKED_UPLOAD_THRESHOLD {
+            let chunked = self
+                .upload_worker_chunked(
+                    name,
+                    &worker.id,
+                    &if_match,
+                    &zip_data,
+                    assets_manifest,
+                    authorization,
+                )
+                .await?;
+
+            match chunked {
+                Some(result) => return Ok(result),
+                None => crate::steps::note(
+                    "  Resumable uploads are not supported by this server, sending in one request",
+                ),
+            }
+        }
 
         let part = Part::bytes(zip_data)
             .file_name("upload.zip")
@@ -261,13 +681,22 @@ impl Backend for ApiBackend {
             form = form.text("assets", manifest_json);
         }
 
+        if let Some(token) = &authorization.approve_token {
+            form = form.text("approveToken", token.clone());
+        }
+
+        if authorization.confirmed {
+            form = form.text("confirmed", "true");
+        }
+
         let response = self
             .request(
                 reqwest::Method::POST,
                 &format!("/workers/{}/upload", worker.id),
             )
+            .header("If-Match", if_match)
             .multipart(form)
-            .send()
+            .timed_send(Phase::Upload)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
@@ -281,56 +710,72 @@ impl Backend for ApiBackend {
             return Err(BackendError::Unauthorized);
         }
 
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Err(deploy_conflict(name));
+        }
+
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         let result: UploadResult = response.json().await?;
         Ok(result)
     }
 
-    // Project methods
-    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
-        Err(BackendError::Api(
-            "Projects require DB access. Use a DB alias.".to_string(),
-        ))
-    }
-
-    async fn delete_project(&self, _name: &str) -> Result<(), BackendError> {
-        Err(BackendError::Api(
-            "Projects require DB access. Use a DB alias.".to_string(),
-        ))
-    }
+    async fn create_deploy_approval(
+        &self,
+        name: &str,
+        ttl_seconds: i64,
+    ) -> Result<DeployApproval, BackendError> {
+        let worker = self.get_worker(name).await?;
 
-    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
         let response = self
-            .request(reqwest::Method::GET, "/environments")
-            .send()
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/approvals", worker.id),
+            )
+            .json(&serde_json::json!({ "ttlSeconds": ttl_seconds }))
+            .timed_send(Phase::Request)
             .await?;
 
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(BackendError::Unauthorized);
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let environments: Vec<Environment> = response.json().await?;
-        Ok(environments)
+        let approval: DeployApproval = response.json().await?;
+        Ok(approval)
     }
 
-    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+    async fn exec_worker(
+        &self,
+        name: &str,
+        event: WorkerEvent,
+    ) -> Result<ExecResult, BackendError> {
+        let worker = self.get_worker(name).await?;
+
         let response = self
-            .request(reqwest::Method::GET, &format!("/environments/{}", name))
-            .send()
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/exec", worker.id),
+            )
+            .json(&event)
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Environment '{}' not found",
+                "Worker '{}' not found",
                 name
             )));
         }
@@ -340,52 +785,62 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let environment: Environment = response.json().await?;
-        Ok(environment)
+        let result: ExecResult = response.json().await?;
+        Ok(result)
     }
 
-    async fn create_environment(
-        &self,
-        input: CreateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
+    async fn worker_runtime(&self, name: &str) -> Result<WorkerRuntime, BackendError> {
+        let worker = self.get_worker(name).await?;
+
         let response = self
-            .request(reqwest::Method::POST, "/environments")
-            .json(&input)
-            .send()
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/runtime", worker.id),
+            )
+            .timed_send(Phase::Request)
             .await?;
 
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(BackendError::Unauthorized);
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let environment: Environment = response.json().await?;
-        Ok(environment)
+        let runtime: WorkerRuntime = response.json().await?;
+        Ok(runtime)
     }
 
-    async fn update_environment(
+    async fn get_captured_request(
         &self,
         name: &str,
-        input: UpdateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
+        id: &str,
+    ) -> Result<CapturedRequest, BackendError> {
+        let worker = self.get_worker(name).await?;
+
         let response = self
-            .request(reqwest::Method::PATCH, &format!("/environments/{}", name))
-            .json(&input)
-            .send()
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/requests/{}", worker.id, id),
+            )
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Environment '{}' not found",
-                name
+                "No captured request '{}' for worker '{}'",
+                id, name
             )));
         }
 
@@ -394,23 +849,1216 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let environment: Environment = response.json().await?;
-        Ok(environment)
+        let request: CapturedRequest = response.json().await?;
+        Ok(request)
     }
 
-    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
-        let response = self
-            .request(reqwest::Method::DELETE, &format!("/environments/{}", name))
-            .send()
-            .await?;
-
+    async fn redeploy_worker(
+        &self,
+        name: &str,
+        message: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/redeploy", worker.id),
+            )
+            .json(&serde_json::json!({ "message": message }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' has no active deployment",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let deployment: Deployment = response.json().await?;
+        Ok(deployment)
+    }
+
+    async fn tag_deployment(
+        &self,
+        name: &str,
+        version: i32,
+        tag: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::PATCH,
+                &format!("/workers/{}/deployments/{}", worker.id, version),
+            )
+            .json(&serde_json::json!({ "tag": tag }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Version {} of worker '{}' not found",
+                version, name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let deployment: Deployment = response.json().await?;
+        Ok(deployment)
+    }
+
+    async fn list_deployments(&self, name: &str) -> Result<Vec<Deployment>, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/deployments", worker.id),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let mut deployments: Vec<Deployment> = response.json().await?;
+        deployments.sort_by_key(|d| d.version);
+        Ok(deployments)
+    }
+
+    async fn get_deployment_code(&self, name: &str, version: i32) -> Result<Vec<u8>, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/deployments/{}/code", worker.id, version),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Version {} of worker '{}' not found",
+                version, name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn prune_deployments(&self, name: &str, keep: u32) -> Result<Vec<i32>, BackendError> {
+        #[derive(Deserialize)]
+        struct PruneResponse {
+            deleted: Vec<i32>,
+        }
+
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/deployments/prune", worker.id),
+            )
+            .json(&serde_json::json!({ "keep": keep }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let pruned: PruneResponse = response.json().await?;
+        Ok(pruned.deleted)
+    }
+
+    async fn get_retention_policy(&self) -> Result<Option<u32>, BackendError> {
+        #[derive(Deserialize)]
+        struct RetentionPolicy {
+            keep: Option<u32>,
+        }
+
+        let response = self
+            .request(reqwest::Method::GET, "/settings/retention")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let policy: RetentionPolicy = response.json().await?;
+        Ok(policy.keep)
+    }
+
+    async fn set_retention_policy(&self, keep: Option<u32>) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::PUT, "/settings/retention")
+            .json(&serde_json::json!({ "keep": keep }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_urls(&self, name: &str) -> Result<Vec<WorkerUrl>, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/urls", worker.id),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let urls: Vec<WorkerUrl> = response.json().await?;
+        Ok(urls)
+    }
+
+    async fn add_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/workers/{}/urls", worker.id),
+            )
+            .json(url)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn remove_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/workers/{}/urls", worker.id),
+            )
+            .json(url)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "'{}' does not point at worker '{}'",
+                url, name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_values(&self, name: &str) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/workers/{}/values", worker.id),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let values: Vec<EnvironmentValue> = response.json().await?;
+        Ok(values)
+    }
+
+    async fn set_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+        value_type: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/workers/{}/values/{}", worker.id, key),
+            )
+            .json(&serde_json::json!({ "value": value, "type": value_type }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let value: EnvironmentValue = response.json().await?;
+        Ok(value)
+    }
+
+    async fn delete_worker_value(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let worker = self.get_worker(name).await?;
+
+        let response = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/workers/{}/values/{}", worker.id, key),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' has no value '{}'",
+                name, key
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_shares(&self, name: &str) -> Result<Vec<WorkerShare>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/workers/{}/shares", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let shares: Vec<WorkerShare> = response.json().await?;
+        Ok(shares)
+    }
+
+    async fn share_worker(
+        &self,
+        name: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/workers/{}/shares/{}", name, username),
+            )
+            .json(&serde_json::json!({ "role": role }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' or user '{}' not found",
+                name, username
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn unshare_worker(&self, name: &str, username: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/workers/{}/shares/{}", name, username),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' is not shared with '{}'",
+                name, username
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    // Project methods
+    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
+        Err(BackendError::Api(
+            "Projects require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn delete_project(&self, _name: &str) -> Result<(), BackendError> {
+        Err(BackendError::Api(
+            "Projects require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn preview_delete_project(&self, _name: &str) -> Result<DeletePreview, BackendError> {
+        Err(BackendError::Api(
+            "Projects require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn add_project_worker(&self, _project: &str, _worker: &str) -> Result<(), BackendError> {
+        Err(BackendError::Api(
+            "Projects require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn remove_project_worker(
+        &self,
+        _project: &str,
+        _worker: &str,
+    ) -> Result<(), BackendError> {
+        Err(BackendError::Api(
+            "Projects require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
+        if let Some(environments) = self.cache.as_ref().and_then(|c| c.get("/environments")) {
+            return Ok(environments);
+        }
+
+        let response = self
+            .request(reqwest::Method::GET, "/environments")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let environments: Vec<Environment> = response.json().await?;
+
+        if let Some(cache) = &self.cache {
+            cache.put("/environments", &environments);
+        }
+
+        Ok(environments)
+    }
+
+    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/environments/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let environment: Environment = response.json().await?;
+        Ok(environment)
+    }
+
+    async fn create_environment(
+        &self,
+        input: CreateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let response = self
+            .request(reqwest::Method::POST, "/environments")
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let environment: Environment = response.json().await?;
+        Ok(environment)
+    }
+
+    async fn update_environment(
+        &self,
+        name: &str,
+        input: UpdateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("/environments/{}", name))
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let environment: Environment = response.json().await?;
+        Ok(environment)
+    }
+
+    async fn rotate_environment_secret(
+        &self,
+        env: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/environments/{}/values/{}/rotate", env, key),
+            )
+            .json(&serde_json::json!({ "value": value }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Secret '{}' not found in environment '{}'",
+                key, env
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let value: EnvironmentValue = response.json().await?;
+        Ok(value)
+    }
+
+    async fn environment_value_history(
+        &self,
+        env: &str,
+        key: &str,
+    ) -> Result<Vec<EnvironmentValueChange>, BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/environments/{}/values/{}/history", env, key),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                env
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let changes: Vec<EnvironmentValueChange> = response.json().await?;
+        Ok(changes)
+    }
+
+    async fn environment_values_at(
+        &self,
+        env: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let at: String = url::form_urlencoded::byte_serialize(at.to_rfc3339().as_bytes()).collect();
+
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/environments/{}/values?at={}", env, at),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                env
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let values: Vec<EnvironmentValue> = response.json().await?;
+        Ok(values)
+    }
+
+    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::DELETE, &format!("/environments/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    // Storage methods
+    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/storage")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let configs: Vec<StorageConfig> = response.json().await?;
+        Ok(configs)
+    }
+
+    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/storage/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Storage '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let config: StorageConfig = response.json().await?;
+        Ok(config)
+    }
+
+    async fn create_storage(
+        &self,
+        input: CreateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        let response = self
+            .request(reqwest::Method::POST, "/storage")
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let config: StorageConfig = response.json().await?;
+        Ok(config)
+    }
+
+    async fn update_storage(
+        &self,
+        name: &str,
+        input: UpdateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("/storage/{}", name))
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Storage '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let config: StorageConfig = response.json().await?;
+        Ok(config)
+    }
+
+    async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::DELETE, &format!("/storage/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Storage '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn get_storage_credentials(
+        &self,
+        _name: &str,
+    ) -> Result<DirectUploadConfig, BackendError> {
+        Err(BackendError::Api(
+            "Storage credentials require DB access. Use a DB alias.".to_string(),
+        ))
+    }
+
+    async fn get_platform_storage(&self) -> Result<Option<PlatformStorage>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/storage/platform")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let storage: PlatformStorage = response.json().await?;
+        Ok(Some(storage))
+    }
+
+    async fn set_platform_storage(
+        &self,
+        storage: PlatformStorageConfig,
+    ) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::PUT, "/storage/platform")
+            .json(&serde_json::json!({
+                "endpoint": storage.endpoint,
+                "bucket": storage.bucket,
+                "accessKeyId": storage.access_key_id,
+                "secretAccessKey": storage.secret_access_key,
+                "region": storage.region,
+                "prefix": storage.prefix,
+                "sessionToken": storage.session_token,
+            }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    // KV methods
+    async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/kv")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let namespaces: Vec<KvNamespace> = response.json().await?;
+        Ok(namespaces)
+    }
+
+    async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/kv/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let namespace: KvNamespace = response.json().await?;
+        Ok(namespace)
+    }
+
+    async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
+        let response = self
+            .request(reqwest::Method::POST, "/kv")
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let namespace: KvNamespace = response.json().await?;
+        Ok(namespace)
+    }
+
+    async fn update_kv(
+        &self,
+        name: &str,
+        input: UpdateKvInput,
+    ) -> Result<KvNamespace, BackendError> {
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("/kv/{}", name))
+            .json(&input)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let namespace: KvNamespace = response.json().await?;
+        Ok(namespace)
+    }
+
+    async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::DELETE, &format!("/kv/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn list_kv_entries(
+        &self,
+        name: &str,
+        prefix: &str,
+    ) -> Result<Vec<KvEntry>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/kv/{}/entries", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let mut entries: Vec<KvEntry> = response.json().await?;
+        entries.retain(|e| e.key.starts_with(prefix));
+        Ok(entries)
+    }
+
+    async fn put_kv_entries(
+        &self,
+        name: &str,
+        entries: Vec<KvEntry>,
+    ) -> Result<usize, BackendError> {
+        let count = entries.len();
+
+        let response = self
+            .request(reqwest::Method::PUT, &format!("/kv/{}/entries", name))
+            .json(&entries)
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(count)
+    }
+
+    async fn get_kv_entry(&self, name: &str, key: &str) -> Result<KvEntry, BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/kv/{}/entries/{}", name, path_segment(key)),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Key '{}' not found in KV namespace '{}'",
+                key, name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(response.json().await?)
+    }
+
+    async fn put_kv_entry(
+        &self,
+        name: &str,
+        entry: KvEntry,
+        ttl: Option<i64>,
+    ) -> Result<(), BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/kv/{}/entries/{}", name, path_segment(&entry.key)),
+            )
+            .json(&serde_json::json!({ "value": entry.value, "ttl": ttl }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_kv_entry(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/kv/{}/entries/{}", name, path_segment(key)),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Key '{}' not found in KV namespace '{}'",
+                key, name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    async fn count_expired_kv_entries(&self, _name: &str) -> Result<u64, BackendError> {
+        Err(BackendError::Api(
+            "Expired keys are purged by the server. Use a DB alias to scan them.".to_string(),
+        ))
+    }
+
+    async fn delete_expired_kv_entries(
+        &self,
+        _name: &str,
+        _limit: u32,
+    ) -> Result<u64, BackendError> {
+        Err(BackendError::Api(
+            "Expired keys are purged by the server. Use a DB alias to scan them.".to_string(),
+        ))
+    }
+
+    // Database methods
+    async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/databases")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let databases: Vec<Database> = response.json().await?;
+        Ok(databases)
+    }
+
+    async fn get_database(&self, name: &str) -> Result<Database, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/databases/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Environment '{}' not found",
+                "Database '{}' not found",
                 name
             )));
         }
@@ -420,18 +2068,18 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        Ok(())
+        let database: Database = response.json().await?;
+        Ok(database)
     }
 
-    // Storage methods
-    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+    async fn create_database(&self, input: CreateDatabaseInput) -> Result<Database, BackendError> {
         let response = self
-            .request(reqwest::Method::GET, "/storage")
-            .send()
+            .request(reqwest::Method::POST, "/databases")
+            .json(&input)
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -439,23 +2087,27 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let configs: Vec<StorageConfig> = response.json().await?;
-        Ok(configs)
+        let database: Database = response.json().await?;
+        Ok(database)
     }
 
-    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+    async fn update_database(
+        &self,
+        name: &str,
+        input: UpdateDatabaseInput,
+    ) -> Result<Database, BackendError> {
         let response = self
-            .request(reqwest::Method::GET, &format!("/storage/{}", name))
-            .send()
+            .request(reqwest::Method::PATCH, &format!("/databases/{}", name))
+            .json(&input)
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Storage '{}' not found",
+                "Database '{}' not found",
                 name
             )));
         }
@@ -465,22 +2117,43 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let config: StorageConfig = response.json().await?;
-        Ok(config)
+        let database: Database = response.json().await?;
+        Ok(database)
     }
 
-    async fn create_storage(
-        &self,
-        input: CreateStorageInput,
-    ) -> Result<StorageConfig, BackendError> {
+    async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
         let response = self
-            .request(reqwest::Method::POST, "/storage")
+            .request(reqwest::Method::DELETE, &format!("/databases/{}", name))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "Database '{}' not found",
+                name
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    // Token methods
+    async fn create_token(&self, input: CreateTokenInput) -> Result<IssuedToken, BackendError> {
+        let response = self
+            .request(reqwest::Method::POST, "/tokens")
             .json(&input)
-            .send()
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -488,23 +2161,40 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let config: StorageConfig = response.json().await?;
-        Ok(config)
+        let token: IssuedToken = response.json().await?;
+        Ok(token)
     }
 
-    async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>, BackendError> {
         let response = self
-            .request(reqwest::Method::DELETE, &format!("/storage/{}", name))
-            .send()
+            .request(reqwest::Method::GET, "/tokens")
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let tokens: Vec<ApiToken> = response.json().await?;
+        Ok(tokens)
+    }
+
+    async fn revoke_token(&self, name: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::DELETE, &format!("/tokens/{}", name))
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Storage '{}' not found",
+                "Token '{}' not found",
                 name
             )));
         }
@@ -514,40 +2204,69 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         Ok(())
     }
 
-    // KV methods
-    async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
-        let response = self.request(reqwest::Method::GET, "/kv").send().await?;
+    // Organization methods
+    async fn list_organizations(&self) -> Result<Vec<Organization>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/organizations")
+            .timed_send(Phase::Request)
+            .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(BackendError::Unauthorized);
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let namespaces: Vec<KvNamespace> = response.json().await?;
-        Ok(namespaces)
+        let organizations: Vec<Organization> = response.json().await?;
+        Ok(organizations)
     }
 
-    async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
+    async fn create_organization(&self, name: &str) -> Result<Organization, BackendError> {
         let response = self
-            .request(reqwest::Method::GET, &format!("/kv/{}", name))
-            .send()
+            .request(reqwest::Method::POST, "/organizations")
+            .json(&serde_json::json!({ "name": name }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let organization: Organization = response.json().await?;
+        Ok(organization)
+    }
+
+    async fn add_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        let response = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/organizations/{}/members/{}", org, username),
+            )
+            .json(&serde_json::json!({ "role": role }))
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "KV namespace '{}' not found",
-                name
+                "Organization '{}' or user '{}' not found",
+                org, username
             )));
         }
 
@@ -556,19 +2275,48 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let namespace: KvNamespace = response.json().await?;
-        Ok(namespace)
+        Ok(())
     }
 
-    async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
+    async fn remove_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<(), BackendError> {
         let response = self
-            .request(reqwest::Method::POST, "/kv")
-            .json(&input)
-            .send()
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/organizations/{}/members/{}", org, username),
+            )
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "'{}' is not a member of organization '{}'",
+                username, org
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    // Account secret methods
+    async fn list_account_secrets(&self) -> Result<Vec<AccountSecret>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/secrets")
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -576,24 +2324,46 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let namespace: KvNamespace = response.json().await?;
-        Ok(namespace)
+        let secrets: Vec<AccountSecret> = response.json().await?;
+        Ok(secrets)
     }
 
-    async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
+    async fn set_account_secret(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<AccountSecret, BackendError> {
         let response = self
-            .request(reqwest::Method::DELETE, &format!("/kv/{}", name))
-            .send()
+            .request(reqwest::Method::PUT, &format!("/secrets/{}", key))
+            .json(&serde_json::json!({ "value": value }))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let secret: AccountSecret = response.json().await?;
+        Ok(secret)
+    }
+
+    async fn delete_account_secret(&self, key: &str) -> Result<(), BackendError> {
+        let response = self
+            .request(reqwest::Method::DELETE, &format!("/secrets/{}", key))
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "KV namespace '{}' not found",
-                name
+                "Account secret '{}' not found",
+                key
             )));
         }
 
@@ -602,64 +2372,98 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         Ok(())
     }
 
-    // Database methods
-    async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
+    // Cross-reference methods
+    async fn resource_usage(
+        &self,
+        kind: ResourceKind,
+        name: &str,
+    ) -> Result<ResourceUsage, BackendError> {
         let response = self
-            .request(reqwest::Method::GET, "/databases")
-            .send()
+            .request(
+                reqwest::Method::GET,
+                &format!("/resources/{}/{}/usage", kind.as_str(), name),
+            )
+            .timed_send(Phase::Request)
             .await?;
 
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "{} '{}' not found",
+                kind.as_str(),
+                name
+            )));
+        }
+
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(BackendError::Unauthorized);
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let databases: Vec<Database> = response.json().await?;
-        Ok(databases)
+        let usage: ResourceUsage = response.json().await?;
+        Ok(usage)
     }
 
-    async fn get_database(&self, name: &str) -> Result<Database, BackendError> {
+    // Search methods
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, BackendError> {
+        let query: String = url::form_urlencoded::byte_serialize(query.as_bytes()).collect();
+
         let response = self
-            .request(reqwest::Method::GET, &format!("/databases/{}", name))
-            .send()
+            .request(
+                reqwest::Method::GET,
+                &format!("/search?q={}&limit={}", query, limit),
+            )
+            .timed_send(Phase::Request)
             .await?;
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            return Err(BackendError::NotFound(format!(
-                "Database '{}' not found",
-                name
-            )));
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
         }
 
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let results: Vec<SearchResult> = response.json().await?;
+        Ok(results)
+    }
+
+    // Notification methods
+    async fn list_notification_webhooks(&self) -> Result<Vec<NotificationWebhook>, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, "/notifications/webhooks")
+            .timed_send(Phase::Request)
+            .await?;
+
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(BackendError::Unauthorized);
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let database: Database = response.json().await?;
-        Ok(database)
+        let webhooks: Vec<NotificationWebhook> = response.json().await?;
+        Ok(webhooks)
     }
 
-    async fn create_database(&self, input: CreateDatabaseInput) -> Result<Database, BackendError> {
+    async fn set_notification_webhook(
+        &self,
+        url: &str,
+        events: &[NotificationEvent],
+    ) -> Result<NotificationWebhook, BackendError> {
         let response = self
-            .request(reqwest::Method::POST, "/databases")
-            .json(&input)
-            .send()
+            .request(reqwest::Method::PUT, "/notifications/webhooks")
+            .json(&serde_json::json!({ "url": url, "events": events }))
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -667,24 +2471,33 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
-        let database: Database = response.json().await?;
-        Ok(database)
+        let webhook: NotificationWebhook = response.json().await?;
+        Ok(webhook)
     }
 
-    async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
+    async fn delete_notification_webhook(&self, url: &str) -> Result<(), BackendError> {
+        let webhook = self
+            .list_notification_webhooks()
+            .await?
+            .into_iter()
+            .find(|w| w.url == url)
+            .ok_or_else(|| BackendError::NotFound(format!("Webhook '{}' not found", url)))?;
+
         let response = self
-            .request(reqwest::Method::DELETE, &format!("/databases/{}", name))
-            .send()
+            .request(
+                reqwest::Method::DELETE,
+                &format!("/notifications/webhooks/{}", webhook.id),
+            )
+            .timed_send(Phase::Request)
             .await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Err(BackendError::NotFound(format!(
-                "Database '{}' not found",
-                name
+                "Webhook '{}' not found",
+                url
             )));
         }
 
@@ -693,10 +2506,233 @@ impl Backend for ApiBackend {
         }
 
         if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            return Err(BackendError::Api(text));
+            return Err(error_response(response).await);
         }
 
         Ok(())
     }
+
+    // Tracing methods
+    async fn record_operation(
+        &self,
+        _context: &RequestContext,
+        _error: Option<&str>,
+    ) -> Result<(), BackendError> {
+        Ok(())
+    }
+
+    async fn get_trace(&self, request_id: &str) -> Result<TraceRecord, BackendError> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/traces/{}", request_id))
+            .timed_send(Phase::Request)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BackendError::NotFound(format!(
+                "No record of request '{}'",
+                request_id
+            )));
+        }
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BackendError::Unauthorized);
+        }
+
+        if !response.status().is_success() {
+            return Err(error_response(response).await);
+        }
+
+        let record: TraceRecord = response.json().await?;
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http1::{self, Request, Response};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+
+    /// Local API answering with `handler`, and the requests it saw as
+    /// "METHOD path [content-range]"
+    async fn mock_api(handler: Handler) -> (ApiBackend, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (handler, log) = (handler.clone(), log.clone());
+
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut reader = tokio::io::BufReader::new(reader);
+
+                    while let Ok(Some(request)) = http1::read_request(&mut reader).await {
+                        let range = request.header("content-range").unwrap_or_default();
+                        let line = format!("{} {} {}", request.method, request.path, range);
+                        log.lock().unwrap().push(line.trim_end().to_string());
+
+                        let response = handler(&request);
+
+                        if http1::write_response(&mut writer, &response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        // Built by hand so a running `ow daemon` or the response cache stay out of it
+        let backend = ApiBackend {
+            client: Client::new(),
+            base_url: base_url.clone(),
+            request_url: base_url,
+            token: None,
+            organization: None,
+            cache: None,
+        };
+
+        (backend, seen)
+    }
+
+    fn json(status: u16, body: serde_json::Value) -> Response {
+        Response {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn upload_result() -> serde_json::Value {
+        serde_json::json!({
+            "success": true,
+            "worker": { "id": "w1", "name": "api", "url": "https://api.workers.dev" },
+            "deployed": { "version": 2, "functions": 0 },
+            "assets": null,
+        })
+    }
+
+    async fn upload_chunked(
+        backend: &ApiBackend,
+        data: &[u8],
+    ) -> Result<Option<UploadResult>, BackendError> {
+        backend
+            .upload_worker_chunked("api", "w1", "\"v1\"", data, &[], &Default::default())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_resumes_at_server_offset() {
+        let handler: Handler = Arc::new(|request| match request.method.as_str() {
+            "POST" if request.path.ends_with("/complete") => json(200, upload_result()),
+            "POST" => json(200, serde_json::json!({ "uploadId": "u1", "offset": 40 })),
+            _ => json(200, serde_json::json!({ "offset": 100 })),
+        });
+        let (backend, seen) = mock_api(handler).await;
+
+        let result = upload_chunked(&backend, &[7; 100]).await.unwrap();
+
+        assert!(result.unwrap().success);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "POST /workers/w1/uploads",
+                "PUT /workers/w1/uploads/u1 bytes 40-99/100",
+                "POST /workers/w1/uploads/u1/complete",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_stalled_ack() {
+        let handler: Handler = Arc::new(|request| match request.method.as_str() {
+            "POST" => json(200, serde_json::json!({ "uploadId": "u1", "offset": 40 })),
+            _ => json(200, serde_json::json!({ "offset": 40 })),
+        });
+        let (backend, seen) = mock_api(handler).await;
+
+        let err = upload_chunked(&backend, &[7; 100]).await.unwrap_err();
+
+        assert!(err.to_string().contains("stalled"));
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_ignores_offset_of_failed_recovery() {
+        let puts = AtomicUsize::new(0);
+        let handler: Handler = Arc::new(move |request| match request.method.as_str() {
+            "POST" if request.path.ends_with("/complete") => json(200, upload_result()),
+            "POST" => json(200, serde_json::json!({ "uploadId": "u1", "offset": 0 })),
+            "PUT" if puts.fetch_add(1, Ordering::SeqCst) == 0 => {
+                Response::error(500, "boom".to_string())
+            }
+            "PUT" => json(200, serde_json::json!({ "offset": 100 })),
+            // An error page that happens to parse as an offset
+            _ => json(500, serde_json::json!({ "offset": 100 })),
+        });
+        let (backend, seen) = mock_api(handler).await;
+
+        upload_chunked(&backend, &[7; 100]).await.unwrap();
+
+        // The chunk is resent instead of skipped
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "POST /workers/w1/uploads",
+                "PUT /workers/w1/uploads/u1 bytes 0-99/100",
+                "GET /workers/w1/uploads/u1",
+                "PUT /workers/w1/uploads/u1 bytes 0-99/100",
+                "POST /workers/w1/uploads/u1/complete",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_falls_back_to_multipart() {
+        let handler: Handler = Arc::new(|request| match request.path.as_str() {
+            "/workers/api" => json(
+                200,
+                serde_json::json!({
+                    "id": "w1",
+                    "name": "api",
+                    "description": null,
+                    "currentVersion": 1,
+                    "environment": null,
+                    "createdAt": "2026-01-01T00:00:00Z",
+                    "updatedAt": "2026-01-01T00:00:00Z",
+                }),
+            ),
+            "/workers/w1/uploads" => Response::error(404, "Not Found".to_string()),
+            "/workers/w1/upload" => json(200, upload_result()),
+            _ => Response::error(500, "unexpected".to_string()),
+        });
+        let (backend, seen) = mock_api(handler).await;
+
+        let zip_data = vec![7; CHUNKED_UPLOAD_THRESHOLD + 1];
+        let result = backend
+            .upload_worker(
+                "api",
+                std::path::Path::new("."),
+                zip_data,
+                &[],
+                &Default::default(),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "GET /workers/api",
+                "POST /workers/w1/uploads",
+                "POST /workers/w1/upload",
+            ]
+        );
+    }
 }
@@ -0,0 +1,134 @@
+//! Short-lived on-disk cache for expensive read-only API responses.
+//!
+//! Entries live in ~/.openworkers/cache/<account>/ and expire after a few
+//! seconds; any mutating request through the same alias drops them.
+
+use crate::config::Config;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+const CACHE_DIR: &str = "cache";
+
+/// Overrides the default TTL, in seconds (0 disables the cache)
+const TTL_ENV: &str = "OW_CACHE_TTL";
+
+const DEFAULT_TTL: Duration = Duration::from_secs(10);
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Bypass the cache for this invocation (`--no-cache`)
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+fn ttl(value: Option<&str>) -> Duration {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TTL)
+}
+
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Cache scoped to one API, token and organization, or None when caching is off
+    pub fn new(base_url: &str, token: Option<&str>, organization: Option<&str>) -> Option<Self> {
+        let ttl = ttl(std::env::var(TTL_ENV).ok().as_deref());
+
+        if DISABLED.load(Ordering::Relaxed) || ttl.is_zero() {
+            return None;
+        }
+
+        let account = Sha256::digest(format!(
+            "{}\n{}\n{}",
+            base_url,
+            token.unwrap_or_default(),
+            organization.unwrap_or_default()
+        ));
+        let dir = Config::config_dir()
+            .ok()?
+            .join(CACHE_DIR)
+            .join(&hex::encode(account)[..16]);
+
+        Some(Self { dir, ttl })
+    }
+
+    fn entry(&self, path: &str) -> PathBuf {
+        let key = hex::encode(Sha256::digest(path));
+        self.dir.join(format!("{}.json", &key[..16]))
+    }
+
+    /// Cached response for `path`, if still fresh
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Option<T> {
+        let entry = self.entry(path);
+        let modified = std::fs::metadata(&entry).ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).ok()?;
+
+        if age > self.ttl {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(entry).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store a response (best effort)
+    pub fn put<T: Serialize>(&self, path: &str, value: &T) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        if let Ok(content) = serde_json::to_string(value) {
+            std::fs::write(self.entry(path), content).ok();
+        }
+    }
+
+    /// Drop every cached response for this account
+    pub fn invalidate(&self) {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_roundtrip_and_invalidate() {
+        let dir = std::env::temp_dir().join(format!("ow-cache-{}", uuid::Uuid::new_v4()));
+        let cache = ResponseCache {
+            dir: dir.clone(),
+            ttl: DEFAULT_TTL,
+        };
+
+        assert_eq!(cache.get::<Vec<String>>("/workers"), None);
+
+        cache.put("/workers", &vec!["api".to_string()]);
+        assert_eq!(
+            cache.get::<Vec<String>>("/workers"),
+            Some(vec!["api".to_string()])
+        );
+        assert_eq!(cache.get::<Vec<String>>("/environments"), None);
+
+        cache.invalidate();
+        assert_eq!(cache.get::<Vec<String>>("/workers"), None);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_ttl() {
+        assert_eq!(ttl(None), DEFAULT_TTL);
+        assert_eq!(ttl(Some("30")), Duration::from_secs(30));
+        assert_eq!(ttl(Some("0")), Duration::ZERO);
+        assert_eq!(ttl(Some("soon")), DEFAULT_TTL);
+    }
+}
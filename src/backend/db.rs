@@ -1,44 +1,153 @@
 use super::{
-    AssetManifestEntry, Backend, BackendError, CreateDatabaseInput, CreateEnvironmentInput,
-    CreateKvInput, CreateStorageInput, CreateWorkerInput, Database, DeployInput, DeployedInfo,
-    Deployment, DirectUploadConfig, Environment, EnvironmentValue, KvNamespace, Project,
-    StorageConfig, UpdateEnvironmentInput, UpdateWorkerInput, UploadResult, UploadWorkerInfo,
-    Worker,
+    ACCOUNT_SECRET_PREFIX, AccountSecret, ApiToken, AssetManifestEntry, Backend, BackendError,
+    CapturedRequest, CreateDatabaseInput, CreateEnvironmentInput, CreateKvInput,
+    CreateStorageInput, CreateTokenInput, CreateWorkerInput, Database, DeletePreview,
+    DeployApproval, DeployAuthorization, DeployInput, DeployedInfo, Deployment, DirectUploadConfig,
+    Environment, EnvironmentValue, EnvironmentValueChange, ExecResult, IssuedToken, KvEntry,
+    KvNamespace, NotificationEvent, NotificationWebhook, Organization, PlatformStorage, Project,
+    RequestContext, ResourceBinding, ResourceKind, ResourceUsage, SearchResult, StorageConfig,
+    TraceRecord, UpdateDatabaseInput, UpdateEnvironmentInput, UpdateKvInput, UpdateStorageInput,
+    UpdateWorkerInput, UploadResult, UploadWorkerInfo, Worker, WorkerEvent, WorkerRuntime,
+    WorkerShare, WorkerUrl, prunable_versions,
 };
-use crate::config::PlatformStorageConfig;
-use serde::Deserialize;
+use crate::config::{self, Config, PlatformStorageConfig};
+use crate::routes::RoutesConfig;
+use crate::timings::{Phase, TimedExt};
+use rand::RngCore;
 use sha2::{Digest, Sha256};
-use sqlx::{PgPool, Row};
-use std::collections::HashMap;
+use sqlx::postgres::{PgConnection, PgPoolOptions};
+use sqlx::{Connection, PgPool, Row};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 use zip::ZipArchive;
 
-#[derive(Debug, Deserialize)]
-struct RoutesConfig {
-    #[serde(default)]
-    immutable: Vec<String>,
-    #[serde(rename = "static", default)]
-    static_routes: Vec<String>,
-    #[serde(default)]
-    prerendered: Vec<String>,
-    #[serde(default)]
-    #[allow(dead_code)]
-    functions: Vec<FunctionRoute>,
-    #[serde(default)]
-    #[allow(dead_code)]
-    ssr: Vec<String>,
+/// How long a deploy waits for one already running on the same worker
+const DEPLOY_LOCK_TIMEOUT: &str = "5min";
+
+/// Overrides the default statement timeout, in seconds (`0` for no limit)
+const STATEMENT_TIMEOUT_ENV: &str = "OW_STATEMENT_TIMEOUT";
+
+const DEFAULT_STATEMENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+static STATEMENT_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Database and server process of the connection a command runs on, so
+/// Ctrl-C can cancel its query from a second connection
+static DATABASE_URL: Mutex<Option<String>> = Mutex::new(None);
+static BACKEND_PID: AtomicI32 = AtomicI32::new(0);
+
+/// Set when the command only reads, so an alias's `read_url` can serve it
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Let a read replica serve this invocation (read-only commands, from `main`)
+pub fn prefer_read_replica() {
+    READ_ONLY.store(true, Ordering::Relaxed);
+}
+
+/// Use `timeout` for this invocation (`--statement-timeout`)
+pub fn set_statement_timeout(timeout: Duration) {
+    STATEMENT_TIMEOUT.get_or_init(|| timeout);
+}
+
+fn parse_statement_timeout(value: Option<&str>) -> Duration {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_STATEMENT_TIMEOUT)
+}
+
+/// Server-side limit on each statement: `--statement-timeout`, then
+/// `OW_STATEMENT_TIMEOUT`, then 60 seconds. Zero means no limit.
+pub fn statement_timeout() -> Duration {
+    *STATEMENT_TIMEOUT.get_or_init(|| {
+        parse_statement_timeout(std::env::var(STATEMENT_TIMEOUT_ENV).ok().as_deref())
+    })
+}
+
+/// Connect the single-connection pool a DB alias runs its command on. Every
+/// statement is bounded by the statement timeout, so a wedged or distant
+/// database fails the command instead of hanging it.
+///
+/// Read-only commands go to `read_url` when the alias has one. The whole
+/// command runs there, so it never reads its own writes from a lagging replica.
+pub async fn connect(database_url: &str, read_url: Option<&str>) -> Result<PgPool, sqlx::Error> {
+    let database_url = match read_url {
+        Some(read_url) if READ_ONLY.load(Ordering::Relaxed) => read_url,
+        _ => database_url,
+    };
+
+    let timeout_ms = statement_timeout().as_millis().to_string();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .after_connect(move |conn, _| {
+            let timeout_ms = timeout_ms.clone();
+
+            Box::pin(async move {
+                sqlx::query("SELECT set_config('statement_timeout', $1, false)")
+                    .bind(timeout_ms)
+                    .execute(&mut *conn)
+                    .await?;
+
+                let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+                    .fetch_one(&mut *conn)
+                    .await?;
+                BACKEND_PID.store(pid, Ordering::Relaxed);
+
+                Ok(())
+            })
+        })
+        .connect(database_url)
+        .timed(Phase::Connect)
+        .await?;
+
+    *DATABASE_URL.lock().unwrap() = Some(database_url.to_string());
+
+    Ok(pool)
+}
+
+/// Ask the server to cancel the statement running for this command, if any.
+/// Closing the socket alone lets it run on until it next writes to the client.
+pub async fn cancel_running_query() -> bool {
+    let pid = BACKEND_PID.load(Ordering::Relaxed);
+    let url = DATABASE_URL.lock().unwrap().clone();
+
+    let Some(url) = url.filter(|_| pid != 0) else {
+        return false;
+    };
+
+    let cancel = async {
+        let mut conn = PgConnection::connect(&url).await?;
+
+        sqlx::query_scalar::<_, bool>("SELECT pg_cancel_backend($1)")
+            .bind(pid)
+            .fetch_one(&mut conn)
+            .await
+    };
+
+    matches!(
+        tokio::time::timeout(Duration::from_secs(5), cancel).await,
+        Ok(Ok(true))
+    )
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct FunctionRoute {
-    pattern: String,
-    worker: String,
+/// Access needed on a worker, checked against its shares
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Write,
 }
 
 pub struct DbBackend {
     pool: PgPool,
+    /// Account owning the resources: the user's, or the selected organization's
     user_id: uuid::Uuid,
+    /// The signed-in user, for organization membership and as the author of
+    /// deployments and approvals
+    member_id: uuid::Uuid,
     platform_storage: Option<PlatformStorageConfig>,
 }
 
@@ -46,6 +155,7 @@ impl DbBackend {
     pub async fn new(
         pool: PgPool,
         username: Option<String>,
+        organization: Option<String>,
         platform_storage: Option<PlatformStorageConfig>,
     ) -> Result<Self, BackendError> {
         let username = username.ok_or_else(|| {
@@ -58,6 +168,7 @@ impl DbBackend {
         let user_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM users WHERE username = $1")
             .bind(&username)
             .fetch_optional(&pool)
+            .timed(Phase::Query)
             .await?
             .ok_or_else(|| {
                 BackendError::NotFound(format!(
@@ -66,9 +177,35 @@ impl DbBackend {
                 ))
             })?;
 
+        // Members act as the organization's account
+        let account_id = match &organization {
+            Some(org) => sqlx::query_scalar(
+                r#"
+                SELECT o.account_id
+                FROM organizations o
+                JOIN organization_members m ON m.organization_id = o.id
+                WHERE o.name = $1 AND m.user_id = $2
+                "#,
+            )
+            .bind(org)
+            .bind(user_id)
+            .fetch_optional(&pool)
+            .timed(Phase::Query)
+            .await?
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "Organization '{}' not found or '{}' is not a member. \
+                     Use 'ow orgs switch --personal' to act as yourself.",
+                    org, username
+                ))
+            })?,
+            None => user_id,
+        };
+
         Ok(Self {
             pool,
-            user_id,
+            user_id: account_id,
+            member_id: user_id,
             platform_storage,
         })
     }
@@ -79,14 +216,18 @@ impl DbBackend {
     ) -> Result<Vec<EnvironmentValue>, BackendError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, key, value, type::text as value_type
-            FROM environment_values
-            WHERE environment_id = $1
-            ORDER BY key
+            SELECT ev.id, ev.key, ev.type::text as value_type, ev.rotated_at,
+                   COALESCE($2 || s.key, ev.value) as value
+            FROM environment_values ev
+            LEFT JOIN account_secrets s ON s.id = ev.account_secret_id
+            WHERE ev.environment_id = $1
+            ORDER BY ev.key
             "#,
         )
         .bind(env_id)
+        .bind(ACCOUNT_SECRET_PREFIX)
         .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
         let values = rows
@@ -96,27 +237,274 @@ impl DbBackend {
                 key: row.get("key"),
                 value: row.get("value"),
                 value_type: row.get("value_type"),
+                rotated_at: row.get("rotated_at"),
             })
             .collect();
 
         Ok(values)
     }
+
+    /// Prune old deployments after a deploy if the account has a policy
+    async fn apply_retention_policy(&self, name: &str) -> Result<(), BackendError> {
+        if let Some(keep) = self.get_retention_policy().await? {
+            self.prune_deployments(name, keep).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Account secret referenced by an environment value (`@account/KEY`), if any
+    async fn account_secret_id(&self, value: &str) -> Result<Option<uuid::Uuid>, BackendError> {
+        let Some(key) = value.strip_prefix(ACCOUNT_SECRET_PREFIX) else {
+            return Ok(None);
+        };
+
+        let id =
+            sqlx::query_scalar("SELECT id FROM account_secrets WHERE key = $1 AND user_id = $2")
+                .bind(key)
+                .bind(self.user_id)
+                .fetch_optional(&self.pool)
+                .timed(Phase::Query)
+                .await?
+                .ok_or_else(|| {
+                    BackendError::NotFound(format!("Account secret '{}' not found", key))
+                })?;
+
+        Ok(Some(id))
+    }
+
+    /// Serialize deploys to one worker with a transaction-scoped advisory lock,
+    /// so concurrent deploys queue up instead of interleaving version bumps
+    async fn lock_deploys(
+        conn: &mut sqlx::PgConnection,
+        worker_id: uuid::Uuid,
+        name: &str,
+    ) -> Result<(), BackendError> {
+        const LOCK_KEY: &str = "hashtextextended('ow-deploy:' || $1::text, 0)";
+
+        let acquired: bool =
+            sqlx::query_scalar(&format!("SELECT pg_try_advisory_xact_lock({})", LOCK_KEY))
+                .bind(worker_id)
+                .fetch_one(&mut *conn)
+                .timed(Phase::Query)
+                .await?;
+
+        if acquired {
+            return Ok(());
+        }
+
+        crate::steps::note(&format!(
+            "  Another deploy to '{}' is in progress, waiting for it to finish...",
+            name
+        ));
+
+        // The wait is bounded by the lock timeout rather than the statement timeout
+        let (lock_timeout, statement_timeout): (String, String) = sqlx::query_as(
+            "SELECT current_setting('lock_timeout'), current_setting('statement_timeout')",
+        )
+        .fetch_one(&mut *conn)
+        .timed(Phase::Query)
+        .await?;
+
+        sqlx::query(
+            "SELECT set_config('lock_timeout', $1, true), set_config('statement_timeout', '0', true)",
+        )
+        .bind(DEPLOY_LOCK_TIMEOUT)
+        .execute(&mut *conn)
+        .timed(Phase::Query)
+        .await?;
+
+        let locked = sqlx::query(&format!("SELECT pg_advisory_xact_lock({})", LOCK_KEY))
+            .bind(worker_id)
+            .execute(&mut *conn)
+            .timed(Phase::Query)
+            .await;
+
+        locked.map_err(|e| deploy_lock_error(e, name))?;
+
+        // The rest of the deploy runs under the configured timeouts again
+        sqlx::query(
+            "SELECT set_config('lock_timeout', $1, true), set_config('statement_timeout', $2, true)",
+        )
+        .bind(lock_timeout)
+        .bind(statement_timeout)
+        .execute(&mut *conn)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Refuse a deploy to a protected worker (or one linked to a protected
+    /// environment) unless it was confirmed or carries an approval token. The
+    /// token is consumed in the deploy transaction, so a failed deploy keeps it.
+    async fn authorize_deploy(
+        conn: &mut sqlx::PgConnection,
+        worker_id: uuid::Uuid,
+        name: &str,
+        authorization: &DeployAuthorization,
+    ) -> Result<(), BackendError> {
+        let protected: bool = sqlx::query_scalar(
+            r#"
+            SELECT w.protected OR COALESCE(e.protected, false)
+            FROM workers w
+            LEFT JOIN environments e ON e.id = w.environment_id
+            WHERE w.id = $1
+            "#,
+        )
+        .bind(worker_id)
+        .fetch_one(&mut *conn)
+        .timed(Phase::Query)
+        .await?;
+
+        if !protected {
+            return Ok(());
+        }
+
+        let Some(token) = &authorization.approve_token else {
+            if authorization.confirmed {
+                return Ok(());
+            }
+
+            return Err(BackendError::Api(format!(
+                "Worker '{}' is protected. Pass --approve-token (see 'ow workers approve').",
+                name
+            )));
+        };
+
+        let token_hash = hex::encode(Sha256::digest(token.as_bytes()));
+
+        // Single statement so a token can't be consumed twice concurrently
+        let result = sqlx::query(
+            r#"
+            UPDATE deploy_approvals
+            SET used_at = now()
+            WHERE token_hash = $1 AND worker_id = $2
+              AND used_at IS NULL AND expires_at > now()
+            "#,
+        )
+        .bind(&token_hash)
+        .bind(worker_id)
+        .execute(&mut *conn)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::Api(
+                "Approval token is invalid, expired or already used".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Owner account and bare name of a worker. Workers shared by another
+    /// user are addressed as `owner/name` and need a matching share.
+    async fn worker_scope(
+        &self,
+        name: &str,
+        access: Access,
+    ) -> Result<(uuid::Uuid, String), BackendError> {
+        let Some((owner, bare)) = name.split_once('/') else {
+            return Ok((self.user_id, name.to_string()));
+        };
+
+        let not_found = || BackendError::NotFound(format!("Worker '{}' not found", name));
+
+        let row = sqlx::query(
+            r#"
+            SELECT w.user_id, s.role::text as role
+            FROM workers w
+            JOIN users u ON u.id = w.user_id
+            LEFT JOIN worker_shares s ON s.worker_id = w.id AND s.user_id = $3
+            WHERE u.username = $1 AND w.name = $2
+            "#,
+        )
+        .bind(owner)
+        .bind(bare)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(not_found)?;
+
+        let owner_id: uuid::Uuid = row.get("user_id");
+        let role: Option<String> = row.get("role");
+
+        if owner_id == self.user_id {
+            return Ok((owner_id, bare.to_string()));
+        }
+
+        match (role.as_deref(), access) {
+            (None, _) => Err(not_found()),
+            (Some("viewer"), Access::Write) => Err(BackendError::Api(format!(
+                "Worker '{}' is shared with you as a viewer; ask {} for editor access",
+                name, owner
+            ))),
+            _ => Ok((owner_id, bare.to_string())),
+        }
+    }
+
+    /// ID of a worker, of the project it belongs to (if any) and of its owner
+    async fn worker_project(
+        &self,
+        name: &str,
+        access: Access,
+    ) -> Result<(uuid::Uuid, Option<uuid::Uuid>, uuid::Uuid), BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, access).await?;
+
+        let row =
+            sqlx::query("SELECT id, project_id FROM workers WHERE name = $1 AND user_id = $2")
+                .bind(&bare)
+                .bind(owner_id)
+                .fetch_optional(&self.pool)
+                .timed(Phase::Query)
+                .await?
+                .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+
+        Ok((row.get("id"), row.get("project_id"), owner_id))
+    }
+
+    /// ID of a worker owned by the current account, for owner-only operations
+    async fn owned_worker_id(&self, name: &str) -> Result<uuid::Uuid, BackendError> {
+        let (worker_id, _, owner_id) = self.worker_project(name, Access::Read).await?;
+
+        if owner_id != self.user_id {
+            return Err(BackendError::Api(format!(
+                "Only the owner can manage who worker '{}' is shared with",
+                name
+            )));
+        }
+
+        Ok(worker_id)
+    }
 }
 
 impl Backend for DbBackend {
     async fn list_workers(&self) -> Result<Vec<Worker>, BackendError> {
         let rows = sqlx::query(
             r#"
-            SELECT w.id, w.name, w."desc", w.current_version, w.created_at, w.updated_at,
-                   e.id as env_id, e.name as env_name
+            SELECT w.id,
+                   CASE WHEN w.user_id = $1 THEN w.name ELSE u.username || '/' || w.name END as name,
+                   w."desc", w.current_version, w.protected, w.metadata, w.tags,
+                   w.created_at, w.updated_at,
+                   e.id as env_id, e.name as env_name, e.protected as env_protected,
+                   (SELECT d.name FROM domains d
+                    WHERE d.worker_id = w.id OR d.project_id = w.id
+                    ORDER BY d.name LIMIT 1) as domain
             FROM workers w
+            JOIN users u ON u.id = w.user_id
             LEFT JOIN environments e ON e.id = w.environment_id
-            WHERE w.user_id = $1 AND w.name IS NOT NULL
-            ORDER BY w.name
+            WHERE w.name IS NOT NULL
+              AND (w.user_id = $1 OR EXISTS (
+                  SELECT 1 FROM worker_shares s WHERE s.worker_id = w.id AND s.user_id = $1
+              ))
+            ORDER BY w.user_id <> $1, name
             "#,
         )
         .bind(self.user_id)
         .fetch_all(&self.pool)
+            .timed(Phase::Query)
         .await?;
 
         let workers = rows
@@ -124,12 +512,14 @@ impl Backend for DbBackend {
             .map(|row| {
                 let env_id: Option<uuid::Uuid> = row.get("env_id");
                 let env_name: Option<String> = row.get("env_name");
+                let env_protected: Option<bool> = row.get("env_protected");
                 let environment =
                     env_id
                         .zip(env_name)
                         .map(|(id, name)| super::WorkerEnvironmentRef {
                             id: id.to_string(),
                             name,
+                            protected: env_protected.unwrap_or(false),
                         });
 
                 Worker {
@@ -138,6 +528,12 @@ impl Backend for DbBackend {
                     description: row.get("desc"),
                     current_version: row.get("current_version"),
                     environment,
+                    protected: row.get("protected"),
+                    url: row
+                        .get::<Option<String>, _>("domain")
+                        .map(|d| format!("https://{}", d)),
+                    metadata: serde_json::from_value(row.get("metadata")).unwrap_or_default(),
+                    tags: serde_json::from_value(row.get("tags")).unwrap_or_default(),
                     created_at: row.get("created_at"),
                     updated_at: row.get("updated_at"),
                 }
@@ -148,36 +544,52 @@ impl Backend for DbBackend {
     }
 
     async fn get_worker(&self, name: &str) -> Result<Worker, BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, Access::Read).await?;
+
         let row = sqlx::query(
             r#"
-            SELECT w.id, w.name, w."desc", w.current_version, w.created_at, w.updated_at,
-                   e.id as env_id, e.name as env_name
+            SELECT w.id, w.name, w."desc", w.current_version, w.protected, w.metadata, w.tags,
+                   w.created_at, w.updated_at,
+                   e.id as env_id, e.name as env_name, e.protected as env_protected,
+                   (SELECT d.name FROM domains d
+                    WHERE d.worker_id = w.id OR d.project_id = w.id
+                    ORDER BY d.name LIMIT 1) as domain
             FROM workers w
             LEFT JOIN environments e ON e.id = w.environment_id
             WHERE w.name = $1 AND w.user_id = $2
             "#,
         )
-        .bind(name)
-        .bind(self.user_id)
+        .bind(&bare)
+        .bind(owner_id)
         .fetch_optional(&self.pool)
+        .timed(Phase::Query)
         .await?
         .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
 
         let env_id: Option<uuid::Uuid> = row.get("env_id");
         let env_name: Option<String> = row.get("env_name");
+        let env_protected: Option<bool> = row.get("env_protected");
         let environment = env_id
             .zip(env_name)
             .map(|(id, name)| super::WorkerEnvironmentRef {
                 id: id.to_string(),
                 name,
+                protected: env_protected.unwrap_or(false),
             });
 
         Ok(Worker {
             id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
+            // Keep the `owner/name` form for shared workers
+            name: name.to_string(),
             description: row.get("desc"),
             current_version: row.get("current_version"),
             environment,
+            protected: row.get("protected"),
+            url: row
+                .get::<Option<String>, _>("domain")
+                .map(|d| format!("https://{}", d)),
+            metadata: serde_json::from_value(row.get("metadata")).unwrap_or_default(),
+            tags: serde_json::from_value(row.get("tags")).unwrap_or_default(),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
@@ -186,15 +598,17 @@ impl Backend for DbBackend {
     async fn create_worker(&self, input: CreateWorkerInput) -> Result<Worker, BackendError> {
         let row = sqlx::query(
             r#"
-            INSERT INTO workers (name, "desc", user_id)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, "desc", current_version, created_at, updated_at
+            INSERT INTO workers (name, "desc", user_id, tags)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, "desc", current_version, protected, tags, created_at, updated_at
             "#,
         )
         .bind(&input.name)
         .bind(&input.description)
         .bind(self.user_id)
+        .bind(serde_json::json!(input.tags))
         .fetch_one(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
         // Note: language is used by API to set initial deployment, DB backend ignores it for now
@@ -205,16 +619,30 @@ impl Backend for DbBackend {
             description: row.get("desc"),
             current_version: row.get("current_version"),
             environment: None,
+            protected: row.get("protected"),
+            url: None,
+            metadata: BTreeMap::new(),
+            tags: serde_json::from_value(row.get("tags")).unwrap_or_default(),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
     }
 
     async fn delete_worker(&self, name: &str) -> Result<(), BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, Access::Write).await?;
+
+        if owner_id != self.user_id {
+            return Err(BackendError::Api(format!(
+                "Only the owner can delete worker '{}'",
+                name
+            )));
+        }
+
         let result = sqlx::query("DELETE FROM workers WHERE name = $1 AND user_id = $2")
-            .bind(name)
-            .bind(self.user_id)
+            .bind(&bare)
+            .bind(owner_id)
             .execute(&self.pool)
+            .timed(Phase::Query)
             .await?;
 
         if result.rows_affected() == 0 {
@@ -227,47 +655,98 @@ impl Backend for DbBackend {
         Ok(())
     }
 
+    async fn preview_delete_worker(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, Access::Read).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM worker_deployments wd WHERE wd.worker_id = w.id) as deployments,
+                (SELECT COUNT(*) FROM project_routes pr WHERE pr.project_id = w.id) as routes,
+                (SELECT COALESCE(array_agg(d.name ORDER BY d.name), '{}')
+                 FROM domains d WHERE d.worker_id = w.id) as domains,
+                (SELECT COUNT(*) FROM crons c
+                 WHERE c.worker_id = w.id AND c.deleted_at IS NULL) as crons,
+                (SELECT sc.bucket || COALESCE('/' || sc.prefix, '')
+                 FROM environment_values ev
+                 JOIN storage_configs sc ON sc.id = ev.value::uuid
+                 WHERE ev.environment_id = w.environment_id AND ev.type = 'assets'
+                 LIMIT 1) as assets
+            FROM workers w
+            WHERE w.name = $1 AND w.user_id = $2
+            "#,
+        )
+        .bind(&bare)
+        .bind(owner_id)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+
+        Ok(DeletePreview {
+            workers: vec![],
+            deployments: row.get("deployments"),
+            routes: row.get("routes"),
+            domains: row.get("domains"),
+            crons: row.get("crons"),
+            assets: row.get("assets"),
+        })
+    }
+
     async fn update_worker(
         &self,
         name: &str,
         input: UpdateWorkerInput,
     ) -> Result<Worker, BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, Access::Write).await?;
+        let unlink = input.environment.as_deref() == Some("");
+
         // Get environment_id if environment is provided (accepts name or UUID)
-        let env_id: Option<uuid::Uuid> = if let Some(env_ref) = &input.environment {
-            // Try parsing as UUID first, then lookup by name
-            if let Ok(uuid) = env_ref.parse::<uuid::Uuid>() {
-                Some(uuid)
-            } else {
-                Some(
-                    sqlx::query_scalar(
-                        "SELECT id FROM environments WHERE name = $1 AND user_id = $2",
+        let env_id: Option<uuid::Uuid> =
+            if let Some(env_ref) = input.environment.as_ref().filter(|e| !e.is_empty()) {
+                // Try parsing as UUID first, then lookup by name
+                if let Ok(uuid) = env_ref.parse::<uuid::Uuid>() {
+                    Some(uuid)
+                } else {
+                    Some(
+                        sqlx::query_scalar(
+                            "SELECT id FROM environments WHERE name = $1 AND user_id = $2",
+                        )
+                        .bind(env_ref)
+                        .bind(owner_id)
+                        .fetch_optional(&self.pool)
+                        .timed(Phase::Query)
+                        .await?
+                        .ok_or_else(|| {
+                            BackendError::NotFound(format!("Environment '{}' not found", env_ref))
+                        })?,
                     )
-                    .bind(env_ref)
-                    .bind(self.user_id)
-                    .fetch_optional(&self.pool)
-                    .await?
-                    .ok_or_else(|| {
-                        BackendError::NotFound(format!("Environment '{}' not found", env_ref))
-                    })?,
-                )
-            }
-        } else {
-            None
-        };
+                }
+            } else {
+                None
+            };
 
         let result = sqlx::query(
             r#"
             UPDATE workers
-            SET environment_id = COALESCE($2, environment_id),
+            SET environment_id = CASE WHEN $6 THEN NULL ELSE COALESCE($2, environment_id) END,
+                protected = COALESCE($4, protected),
+                "desc" = CASE WHEN $5::text IS NULL THEN "desc" ELSE NULLIF($5, '') END,
+                tags = COALESCE($7, tags),
                 updated_at = now()
             WHERE name = $1 AND user_id = $3
             RETURNING id
             "#,
         )
-        .bind(name)
+        .bind(&bare)
         .bind(env_id)
-        .bind(self.user_id)
+        .bind(owner_id)
+        .bind(input.protected)
+        .bind(&input.desc)
+        .bind(unlink)
+        .bind(input.tags.as_ref().map(|tags| serde_json::json!(tags)))
         .fetch_optional(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
         if result.is_none() {
@@ -298,33 +777,148 @@ impl Backend for DbBackend {
             .bind(worker_uuid)
             .bind(env_uuid)
             .execute(&self.pool)
+            .timed(Phase::Query)
             .await?;
 
         Ok(())
     }
 
+    async fn set_worker_metadata(
+        &self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Worker, BackendError> {
+        let (owner_id, bare) = self.worker_scope(name, Access::Write).await?;
+
+        sqlx::query(
+            "UPDATE workers SET metadata = $1, updated_at = now() WHERE name = $2 AND user_id = $3",
+        )
+        .bind(serde_json::json!(metadata))
+        .bind(&bare)
+        .bind(owner_id)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        self.get_worker(name).await
+    }
+
+    async fn get_current_deployment(&self, name: &str) -> Result<Option<Deployment>, BackendError> {
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT d.worker_id, d.version, d.hash, d.code_type::text, d.deployed_at, d.message, d.tag,
+                   u.username as deployed_by
+            FROM worker_deployments d
+            JOIN workers w ON w.id = d.worker_id AND w.current_version = d.version
+            LEFT JOIN users u ON u.id = d.deployed_by
+            WHERE d.worker_id = $1
+            "#,
+        )
+        .bind(worker_id)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?;
+
+        Ok(row.map(|row| Deployment {
+            worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
+            version: row.get("version"),
+            hash: row.get("hash"),
+            code_type: row.get("code_type"),
+            deployed_at: row.get("deployed_at"),
+            deployed_by: row.get("deployed_by"),
+            message: row.get("message"),
+            tag: row.get("tag"),
+        }))
+    }
+
+    async fn list_current_deployments(
+        &self,
+        workers: &[Worker],
+    ) -> Result<Vec<Option<Deployment>>, BackendError> {
+        let ids: Vec<uuid::Uuid> = workers
+            .iter()
+            .filter(|w| w.current_version.is_some())
+            .filter_map(|w| w.id.parse().ok())
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(vec![None; workers.len()]);
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT d.worker_id, d.version, d.hash, d.code_type::text, d.deployed_at, d.message, d.tag,
+                   u.username as deployed_by
+            FROM worker_deployments d
+            JOIN workers w ON w.id = d.worker_id AND w.current_version = d.version
+            LEFT JOIN users u ON u.id = d.deployed_by
+            WHERE d.worker_id = ANY($1)
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let mut deployments: HashMap<String, Deployment> = rows
+            .into_iter()
+            .map(|row| {
+                let deployment = Deployment {
+                    worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
+                    version: row.get("version"),
+                    hash: row.get("hash"),
+                    code_type: row.get("code_type"),
+                    deployed_at: row.get("deployed_at"),
+                    deployed_by: row.get("deployed_by"),
+                    message: row.get("message"),
+                    tag: row.get("tag"),
+                };
+                (deployment.worker_id.clone(), deployment)
+            })
+            .collect();
+
+        Ok(workers.iter().map(|w| deployments.remove(&w.id)).collect())
+    }
+
     async fn deploy_worker(
         &self,
         name: &str,
         input: DeployInput,
     ) -> Result<Deployment, BackendError> {
-        // Get worker ID
-        let worker_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM workers WHERE name = $1")
-            .bind(name)
-            .fetch_optional(&self.pool)
-            .await?
-            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+        let (worker_id, _, _) = self.worker_project(name, Access::Write).await?;
 
         // Calculate hash
         let mut hasher = Sha256::new();
         hasher.update(&input.code);
         let hash = hex::encode(hasher.finalize());
 
+        if let Some(expected) = input
+            .hash
+            .as_ref()
+            .filter(|h| !h.eq_ignore_ascii_case(&hash))
+        {
+            return Err(BackendError::Api(format!(
+                "Checksum mismatch: expected {}, received payload hashes to {}",
+                expected, hash
+            )));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        Self::lock_deploys(&mut tx, worker_id, name).await?;
+        Self::authorize_deploy(&mut tx, worker_id, name, &input.authorization).await?;
+
         // Get next version
         let current_version: Option<i32> =
             sqlx::query_scalar("SELECT MAX(version) FROM worker_deployments WHERE worker_id = $1")
                 .bind(worker_id)
-                .fetch_one(&self.pool)
+                .fetch_one(&mut *tx)
+                .timed(Phase::Query)
                 .await?;
 
         let next_version = current_version.unwrap_or(0) + 1;
@@ -332,9 +926,10 @@ impl Backend for DbBackend {
         // Insert deployment
         let row = sqlx::query(
             r#"
-            INSERT INTO worker_deployments (worker_id, version, hash, code_type, code, message)
-            VALUES ($1, $2, $3, $4::enum_code_type, $5, $6)
-            RETURNING worker_id, version, hash, code_type::text, deployed_at, message
+            INSERT INTO worker_deployments (worker_id, version, hash, code_type, code, message, deployed_by)
+            VALUES ($1, $2, $3, $4::enum_code_type, $5, $6, $7)
+            RETURNING worker_id, version, hash, code_type::text, deployed_at, message, tag,
+                      (SELECT username FROM users WHERE id = deployed_by) as deployed_by
             "#,
         )
         .bind(worker_id)
@@ -343,23 +938,32 @@ impl Backend for DbBackend {
         .bind(&input.code_type)
         .bind(&input.code)
         .bind(&input.message)
-        .fetch_one(&self.pool)
+        .bind(self.member_id)
+        .fetch_one(&mut *tx)
+        .timed(Phase::Query)
         .await?;
 
         // Update worker's current_version
         sqlx::query("UPDATE workers SET current_version = $1 WHERE id = $2")
             .bind(next_version)
             .bind(worker_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .timed(Phase::Query)
             .await?;
 
+        tx.commit().await?;
+
+        self.apply_retention_policy(name).await?;
+
         Ok(Deployment {
             worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
             version: row.get("version"),
             hash: row.get("hash"),
             code_type: row.get("code_type"),
             deployed_at: row.get("deployed_at"),
+            deployed_by: row.get("deployed_by"),
             message: row.get("message"),
+            tag: row.get("tag"),
         })
     }
 
@@ -369,8 +973,10 @@ impl Backend for DbBackend {
         _path: &std::path::Path,
         zip_data: Vec<u8>,
         assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
     ) -> Result<UploadResult, BackendError> {
         // 1. Get worker by name
+        let (owner_id, _) = self.worker_scope(name, Access::Write).await?;
         let worker = self.get_worker(name).await?;
         let worker_id: uuid::Uuid = worker
             .id
@@ -432,15 +1038,16 @@ impl Backend for DbBackend {
                     BackendError::Api(format!("Failed to read _routes.json: {}", e))
                 })?;
                 routes_json = Some(content);
-            } else if filename.contains("functions/") && filename.ends_with(".js") {
-                if let Some(pos) = filename.find("functions/") {
-                    let func_path = &filename[pos..];
-                    let mut content = String::new();
-                    file.read_to_string(&mut content).map_err(|e| {
-                        BackendError::Api(format!("Failed to read function script: {}", e))
-                    })?;
-                    function_scripts.insert(func_path.to_string(), content);
-                }
+            } else if filename.contains("functions/")
+                && filename.ends_with(".js")
+                && let Some(pos) = filename.find("functions/")
+            {
+                let func_path = &filename[pos..];
+                let mut content = String::new();
+                file.read_to_string(&mut content).map_err(|e| {
+                    BackendError::Api(format!("Failed to read function script: {}", e))
+                })?;
+                function_scripts.insert(func_path.to_string(), content);
             }
         }
 
@@ -456,8 +1063,8 @@ impl Backend for DbBackend {
 
         let mut storage_routes = Vec::new();
         let routes = if let Some(ref routes_content) = routes_json {
-            let r: RoutesConfig = serde_json::from_str(routes_content)
-                .map_err(|e| BackendError::Api(format!("Failed to parse routes.json: {}", e)))?;
+            let r = RoutesConfig::parse(routes_content)
+                .map_err(|e| BackendError::Api(e.to_string()))?;
 
             for pattern in &r.immutable {
                 storage_routes.push(serde_json::json!({"pattern": pattern, "priority": 3}));
@@ -477,39 +1084,66 @@ impl Backend for DbBackend {
         };
 
         let mut function_workers = Vec::new();
+        let mut worker_routes = Vec::new();
 
         if let Some(ref routes) = routes {
             for func in &routes.functions {
-                if let Some(script) = function_scripts.get(&func.worker) {
-                    function_workers
-                        .push(serde_json::json!({"pattern": func.pattern, "script": script}));
-                } else {
-                    eprintln!("Warning: function file not found in zip: {}", func.worker);
-                }
+                let script = function_scripts.get(func.script_path()).ok_or_else(|| {
+                    BackendError::Api(format!(
+                        "Function file '{}' for route '{}' not found in the upload",
+                        func.worker, func.pattern
+                    ))
+                })?;
+
+                function_workers
+                    .push(serde_json::json!({"pattern": func.pattern, "script": script}));
+            }
+
+            // Served by the main worker, above storage routes and below functions
+            for pattern in &routes.ssr {
+                worker_routes.push(serde_json::json!({"pattern": pattern, "priority": 5}));
             }
         }
 
         // 4. Call deploy_project — single DB round-trip for all routes + functions
+        let mut tx = self.pool.begin().await?;
+        Self::lock_deploys(&mut tx, worker_id, name).await?;
+        Self::authorize_deploy(&mut tx, worker_id, name, authorization).await?;
+
         let row = sqlx::query(
             r#"
-            SELECT * FROM deploy_project($1, $2, $3, $4, $5::enum_code_type, $6::jsonb, $7::jsonb)
+            SELECT * FROM deploy_project($1, $2, $3, $4, $5::enum_code_type, $6::jsonb, $7::jsonb, $8::jsonb)
             "#,
         )
         .bind(worker_id)
-        .bind(self.user_id)
+        .bind(owner_id)
         .bind(script_bytes)
         .bind(&hash)
         .bind(language)
         .bind(serde_json::Value::Array(storage_routes))
         .bind(serde_json::Value::Array(function_workers))
-        .fetch_one(&self.pool)
+        .bind(serde_json::Value::Array(worker_routes))
+        .fetch_one(&mut *tx)
+        .timed(Phase::Query)
         .await?;
 
         let next_version: i32 = row.get("out_next_version");
         let functions_created: i32 = row.get("functions_created");
 
-        if functions_created > 0 {
-            eprintln!("  Created {} function workers", functions_created);
+        sqlx::query(
+            "UPDATE worker_deployments SET deployed_by = $1 WHERE worker_id = $2 AND version = $3",
+        )
+        .bind(self.member_id)
+        .bind(worker_id)
+        .bind(next_version)
+        .execute(&mut *tx)
+        .timed(Phase::Query)
+        .await?;
+
+        tx.commit().await?;
+
+        if functions_created > 0 {
+            crate::steps::note(&format!("  Created {} function workers", functions_created));
         }
 
         // 5. Resolve ASSETS binding S3 config (upload happens in workers.rs)
@@ -522,7 +1156,8 @@ impl Backend for DbBackend {
                     sc.access_key_id,
                     sc.secret_access_key,
                     sc.endpoint,
-                    sc.region
+                    sc.region,
+                    sc.session_token
                 FROM workers w
                 JOIN environment_values ev ON ev.environment_id = w.environment_id
                 JOIN storage_configs sc ON sc.id = ev.value::uuid
@@ -531,8 +1166,9 @@ impl Backend for DbBackend {
                 "#,
             )
             .bind(worker_id)
-            .bind(self.user_id)
+            .bind(owner_id)
             .fetch_optional(&self.pool)
+            .timed(Phase::Query)
             .await?
             .ok_or_else(|| {
                 BackendError::Api(
@@ -544,7 +1180,12 @@ impl Backend for DbBackend {
             let binding_endpoint: Option<String> = row.get("endpoint");
             let endpoint = binding_endpoint
                 .or_else(|| self.platform_storage.as_ref().map(|ps| ps.endpoint.clone()))
-                .ok_or_else(|| BackendError::Api("Storage endpoint not configured".to_string()))?;
+                .ok_or_else(|| {
+                    BackendError::Api(
+                        "Storage endpoint not configured. Set one with 'ow storage default set'."
+                            .to_string(),
+                    )
+                })?;
 
             Some(DirectUploadConfig {
                 bucket: row.get("bucket"),
@@ -555,6 +1196,7 @@ impl Backend for DbBackend {
                     .get::<Option<String>, _>("region")
                     .unwrap_or_else(|| "auto".to_string()),
                 prefix: row.get("prefix"),
+                session_token: row.get("session_token"),
             })
         } else {
             None
@@ -570,6 +1212,7 @@ impl Backend for DbBackend {
         )
         .bind(worker_id)
         .fetch_optional(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
         let url = if let Some(domain) = custom_domain {
@@ -578,6 +1221,8 @@ impl Backend for DbBackend {
             name.to_string()
         };
 
+        self.apply_retention_policy(name).await?;
+
         Ok(UploadResult {
             success: true,
             worker: UploadWorkerInfo {
@@ -594,571 +1239,2804 @@ impl Backend for DbBackend {
         })
     }
 
-    // Project methods
-    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
-        let rows = sqlx::query(
+    async fn create_deploy_approval(
+        &self,
+        name: &str,
+        ttl_seconds: i64,
+    ) -> Result<DeployApproval, BackendError> {
+        let (owner_id, _) = self.worker_scope(name, Access::Write).await?;
+
+        // Approving is for whoever runs the account: not editors of a shared
+        // worker, nor plain members of an organization
+        let approver = owner_id == self.user_id
+            && (self.user_id == self.member_id
+                || sqlx::query_scalar::<_, bool>(
+                    r#"
+                    SELECT EXISTS (
+                        SELECT 1
+                        FROM organizations o
+                        JOIN organization_members m ON m.organization_id = o.id
+                        WHERE o.account_id = $1 AND m.user_id = $2 AND m.role = 'owner'
+                    )
+                    "#,
+                )
+                .bind(self.user_id)
+                .bind(self.member_id)
+                .fetch_one(&self.pool)
+                .timed(Phase::Query)
+                .await?);
+
+        if !approver {
+            return Err(BackendError::Api(format!(
+                "Only the owner of worker '{}' can approve deploys to it",
+                name
+            )));
+        }
+
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
+
+        let mut bytes = [0u8; 24];
+        rand::rng().fill_bytes(&mut bytes);
+        let token = format!("owa_{}", hex::encode(bytes));
+        let token_hash = hex::encode(Sha256::digest(token.as_bytes()));
+
+        let expires_at: chrono::DateTime<chrono::Utc> = sqlx::query_scalar(
             r#"
-            SELECT id, name, "desc", created_at, updated_at
-            FROM projects
-            WHERE user_id = $1
-            ORDER BY name
+            INSERT INTO deploy_approvals (worker_id, token_hash, created_by, expires_at)
+            VALUES ($1, $2, $3, now() + make_interval(secs => $4))
+            RETURNING expires_at
             "#,
         )
-        .bind(self.user_id)
-        .fetch_all(&self.pool)
+        .bind(worker_id)
+        .bind(&token_hash)
+        .bind(self.member_id)
+        .bind(ttl_seconds as f64)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        let projects = rows
-            .iter()
-            .map(|row| Project {
-                id: row.get::<uuid::Uuid, _>("id").to_string(),
-                name: row.get("name"),
-                description: row.get("desc"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })
-            .collect();
+        Ok(DeployApproval { token, expires_at })
+    }
 
-        Ok(projects)
+    async fn exec_worker(
+        &self,
+        _name: &str,
+        _event: WorkerEvent,
+    ) -> Result<ExecResult, BackendError> {
+        Err(BackendError::Api(
+            "Running a worker handler requires a runner. Use an API alias.".to_string(),
+        ))
     }
 
-    async fn delete_project(&self, name: &str) -> Result<(), BackendError> {
-        let result = sqlx::query("DELETE FROM projects WHERE name = $1 AND user_id = $2")
-            .bind(name)
-            .bind(self.user_id)
-            .execute(&self.pool)
-            .await?;
+    async fn worker_runtime(&self, _name: &str) -> Result<WorkerRuntime, BackendError> {
+        Err(BackendError::Api(
+            "Runtime info is only known to the runners. Use an API alias.".to_string(),
+        ))
+    }
 
-        if result.rows_affected() == 0 {
-            return Err(BackendError::NotFound(format!(
-                "Project '{}' not found",
-                name
-            )));
-        }
+    async fn get_captured_request(
+        &self,
+        name: &str,
+        id: &str,
+    ) -> Result<CapturedRequest, BackendError> {
+        let (worker_id, _, _) = self.worker_project(name, Access::Read).await?;
+
+        let not_found = || {
+            BackendError::NotFound(format!(
+                "No captured request '{}' for worker '{}'",
+                id, name
+            ))
+        };
 
-        Ok(())
+        let capture_id: uuid::Uuid = id.parse().map_err(|_| not_found())?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, method, path, headers, body, status, captured_at
+            FROM request_captures
+            WHERE id = $1 AND worker_id = $2
+            "#,
+        )
+        .bind(capture_id)
+        .bind(worker_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(not_found)?;
+
+        let headers: serde_json::Value = row.get("headers");
+        let headers = serde_json::from_value(headers)
+            .map_err(|e| BackendError::Api(format!("Invalid captured headers: {}", e)))?;
+
+        Ok(CapturedRequest {
+            id: Some(row.get::<uuid::Uuid, _>("id").to_string()),
+            method: row.get("method"),
+            path: row.get("path"),
+            headers,
+            body: row.get("body"),
+            status: row.get::<Option<i16>, _>("status").map(|s| s as u16),
+            captured_at: row.get("captured_at"),
+        })
     }
 
-    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
-        let rows = sqlx::query(
+    async fn redeploy_worker(
+        &self,
+        name: &str,
+        message: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        let (worker_id, _, _) = self.worker_project(name, Access::Write).await?;
+
+        // Copy the active deployment's code into a new version
+        let row = sqlx::query(
             r#"
-            SELECT id, name, "desc", created_at, updated_at
-            FROM environments
-            WHERE user_id = $1
-            ORDER BY name
+            INSERT INTO worker_deployments (worker_id, version, hash, code_type, code, message, deployed_by)
+            SELECT d.worker_id,
+                   (SELECT MAX(version) + 1 FROM worker_deployments WHERE worker_id = $1),
+                   d.hash, d.code_type, d.code, COALESCE($2, 'Redeploy of v' || d.version), $3
+            FROM worker_deployments d
+            JOIN workers w ON w.id = d.worker_id AND w.current_version = d.version
+            WHERE d.worker_id = $1
+            RETURNING worker_id, version, hash, code_type::text, deployed_at, message, tag,
+                      (SELECT username FROM users WHERE id = deployed_by) as deployed_by
             "#,
         )
-        .bind(self.user_id)
-        .fetch_all(&self.pool)
-        .await?;
+        .bind(worker_id)
+        .bind(message)
+        .bind(self.member_id)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| {
+            BackendError::NotFound(format!("Worker '{}' has no active deployment", name))
+        })?;
 
-        let mut environments = Vec::new();
+        let version: i32 = row.get("version");
 
-        for row in rows {
-            let id: uuid::Uuid = row.get("id");
-            let values = self.get_environment_values(&id).await?;
+        sqlx::query("UPDATE workers SET current_version = $1 WHERE id = $2")
+            .bind(version)
+            .bind(worker_id)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
 
-            environments.push(Environment {
-                id: id.to_string(),
-                name: row.get("name"),
-                description: row.get("desc"),
-                values,
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            });
-        }
+        self.apply_retention_policy(name).await?;
 
-        Ok(environments)
+        Ok(Deployment {
+            worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
+            version,
+            hash: row.get("hash"),
+            code_type: row.get("code_type"),
+            deployed_at: row.get("deployed_at"),
+            deployed_by: row.get("deployed_by"),
+            message: row.get("message"),
+            tag: row.get("tag"),
+        })
     }
 
-    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+    async fn tag_deployment(
+        &self,
+        name: &str,
+        version: i32,
+        tag: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        self.worker_scope(name, Access::Write).await?;
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
+
         let row = sqlx::query(
             r#"
-            SELECT id, name, "desc", created_at, updated_at
-            FROM environments
-            WHERE name = $1 AND user_id = $2
+            UPDATE worker_deployments
+            SET tag = $3
+            WHERE worker_id = $1 AND version = $2
+            RETURNING worker_id, version, hash, code_type::text, deployed_at, message, tag,
+                      (SELECT username FROM users WHERE id = deployed_by) as deployed_by
             "#,
         )
-        .bind(name)
-        .bind(self.user_id)
+        .bind(worker_id)
+        .bind(version)
+        .bind(tag)
         .fetch_optional(&self.pool)
+        .timed(Phase::Query)
         .await?
-        .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
-
-        let id: uuid::Uuid = row.get("id");
-        let values = self.get_environment_values(&id).await?;
+        .ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "Version {} of worker '{}' not found",
+                version, name
+            ))
+        })?;
 
-        Ok(Environment {
-            id: id.to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            values,
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
+        Ok(Deployment {
+            worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
+            version: row.get("version"),
+            hash: row.get("hash"),
+            code_type: row.get("code_type"),
+            deployed_at: row.get("deployed_at"),
+            deployed_by: row.get("deployed_by"),
+            message: row.get("message"),
+            tag: row.get("tag"),
         })
     }
 
-    async fn create_environment(
-        &self,
-        input: CreateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
-        let row = sqlx::query(
+    async fn list_deployments(&self, name: &str) -> Result<Vec<Deployment>, BackendError> {
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
+
+        let rows = sqlx::query(
             r#"
-            INSERT INTO environments (name, "desc", user_id)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, "desc", created_at, updated_at
+            SELECT d.worker_id, d.version, d.hash, d.code_type::text, d.deployed_at, d.message, d.tag,
+                   u.username as deployed_by
+            FROM worker_deployments d
+            LEFT JOIN users u ON u.id = d.deployed_by
+            WHERE d.worker_id = $1
+            ORDER BY d.version
             "#,
         )
-        .bind(&input.name)
-        .bind(&input.desc)
-        .bind(self.user_id)
-        .fetch_one(&self.pool)
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        Ok(Environment {
-            id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            values: vec![],
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
+        Ok(rows
+            .into_iter()
+            .map(|row| Deployment {
+                worker_id: row.get::<uuid::Uuid, _>("worker_id").to_string(),
+                version: row.get("version"),
+                hash: row.get("hash"),
+                code_type: row.get("code_type"),
+                deployed_at: row.get("deployed_at"),
+                deployed_by: row.get("deployed_by"),
+                message: row.get("message"),
+                tag: row.get("tag"),
+            })
+            .collect())
+    }
+
+    async fn get_deployment_code(&self, name: &str, version: i32) -> Result<Vec<u8>, BackendError> {
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
+
+        sqlx::query_scalar(
+            "SELECT code FROM worker_deployments WHERE worker_id = $1 AND version = $2",
+        )
+        .bind(worker_id)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "Version {} of worker '{}' not found",
+                version, name
+            ))
         })
     }
 
-    async fn update_environment(
-        &self,
-        name: &str,
-        input: UpdateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
-        // Get environment ID
-        let row = sqlx::query("SELECT id FROM environments WHERE name = $1 AND user_id = $2")
-            .bind(name)
-            .bind(self.user_id)
-            .fetch_optional(&self.pool)
-            .await?
-            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
+    async fn prune_deployments(&self, name: &str, keep: u32) -> Result<Vec<i32>, BackendError> {
+        self.worker_scope(name, Access::Write).await?;
+        let worker = self.get_worker(name).await?;
+        let worker_id: uuid::Uuid = worker
+            .id
+            .parse()
+            .map_err(|_| BackendError::Api(format!("Invalid worker ID: {}", worker.id)))?;
 
-        let env_id: uuid::Uuid = row.get("id");
+        let versions: Vec<(i32, bool)> = sqlx::query(
+            "SELECT version, tag IS NOT NULL AS tagged FROM worker_deployments WHERE worker_id = $1",
+        )
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .iter()
+        .map(|row| (row.get("version"), row.get("tagged")))
+        .collect();
 
-        // Update name if provided
-        if let Some(new_name) = &input.name {
-            sqlx::query("UPDATE environments SET name = $1, updated_at = now() WHERE id = $2")
-                .bind(new_name)
-                .bind(env_id)
-                .execute(&self.pool)
-                .await?;
+        let prunable = prunable_versions(&versions, worker.current_version, keep);
+
+        if prunable.is_empty() {
+            return Ok(prunable);
         }
 
-        // Update values if provided
-        if let Some(values) = &input.values {
-            for value in values {
-                if let Some(id) = &value.id {
-                    // Update existing value
-                    let value_id: uuid::Uuid = id
-                        .parse()
-                        .map_err(|_| BackendError::Api(format!("Invalid value ID: {}", id)))?;
+        // Re-check the active version in SQL in case a deploy landed meanwhile
+        let deleted: Vec<i32> = sqlx::query_scalar(
+            r#"
+            DELETE FROM worker_deployments d
+            USING workers w
+            WHERE d.worker_id = $1 AND w.id = d.worker_id
+              AND d.version = ANY($2) AND d.tag IS NULL
+              AND d.version IS DISTINCT FROM w.current_version
+            RETURNING d.version
+            "#,
+        )
+        .bind(worker_id)
+        .bind(&prunable)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
 
-                    if let Some(val) = &value.value {
-                        sqlx::query(
-                            r#"
-                            UPDATE environment_values
-                            SET key = $1, value = $2, type = $3::enum_binding_type
-                            WHERE id = $4
-                            "#,
-                        )
-                        .bind(&value.key)
-                        .bind(val)
-                        .bind(&value.value_type)
-                        .bind(value_id)
-                        .execute(&self.pool)
-                        .await?;
-                    }
-                } else if let Some(val) = &value.value {
-                    // Create new value
-                    sqlx::query(
-                        r#"
-                        INSERT INTO environment_values (environment_id, user_id, key, value, type)
-                        VALUES ($1, $2, $3, $4, $5::enum_binding_type)
-                        "#,
-                    )
-                    .bind(env_id)
-                    .bind(self.user_id)
-                    .bind(&value.key)
-                    .bind(val)
-                    .bind(&value.value_type)
-                    .execute(&self.pool)
-                    .await?;
+        Ok(deleted)
+    }
+
+    async fn get_retention_policy(&self) -> Result<Option<u32>, BackendError> {
+        let keep: Option<i32> =
+            sqlx::query_scalar("SELECT deployment_retention FROM users WHERE id = $1")
+                .bind(self.user_id)
+                .fetch_one(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+
+        Ok(keep.map(|k| k as u32))
+    }
+
+    async fn set_retention_policy(&self, keep: Option<u32>) -> Result<(), BackendError> {
+        sqlx::query("UPDATE users SET deployment_retention = $2 WHERE id = $1")
+            .bind(self.user_id)
+            .bind(keep.map(|k| k as i32))
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_worker_urls(&self, name: &str) -> Result<Vec<WorkerUrl>, BackendError> {
+        let (worker_id, _, _) = self.worker_project(name, Access::Read).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT 'domain' AS kind, d.name AS value FROM domains d
+            WHERE d.worker_id = $1 OR d.project_id = $1
+            UNION ALL
+            SELECT 'route' AS kind, pr.pattern AS value FROM project_routes pr
+            WHERE pr.worker_id = $1
+            ORDER BY kind, value
+            "#,
+        )
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let urls = rows
+            .iter()
+            .map(|row| {
+                let value: String = row.get("value");
+
+                match row.get::<String, _>("kind").as_str() {
+                    "domain" => WorkerUrl::Domain { name: value },
+                    _ => WorkerUrl::Route { pattern: value },
                 }
+            })
+            .collect();
+
+        Ok(urls)
+    }
+
+    async fn add_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let (worker_id, project_id, owner_id) = self.worker_project(name, Access::Write).await?;
+
+        let inserted = match url {
+            WorkerUrl::Domain { name: domain } => {
+                // A project's main worker shares its ID; its domains must target the project
+                let (worker_target, project_target) = if project_id == Some(worker_id) {
+                    (None, Some(worker_id))
+                } else {
+                    (Some(worker_id), None)
+                };
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO domains (name, user_id, worker_id, project_id, created_at, updated_at)
+                    VALUES ($1, $2, $3, $4, now(), now())
+                    ON CONFLICT (name) DO NOTHING
+                    "#,
+                )
+                .bind(domain)
+                .bind(owner_id)
+                .bind(worker_target)
+                .bind(project_target)
+                .execute(&self.pool)
+            .timed(Phase::Query)
+                .await?
+            }
+            WorkerUrl::Route { pattern } => {
+                let project_id = project_id.ok_or_else(|| {
+                    BackendError::Api(format!(
+                        "Worker '{}' is not part of a project. Routes need a project \
+                         (see `ow projects add-worker`).",
+                        name
+                    ))
+                })?;
+
+                // Priority 0 keeps the route when an upload regenerates the asset routes
+                sqlx::query(
+                    r#"
+                    INSERT INTO project_routes (project_id, pattern, priority, backend_type, worker_id)
+                    VALUES ($1, $2, 0, 'worker'::enum_backend_type, $3)
+                    ON CONFLICT (project_id, pattern) DO NOTHING
+                    "#,
+                )
+                .bind(project_id)
+                .bind(pattern)
+                .bind(worker_id)
+                .execute(&self.pool)
+            .timed(Phase::Query)
+                .await?
             }
+        };
+
+        if inserted.rows_affected() == 0 {
+            return Err(BackendError::Api(format!("'{}' is already in use", url)));
         }
 
-        // Return updated environment
-        let final_name = input.name.as_deref().unwrap_or(name);
-        self.get_environment(final_name).await
+        Ok(())
     }
 
-    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
-        let result = sqlx::query("DELETE FROM environments WHERE name = $1 AND user_id = $2")
+    async fn remove_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let (worker_id, project_id, owner_id) = self.worker_project(name, Access::Write).await?;
+
+        let deleted = match url {
+            WorkerUrl::Domain { name: domain } => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM domains
+                    WHERE name = $1 AND user_id = $2 AND (worker_id = $3 OR project_id = $3)
+                    "#,
+                )
+                .bind(domain)
+                .bind(owner_id)
+                .bind(worker_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?
+            }
+            WorkerUrl::Route { pattern } => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM project_routes
+                    WHERE project_id = $1 AND pattern = $2 AND worker_id = $3
+                    "#,
+                )
+                .bind(project_id)
+                .bind(pattern)
+                .bind(worker_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?
+            }
+        };
+
+        if deleted.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "'{}' does not point at worker '{}'",
+                url, name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_values(&self, name: &str) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let (worker_id, _, _) = self.worker_project(name, Access::Read).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, key, value, type::text as value_type
+            FROM worker_values
+            WHERE worker_id = $1
+            ORDER BY key
+            "#,
+        )
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let values = rows
+            .iter()
+            .map(|row| EnvironmentValue {
+                id: row.get::<uuid::Uuid, _>("id").to_string(),
+                key: row.get("key"),
+                value: row.get("value"),
+                value_type: row.get("value_type"),
+                rotated_at: None,
+            })
+            .collect();
+
+        Ok(values)
+    }
+
+    async fn set_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+        value_type: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        let (worker_id, _, owner_id) = self.worker_project(name, Access::Write).await?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO worker_values (worker_id, user_id, key, value, type)
+            VALUES ($1, $2, $3, $4, $5::enum_binding_type)
+            ON CONFLICT (worker_id, key)
+            DO UPDATE SET value = EXCLUDED.value, type = EXCLUDED.type, updated_at = now()
+            RETURNING id, key, value, type::text as value_type
+            "#,
+        )
+        .bind(worker_id)
+        .bind(owner_id)
+        .bind(key)
+        .bind(value)
+        .bind(value_type)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(EnvironmentValue {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            key: row.get("key"),
+            value: row.get("value"),
+            value_type: row.get("value_type"),
+            rotated_at: None,
+        })
+    }
+
+    async fn delete_worker_value(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let (worker_id, _, _) = self.worker_project(name, Access::Write).await?;
+
+        let result = sqlx::query("DELETE FROM worker_values WHERE worker_id = $1 AND key = $2")
+            .bind(worker_id)
+            .bind(key)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' has no value '{}'",
+                name, key
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_shares(&self, name: &str) -> Result<Vec<WorkerShare>, BackendError> {
+        let worker_id = self.owned_worker_id(name).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT u.username, s.role::text as role, s.created_at
+            FROM worker_shares s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.worker_id = $1
+            ORDER BY u.username
+            "#,
+        )
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WorkerShare {
+                username: row.get("username"),
+                role: row.get("role"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn share_worker(
+        &self,
+        name: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        let worker_id = self.owned_worker_id(name).await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO worker_shares (worker_id, user_id, role)
+            SELECT $1, u.id, $3::enum_share_role
+            FROM users u
+            WHERE u.username = $2 AND u.id <> $4
+            ON CONFLICT (worker_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            "#,
+        )
+        .bind(worker_id)
+        .bind(username)
+        .bind(role)
+        .bind(self.user_id)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "User '{}' not found",
+                username
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn unshare_worker(&self, name: &str, username: &str) -> Result<(), BackendError> {
+        let worker_id = self.owned_worker_id(name).await?;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM worker_shares s
+            USING users u
+            WHERE s.worker_id = $1 AND u.id = s.user_id AND u.username = $2
+            "#,
+        )
+        .bind(worker_id)
+        .bind(username)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' is not shared with '{}'",
+                name, username
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Project methods
+    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.name, p."desc", e.name AS environment, p.created_at, p.updated_at
+            FROM projects p
+            LEFT JOIN environments e ON e.id = p.environment_id
+            WHERE p.user_id = $1
+            ORDER BY p.name
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let projects = rows
+            .iter()
+            .map(|row| Project {
+                id: row.get::<uuid::Uuid, _>("id").to_string(),
+                name: row.get("name"),
+                description: row.get("desc"),
+                environment: row.get("environment"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect();
+
+        Ok(projects)
+    }
+
+    async fn delete_project(&self, name: &str) -> Result<(), BackendError> {
+        let result = sqlx::query("DELETE FROM projects WHERE name = $1 AND user_id = $2")
             .bind(name)
             .bind(self.user_id)
             .execute(&self.pool)
+            .timed(Phase::Query)
             .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(BackendError::NotFound(format!(
-                "Environment '{}' not found",
-                name
-            )));
-        }
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Project '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn preview_delete_project(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        let row = sqlx::query(
+            r#"
+            WITH project AS (
+                SELECT id, environment_id FROM projects WHERE name = $1 AND user_id = $2
+            ),
+            project_workers AS (
+                SELECT w.id, w.name FROM workers w, project
+                WHERE w.project_id = project.id OR w.id = project.id
+            )
+            SELECT
+                (SELECT COALESCE(array_agg(name ORDER BY name), '{}') FROM project_workers) as workers,
+                (SELECT COUNT(*) FROM worker_deployments wd
+                 WHERE wd.worker_id IN (SELECT id FROM project_workers)) as deployments,
+                (SELECT COUNT(*) FROM project_routes pr, project
+                 WHERE pr.project_id = project.id) as routes,
+                (SELECT COALESCE(array_agg(d.name ORDER BY d.name), '{}') FROM domains d, project
+                 WHERE d.project_id = project.id
+                    OR d.worker_id IN (SELECT id FROM project_workers)) as domains,
+                (SELECT COUNT(*) FROM crons c
+                 WHERE c.worker_id IN (SELECT id FROM project_workers)
+                   AND c.deleted_at IS NULL) as crons,
+                (SELECT sc.bucket || COALESCE('/' || sc.prefix, '')
+                 FROM environment_values ev
+                 JOIN storage_configs sc ON sc.id = ev.value::uuid
+                 JOIN project ON ev.environment_id = project.environment_id
+                 WHERE ev.type = 'assets'
+                 LIMIT 1) as assets
+            FROM project
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Project '{}' not found", name)))?;
+
+        Ok(DeletePreview {
+            workers: row.get("workers"),
+            deployments: row.get("deployments"),
+            routes: row.get("routes"),
+            domains: row.get("domains"),
+            crons: row.get("crons"),
+            assets: row.get("assets"),
+        })
+    }
+
+    async fn add_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        let project_row =
+            sqlx::query("SELECT id, environment_id FROM projects WHERE name = $1 AND user_id = $2")
+                .bind(project)
+                .bind(self.user_id)
+                .fetch_optional(&self.pool)
+                .timed(Phase::Query)
+                .await?
+                .ok_or_else(|| {
+                    BackendError::NotFound(format!("Project '{}' not found", project))
+                })?;
+
+        let project_id: uuid::Uuid = project_row.get("id");
+        let environment_id: Option<uuid::Uuid> = project_row.get("environment_id");
+
+        let worker_row = sqlx::query(
+            r#"
+            SELECT w.id, p.name AS project_name
+            FROM workers w
+            LEFT JOIN projects p ON p.id = w.project_id
+            WHERE w.name = $1 AND w.user_id = $2
+            "#,
+        )
+        .bind(worker)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", worker)))?;
+
+        let worker_id: uuid::Uuid = worker_row.get("id");
+        let current: Option<String> = worker_row.get("project_name");
+
+        if let Some(current) = current {
+            return Err(BackendError::Api(format!(
+                "Worker '{}' already belongs to project '{}'",
+                worker, current
+            )));
+        }
+
+        // Workers in a project must share its environment (enforced by a trigger).
+        // Guarded so a worker added to another project meanwhile is left alone.
+        let result = sqlx::query(
+            r#"
+            UPDATE workers SET project_id = $1, environment_id = $2
+            WHERE id = $3 AND project_id IS NULL
+            "#,
+        )
+        .bind(project_id)
+        .bind(environment_id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::Api(format!(
+                "Worker '{}' was added to another project meanwhile",
+                worker
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT p.id AS project_id, w.id AS worker_id
+            FROM projects p
+            JOIN workers w ON w.project_id = p.id
+            WHERE p.name = $1 AND w.name = $2 AND p.user_id = $3
+            "#,
+        )
+        .bind(project)
+        .bind(worker)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "Worker '{}' not found in project '{}'",
+                worker, project
+            ))
+        })?;
+
+        let project_id: uuid::Uuid = row.get("project_id");
+        let worker_id: uuid::Uuid = row.get("worker_id");
+
+        // The main worker shares the project's ID, the project can't exist without it
+        if project_id == worker_id {
+            return Err(BackendError::Api(format!(
+                "'{}' is the main worker of project '{}'. Delete the project instead.",
+                worker, project
+            )));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM project_routes WHERE project_id = $1 AND worker_id = $2")
+            .bind(project_id)
+            .bind(worker_id)
+            .execute(&mut *tx)
+            .timed(Phase::Query)
+            .await?;
+
+        sqlx::query("UPDATE workers SET project_id = NULL WHERE id = $1")
+            .bind(worker_id)
+            .execute(&mut *tx)
+            .timed(Phase::Query)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, "desc", protected, created_at, updated_at
+            FROM environments
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let mut environments = Vec::new();
+
+        for row in rows {
+            let id: uuid::Uuid = row.get("id");
+            let values = self.get_environment_values(&id).await?;
+
+            environments.push(Environment {
+                id: id.to_string(),
+                name: row.get("name"),
+                description: row.get("desc"),
+                values,
+                protected: row.get("protected"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            });
+        }
+
+        Ok(environments)
+    }
+
+    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, "desc", protected, created_at, updated_at
+            FROM environments
+            WHERE name = $1 AND user_id = $2
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
+
+        let id: uuid::Uuid = row.get("id");
+        let values = self.get_environment_values(&id).await?;
+
+        Ok(Environment {
+            id: id.to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            values,
+            protected: row.get("protected"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn create_environment(
+        &self,
+        input: CreateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO environments (name, "desc", user_id)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, "desc", protected, created_at, updated_at
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.desc)
+        .bind(self.user_id)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(Environment {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            values: vec![],
+            protected: row.get("protected"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn update_environment(
+        &self,
+        name: &str,
+        input: UpdateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        // Get environment ID
+        let row = sqlx::query("SELECT id FROM environments WHERE name = $1 AND user_id = $2")
+            .bind(name)
+            .bind(self.user_id)
+            .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+            .await?
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
+
+        let env_id: uuid::Uuid = row.get("id");
+
+        // Update name if provided
+        if let Some(new_name) = &input.name {
+            sqlx::query("UPDATE environments SET name = $1, updated_at = now() WHERE id = $2")
+                .bind(new_name)
+                .bind(env_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+        }
+
+        // Update protected flag if provided
+        if let Some(protected) = input.protected {
+            sqlx::query("UPDATE environments SET protected = $1, updated_at = now() WHERE id = $2")
+                .bind(protected)
+                .bind(env_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+        }
+
+        // Update values if provided
+        if let Some(values) = &input.values {
+            for value in values {
+                if let Some(id) = &value.id {
+                    // Update existing value
+                    let value_id: uuid::Uuid = id
+                        .parse()
+                        .map_err(|_| BackendError::Api(format!("Invalid value ID: {}", id)))?;
+
+                    if let Some(val) = &value.value {
+                        let secret_id = self.account_secret_id(val).await?;
+
+                        sqlx::query(
+                            r#"
+                            UPDATE environment_values
+                            SET key = $1, value = $2, type = $3::enum_binding_type,
+                                account_secret_id = $5
+                            WHERE id = $4
+                            "#,
+                        )
+                        .bind(&value.key)
+                        .bind(val)
+                        .bind(&value.value_type)
+                        .bind(value_id)
+                        .bind(secret_id)
+                        .execute(&self.pool)
+                        .timed(Phase::Query)
+                        .await?;
+                    } else {
+                        // Null value deletes it
+                        sqlx::query(
+                            "DELETE FROM environment_values WHERE id = $1 AND environment_id = $2",
+                        )
+                        .bind(value_id)
+                        .bind(env_id)
+                        .execute(&self.pool)
+                        .timed(Phase::Query)
+                        .await?;
+                    }
+                } else if let Some(val) = &value.value {
+                    // Create new value
+                    let secret_id = self.account_secret_id(val).await?;
+
+                    sqlx::query(
+                        r#"
+                        INSERT INTO environment_values (environment_id, user_id, key, value, type, account_secret_id)
+                        VALUES ($1, $2, $3, $4, $5::enum_binding_type, $6)
+                        "#,
+                    )
+                    .bind(env_id)
+                    .bind(self.user_id)
+                    .bind(&value.key)
+                    .bind(val)
+                    .bind(&value.value_type)
+                    .bind(secret_id)
+                    .execute(&self.pool)
+            .timed(Phase::Query)
+                    .await?;
+                }
+            }
+        }
+
+        // Return updated environment
+        let final_name = input.name.as_deref().unwrap_or(name);
+        self.get_environment(final_name).await
+    }
+
+    async fn rotate_environment_secret(
+        &self,
+        env: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        // Values mirroring an account secret are rotated with `ow secrets set`
+        let row = sqlx::query(
+            r#"
+            UPDATE environment_values ev
+            SET value = $3, rotated_at = now(), updated_at = now()
+            FROM environments e
+            WHERE e.id = ev.environment_id AND e.name = $1 AND e.user_id = $4
+              AND ev.key = $2 AND ev.type = 'secret' AND ev.account_secret_id IS NULL
+            RETURNING ev.id, ev.key, ev.value, ev.type::text as value_type, ev.rotated_at
+            "#,
+        )
+        .bind(env)
+        .bind(key)
+        .bind(value)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "Secret '{}' not found in environment '{}'",
+                key, env
+            ))
+        })?;
+
+        Ok(EnvironmentValue {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            key: row.get("key"),
+            value: row.get("value"),
+            value_type: row.get("value_type"),
+            rotated_at: row.get("rotated_at"),
+        })
+    }
+
+    async fn environment_value_history(
+        &self,
+        env: &str,
+        key: &str,
+    ) -> Result<Vec<EnvironmentValueChange>, BackendError> {
+        let env_id: Option<uuid::Uuid> =
+            sqlx::query_scalar("SELECT id FROM environments WHERE name = $1 AND user_id = $2")
+                .bind(env)
+                .bind(self.user_id)
+                .fetch_optional(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+
+        let env_id = env_id
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", env)))?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT value, type::text as value_type, changed_at
+            FROM environment_value_history
+            WHERE environment_id = $1 AND key = $2
+            ORDER BY changed_at, id
+            "#,
+        )
+        .bind(env_id)
+        .bind(key)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| EnvironmentValueChange {
+                value: row.get("value"),
+                value_type: row.get("value_type"),
+                changed_at: row.get("changed_at"),
+            })
+            .collect())
+    }
+
+    async fn environment_values_at(
+        &self,
+        env: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let env_id: Option<uuid::Uuid> =
+            sqlx::query_scalar("SELECT id FROM environments WHERE name = $1 AND user_id = $2")
+                .bind(env)
+                .bind(self.user_id)
+                .fetch_optional(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+
+        let env_id = env_id
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", env)))?;
+
+        // Latest change of each key at that time; keys removed by then are dropped
+        let rows = sqlx::query(
+            r#"
+            SELECT key, value, value_type
+            FROM (
+                SELECT DISTINCT ON (key) key, value, type::text as value_type
+                FROM environment_value_history
+                WHERE environment_id = $1 AND changed_at <= $2
+                ORDER BY key, changed_at DESC, id DESC
+            ) latest
+            WHERE value IS NOT NULL
+            ORDER BY key
+            "#,
+        )
+        .bind(env_id)
+        .bind(at)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| EnvironmentValue {
+                id: String::new(),
+                key: row.get("key"),
+                value: row.get("value"),
+                value_type: row.get("value_type"),
+                rotated_at: None,
+            })
+            .collect())
+    }
+
+    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
+        let result = sqlx::query("DELETE FROM environments WHERE name = $1 AND user_id = $2")
+            .bind(name)
+            .bind(self.user_id)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Storage methods
+    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, "desc", 'r2' as provider, bucket, prefix, endpoint, region, public_url, created_at, updated_at
+            FROM storage_configs
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.pool)
+            .timed(Phase::Query)
+        .await?;
+
+        let configs = rows
+            .iter()
+            .map(|row| StorageConfig {
+                id: row.get::<uuid::Uuid, _>("id").to_string(),
+                name: row.get("name"),
+                description: row.get("desc"),
+                provider: row.get("provider"),
+                bucket: row.get("bucket"),
+                prefix: row.get("prefix"),
+                endpoint: row.get("endpoint"),
+                region: row.get("region"),
+                public_url: row.get("public_url"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect();
+
+        Ok(configs)
+    }
+
+    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, "desc", 'r2' as provider, bucket, prefix, endpoint, region, public_url, created_at, updated_at
+            FROM storage_configs
+            WHERE name = $1 AND user_id = $2
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Storage config '{}' not found", name)))?;
+
+        Ok(StorageConfig {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            provider: row.get("provider"),
+            bucket: row.get("bucket"),
+            prefix: row.get("prefix"),
+            endpoint: row.get("endpoint"),
+            region: row.get("region"),
+            public_url: row.get("public_url"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn get_storage_credentials(
+        &self,
+        name: &str,
+    ) -> Result<DirectUploadConfig, BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT bucket, prefix, access_key_id, secret_access_key, endpoint, region, session_token
+            FROM storage_configs
+            WHERE name = $1 AND user_id = $2
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Storage config '{}' not found", name)))?;
+
+        let endpoint = row
+            .get::<Option<String>, _>("endpoint")
+            .or_else(|| self.platform_storage.as_ref().map(|ps| ps.endpoint.clone()))
+            .ok_or_else(|| {
+                BackendError::Api(
+                    "Storage endpoint not configured. Set one with 'ow storage default set'."
+                        .to_string(),
+                )
+            })?;
+
+        Ok(DirectUploadConfig {
+            bucket: row.get::<Option<String>, _>("bucket").unwrap_or_default(),
+            endpoint,
+            access_key_id: row
+                .get::<Option<String>, _>("access_key_id")
+                .unwrap_or_default(),
+            secret_access_key: row
+                .get::<Option<String>, _>("secret_access_key")
+                .unwrap_or_default(),
+            region: row
+                .get::<Option<String>, _>("region")
+                .unwrap_or_else(|| "auto".to_string()),
+            prefix: row.get("prefix"),
+            session_token: row.get("session_token"),
+        })
+    }
+
+    async fn get_platform_storage(&self) -> Result<Option<PlatformStorage>, BackendError> {
+        Ok(self.platform_storage.as_ref().map(PlatformStorage::from))
+    }
+
+    async fn set_platform_storage(
+        &self,
+        storage: PlatformStorageConfig,
+    ) -> Result<(), BackendError> {
+        // DB aliases keep the platform bucket in their alias config
+        let alias = config::active_alias()
+            .ok_or_else(|| BackendError::Api("No alias selected".to_string()))?;
+
+        let mut config = Config::load().map_err(|e| BackendError::Api(e.to_string()))?;
+        config
+            .set_storage(alias, storage)
+            .map_err(|e| BackendError::Api(e.to_string()))?;
+        config
+            .save()
+            .map_err(|e| BackendError::Api(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn create_storage(
+        &self,
+        input: CreateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        // Handle platform provider - use platform storage config
+        let (
+            bucket,
+            prefix,
+            access_key_id,
+            secret_access_key,
+            session_token,
+            endpoint,
+            region,
+            public_url,
+        ) = if input.provider == "platform" {
+            let ps = self.platform_storage.as_ref().ok_or_else(|| {
+                BackendError::Api(
+                    "Platform storage not configured. Use 'ow storage default set' to configure it."
+                        .to_string(),
+                )
+            })?;
+
+            // Generate unique prefix for this storage config
+            let prefix = Some(uuid::Uuid::new_v4().to_string());
+
+            (
+                Some(ps.bucket.clone()),
+                prefix,
+                Some(ps.access_key_id.clone()),
+                Some(ps.secret_access_key.clone()),
+                ps.session_token.clone(),
+                Some(ps.endpoint.clone()),
+                Some(ps.region.clone()),
+                None, // public_url not in PlatformStorageConfig
+            )
+        } else {
+            (
+                input.bucket,
+                input.prefix,
+                input.access_key_id,
+                input.secret_access_key,
+                None,
+                input.endpoint,
+                input.region,
+                input.public_url,
+            )
+        };
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO storage_configs (name, "desc", user_id, bucket, prefix, access_key_id, secret_access_key, session_token, endpoint, region, public_url)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, name, "desc", bucket, prefix, endpoint, region, public_url, created_at, updated_at
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.desc)
+        .bind(self.user_id)
+        .bind(&bucket)
+        .bind(&prefix)
+        .bind(&access_key_id)
+        .bind(&secret_access_key)
+        .bind(&session_token)
+        .bind(&endpoint)
+        .bind(&region)
+        .bind(&public_url)
+        .fetch_one(&self.pool)
+            .timed(Phase::Query)
+        .await?;
+
+        Ok(StorageConfig {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            provider: input.provider,
+            bucket: row.get("bucket"),
+            prefix: row.get("prefix"),
+            endpoint: row.get("endpoint"),
+            region: row.get("region"),
+            public_url: row.get("public_url"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn update_storage(
+        &self,
+        name: &str,
+        input: UpdateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE storage_configs
+            SET name = COALESCE($3, name),
+                "desc" = CASE WHEN $4::text IS NULL THEN "desc" ELSE NULLIF($4, '') END,
+                updated_at = now()
+            WHERE name = $1 AND user_id = $2
+            RETURNING id, name, "desc", 'r2' as provider, bucket, prefix, endpoint, region, public_url, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .bind(&input.name)
+        .bind(&input.desc)
+        .fetch_optional(&self.pool)
+            .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Storage config '{}' not found", name)))?;
+
+        Ok(StorageConfig {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            provider: row.get("provider"),
+            bucket: row.get("bucket"),
+            prefix: row.get("prefix"),
+            endpoint: row.get("endpoint"),
+            region: row.get("region"),
+            public_url: row.get("public_url"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
+        let result = sqlx::query("DELETE FROM storage_configs WHERE name = $1 AND user_id = $2")
+            .bind(name)
+            .bind(self.user_id)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Storage config '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // KV methods
+    async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, "desc", created_at, updated_at
+            FROM kv_configs
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let namespaces = rows
+            .iter()
+            .map(|row| KvNamespace {
+                id: row.get::<uuid::Uuid, _>("id").to_string(),
+                name: row.get("name"),
+                description: row.get("desc"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect();
+
+        Ok(namespaces)
+    }
+
+    async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, "desc", created_at, updated_at
+            FROM kv_configs
+            WHERE name = $1 AND user_id = $2
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("KV namespace '{}' not found", name)))?;
+
+        Ok(KvNamespace {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO kv_configs (name, "desc", user_id)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, "desc", created_at, updated_at
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.desc)
+        .bind(self.user_id)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(KvNamespace {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn update_kv(
+        &self,
+        name: &str,
+        input: UpdateKvInput,
+    ) -> Result<KvNamespace, BackendError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE kv_configs
+            SET name = COALESCE($3, name),
+                "desc" = CASE WHEN $4::text IS NULL THEN "desc" ELSE NULLIF($4, '') END,
+                updated_at = now()
+            WHERE name = $1 AND user_id = $2
+            RETURNING id, name, "desc", created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .bind(&input.name)
+        .bind(&input.desc)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("KV namespace '{}' not found", name)))?;
+
+        Ok(KvNamespace {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
+        let result = sqlx::query("DELETE FROM kv_configs WHERE name = $1 AND user_id = $2")
+            .bind(name)
+            .bind(self.user_id)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_kv_entries(
+        &self,
+        name: &str,
+        prefix: &str,
+    ) -> Result<Vec<KvEntry>, BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT key, value
+            FROM kv_data
+            WHERE namespace_id = $1
+              AND starts_with(key, $2)
+              AND (expires_at IS NULL OR expires_at > now())
+            ORDER BY key
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| KvEntry {
+                key: row.get("key"),
+                value: row.get("value"),
+            })
+            .collect())
+    }
+
+    async fn put_kv_entries(
+        &self,
+        name: &str,
+        entries: Vec<KvEntry>,
+    ) -> Result<usize, BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        // One statement for the whole batch; later entries win on duplicate keys
+        let document: serde_json::Map<String, serde_json::Value> =
+            entries.into_iter().map(|e| (e.key, e.value)).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO kv_data (namespace_id, key, value)
+            SELECT $1, e.key, e.value FROM jsonb_each($2) AS e
+            ON CONFLICT (namespace_id, key) DO UPDATE
+            SET value = EXCLUDED.value, expires_at = NULL, updated_at = now()
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(serde_json::Value::Object(document))
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn get_kv_entry(&self, name: &str, key: &str) -> Result<KvEntry, BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        let value: Option<serde_json::Value> = sqlx::query_scalar(
+            r#"
+            SELECT value
+            FROM kv_data
+            WHERE namespace_id = $1
+              AND key = $2
+              AND (expires_at IS NULL OR expires_at > now())
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let value = value.ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "Key '{}' not found in KV namespace '{}'",
+                key, name
+            ))
+        })?;
+
+        Ok(KvEntry {
+            key: key.to_string(),
+            value,
+        })
+    }
+
+    async fn put_kv_entry(
+        &self,
+        name: &str,
+        entry: KvEntry,
+        ttl: Option<i64>,
+    ) -> Result<(), BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        // A NULL TTL gives a NULL expiry, clearing any previous one
+        sqlx::query(
+            r#"
+            INSERT INTO kv_data (namespace_id, key, value, expires_at)
+            VALUES ($1, $2, $3, now() + $4::bigint * interval '1 second')
+            ON CONFLICT (namespace_id, key) DO UPDATE
+            SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at, updated_at = now()
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(&entry.key)
+        .bind(&entry.value)
+        .bind(ttl)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_kv_entry(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM kv_data
+            WHERE namespace_id = $1
+              AND key = $2
+              AND (expires_at IS NULL OR expires_at > now())
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(key)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Key '{}' not found in KV namespace '{}'",
+                key, name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn count_expired_kv_entries(&self, name: &str) -> Result<u64, BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT count(*) FROM kv_data WHERE namespace_id = $1 AND expires_at <= now()",
+        )
+        .bind(namespace_id)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(count as u64)
+    }
 
-        Ok(())
+    async fn delete_expired_kv_entries(&self, name: &str, limit: u32) -> Result<u64, BackendError> {
+        let namespace = self.get_kv(name).await?;
+        let namespace_id = uuid::Uuid::parse_str(&namespace.id)
+            .map_err(|e| BackendError::Api(format!("Invalid namespace ID: {}", e)))?;
+
+        // Bounded batches keep each transaction (and its locks) short
+        let result = sqlx::query(
+            r#"
+            DELETE FROM kv_data
+            WHERE namespace_id = $1
+              AND key IN (
+                SELECT key FROM kv_data
+                WHERE namespace_id = $1 AND expires_at <= now()
+                LIMIT $2
+              )
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(limit as i64)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(result.rows_affected())
     }
 
-    // Storage methods
-    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+    // Database methods
+    async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, "desc", 'r2' as provider, bucket, prefix, endpoint, region, public_url, created_at, updated_at
-            FROM storage_configs
+            SELECT id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
+            FROM database_configs
             WHERE user_id = $1
             ORDER BY name
             "#,
         )
         .bind(self.user_id)
         .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        let configs = rows
+        let databases = rows
             .iter()
-            .map(|row| StorageConfig {
+            .map(|row| Database {
                 id: row.get::<uuid::Uuid, _>("id").to_string(),
                 name: row.get("name"),
                 description: row.get("desc"),
                 provider: row.get("provider"),
-                bucket: row.get("bucket"),
-                prefix: row.get("prefix"),
-                endpoint: row.get("endpoint"),
-                region: row.get("region"),
-                public_url: row.get("public_url"),
+                max_rows: row.get("max_rows"),
+                timeout_seconds: row.get("timeout_seconds"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             })
             .collect();
 
-        Ok(configs)
+        Ok(databases)
     }
 
-    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+    async fn get_database(&self, name: &str) -> Result<Database, BackendError> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, "desc", 'r2' as provider, bucket, prefix, endpoint, region, public_url, created_at, updated_at
-            FROM storage_configs
+            SELECT id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
+            FROM database_configs
             WHERE name = $1 AND user_id = $2
             "#,
         )
         .bind(name)
         .bind(self.user_id)
         .fetch_optional(&self.pool)
+        .timed(Phase::Query)
         .await?
-        .ok_or_else(|| BackendError::NotFound(format!("Storage config '{}' not found", name)))?;
+        .ok_or_else(|| BackendError::NotFound(format!("Database '{}' not found", name)))?;
 
-        Ok(StorageConfig {
+        Ok(Database {
             id: row.get::<uuid::Uuid, _>("id").to_string(),
             name: row.get("name"),
             description: row.get("desc"),
             provider: row.get("provider"),
-            bucket: row.get("bucket"),
-            prefix: row.get("prefix"),
-            endpoint: row.get("endpoint"),
-            region: row.get("region"),
-            public_url: row.get("public_url"),
+            max_rows: row.get("max_rows"),
+            timeout_seconds: row.get("timeout_seconds"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
     }
 
-    async fn create_storage(
-        &self,
-        input: CreateStorageInput,
-    ) -> Result<StorageConfig, BackendError> {
-        // Handle platform provider - use platform storage config
-        let (bucket, prefix, access_key_id, secret_access_key, endpoint, region, public_url) =
-            if input.provider == "platform" {
-                let ps = self.platform_storage.as_ref().ok_or_else(|| {
-                    BackendError::Api(
-                        "Platform storage not configured. Use 'ow setup-storage' to configure it."
-                            .to_string(),
-                    )
-                })?;
-
-                // Generate unique prefix for this storage config
-                let prefix = Some(uuid::Uuid::new_v4().to_string());
-
-                (
-                    Some(ps.bucket.clone()),
-                    prefix,
-                    Some(ps.access_key_id.clone()),
-                    Some(ps.secret_access_key.clone()),
-                    Some(ps.endpoint.clone()),
-                    Some(ps.region.clone()),
-                    None, // public_url not in PlatformStorageConfig
-                )
-            } else {
-                (
-                    input.bucket,
-                    input.prefix,
-                    input.access_key_id,
-                    input.secret_access_key,
-                    input.endpoint,
-                    input.region,
-                    input.public_url,
-                )
-            };
-
+    async fn create_database(&self, input: CreateDatabaseInput) -> Result<Database, BackendError> {
         let row = sqlx::query(
             r#"
-            INSERT INTO storage_configs (name, "desc", user_id, bucket, prefix, access_key_id, secret_access_key, endpoint, region, public_url)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, name, "desc", bucket, prefix, endpoint, region, public_url, created_at, updated_at
+            INSERT INTO database_configs (user_id, name, "desc", provider, connection_string, max_rows, timeout_seconds)
+            VALUES ($1, $2, $3, $4, $5, COALESCE($6, 1000), COALESCE($7, 30))
+            RETURNING id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
             "#,
         )
+        .bind(self.user_id)
         .bind(&input.name)
         .bind(&input.desc)
-        .bind(self.user_id)
-        .bind(&bucket)
-        .bind(&prefix)
-        .bind(&access_key_id)
-        .bind(&secret_access_key)
-        .bind(&endpoint)
-        .bind(&region)
-        .bind(&public_url)
+        .bind(&input.provider)
+        .bind(&input.connection_string)
+        .bind(input.max_rows)
+        .bind(input.timeout_seconds)
         .fetch_one(&self.pool)
+            .timed(Phase::Query)
         .await?;
 
-        Ok(StorageConfig {
+        Ok(Database {
             id: row.get::<uuid::Uuid, _>("id").to_string(),
             name: row.get("name"),
             description: row.get("desc"),
-            provider: input.provider,
-            bucket: row.get("bucket"),
-            prefix: row.get("prefix"),
-            endpoint: row.get("endpoint"),
-            region: row.get("region"),
-            public_url: row.get("public_url"),
+            provider: row.get("provider"),
+            max_rows: row.get("max_rows"),
+            timeout_seconds: row.get("timeout_seconds"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn update_database(
+        &self,
+        name: &str,
+        input: UpdateDatabaseInput,
+    ) -> Result<Database, BackendError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE database_configs
+            SET name = COALESCE($3, name),
+                "desc" = CASE WHEN $4::text IS NULL THEN "desc" ELSE NULLIF($4, '') END,
+                updated_at = now()
+            WHERE name = $1 AND user_id = $2
+            RETURNING id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(self.user_id)
+        .bind(&input.name)
+        .bind(&input.desc)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("Database '{}' not found", name)))?;
+
+        Ok(Database {
+            id: row.get::<uuid::Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            description: row.get("desc"),
+            provider: row.get("provider"),
+            max_rows: row.get("max_rows"),
+            timeout_seconds: row.get("timeout_seconds"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
     }
 
-    async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
-        let result = sqlx::query("DELETE FROM storage_configs WHERE name = $1 AND user_id = $2")
-            .bind(name)
-            .bind(self.user_id)
-            .execute(&self.pool)
-            .await?;
+    async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
+        let result = sqlx::query("DELETE FROM database_configs WHERE name = $1 AND user_id = $2")
+            .bind(name)
+            .bind(self.user_id)
+            .execute(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Database '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Token methods
+    async fn create_token(&self, input: CreateTokenInput) -> Result<IssuedToken, BackendError> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM api_keys WHERE name = $1 AND user_id = $2)",
+        )
+        .bind(&input.name)
+        .bind(self.user_id)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if exists {
+            return Err(BackendError::Api(format!(
+                "Token '{}' already exists",
+                input.name
+            )));
+        }
+
+        let mut bytes = [0u8; 24];
+        rand::rng().fill_bytes(&mut bytes);
+        let token = format!("ow_{}", hex::encode(bytes));
+        let token_hash = hex::encode(Sha256::digest(token.as_bytes()));
+
+        let id: uuid::Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO api_keys (user_id, name, token_prefix, token_hash, scope, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(&input.name)
+        .bind(&token[..12])
+        .bind(&token_hash)
+        .bind(input.scope.to_string())
+        .bind(input.expires_at)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(IssuedToken {
+            id: id.to_string(),
+            name: input.name,
+            scope: input.scope,
+            token,
+            expires_at: input.expires_at,
+        })
+    }
+
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, scope, token_prefix, last_used_at, expires_at, created_at
+            FROM api_keys
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ApiToken {
+                    id: row.get::<uuid::Uuid, _>("id").to_string(),
+                    name: row.get("name"),
+                    scope: row
+                        .get::<String, _>("scope")
+                        .parse()
+                        .map_err(BackendError::Api)?,
+                    prefix: row.get("token_prefix"),
+                    last_used_at: row.get("last_used_at"),
+                    expires_at: row.get("expires_at"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect()
+    }
+
+    async fn revoke_token(&self, name: &str) -> Result<(), BackendError> {
+        let result =
+            sqlx::query("DELETE FROM api_keys WHERE (name = $1 OR id::text = $1) AND user_id = $2")
+                .bind(name)
+                .bind(self.user_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Token '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Organization methods
+    async fn list_organizations(&self) -> Result<Vec<Organization>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT o.id, o.name, m.role::text as role, o.created_at,
+                   (SELECT count(*) FROM organization_members x
+                    WHERE x.organization_id = o.id) as members
+            FROM organizations o
+            JOIN organization_members m ON m.organization_id = o.id
+            WHERE m.user_id = $1
+            ORDER BY o.name
+            "#,
+        )
+        .bind(self.member_id)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Organization {
+                id: row.get::<uuid::Uuid, _>("id").to_string(),
+                name: row.get("name"),
+                role: row.get("role"),
+                members: row.get("members"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn create_organization(&self, name: &str) -> Result<Organization, BackendError> {
+        // The organization's account shares the username namespace
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE username = $1) \
+             OR EXISTS(SELECT 1 FROM organizations WHERE name = $1)",
+        )
+        .bind(name)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if taken {
+            return Err(BackendError::Api(format!(
+                "The name '{}' is already taken",
+                name
+            )));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let account_id: uuid::Uuid =
+            sqlx::query_scalar("INSERT INTO users (username) VALUES ($1) RETURNING id")
+                .bind(name)
+                .fetch_one(&mut *tx)
+                .timed(Phase::Query)
+                .await?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO organizations (name, account_id)
+            VALUES ($1, $2)
+            RETURNING id, created_at
+            "#,
+        )
+        .bind(name)
+        .bind(account_id)
+        .fetch_one(&mut *tx)
+        .timed(Phase::Query)
+        .await?;
+
+        let id: uuid::Uuid = row.get("id");
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, 'owner')",
+        )
+        .bind(id)
+        .bind(self.member_id)
+        .execute(&mut *tx)
+        .timed(Phase::Query)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Organization {
+            id: id.to_string(),
+            name: name.to_string(),
+            role: "owner".to_string(),
+            members: 1,
+            created_at: row.get("created_at"),
+        })
+    }
+
+    async fn add_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        // Owners only; an owner cannot demote themselves
+        let result = sqlx::query(
+            r#"
+            INSERT INTO organization_members (organization_id, user_id, role)
+            SELECT o.id, u.id, $3::enum_organization_role
+            FROM organizations o
+            JOIN organization_members me
+              ON me.organization_id = o.id AND me.user_id = $4 AND me.role = 'owner'
+            JOIN users u ON u.username = $2
+            WHERE o.name = $1 AND NOT (u.id = $4 AND $3 <> 'owner')
+            ON CONFLICT (organization_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            "#,
+        )
+        .bind(org)
+        .bind(username)
+        .bind(role)
+        .bind(self.member_id)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Organization '{}' or user '{}' not found, or you are not an owner of '{}'",
+                org, username, org
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<(), BackendError> {
+        // Owners remove anyone, members only themselves; the last owner stays
+        let result = sqlx::query(
+            r#"
+            DELETE FROM organization_members m
+            USING organizations o, users u, organization_members me
+            WHERE m.organization_id = o.id AND o.name = $1
+              AND m.user_id = u.id AND u.username = $2
+              AND me.organization_id = o.id AND me.user_id = $3
+              AND (me.role = 'owner' OR u.id = $3)
+              AND (m.role <> 'owner' OR EXISTS (
+                  SELECT 1 FROM organization_members x
+                  WHERE x.organization_id = o.id AND x.role = 'owner' AND x.user_id <> m.user_id
+              ))
+            "#,
+        )
+        .bind(org)
+        .bind(username)
+        .bind(self.member_id)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
 
         if result.rows_affected() == 0 {
             return Err(BackendError::NotFound(format!(
-                "Storage config '{}' not found",
-                name
+                "Could not remove '{}' from '{}': not a member, not allowed, or the last owner",
+                username, org
             )));
         }
 
         Ok(())
     }
 
-    // KV methods
-    async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
+    // Account secret methods
+    async fn list_account_secrets(&self) -> Result<Vec<AccountSecret>, BackendError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, "desc", created_at, updated_at
-            FROM kv_configs
-            WHERE user_id = $1
-            ORDER BY name
+            SELECT s.id, s.key, s.updated_at,
+                   COALESCE(array_agg(DISTINCT e.name) FILTER (WHERE e.name IS NOT NULL), '{}')
+                       as environments
+            FROM account_secrets s
+            LEFT JOIN environment_values ev ON ev.account_secret_id = s.id
+            LEFT JOIN environments e ON e.id = ev.environment_id
+            WHERE s.user_id = $1
+            GROUP BY s.id
+            ORDER BY s.key
             "#,
         )
         .bind(self.user_id)
         .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        let namespaces = rows
+        let secrets = rows
             .iter()
-            .map(|row| KvNamespace {
+            .map(|row| AccountSecret {
                 id: row.get::<uuid::Uuid, _>("id").to_string(),
-                name: row.get("name"),
-                description: row.get("desc"),
-                created_at: row.get("created_at"),
+                key: row.get("key"),
+                environments: row.get("environments"),
                 updated_at: row.get("updated_at"),
             })
             .collect();
 
-        Ok(namespaces)
+        Ok(secrets)
     }
 
-    async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
-        let row = sqlx::query(
+    async fn set_account_secret(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<AccountSecret, BackendError> {
+        // Referencing environment values are refreshed by a trigger
+        sqlx::query(
             r#"
-            SELECT id, name, "desc", created_at, updated_at
-            FROM kv_configs
-            WHERE name = $1 AND user_id = $2
+            INSERT INTO account_secrets (user_id, key, value)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, key) DO UPDATE
+            SET value = EXCLUDED.value, updated_at = now()
             "#,
         )
-        .bind(name)
         .bind(self.user_id)
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_else(|| BackendError::NotFound(format!("KV namespace '{}' not found", name)))?;
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .timed(Phase::Query)
+        .await?;
 
-        Ok(KvNamespace {
-            id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        })
+        self.list_account_secrets()
+            .await?
+            .into_iter()
+            .find(|s| s.key == key)
+            .ok_or_else(|| BackendError::NotFound(format!("Account secret '{}' not found", key)))
     }
 
-    async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
-        let row = sqlx::query(
+    async fn delete_account_secret(&self, key: &str) -> Result<(), BackendError> {
+        let in_use: Vec<String> = sqlx::query_scalar(
             r#"
-            INSERT INTO kv_configs (name, "desc", user_id)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, "desc", created_at, updated_at
+            SELECT DISTINCT e.name
+            FROM account_secrets s
+            JOIN environment_values ev ON ev.account_secret_id = s.id
+            JOIN environments e ON e.id = ev.environment_id
+            WHERE s.key = $1 AND s.user_id = $2
+            ORDER BY e.name
             "#,
         )
-        .bind(&input.name)
-        .bind(&input.desc)
+        .bind(key)
         .bind(self.user_id)
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        Ok(KvNamespace {
-            id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        })
-    }
+        if !in_use.is_empty() {
+            return Err(BackendError::Api(format!(
+                "Account secret '{}' is still referenced by: {}",
+                key,
+                in_use.join(", ")
+            )));
+        }
 
-    async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
-        let result = sqlx::query("DELETE FROM kv_configs WHERE name = $1 AND user_id = $2")
-            .bind(name)
+        let result = sqlx::query("DELETE FROM account_secrets WHERE key = $1 AND user_id = $2")
+            .bind(key)
             .bind(self.user_id)
             .execute(&self.pool)
+            .timed(Phase::Query)
             .await?;
 
         if result.rows_affected() == 0 {
             return Err(BackendError::NotFound(format!(
-                "KV namespace '{}' not found",
-                name
+                "Account secret '{}' not found",
+                key
             )));
         }
 
         Ok(())
     }
 
-    // Database methods
-    async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
+    // Cross-reference methods
+    async fn resource_usage(
+        &self,
+        kind: ResourceKind,
+        name: &str,
+    ) -> Result<ResourceUsage, BackendError> {
+        let table = match kind {
+            ResourceKind::Kv => "kv_configs",
+            ResourceKind::Storage => "storage_configs",
+            ResourceKind::Database => "database_configs",
+            ResourceKind::Environment => "environments",
+        };
+
+        let id: uuid::Uuid = sqlx::query_scalar(&format!(
+            "SELECT id FROM {} WHERE name = $1 AND user_id = $2",
+            table
+        ))
+        .bind(name)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("{} '{}' not found", kind.as_str(), name)))?;
+
+        let mut bindings = Vec::new();
+        let mut environment_ids = Vec::new();
+
+        if kind == ResourceKind::Environment {
+            environment_ids.push(id);
+        } else {
+            let binding_types: Vec<String> =
+                kind.binding_types().iter().map(|t| t.to_string()).collect();
+
+            let rows = sqlx::query(
+                r#"
+                SELECT e.id AS environment_id, e.name AS environment, ev.key, ev.type::text AS type
+                FROM environment_values ev
+                JOIN environments e ON e.id = ev.environment_id
+                WHERE e.user_id = $1 AND ev.type::text = ANY($2) AND ev.value = $3
+                ORDER BY e.name, ev.key
+                "#,
+            )
+            .bind(self.user_id)
+            .bind(&binding_types)
+            .bind(id.to_string())
+            .fetch_all(&self.pool)
+            .timed(Phase::Query)
+            .await?;
+
+            for row in &rows {
+                environment_ids.push(row.get::<uuid::Uuid, _>("environment_id"));
+                bindings.push(ResourceBinding {
+                    environment: row.get("environment"),
+                    key: row.get("key"),
+                    binding_type: row.get("type"),
+                });
+            }
+        }
+
+        let workers: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT name FROM workers
+            WHERE user_id = $1 AND environment_id = ANY($2)
+            ORDER BY name
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(&environment_ids)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        // Storage routes are served from the main worker's (same id as the project) ASSETS binding
+        let projects: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT p.name
+            FROM projects p
+            JOIN project_routes pr ON pr.project_id = p.id
+            JOIN workers w ON w.id = COALESCE(pr.worker_id, p.id)
+            WHERE p.user_id = $1 AND w.environment_id = ANY($2)
+            ORDER BY p.name
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(&environment_ids)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        Ok(ResourceUsage {
+            bindings,
+            workers,
+            projects,
+        })
+    }
+
+    // Search methods
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, BackendError> {
+        // strpos rather than ILIKE, so '%' and '_' in the query match literally
         let rows = sqlx::query(
             r#"
-            SELECT id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
-            FROM database_configs
+            SELECT r.kind, r.name, r.description
+            FROM (
+                SELECT 'worker' AS kind, name, "desc" AS description
+                FROM workers WHERE user_id = $1 AND name IS NOT NULL
+                UNION ALL
+                SELECT 'project', name, "desc" FROM projects WHERE user_id = $1
+                UNION ALL
+                SELECT 'environment', name, "desc" FROM environments WHERE user_id = $1
+                UNION ALL
+                SELECT 'kv', name, "desc" FROM kv_configs WHERE user_id = $1
+                UNION ALL
+                SELECT 'storage', name, "desc" FROM storage_configs WHERE user_id = $1
+                UNION ALL
+                SELECT 'database', name, "desc" FROM database_configs WHERE user_id = $1
+            ) r
+            WHERE strpos(lower(r.name), lower($2)) > 0
+               OR strpos(lower(COALESCE(r.description, '')), lower($2)) > 0
+            ORDER BY lower(r.name) <> lower($2), strpos(lower(r.name), lower($2)) = 0, r.name, r.kind
+            LIMIT $3
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(query)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .timed(Phase::Query)
+        .await?;
+
+        let results = rows
+            .iter()
+            .map(|row| SearchResult {
+                kind: row.get("kind"),
+                name: row.get("name"),
+                description: row.get("description"),
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    // Notification methods
+    async fn list_notification_webhooks(&self) -> Result<Vec<NotificationWebhook>, BackendError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, url, events, created_at, updated_at
+            FROM notification_webhooks
             WHERE user_id = $1
-            ORDER BY name
+            ORDER BY created_at
             "#,
         )
         .bind(self.user_id)
         .fetch_all(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        let databases = rows
+        let webhooks = rows
             .iter()
-            .map(|row| Database {
+            .map(|row| NotificationWebhook {
                 id: row.get::<uuid::Uuid, _>("id").to_string(),
-                name: row.get("name"),
-                description: row.get("desc"),
-                provider: row.get("provider"),
-                max_rows: row.get("max_rows"),
-                timeout_seconds: row.get("timeout_seconds"),
+                url: row.get("url"),
+                events: row
+                    .get::<Vec<String>, _>("events")
+                    .iter()
+                    .filter_map(|e| NotificationEvent::parse(e))
+                    .collect(),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             })
             .collect();
 
-        Ok(databases)
+        Ok(webhooks)
     }
 
-    async fn get_database(&self, name: &str) -> Result<Database, BackendError> {
+    async fn set_notification_webhook(
+        &self,
+        url: &str,
+        events: &[NotificationEvent],
+    ) -> Result<NotificationWebhook, BackendError> {
+        let events: Vec<&str> = events.iter().map(|e| e.as_str()).collect();
+
         let row = sqlx::query(
             r#"
-            SELECT id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
-            FROM database_configs
-            WHERE name = $1 AND user_id = $2
+            INSERT INTO notification_webhooks (user_id, url, events)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, url) DO UPDATE
+            SET events = EXCLUDED.events, updated_at = now()
+            RETURNING id, url, events, created_at, updated_at
             "#,
         )
-        .bind(name)
         .bind(self.user_id)
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_else(|| BackendError::NotFound(format!("Database '{}' not found", name)))?;
+        .bind(url)
+        .bind(&events)
+        .fetch_one(&self.pool)
+        .timed(Phase::Query)
+        .await?;
 
-        Ok(Database {
+        Ok(NotificationWebhook {
             id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            provider: row.get("provider"),
-            max_rows: row.get("max_rows"),
-            timeout_seconds: row.get("timeout_seconds"),
+            url: row.get("url"),
+            events: row
+                .get::<Vec<String>, _>("events")
+                .iter()
+                .filter_map(|e| NotificationEvent::parse(e))
+                .collect(),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
     }
 
-    async fn create_database(&self, input: CreateDatabaseInput) -> Result<Database, BackendError> {
-        let row = sqlx::query(
+    async fn delete_notification_webhook(&self, url: &str) -> Result<(), BackendError> {
+        let result =
+            sqlx::query("DELETE FROM notification_webhooks WHERE url = $1 AND user_id = $2")
+                .bind(url)
+                .bind(self.user_id)
+                .execute(&self.pool)
+                .timed(Phase::Query)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackendError::NotFound(format!(
+                "Webhook '{}' not found",
+                url
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Tracing methods
+    async fn record_operation(
+        &self,
+        context: &RequestContext,
+        error: Option<&str>,
+    ) -> Result<(), BackendError> {
+        sqlx::query(
             r#"
-            INSERT INTO database_configs (user_id, name, "desc", provider, connection_string, max_rows, timeout_seconds)
-            VALUES ($1, $2, $3, $4, $5, COALESCE($6, 1000), COALESCE($7, 30))
-            RETURNING id, name, "desc", provider, max_rows, timeout_seconds, created_at, updated_at
+            INSERT INTO cli_operations (request_id, user_id, operation, status, error)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (request_id) DO NOTHING
             "#,
         )
+        .bind(&context.id)
         .bind(self.user_id)
-        .bind(&input.name)
-        .bind(&input.desc)
-        .bind(&input.provider)
-        .bind(&input.connection_string)
-        .bind(input.max_rows)
-        .bind(input.timeout_seconds)
-        .fetch_one(&self.pool)
+        .bind(&context.operation)
+        .bind(if error.is_some() { "error" } else { "ok" })
+        .bind(error)
+        .execute(&self.pool)
+        .timed(Phase::Query)
         .await?;
 
-        Ok(Database {
-            id: row.get::<uuid::Uuid, _>("id").to_string(),
-            name: row.get("name"),
-            description: row.get("desc"),
-            provider: row.get("provider"),
-            max_rows: row.get("max_rows"),
-            timeout_seconds: row.get("timeout_seconds"),
+        context.mark_sent();
+
+        Ok(())
+    }
+
+    async fn get_trace(&self, request_id: &str) -> Result<TraceRecord, BackendError> {
+        let row = sqlx::query(
+            r#"
+            SELECT request_id, operation, status, error, created_at
+            FROM cli_operations
+            WHERE request_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(request_id)
+        .bind(self.user_id)
+        .fetch_optional(&self.pool)
+        .timed(Phase::Query)
+        .await?
+        .ok_or_else(|| BackendError::NotFound(format!("No record of request '{}'", request_id)))?;
+
+        Ok(TraceRecord {
+            request_id: row.get("request_id"),
+            operation: row.get("operation"),
+            status: row.get("status"),
+            error: row.get("error"),
             created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
+            details: None,
         })
     }
+}
 
-    async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
-        let result = sqlx::query("DELETE FROM database_configs WHERE name = $1 AND user_id = $2")
+/// Error of a wait for the deploy lock: running out of time is the expected
+/// failure, anything else is a database error
+fn deploy_lock_error(error: sqlx::Error, name: &str) -> BackendError {
+    match error {
+        // lock_not_available
+        sqlx::Error::Database(e) if e.code().as_deref() == Some("55P03") => {
+            BackendError::Api(format!(
+                "Timed out after {} waiting for another deploy to '{}'",
+                DEPLOY_LOCK_TIMEOUT, name
+            ))
+        }
+        e => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Backend signed in as a fresh user of the scratch database (see
+    /// `commands::db`), or None when OW_TEST_DATABASE_URL is unset
+    async fn test_backend() -> Option<DbBackend> {
+        let database_url = std::env::var("OW_TEST_DATABASE_URL").ok()?;
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&database_url)
+            .await
+            .unwrap();
+
+        let username = format!("test-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+
+        sqlx::query("INSERT INTO users (username) VALUES ($1)")
+            .bind(&username)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        Some(
+            DbBackend::new(pool, Some(username), None, None)
+                .await
+                .unwrap(),
+        )
+    }
+
+    async fn drop_user(backend: &DbBackend) {
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(backend.user_id)
+            .execute(&backend.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_current_deployments_keeps_order() {
+        let Some(backend) = test_backend().await else {
+            return;
+        };
+
+        let mut workers = vec![];
+
+        for (name, version) in [("web", Some(2)), ("cron", None), ("api", Some(1))] {
+            let id: uuid::Uuid = sqlx::query_scalar(
+                "INSERT INTO workers (name, user_id, current_version) VALUES ($1, $2, $3) RETURNING id",
+            )
             .bind(name)
-            .bind(self.user_id)
-            .execute(&self.pool)
-            .await?;
+            .bind(backend.user_id)
+            .bind(version)
+            .fetch_one(&backend.pool)
+            .await
+            .unwrap();
+
+            for v in 1..=version.unwrap_or(0) {
+                sqlx::query(
+                    r#"
+                    INSERT INTO worker_deployments (worker_id, version, hash, code_type, code, message)
+                    VALUES ($1, $2, '', 'javascript', '', $3)
+                    "#,
+                )
+                .bind(id)
+                .bind(v)
+                .bind(format!("{} v{}", name, v))
+                .execute(&backend.pool)
+                .await
+                .unwrap();
+            }
 
-        if result.rows_affected() == 0 {
-            return Err(BackendError::NotFound(format!(
-                "Database '{}' not found",
-                name
-            )));
+            workers.push(backend.get_worker(name).await.unwrap());
         }
 
-        Ok(())
+        let deployments = backend.list_current_deployments(&workers).await;
+        drop_user(&backend).await;
+
+        let messages: Vec<Option<String>> = deployments
+            .unwrap()
+            .into_iter()
+            .map(|d| d.and_then(|d| d.message))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![Some("web v2".to_string()), None, Some("api v1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_deploy_lock_error_passes_other_errors() {
+        let err = deploy_lock_error(sqlx::Error::PoolTimedOut, "api");
+        assert!(matches!(
+            err,
+            BackendError::Database(sqlx::Error::PoolTimedOut)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_lock_timeout() {
+        let Some(backend) = test_backend().await else {
+            return;
+        };
+
+        let worker_id = uuid::Uuid::new_v4();
+        let mut holder = backend.pool.acquire().await.unwrap().detach();
+        let mut tx = holder.begin().await.unwrap();
+        DbBackend::lock_deploys(&mut tx, worker_id, "api")
+            .await
+            .unwrap();
+
+        // A second deploy giving up right away, as it would after DEPLOY_LOCK_TIMEOUT
+        let database_url = std::env::var("OW_TEST_DATABASE_URL").unwrap();
+        let mut waiter = PgConnection::connect(&database_url).await.unwrap();
+        let err = sqlx::query(
+            "SELECT set_config('lock_timeout', '10ms', false), pg_advisory_lock(hashtextextended('ow-deploy:' || $1::text, 0))",
+        )
+        .bind(worker_id)
+        .execute(&mut waiter)
+        .await
+        .unwrap_err();
+
+        tx.rollback().await.unwrap();
+        drop_user(&backend).await;
+
+        let err = deploy_lock_error(err, "api");
+        assert!(err.to_string().contains("Timed out after 5min"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_lock_wait_restores_timeouts() {
+        let Some(backend) = test_backend().await else {
+            return;
+        };
+
+        let worker_id = uuid::Uuid::new_v4();
+        let mut holder = backend.pool.acquire().await.unwrap().detach();
+        let mut held = holder.begin().await.unwrap();
+        DbBackend::lock_deploys(&mut held, worker_id, "api")
+            .await
+            .unwrap();
+
+        let database_url = std::env::var("OW_TEST_DATABASE_URL").unwrap();
+        let mut waiter = PgConnection::connect(&database_url).await.unwrap();
+        let mut tx = waiter.begin().await.unwrap();
+
+        sqlx::query("SELECT set_config('lock_timeout', '3s', true), set_config('statement_timeout', '7s', true)")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        let release = async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            held.rollback().await.unwrap();
+        };
+        let (locked, _) = tokio::join!(DbBackend::lock_deploys(&mut tx, worker_id, "api"), release);
+
+        let timeouts: (String, String) = sqlx::query_as(
+            "SELECT current_setting('lock_timeout'), current_setting('statement_timeout')",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+
+        tx.rollback().await.unwrap();
+        drop_user(&backend).await;
+
+        locked.unwrap();
+        assert_eq!(timeouts, ("3s".to_string(), "7s".to_string()));
     }
 }
@@ -0,0 +1,1610 @@
+//! Mirror backend: runs every command against a primary and a shadow backend
+//! while migrating a self-hosted database onto the hosted API.
+//!
+//! The primary answers. Writes that succeed on the primary are replayed on
+//! the shadow; reads run on both and are compared, ignoring IDs and
+//! timestamps. Differences are collected as divergences, never errors.
+
+use super::{
+    AccountSecret, ApiToken, AssetManifestEntry, Backend, BackendError, CapturedRequest,
+    CreateDatabaseInput, CreateEnvironmentInput, CreateKvInput, CreateStorageInput,
+    CreateTokenInput, CreateWorkerInput, Database, DeletePreview, DeployApproval,
+    DeployAuthorization, DeployInput, Deployment, DirectUploadConfig, Environment,
+    EnvironmentValue, EnvironmentValueChange, EnvironmentValueInput, ExecResult, IssuedToken,
+    KvEntry, KvNamespace, NotificationEvent, NotificationWebhook, Organization, PlatformStorage,
+    Project, RequestContext, ResourceKind, ResourceUsage, SearchResult, StorageConfig, TraceRecord,
+    UpdateDatabaseInput, UpdateEnvironmentInput, UpdateKvInput, UpdateStorageInput,
+    UpdateWorkerInput, UploadResult, Worker, WorkerEvent, WorkerRuntime, WorkerShare, WorkerUrl,
+};
+use crate::config::{Config, PlatformStorageConfig};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Divergences of every run, in ~/.openworkers
+const LOG_FILE: &str = "mirror.log";
+
+/// Longest value quoted in a divergence
+const MAX_QUOTED: usize = 60;
+
+/// A result that differed between primary and shadow
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Backend method, e.g. "get_worker"
+    pub operation: String,
+    pub detail: String,
+}
+
+pub struct MirrorBackend<P, S> {
+    primary: P,
+    shadow: S,
+    /// Primary ID -> shadow ID of workers, environments, their values and
+    /// bound resources, learned from results so writes taking IDs can be replayed
+    ids: Mutex<HashMap<String, String>>,
+    divergences: Mutex<Vec<Divergence>>,
+}
+
+impl<P: Backend, S: Backend> MirrorBackend<P, S> {
+    pub fn new(primary: P, shadow: S) -> Self {
+        Self {
+            primary,
+            shadow,
+            ids: Mutex::new(HashMap::new()),
+            divergences: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.divergences.lock().unwrap().clone()
+    }
+
+    /// Print divergences to stderr and append them to ~/.openworkers/mirror.log
+    pub fn report(&self, mirror: &str) {
+        let divergences = self.divergences();
+
+        if divergences.is_empty() {
+            return;
+        }
+
+        for divergence in &divergences {
+            eprintln!(
+                "{} {}: {}",
+                "diverged:".yellow().bold(),
+                divergence.operation,
+                divergence.detail
+            );
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let lines: String = divergences
+            .iter()
+            .map(|d| format!("{}\t{}\t{}\t{}\n", now, mirror, d.operation, d.detail))
+            .collect();
+
+        let written = Config::config_dir()
+            .map_err(|e| e.to_string())
+            .and_then(|dir| {
+                std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dir.join(LOG_FILE))
+                    .and_then(|mut file| file.write_all(lines.as_bytes()))
+                    .map_err(|e| e.to_string())
+            });
+
+        if let Err(e) = written {
+            eprintln!(
+                "{} Could not write {}: {}",
+                "warning:".yellow().bold(),
+                LOG_FILE,
+                e
+            );
+        }
+    }
+
+    fn diverged(&self, operation: &str, detail: String) {
+        self.divergences.lock().unwrap().push(Divergence {
+            operation: operation.to_string(),
+            detail,
+        });
+    }
+
+    fn learn(&self, primary: &str, shadow: &str) {
+        self.ids
+            .lock()
+            .unwrap()
+            .insert(primary.to_string(), shadow.to_string());
+    }
+
+    fn shadow_id(&self, primary: &str) -> Option<String> {
+        self.ids.lock().unwrap().get(primary).cloned()
+    }
+
+    fn learn_workers(&self, primary: &[Worker], shadow: &[Worker]) {
+        for worker in primary {
+            if let Some(other) = shadow.iter().find(|w| w.name == worker.name) {
+                self.learn(&worker.id, &other.id);
+            }
+        }
+    }
+
+    fn learn_environment(&self, primary: &Environment, shadow: &Environment) {
+        self.learn(&primary.id, &shadow.id);
+
+        for value in &primary.values {
+            if let Some(other) = shadow.values.iter().find(|v| v.key == value.key) {
+                self.learn(&value.id, &other.id);
+            }
+        }
+    }
+
+    /// Learn kv, storage and database IDs by name, for bindings to resources
+    /// the mirror hasn't seen created
+    async fn learn_resources(&self) {
+        let (primary, shadow) = futures::join!(self.primary.list_kv(), self.shadow.list_kv());
+
+        if let (Ok(p), Ok(s)) = (primary, shadow) {
+            self.learn_named(
+                p.iter().map(|k| (&k.name, &k.id)),
+                s.iter().map(|k| (&k.name, &k.id)),
+            );
+        }
+
+        let (primary, shadow) =
+            futures::join!(self.primary.list_storage(), self.shadow.list_storage());
+
+        if let (Ok(p), Ok(s)) = (primary, shadow) {
+            self.learn_named(
+                p.iter().map(|c| (&c.name, &c.id)),
+                s.iter().map(|c| (&c.name, &c.id)),
+            );
+        }
+
+        let (primary, shadow) =
+            futures::join!(self.primary.list_databases(), self.shadow.list_databases());
+
+        if let (Ok(p), Ok(s)) = (primary, shadow) {
+            self.learn_named(
+                p.iter().map(|d| (&d.name, &d.id)),
+                s.iter().map(|d| (&d.name, &d.id)),
+            );
+        }
+    }
+
+    fn learn_named<'a>(
+        &self,
+        primary: impl Iterator<Item = (&'a String, &'a String)>,
+        shadow: impl Iterator<Item = (&'a String, &'a String)> + Clone,
+    ) {
+        for (name, id) in primary {
+            if let Some((_, other)) = shadow.clone().find(|(n, _)| *n == name) {
+                self.learn(id, other);
+            }
+        }
+    }
+
+    /// Primary resource IDs bound in a normalized result, as the shadow's
+    fn translate_bindings(&self, value: Value) -> Value {
+        match value {
+            Value::Object(mut map) => {
+                let bound = map
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .is_some_and(is_binding);
+
+                if bound
+                    && let Some(id) = map
+                        .get("value")
+                        .and_then(Value::as_str)
+                        .and_then(|id| self.shadow_id(id))
+                {
+                    map.insert("value".to_string(), Value::String(id));
+                }
+
+                Value::Object(
+                    map.into_iter()
+                        .map(|(key, value)| (key, self.translate_bindings(value)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.translate_bindings(item))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn compare<T: Serialize>(
+        &self,
+        operation: &str,
+        primary: Result<T, &BackendError>,
+        shadow: Result<T, &BackendError>,
+    ) {
+        let detail = match (primary, shadow) {
+            (Ok(primary), Ok(shadow)) => {
+                let primary = normalize(&self.translate_bindings(to_json(&primary)));
+                let shadow = normalize(&shadow);
+
+                match first_difference(&primary, &shadow, "") {
+                    Some(difference) => difference,
+                    None => return,
+                }
+            }
+            (Ok(_), Err(e)) => format!("shadow failed: {}", e),
+            (Err(e), Ok(_)) => format!("primary failed ({}), shadow succeeded", e),
+            (Err(p), Err(s)) if std::mem::discriminant(p) == std::mem::discriminant(s) => return,
+            (Err(p), Err(s)) => format!("primary failed ({}), shadow failed ({})", p, s),
+        };
+
+        self.diverged(operation, detail);
+    }
+
+    /// Run a read on both backends concurrently and return the primary's result
+    async fn read<T: Serialize>(
+        &self,
+        operation: &str,
+        primary: impl Future<Output = Result<T, BackendError>>,
+        shadow: impl Future<Output = Result<T, BackendError>>,
+    ) -> Result<T, BackendError> {
+        let (primary, shadow) = futures::join!(primary, shadow);
+        self.compare(operation, primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    /// Run a write on the primary, then on the shadow if the primary accepted it
+    async fn write<T: Serialize, F: Future<Output = Result<T, BackendError>>>(
+        &self,
+        operation: &str,
+        primary: impl Future<Output = Result<T, BackendError>>,
+        shadow: impl FnOnce() -> F,
+    ) -> Result<T, BackendError> {
+        let primary = primary.await;
+
+        if primary.is_ok() {
+            let shadow = shadow().await;
+            self.compare(operation, primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+}
+
+/// Authorization for replaying a deploy on the shadow. The primary already
+/// checked it and consumed any approval token, which the shadow never saw.
+fn replayed() -> DeployAuthorization {
+    DeployAuthorization {
+        approve_token: None,
+        confirmed: true,
+    }
+}
+
+/// Value types holding the ID of a resource rather than a literal value
+fn is_binding(value_type: &str) -> bool {
+    matches!(value_type, "kv" | "storage" | "assets" | "database")
+}
+
+/// Resource a value binds, if it's a binding
+fn bound_resource(value: &EnvironmentValueInput) -> Option<&str> {
+    value
+        .value
+        .as_deref()
+        .filter(|_| is_binding(&value.value_type))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Result as JSON without what legitimately differs between two backends:
+/// IDs, timestamps, worker URLs and secret values. Arrays are sorted so
+/// listing order doesn't matter.
+fn normalize<T: Serialize>(value: &T) -> Value {
+    fn strip(value: Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let secret = map.get("type").and_then(Value::as_str) == Some("secret");
+                // Workers are served from a different domain by each backend
+                let worker = map.contains_key("name");
+
+                Value::Object(
+                    map.into_iter()
+                        .filter(|(key, _)| !is_volatile(key))
+                        .filter(|(key, _)| !(secret && key == "value" || worker && key == "url"))
+                        .map(|(key, value)| (key, strip(value)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => {
+                let mut items: Vec<Value> = items.into_iter().map(strip).collect();
+                items.sort_by_cached_key(|item| item.to_string());
+                Value::Array(items)
+            }
+            other => other,
+        }
+    }
+
+    strip(to_json(value))
+}
+
+fn is_volatile(key: &str) -> bool {
+    key == "id"
+        || key.ends_with("Id")
+        || key.ends_with("_id")
+        || key.ends_with("At")
+        || key.ends_with("_at")
+}
+
+/// Path and values of the first difference between two normalized results
+fn first_difference(primary: &Value, shadow: &Value, path: &str) -> Option<String> {
+    match (primary, shadow) {
+        (Value::Object(p), Value::Object(s)) => {
+            let keys: std::collections::BTreeSet<&String> = p.keys().chain(s.keys()).collect();
+
+            keys.into_iter().find_map(|key| {
+                let path = format!("{}.{}", path, key);
+
+                first_difference(
+                    p.get(key).unwrap_or(&Value::Null),
+                    s.get(key).unwrap_or(&Value::Null),
+                    &path,
+                )
+            })
+        }
+        (Value::Array(p), Value::Array(s)) if p.len() != s.len() => Some(format!(
+            "{}: {} items on primary, {} on shadow",
+            display_path(path),
+            p.len(),
+            s.len()
+        )),
+        (Value::Array(p), Value::Array(s)) => p
+            .iter()
+            .zip(s)
+            .enumerate()
+            .find_map(|(i, (p, s))| first_difference(p, s, &format!("{}[{}]", path, i))),
+        (p, s) if p == s => None,
+        (p, s) => Some(format!(
+            "{}: {} on primary, {} on shadow",
+            display_path(path),
+            quote(p),
+            quote(s)
+        )),
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    match path.trim_start_matches('.') {
+        "" => "result",
+        path => path,
+    }
+}
+
+fn quote(value: &Value) -> String {
+    let text = value.to_string();
+
+    match text.char_indices().nth(MAX_QUOTED) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+impl<P: Backend, S: Backend> Backend for MirrorBackend<P, S> {
+    fn is_default_cloud(&self) -> bool {
+        self.primary.is_default_cloud()
+    }
+
+    async fn list_workers(&self) -> Result<Vec<Worker>, BackendError> {
+        let (primary, shadow) =
+            futures::join!(self.primary.list_workers(), self.shadow.list_workers());
+
+        if let (Ok(p), Ok(s)) = (&primary, &shadow) {
+            self.learn_workers(p, s);
+        }
+
+        self.compare("list_workers", primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    async fn get_worker(&self, name: &str) -> Result<Worker, BackendError> {
+        let (primary, shadow) =
+            futures::join!(self.primary.get_worker(name), self.shadow.get_worker(name));
+
+        if let (Ok(p), Ok(s)) = (&primary, &shadow) {
+            self.learn(&p.id, &s.id);
+
+            if let (Some(p), Some(s)) = (&p.environment, &s.environment) {
+                self.learn(&p.id, &s.id);
+            }
+        }
+
+        self.compare("get_worker", primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    async fn create_worker(&self, input: CreateWorkerInput) -> Result<Worker, BackendError> {
+        let primary = self.primary.create_worker(input.clone()).await;
+
+        if let Ok(p) = &primary {
+            let shadow = self.shadow.create_worker(input).await;
+
+            if let Ok(s) = &shadow {
+                self.learn(&p.id, &s.id);
+            }
+
+            self.compare("create_worker", primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+
+    async fn delete_worker(&self, name: &str) -> Result<(), BackendError> {
+        self.write("delete_worker", self.primary.delete_worker(name), || {
+            self.shadow.delete_worker(name)
+        })
+        .await
+    }
+
+    async fn preview_delete_worker(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        self.read(
+            "preview_delete_worker",
+            self.primary.preview_delete_worker(name),
+            self.shadow.preview_delete_worker(name),
+        )
+        .await
+    }
+
+    async fn update_worker(
+        &self,
+        name: &str,
+        input: UpdateWorkerInput,
+    ) -> Result<Worker, BackendError> {
+        // The environment may be given by ID
+        let mut shadow_input = input.clone();
+
+        if let Some(id) = shadow_input
+            .environment
+            .as_deref()
+            .and_then(|e| self.shadow_id(e))
+        {
+            shadow_input.environment = Some(id);
+        }
+
+        self.write(
+            "update_worker",
+            self.primary.update_worker(name, input),
+            || self.shadow.update_worker(name, shadow_input),
+        )
+        .await
+    }
+
+    async fn link_worker_environment(
+        &self,
+        worker_id: &str,
+        environment_id: &str,
+    ) -> Result<(), BackendError> {
+        self.primary
+            .link_worker_environment(worker_id, environment_id)
+            .await?;
+
+        let (Some(worker), Some(environment)) =
+            (self.shadow_id(worker_id), self.shadow_id(environment_id))
+        else {
+            self.diverged(
+                "link_worker_environment",
+                "not mirrored: worker or environment unknown to the shadow".to_string(),
+            );
+            return Ok(());
+        };
+
+        if let Err(e) = self
+            .shadow
+            .link_worker_environment(&worker, &environment)
+            .await
+        {
+            self.diverged("link_worker_environment", format!("shadow failed: {}", e));
+        }
+
+        Ok(())
+    }
+
+    async fn set_worker_metadata(
+        &self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Worker, BackendError> {
+        self.write(
+            "set_worker_metadata",
+            self.primary.set_worker_metadata(name, metadata),
+            || self.shadow.set_worker_metadata(name, metadata),
+        )
+        .await
+    }
+
+    async fn get_current_deployment(&self, name: &str) -> Result<Option<Deployment>, BackendError> {
+        self.read(
+            "get_current_deployment",
+            self.primary.get_current_deployment(name),
+            self.shadow.get_current_deployment(name),
+        )
+        .await
+    }
+
+    async fn list_current_deployments(
+        &self,
+        workers: &[Worker],
+    ) -> Result<Vec<Option<Deployment>>, BackendError> {
+        // The same workers, by the IDs the shadow knows them by
+        let shadow_workers: Vec<Worker> = workers
+            .iter()
+            .map(|worker| Worker {
+                id: self
+                    .shadow_id(&worker.id)
+                    .unwrap_or_else(|| worker.id.clone()),
+                ..worker.clone()
+            })
+            .collect();
+
+        self.read(
+            "list_current_deployments",
+            self.primary.list_current_deployments(workers),
+            self.shadow.list_current_deployments(&shadow_workers),
+        )
+        .await
+    }
+
+    async fn deploy_worker(
+        &self,
+        name: &str,
+        input: DeployInput,
+    ) -> Result<Deployment, BackendError> {
+        let replay = DeployInput {
+            authorization: replayed(),
+            ..input.clone()
+        };
+
+        self.write(
+            "deploy_worker",
+            self.primary.deploy_worker(name, input),
+            || self.shadow.deploy_worker(name, replay),
+        )
+        .await
+    }
+
+    /// Assets are only uploaded through the primary's presigned URLs or
+    /// credentials; the shadow gets the code and manifest.
+    async fn upload_worker(
+        &self,
+        name: &str,
+        path: &std::path::Path,
+        zip_data: Vec<u8>,
+        assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
+    ) -> Result<UploadResult, BackendError> {
+        let primary = self
+            .primary
+            .upload_worker(name, path, zip_data.clone(), assets_manifest, authorization)
+            .await;
+
+        if primary.is_ok() {
+            let shadow = self
+                .shadow
+                .upload_worker(name, path, zip_data, assets_manifest, &replayed())
+                .await;
+
+            self.compare(
+                "upload_worker",
+                primary.as_ref().map(|r| (&r.worker, &r.deployed)),
+                shadow.as_ref().map(|r| (&r.worker, &r.deployed)),
+            );
+        }
+
+        primary
+    }
+
+    async fn create_deploy_approval(
+        &self,
+        name: &str,
+        ttl_seconds: i64,
+    ) -> Result<DeployApproval, BackendError> {
+        self.primary.create_deploy_approval(name, ttl_seconds).await
+    }
+
+    async fn exec_worker(
+        &self,
+        name: &str,
+        event: WorkerEvent,
+    ) -> Result<ExecResult, BackendError> {
+        self.primary.exec_worker(name, event).await
+    }
+
+    async fn worker_runtime(&self, name: &str) -> Result<WorkerRuntime, BackendError> {
+        self.primary.worker_runtime(name).await
+    }
+
+    async fn get_captured_request(
+        &self,
+        name: &str,
+        id: &str,
+    ) -> Result<CapturedRequest, BackendError> {
+        self.primary.get_captured_request(name, id).await
+    }
+
+    async fn redeploy_worker(
+        &self,
+        name: &str,
+        message: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        self.write(
+            "redeploy_worker",
+            self.primary.redeploy_worker(name, message),
+            || self.shadow.redeploy_worker(name, message),
+        )
+        .await
+    }
+
+    async fn tag_deployment(
+        &self,
+        name: &str,
+        version: i32,
+        tag: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        self.write(
+            "tag_deployment",
+            self.primary.tag_deployment(name, version, tag),
+            || self.shadow.tag_deployment(name, version, tag),
+        )
+        .await
+    }
+
+    async fn list_deployments(&self, name: &str) -> Result<Vec<Deployment>, BackendError> {
+        self.read(
+            "list_deployments",
+            self.primary.list_deployments(name),
+            self.shadow.list_deployments(name),
+        )
+        .await
+    }
+
+    async fn get_deployment_code(&self, name: &str, version: i32) -> Result<Vec<u8>, BackendError> {
+        self.primary.get_deployment_code(name, version).await
+    }
+
+    async fn prune_deployments(&self, name: &str, keep: u32) -> Result<Vec<i32>, BackendError> {
+        self.write(
+            "prune_deployments",
+            self.primary.prune_deployments(name, keep),
+            || self.shadow.prune_deployments(name, keep),
+        )
+        .await
+    }
+
+    async fn get_retention_policy(&self) -> Result<Option<u32>, BackendError> {
+        self.read(
+            "get_retention_policy",
+            self.primary.get_retention_policy(),
+            self.shadow.get_retention_policy(),
+        )
+        .await
+    }
+
+    async fn set_retention_policy(&self, keep: Option<u32>) -> Result<(), BackendError> {
+        self.write(
+            "set_retention_policy",
+            self.primary.set_retention_policy(keep),
+            || self.shadow.set_retention_policy(keep),
+        )
+        .await
+    }
+
+    async fn list_worker_urls(&self, name: &str) -> Result<Vec<WorkerUrl>, BackendError> {
+        self.read(
+            "list_worker_urls",
+            self.primary.list_worker_urls(name),
+            self.shadow.list_worker_urls(name),
+        )
+        .await
+    }
+
+    async fn add_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        self.write(
+            "add_worker_url",
+            self.primary.add_worker_url(name, url),
+            || self.shadow.add_worker_url(name, url),
+        )
+        .await
+    }
+
+    async fn remove_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        self.write(
+            "remove_worker_url",
+            self.primary.remove_worker_url(name, url),
+            || self.shadow.remove_worker_url(name, url),
+        )
+        .await
+    }
+
+    async fn list_worker_values(&self, name: &str) -> Result<Vec<EnvironmentValue>, BackendError> {
+        self.read(
+            "list_worker_values",
+            self.primary.list_worker_values(name),
+            self.shadow.list_worker_values(name),
+        )
+        .await
+    }
+
+    async fn set_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+        value_type: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        self.write(
+            "set_worker_value",
+            self.primary.set_worker_value(name, key, value, value_type),
+            || self.shadow.set_worker_value(name, key, value, value_type),
+        )
+        .await
+    }
+
+    async fn delete_worker_value(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_worker_value",
+            self.primary.delete_worker_value(name, key),
+            || self.shadow.delete_worker_value(name, key),
+        )
+        .await
+    }
+
+    async fn list_worker_shares(&self, name: &str) -> Result<Vec<WorkerShare>, BackendError> {
+        self.read(
+            "list_worker_shares",
+            self.primary.list_worker_shares(name),
+            self.shadow.list_worker_shares(name),
+        )
+        .await
+    }
+
+    async fn share_worker(
+        &self,
+        name: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        self.write(
+            "share_worker",
+            self.primary.share_worker(name, username, role),
+            || self.shadow.share_worker(name, username, role),
+        )
+        .await
+    }
+
+    async fn unshare_worker(&self, name: &str, username: &str) -> Result<(), BackendError> {
+        self.write(
+            "unshare_worker",
+            self.primary.unshare_worker(name, username),
+            || self.shadow.unshare_worker(name, username),
+        )
+        .await
+    }
+
+    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
+        self.read(
+            "list_projects",
+            self.primary.list_projects(),
+            self.shadow.list_projects(),
+        )
+        .await
+    }
+
+    async fn delete_project(&self, name: &str) -> Result<(), BackendError> {
+        self.write("delete_project", self.primary.delete_project(name), || {
+            self.shadow.delete_project(name)
+        })
+        .await
+    }
+
+    async fn preview_delete_project(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        self.read(
+            "preview_delete_project",
+            self.primary.preview_delete_project(name),
+            self.shadow.preview_delete_project(name),
+        )
+        .await
+    }
+
+    async fn add_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        self.write(
+            "add_project_worker",
+            self.primary.add_project_worker(project, worker),
+            || self.shadow.add_project_worker(project, worker),
+        )
+        .await
+    }
+
+    async fn remove_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        self.write(
+            "remove_project_worker",
+            self.primary.remove_project_worker(project, worker),
+            || self.shadow.remove_project_worker(project, worker),
+        )
+        .await
+    }
+
+    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
+        let (primary, shadow) = futures::join!(
+            self.primary.list_environments(),
+            self.shadow.list_environments()
+        );
+
+        if let (Ok(p), Ok(s)) = (&primary, &shadow) {
+            for environment in p {
+                if let Some(other) = s.iter().find(|e| e.name == environment.name) {
+                    self.learn_environment(environment, other);
+                }
+            }
+        }
+
+        self.compare("list_environments", primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+        let (primary, shadow) = futures::join!(
+            self.primary.get_environment(name),
+            self.shadow.get_environment(name)
+        );
+
+        if let (Ok(p), Ok(s)) = (&primary, &shadow) {
+            self.learn_environment(p, s);
+        }
+
+        self.compare("get_environment", primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    async fn create_environment(
+        &self,
+        input: CreateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let primary = self.primary.create_environment(input.clone()).await;
+
+        if let Ok(p) = &primary {
+            let shadow = self.shadow.create_environment(input).await;
+
+            if let Ok(s) = &shadow {
+                self.learn_environment(p, s);
+            }
+
+            self.compare("create_environment", primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+
+    async fn update_environment(
+        &self,
+        name: &str,
+        input: UpdateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        // Value IDs and bound resource IDs are the primary's: translate them,
+        // or leave the shadow alone
+        let unseen = input
+            .values
+            .iter()
+            .flatten()
+            .filter_map(bound_resource)
+            .any(|id| self.shadow_id(id).is_none());
+
+        if unseen {
+            self.learn_resources().await;
+        }
+
+        let mut shadow_input = input.clone();
+        let mut unknown = None;
+
+        for value in shadow_input.values.iter_mut().flatten() {
+            if let Some(id) = value.id.take() {
+                match self.shadow_id(&id) {
+                    Some(shadow_id) => value.id = Some(shadow_id),
+                    None => unknown = Some(value.key.clone()),
+                }
+            }
+
+            if let Some(resource) = bound_resource(value).map(str::to_string) {
+                match self.shadow_id(&resource) {
+                    Some(shadow_id) => value.value = Some(shadow_id),
+                    None => unknown = Some(value.key.clone()),
+                }
+            }
+        }
+
+        let primary = self.primary.update_environment(name, input).await;
+
+        let Ok(p) = &primary else {
+            return primary;
+        };
+
+        if let Some(key) = unknown {
+            self.diverged(
+                "update_environment",
+                format!("not mirrored: '{}' unknown to the shadow", key),
+            );
+            return primary;
+        }
+
+        let shadow = self.shadow.update_environment(name, shadow_input).await;
+
+        if let Ok(s) = &shadow {
+            self.learn_environment(p, s);
+        }
+
+        self.compare("update_environment", primary.as_ref(), shadow.as_ref());
+        primary
+    }
+
+    async fn rotate_environment_secret(
+        &self,
+        env: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        self.write(
+            "rotate_environment_secret",
+            self.primary.rotate_environment_secret(env, key, value),
+            || self.shadow.rotate_environment_secret(env, key, value),
+        )
+        .await
+    }
+
+    async fn environment_value_history(
+        &self,
+        env: &str,
+        key: &str,
+    ) -> Result<Vec<EnvironmentValueChange>, BackendError> {
+        self.read(
+            "environment_value_history",
+            self.primary.environment_value_history(env, key),
+            self.shadow.environment_value_history(env, key),
+        )
+        .await
+    }
+
+    async fn environment_values_at(
+        &self,
+        env: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<EnvironmentValue>, BackendError> {
+        self.read(
+            "environment_values_at",
+            self.primary.environment_values_at(env, at),
+            self.shadow.environment_values_at(env, at),
+        )
+        .await
+    }
+
+    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_environment",
+            self.primary.delete_environment(name),
+            || self.shadow.delete_environment(name),
+        )
+        .await
+    }
+
+    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+        self.read(
+            "list_storage",
+            self.primary.list_storage(),
+            self.shadow.list_storage(),
+        )
+        .await
+    }
+
+    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+        self.read(
+            "get_storage",
+            self.primary.get_storage(name),
+            self.shadow.get_storage(name),
+        )
+        .await
+    }
+
+    async fn create_storage(
+        &self,
+        input: CreateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        let primary = self.primary.create_storage(input.clone()).await;
+
+        if let Ok(p) = &primary {
+            let shadow = self.shadow.create_storage(input).await;
+
+            if let Ok(s) = &shadow {
+                self.learn(&p.id, &s.id);
+            }
+
+            self.compare("create_storage", primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+
+    async fn update_storage(
+        &self,
+        name: &str,
+        input: UpdateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        self.write(
+            "update_storage",
+            self.primary.update_storage(name, input.clone()),
+            || self.shadow.update_storage(name, input),
+        )
+        .await
+    }
+
+    async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
+        self.write("delete_storage", self.primary.delete_storage(name), || {
+            self.shadow.delete_storage(name)
+        })
+        .await
+    }
+
+    async fn get_storage_credentials(
+        &self,
+        name: &str,
+    ) -> Result<DirectUploadConfig, BackendError> {
+        self.primary.get_storage_credentials(name).await
+    }
+
+    async fn get_platform_storage(&self) -> Result<Option<PlatformStorage>, BackendError> {
+        self.primary.get_platform_storage().await
+    }
+
+    async fn set_platform_storage(
+        &self,
+        storage: PlatformStorageConfig,
+    ) -> Result<(), BackendError> {
+        self.primary.set_platform_storage(storage).await
+    }
+
+    async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
+        self.read("list_kv", self.primary.list_kv(), self.shadow.list_kv())
+            .await
+    }
+
+    async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
+        self.read(
+            "get_kv",
+            self.primary.get_kv(name),
+            self.shadow.get_kv(name),
+        )
+        .await
+    }
+
+    async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
+        let primary = self.primary.create_kv(input.clone()).await;
+
+        if let Ok(p) = &primary {
+            let shadow = self.shadow.create_kv(input).await;
+
+            if let Ok(s) = &shadow {
+                self.learn(&p.id, &s.id);
+            }
+
+            self.compare("create_kv", primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+
+    async fn update_kv(
+        &self,
+        name: &str,
+        input: UpdateKvInput,
+    ) -> Result<KvNamespace, BackendError> {
+        self.write(
+            "update_kv",
+            self.primary.update_kv(name, input.clone()),
+            || self.shadow.update_kv(name, input),
+        )
+        .await
+    }
+
+    async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
+        self.write("delete_kv", self.primary.delete_kv(name), || {
+            self.shadow.delete_kv(name)
+        })
+        .await
+    }
+
+    async fn list_kv_entries(
+        &self,
+        name: &str,
+        prefix: &str,
+    ) -> Result<Vec<KvEntry>, BackendError> {
+        self.read(
+            "list_kv_entries",
+            self.primary.list_kv_entries(name, prefix),
+            self.shadow.list_kv_entries(name, prefix),
+        )
+        .await
+    }
+
+    async fn put_kv_entries(
+        &self,
+        name: &str,
+        entries: Vec<KvEntry>,
+    ) -> Result<usize, BackendError> {
+        self.write(
+            "put_kv_entries",
+            self.primary.put_kv_entries(name, entries.clone()),
+            || self.shadow.put_kv_entries(name, entries),
+        )
+        .await
+    }
+
+    async fn get_kv_entry(&self, name: &str, key: &str) -> Result<KvEntry, BackendError> {
+        self.read(
+            "get_kv_entry",
+            self.primary.get_kv_entry(name, key),
+            self.shadow.get_kv_entry(name, key),
+        )
+        .await
+    }
+
+    async fn put_kv_entry(
+        &self,
+        name: &str,
+        entry: KvEntry,
+        ttl: Option<i64>,
+    ) -> Result<(), BackendError> {
+        self.write(
+            "put_kv_entry",
+            self.primary.put_kv_entry(name, entry.clone(), ttl),
+            || self.shadow.put_kv_entry(name, entry, ttl),
+        )
+        .await
+    }
+
+    async fn delete_kv_entry(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_kv_entry",
+            self.primary.delete_kv_entry(name, key),
+            || self.shadow.delete_kv_entry(name, key),
+        )
+        .await
+    }
+
+    // Expired keys are purged by the server on the shadow
+
+    async fn count_expired_kv_entries(&self, name: &str) -> Result<u64, BackendError> {
+        self.primary.count_expired_kv_entries(name).await
+    }
+
+    async fn delete_expired_kv_entries(&self, name: &str, limit: u32) -> Result<u64, BackendError> {
+        self.primary.delete_expired_kv_entries(name, limit).await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
+        self.read(
+            "list_databases",
+            self.primary.list_databases(),
+            self.shadow.list_databases(),
+        )
+        .await
+    }
+
+    async fn get_database(&self, name: &str) -> Result<Database, BackendError> {
+        self.read(
+            "get_database",
+            self.primary.get_database(name),
+            self.shadow.get_database(name),
+        )
+        .await
+    }
+
+    async fn create_database(&self, input: CreateDatabaseInput) -> Result<Database, BackendError> {
+        let primary = self.primary.create_database(input.clone()).await;
+
+        if let Ok(p) = &primary {
+            let shadow = self.shadow.create_database(input).await;
+
+            if let Ok(s) = &shadow {
+                self.learn(&p.id, &s.id);
+            }
+
+            self.compare("create_database", primary.as_ref(), shadow.as_ref());
+        }
+
+        primary
+    }
+
+    async fn update_database(
+        &self,
+        name: &str,
+        input: UpdateDatabaseInput,
+    ) -> Result<Database, BackendError> {
+        self.write(
+            "update_database",
+            self.primary.update_database(name, input.clone()),
+            || self.shadow.update_database(name, input),
+        )
+        .await
+    }
+
+    async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_database",
+            self.primary.delete_database(name),
+            || self.shadow.delete_database(name),
+        )
+        .await
+    }
+
+    // Tokens authenticate against one backend: they are not mirrored
+
+    async fn create_token(&self, input: CreateTokenInput) -> Result<IssuedToken, BackendError> {
+        self.primary.create_token(input).await
+    }
+
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>, BackendError> {
+        self.primary.list_tokens().await
+    }
+
+    async fn revoke_token(&self, name: &str) -> Result<(), BackendError> {
+        self.primary.revoke_token(name).await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>, BackendError> {
+        self.read(
+            "list_organizations",
+            self.primary.list_organizations(),
+            self.shadow.list_organizations(),
+        )
+        .await
+    }
+
+    async fn create_organization(&self, name: &str) -> Result<Organization, BackendError> {
+        self.write(
+            "create_organization",
+            self.primary.create_organization(name),
+            || self.shadow.create_organization(name),
+        )
+        .await
+    }
+
+    async fn add_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        self.write(
+            "add_organization_member",
+            self.primary.add_organization_member(org, username, role),
+            || self.shadow.add_organization_member(org, username, role),
+        )
+        .await
+    }
+
+    async fn remove_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<(), BackendError> {
+        self.write(
+            "remove_organization_member",
+            self.primary.remove_organization_member(org, username),
+            || self.shadow.remove_organization_member(org, username),
+        )
+        .await
+    }
+
+    async fn list_account_secrets(&self) -> Result<Vec<AccountSecret>, BackendError> {
+        self.read(
+            "list_account_secrets",
+            self.primary.list_account_secrets(),
+            self.shadow.list_account_secrets(),
+        )
+        .await
+    }
+
+    async fn set_account_secret(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<AccountSecret, BackendError> {
+        self.write(
+            "set_account_secret",
+            self.primary.set_account_secret(key, value),
+            || self.shadow.set_account_secret(key, value),
+        )
+        .await
+    }
+
+    async fn delete_account_secret(&self, key: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_account_secret",
+            self.primary.delete_account_secret(key),
+            || self.shadow.delete_account_secret(key),
+        )
+        .await
+    }
+
+    async fn resource_usage(
+        &self,
+        kind: ResourceKind,
+        name: &str,
+    ) -> Result<ResourceUsage, BackendError> {
+        self.read(
+            "resource_usage",
+            self.primary.resource_usage(kind, name),
+            self.shadow.resource_usage(kind, name),
+        )
+        .await
+    }
+
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, BackendError> {
+        self.read(
+            "search",
+            self.primary.search(query, limit),
+            self.shadow.search(query, limit),
+        )
+        .await
+    }
+
+    async fn list_notification_webhooks(&self) -> Result<Vec<NotificationWebhook>, BackendError> {
+        self.read(
+            "list_notification_webhooks",
+            self.primary.list_notification_webhooks(),
+            self.shadow.list_notification_webhooks(),
+        )
+        .await
+    }
+
+    async fn set_notification_webhook(
+        &self,
+        url: &str,
+        events: &[NotificationEvent],
+    ) -> Result<NotificationWebhook, BackendError> {
+        self.write(
+            "set_notification_webhook",
+            self.primary.set_notification_webhook(url, events),
+            || self.shadow.set_notification_webhook(url, events),
+        )
+        .await
+    }
+
+    async fn delete_notification_webhook(&self, url: &str) -> Result<(), BackendError> {
+        self.write(
+            "delete_notification_webhook",
+            self.primary.delete_notification_webhook(url),
+            || self.shadow.delete_notification_webhook(url),
+        )
+        .await
+    }
+
+    async fn record_operation(
+        &self,
+        context: &RequestContext,
+        error: Option<&str>,
+    ) -> Result<(), BackendError> {
+        self.primary.record_operation(context, error).await
+    }
+
+    async fn get_trace(&self, request_id: &str) -> Result<TraceRecord, BackendError> {
+        self.primary.get_trace(request_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::EnvironmentValueInput;
+    use crate::backend::mock::MockBackend;
+
+    fn environment_input(name: &str) -> CreateEnvironmentInput {
+        CreateEnvironmentInput {
+            name: name.to_string(),
+            desc: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_reach_both() {
+        let primary = MockBackend::new().with_worker("api", Some("Public API"));
+        let shadow = MockBackend::new().with_worker("api", Some("Public API"));
+        let mirror = MirrorBackend::new(primary, shadow);
+
+        let env = mirror
+            .create_environment(environment_input("prod"))
+            .await
+            .unwrap();
+        let worker = mirror.get_worker("api").await.unwrap();
+
+        // Primary IDs are translated for the shadow
+        mirror
+            .link_worker_environment(&worker.id, &env.id)
+            .await
+            .unwrap();
+
+        let input = UpdateEnvironmentInput {
+            values: Some(vec![EnvironmentValueInput {
+                id: None,
+                key: "API_URL".to_string(),
+                value: Some("https://example.com".to_string()),
+                value_type: "var".to_string(),
+            }]),
+            ..Default::default()
+        };
+        mirror.update_environment("prod", input).await.unwrap();
+
+        let linked = mirror.shadow.get_worker("api").await.unwrap();
+        assert_eq!(linked.environment.unwrap().name, "prod");
+
+        let values = mirror.shadow.get_environment("prod").await.unwrap().values;
+        assert_eq!(values[0].value, "https://example.com");
+
+        mirror.list_workers().await.unwrap();
+        mirror.get_environment("prod").await.unwrap();
+
+        assert_eq!(mirror.divergences(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_bindings_use_shadow_ids() {
+        let primary = MockBackend::new();
+        let shadow = MockBackend::new();
+        let mirror = MirrorBackend::new(primary, shadow);
+
+        let kv_input = |name: &str| CreateKvInput {
+            name: name.to_string(),
+            desc: None,
+        };
+
+        mirror
+            .create_environment(environment_input("prod"))
+            .await
+            .unwrap();
+        let kv = mirror.create_kv(kv_input("cache")).await.unwrap();
+
+        // Created before the mirror: learned by name
+        mirror
+            .primary
+            .create_kv(kv_input("sessions"))
+            .await
+            .unwrap();
+        mirror.shadow.create_kv(kv_input("sessions")).await.unwrap();
+        let sessions = mirror.primary.get_kv("sessions").await.unwrap();
+
+        let binding = |key: &str, id: &str| EnvironmentValueInput {
+            id: None,
+            key: key.to_string(),
+            value: Some(id.to_string()),
+            value_type: "kv".to_string(),
+        };
+
+        let input = UpdateEnvironmentInput {
+            values: Some(vec![
+                binding("CACHE", &kv.id),
+                binding("SESSIONS", &sessions.id),
+            ]),
+            ..Default::default()
+        };
+        mirror.update_environment("prod", input).await.unwrap();
+
+        let values = mirror.shadow.get_environment("prod").await.unwrap().values;
+        let bound = |key: &str| &values.iter().find(|v| v.key == key).unwrap().value;
+
+        let shadow_cache = mirror.shadow.get_kv("cache").await.unwrap();
+        let shadow_sessions = mirror.shadow.get_kv("sessions").await.unwrap();
+        assert_ne!(shadow_cache.id, kv.id);
+        assert_eq!(bound("CACHE"), &shadow_cache.id);
+        assert_eq!(bound("SESSIONS"), &shadow_sessions.id);
+
+        // Bindings to the same resource are not divergences
+        mirror.get_environment("prod").await.unwrap();
+        assert_eq!(mirror.divergences(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_binding_unknown_to_shadow() {
+        let mirror = MirrorBackend::new(MockBackend::new(), MockBackend::new());
+
+        mirror
+            .create_environment(environment_input("prod"))
+            .await
+            .unwrap();
+        let kv = mirror
+            .primary
+            .create_kv(CreateKvInput {
+                name: "cache".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let input = UpdateEnvironmentInput {
+            values: Some(vec![EnvironmentValueInput {
+                id: None,
+                key: "CACHE".to_string(),
+                value: Some(kv.id),
+                value_type: "kv".to_string(),
+            }]),
+            ..Default::default()
+        };
+        mirror.update_environment("prod", input).await.unwrap();
+
+        let values = mirror.shadow.get_environment("prod").await.unwrap().values;
+        assert!(values.is_empty());
+        assert_eq!(mirror.divergences().len(), 1);
+        assert_eq!(mirror.divergences()[0].operation, "update_environment");
+    }
+
+    #[tokio::test]
+    async fn test_divergence() {
+        let primary = MockBackend::new()
+            .with_worker("api", Some("Public API"))
+            .with_worker("web", None);
+        let shadow = MockBackend::new().with_worker("api", Some("Old description"));
+        let mirror = MirrorBackend::new(primary, shadow);
+
+        mirror.get_worker("api").await.unwrap();
+        mirror.get_worker("web").await.unwrap();
+        mirror.list_workers().await.unwrap();
+
+        let divergences = mirror.divergences();
+        let details: Vec<&str> = divergences.iter().map(|d| d.detail.as_str()).collect();
+
+        assert_eq!(
+            details,
+            vec![
+                "description: \"Public API\" on primary, \"Old description\" on shadow",
+                "shadow failed: Not found: Worker 'web' not found",
+                "result: 2 items on primary, 1 on shadow",
+            ]
+        );
+
+        // A write the primary rejects never reaches the shadow
+        let result = mirror.delete_worker("missing").await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+        assert_eq!(mirror.divergences().len(), 3);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let primary = serde_json::json!({
+            "id": "1",
+            "name": "prod",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "values": [
+                { "id": "a", "key": "B", "value": "2", "type": "var" },
+                { "id": "b", "key": "A", "value": "secret-1", "type": "secret" }
+            ]
+        });
+        let shadow = serde_json::json!({
+            "id": "9",
+            "name": "prod",
+            "createdAt": "2026-05-01T00:00:00Z",
+            "values": [
+                { "id": "y", "key": "A", "value": "encrypted", "type": "secret" },
+                { "id": "z", "key": "B", "value": "2", "type": "var" }
+            ]
+        });
+
+        assert_eq!(normalize(&primary), normalize(&shadow));
+        assert_eq!(
+            first_difference(&serde_json::json!("a"), &serde_json::json!(1), ""),
+            Some("result: \"a\" on primary, 1 on shadow".to_string())
+        );
+    }
+}
@@ -1,19 +1,95 @@
 use super::{
-    AssetManifestEntry, Backend, BackendError, CreateDatabaseInput, CreateEnvironmentInput,
-    CreateKvInput, CreateStorageInput, CreateWorkerInput, Database, DeployInput, Deployment,
-    Environment, KvNamespace, Project, StorageConfig, UpdateEnvironmentInput, UpdateWorkerInput,
-    UploadResult, UploadWorkerInfo, Worker,
+    ACCOUNT_SECRET_PREFIX, AccountSecret, ApiToken, AssetManifestEntry, Backend, BackendError,
+    CapturedRequest, ColdStart, CreateDatabaseInput, CreateEnvironmentInput, CreateKvInput,
+    CreateStorageInput, CreateTokenInput, CreateWorkerInput, Database, DeletePreview,
+    DeployApproval, DeployAuthorization, DeployInput, Deployment, DirectUploadConfig, Environment,
+    EnvironmentValue, EnvironmentValueChange, ExecLog, ExecResult, IssuedToken, KvEntry,
+    KvNamespace, NotificationEvent, NotificationWebhook, Organization, PlatformStorage, Project,
+    RequestContext, ResourceBinding, ResourceKind, ResourceUsage, SearchResult, StorageConfig,
+    TraceRecord, UpdateDatabaseInput, UpdateEnvironmentInput, UpdateKvInput, UpdateStorageInput,
+    UpdateWorkerInput, UploadResult, UploadWorkerInfo, Worker, WorkerEnvironmentRef, WorkerEvent,
+    WorkerRuntime, WorkerShare, WorkerUrl, prunable_versions,
 };
-use chrono::Utc;
+use crate::config::PlatformStorageConfig;
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 
 #[derive(Default)]
 struct MockState {
     workers: HashMap<String, Worker>,
     deployments: HashMap<String, Vec<Deployment>>,
+    /// (worker name, version) -> deployed code
+    deployment_code: HashMap<(String, i32), Vec<u8>>,
     environments: HashMap<String, Environment>,
+    /// token -> (worker name, expires_at, used)
+    approvals: HashMap<String, (String, DateTime<Utc>, bool)>,
+    tokens: Vec<ApiToken>,
+    retention: Option<u32>,
+    urls: HashMap<String, Vec<WorkerUrl>>,
+    secrets: HashMap<String, AccountSecret>,
+    worker_values: HashMap<String, Vec<EnvironmentValue>>,
+    kv: HashMap<String, KvNamespace>,
+    storage: HashMap<String, StorageConfig>,
+    platform_storage: Option<PlatformStorageConfig>,
+    kv_entries: HashMap<String, Vec<KvEntry>>,
+    /// namespace name -> expired entries still stored
+    kv_expired: HashMap<String, u64>,
+    /// (environment id, key) -> past values
+    env_history: HashMap<(String, String), Vec<EnvironmentValueChange>>,
+    /// organization name -> (organization, username -> role)
+    organizations: HashMap<String, (Organization, HashMap<String, String>)>,
+    /// worker name -> shares
+    shares: HashMap<String, Vec<WorkerShare>>,
+    /// (worker name, capture id) -> captured request
+    captures: HashMap<(String, String), CapturedRequest>,
+    webhooks: Vec<NotificationWebhook>,
+    projects: HashMap<String, Project>,
+    /// worker name -> project name
+    project_workers: HashMap<String, String>,
+    /// Signed in as a plain member of the account, who can't approve deploys
+    member: bool,
+}
+
+impl MockState {
+    /// Check a deploy to a protected worker, returning the approval token to
+    /// mark used once the deploy went through
+    fn authorize_deploy(
+        &self,
+        name: &str,
+        authorization: &DeployAuthorization,
+    ) -> Result<Option<String>, BackendError> {
+        let worker = &self.workers[name];
+        let protected =
+            worker.protected || worker.environment.as_ref().is_some_and(|e| e.protected);
+
+        if !protected {
+            return Ok(None);
+        }
+
+        let Some(token) = &authorization.approve_token else {
+            if authorization.confirmed {
+                return Ok(None);
+            }
+
+            return Err(BackendError::Api(format!(
+                "Worker '{}' is protected. Pass --approve-token (see 'ow workers approve').",
+                name
+            )));
+        };
+
+        match self.approvals.get(token) {
+            Some((worker, expires_at, used))
+                if worker == name && !*used && *expires_at > Utc::now() =>
+            {
+                Ok(Some(token.clone()))
+            }
+            _ => Err(BackendError::Api(
+                "Approval token is invalid, expired or already used".to_string(),
+            )),
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -33,6 +109,10 @@ impl MockBackend {
             description: description.map(|s| s.to_string()),
             current_version: None,
             environment: None,
+            protected: false,
+            url: None,
+            metadata: BTreeMap::new(),
+            tags: BTreeMap::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -51,6 +131,10 @@ impl MockBackend {
             description: None,
             current_version: Some(version),
             environment: None,
+            protected: false,
+            url: None,
+            metadata: BTreeMap::new(),
+            tags: BTreeMap::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -61,6 +145,88 @@ impl MockBackend {
 
         self
     }
+
+    pub fn with_protected_worker(self, name: &str) -> Self {
+        let this = self.with_worker(name, None);
+
+        let mut state = this.state.lock().unwrap();
+        state.workers.get_mut(name).unwrap().protected = true;
+        drop(state);
+
+        this
+    }
+
+    pub fn with_member_login(self) -> Self {
+        self.state.lock().unwrap().member = true;
+        self
+    }
+
+    /// A project with its main worker, which shares the project's ID
+    pub fn with_project(self, name: &str, environment: Option<&str>) -> Self {
+        let this = self.with_worker(name, None);
+
+        let mut state = this.state.lock().unwrap();
+        let environment = environment.map(|env| state.environments[env].clone());
+        let worker = state.workers.get_mut(name).unwrap();
+
+        worker.environment = environment.as_ref().map(|e| WorkerEnvironmentRef {
+            id: e.id.clone(),
+            name: e.name.clone(),
+            protected: e.protected,
+        });
+
+        let project = Project {
+            id: worker.id.clone(),
+            name: name.to_string(),
+            description: None,
+            environment: environment.map(|e| e.name),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        state.projects.insert(name.to_string(), project);
+        state
+            .project_workers
+            .insert(name.to_string(), name.to_string());
+        drop(state);
+
+        this
+    }
+
+    pub fn with_captured_request(self, name: &str, request: CapturedRequest) -> Self {
+        let id = request.id.clone().unwrap_or_default();
+
+        let mut state = self.state.lock().unwrap();
+        state.captures.insert((name.to_string(), id), request);
+        drop(state);
+
+        self
+    }
+
+    /// Versions still stored for a worker, oldest first
+    pub fn deployment_versions(&self, name: &str) -> Vec<i32> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .deployments
+            .get(name)
+            .map(|deployments| deployments.iter().map(|d| d.version).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn with_expired_kv_entries(self, name: &str, count: u64) -> Self {
+        let mut state = self.state.lock().unwrap();
+        state.kv_expired.insert(name.to_string(), count);
+        drop(state);
+
+        self
+    }
+
+    /// Entries written to a KV namespace, in write order
+    pub fn kv_entries(&self, name: &str) -> Vec<KvEntry> {
+        let state = self.state.lock().unwrap();
+        state.kv_entries.get(name).cloned().unwrap_or_default()
+    }
 }
 
 impl Backend for MockBackend {
@@ -97,6 +263,10 @@ impl Backend for MockBackend {
             description: input.description,
             current_version: None,
             environment: None,
+            protected: false,
+            url: None,
+            metadata: BTreeMap::new(),
+            tags: input.tags,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -119,71 +289,232 @@ impl Backend for MockBackend {
         Ok(())
     }
 
+    async fn preview_delete_worker(&self, name: &str) -> Result<DeletePreview, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        Ok(DeletePreview {
+            deployments: state.deployments.get(name).map_or(0, |d| d.len() as i64),
+            ..Default::default()
+        })
+    }
+
     async fn update_worker(
         &self,
         name: &str,
-        _input: UpdateWorkerInput,
+        input: UpdateWorkerInput,
     ) -> Result<Worker, BackendError> {
         let mut state = self.state.lock().unwrap();
 
+        let environment = match input.environment.as_deref() {
+            None => None,
+            Some("") => Some(None),
+            Some(env) => {
+                let environment = state
+                    .environments
+                    .values()
+                    .find(|e| e.name == env || e.id == env)
+                    .ok_or_else(|| {
+                        BackendError::NotFound(format!("Environment '{}' not found", env))
+                    })?;
+
+                Some(Some(WorkerEnvironmentRef {
+                    id: environment.id.clone(),
+                    name: environment.name.clone(),
+                    protected: environment.protected,
+                }))
+            }
+        };
+
         let worker = state
             .workers
             .get_mut(name)
             .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
 
+        if let Some(environment) = environment {
+            worker.environment = environment;
+        }
+
+        if let Some(protected) = input.protected {
+            worker.protected = protected;
+        }
+
+        if let Some(desc) = input.desc {
+            worker.description = Some(desc).filter(|d| !d.is_empty());
+        }
+
+        if let Some(tags) = input.tags {
+            worker.tags = tags;
+        }
+
         worker.updated_at = Utc::now();
         Ok(worker.clone())
     }
 
     async fn link_worker_environment(
         &self,
-        _worker_id: &str,
-        _environment_id: &str,
+        worker_id: &str,
+        environment_id: &str,
     ) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let environment = state
+            .environments
+            .values()
+            .find(|e| e.id == environment_id)
+            .map(|e| WorkerEnvironmentRef {
+                id: e.id.clone(),
+                name: e.name.clone(),
+                protected: e.protected,
+            })
+            .ok_or_else(|| {
+                BackendError::NotFound(format!("Environment '{}' not found", environment_id))
+            })?;
+
+        let worker = state
+            .workers
+            .values_mut()
+            .find(|w| w.id == worker_id)
+            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", worker_id)))?;
+
+        worker.environment = Some(environment);
         Ok(())
     }
 
+    async fn set_worker_metadata(
+        &self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Worker, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let worker = state
+            .workers
+            .get_mut(name)
+            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+
+        worker.metadata = metadata.clone();
+        worker.updated_at = Utc::now();
+
+        Ok(worker.clone())
+    }
+
+    async fn get_current_deployment(&self, name: &str) -> Result<Option<Deployment>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let worker = state
+            .workers
+            .get(name)
+            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+
+        let deployment = state.deployments.get(name).and_then(|deployments| {
+            deployments
+                .iter()
+                .find(|d| Some(d.version) == worker.current_version)
+                .cloned()
+        });
+
+        Ok(deployment)
+    }
+
+    async fn list_current_deployments(
+        &self,
+        workers: &[Worker],
+    ) -> Result<Vec<Option<Deployment>>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        Ok(workers
+            .iter()
+            .map(|worker| {
+                state.deployments.get(&worker.name).and_then(|deployments| {
+                    deployments
+                        .iter()
+                        .find(|d| Some(d.version) == worker.current_version)
+                        .cloned()
+                })
+            })
+            .collect())
+    }
+
     async fn deploy_worker(
         &self,
         name: &str,
         input: DeployInput,
     ) -> Result<Deployment, BackendError> {
-        let mut state = self.state.lock().unwrap();
+        // The guard must not live across the prune below: the future has to stay Send
+        let (deployment, retention) = {
+            let mut state = self.state.lock().unwrap();
 
-        if !state.workers.contains_key(name) {
-            return Err(BackendError::NotFound(format!(
-                "Worker '{}' not found",
-                name
-            )));
-        }
+            if !state.workers.contains_key(name) {
+                return Err(BackendError::NotFound(format!(
+                    "Worker '{}' not found",
+                    name
+                )));
+            }
 
-        let deployments = state.deployments.entry(name.to_string()).or_default();
-        let next_version = deployments.len() as i32 + 1;
+            let approval = state.authorize_deploy(name, &input.authorization)?;
 
-        let mut hasher = Sha256::new();
-        hasher.update(&input.code);
-        let hash = hex::encode(hasher.finalize());
+            let deployments = state.deployments.entry(name.to_string()).or_default();
+            let next_version = deployments.last().map_or(0, |d| d.version) + 1;
 
-        let worker = state.workers.get_mut(name).unwrap();
-        let worker_id = worker.id.clone();
+            let mut hasher = Sha256::new();
+            hasher.update(&input.code);
+            let hash = hex::encode(hasher.finalize());
 
-        worker.current_version = Some(next_version);
-        worker.updated_at = Utc::now();
+            if let Some(expected) = input
+                .hash
+                .as_ref()
+                .filter(|h| !h.eq_ignore_ascii_case(&hash))
+            {
+                return Err(BackendError::Api(format!(
+                    "Checksum mismatch: expected {}, received payload hashes to {}",
+                    expected, hash
+                )));
+            }
 
-        let deployment = Deployment {
-            worker_id,
-            version: next_version,
-            hash,
-            code_type: input.code_type,
-            deployed_at: Utc::now(),
-            message: input.message,
+            if let Some(token) = approval {
+                state.approvals.get_mut(&token).unwrap().2 = true;
+            }
+
+            let worker = state.workers.get_mut(name).unwrap();
+            let worker_id = worker.id.clone();
+
+            worker.current_version = Some(next_version);
+            worker.updated_at = Utc::now();
+
+            let deployment = Deployment {
+                worker_id,
+                version: next_version,
+                hash,
+                code_type: input.code_type,
+                deployed_at: Utc::now(),
+                deployed_by: None,
+                message: input.message,
+                tag: None,
+            };
+
+            state
+                .deployment_code
+                .insert((name.to_string(), next_version), input.code);
+
+            state
+                .deployments
+                .get_mut(name)
+                .unwrap()
+                .push(deployment.clone());
+
+            (deployment, state.retention)
         };
 
-        state
-            .deployments
-            .get_mut(name)
-            .unwrap()
-            .push(deployment.clone());
+        if let Some(keep) = retention {
+            self.prune_deployments(name, keep).await?;
+        }
 
         Ok(deployment)
     }
@@ -194,13 +525,22 @@ impl Backend for MockBackend {
         _path: &std::path::Path,
         _zip_data: Vec<u8>,
         _assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
     ) -> Result<UploadResult, BackendError> {
-        let state = self.state.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
 
-        let worker = state
-            .workers
-            .get(name)
-            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?;
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if let Some(token) = state.authorize_deploy(name, authorization)? {
+            state.approvals.get_mut(&token).unwrap().2 = true;
+        }
+
+        let worker = &state.workers[name];
 
         Ok(UploadResult {
             success: true,
@@ -215,107 +555,749 @@ impl Backend for MockBackend {
         })
     }
 
-    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
-        Ok(vec![])
-    }
-
-    async fn delete_project(&self, _name: &str) -> Result<(), BackendError> {
-        Ok(())
-    }
-
-    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
-        let state = self.state.lock().unwrap();
-        let mut environments: Vec<Environment> = state.environments.values().cloned().collect();
-        environments.sort_by(|a, b| a.name.cmp(&b.name));
-        Ok(environments)
-    }
-
-    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
-        let state = self.state.lock().unwrap();
-        state
-            .environments
-            .get(name)
-            .cloned()
-            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))
-    }
-
-    async fn create_environment(
+    async fn create_deploy_approval(
         &self,
-        input: CreateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
+        name: &str,
+        ttl_seconds: i64,
+    ) -> Result<DeployApproval, BackendError> {
         let mut state = self.state.lock().unwrap();
 
-        if state.environments.contains_key(&input.name) {
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if state.member {
             return Err(BackendError::Api(format!(
-                "Environment '{}' already exists",
-                input.name
+                "Only the owner of worker '{}' can approve deploys to it",
+                name
             )));
         }
 
-        let environment = Environment {
-            id: uuid::Uuid::new_v4().to_string(),
-            name: input.name.clone(),
-            description: input.desc,
-            values: vec![],
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
+        let token = format!("owa_{}", uuid::Uuid::new_v4().simple());
+        let expires_at = Utc::now() + chrono::Duration::seconds(ttl_seconds);
 
-        state.environments.insert(input.name, environment.clone());
-        Ok(environment)
+        state
+            .approvals
+            .insert(token.clone(), (name.to_string(), expires_at, false));
+
+        Ok(DeployApproval { token, expires_at })
     }
 
-    async fn update_environment(
+    async fn exec_worker(
         &self,
         name: &str,
-        input: UpdateEnvironmentInput,
-    ) -> Result<Environment, BackendError> {
-        let mut state = self.state.lock().unwrap();
-
-        let environment = state
-            .environments
-            .get_mut(name)
-            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
+        event: WorkerEvent,
+    ) -> Result<ExecResult, BackendError> {
+        let state = self.state.lock().unwrap();
 
-        if let Some(new_name) = input.name {
-            environment.name = new_name;
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
         }
 
-        environment.updated_at = Utc::now();
-
-        Ok(environment.clone())
+        Ok(ExecResult {
+            success: true,
+            duration_ms: 1,
+            logs: vec![ExecLog {
+                level: "log".to_string(),
+                message: format!("{} event handled", event.kind()),
+            }],
+            error: None,
+        })
     }
 
-    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
-        let mut state = self.state.lock().unwrap();
+    async fn worker_runtime(&self, name: &str) -> Result<WorkerRuntime, BackendError> {
+        let state = self.state.lock().unwrap();
 
-        if state.environments.remove(name).is_none() {
+        if !state.workers.contains_key(name) {
             return Err(BackendError::NotFound(format!(
-                "Environment '{}' not found",
+                "Worker '{}' not found",
                 name
             )));
         }
 
-        Ok(())
-    }
-
-    // Storage methods (basic mock implementations)
-    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
-        Ok(vec![])
-    }
-
-    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
-        Err(BackendError::NotFound(format!(
-            "Storage '{}' not found",
-            name
-        )))
+        Ok(WorkerRuntime {
+            active_isolates: 1,
+            in_flight_requests: 0,
+            memory_bytes: 8 * 1024 * 1024,
+            cold_starts: vec![ColdStart {
+                at: Utc::now(),
+                duration_ms: 12,
+            }],
+        })
     }
 
-    async fn create_storage(
+    async fn get_captured_request(
         &self,
-        input: CreateStorageInput,
-    ) -> Result<StorageConfig, BackendError> {
-        Ok(StorageConfig {
+        name: &str,
+        id: &str,
+    ) -> Result<CapturedRequest, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        state
+            .captures
+            .get(&(name.to_string(), id.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "No captured request '{}' for worker '{}'",
+                    id, name
+                ))
+            })
+    }
+
+    async fn redeploy_worker(
+        &self,
+        name: &str,
+        message: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let current = state
+            .workers
+            .get(name)
+            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?
+            .current_version;
+
+        let deployments = state.deployments.entry(name.to_string()).or_default();
+
+        let active = deployments
+            .iter()
+            .find(|d| Some(d.version) == current)
+            .cloned()
+            .ok_or_else(|| {
+                BackendError::NotFound(format!("Worker '{}' has no active deployment", name))
+            })?;
+
+        let deployment = Deployment {
+            version: deployments.last().map_or(0, |d| d.version) + 1,
+            deployed_at: Utc::now(),
+            message: Some(
+                message
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("Redeploy of v{}", active.version)),
+            ),
+            tag: None,
+            ..active
+        };
+        deployments.push(deployment.clone());
+
+        if let Some(code) = state
+            .deployment_code
+            .get(&(name.to_string(), active.version))
+            .cloned()
+        {
+            state
+                .deployment_code
+                .insert((name.to_string(), deployment.version), code);
+        }
+
+        let worker = state.workers.get_mut(name).unwrap();
+        worker.current_version = Some(deployment.version);
+        worker.updated_at = Utc::now();
+
+        Ok(deployment)
+    }
+
+    async fn tag_deployment(
+        &self,
+        name: &str,
+        version: i32,
+        tag: Option<&str>,
+    ) -> Result<Deployment, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let deployment = state
+            .deployments
+            .get_mut(name)
+            .and_then(|deployments| deployments.iter_mut().find(|d| d.version == version))
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "Version {} of worker '{}' not found",
+                    version, name
+                ))
+            })?;
+
+        deployment.tag = tag.map(|t| t.to_string());
+
+        Ok(deployment.clone())
+    }
+
+    async fn list_deployments(&self, name: &str) -> Result<Vec<Deployment>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        Ok(state.deployments.get(name).cloned().unwrap_or_default())
+    }
+
+    async fn get_deployment_code(&self, name: &str, version: i32) -> Result<Vec<u8>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .deployment_code
+            .get(&(name.to_string(), version))
+            .cloned()
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "Version {} of worker '{}' not found",
+                    version, name
+                ))
+            })
+    }
+
+    async fn prune_deployments(&self, name: &str, keep: u32) -> Result<Vec<i32>, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let current = state
+            .workers
+            .get(name)
+            .ok_or_else(|| BackendError::NotFound(format!("Worker '{}' not found", name)))?
+            .current_version;
+
+        let deployments = state.deployments.entry(name.to_string()).or_default();
+
+        let versions: Vec<(i32, bool)> = deployments
+            .iter()
+            .map(|d| (d.version, d.tag.is_some()))
+            .collect();
+
+        let prunable = prunable_versions(&versions, current, keep);
+        deployments.retain(|d| !prunable.contains(&d.version));
+
+        Ok(prunable)
+    }
+
+    async fn get_retention_policy(&self) -> Result<Option<u32>, BackendError> {
+        Ok(self.state.lock().unwrap().retention)
+    }
+
+    async fn set_retention_policy(&self, keep: Option<u32>) -> Result<(), BackendError> {
+        self.state.lock().unwrap().retention = keep;
+        Ok(())
+    }
+
+    async fn list_worker_urls(&self, name: &str) -> Result<Vec<WorkerUrl>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        let mut urls = state.urls.get(name).cloned().unwrap_or_default();
+        urls.sort();
+        Ok(urls)
+    }
+
+    async fn add_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        if state.urls.values().flatten().any(|u| u == url) {
+            return Err(BackendError::Api(format!("'{}' is already in use", url)));
+        }
+
+        state
+            .urls
+            .entry(name.to_string())
+            .or_default()
+            .push(url.clone());
+        Ok(())
+    }
+
+    async fn remove_worker_url(&self, name: &str, url: &WorkerUrl) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let urls = state.urls.entry(name.to_string()).or_default();
+        let before = urls.len();
+        urls.retain(|u| u != url);
+
+        if urls.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "'{}' does not point at worker '{}'",
+                url, name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_values(&self, name: &str) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        let mut values = state.worker_values.get(name).cloned().unwrap_or_default();
+        values.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(values)
+    }
+
+    async fn set_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+        value_type: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        let new_value = EnvironmentValue {
+            id: uuid::Uuid::new_v4().to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            value_type: value_type.to_string(),
+            rotated_at: None,
+        };
+
+        let values = state.worker_values.entry(name.to_string()).or_default();
+        values.retain(|v| v.key != key);
+        values.push(new_value.clone());
+
+        Ok(new_value)
+    }
+
+    async fn delete_worker_value(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let values = state.worker_values.entry(name.to_string()).or_default();
+        let before = values.len();
+        values.retain(|v| v.key != key);
+
+        if values.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' has no value '{}'",
+                name, key
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_worker_shares(&self, name: &str) -> Result<Vec<WorkerShare>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        let mut shares = state.shares.get(name).cloned().unwrap_or_default();
+        shares.sort_by(|a, b| a.username.cmp(&b.username));
+        Ok(shares)
+    }
+
+    async fn share_worker(
+        &self,
+        name: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.workers.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                name
+            )));
+        }
+
+        let shares = state.shares.entry(name.to_string()).or_default();
+        shares.retain(|s| s.username != username);
+        shares.push(WorkerShare {
+            username: username.to_string(),
+            role: role.to_string(),
+            created_at: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    async fn unshare_worker(&self, name: &str, username: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let shares = state.shares.entry(name.to_string()).or_default();
+        let before = shares.len();
+        shares.retain(|s| s.username != username);
+
+        if shares.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' is not shared with '{}'",
+                name, username
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_projects(&self) -> Result<Vec<Project>, BackendError> {
+        let state = self.state.lock().unwrap();
+        let mut projects: Vec<Project> = state.projects.values().cloned().collect();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(projects)
+    }
+
+    async fn delete_project(&self, _name: &str) -> Result<(), BackendError> {
+        Ok(())
+    }
+
+    async fn preview_delete_project(&self, _name: &str) -> Result<DeletePreview, BackendError> {
+        Ok(DeletePreview::default())
+    }
+
+    async fn add_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let environment = state
+            .projects
+            .get(project)
+            .ok_or_else(|| BackendError::NotFound(format!("Project '{}' not found", project)))?
+            .environment
+            .clone();
+
+        if !state.workers.contains_key(worker) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found",
+                worker
+            )));
+        }
+
+        if let Some(current) = state.project_workers.get(worker) {
+            return Err(BackendError::Api(format!(
+                "Worker '{}' already belongs to project '{}'",
+                worker, current
+            )));
+        }
+
+        let environment = environment.map(|env| {
+            let e = &state.environments[&env];
+            WorkerEnvironmentRef {
+                id: e.id.clone(),
+                name: e.name.clone(),
+                protected: e.protected,
+            }
+        });
+
+        state.workers.get_mut(worker).unwrap().environment = environment;
+        state
+            .project_workers
+            .insert(worker.to_string(), project.to_string());
+
+        Ok(())
+    }
+
+    async fn remove_project_worker(&self, project: &str, worker: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.project_workers.get(worker).map(String::as_str) != Some(project) {
+            return Err(BackendError::NotFound(format!(
+                "Worker '{}' not found in project '{}'",
+                worker, project
+            )));
+        }
+
+        if state.projects[project].id == state.workers[worker].id {
+            return Err(BackendError::Api(format!(
+                "'{}' is the main worker of project '{}'. Delete the project instead.",
+                worker, project
+            )));
+        }
+
+        state.project_workers.remove(worker);
+        Ok(())
+    }
+
+    async fn list_environments(&self) -> Result<Vec<Environment>, BackendError> {
+        let state = self.state.lock().unwrap();
+        let mut environments: Vec<Environment> = state.environments.values().cloned().collect();
+        environments.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(environments)
+    }
+
+    async fn get_environment(&self, name: &str) -> Result<Environment, BackendError> {
+        let state = self.state.lock().unwrap();
+        state
+            .environments
+            .get(name)
+            .cloned()
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))
+    }
+
+    async fn create_environment(
+        &self,
+        input: CreateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.environments.contains_key(&input.name) {
+            return Err(BackendError::Api(format!(
+                "Environment '{}' already exists",
+                input.name
+            )));
+        }
+
+        let environment = Environment {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: input.name.clone(),
+            description: input.desc,
+            values: vec![],
+            protected: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        state.environments.insert(input.name, environment.clone());
+        Ok(environment)
+    }
+
+    async fn update_environment(
+        &self,
+        name: &str,
+        input: UpdateEnvironmentInput,
+    ) -> Result<Environment, BackendError> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+
+        let environment = state
+            .environments
+            .get_mut(name)
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", name)))?;
+
+        // Keys touched by this update, with their new value or None if removed
+        let mut changes: Vec<(String, Option<EnvironmentValue>)> = Vec::new();
+
+        if let Some(new_name) = input.name {
+            environment.name = new_name;
+        }
+
+        if let Some(protected) = input.protected {
+            environment.protected = protected;
+        }
+
+        for value in input.values.unwrap_or_default() {
+            let existing = environment
+                .values
+                .iter()
+                .position(|v| Some(&v.id) == value.id.as_ref());
+
+            match (existing, value.value) {
+                (Some(index), Some(new_value)) => {
+                    let current = &mut environment.values[index];
+
+                    if current.key != value.key {
+                        changes.push((current.key.clone(), None));
+                    }
+
+                    current.key = value.key;
+                    current.value = new_value;
+                    current.value_type = value.value_type;
+                    changes.push((current.key.clone(), Some(current.clone())));
+                }
+                (Some(index), None) => {
+                    let removed = environment.values.remove(index);
+                    changes.push((removed.key, None));
+                }
+                (None, Some(new_value)) => {
+                    let created = EnvironmentValue {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        key: value.key,
+                        value: new_value,
+                        value_type: value.value_type,
+                        rotated_at: None,
+                    };
+
+                    changes.push((created.key.clone(), Some(created.clone())));
+                    environment.values.push(created);
+                }
+                (None, None) => {}
+            }
+        }
+
+        for (key, value) in changes {
+            state
+                .env_history
+                .entry((environment.id.clone(), key))
+                .or_default()
+                .push(EnvironmentValueChange {
+                    value: value.as_ref().map(|v| v.value.clone()),
+                    value_type: value.map(|v| v.value_type),
+                    changed_at: Utc::now(),
+                });
+        }
+
+        environment.updated_at = Utc::now();
+
+        Ok(environment.clone())
+    }
+
+    async fn rotate_environment_secret(
+        &self,
+        env: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<EnvironmentValue, BackendError> {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+
+        let environment = state.environments.get_mut(env);
+        let env_id = environment.as_ref().map(|e| e.id.clone());
+
+        let secret = environment
+            .and_then(|e| {
+                e.values.iter_mut().find(|v| {
+                    v.key == key
+                        && v.value_type == "secret"
+                        && !v.value.starts_with(ACCOUNT_SECRET_PREFIX)
+                })
+            })
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "Secret '{}' not found in environment '{}'",
+                    key, env
+                ))
+            })?;
+
+        secret.value = value.to_string();
+        secret.rotated_at = Some(Utc::now());
+
+        state
+            .env_history
+            .entry((env_id.unwrap_or_default(), key.to_string()))
+            .or_default()
+            .push(EnvironmentValueChange {
+                value: Some(secret.value.clone()),
+                value_type: Some(secret.value_type.clone()),
+                changed_at: Utc::now(),
+            });
+
+        Ok(secret.clone())
+    }
+
+    async fn environment_value_history(
+        &self,
+        env: &str,
+        key: &str,
+    ) -> Result<Vec<EnvironmentValueChange>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let environment = state
+            .environments
+            .get(env)
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", env)))?;
+
+        Ok(state
+            .env_history
+            .get(&(environment.id.clone(), key.to_string()))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn environment_values_at(
+        &self,
+        env: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<EnvironmentValue>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let environment = state
+            .environments
+            .get(env)
+            .ok_or_else(|| BackendError::NotFound(format!("Environment '{}' not found", env)))?;
+
+        let mut values: Vec<EnvironmentValue> = state
+            .env_history
+            .iter()
+            .filter(|((env_id, _), _)| *env_id == environment.id)
+            .filter_map(|((_, key), changes)| {
+                let change = changes.iter().rev().find(|c| c.changed_at <= at)?;
+
+                Some(EnvironmentValue {
+                    id: String::new(),
+                    key: key.clone(),
+                    value: change.value.clone()?,
+                    value_type: change.value_type.clone()?,
+                    rotated_at: None,
+                })
+            })
+            .collect();
+
+        values.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok(values)
+    }
+
+    async fn delete_environment(&self, name: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.environments.remove(name).is_none() {
+            return Err(BackendError::NotFound(format!(
+                "Environment '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Storage methods
+    async fn list_storage(&self) -> Result<Vec<StorageConfig>, BackendError> {
+        let state = self.state.lock().unwrap();
+        let mut configs: Vec<StorageConfig> = state.storage.values().cloned().collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(configs)
+    }
+
+    async fn get_storage(&self, name: &str) -> Result<StorageConfig, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .storage
+            .get(name)
+            .cloned()
+            .ok_or_else(|| BackendError::NotFound(format!("Storage '{}' not found", name)))
+    }
+
+    async fn create_storage(
+        &self,
+        input: CreateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        let config = StorageConfig {
             id: uuid::Uuid::new_v4().to_string(),
             name: input.name,
             description: input.desc,
@@ -327,7 +1309,23 @@ impl Backend for MockBackend {
             public_url: input.public_url,
             created_at: Utc::now(),
             updated_at: Utc::now(),
-        })
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.storage.insert(config.name.clone(), config.clone());
+
+        Ok(config)
+    }
+
+    async fn update_storage(
+        &self,
+        name: &str,
+        _input: UpdateStorageInput,
+    ) -> Result<StorageConfig, BackendError> {
+        Err(BackendError::NotFound(format!(
+            "Storage '{}' not found",
+            name
+        )))
     }
 
     async fn delete_storage(&self, name: &str) -> Result<(), BackendError> {
@@ -337,26 +1335,72 @@ impl Backend for MockBackend {
         )))
     }
 
-    // KV methods (basic mock implementations)
+    async fn get_storage_credentials(
+        &self,
+        name: &str,
+    ) -> Result<DirectUploadConfig, BackendError> {
+        Err(BackendError::NotFound(format!(
+            "Storage '{}' not found",
+            name
+        )))
+    }
+
+    async fn get_platform_storage(&self) -> Result<Option<PlatformStorage>, BackendError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.platform_storage.as_ref().map(PlatformStorage::from))
+    }
+
+    async fn set_platform_storage(
+        &self,
+        storage: PlatformStorageConfig,
+    ) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        state.platform_storage = Some(storage);
+        Ok(())
+    }
+
+    // KV methods
     async fn list_kv(&self) -> Result<Vec<KvNamespace>, BackendError> {
-        Ok(vec![])
+        let state = self.state.lock().unwrap();
+        let mut namespaces: Vec<KvNamespace> = state.kv.values().cloned().collect();
+        namespaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(namespaces)
     }
 
     async fn get_kv(&self, name: &str) -> Result<KvNamespace, BackendError> {
-        Err(BackendError::NotFound(format!(
-            "KV namespace '{}' not found",
-            name
-        )))
+        let state = self.state.lock().unwrap();
+
+        state
+            .kv
+            .get(name)
+            .cloned()
+            .ok_or_else(|| BackendError::NotFound(format!("KV namespace '{}' not found", name)))
     }
 
     async fn create_kv(&self, input: CreateKvInput) -> Result<KvNamespace, BackendError> {
-        Ok(KvNamespace {
+        let namespace = KvNamespace {
             id: uuid::Uuid::new_v4().to_string(),
             name: input.name,
             description: input.desc,
             created_at: Utc::now(),
             updated_at: Utc::now(),
-        })
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.kv.insert(namespace.name.clone(), namespace.clone());
+
+        Ok(namespace)
+    }
+
+    async fn update_kv(
+        &self,
+        name: &str,
+        _input: UpdateKvInput,
+    ) -> Result<KvNamespace, BackendError> {
+        Err(BackendError::NotFound(format!(
+            "KV namespace '{}' not found",
+            name
+        )))
     }
 
     async fn delete_kv(&self, name: &str) -> Result<(), BackendError> {
@@ -366,6 +1410,117 @@ impl Backend for MockBackend {
         )))
     }
 
+    async fn list_kv_entries(
+        &self,
+        name: &str,
+        prefix: &str,
+    ) -> Result<Vec<KvEntry>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let mut entries: Vec<KvEntry> = state
+            .kv_entries
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(|e| e.key.starts_with(prefix))
+            .cloned()
+            .collect();
+
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+
+    async fn put_kv_entries(
+        &self,
+        name: &str,
+        entries: Vec<KvEntry>,
+    ) -> Result<usize, BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let stored = state.kv_entries.entry(name.to_string()).or_default();
+        let count = entries.len();
+
+        for entry in entries {
+            stored.retain(|e| e.key != entry.key);
+            stored.push(entry);
+        }
+
+        Ok(count)
+    }
+
+    async fn get_kv_entry(&self, name: &str, key: &str) -> Result<KvEntry, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .kv_entries
+            .get(name)
+            .into_iter()
+            .flatten()
+            .find(|e| e.key == key)
+            .cloned()
+            .ok_or_else(|| {
+                BackendError::NotFound(format!(
+                    "Key '{}' not found in KV namespace '{}'",
+                    key, name
+                ))
+            })
+    }
+
+    async fn put_kv_entry(
+        &self,
+        name: &str,
+        entry: KvEntry,
+        _ttl: Option<i64>,
+    ) -> Result<(), BackendError> {
+        self.put_kv_entries(name, vec![entry]).await?;
+        Ok(())
+    }
+
+    async fn delete_kv_entry(&self, name: &str, key: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let stored = state.kv_entries.entry(name.to_string()).or_default();
+        let before = stored.len();
+        stored.retain(|e| e.key != key);
+
+        if stored.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "Key '{}' not found in KV namespace '{}'",
+                key, name
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn count_expired_kv_entries(&self, name: &str) -> Result<u64, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        if !state.kv.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        Ok(state.kv_expired.get(name).copied().unwrap_or(0))
+    }
+
+    async fn delete_expired_kv_entries(&self, name: &str, limit: u32) -> Result<u64, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.kv.contains_key(name) {
+            return Err(BackendError::NotFound(format!(
+                "KV namespace '{}' not found",
+                name
+            )));
+        }
+
+        let expired = state.kv_expired.entry(name.to_string()).or_default();
+        let deleted = (*expired).min(limit as u64);
+        *expired -= deleted;
+
+        Ok(deleted)
+    }
+
     // Database methods (basic mock implementations)
     async fn list_databases(&self) -> Result<Vec<Database>, BackendError> {
         Ok(vec![])
@@ -391,10 +1546,397 @@ impl Backend for MockBackend {
         })
     }
 
+    async fn update_database(
+        &self,
+        name: &str,
+        _input: UpdateDatabaseInput,
+    ) -> Result<Database, BackendError> {
+        Err(BackendError::NotFound(format!(
+            "Database '{}' not found",
+            name
+        )))
+    }
+
     async fn delete_database(&self, name: &str) -> Result<(), BackendError> {
         Err(BackendError::NotFound(format!(
             "Database '{}' not found",
             name
         )))
     }
+
+    // Token methods
+    async fn create_token(&self, input: CreateTokenInput) -> Result<IssuedToken, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.tokens.iter().any(|t| t.name == input.name) {
+            return Err(BackendError::Api(format!(
+                "Token '{}' already exists",
+                input.name
+            )));
+        }
+
+        let token = format!("ow_{}", uuid::Uuid::new_v4().simple());
+        let id = uuid::Uuid::new_v4().to_string();
+
+        state.tokens.push(ApiToken {
+            id: id.clone(),
+            name: input.name.clone(),
+            scope: input.scope,
+            prefix: token[..12].to_string(),
+            last_used_at: None,
+            expires_at: input.expires_at,
+            created_at: Utc::now(),
+        });
+
+        Ok(IssuedToken {
+            id,
+            name: input.name,
+            scope: input.scope,
+            token,
+            expires_at: input.expires_at,
+        })
+    }
+
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>, BackendError> {
+        Ok(self.state.lock().unwrap().tokens.clone())
+    }
+
+    async fn revoke_token(&self, name: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.tokens.len();
+        state.tokens.retain(|t| t.name != name && t.id != name);
+
+        if state.tokens.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "Token '{}' not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Organization methods
+    async fn list_organizations(&self) -> Result<Vec<Organization>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let mut organizations: Vec<Organization> = state
+            .organizations
+            .values()
+            .map(|(org, members)| Organization {
+                members: members.len() as i64,
+                ..org.clone()
+            })
+            .collect();
+
+        organizations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(organizations)
+    }
+
+    async fn create_organization(&self, name: &str) -> Result<Organization, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.organizations.contains_key(name) {
+            return Err(BackendError::Api(format!(
+                "The name '{}' is already taken",
+                name
+            )));
+        }
+
+        let organization = Organization {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            role: "owner".to_string(),
+            members: 1,
+            created_at: Utc::now(),
+        };
+
+        let members = HashMap::from([("me".to_string(), "owner".to_string())]);
+        state
+            .organizations
+            .insert(name.to_string(), (organization.clone(), members));
+
+        Ok(organization)
+    }
+
+    async fn add_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+        role: &str,
+    ) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let (_, members) = state
+            .organizations
+            .get_mut(org)
+            .ok_or_else(|| BackendError::NotFound(format!("Organization '{}' not found", org)))?;
+
+        members.insert(username.to_string(), role.to_string());
+        Ok(())
+    }
+
+    async fn remove_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let removed = state
+            .organizations
+            .get_mut(org)
+            .and_then(|(_, members)| members.remove(username));
+
+        if removed.is_none() {
+            return Err(BackendError::NotFound(format!(
+                "'{}' is not a member of organization '{}'",
+                username, org
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Account secret methods
+    async fn list_account_secrets(&self) -> Result<Vec<AccountSecret>, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let mut secrets: Vec<AccountSecret> = state
+            .secrets
+            .values()
+            .cloned()
+            .map(|mut secret| {
+                let reference = format!("{}{}", ACCOUNT_SECRET_PREFIX, secret.key);
+                let mut environments: Vec<String> = state
+                    .environments
+                    .values()
+                    .filter(|env| env.values.iter().any(|v| v.value == reference))
+                    .map(|env| env.name.clone())
+                    .collect();
+                environments.sort();
+                secret.environments = environments;
+                secret
+            })
+            .collect();
+
+        secrets.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(secrets)
+    }
+
+    async fn set_account_secret(
+        &self,
+        key: &str,
+        _value: &str,
+    ) -> Result<AccountSecret, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        let secret = state
+            .secrets
+            .entry(key.to_string())
+            .or_insert_with(|| AccountSecret {
+                id: uuid::Uuid::new_v4().to_string(),
+                key: key.to_string(),
+                environments: vec![],
+                updated_at: Utc::now(),
+            });
+        secret.updated_at = Utc::now();
+
+        Ok(secret.clone())
+    }
+
+    async fn delete_account_secret(&self, key: &str) -> Result<(), BackendError> {
+        let in_use = self
+            .list_account_secrets()
+            .await?
+            .into_iter()
+            .find(|s| s.key == key)
+            .ok_or_else(|| BackendError::NotFound(format!("Account secret '{}' not found", key)))?
+            .environments;
+
+        if !in_use.is_empty() {
+            return Err(BackendError::Api(format!(
+                "Account secret '{}' is still referenced by: {}",
+                key,
+                in_use.join(", ")
+            )));
+        }
+
+        self.state.lock().unwrap().secrets.remove(key);
+        Ok(())
+    }
+
+    // Cross-reference methods
+    async fn resource_usage(
+        &self,
+        kind: ResourceKind,
+        name: &str,
+    ) -> Result<ResourceUsage, BackendError> {
+        let state = self.state.lock().unwrap();
+
+        let id = match kind {
+            ResourceKind::Kv => state.kv.get(name).map(|kv| kv.id.clone()),
+            ResourceKind::Storage => state.storage.get(name).map(|s| s.id.clone()),
+            ResourceKind::Database => None,
+            ResourceKind::Environment => state.environments.get(name).map(|e| e.id.clone()),
+        }
+        .ok_or_else(|| BackendError::NotFound(format!("{} '{}' not found", kind.as_str(), name)))?;
+
+        let mut usage = ResourceUsage::default();
+        let mut environment_ids = Vec::new();
+
+        if kind == ResourceKind::Environment {
+            environment_ids.push(id);
+        } else {
+            for env in state.environments.values() {
+                for value in &env.values {
+                    if value.value == id
+                        && kind.binding_types().contains(&value.value_type.as_str())
+                    {
+                        environment_ids.push(env.id.clone());
+                        usage.bindings.push(ResourceBinding {
+                            environment: env.name.clone(),
+                            key: value.key.clone(),
+                            binding_type: value.value_type.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        usage
+            .bindings
+            .sort_by(|a, b| (&a.environment, &a.key).cmp(&(&b.environment, &b.key)));
+
+        usage.workers = state
+            .workers
+            .values()
+            .filter(|w| {
+                w.environment
+                    .as_ref()
+                    .is_some_and(|e| environment_ids.contains(&e.id))
+            })
+            .map(|w| w.name.clone())
+            .collect();
+        usage.workers.sort();
+
+        Ok(usage)
+    }
+
+    // Search methods
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, BackendError> {
+        let state = self.state.lock().unwrap();
+        let query = query.to_lowercase();
+
+        let result = |kind: &str, name: &str, description: &Option<String>| SearchResult {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            description: description.clone(),
+        };
+
+        let mut results: Vec<SearchResult> = state
+            .workers
+            .values()
+            .map(|w| result("worker", &w.name, &w.description))
+            .chain(
+                state
+                    .environments
+                    .values()
+                    .map(|e| result("environment", &e.name, &e.description)),
+            )
+            .chain(
+                state
+                    .kv
+                    .values()
+                    .map(|k| result("kv", &k.name, &k.description)),
+            )
+            .chain(
+                state
+                    .storage
+                    .values()
+                    .map(|s| result("storage", &s.name, &s.description)),
+            )
+            .filter(|r| {
+                r.name.to_lowercase().contains(&query)
+                    || r.description
+                        .as_ref()
+                        .is_some_and(|d| d.to_lowercase().contains(&query))
+            })
+            .collect();
+
+        results.sort_by_key(|r| {
+            let name = r.name.to_lowercase();
+            (
+                name != query,
+                !name.contains(&query),
+                r.name.clone(),
+                r.kind.clone(),
+            )
+        });
+        results.truncate(limit.max(0) as usize);
+
+        Ok(results)
+    }
+
+    // Notification methods
+    async fn list_notification_webhooks(&self) -> Result<Vec<NotificationWebhook>, BackendError> {
+        Ok(self.state.lock().unwrap().webhooks.clone())
+    }
+
+    async fn set_notification_webhook(
+        &self,
+        url: &str,
+        events: &[NotificationEvent],
+    ) -> Result<NotificationWebhook, BackendError> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(webhook) = state.webhooks.iter_mut().find(|w| w.url == url) {
+            webhook.events = events.to_vec();
+            webhook.updated_at = Utc::now();
+            return Ok(webhook.clone());
+        }
+
+        let webhook = NotificationWebhook {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            events: events.to_vec(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        state.webhooks.push(webhook.clone());
+        Ok(webhook)
+    }
+
+    async fn delete_notification_webhook(&self, url: &str) -> Result<(), BackendError> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.webhooks.len();
+
+        state.webhooks.retain(|w| w.url != url);
+
+        if state.webhooks.len() == before {
+            return Err(BackendError::NotFound(format!(
+                "Webhook '{}' not found",
+                url
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Tracing methods
+    async fn record_operation(
+        &self,
+        _context: &RequestContext,
+        _error: Option<&str>,
+    ) -> Result<(), BackendError> {
+        Ok(())
+    }
+
+    async fn get_trace(&self, request_id: &str) -> Result<TraceRecord, BackendError> {
+        Err(BackendError::NotFound(format!(
+            "No record of request '{}'",
+            request_id
+        )))
+    }
 }
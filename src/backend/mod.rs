@@ -1,11 +1,17 @@
 pub mod api;
+pub mod cache;
 pub mod db;
+pub mod mirror;
 
 #[cfg(test)]
 pub mod mock;
 
+use crate::config::{PlatformStorageConfig, TokenScope};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -24,6 +30,18 @@ pub enum BackendError {
 
     #[error("Unauthorized")]
     Unauthorized,
+
+    /// The API answered with a gateway error (502, 503 or 504)
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+
+    /// Some items of a batch failed, the others went through
+    #[error("{0}")]
+    Partial(String),
+
+    /// Bad or missing arguments, caught by the command before any request
+    #[error("{0}")]
+    Usage(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +49,8 @@ pub enum BackendError {
 pub struct WorkerEnvironmentRef {
     pub id: String,
     pub name: String,
+    #[serde(default)]
+    pub protected: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +60,9 @@ pub struct Project {
     pub name: String,
     #[serde(alias = "desc")]
     pub description: Option<String>,
+    /// Environment shared by the project's workers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -53,10 +76,28 @@ pub struct Worker {
     pub description: Option<String>,
     pub current_version: Option<i32>,
     pub environment: Option<WorkerEnvironmentRef>,
+    #[serde(default)]
+    pub protected: bool,
+    /// Public URL (custom domain), when known
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Freeform fields such as owner, repo, runbook and tags
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// `key=value` labels to group and filter workers by
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Worker {
+    /// True if the worker or its linked environment is protected
+    pub fn is_protected(&self) -> bool {
+        self.protected || self.environment.as_ref().is_some_and(|e| e.protected)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWorkerInput {
@@ -64,15 +105,26 @@ pub struct CreateWorkerInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub language: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateWorkerInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Environment name or ID; an empty string unlinks the worker
     #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
+    /// An empty string clears the description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// Replaces every tag of the worker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,7 +135,28 @@ pub struct Deployment {
     pub hash: String,
     pub code_type: String,
     pub deployed_at: DateTime<Utc>,
+    /// Username of whoever deployed this version, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployed_by: Option<String>,
     pub message: Option<String>,
+    /// Tagged deployments are never pruned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+/// Versions to delete when keeping the `keep` newest deployments. The active
+/// version and tagged ones survive even when they fall outside the window.
+/// `versions` holds (version, tagged) pairs in any order.
+pub fn prunable_versions(versions: &[(i32, bool)], current: Option<i32>, keep: u32) -> Vec<i32> {
+    let mut versions = versions.to_vec();
+    versions.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+
+    versions
+        .into_iter()
+        .skip(keep as usize)
+        .filter(|(version, tagged)| !tagged && Some(*version) != current)
+        .map(|(version, _)| version)
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +166,157 @@ pub struct DeployInput {
     pub code_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// SHA-256 (hex) of `code`, verified by the backend before accepting the deploy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(flatten)]
+    pub authorization: DeployAuthorization,
+}
+
+/// What allows a deploy to a protected worker. The backend checks it in the
+/// deploy itself, so no client can skip the gate, and consumes the token only
+/// if the deploy goes through.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployAuthorization {
+    /// Single-use token minted with `ow workers approve`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approve_token: Option<String>,
+    /// The worker name was typed out at an interactive prompt
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirmed: bool,
+}
+
+/// Extra hostname or route pointing at a worker
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WorkerUrl {
+    /// Custom domain served by the worker (or its project)
+    Domain { name: String },
+    /// Path pattern routed to the worker inside its project
+    Route { pattern: String },
+}
+
+impl std::fmt::Display for WorkerUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerUrl::Domain { name } => write!(f, "{}", name),
+            WorkerUrl::Route { pattern } => write!(f, "{}", pattern),
+        }
+    }
+}
+
+/// Resources removed along with a worker or project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePreview {
+    #[serde(default)]
+    pub workers: Vec<String>,
+    #[serde(default)]
+    pub deployments: i64,
+    #[serde(default)]
+    pub routes: i64,
+    #[serde(default)]
+    pub domains: Vec<String>,
+    #[serde(default)]
+    pub crons: i64,
+    /// `bucket/prefix` of the ASSETS storage (objects are left in place)
+    #[serde(default)]
+    pub assets: Option<String>,
+}
+
+/// Single-use token authorizing a deploy to a protected worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployApproval {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Synthetic event delivered to a worker handler by `workers exec`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum WorkerEvent {
+    #[serde(rename_all = "camelCase")]
+    Scheduled {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cron: Option<String>,
+        scheduled_time: DateTime<Utc>,
+    },
+    Queue {
+        messages: Vec<serde_json::Value>,
+    },
+}
+
+impl WorkerEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Scheduled { .. } => "scheduled",
+            Self::Queue { .. } => "queue",
+        }
+    }
+}
+
+/// Outcome of running a worker handler once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecResult {
+    pub success: bool,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub logs: Vec<ExecLog>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecLog {
+    pub level: String,
+    pub message: String,
+}
+
+/// Live state of a worker on the runners, summed across them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerRuntime {
+    pub active_isolates: u32,
+    pub in_flight_requests: u32,
+    pub memory_bytes: u64,
+    /// Most recent first
+    #[serde(default)]
+    pub cold_starts: Vec<ColdStart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColdStart {
+    pub at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// A request recorded by the runtime (or written by hand) for `workers replay`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Path and query string, e.g. "/api/orders?page=2"
+    pub path: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Status the worker originally answered with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captured_at: Option<DateTime<Utc>>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -114,6 +338,7 @@ pub struct DirectUploadConfig {
     pub secret_access_key: String,
     pub region: String,
     pub prefix: Option<String>,
+    pub session_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +371,8 @@ pub struct AssetManifestEntry {
     pub size: usize,
     pub content_type: String,
     pub hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,6 +384,8 @@ pub struct Environment {
     pub description: Option<String>,
     #[serde(default)]
     pub values: Vec<EnvironmentValue>,
+    #[serde(default)]
+    pub protected: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -169,6 +398,20 @@ pub struct EnvironmentValue {
     pub value: String,
     #[serde(rename = "type")]
     pub value_type: String,
+    /// Last time the secret was rotated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+/// One past state of an environment key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentValueChange {
+    /// `None` when the key was removed
+    pub value: Option<String>,
+    #[serde(rename = "type")]
+    pub value_type: Option<String>,
+    pub changed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -179,13 +422,15 @@ pub struct CreateEnvironmentInput {
     pub desc: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEnvironmentInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub values: Option<Vec<EnvironmentValueInput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -201,6 +446,29 @@ pub struct EnvironmentValueInput {
 }
 
 // Storage types
+
+/// Bucket behind `--provider platform` storage configs (credentials omitted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformStorage {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+impl From<&PlatformStorageConfig> for PlatformStorage {
+    fn from(config: &PlatformStorageConfig) -> Self {
+        Self {
+            endpoint: config.endpoint.clone(),
+            bucket: config.bucket.clone(),
+            region: config.region.clone(),
+            prefix: config.prefix.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageConfig {
@@ -241,6 +509,15 @@ pub struct CreateStorageInput {
     pub public_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStorageInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+}
+
 // KV types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -261,6 +538,23 @@ pub struct CreateKvInput {
     pub desc: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateKvInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+}
+
+/// A key and its JSON value in a KV namespace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KvEntry {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
 // Database types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type, clap::ValueEnum)]
 #[cfg_attr(feature = "mcp", derive(schemars::JsonSchema))]
@@ -309,6 +603,253 @@ pub struct CreateDatabaseInput {
     pub timeout_seconds: Option<i32>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDatabaseInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+}
+
+// Request tracing
+/// Correlation ID for the current mutating CLI operation
+#[derive(Debug)]
+pub struct RequestContext {
+    pub id: String,
+    /// e.g. "workers deploy"
+    pub operation: String,
+    /// Whether the backend has seen the ID, so `ow trace` can find it
+    sent: AtomicBool,
+}
+
+impl RequestContext {
+    pub fn mark_sent(&self) {
+        self.sent.store(true, Ordering::Relaxed);
+    }
+
+    pub fn was_sent(&self) -> bool {
+        self.sent.load(Ordering::Relaxed)
+    }
+}
+
+static REQUEST_CONTEXT: OnceLock<RequestContext> = OnceLock::new();
+
+/// Start tracing the current CLI invocation (once, from `main`)
+pub fn set_request_context(operation: String) -> &'static RequestContext {
+    REQUEST_CONTEXT.get_or_init(|| RequestContext {
+        id: format!("req_{}", uuid::Uuid::new_v4().simple()),
+        operation,
+        sent: AtomicBool::new(false),
+    })
+}
+
+pub fn request_context() -> Option<&'static RequestContext> {
+    REQUEST_CONTEXT.get()
+}
+
+/// Server-side record of a traced request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceRecord {
+    pub request_id: String,
+    pub operation: String,
+    /// "ok" or "error"
+    pub status: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Backend-specific details (API: handler, duration, upstream errors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+// Token types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTokenInput {
+    pub name: String,
+    pub scope: TokenScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A freshly issued token; the secret is only ever returned once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedToken {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub token: String,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Access to a worker granted to another account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerShare {
+    pub username: String,
+    /// "viewer" or "editor"
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An organization the current user belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    /// The current user's role: "owner" or "member"
+    pub role: String,
+    #[serde(default)]
+    pub members: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A token as listed: identified by its prefix, never the secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub prefix: String,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Account secret types
+/// Prefix of an environment value referencing an account secret (`@account/KEY`)
+pub const ACCOUNT_SECRET_PREFIX: &str = "@account/";
+
+/// An account-scoped secret as listed: the value is never returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSecret {
+    pub id: String,
+    pub key: String,
+    /// Environments referencing the secret
+    #[serde(default)]
+    pub environments: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Cross-reference types
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Kv,
+    Storage,
+    Database,
+    Environment,
+}
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Kv => "kv",
+            Self::Storage => "storage",
+            Self::Database => "database",
+            Self::Environment => "environment",
+        }
+    }
+
+    /// Environment value types binding a resource of this kind
+    pub fn binding_types(&self) -> &'static [&'static str] {
+        match self {
+            Self::Kv => &["kv"],
+            Self::Storage => &["storage", "assets"],
+            Self::Database => &["database"],
+            Self::Environment => &[],
+        }
+    }
+}
+
+/// Environment key bound to a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceBinding {
+    pub environment: String,
+    pub key: String,
+    #[serde(rename = "type")]
+    pub binding_type: String,
+}
+
+/// Everything referencing a resource, directly or through an environment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    #[serde(default)]
+    pub bindings: Vec<ResourceBinding>,
+    /// Workers linked to a binding environment (or to the environment itself)
+    #[serde(default)]
+    pub workers: Vec<String>,
+    /// Projects routing to one of those workers
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl ResourceUsage {
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty() && self.workers.is_empty() && self.projects.is_empty()
+    }
+}
+
+// Search types
+/// A resource whose name or description matched a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// worker, project, environment, kv, storage or database
+    pub kind: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+// Notification types
+/// Operation a webhook can be notified of
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationEvent {
+    Deploy,
+    Rollback,
+    Delete,
+}
+
+impl NotificationEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deploy => "deploy",
+            Self::Rollback => "rollback",
+            Self::Delete => "delete",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "deploy" => Some(Self::Deploy),
+            "rollback" => Some(Self::Rollback),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// A webhook of the signed-in user, POSTed a JSON payload after matching operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationWebhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<NotificationEvent>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 pub trait Backend: Send + Sync {
     /// Returns true if this backend should use workers.rocks as fallback URL
     fn is_default_cloud(&self) -> bool {
@@ -334,6 +875,12 @@ pub trait Backend: Send + Sync {
         name: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
 
+    /// List what deleting a worker cascades to
+    fn preview_delete_worker(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<DeletePreview, BackendError>> + Send;
+
     fn update_worker(
         &self,
         name: &str,
@@ -346,6 +893,26 @@ pub trait Backend: Send + Sync {
         environment_id: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
 
+    /// Replace the metadata of a worker
+    fn set_worker_metadata(
+        &self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> impl std::future::Future<Output = Result<Worker, BackendError>> + Send;
+
+    /// Get the deployment currently live for a worker (None if never deployed)
+    fn get_current_deployment(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Option<Deployment>, BackendError>> + Send;
+
+    /// Live deployment of each listed worker, in the same order, without
+    /// looking the workers up again
+    fn list_current_deployments(
+        &self,
+        workers: &[Worker],
+    ) -> impl std::future::Future<Output = Result<Vec<Option<Deployment>>, BackendError>> + Send;
+
     fn deploy_worker(
         &self,
         name: &str,
@@ -358,8 +925,142 @@ pub trait Backend: Send + Sync {
         path: &std::path::Path,
         zip_data: Vec<u8>,
         assets_manifest: &[AssetManifestEntry],
+        authorization: &DeployAuthorization,
     ) -> impl std::future::Future<Output = Result<UploadResult, BackendError>> + Send;
 
+    /// Mint a single-use approval token for deploying to a protected worker.
+    /// Only the worker's owner (an owner, for organizations) may approve.
+    fn create_deploy_approval(
+        &self,
+        name: &str,
+        ttl_seconds: i64,
+    ) -> impl std::future::Future<Output = Result<DeployApproval, BackendError>> + Send;
+
+    /// Run a worker's scheduled or queue handler once with a synthetic event
+    fn exec_worker(
+        &self,
+        name: &str,
+        event: WorkerEvent,
+    ) -> impl std::future::Future<Output = Result<ExecResult, BackendError>> + Send;
+
+    /// Isolates, in-flight requests, memory and recent cold starts of a worker
+    fn worker_runtime(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<WorkerRuntime, BackendError>> + Send;
+
+    /// A request captured for a worker, by its log ID
+    fn get_captured_request(
+        &self,
+        name: &str,
+        id: &str,
+    ) -> impl std::future::Future<Output = Result<CapturedRequest, BackendError>> + Send;
+
+    /// Deploy the active code again as a new version (e.g. to pick up new env values)
+    fn redeploy_worker(
+        &self,
+        name: &str,
+        message: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<Deployment, BackendError>> + Send;
+
+    /// Set or clear (None) the tag on a deployment
+    fn tag_deployment(
+        &self,
+        name: &str,
+        version: i32,
+        tag: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<Deployment, BackendError>> + Send;
+
+    /// All deployments of a worker, oldest first
+    fn list_deployments(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<Deployment>, BackendError>> + Send;
+
+    /// Code stored with one deployment of a worker
+    fn get_deployment_code(
+        &self,
+        name: &str,
+        version: i32,
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, BackendError>> + Send;
+
+    /// Delete all but the `keep` newest deployments of a worker (see
+    /// `prunable_versions`), returning the deleted versions
+    fn prune_deployments(
+        &self,
+        name: &str,
+        keep: u32,
+    ) -> impl std::future::Future<Output = Result<Vec<i32>, BackendError>> + Send;
+
+    /// Account-wide number of deployments kept per worker (None keeps all)
+    fn get_retention_policy(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<u32>, BackendError>> + Send;
+
+    fn set_retention_policy(
+        &self,
+        keep: Option<u32>,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Domains and project routes pointing at a worker
+    fn list_worker_urls(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<WorkerUrl>, BackendError>> + Send;
+
+    fn add_worker_url(
+        &self,
+        name: &str,
+        url: &WorkerUrl,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    fn remove_worker_url(
+        &self,
+        name: &str,
+        url: &WorkerUrl,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Vars and secrets set on the worker itself, overriding its environment
+    fn list_worker_values(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<EnvironmentValue>, BackendError>> + Send;
+
+    /// Create or replace a worker value (`value_type` is "var" or "secret")
+    fn set_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+        value_type: &str,
+    ) -> impl std::future::Future<Output = Result<EnvironmentValue, BackendError>> + Send;
+
+    fn delete_worker_value(
+        &self,
+        name: &str,
+        key: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Accounts the worker is shared with (owner only)
+    fn list_worker_shares(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<WorkerShare>, BackendError>> + Send;
+
+    /// Share a worker with another user, or change their role (owner only)
+    fn share_worker(
+        &self,
+        name: &str,
+        username: &str,
+        role: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    fn unshare_worker(
+        &self,
+        name: &str,
+        username: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
     // Project methods
     fn list_projects(
         &self,
@@ -370,6 +1071,26 @@ pub trait Backend: Send + Sync {
         name: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
 
+    /// List what deleting a project cascades to
+    fn preview_delete_project(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<DeletePreview, BackendError>> + Send;
+
+    /// Move a standalone worker into a project (it takes the project's environment)
+    fn add_project_worker(
+        &self,
+        project: &str,
+        worker: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Detach a worker from a project and drop the routes pointing at it
+    fn remove_project_worker(
+        &self,
+        project: &str,
+        worker: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
     // Environment methods
     fn list_environments(
         &self,
@@ -391,6 +1112,29 @@ pub trait Backend: Send + Sync {
         input: UpdateEnvironmentInput,
     ) -> impl std::future::Future<Output = Result<Environment, BackendError>> + Send;
 
+    /// Replace a secret's value and record the rotation time
+    fn rotate_environment_secret(
+        &self,
+        env: &str,
+        key: &str,
+        value: &str,
+    ) -> impl std::future::Future<Output = Result<EnvironmentValue, BackendError>> + Send;
+
+    /// Past values of a key, oldest first
+    fn environment_value_history(
+        &self,
+        env: &str,
+        key: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<EnvironmentValueChange>, BackendError>> + Send;
+
+    /// Keys of an environment as they were at `at`, by key. Past values have
+    /// no id: only the current ones can be updated.
+    fn environment_values_at(
+        &self,
+        env: &str,
+        at: DateTime<Utc>,
+    ) -> impl std::future::Future<Output = Result<Vec<EnvironmentValue>, BackendError>> + Send;
+
     fn delete_environment(
         &self,
         name: &str,
@@ -411,11 +1155,35 @@ pub trait Backend: Send + Sync {
         input: CreateStorageInput,
     ) -> impl std::future::Future<Output = Result<StorageConfig, BackendError>> + Send;
 
+    fn update_storage(
+        &self,
+        name: &str,
+        input: UpdateStorageInput,
+    ) -> impl std::future::Future<Output = Result<StorageConfig, BackendError>> + Send;
+
     fn delete_storage(
         &self,
         name: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
 
+    /// Bucket credentials of a storage configuration (DB aliases only)
+    fn get_storage_credentials(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<DirectUploadConfig, BackendError>> + Send;
+
+    /// Bucket used for platform storage configs (None if not configured)
+    fn get_platform_storage(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<PlatformStorage>, BackendError>> + Send;
+
+    /// Configure the platform bucket: in the alias config for DB aliases,
+    /// on the server for API aliases
+    fn set_platform_storage(
+        &self,
+        storage: PlatformStorageConfig,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
     // KV methods
     fn list_kv(
         &self,
@@ -431,11 +1199,69 @@ pub trait Backend: Send + Sync {
         input: CreateKvInput,
     ) -> impl std::future::Future<Output = Result<KvNamespace, BackendError>> + Send;
 
+    fn update_kv(
+        &self,
+        name: &str,
+        input: UpdateKvInput,
+    ) -> impl std::future::Future<Output = Result<KvNamespace, BackendError>> + Send;
+
     fn delete_kv(
         &self,
         name: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
 
+    /// Live (non-expired) entries of a namespace whose key starts with `prefix`
+    fn list_kv_entries(
+        &self,
+        name: &str,
+        prefix: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<KvEntry>, BackendError>> + Send;
+
+    /// Write entries into a namespace, replacing existing keys. Returns how
+    /// many were written.
+    fn put_kv_entries(
+        &self,
+        name: &str,
+        entries: Vec<KvEntry>,
+    ) -> impl std::future::Future<Output = Result<usize, BackendError>> + Send;
+
+    /// A live (non-expired) entry of a namespace
+    fn get_kv_entry(
+        &self,
+        name: &str,
+        key: &str,
+    ) -> impl std::future::Future<Output = Result<KvEntry, BackendError>> + Send;
+
+    /// Write one entry, replacing the key if it exists. With a TTL (in
+    /// seconds) the key expires, otherwise it is kept until deleted.
+    fn put_kv_entry(
+        &self,
+        name: &str,
+        entry: KvEntry,
+        ttl: Option<i64>,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    fn delete_kv_entry(
+        &self,
+        name: &str,
+        key: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Entries of a namespace whose TTL has passed but that are still stored
+    /// (DB aliases)
+    fn count_expired_kv_entries(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<u64, BackendError>> + Send;
+
+    /// Delete up to `limit` expired entries of a namespace, returning how
+    /// many were deleted (DB aliases)
+    fn delete_expired_kv_entries(
+        &self,
+        name: &str,
+        limit: u32,
+    ) -> impl std::future::Future<Output = Result<u64, BackendError>> + Send;
+
     // Database methods
     fn list_databases(
         &self,
@@ -451,8 +1277,122 @@ pub trait Backend: Send + Sync {
         input: CreateDatabaseInput,
     ) -> impl std::future::Future<Output = Result<Database, BackendError>> + Send;
 
+    fn update_database(
+        &self,
+        name: &str,
+        input: UpdateDatabaseInput,
+    ) -> impl std::future::Future<Output = Result<Database, BackendError>> + Send;
+
     fn delete_database(
         &self,
         name: &str,
     ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    // Token methods
+    fn create_token(
+        &self,
+        input: CreateTokenInput,
+    ) -> impl std::future::Future<Output = Result<IssuedToken, BackendError>> + Send;
+
+    fn list_tokens(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<ApiToken>, BackendError>> + Send;
+
+    /// Revoke a token by name or ID
+    fn revoke_token(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    // Organization methods (always for the signed-in user, whichever org is selected)
+    fn list_organizations(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<Organization>, BackendError>> + Send;
+
+    /// Create an organization owned by the current user
+    fn create_organization(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Organization, BackendError>> + Send;
+
+    /// Add a user to an organization, or change their role (owners only)
+    fn add_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+        role: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    /// Remove a user from an organization: owners remove anyone, members only themselves
+    fn remove_organization_member(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    // Account secret methods
+    fn list_account_secrets(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<AccountSecret>, BackendError>> + Send;
+
+    /// Create or rotate a secret; referencing environments pick up the new value
+    fn set_account_secret(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> impl std::future::Future<Output = Result<AccountSecret, BackendError>> + Send;
+
+    /// Delete a secret no environment references anymore
+    fn delete_account_secret(
+        &self,
+        key: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    // Cross-reference methods
+    /// Environments binding a resource, and the workers and projects using them
+    fn resource_usage(
+        &self,
+        kind: ResourceKind,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<ResourceUsage, BackendError>> + Send;
+
+    // Search methods
+    /// Resources whose name or description contains `query` (case-insensitive),
+    /// exact name matches first, then names containing it, then descriptions
+    fn search(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> impl std::future::Future<Output = Result<Vec<SearchResult>, BackendError>> + Send;
+
+    // Notification methods
+    fn list_notification_webhooks(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<NotificationWebhook>, BackendError>> + Send;
+
+    /// Add a webhook, or replace the events of an existing one with the same URL
+    fn set_notification_webhook(
+        &self,
+        url: &str,
+        events: &[NotificationEvent],
+    ) -> impl std::future::Future<Output = Result<NotificationWebhook, BackendError>> + Send;
+
+    fn delete_notification_webhook(
+        &self,
+        url: &str,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    // Tracing methods
+    /// Store the outcome of a traced operation. The API records requests
+    /// server-side from the `X-Request-Id` header, so only DB backends write here.
+    fn record_operation(
+        &self,
+        context: &RequestContext,
+        error: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<(), BackendError>> + Send;
+
+    fn get_trace(
+        &self,
+        request_id: &str,
+    ) -> impl std::future::Future<Output = Result<TraceRecord, BackendError>> + Send;
 }
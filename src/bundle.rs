@@ -0,0 +1,217 @@
+//! Worker bundles (`ow workers export` / `ow workers import`): a gzipped
+//! tarball with the deployed code and a `bundle.json` describing the worker,
+//! its environment and its routes.
+
+use crate::backend::WorkerUrl;
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+pub const BUNDLE_FILE: &str = "bundle.json";
+
+/// Bumped when a bundle can no longer be read by older versions
+pub const BUNDLE_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub format: u32,
+    pub exported_at: DateTime<Utc>,
+    pub worker: BundleWorker,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<BundleEnvironment>,
+    /// Values set on the worker itself, overriding the environment
+    #[serde(default)]
+    pub worker_values: Vec<BundleValue>,
+    #[serde(default)]
+    pub urls: Vec<WorkerUrl>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleWorker {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub code_type: String,
+    pub version: i32,
+    /// SHA-256 (hex) of the code file
+    pub hash: String,
+    /// Code file inside the archive
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEnvironment {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub values: Vec<BundleValue>,
+}
+
+/// An environment or worker value. Secrets are redacted (neither `value` nor
+/// `resource`), bindings refer to their resource by name so they can be
+/// matched in another account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleValue {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+}
+
+impl BundleValue {
+    pub fn is_redacted(&self) -> bool {
+        self.value.is_none() && self.resource.is_none()
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Bundle {
+    /// Write the bundle and its code as a `.tar.gz` archive
+    pub fn write(&self, path: &Path, code: &[u8]) -> io::Result<()> {
+        let manifest = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+
+        let file = File::create(path)?;
+        let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+        for (name, data) in [
+            (BUNDLE_FILE, &manifest[..]),
+            (self.worker.code.as_str(), code),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(self.exported_at.timestamp().max(0) as u64);
+            header.set_cksum();
+
+            archive.append_data(&mut header, name, data)?;
+        }
+
+        archive.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    /// Read a bundle and its code, checking the format and the code hash
+    pub fn read(path: &Path) -> io::Result<(Self, Vec<u8>)> {
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(path)?));
+        let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            files.insert(name, data);
+        }
+
+        let manifest = files
+            .get(BUNDLE_FILE)
+            .ok_or_else(|| invalid(format!("{} is missing, not a worker bundle", BUNDLE_FILE)))?;
+
+        let bundle: Self = serde_json::from_slice(manifest)
+            .map_err(|e| invalid(format!("invalid {}: {}", BUNDLE_FILE, e)))?;
+
+        if bundle.format > BUNDLE_FORMAT {
+            return Err(invalid(format!(
+                "bundle format {} is newer than this CLI supports ({}), upgrade ow",
+                bundle.format, BUNDLE_FORMAT
+            )));
+        }
+
+        let code = files
+            .remove(&bundle.worker.code)
+            .ok_or_else(|| invalid(format!("{} is missing", bundle.worker.code)))?;
+
+        let hash = hex::encode(Sha256::digest(&code));
+
+        if !hash.eq_ignore_ascii_case(&bundle.worker.hash) {
+            return Err(invalid(format!(
+                "{} hashes to {}, but the bundle records {}",
+                bundle.worker.code, hash, bundle.worker.hash
+            )));
+        }
+
+        Ok((bundle, code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(code: &[u8]) -> Bundle {
+        Bundle {
+            format: BUNDLE_FORMAT,
+            exported_at: Utc::now(),
+            worker: BundleWorker {
+                name: "api".to_string(),
+                description: None,
+                code_type: "javascript".to_string(),
+                version: 3,
+                hash: hex::encode(Sha256::digest(code)),
+                code: "worker.js".to_string(),
+            },
+            environment: Some(BundleEnvironment {
+                name: "prod".to_string(),
+                description: None,
+                values: vec![BundleValue {
+                    key: "API_KEY".to_string(),
+                    value_type: "secret".to_string(),
+                    value: None,
+                    resource: None,
+                }],
+            }),
+            worker_values: vec![],
+            urls: vec![WorkerUrl::Route {
+                pattern: "/api/*".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.tar.gz");
+        let code = b"export default {}";
+
+        sample(code).write(&path, code).unwrap();
+
+        let (bundle, read) = Bundle::read(&path).unwrap();
+
+        assert_eq!(read, code);
+        assert_eq!(bundle.worker.version, 3);
+        assert!(bundle.environment.unwrap().values[0].is_redacted());
+        assert_eq!(bundle.urls.len(), 1);
+    }
+
+    #[test]
+    fn test_read_rejects_tampered_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.tar.gz");
+
+        let mut bundle = sample(b"export default {}");
+        bundle.worker.hash = "00".repeat(32);
+        bundle.write(&path, b"export default {}").unwrap();
+
+        let err = Bundle::read(&path).unwrap_err();
+        assert!(err.to_string().contains("hashes to"));
+    }
+}
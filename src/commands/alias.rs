@@ -1,6 +1,32 @@
-use crate::config::{AliasConfig, Config, ConfigError};
+use crate::commands::health::{self, Check, Status};
+use crate::config::{AliasConfig, Config, ConfigError, Mirror, PlatformStorageConfig};
 use clap::Subcommand;
 use colored::Colorize;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Timeout in seconds for each connectivity check (wizard and `set --db`)
+const CHECK_TIMEOUT: u64 = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AliasError {
+    #[error("{0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Failed to read input: {0}")]
+    Input(#[from] io::Error),
+
+    #[error("'ow alias add' is interactive, use 'ow alias set' in scripts")]
+    NotInteractive,
+
+    #[error("Cancelled, alias not saved")]
+    Cancelled,
+
+    #[error("Alias not saved: {0} check(s) failed (use --no-verify to save anyway)")]
+    Unverified(usize),
+}
 
 #[derive(Subcommand)]
 pub enum AliasCommand {
@@ -8,6 +34,8 @@ pub enum AliasCommand {
     #[command(after_help = "Examples:\n  \
         ow alias set prod --api https://dash.openworkers.com\n  \
         ow alias set local --db postgres://localhost/ow --user admin@example.com\n  \
+        ow alias set prod-db --db postgres://primary/ow --read-url postgres://replica/ow --user admin --force\n  \
+        ow alias set offline --db postgres://db.internal/ow --user admin --no-verify\n  \
         ow alias set dev --api https://localhost:8080 --insecure")]
     Set {
         /// Alias name (used as prefix: ow <alias> workers list)
@@ -33,11 +61,26 @@ pub enum AliasCommand {
         #[arg(long, requires = "db")]
         user: Option<String>,
 
+        /// PostgreSQL URL of a read replica for read-only commands (list, get, ...)
+        #[arg(long, requires = "db")]
+        read_url: Option<String>,
+
+        /// Save the db alias without checking the connection, migrations and user
+        #[arg(long, requires = "db")]
+        no_verify: bool,
+
         /// Overwrite existing alias without confirmation
         #[arg(short, long)]
         force: bool,
     },
 
+    /// Create an alias step by step, testing connectivity before saving
+    #[command(after_help = "Example:\n  ow alias add staging")]
+    Add {
+        /// Alias name (prompted when omitted)
+        name: Option<String>,
+    },
+
     /// List all configured aliases (* = default)
     #[command(alias = "ls")]
     List,
@@ -55,11 +98,53 @@ pub enum AliasCommand {
         /// Alias name to set as default
         name: String,
     },
+
+    /// Group aliases to run a command against all of them at once
+    #[command(after_help = "Examples:\n  \
+        ow alias group all-prod prod-eu prod-us\n  \
+        ow all-prod workers list             Runs once per alias, output labelled\n  \
+        ow alias group all-prod --remove")]
+    Group {
+        /// Group name (used as prefix like an alias)
+        name: String,
+
+        /// Member aliases
+        #[arg(required_unless_present = "remove", conflicts_with = "remove")]
+        aliases: Vec<String>,
+
+        /// Delete the group
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Send commands to a DB alias and the API alias it is migrating to,
+    /// comparing results (divergences go to ~/.openworkers/mirror.log)
+    #[command(after_help = "Examples:\n  \
+        ow alias mirror migrating local cloud\n  \
+        ow migrating workers list           Answered by local, compared with cloud\n  \
+        ow migrating env set prod KEY v     Written to local, then to cloud\n  \
+        ow alias mirror migrating --remove")]
+    Mirror {
+        /// Mirror name (used as prefix like an alias)
+        name: String,
+
+        /// DB alias answering commands
+        #[arg(required_unless_present = "remove", conflicts_with = "remove")]
+        primary: Option<String>,
+
+        /// API alias receiving the same writes, its reads compared
+        #[arg(required_unless_present = "remove", conflicts_with = "remove")]
+        shadow: Option<String>,
+
+        /// Delete the mirror
+        #[arg(long)]
+        remove: bool,
+    },
 }
 
 impl AliasCommand {
-    pub fn run(self) -> Result<(), ConfigError> {
-        match self {
+    pub async fn run(self) -> Result<(), AliasError> {
+        let result = match self {
             Self::Set {
                 name,
                 api,
@@ -67,29 +152,61 @@ impl AliasCommand {
                 insecure,
                 db,
                 user,
+                read_url,
+                no_verify,
                 force,
-            } => cmd_set(name, api, token, insecure, db, user, force),
+            } => {
+                return cmd_set(
+                    name, api, token, insecure, db, user, read_url, no_verify, force,
+                )
+                .await;
+            }
+            Self::Add { name } => return cmd_add(name).await,
             Self::List => cmd_list(),
             Self::Remove { name } => cmd_remove(name),
             Self::SetDefault { name } => cmd_set_default(name),
-        }
+            Self::Group {
+                name,
+                aliases,
+                remove,
+            } => cmd_group(name, aliases, remove),
+            Self::Mirror {
+                name,
+                primary,
+                shadow,
+                remove,
+            } => cmd_mirror(name, primary, shadow, remove),
+        };
+
+        Ok(result?)
     }
 }
 
-fn cmd_set(
+#[allow(clippy::too_many_arguments)]
+async fn cmd_set(
     name: String,
     api: Option<String>,
     token: Option<String>,
     insecure: bool,
     db: Option<String>,
     user: Option<String>,
+    read_url: Option<String>,
+    no_verify: bool,
     force: bool,
-) -> Result<(), ConfigError> {
+) -> Result<(), AliasError> {
     let mut config = Config::load()?;
 
     let alias_config = match (api, db) {
         (Some(url), None) => AliasConfig::api(url, token, insecure),
-        (None, Some(database_url)) => AliasConfig::db(database_url, user, None),
+        (None, Some(database_url)) => {
+            let mut alias = AliasConfig::db(database_url, user, None);
+
+            if let AliasConfig::Db { read_url: slot, .. } = &mut alias {
+                *slot = read_url;
+            }
+
+            alias
+        }
         _ => {
             eprintln!(
                 "{} Either --api or --db must be specified",
@@ -100,13 +217,55 @@ fn cmd_set(
     };
 
     let is_update = config.aliases.contains_key(&name);
+
+    if is_update && !force {
+        return Err(ConfigError::AliasExists(name).into());
+    }
+
+    if let (
+        AliasConfig::Db {
+            database_url,
+            user,
+            read_url,
+            ..
+        },
+        false,
+    ) = (&alias_config, no_verify)
+    {
+        eprintln!("{} Verifying database access", "→".cyan());
+
+        let mut checks = verify_db(database_url, user.as_deref(), CHECK_TIMEOUT).await;
+
+        if let Some(read_url) = read_url {
+            eprintln!("{} Verifying the read replica", "→".cyan());
+            checks.extend(verify_db(read_url, user.as_deref(), CHECK_TIMEOUT).await);
+        }
+
+        health::print_checks(&checks);
+        println!();
+
+        let failed = checks
+            .iter()
+            .filter(|c| c.status == Status::Degraded)
+            .count();
+
+        if failed > 0 {
+            return Err(AliasError::Unverified(failed));
+        }
+    }
+
     config.set_alias(&name, alias_config.clone(), force)?;
+
+    if is_update {
+        Config::snapshot("alias overwrite")?;
+    }
+
     config.save()?;
 
     let action = if is_update { "Updated" } else { "Added" };
     let type_name = alias_config.type_name();
 
-    println!(
+    eprintln!(
         "{} {} alias '{}' ({})",
         action,
         type_name.cyan(),
@@ -120,12 +279,235 @@ fn cmd_set(
     Ok(())
 }
 
+async fn cmd_add(name: Option<String>) -> Result<(), AliasError> {
+    if !io::stdin().is_terminal() {
+        return Err(AliasError::NotInteractive);
+    }
+
+    let mut config = Config::load()?;
+
+    let name = match name {
+        Some(name) => name,
+        None => ask("Alias name", None)?,
+    };
+
+    let is_update = config.aliases.contains_key(&name);
+
+    if is_update && !ask_yes_no(&format!("Alias '{}' exists, overwrite?", name), false)? {
+        return Err(AliasError::Cancelled);
+    }
+
+    let backend = loop {
+        match ask("Backend (api/db)", Some("api"))?
+            .to_lowercase()
+            .as_str()
+        {
+            "api" => break "api",
+            "db" => break "db",
+            _ => eprintln!("{} answer 'api' or 'db'", "error:".red().bold()),
+        }
+    };
+
+    let alias_config = if backend == "api" {
+        let url = ask("API URL", Some("https://dash.openworkers.com"))?;
+        let insecure = ask_yes_no("Accept invalid TLS certificates?", false)?;
+        let token = ask_secret("API token (empty to run 'ow login' later)")?;
+
+        AliasConfig::api(url, (!token.is_empty()).then_some(token), insecure)
+    } else {
+        let database_url = ask("PostgreSQL URL", Some("postgres://localhost/openworkers"))?;
+        let user = ask("User to operate as", None)?;
+
+        let storage = if ask_yes_no("Configure platform storage (S3/R2)?", false)? {
+            Some(ask_storage()?)
+        } else {
+            None
+        };
+
+        AliasConfig::db(database_url, Some(user), storage)
+    };
+
+    eprintln!();
+    eprintln!("{} Testing connectivity", "→".cyan());
+
+    let checks = match &alias_config {
+        AliasConfig::Api { url, insecure, .. } => {
+            match health::check_api(url, *insecure, CHECK_TIMEOUT).await {
+                Ok(checks) => checks,
+                Err(e) => vec![Check::degraded("api", e.to_string())],
+            }
+        }
+        AliasConfig::Db {
+            database_url,
+            storage,
+            ..
+        } => health::check_db(database_url, storage.as_ref(), CHECK_TIMEOUT).await,
+    };
+
+    health::print_checks(&checks);
+    println!();
+
+    let degraded = checks.iter().any(|c| c.status == Status::Degraded);
+
+    if degraded && !ask_yes_no("Some checks failed. Save anyway?", false)? {
+        return Err(AliasError::Cancelled);
+    }
+
+    config.set_alias(&name, alias_config, true)?;
+
+    if is_update {
+        Config::snapshot("alias overwrite")?;
+    }
+
+    let made_default = config.default.is_none();
+
+    if made_default {
+        config.set_default(&name)?;
+    }
+
+    config.save()?;
+
+    let action = if is_update { "Updated" } else { "Added" };
+    let default_note = if made_default { " (default)" } else { "" };
+
+    eprintln!(
+        "{} {} alias '{}'{}",
+        action,
+        backend.cyan(),
+        name.green().bold(),
+        default_note.dimmed()
+    );
+
+    Ok(())
+}
+
+/// Check a db alias before saving it: the URL parses, the server answers,
+/// migrations are applied and the user exists. Stops at the first failure
+/// the later checks depend on.
+async fn verify_db(database_url: &str, user: Option<&str>, timeout: u64) -> Vec<Check> {
+    let options = match PgConnectOptions::from_str(database_url) {
+        Ok(options) => options,
+        Err(e) => return vec![Check::degraded("url", e.to_string())],
+    };
+
+    let start = Instant::now();
+
+    let pool = match PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(timeout))
+        .connect_with(options)
+        .await
+    {
+        Ok(pool) => pool,
+        Err(e) => return vec![Check::degraded("database", e.to_string())],
+    };
+
+    let mut checks = vec![
+        Check::ok("database", start, "connected"),
+        health::check_migrations(&pool).await,
+    ];
+
+    let Some(user) = user else {
+        checks.push(Check::skipped("user", "no --user given"));
+        return checks;
+    };
+
+    let start = Instant::now();
+    let exists: Result<bool, _> =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
+            .bind(user)
+            .fetch_one(&pool)
+            .await;
+
+    checks.push(match exists {
+        Ok(true) => Check::ok("user", start, user),
+        Ok(false) => Check::degraded(
+            "user",
+            format!("'{}' not found (create it with 'ow users create')", user),
+        ),
+        Err(e) => Check::degraded("user", e.to_string()),
+    });
+
+    checks
+}
+
+fn ask_storage() -> Result<PlatformStorageConfig, AliasError> {
+    let endpoint = ask("  Endpoint", None)?;
+    let bucket = ask("  Bucket", None)?;
+    let access_key_id = ask("  Access key ID", None)?;
+    let secret_access_key = ask_secret("  Secret access key")?;
+    let region = ask("  Region", Some("auto"))?;
+    let prefix = ask("  Key prefix (optional)", Some(""))?;
+
+    Ok(PlatformStorageConfig {
+        endpoint,
+        bucket,
+        access_key_id,
+        secret_access_key,
+        region,
+        prefix: (!prefix.is_empty()).then_some(prefix),
+        session_token: None,
+    })
+}
+
+/// Prompt for a line; empty input takes `default`, or asks again when there is none
+fn ask(label: &str, default: Option<&str>) -> io::Result<String> {
+    loop {
+        match default {
+            Some(d) if !d.is_empty() => eprint!("{} {}: ", label, format!("[{}]", d).dimmed()),
+            _ => eprint!("{}: ", label),
+        }
+        io::stderr().flush().ok();
+
+        let mut buf = String::new();
+
+        if io::stdin().lock().read_line(&mut buf)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        match (buf.trim(), default) {
+            ("", Some(d)) => return Ok(d.to_string()),
+            ("", None) => continue,
+            (answer, _) => return Ok(answer.to_string()),
+        }
+    }
+}
+
+/// Prompt for a credential without echoing it
+fn ask_secret(label: &str) -> io::Result<String> {
+    Ok(rpassword::prompt_password(format!("{}: ", label))?
+        .trim()
+        .to_string())
+}
+
+fn ask_yes_no(label: &str, default: bool) -> io::Result<bool> {
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+
+    loop {
+        let answer = ask(&format!("{} {}", label, hint), Some(""))?;
+
+        match parse_yes_no(&answer, default) {
+            Some(yes) => return Ok(yes),
+            None => eprintln!("{} answer 'y' or 'n'", "error:".red().bold()),
+        }
+    }
+}
+
+fn parse_yes_no(answer: &str, default: bool) -> Option<bool> {
+    match answer.trim().to_lowercase().as_str() {
+        "" => Some(default),
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
 fn cmd_list() -> Result<(), ConfigError> {
     let config = Config::load()?;
 
     if config.aliases.is_empty() {
-        println!("No aliases configured.");
-        println!(
+        eprintln!("No aliases configured.");
+        eprintln!(
             "Run '{}' to add one.",
             "ow alias set <name> --api <url>".cyan()
         );
@@ -143,41 +525,92 @@ fn cmd_list() -> Result<(), ConfigError> {
         };
 
         let (type_str, detail) = match alias {
-            AliasConfig::Api { url, token, .. } => {
-                let auth = if token.is_some() { " (auth)" } else { "" };
+            AliasConfig::Api {
+                url, token, scope, ..
+            } => {
+                let auth = match (token, scope) {
+                    (Some(_), Some(scope)) => format!(" (auth: {})", scope),
+                    (Some(_), None) => " (auth)".to_string(),
+                    (None, _) => String::new(),
+                };
                 ("api".cyan(), format!("{}{}", url, auth.dimmed()))
             }
             AliasConfig::Db {
                 database_url,
                 user,
                 storage,
+                read_url,
+                ..
             } => {
                 let user_info = user
                     .as_ref()
                     .map(|u| format!(" @{}", u))
                     .unwrap_or_default();
                 let storage_info = if storage.is_some() { " (storage)" } else { "" };
+                let replica_info = read_url
+                    .as_ref()
+                    .map(|url| format!(" (reads: {})", mask_password(url)))
+                    .unwrap_or_default();
                 (
                     "db".yellow(),
                     format!(
-                        "{}{}{}",
+                        "{}{}{}{}",
                         mask_password(database_url),
                         user_info.cyan(),
-                        storage_info.dimmed()
+                        storage_info.dimmed(),
+                        replica_info.dimmed()
                     ),
                 )
             }
         };
 
+        let org_info = alias
+            .organization()
+            .map(|org| format!(" [org: {}]", org))
+            .unwrap_or_default();
+
         println!(
-            "{} {:12} {:4} {}",
+            "{} {:12} {:4} {}{}",
             marker,
             name.bold(),
             type_str,
-            detail.dimmed()
+            detail.dimmed(),
+            org_info.magenta()
         );
     }
 
+    if !config.groups.is_empty() {
+        println!();
+
+        let mut groups: Vec<_> = config.groups.iter().collect();
+        groups.sort();
+
+        for (name, members) in groups {
+            println!(
+                "  {:12} {:4} {}",
+                name.bold(),
+                "grp".magenta(),
+                members.join(", ").dimmed()
+            );
+        }
+    }
+
+    if !config.mirrors.is_empty() {
+        println!();
+
+        let mut mirrors: Vec<_> = config.mirrors.iter().collect();
+        mirrors.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, mirror) in mirrors {
+            println!(
+                "  {:12} {:4} {}",
+                name.bold(),
+                "mir".magenta(),
+                format!("{} -> {}", mirror.primary, mirror.shadow).dimmed()
+            );
+        }
+    }
+
     if default.is_some() {
         println!();
         println!("{}", "* = default".dimmed());
@@ -190,9 +623,10 @@ fn cmd_remove(name: String) -> Result<(), ConfigError> {
     let mut config = Config::load()?;
 
     config.remove_alias(&name)?;
+    Config::snapshot("alias remove")?;
     config.save()?;
 
-    println!("Removed alias '{}'", name.red().bold());
+    eprintln!("Removed alias '{}'", name.red().bold());
 
     Ok(())
 }
@@ -203,7 +637,34 @@ fn cmd_set_default(name: String) -> Result<(), ConfigError> {
     config.set_default(&name)?;
     config.save()?;
 
-    println!("Default alias set to '{}'", name.green().bold());
+    eprintln!("Default alias set to '{}'", name.green().bold());
+
+    Ok(())
+}
+
+fn cmd_group(name: String, aliases: Vec<String>, remove: bool) -> Result<(), ConfigError> {
+    let mut config = Config::load()?;
+
+    if remove {
+        config.remove_group(&name)?;
+        config.save()?;
+
+        eprintln!("Removed group '{}'", name.red().bold());
+        return Ok(());
+    }
+
+    config.set_group(&name, aliases.clone())?;
+    config.save()?;
+
+    eprintln!(
+        "Group '{}' set to {}",
+        name.green().bold(),
+        aliases.join(", ").bold()
+    );
+    eprintln!(
+        "Run '{}' to use it.",
+        format!("ow {} <command>", name).cyan()
+    );
 
     Ok(())
 }
@@ -232,6 +693,45 @@ fn mask_password(url: &str) -> String {
     url.to_string()
 }
 
+fn cmd_mirror(
+    name: String,
+    primary: Option<String>,
+    shadow: Option<String>,
+    remove: bool,
+) -> Result<(), ConfigError> {
+    let mut config = Config::load()?;
+
+    let (Some(primary), Some(shadow), false) = (primary, shadow, remove) else {
+        config.remove_mirror(&name)?;
+        config.save()?;
+
+        eprintln!("Removed mirror '{}'", name.red().bold());
+        return Ok(());
+    };
+
+    config.set_mirror(
+        &name,
+        Mirror {
+            primary: primary.clone(),
+            shadow: shadow.clone(),
+        },
+    )?;
+    config.save()?;
+
+    eprintln!(
+        "Mirror '{}' set: {} answers, {} shadows",
+        name.green().bold(),
+        primary.bold(),
+        shadow.bold()
+    );
+    eprintln!(
+        "Run '{}' to use it.",
+        format!("ow {} <command>", name).cyan()
+    );
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,4 +749,22 @@ mod tests {
         // No password
         assert_eq!(mask_password("postgres://host/db"), "postgres://host/db");
     }
+
+    #[tokio::test]
+    async fn test_verify_db_rejects_invalid_url() {
+        let checks = verify_db("not a url", Some("admin"), 1).await;
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "url");
+        assert_eq!(checks[0].status, Status::Degraded);
+    }
+
+    #[test]
+    fn test_parse_yes_no() {
+        assert_eq!(parse_yes_no("", false), Some(false));
+        assert_eq!(parse_yes_no("", true), Some(true));
+        assert_eq!(parse_yes_no(" Y ", false), Some(true));
+        assert_eq!(parse_yes_no("no", true), Some(false));
+        assert_eq!(parse_yes_no("maybe", true), None);
+    }
 }
@@ -0,0 +1,316 @@
+use super::session::MAX_RECORDED;
+use super::workers::{INVOKE_BODY_LIMIT, InvokeResponse};
+use crate::backend::BackendError;
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+
+/// Per-request timeout while benchmarking
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct BenchOptions {
+    /// Target request rate; unlimited (as fast as `concurrency` allows) if None
+    pub rps: Option<u32>,
+    pub duration: Duration,
+    pub concurrency: usize,
+    /// Keep the responses (or transport errors) of the first requests
+    pub record: bool,
+}
+
+/// Outcome of one request: latency in ms, or why it failed
+type Outcome = Result<f64, String>;
+
+/// Response of a recorded request, or why none was received
+pub type Recorded = Result<InvokeResponse, String>;
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub requests: usize,
+    pub elapsed: Duration,
+    /// Latencies of successful requests, sorted ascending
+    pub latencies: Vec<f64>,
+    /// Failure reason -> count
+    pub errors: BTreeMap<String, usize>,
+    /// First responses, in completion order (when recording)
+    pub recorded: Vec<Recorded>,
+}
+
+impl BenchReport {
+    pub fn new(outcomes: Vec<Outcome>, elapsed: Duration) -> Self {
+        let requests = outcomes.len();
+        let mut latencies = Vec::with_capacity(requests);
+        let mut errors = BTreeMap::new();
+
+        for outcome in outcomes {
+            match outcome {
+                Ok(ms) => latencies.push(ms),
+                Err(reason) => *errors.entry(reason).or_insert(0) += 1,
+            }
+        }
+
+        latencies.sort_by(f64::total_cmp);
+
+        Self {
+            requests,
+            elapsed,
+            latencies,
+            errors,
+            recorded: Vec::new(),
+        }
+    }
+
+    pub fn throughput(&self) -> f64 {
+        self.requests as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors.values().sum()
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+
+        self.error_count() as f64 / self.requests as f64
+    }
+
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        percentile(&self.latencies, p)
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+async fn send_one(
+    client: &reqwest::Client,
+    url: &str,
+    record: bool,
+) -> (Outcome, Option<Recorded>) {
+    let start = Instant::now();
+
+    let response = match client.get(url).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let reason = if e.is_timeout() {
+                "timeout"
+            } else if e.is_connect() {
+                "connection error"
+            } else {
+                "request error"
+            };
+
+            return (
+                Err(reason.to_string()),
+                record.then(|| Err(reason.to_string())),
+            );
+        }
+    };
+
+    let status = response.status();
+    let headers = record.then(|| {
+        response
+            .headers()
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    String::from_utf8_lossy(v.as_bytes()).to_string(),
+                )
+            })
+            .collect()
+    });
+
+    let Ok(bytes) = response.bytes().await else {
+        let reason = "body error".to_string();
+        return (Err(reason.clone()), record.then_some(Err(reason)));
+    };
+
+    let elapsed = start.elapsed();
+
+    let recorded = headers.map(|headers| {
+        Ok(InvokeResponse {
+            status: status.as_u16(),
+            headers,
+            body: String::from_utf8_lossy(&bytes[..bytes.len().min(INVOKE_BODY_LIMIT)]).to_string(),
+            truncated: bytes.len() > INVOKE_BODY_LIMIT,
+            duration_ms: elapsed.as_millis(),
+        })
+    });
+
+    if status.is_client_error() || status.is_server_error() {
+        return (Err(format!("HTTP {}", status.as_u16())), recorded);
+    }
+
+    (Ok(elapsed.as_secs_f64() * 1000.0), recorded)
+}
+
+/// Generate sustained load against `url` and collect the outcomes
+pub async fn run(url: &str, options: &BenchOptions) -> Result<BenchReport, BackendError> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .pool_max_idle_per_host(options.concurrency)
+        .build()?;
+
+    let mut ticker = options.rps.map(|rps| {
+        let period = Duration::from_secs_f64(1.0 / rps.max(1) as f64);
+        let mut interval = tokio::time::interval(period);
+        // Don't burst to catch up when every slot was busy
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        interval
+    });
+
+    let start = Instant::now();
+    let deadline = start + options.duration;
+
+    // `buffer_unordered` only pulls the next tick when a slot is free,
+    // so `concurrency` caps the requests in flight.
+    let requests = stream::poll_fn(move |cx| {
+        if let Some(ticker) = ticker.as_mut() {
+            std::task::ready!(ticker.poll_tick(cx));
+        }
+
+        std::task::Poll::Ready((Instant::now() < deadline).then_some(()))
+    });
+
+    let mut results = requests
+        .enumerate()
+        .map(|(i, _)| send_one(&client, url, options.record && i < MAX_RECORDED))
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut outcomes = Vec::new();
+    let mut recorded = Vec::new();
+    let mut last_render = Instant::now();
+
+    while let Some((outcome, response)) = results.next().await {
+        outcomes.push(outcome);
+        recorded.extend(response);
+
+        if last_render.elapsed() >= Duration::from_millis(250) {
+            last_render = Instant::now();
+            let errors = outcomes.iter().filter(|o| o.is_err()).count();
+            eprint!(
+                "\r\x1b[2K  {}",
+                format!(
+                    "{:.0}s / {}s | {} requests | {} errors",
+                    start
+                        .elapsed()
+                        .as_secs_f64()
+                        .min(options.duration.as_secs_f64()),
+                    options.duration.as_secs(),
+                    outcomes.len(),
+                    errors
+                )
+                .dimmed()
+            );
+            io::stderr().flush().ok();
+        }
+    }
+
+    eprint!("\r\x1b[2K");
+    io::stderr().flush().ok();
+
+    let mut report = BenchReport::new(outcomes, start.elapsed());
+    report.recorded = recorded;
+
+    Ok(report)
+}
+
+pub fn print_report(report: &BenchReport) {
+    println!("{}", "Results".bold());
+    println!("{}", "─".repeat(60));
+    println!(
+        "  {:12} {} ({:.1}/s over {:.1}s)",
+        "Requests:".dimmed(),
+        report.requests,
+        report.throughput(),
+        report.elapsed.as_secs_f64()
+    );
+
+    if let (Some(p50), Some(p90), Some(p99), Some(max)) = (
+        report.percentile(50.0),
+        report.percentile(90.0),
+        report.percentile(99.0),
+        report.latencies.last(),
+    ) {
+        println!(
+            "  {:12} p50 {:.2} ms / p90 {:.2} ms / p99 {:.2} ms / max {:.2} ms",
+            "Latency:".dimmed(),
+            p50,
+            p90,
+            p99,
+            max
+        );
+    }
+
+    let errors = report.error_count();
+
+    if errors == 0 {
+        println!("  {:12} {}", "Errors:".dimmed(), "0".green());
+        return;
+    }
+
+    let breakdown = report
+        .errors
+        .iter()
+        .map(|(reason, count)| format!("{} ×{}", reason, count))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    println!(
+        "  {:12} {} ({:.1}%) {}",
+        "Errors:".dimmed(),
+        errors.to_string().red(),
+        report.error_rate() * 100.0,
+        breakdown.dimmed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<f64> = (1..=100).map(|n| n as f64).collect();
+
+        assert_eq!(percentile(&sorted, 50.0), Some(50.0));
+        assert_eq!(percentile(&sorted, 99.0), Some(99.0));
+        assert_eq!(percentile(&sorted, 100.0), Some(100.0));
+        assert_eq!(percentile(&sorted, 0.0), Some(1.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let outcomes = vec![
+            Ok(30.0),
+            Ok(10.0),
+            Err("HTTP 502".to_string()),
+            Ok(20.0),
+            Err("HTTP 502".to_string()),
+            Err("timeout".to_string()),
+        ];
+
+        let report = BenchReport::new(outcomes, Duration::from_secs(2));
+
+        assert_eq!(report.requests, 6);
+        assert_eq!(report.latencies, vec![10.0, 20.0, 30.0]);
+        assert_eq!(report.throughput(), 3.0);
+        assert_eq!(report.error_count(), 3);
+        assert_eq!(report.error_rate(), 0.5);
+        assert_eq!(report.errors.get("HTTP 502"), Some(&2));
+        assert_eq!(report.percentile(50.0), Some(20.0));
+    }
+}
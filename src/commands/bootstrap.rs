@@ -0,0 +1,289 @@
+use crate::commands::health::{self, Status};
+use crate::commands::migrate::{self, MigrateError};
+use crate::commands::users::{self, UsersError};
+use crate::config::{AliasConfig, Config, ConfigError, PlatformStorageConfig};
+use crate::timings::{Phase, TimedExt};
+use clap::Args;
+use colored::Colorize;
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BootstrapError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Database error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
+    #[error("{0}")]
+    Users(#[from] UsersError),
+
+    #[error("Incomplete storage settings: {0} required with --storage-endpoint")]
+    IncompleteStorage(String),
+
+    #[error("Bootstrap finished but {0} check(s) are degraded (see above)")]
+    Degraded(usize),
+}
+
+#[derive(Args)]
+pub struct BootstrapArgs {
+    /// PostgreSQL connection URL of the self-hosted install
+    #[arg(long)]
+    pub db: String,
+
+    /// Name of the alias to create
+    #[arg(long, default_value = "local")]
+    pub alias: String,
+
+    /// Admin username (created, or claimed from the system user)
+    #[arg(long, default_value = "admin")]
+    pub user: String,
+
+    /// Prompt for a dashboard password for the admin user
+    #[arg(long)]
+    pub password: bool,
+
+    /// Replace an existing alias with the same name
+    #[arg(long)]
+    pub force: bool,
+
+    /// S3-compatible endpoint for platform storage (optional)
+    #[arg(long)]
+    pub storage_endpoint: Option<String>,
+
+    /// Bucket name
+    #[arg(long, requires = "storage_endpoint")]
+    pub storage_bucket: Option<String>,
+
+    /// Access key ID
+    #[arg(long, requires = "storage_endpoint")]
+    pub storage_access_key_id: Option<String>,
+
+    /// Secret access key
+    #[arg(long, requires = "storage_endpoint")]
+    pub storage_secret_access_key: Option<String>,
+
+    /// Region
+    #[arg(long, default_value = "auto")]
+    pub storage_region: String,
+
+    /// Key prefix for all uploads
+    #[arg(long, requires = "storage_endpoint")]
+    pub storage_prefix: Option<String>,
+
+    /// Timeout in seconds for each verification check
+    #[arg(long, default_value = "5")]
+    pub timeout: u64,
+}
+
+impl BootstrapArgs {
+    pub async fn run(self) -> Result<(), BootstrapError> {
+        let storage = self.storage_config()?;
+
+        step(1, "Connecting to the database");
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&self.db)
+            .timed(Phase::Connect)
+            .await?;
+
+        step(2, "Running migrations");
+        let applied = migrate::run_migrations(&pool).await?;
+
+        match applied.len() {
+            0 => done("schema already up to date"),
+            n => done(&format!("{} migration(s) applied", n)),
+        }
+
+        step(3, &format!("Setting up admin user '{}'", self.user));
+        done(&ensure_user(&pool, &self.user).await?);
+
+        if self.password {
+            let password = users::prompt_password()?;
+            users::set_password_hash(&pool, &self.user, &users::hash_password(&password)).await?;
+            done("password set");
+        }
+
+        step(4, &format!("Saving alias '{}'", self.alias));
+        let mut config = Config::load()?;
+        let is_update = config.aliases.contains_key(&self.alias);
+        let alias_config =
+            AliasConfig::db(self.db.clone(), Some(self.user.clone()), storage.clone());
+
+        config.set_alias(&self.alias, alias_config, self.force)?;
+
+        if is_update {
+            Config::snapshot("bootstrap alias overwrite")?;
+        }
+
+        let made_default = config.default.is_none();
+
+        if made_default {
+            config.set_default(&self.alias)?;
+        }
+
+        config.save()?;
+
+        match (&storage, made_default) {
+            (Some(s), true) => done(&format!(
+                "default alias, storage {}/{}",
+                s.endpoint, s.bucket
+            )),
+            (Some(s), false) => done(&format!("storage {}/{}", s.endpoint, s.bucket)),
+            (None, true) => done("default alias, no platform storage"),
+            (None, false) => done("no platform storage"),
+        }
+
+        step(5, "Verifying");
+        let checks = health::check_db(&self.db, storage.as_ref(), self.timeout).await;
+        health::print_checks(&checks);
+
+        let degraded = checks
+            .iter()
+            .filter(|c| c.status == Status::Degraded)
+            .count();
+
+        if degraded > 0 {
+            return Err(BootstrapError::Degraded(degraded));
+        }
+
+        let prefix = if made_default {
+            String::new()
+        } else {
+            format!("{} ", self.alias)
+        };
+
+        eprintln!();
+        eprintln!("{}", "Self-hosted install ready.".green().bold());
+        eprintln!("  {}", format!("ow {}workers create my-api", prefix).cyan());
+
+        Ok(())
+    }
+
+    /// Platform storage from the --storage-* flags, all or nothing
+    fn storage_config(&self) -> Result<Option<PlatformStorageConfig>, BootstrapError> {
+        let Some(endpoint) = &self.storage_endpoint else {
+            return Ok(None);
+        };
+
+        let missing: Vec<&str> = [
+            ("--storage-bucket", self.storage_bucket.is_none()),
+            (
+                "--storage-access-key-id",
+                self.storage_access_key_id.is_none(),
+            ),
+            (
+                "--storage-secret-access-key",
+                self.storage_secret_access_key.is_none(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(flag, _)| flag)
+        .collect();
+
+        if !missing.is_empty() {
+            return Err(BootstrapError::IncompleteStorage(missing.join(", ")));
+        }
+
+        Ok(Some(PlatformStorageConfig {
+            endpoint: endpoint.clone(),
+            bucket: self.storage_bucket.clone().unwrap_or_default(),
+            access_key_id: self.storage_access_key_id.clone().unwrap_or_default(),
+            secret_access_key: self.storage_secret_access_key.clone().unwrap_or_default(),
+            region: self.storage_region.clone(),
+            prefix: self.storage_prefix.clone(),
+            session_token: None,
+        }))
+    }
+}
+
+/// Make sure `username` exists: keep it, claim the unclaimed system user, or
+/// create it. Returns what was done.
+async fn ensure_user(pool: &PgPool, username: &str) -> Result<String, BootstrapError> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
+        .bind(username)
+        .fetch_one(pool)
+        .await?;
+
+    if exists {
+        return Ok("user already exists".to_string());
+    }
+
+    let unclaimed: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = '00000000-0000-0000-0000-000000000000' AND username = '__system__')",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if unclaimed {
+        users::claim_system_user(pool, username).await?;
+        return Ok("claimed the system user".to_string());
+    }
+
+    users::create_user(pool, username).await?;
+    Ok("user created".to_string())
+}
+
+fn step(number: usize, title: &str) {
+    eprintln!("{} {}", format!("[{}/5]", number).dimmed(), title.bold());
+}
+
+fn done(detail: &str) {
+    eprintln!("      {} {}", "✓".green(), detail);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: BootstrapArgs,
+    }
+
+    #[test]
+    fn test_storage_config() {
+        let cli = Cli::parse_from(["ow", "--db", "postgres://localhost/ow"]);
+        assert!(cli.args.storage_config().unwrap().is_none());
+        assert_eq!(cli.args.alias, "local");
+        assert_eq!(cli.args.user, "admin");
+
+        let cli = Cli::parse_from([
+            "ow",
+            "--db",
+            "postgres://localhost/ow",
+            "--storage-endpoint",
+            "https://s3.example.com",
+            "--storage-bucket",
+            "assets",
+        ]);
+        let result = cli.args.storage_config();
+        assert!(
+            matches!(result, Err(BootstrapError::IncompleteStorage(ref m)) if m == "--storage-access-key-id, --storage-secret-access-key")
+        );
+
+        let cli = Cli::parse_from([
+            "ow",
+            "--db",
+            "postgres://localhost/ow",
+            "--storage-endpoint",
+            "https://s3.example.com",
+            "--storage-bucket",
+            "assets",
+            "--storage-access-key-id",
+            "key",
+            "--storage-secret-access-key",
+            "secret",
+        ]);
+        let storage = cli.args.storage_config().unwrap().unwrap();
+        assert_eq!(storage.bucket, "assets");
+        assert_eq!(storage.region, "auto");
+    }
+}
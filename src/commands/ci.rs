@@ -0,0 +1,492 @@
+use crate::commands::context;
+use crate::config::project::{Manifest, ManifestError};
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+const GITHUB_WORKFLOW: &str = ".github/workflows/ow-deploy.yml";
+const GITLAB_PIPELINE: &str = ".gitlab-ci.yml";
+
+/// Alias the generated workflows configure from their secrets
+const CI_ALIAS: &str = "ci-deploy";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CiError {
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+
+    #[error("Failed to write {0}: {1}")]
+    Write(PathBuf, std::io::Error),
+
+    #[error("{0} already exists (use --force to overwrite)")]
+    Exists(PathBuf),
+
+    #[error("No worker given and none remembered for this directory (pass --worker)")]
+    NoWorker,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CiProvider {
+    Github,
+    Gitlab,
+}
+
+#[derive(Subcommand)]
+pub enum CiCommand {
+    /// Write a CI workflow that builds the project and runs 'ow workers upload'
+    #[command(after_help = "Examples:\n  \
+        ow ci init --worker my-app\n  \
+        ow ci init --worker my-app --dir build --branch production\n  \
+        ow ci init --provider gitlab\n\n\
+        The environment pinned in ow.toml is passed to the upload. The workflow \
+        expects an OW_TOKEN secret (see 'ow tokens create-service').")]
+    Init {
+        #[arg(long, value_enum, default_value = "github")]
+        provider: CiProvider,
+
+        /// Worker to upload to (default: the last one used in this directory)
+        #[arg(long)]
+        worker: Option<String>,
+
+        /// Folder uploaded after the build
+        #[arg(long, default_value = "dist")]
+        dir: String,
+
+        /// Branch whose pushes deploy
+        #[arg(long, default_value = "main")]
+        branch: String,
+
+        /// Overwrite an existing workflow file
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+impl CiCommand {
+    /// `alias` picks the worker remembered for this directory, when none is given
+    pub fn run(self, alias: Option<String>) -> Result<(), CiError> {
+        match self {
+            Self::Init {
+                provider,
+                worker,
+                dir,
+                branch,
+                force,
+            } => {
+                let worker = worker.or_else(|| alias.as_deref().and_then(context::last_worker));
+                cmd_init(provider, worker, dir, branch, force)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+}
+
+impl PackageManager {
+    const ALL: [Self; 3] = [Self::Pnpm, Self::Yarn, Self::Npm];
+
+    /// Detect from the lockfile in `dir`; CI installs need one
+    fn detect(dir: &Path) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|pm| dir.join(pm.lockfile()).exists())
+    }
+
+    fn lockfile(self) -> &'static str {
+        match self {
+            Self::Npm => "package-lock.json",
+            Self::Pnpm => "pnpm-lock.yaml",
+            Self::Yarn => "yarn.lock",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Pnpm => "pnpm",
+            Self::Yarn => "yarn",
+        }
+    }
+
+    fn install(self) -> &'static str {
+        match self {
+            Self::Npm => "npm ci",
+            Self::Pnpm => "pnpm install --frozen-lockfile",
+            Self::Yarn => "yarn install --frozen-lockfile",
+        }
+    }
+}
+
+/// What the generated workflow does
+#[derive(Debug)]
+struct CiPlan {
+    worker: String,
+    dir: String,
+    branch: String,
+    environment: Option<String>,
+    /// Project directory relative to the repository root, if not the root
+    subdir: Option<String>,
+    package_manager: Option<PackageManager>,
+    /// Whether package.json has a build script
+    build: bool,
+}
+
+impl CiPlan {
+    fn upload_command(&self) -> String {
+        let env = self
+            .environment
+            .as_ref()
+            .map(|e| format!(" --env {}", e))
+            .unwrap_or_default();
+
+        format!(
+            "ow {} workers upload {} {}{}",
+            CI_ALIAS, self.worker, self.dir, env
+        )
+    }
+
+    fn alias_command(&self) -> String {
+        format!(
+            "ow alias set {} --api \"$OW_API_URL\" --token \"$OW_TOKEN\" --force",
+            CI_ALIAS
+        )
+    }
+
+    fn render_github(&self) -> String {
+        let mut out = format!(
+            "# Generated by 'ow ci init'. Needs the OW_TOKEN secret, and the\n\
+             # OW_API_URL variable when not deploying to the hosted platform.\n\
+             name: Deploy {worker}\n\
+             \n\
+             on:\n  \
+               push:\n    \
+                 branches: [{branch}]\n  \
+               workflow_dispatch:\n\
+             \n\
+             # Never cancel a deploy halfway, queue the next one instead\n\
+             concurrency:\n  \
+               group: ow-deploy-{worker}\n  \
+               cancel-in-progress: false\n\
+             \n\
+             jobs:\n  \
+               deploy:\n    \
+                 runs-on: ubuntu-latest\n",
+            worker = self.worker,
+            branch = self.branch,
+        );
+
+        if let Some(subdir) = &self.subdir {
+            out.push_str(&format!(
+                "    defaults:\n      run:\n        working-directory: {}\n",
+                subdir
+            ));
+        }
+
+        out.push_str("    steps:\n      - uses: actions/checkout@v4\n");
+
+        if let Some(pm) = self.package_manager {
+            let lockfile = self
+                .subdir
+                .as_ref()
+                .map(|s| format!("\n          cache-dependency-path: {}/{}", s, pm.lockfile()))
+                .unwrap_or_default();
+
+            if pm == PackageManager::Pnpm {
+                out.push_str("\n      - uses: pnpm/action-setup@v4\n");
+            }
+
+            out.push_str(&format!(
+                "\n      - uses: actions/setup-node@v4\n        \
+                   with:\n          \
+                     node-version: 22\n          \
+                     cache: {}{}\n\
+                 \n      - run: {}\n",
+                pm.name(),
+                lockfile,
+                pm.install()
+            ));
+
+            if self.build {
+                out.push_str(&format!("      - run: {} run build\n", pm.name()));
+            }
+        }
+
+        out.push_str(&format!(
+            "\n      - name: Cache ow\n        \
+               id: cache-ow\n        \
+               uses: actions/cache@v4\n        \
+               with:\n          \
+                 path: ~/.local/bin/ow\n          \
+                 key: ow-${{{{ runner.os }}}}-{version}\n\
+             \n      - name: Install ow\n        \
+               if: steps.cache-ow.outputs.cache-hit != 'true'\n        \
+               run: |\n          \
+                 mkdir -p ~/.local/bin\n          \
+                 curl -sSL {url} \\\n            \
+                   | tar xz -C ~/.local/bin\n\
+             \n      - name: Deploy\n        \
+               env:\n          \
+                 OW_TOKEN: ${{{{ secrets.OW_TOKEN }}}}\n          \
+                 OW_API_URL: ${{{{ vars.OW_API_URL || 'https://dash.openworkers.com' }}}}\n        \
+               run: |\n          \
+                 export PATH=\"$HOME/.local/bin:$PATH\"\n          \
+                 {alias}\n          \
+                 {upload}\n",
+            version = env!("CARGO_PKG_VERSION"),
+            url = release_url(),
+            alias = self.alias_command(),
+            upload = self.upload_command(),
+        ));
+
+        out
+    }
+
+    fn render_gitlab(&self) -> String {
+        let image = if self.package_manager.is_some() {
+            "node:22"
+        } else {
+            "debian:stable-slim"
+        };
+
+        let mut script = Vec::new();
+
+        if let Some(subdir) = &self.subdir {
+            script.push(format!("cd {}", subdir));
+        }
+
+        if let Some(pm) = self.package_manager {
+            // pnpm and yarn come with Node through corepack
+            if pm != PackageManager::Npm {
+                script.push("corepack enable".to_string());
+            }
+
+            script.push(pm.install().to_string());
+
+            if self.build {
+                script.push(format!("{} run build", pm.name()));
+            }
+        }
+
+        script.push("export PATH=\"$CI_PROJECT_DIR/.ow:$PATH\"".to_string());
+        script.push(self.alias_command());
+        script.push(self.upload_command());
+
+        let script: String = script
+            .iter()
+            .map(|line| format!("    - {}\n", line))
+            .collect();
+
+        let cache_key = self
+            .package_manager
+            .map(|_| "\n    - node_modules/")
+            .unwrap_or_default();
+
+        format!(
+            "# Generated by 'ow ci init'. Needs the OW_TOKEN CI/CD variable (masked), and\n\
+             # OW_API_URL when not deploying to the hosted platform.\n\
+             variables:\n  \
+               OW_API_URL: https://dash.openworkers.com\n\
+             \n\
+             deploy:\n  \
+               stage: deploy\n  \
+               image: {image}\n  \
+               rules:\n    \
+                 - if: $CI_COMMIT_BRANCH == \"{branch}\"\n  \
+               # One deploy at a time\n  \
+               resource_group: ow-deploy-{worker}\n  \
+               cache:\n    \
+                 key: ow-{version}\n    \
+                 paths:\n    \
+                 - .ow/{cache_key}\n  \
+               before_script:\n    \
+                 - command -v curl || (apt-get update && apt-get install -y curl)\n    \
+                 - mkdir -p .ow\n    \
+                 - test -x .ow/ow || curl -sSL {url} | tar xz -C .ow\n  \
+               script:\n\
+             {script}",
+            image = image,
+            branch = self.branch,
+            worker = self.worker,
+            version = env!("CARGO_PKG_VERSION"),
+            cache_key = cache_key,
+            url = release_url(),
+            script = script,
+        )
+    }
+}
+
+/// Linux build of this version, so CI runs the CLI that wrote the workflow
+fn release_url() -> String {
+    format!(
+        "https://github.com/openworkers/openworkers-cli/releases/download/v{}/ow-linux-x86_64.tar.gz",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// Whether package.json in `dir` has a `build` script
+fn has_build_script(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"]["build"].is_string())
+}
+
+fn cmd_init(
+    provider: CiProvider,
+    worker: Option<String>,
+    dir: String,
+    branch: String,
+    force: bool,
+) -> Result<(), CiError> {
+    let worker = worker.ok_or(CiError::NoWorker)?;
+
+    let cwd = std::env::current_dir().map_err(|e| CiError::Write(PathBuf::from("."), e))?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let root = context::project_dir().unwrap_or_else(|| cwd.clone());
+
+    let subdir = cwd
+        .strip_prefix(&root)
+        .ok()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .filter(|p| !p.is_empty());
+
+    let plan = CiPlan {
+        worker,
+        dir,
+        branch,
+        environment: Manifest::find()?.and_then(|m| m.environment),
+        subdir,
+        package_manager: PackageManager::detect(&cwd),
+        build: has_build_script(&cwd),
+    };
+
+    let (file, content) = match provider {
+        CiProvider::Github => (root.join(GITHUB_WORKFLOW), plan.render_github()),
+        CiProvider::Gitlab => (root.join(GITLAB_PIPELINE), plan.render_gitlab()),
+    };
+
+    if file.exists() && !force {
+        return Err(CiError::Exists(file));
+    }
+
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CiError::Write(file.clone(), e))?;
+    }
+
+    std::fs::write(&file, content).map_err(|e| CiError::Write(file.clone(), e))?;
+
+    eprintln!(
+        "{} {}",
+        "Created".green(),
+        file.strip_prefix(&root).unwrap_or(&file).display()
+    );
+    eprintln!();
+    eprintln!(
+        "Deploys '{}' on pushes to '{}'.",
+        plan.worker.bold(),
+        plan.branch
+    );
+
+    if plan.package_manager.is_none() && cwd.join("package.json").exists() {
+        eprintln!(
+            "{} No lockfile found: add the install and build steps yourself.",
+            "Note:".yellow()
+        );
+    }
+
+    eprintln!("Next, create a token for the workflow and store it as OW_TOKEN:");
+    eprintln!(
+        "  {}",
+        "ow tokens create-service github-actions --scope deploy".cyan()
+    );
+
+    match provider {
+        CiProvider::Github => eprintln!("  {}", "gh secret set OW_TOKEN".cyan()),
+        CiProvider::Gitlab => {
+            eprintln!("  Settings > CI/CD > Variables, masked and protected")
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> CiPlan {
+        CiPlan {
+            worker: "my-app".to_string(),
+            dir: "dist".to_string(),
+            branch: "main".to_string(),
+            environment: Some("prod".to_string()),
+            subdir: None,
+            package_manager: Some(PackageManager::Pnpm),
+            build: true,
+        }
+    }
+
+    #[test]
+    fn test_render_github() {
+        let workflow = plan().render_github();
+
+        assert!(workflow.contains("branches: [main]"));
+        assert!(workflow.contains("uses: pnpm/action-setup@v4"));
+        assert!(workflow.contains("cache: pnpm"));
+        assert!(workflow.contains("- run: pnpm run build"));
+        assert!(workflow.contains("OW_TOKEN: ${{ secrets.OW_TOKEN }}"));
+        assert!(workflow.contains("ow ci-deploy workers upload my-app dist --env prod"));
+        assert!(!workflow.contains("working-directory"));
+    }
+
+    #[test]
+    fn test_render_gitlab_without_node() {
+        let plan = CiPlan {
+            package_manager: None,
+            build: false,
+            environment: None,
+            subdir: Some("apps/site".to_string()),
+            ..plan()
+        };
+
+        let pipeline = plan.render_gitlab();
+
+        assert!(pipeline.contains("image: debian:stable-slim"));
+        assert!(pipeline.contains("    - cd apps/site\n"));
+        assert!(pipeline.contains("    - ow ci-deploy workers upload my-app dist\n"));
+        assert!(!pipeline.contains("run build"));
+    }
+
+    #[test]
+    fn test_detect_package_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(PackageManager::detect(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"build": "vite build"}}"#,
+        )
+        .unwrap();
+        assert!(has_build_script(dir.path()));
+
+        // No lockfile, no reproducible install
+        assert_eq!(PackageManager::detect(dir.path()), None);
+
+        std::fs::write(dir.path().join("package-lock.json"), "{}").unwrap();
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Npm)
+        );
+
+        std::fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Yarn)
+        );
+    }
+}
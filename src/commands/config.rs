@@ -0,0 +1,176 @@
+use crate::config::{Config, ConfigError, ConfigSnapshot};
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Save a snapshot of the CLI config (aliases, tokens, defaults)
+    #[command(after_help = "Examples:\n  \
+        ow config backup                    Save to ~/.openworkers/backups/\n  \
+        ow config backup --out ow.json      Save to a file")]
+    Backup {
+        /// Write the snapshot here instead of the backups directory
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// List saved snapshots, newest last
+    #[command(alias = "ls")]
+    List,
+
+    /// Replace the CLI config with a snapshot (the current config is backed up first)
+    #[command(after_help = "Examples:\n  \
+        ow config restore ow.json\n  \
+        ow config restore config-20240102T030405.000Z-alias-remove.json")]
+    Restore {
+        /// Snapshot file, or the name of one in ~/.openworkers/backups/
+        file: PathBuf,
+    },
+
+    /// Change a CLI setting
+    #[command(after_help = "Examples:\n  \
+        ow config set telemetry on          Record command durations locally (ow metrics self)\n  \
+        ow config set telemetry off")]
+    Set { key: Setting, value: Toggle },
+
+    /// Show a CLI setting
+    Get { key: Setting },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Setting {
+    /// Local, opt-in command metrics
+    Telemetry,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+impl ConfigCommand {
+    pub fn run(self) -> Result<(), ConfigError> {
+        match self {
+            Self::Backup { out } => cmd_backup(out),
+            Self::List => cmd_list(),
+            Self::Restore { file } => cmd_restore(&file),
+            Self::Set { key, value } => cmd_set(key, value),
+            Self::Get { key } => cmd_get(key),
+        }
+    }
+}
+
+fn cmd_backup(out: Option<PathBuf>) -> Result<(), ConfigError> {
+    let path = match out {
+        Some(out) => {
+            ConfigSnapshot::new(Config::load()?, "manual").write_to(&out)?;
+            Some(out)
+        }
+        None => Config::snapshot("manual")?,
+    };
+
+    match path {
+        Some(path) => eprintln!("{} Config saved to {}", "Backed up".green(), path.display()),
+        None => eprintln!("No config file yet, nothing to back up."),
+    }
+
+    Ok(())
+}
+
+fn cmd_list() -> Result<(), ConfigError> {
+    let backups = Config::list_backups()?;
+
+    if backups.is_empty() {
+        eprintln!("No config snapshots found.");
+        return Ok(());
+    }
+
+    for path in backups {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        let detail = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| ConfigSnapshot::parse(&content).ok())
+            .map(|s| {
+                format!(
+                    "{} alias(es), {}",
+                    s.config.aliases.len(),
+                    s.created_at.format("%Y-%m-%d %H:%M:%S")
+                )
+            })
+            .unwrap_or_else(|| "unreadable".to_string());
+
+        println!("  {}  {}", name, detail.dimmed());
+    }
+
+    Ok(())
+}
+
+fn cmd_restore(file: &Path) -> Result<(), ConfigError> {
+    let path = if file.exists() {
+        file.to_path_buf()
+    } else {
+        Config::backups_dir()?.join(file)
+    };
+
+    let content = std::fs::read_to_string(&path)?;
+    let snapshot = ConfigSnapshot::parse(&content)?;
+
+    if let Some(previous) = Config::snapshot("restore")? {
+        eprintln!(
+            "{}",
+            format!("Previous config saved to {}", previous.display()).dimmed()
+        );
+    }
+
+    snapshot.config.save()?;
+
+    eprintln!(
+        "{} Config from {} ({} alias(es))",
+        "Restored".green(),
+        snapshot.created_at.format("%Y-%m-%d %H:%M:%S"),
+        snapshot.config.aliases.len()
+    );
+
+    Ok(())
+}
+
+fn cmd_set(key: Setting, value: Toggle) -> Result<(), ConfigError> {
+    let mut config = Config::load()?;
+    let on = matches!(value, Toggle::On);
+
+    match key {
+        Setting::Telemetry => config.telemetry = on,
+    }
+
+    config.save()?;
+
+    if on {
+        eprintln!(
+            "{} telemetry. Command durations are kept in ~/.openworkers/metrics.jsonl, see {}",
+            "Enabled".green(),
+            "ow metrics self".cyan()
+        );
+    } else {
+        eprintln!(
+            "{} telemetry. Recorded samples are kept until {}",
+            "Disabled".yellow(),
+            "ow metrics self --clear".cyan()
+        );
+    }
+
+    Ok(())
+}
+
+fn cmd_get(key: Setting) -> Result<(), ConfigError> {
+    let config = Config::load()?;
+
+    let on = match key {
+        Setting::Telemetry => config.telemetry,
+    };
+
+    println!("{}", if on { "on" } else { "off" });
+    Ok(())
+}
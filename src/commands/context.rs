@@ -0,0 +1,235 @@
+use crate::config::{Config, ConfigError};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const CONTEXT_FILE: &str = "context.json";
+
+#[derive(Subcommand)]
+pub enum ContextCommand {
+    /// Show the remembered worker for the current directory and alias
+    Show,
+
+    /// Forget the remembered worker for the current directory and alias
+    Clear,
+}
+
+impl ContextCommand {
+    /// `alias` is the one given on the command line, else the default
+    pub fn run(self, alias: Option<String>) -> Result<(), ConfigError> {
+        let alias = alias.ok_or(ConfigError::NoDefaultAlias)?;
+
+        match self {
+            Self::Show => cmd_show(&alias),
+            Self::Clear => cmd_clear(&alias),
+        }
+    }
+}
+
+/// Last-used worker per alias and project directory, stored in
+/// ~/.openworkers/context.json. The same directory can target workers of the
+/// same name on different aliases, so each alias remembers its own.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Context {
+    /// Alias name, then project directory
+    #[serde(default)]
+    pub aliases: HashMap<String, HashMap<String, DirectoryContext>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryContext {
+    pub worker: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Context {
+    pub fn path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join(CONTEXT_FILE))
+    }
+
+    pub fn load() -> Result<Self, ConfigError> {
+        let path = Self::path()?;
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let dir = Config::config_dir()?;
+
+        if !dir.exists() {
+            std::fs::create_dir_all(&dir)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(Self::path()?, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, alias: &str, dir: &Path) -> Option<&DirectoryContext> {
+        self.aliases
+            .get(alias)?
+            .get(&dir.to_string_lossy().to_string())
+    }
+
+    pub fn set_worker(&mut self, alias: &str, dir: &Path, worker: &str) {
+        self.aliases.entry(alias.to_string()).or_default().insert(
+            dir.to_string_lossy().to_string(),
+            DirectoryContext {
+                worker: worker.to_string(),
+                updated_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn clear(&mut self, alias: &str, dir: &Path) -> Option<DirectoryContext> {
+        let directories = self.aliases.get_mut(alias)?;
+        let removed = directories.remove(&dir.to_string_lossy().to_string());
+
+        if directories.is_empty() {
+            self.aliases.remove(alias);
+        }
+
+        removed
+    }
+}
+
+/// Project directory for the current working directory: the nearest ancestor
+/// containing `.git`, or the working directory itself.
+pub fn project_dir() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+
+    let root = cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf);
+
+    Some(root.unwrap_or(cwd))
+}
+
+/// Worker last targeted on `alias` from the current project directory
+pub fn last_worker(alias: &str) -> Option<String> {
+    let dir = project_dir()?;
+    let context = Context::load().ok()?;
+    context.get(alias, &dir).map(|c| c.worker.clone())
+}
+
+/// Remember `worker` for `alias` and the current project directory (best effort)
+pub fn remember_worker(alias: &str, worker: &str) {
+    let Some(dir) = project_dir() else {
+        return;
+    };
+
+    let mut context = Context::load().unwrap_or_default();
+
+    if context.get(alias, &dir).is_some_and(|c| c.worker == worker) {
+        return;
+    }
+
+    context.set_worker(alias, &dir, worker);
+    context.save().ok();
+}
+
+fn cmd_show(alias: &str) -> Result<(), ConfigError> {
+    let dir = project_dir().ok_or(ConfigError::HomeDirNotFound)?;
+    let context = Context::load()?;
+
+    println!("{:12} {}", "Directory:".dimmed(), dir.display());
+    println!("{:12} {}", "Alias:".dimmed(), alias);
+
+    match context.get(alias, &dir) {
+        Some(c) => {
+            println!("{:12} {}", "Worker:".dimmed(), c.worker.bold());
+            println!(
+                "{:12} {}",
+                "Updated:".dimmed(),
+                c.updated_at.format("%Y-%m-%d %H:%M:%S")
+            );
+        }
+        None => println!("{:12} {}", "Worker:".dimmed(), "none".dimmed()),
+    }
+
+    Ok(())
+}
+
+fn cmd_clear(alias: &str) -> Result<(), ConfigError> {
+    let dir = project_dir().ok_or(ConfigError::HomeDirNotFound)?;
+    let mut context = Context::load()?;
+
+    match context.clear(alias, &dir) {
+        Some(c) => {
+            context.save()?;
+            eprintln!(
+                "{} Forgot worker '{}' for {} on '{}'.",
+                "Cleared".green(),
+                c.worker.bold(),
+                dir.display(),
+                alias
+            );
+        }
+        None => eprintln!("No worker remembered for {} on '{}'.", dir.display(), alias),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_clear_worker() {
+        let mut context = Context::default();
+        let dir = Path::new("/home/me/project");
+
+        assert!(context.get("prod", dir).is_none());
+
+        context.set_worker("prod", dir, "my-api");
+        assert_eq!(context.get("prod", dir).unwrap().worker, "my-api");
+
+        context.set_worker("prod", dir, "my-web");
+        assert_eq!(context.get("prod", dir).unwrap().worker, "my-web");
+        assert!(context.get("prod", Path::new("/home/me/other")).is_none());
+
+        assert!(context.clear("prod", dir).is_some());
+        assert!(context.get("prod", dir).is_none());
+        assert!(context.aliases.is_empty());
+    }
+
+    #[test]
+    fn test_worker_is_remembered_per_alias() {
+        let mut context = Context::default();
+        let dir = Path::new("/home/me/project");
+
+        context.set_worker("staging", dir, "api-staging");
+        assert!(context.get("prod", dir).is_none());
+
+        context.set_worker("prod", dir, "api");
+        assert_eq!(context.get("staging", dir).unwrap().worker, "api-staging");
+
+        assert!(context.clear("prod", dir).is_some());
+        assert_eq!(context.get("staging", dir).unwrap().worker, "api-staging");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut context = Context::default();
+        context.set_worker("prod", Path::new("/srv/app"), "app");
+
+        let json = serde_json::to_string(&context).unwrap();
+        let parsed: Context = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            parsed.get("prod", Path::new("/srv/app")).unwrap().worker,
+            "app"
+        );
+    }
+}
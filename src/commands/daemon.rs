@@ -0,0 +1,409 @@
+//! `ow daemon`: keep warm HTTPS connections to the API between invocations.
+//!
+//! The daemon listens on ~/.openworkers/daemon.sock. When the socket is live,
+//! API aliases send plain HTTP over it and the daemon forwards each request
+//! with a pooled keep-alive client, so TLS handshakes are paid once.
+//!
+//! Only API aliases benefit. DB aliases keep connecting to Postgres from each
+//! process: a pooled session can't be handed to another process, and proxying
+//! the wire protocol would put the daemon in front of every query.
+
+use crate::config::{Config, ConfigError};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const SOCKET_FILE: &str = "daemon.sock";
+
+/// Set to bypass a running daemon
+const NO_DAEMON_ENV: &str = "OW_NO_DAEMON";
+
+/// Real API origin of a proxied request
+const UPSTREAM_HEADER: &str = "x-ow-upstream";
+
+/// Accept invalid certificates upstream (alias `insecure`)
+const INSECURE_HEADER: &str = "x-ow-insecure";
+
+/// Daemon status (GET) and shutdown (DELETE)
+const CONTROL_PATH: &str = "/__ow/daemon";
+
+#[derive(Debug, thiserror::Error)]
+pub enum DaemonError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("A daemon is already listening on {0}")]
+    AlreadyRunning(String),
+
+    #[error("No daemon is running")]
+    NotRunning,
+
+    #[cfg(not(unix))]
+    #[error("ow daemon requires Unix domain sockets")]
+    Unsupported,
+}
+
+#[derive(Subcommand)]
+pub enum DaemonCommand {
+    /// Run the daemon in the foreground
+    #[command(after_help = "Examples:\n  \
+        ow daemon start &                   Keep connections warm in the background\n  \
+        ow daemon start --idle-timeout 0    Never exit on inactivity")]
+    Start {
+        /// Exit after this many idle minutes (0 = never)
+        #[arg(long, default_value = "30")]
+        idle_timeout: u64,
+    },
+
+    /// Show whether a daemon is running and what it serves
+    Status,
+
+    /// Stop the running daemon
+    Stop,
+}
+
+impl DaemonCommand {
+    pub async fn run(self) -> Result<(), DaemonError> {
+        match self {
+            Self::Start { idle_timeout } => cmd_start(idle_timeout).await,
+            Self::Status => cmd_status().await,
+            Self::Stop => cmd_stop().await,
+        }
+    }
+}
+
+/// Daemon state reported by `ow daemon status`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DaemonStatus {
+    pid: u32,
+    started_at: chrono::DateTime<chrono::Utc>,
+    requests: u64,
+    upstreams: Vec<String>,
+}
+
+pub fn socket_path() -> Result<PathBuf, ConfigError> {
+    Ok(Config::config_dir()?.join(SOCKET_FILE))
+}
+
+/// `http://` URL sent through the daemon for an API URL
+fn proxied_url(base_url: &str) -> Option<String> {
+    let (scheme, rest) = base_url.split_once("://")?;
+
+    match scheme {
+        "http" | "https" => Some(format!("http://{}", rest)),
+        _ => None,
+    }
+}
+
+/// Scheme, host and port of an API URL
+fn upstream_origin(base_url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(base_url).ok()?;
+    Some(url.origin().ascii_serialization())
+}
+
+/// Client and base URL routing API requests through a live daemon, if any
+#[cfg(unix)]
+pub fn proxy_client(base_url: &str, insecure: bool) -> Option<(reqwest::Client, String)> {
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    if std::env::var_os(NO_DAEMON_ENV).is_some() {
+        return None;
+    }
+
+    let socket = socket_path().ok()?;
+
+    // A leftover socket from a daemon that died is not worth a failed request
+    std::os::unix::net::UnixStream::connect(&socket).ok()?;
+
+    let mut headers = HeaderMap::new();
+    let origin = HeaderValue::from_str(&upstream_origin(base_url)?).ok()?;
+    headers.insert(UPSTREAM_HEADER, origin);
+
+    if insecure {
+        headers.insert(INSECURE_HEADER, HeaderValue::from_static("1"));
+    }
+
+    let client = reqwest::Client::builder()
+        .unix_socket(socket)
+        .default_headers(headers)
+        .build()
+        .ok()?;
+
+    Some((client, proxied_url(base_url)?))
+}
+
+#[cfg(unix)]
+fn control_client() -> Result<reqwest::Client, DaemonError> {
+    let socket = socket_path()?;
+
+    if std::os::unix::net::UnixStream::connect(&socket).is_err() {
+        return Err(DaemonError::NotRunning);
+    }
+
+    Ok(reqwest::Client::builder().unix_socket(socket).build()?)
+}
+
+#[cfg(unix)]
+async fn cmd_status() -> Result<(), DaemonError> {
+    let client = control_client()?;
+    let status: DaemonStatus = client
+        .get(format!("http://daemon{}", CONTROL_PATH))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    println!(
+        "{} Daemon running (pid {}) on {}",
+        "✓".green(),
+        status.pid,
+        socket_path()?.display().to_string().cyan()
+    );
+    println!(
+        "  {} {}",
+        "Started:".dimmed(),
+        status.started_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    println!("  {} {}", "Requests:".dimmed(), status.requests);
+
+    for upstream in &status.upstreams {
+        println!("  {} {}", "Upstream:".dimmed(), upstream);
+    }
+
+    eprintln!(
+        "{}",
+        "API aliases only: DB aliases connect to Postgres directly.".dimmed()
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn cmd_stop() -> Result<(), DaemonError> {
+    let client = control_client()?;
+    client
+        .delete(format!("http://daemon{}", CONTROL_PATH))
+        .send()
+        .await?;
+
+    eprintln!("{} Daemon stopped.", "Stopped".red());
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn cmd_start(idle_timeout: u64) -> Result<(), DaemonError> {
+    server::serve(std::time::Duration::from_secs(idle_timeout * 60)).await
+}
+
+#[cfg(not(unix))]
+async fn cmd_start(_idle_timeout: u64) -> Result<(), DaemonError> {
+    Err(DaemonError::Unsupported)
+}
+
+#[cfg(not(unix))]
+async fn cmd_status() -> Result<(), DaemonError> {
+    Err(DaemonError::Unsupported)
+}
+
+#[cfg(not(unix))]
+async fn cmd_stop() -> Result<(), DaemonError> {
+    Err(DaemonError::Unsupported)
+}
+
+#[cfg(unix)]
+mod server {
+    use super::*;
+    use crate::backend::api::ApiBackend;
+    use crate::http1::{self, Request, Response, read_request, write_response};
+    use futures::StreamExt;
+    use futures::channel::mpsc;
+    use std::collections::HashMap;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+    use tokio::io::BufReader;
+    use tokio::net::{UnixListener, UnixStream};
+
+    struct State {
+        started_at: chrono::DateTime<chrono::Utc>,
+        requests: AtomicU64,
+        /// One pooled client per (origin, insecure)
+        clients: Mutex<HashMap<(String, bool), reqwest::Client>>,
+        stop: mpsc::UnboundedSender<()>,
+    }
+
+    impl State {
+        fn client(&self, origin: &str, insecure: bool) -> reqwest::Client {
+            let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+
+            clients
+                .entry((origin.to_string(), insecure))
+                .or_insert_with(|| ApiBackend::http_client(origin, insecure))
+                .clone()
+        }
+
+        fn status(&self) -> DaemonStatus {
+            let clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+            let mut upstreams: Vec<String> = clients.keys().map(|(o, _)| o.clone()).collect();
+            upstreams.sort();
+            upstreams.dedup();
+
+            DaemonStatus {
+                pid: std::process::id(),
+                started_at: self.started_at,
+                requests: self.requests.load(Ordering::Relaxed),
+                upstreams,
+            }
+        }
+    }
+
+    pub async fn serve(idle_timeout: Duration) -> Result<(), DaemonError> {
+        let socket = socket_path()?;
+
+        if std::os::unix::net::UnixStream::connect(&socket).is_ok() {
+            return Err(DaemonError::AlreadyRunning(socket.display().to_string()));
+        }
+
+        if let Some(dir) = socket.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        // Stale socket from a daemon that did not shut down cleanly
+        std::fs::remove_file(&socket).ok();
+
+        let listener = UnixListener::bind(&socket)?;
+        std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+
+        let (stop, mut stopped) = mpsc::unbounded();
+        let state = Arc::new(State {
+            started_at: chrono::Utc::now(),
+            requests: AtomicU64::new(0),
+            clients: Mutex::new(HashMap::new()),
+            stop,
+        });
+
+        eprintln!(
+            "{} Daemon listening on {}",
+            "✓".green(),
+            socket.display().to_string().cyan()
+        );
+
+        loop {
+            let idle = async {
+                if idle_timeout.is_zero() {
+                    std::future::pending::<()>().await;
+                }
+                tokio::time::sleep(idle_timeout).await;
+            };
+
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let state = state.clone();
+
+                    tokio::spawn(async move {
+                        handle_connection(stream, state).await.ok();
+                    });
+                }
+                _ = stopped.next() => break,
+                _ = idle => {
+                    eprintln!("{} Idle for {} minutes, exiting.", "→".blue(), idle_timeout.as_secs() / 60);
+                    break;
+                }
+            }
+        }
+
+        std::fs::remove_file(&socket).ok();
+        Ok(())
+    }
+
+    /// Serve keep-alive HTTP/1.1 requests on one client connection
+    async fn handle_connection(stream: UnixStream, state: Arc<State>) -> std::io::Result<()> {
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+
+        loop {
+            let request = match read_request(&mut reader).await {
+                Ok(Some(request)) => request,
+                Ok(None) => break,
+                Err(e) => return http1::reject(&mut write, e).await,
+            };
+
+            let response = if request.path == CONTROL_PATH {
+                control(&state, &request)
+            } else {
+                state.requests.fetch_add(1, Ordering::Relaxed);
+                forward(&state, request).await
+            };
+
+            write_response(&mut write, &response).await?;
+        }
+
+        Ok(())
+    }
+
+    fn control(state: &State, request: &Request) -> Response {
+        match request.method.as_str() {
+            "DELETE" => {
+                state.stop.unbounded_send(()).ok();
+                Response::error(200, "stopping".to_string())
+            }
+            _ => Response {
+                status: 200,
+                headers: vec![("content-type".to_string(), "application/json".to_string())],
+                body: serde_json::to_vec(&state.status()).unwrap_or_default(),
+            },
+        }
+    }
+
+    async fn forward(state: &State, mut request: Request) -> Response {
+        let Some(origin) = request.header(UPSTREAM_HEADER).map(str::to_string) else {
+            return Response::error(400, format!("Missing {} header", UPSTREAM_HEADER));
+        };
+
+        let insecure = request.header(INSECURE_HEADER).is_some();
+        let client = state.client(&origin, insecure);
+        let url = format!("{}{}", origin, request.path);
+
+        // Daemon instructions, not for the API
+        request.headers.retain(|(name, _)| {
+            ![UPSTREAM_HEADER, INSECURE_HEADER]
+                .iter()
+                .any(|h| name.eq_ignore_ascii_case(h))
+        });
+
+        http1::forward(&client, &url, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxied_url() {
+        assert_eq!(
+            proxied_url("https://dash.openworkers.com/api/v1"),
+            Some("http://dash.openworkers.com/api/v1".to_string())
+        );
+        assert_eq!(
+            upstream_origin("https://dash.openworkers.com/api/v1"),
+            Some("https://dash.openworkers.com".to_string())
+        );
+        assert_eq!(
+            upstream_origin("http://api.localhost:8080/api/v1"),
+            Some("http://api.localhost:8080".to_string())
+        );
+        assert_eq!(proxied_url("dash.openworkers.com"), None);
+    }
+}
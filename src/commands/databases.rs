@@ -1,4 +1,7 @@
-use crate::backend::{Backend, BackendError, CreateDatabaseInput, DatabaseProvider};
+use crate::backend::{
+    Backend, BackendError, CreateDatabaseInput, DatabaseProvider, UpdateDatabaseInput,
+};
+use crate::quiet;
 use clap::Subcommand;
 use colored::Colorize;
 
@@ -6,13 +9,21 @@ use colored::Colorize;
 pub enum DatabasesCommand {
     /// List all database configurations
     #[command(alias = "ls")]
-    List,
+    List {
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
 
     /// Show database configuration details
     #[command(after_help = "Example:\n  ow databases get my-db")]
     Get {
         /// Database name
         name: String,
+
+        /// Only print the name (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
     },
 
     /// Create a database configuration for SQL access from workers
@@ -46,19 +57,49 @@ pub enum DatabasesCommand {
         timeout: Option<i32>,
     },
 
+    /// Update a database configuration's details
+    #[command(after_help = "Examples:\n  \
+        ow databases update main-db -d \"Primary database\"\n  \
+        ow databases update main-db -d \"\"   (clear)")]
+    Update {
+        /// Database name
+        name: String,
+
+        /// New description (empty to clear)
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Rename a database configuration (bindings keep working)
+    #[command(
+        alias = "mv",
+        after_help = "Example:\n  ow databases rename db main-db"
+    )]
+    Rename {
+        /// Current name
+        name: String,
+
+        /// New name
+        new_name: String,
+    },
+
     /// Delete a database configuration
     #[command(alias = "rm", after_help = "Example:\n  ow databases delete old-db")]
     Delete {
         /// Database name to delete
         name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
     },
 }
 
 impl DatabasesCommand {
     pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
         match self {
-            Self::List => cmd_list(backend).await,
-            Self::Get { name } => cmd_get(backend, &name).await,
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::Get { name, ids } => cmd_get(backend, &name, ids).await,
             Self::Create {
                 name,
                 provider,
@@ -78,16 +119,25 @@ impl DatabasesCommand {
                 )
                 .await
             }
-            Self::Delete { name } => cmd_delete(backend, &name).await,
+            Self::Update { name, description } => cmd_update(backend, &name, description).await,
+            Self::Rename { name, new_name } => cmd_rename(backend, &name, new_name).await,
+            Self::Delete { name, yes } => cmd_delete(backend, &name, yes).await,
         }
     }
 }
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
     let databases = backend.list_databases().await?;
 
+    if ids {
+        for database in &databases {
+            quiet::print(&database.name);
+        }
+        return Ok(());
+    }
+
     if databases.is_empty() {
-        println!("No databases found.");
+        eprintln!("No databases found.");
         return Ok(());
     }
 
@@ -106,9 +156,14 @@ async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
     Ok(())
 }
 
-async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_get<B: Backend>(backend: &B, name: &str, ids: bool) -> Result<(), BackendError> {
     let db = backend.get_database(name).await?;
 
+    if ids {
+        quiet::print(&db.name);
+        return Ok(());
+    }
+
     println!("{:12} {}", "Name:".dimmed(), db.name.bold());
     println!("{:12} {}", "ID:".dimmed(), db.id);
     println!("{:12} {}", "Provider:".dimmed(), db.provider);
@@ -155,7 +210,7 @@ async fn cmd_create<B: Backend>(
 
     let db = backend.create_database(input).await?;
 
-    println!(
+    eprintln!(
         "{} Database '{}' created ({} provider).",
         "Created".green(),
         db.name.bold(),
@@ -165,10 +220,57 @@ async fn cmd_create<B: Backend>(
     Ok(())
 }
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_update<B: Backend>(
+    backend: &B,
+    name: &str,
+    description: Option<String>,
+) -> Result<(), BackendError> {
+    if description.is_none() {
+        return Err(BackendError::Usage(
+            "Nothing to update. Pass --description.".to_string(),
+        ));
+    }
+
+    let input = UpdateDatabaseInput {
+        desc: description,
+        ..Default::default()
+    };
+
+    backend.update_database(name, input).await?;
+
+    eprintln!("{} Database '{}' updated.", "Updated".green(), name.bold());
+
+    Ok(())
+}
+
+async fn cmd_rename<B: Backend>(
+    backend: &B,
+    name: &str,
+    new_name: String,
+) -> Result<(), BackendError> {
+    let input = UpdateDatabaseInput {
+        name: Some(new_name),
+        ..Default::default()
+    };
+
+    let updated = backend.update_database(name, input).await?;
+
+    eprintln!(
+        "{} Database '{}' renamed to '{}'.",
+        "Renamed".green(),
+        name,
+        updated.name.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("database '{}'", name), yes)?;
+
     backend.delete_database(name).await?;
 
-    println!("{} Database '{}' deleted.", "Deleted".red(), name.bold());
+    eprintln!("{} Database '{}' deleted.", "Deleted".red(), name.bold());
 
     Ok(())
 }
@@ -0,0 +1,312 @@
+use crate::config::{AliasConfig, Config, ConfigError};
+use crate::timings::{Phase, TimedExt};
+use clap::Subcommand;
+use colored::Colorize;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Row};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Database error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("Alias '{0}' is not a database alias. Use --db when creating the alias.")]
+    NotDbAlias(String),
+
+    #[error("No alias specified and no default alias configured")]
+    NoAlias,
+
+    #[error("{0} inconsistenc(ies) found")]
+    Inconsistent(usize),
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Scan for inconsistent rows (dangling versions, broken bindings, ...)
+    #[command(after_help = "Examples:\n  \
+        ow local db check          Report problems\n  \
+        ow local db check --fix    Also apply the safe repairs")]
+    Check {
+        /// Apply safe repairs (projects without routes are only reported)
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+impl DbCommand {
+    pub async fn run(self, alias: Option<String>) -> Result<(), DbError> {
+        let database_url = resolve_database_url(alias)?;
+        let pool = connect(&database_url).await?;
+
+        match self {
+            Self::Check { fix } => cmd_check(&pool, fix).await,
+        }
+    }
+}
+
+fn resolve_database_url(alias: Option<String>) -> Result<String, DbError> {
+    let config = Config::load()?;
+
+    let alias_name = alias.or(config.default.clone()).ok_or(DbError::NoAlias)?;
+
+    let alias_config = config
+        .get_alias(&alias_name)
+        .ok_or_else(|| ConfigError::AliasNotFound(alias_name.clone()))?;
+
+    match alias_config {
+        AliasConfig::Db { database_url, .. } => Ok(database_url.clone()),
+        AliasConfig::Api { .. } => Err(DbError::NotDbAlias(alias_name)),
+    }
+}
+
+async fn connect(database_url: &str) -> Result<PgPool, DbError> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(database_url)
+        .timed(Phase::Connect)
+        .await?;
+
+    Ok(pool)
+}
+
+/// One consistency rule: a query listing offending rows as (subject, detail),
+/// and optionally a statement that repairs all of them.
+struct Check {
+    title: &'static str,
+    query: &'static str,
+    fix: Option<&'static str>,
+}
+
+const CHECKS: &[Check] = &[
+    // Points at the newest deployment there is, or none
+    Check {
+        title: "Workers whose current version has no deployment",
+        query: r#"
+            SELECT COALESCE(w.name, w.id::text) AS subject,
+                   'current_version ' || w.current_version || ' does not exist' AS detail
+            FROM workers w
+            WHERE w.current_version IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM worker_deployments d
+                  WHERE d.worker_id = w.id AND d.version = w.current_version
+              )
+            ORDER BY 1
+        "#,
+        fix: Some(
+            r#"
+            UPDATE workers w
+            SET current_version = (
+                SELECT MAX(d.version) FROM worker_deployments d
+                WHERE d.worker_id = w.id
+            )
+            WHERE w.current_version IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM worker_deployments d
+                  WHERE d.worker_id = w.id AND d.version = w.current_version
+              )
+            "#,
+        ),
+    },
+    // A deploy that failed between inserting the code and activating it.
+    // Workers without a current version are left alone: there is nothing to
+    // tell a failed deploy from the only code they have.
+    Check {
+        title: "Orphaned deployments (never activated)",
+        query: r#"
+            SELECT COALESCE(w.name, w.id::text) AS subject,
+                   'version ' || d.version || ' is newer than current ('
+                       || w.current_version || ')' AS detail
+            FROM worker_deployments d
+            JOIN workers w ON w.id = d.worker_id
+            WHERE d.version > w.current_version
+            ORDER BY 1, d.version
+        "#,
+        fix: Some(
+            r#"
+            DELETE FROM worker_deployments d
+            USING workers w
+            WHERE w.id = d.worker_id
+              AND d.version > w.current_version
+            "#,
+        ),
+    },
+    // Binding values are stored as text, so deleting a resource leaves them behind
+    Check {
+        title: "Environment bindings pointing at deleted resources",
+        query: r#"
+            SELECT e.name AS subject,
+                   ev.key || ' (' || ev.type::text || ') -> ' || ev.value AS detail
+            FROM environment_values ev
+            JOIN environments e ON e.id = ev.environment_id
+            WHERE (ev.type IN ('assets', 'storage')
+                   AND NOT EXISTS (SELECT 1 FROM storage_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'kv'
+                   AND NOT EXISTS (SELECT 1 FROM kv_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'database'
+                   AND NOT EXISTS (SELECT 1 FROM database_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'worker'
+                   AND NOT EXISTS (SELECT 1 FROM workers r WHERE r.id::text = ev.value))
+            ORDER BY 1, ev.key
+        "#,
+        fix: Some(
+            r#"
+            DELETE FROM environment_values ev
+            WHERE (ev.type IN ('assets', 'storage')
+                   AND NOT EXISTS (SELECT 1 FROM storage_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'kv'
+                   AND NOT EXISTS (SELECT 1 FROM kv_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'database'
+                   AND NOT EXISTS (SELECT 1 FROM database_configs r WHERE r.id::text = ev.value))
+               OR (ev.type = 'worker'
+                   AND NOT EXISTS (SELECT 1 FROM workers r WHERE r.id::text = ev.value))
+            "#,
+        ),
+    },
+    // Needs a redeploy, there is no safe default route
+    Check {
+        title: "Projects without routes",
+        query: r#"
+            SELECT p.name AS subject, 'no routes, requests will 404' AS detail
+            FROM projects p
+            WHERE NOT EXISTS (SELECT 1 FROM project_routes pr WHERE pr.project_id = p.id)
+            ORDER BY 1
+        "#,
+        fix: None,
+    },
+];
+
+async fn cmd_check(pool: &PgPool, fix: bool) -> Result<(), DbError> {
+    let mut remaining = 0;
+
+    // All or nothing: a failing check or repair leaves the database as it was
+    let mut tx = pool.begin().await?;
+
+    for check in CHECKS {
+        let rows = sqlx::query(check.query).fetch_all(&mut *tx).await?;
+
+        if rows.is_empty() {
+            eprintln!("{} {}", "✓".green(), check.title);
+            continue;
+        }
+
+        println!("{} {} ({})", "✗".red(), check.title.bold(), rows.len());
+
+        for row in &rows {
+            let subject: String = row.get("subject");
+            let detail: String = row.get("detail");
+            println!("    {:24} {}", subject, detail.dimmed());
+        }
+
+        match (fix, check.fix) {
+            (true, Some(statement)) => {
+                let result = sqlx::query(statement).execute(&mut *tx).await?;
+
+                eprintln!("    {} {} row(s)", "Fixed".green(), result.rows_affected());
+            }
+            (true, None) => {
+                eprintln!("    {}", "No safe automatic fix, review manually.".yellow());
+                remaining += rows.len();
+            }
+            (false, _) => remaining += rows.len(),
+        }
+    }
+
+    tx.commit().await?;
+
+    if remaining > 0 {
+        if !fix {
+            eprintln!();
+            eprintln!("{}", "Run with --fix to apply the safe repairs.".dimmed());
+        }
+
+        return Err(DbError::Inconsistent(remaining));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch database with the schema of migrations/. The SQL checks are
+    /// skipped unless OW_TEST_DATABASE_URL points at one.
+    async fn test_pool() -> Option<PgPool> {
+        let database_url = std::env::var("OW_TEST_DATABASE_URL").ok()?;
+        Some(connect(&database_url).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_fix_keeps_newer_deployments() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+
+        let name = format!("check-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+
+        let user_id: uuid::Uuid =
+            sqlx::query_scalar("INSERT INTO users (username) VALUES ($1) RETURNING id")
+                .bind(&name)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        // Current version 3 is gone, only newer deployments are left
+        let worker_id: uuid::Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO workers (name, user_id, current_version)
+            VALUES ($1, $2, 3)
+            RETURNING id
+            "#,
+        )
+        .bind(&name)
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        for version in [5, 6] {
+            sqlx::query(
+                r#"
+                INSERT INTO worker_deployments (worker_id, version, hash, code_type, code)
+                VALUES ($1, $2, '', 'javascript', '')
+                "#,
+            )
+            .bind(worker_id)
+            .bind(version)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Other rows of the scratch database may still be reported
+        cmd_check(&pool, true).await.ok();
+
+        let current: Option<i32> =
+            sqlx::query_scalar("SELECT current_version FROM workers WHERE id = $1")
+                .bind(worker_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let versions: Vec<i32> = sqlx::query_scalar(
+            "SELECT version FROM worker_deployments WHERE worker_id = $1 ORDER BY version",
+        )
+        .bind(worker_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(current, Some(6));
+        assert_eq!(versions, vec![5, 6]);
+    }
+}
@@ -0,0 +1,304 @@
+use super::env::{check_values, resource_id};
+use super::workers::{
+    AssetSource, UploadOptions, UploadPolicy, authorize_deploy, cmd_deploy, cmd_upload,
+    environment_gate, schema_gate,
+};
+use crate::backend::{
+    Backend, BackendError, Environment, EnvironmentValue, EnvironmentValueInput,
+    UpdateEnvironmentInput, UpdateWorkerInput,
+};
+use crate::config::project::{MANIFEST_FILE, Manifest};
+use clap::Args;
+use colored::Colorize;
+use std::io::IsTerminal;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct DeployArgs {
+    /// Deployment message (single-file deploys, shown in version history)
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Approval token for protected workers (see 'ow workers approve')
+    #[arg(long)]
+    pub approve_token: Option<String>,
+}
+
+impl DeployArgs {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        let manifest = Manifest::find()
+            .map_err(|e| BackendError::Api(e.to_string()))?
+            .ok_or_else(|| {
+                BackendError::Api(format!(
+                    "No {} (or openworkers.toml) in this directory. \
+                     Use 'ow workers deploy' to deploy without one.",
+                    MANIFEST_FILE
+                ))
+            })?;
+
+        let missing =
+            |field: &str| BackendError::Api(format!("{} has no `{}`", MANIFEST_FILE, field));
+
+        let name = manifest.name.clone().ok_or_else(|| missing("name"))?;
+        let main = manifest.main.clone().ok_or_else(|| missing("main"))?;
+
+        let folder = is_folder(&main);
+
+        if !folder && manifest.assets.dir.is_some() {
+            return Err(BackendError::Api(format!(
+                "`assets.dir` needs `main` to be a built folder or .zip holding worker.js, not {}",
+                main.display()
+            )));
+        }
+
+        // Every check runs before the environment or the worker is changed
+        environment_gate(backend, &name, None, true).await?;
+
+        let environment = match &manifest.environment {
+            Some(env) => Some(backend.get_environment(env).await?),
+            None if !manifest.bindings.is_empty() => {
+                return Err(BackendError::Api(format!(
+                    "{} has bindings but no `environment` to set them on",
+                    MANIFEST_FILE
+                )));
+            }
+            None => None,
+        };
+
+        let bindings = match &environment {
+            Some(environment) => plan_bindings(backend, environment, &manifest).await?,
+            None => Vec::new(),
+        };
+
+        // The schema applies to the environment as the deploy leaves it
+        match (&environment, &manifest.env_schema) {
+            (Some(environment), Some(schema)) => check_values(
+                &environment.name,
+                &pending_values(environment, &bindings),
+                Path::new(schema),
+            )?,
+            _ => schema_gate(backend, &name).await?,
+        }
+
+        let authorization = authorize_deploy(
+            backend,
+            &name,
+            self.approve_token.as_deref(),
+            std::io::stdin().is_terminal(),
+        )
+        .await?;
+
+        if let Some(env) = &manifest.environment {
+            apply_bindings(backend, env, &manifest, bindings).await?;
+            link_environment(backend, &name, env).await?;
+        }
+
+        if !folder {
+            return cmd_deploy(backend, &name, main, self.message, authorization, false).await;
+        }
+
+        if self.message.is_some() {
+            eprintln!(
+                "{} --message is ignored: folder uploads have no deployment message",
+                "warning:".yellow().bold()
+            );
+        }
+
+        let options = UploadOptions {
+            assets: manifest
+                .assets
+                .dir
+                .map(|dir| AssetSource {
+                    dir,
+                    mount: String::new(),
+                })
+                .into_iter()
+                .collect(),
+            policy: UploadPolicy::default(),
+            fingerprint: false,
+            auto_bind: None,
+            verify: None,
+        };
+
+        cmd_upload(backend, &name, main, authorization, options).await
+    }
+}
+
+/// Values to set for the manifest's bindings, leaving unchanged ones out
+async fn plan_bindings<B: Backend>(
+    backend: &B,
+    environment: &Environment,
+    manifest: &Manifest,
+) -> Result<Vec<EnvironmentValueInput>, BackendError> {
+    let mut values = Vec::new();
+
+    for (key, binding) in &manifest.bindings {
+        let id = resource_id(backend, &binding.binding_type, &binding.resource).await?;
+        let existing = environment.values.iter().find(|v| &v.key == key);
+
+        if existing.is_some_and(|v| v.value == id && v.value_type == binding.binding_type) {
+            continue;
+        }
+
+        values.push(EnvironmentValueInput {
+            id: existing.map(|v| v.id.clone()),
+            key: key.clone(),
+            value: Some(id),
+            value_type: binding.binding_type.clone(),
+        });
+    }
+
+    Ok(values)
+}
+
+/// The environment's values once the planned bindings are set
+fn pending_values(
+    environment: &Environment,
+    bindings: &[EnvironmentValueInput],
+) -> Vec<EnvironmentValue> {
+    let mut values = environment.values.clone();
+
+    for binding in bindings {
+        let value = EnvironmentValue {
+            id: binding.id.clone().unwrap_or_default(),
+            key: binding.key.clone(),
+            value: binding.value.clone().unwrap_or_default(),
+            value_type: binding.value_type.clone(),
+            rotated_at: None,
+        };
+
+        match values.iter_mut().find(|v| v.key == binding.key) {
+            Some(existing) => *existing = value,
+            None => values.push(value),
+        }
+    }
+
+    values
+}
+
+/// Set the planned bindings on the environment
+async fn apply_bindings<B: Backend>(
+    backend: &B,
+    env: &str,
+    manifest: &Manifest,
+    bindings: Vec<EnvironmentValueInput>,
+) -> Result<(), BackendError> {
+    if bindings.is_empty() {
+        return Ok(());
+    }
+
+    for binding in &bindings {
+        eprintln!(
+            "{} {} → {} ({})",
+            "Binding".green(),
+            binding.key.bold(),
+            manifest.bindings[&binding.key].resource,
+            binding.value_type
+        );
+    }
+
+    let input = UpdateEnvironmentInput {
+        values: Some(bindings),
+        ..Default::default()
+    };
+
+    backend.update_environment(env, input).await?;
+    Ok(())
+}
+
+/// Link the worker to the manifest's environment if it isn't already
+pub(super) async fn link_environment<B: Backend>(
+    backend: &B,
+    name: &str,
+    env: &str,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    if worker.environment.is_some_and(|e| e.name == env) {
+        return Ok(());
+    }
+
+    let input = UpdateWorkerInput {
+        environment: Some(env.to_string()),
+        ..Default::default()
+    };
+
+    backend.update_worker(name, input).await?;
+
+    eprintln!(
+        "{} '{}' to environment '{}'",
+        "Linked".green(),
+        name.bold(),
+        env.bold()
+    );
+
+    Ok(())
+}
+
+/// Whether `main` is uploaded (folder or .zip) rather than deployed as a script
+fn is_folder(path: &Path) -> bool {
+    path.is_dir() || path.extension().is_some_and(|e| e == "zip")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{CreateEnvironmentInput, CreateKvInput};
+
+    #[tokio::test]
+    async fn test_plan_and_apply_bindings() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        backend
+            .create_environment(CreateEnvironmentInput {
+                name: "prod".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let kv = backend
+            .create_kv(CreateKvInput {
+                name: "cache".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let manifest = Manifest::parse(
+            "environment = \"prod\"\n[bindings]\nCACHE = { type = \"kv\", resource = \"cache\" }\n",
+        )
+        .unwrap();
+
+        let environment = backend.get_environment("prod").await.unwrap();
+        let bindings = plan_bindings(&backend, &environment, &manifest)
+            .await
+            .unwrap();
+
+        let pending = pending_values(&environment, &bindings);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].value, kv.id);
+
+        apply_bindings(&backend, "prod", &manifest, bindings)
+            .await
+            .unwrap();
+        link_environment(&backend, "api", "prod").await.unwrap();
+
+        // Nothing left to set once applied
+        let environment = backend.get_environment("prod").await.unwrap();
+        let bindings = plan_bindings(&backend, &environment, &manifest)
+            .await
+            .unwrap();
+        assert!(bindings.is_empty());
+
+        let values = backend.get_environment("prod").await.unwrap().values;
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].key, "CACHE");
+        assert_eq!(values[0].value, kv.id);
+        assert_eq!(values[0].value_type, "kv");
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.environment.unwrap().name, "prod");
+    }
+}
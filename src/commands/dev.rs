@@ -0,0 +1,321 @@
+//! `ow dev`: serve a development copy of a worker on localhost.
+//!
+//! The script is deployed to its own worker (`<name>-dev` unless told
+//! otherwise), linked to the chosen environment so it runs with the same
+//! variables, secrets and bindings. A local HTTP proxy forwards to it, and the
+//! script is redeployed whenever it changes on disk.
+
+use super::deploy::link_environment;
+use super::workers::{code_type, sha256_hex, worker_url};
+use crate::backend::{Backend, BackendError, CreateWorkerInput, DeployInput};
+use crate::config::project::{MANIFEST_FILE, Manifest};
+use crate::http1::{self, read_request, write_response};
+use clap::Args;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+
+/// How often the script is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Deployment message of dev deploys, shown in `workers history`
+const DEV_MESSAGE: &str = "ow dev";
+
+#[derive(Args)]
+pub struct DevArgs {
+    /// Worker script (default: `main` from ow.toml)
+    pub file: Option<PathBuf>,
+
+    /// Worker being developed (default: `name` from ow.toml)
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// Environment providing variables, secrets and bindings (default: `environment` from ow.toml)
+    #[arg(short, long)]
+    pub env: Option<String>,
+
+    /// Worker the script is deployed to (default: <name>-dev)
+    #[arg(long)]
+    pub dev_worker: Option<String>,
+
+    /// Local port to listen on
+    #[arg(short, long, default_value = "8787")]
+    pub port: u16,
+
+    /// Forward to this URL instead of the dev worker's public URL (e.g. a local runtime)
+    #[arg(long)]
+    pub upstream: Option<String>,
+}
+
+impl DevArgs {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        let manifest = Manifest::find()
+            .map_err(|e| BackendError::Api(e.to_string()))?
+            .unwrap_or_default();
+
+        let file = self.file.or(manifest.main).ok_or_else(|| {
+            BackendError::Api(format!(
+                "No script given and no `main` in {}",
+                MANIFEST_FILE
+            ))
+        })?;
+
+        if file.is_dir() {
+            return Err(BackendError::Api(format!(
+                "ow dev serves a single script, {} is a folder",
+                file.display()
+            )));
+        }
+
+        code_type(&file)?;
+
+        let name = self.name.or(manifest.name).ok_or_else(|| {
+            BackendError::Api(format!(
+                "No --name given and no `name` in {}",
+                MANIFEST_FILE
+            ))
+        })?;
+
+        let dev = self.dev_worker.unwrap_or_else(|| format!("{}-dev", name));
+
+        if dev == name {
+            return Err(BackendError::Usage(format!(
+                "The dev worker must not be '{}' itself",
+                name
+            )));
+        }
+
+        ensure_worker(backend, &dev, &name, &file).await?;
+
+        if let Some(env) = self.env.or(manifest.environment) {
+            link_environment(backend, &dev, &env).await?;
+        }
+
+        let mut modified = modified_at(&file);
+        let version = deploy(backend, &dev, &file).await?;
+
+        eprintln!("{} '{}' v{}", "Deployed".green(), dev.bold(), version);
+
+        let upstream = match self.upstream {
+            Some(upstream) => upstream,
+            None => {
+                let worker = backend.get_worker(&dev).await?;
+
+                worker_url(backend, &worker).ok_or_else(|| {
+                    BackendError::Api(format!(
+                        "No public URL known for worker '{}'. \
+                         Pass --upstream to forward to a local runtime.",
+                        dev
+                    ))
+                })?
+            }
+        };
+
+        let listener = TcpListener::bind(("127.0.0.1", self.port))
+            .await
+            .map_err(|e| {
+                BackendError::Api(format!("Failed to listen on port {}: {}", self.port, e))
+            })?;
+
+        eprintln!(
+            "{} http://localhost:{} → {}",
+            "Serving".green(),
+            self.port,
+            upstream.cyan()
+        );
+        eprintln!(
+            "{} {} {}",
+            "Watching".green(),
+            file.display(),
+            "(Ctrl-C to stop)".dimmed()
+        );
+        eprintln!();
+
+        let client = reqwest::Client::new();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        let stop = tokio::signal::ctrl_c();
+        tokio::pin!(stop);
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else {
+                        continue;
+                    };
+                    let client = client.clone();
+                    let upstream = upstream.clone();
+
+                    tokio::spawn(async move {
+                        serve(stream, &client, &upstream).await.ok();
+                    });
+                }
+                _ = poll.tick() => {
+                    let now = modified_at(&file);
+
+                    if now == modified {
+                        continue;
+                    }
+
+                    modified = now;
+                    let start = Instant::now();
+
+                    // On failure the last good version keeps serving
+                    match deploy(backend, &dev, &file).await {
+                        Ok(version) => eprintln!(
+                            "{} v{} in {} ms",
+                            "Redeployed".green(),
+                            version,
+                            start.elapsed().as_millis()
+                        ),
+                        Err(e) => eprintln!("{} {}", "error:".red().bold(), e),
+                    }
+                }
+                _ = &mut stop => break,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Create the dev worker on first use
+async fn ensure_worker<B: Backend>(
+    backend: &B,
+    dev: &str,
+    name: &str,
+    file: &Path,
+) -> Result<(), BackendError> {
+    match backend.get_worker(dev).await {
+        Ok(_) => return Ok(()),
+        Err(BackendError::NotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let language = match code_type(file)? {
+        "typescript" => "typescript",
+        _ => "javascript",
+    };
+
+    let input = CreateWorkerInput {
+        name: dev.to_string(),
+        description: Some(format!("Development copy of {}", name)),
+        language: language.to_string(),
+        tags: BTreeMap::new(),
+    };
+
+    backend.create_worker(input).await?;
+
+    eprintln!("{} dev worker '{}'", "Created".green(), dev.bold());
+    Ok(())
+}
+
+/// Deploy the script to the dev worker, returning the new version
+async fn deploy<B: Backend>(backend: &B, dev: &str, file: &Path) -> Result<i32, BackendError> {
+    let code = std::fs::read(file).map_err(|e| {
+        BackendError::Api(format!("Failed to read file '{}': {}", file.display(), e))
+    })?;
+
+    let input = DeployInput {
+        hash: Some(sha256_hex(&code)),
+        code,
+        code_type: code_type(file)?.to_string(),
+        message: Some(DEV_MESSAGE.to_string()),
+        authorization: Default::default(),
+    };
+
+    Ok(backend.deploy_worker(dev, input).await?.version)
+}
+
+fn modified_at(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+/// Forward the requests of one local connection, logging each on stdout
+async fn serve(stream: TcpStream, client: &reqwest::Client, upstream: &str) -> std::io::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+
+    loop {
+        let request = match read_request(&mut reader).await {
+            Ok(Some(request)) => request,
+            Ok(None) => break,
+            Err(e) => return http1::reject(&mut write, e).await,
+        };
+
+        let start = Instant::now();
+        let line = format!("{} {}", request.method, request.path);
+        let url = format!(
+            "{}/{}",
+            upstream.trim_end_matches('/'),
+            request.path.trim_start_matches('/')
+        );
+
+        let response = http1::forward(client, &url, request).await;
+
+        let status = match response.status {
+            500.. => response.status.to_string().red(),
+            400.. => response.status.to_string().yellow(),
+            _ => response.status.to_string().green(),
+        };
+
+        println!(
+            "{} {} {}",
+            status,
+            line,
+            format!("{} ms", start.elapsed().as_millis()).dimmed()
+        );
+
+        write_response(&mut write, &response).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CreateEnvironmentInput;
+    use crate::backend::mock::MockBackend;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_dev_worker() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        backend
+            .create_environment(CreateEnvironmentInput {
+                name: "staging".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let mut script = NamedTempFile::with_suffix(".ts").unwrap();
+        write!(script, "export default {{}}").unwrap();
+
+        ensure_worker(&backend, "api-dev", "api", script.path())
+            .await
+            .unwrap();
+        ensure_worker(&backend, "api-dev", "api", script.path())
+            .await
+            .unwrap();
+        link_environment(&backend, "api-dev", "staging")
+            .await
+            .unwrap();
+
+        assert_eq!(deploy(&backend, "api-dev", script.path()).await.unwrap(), 1);
+        assert_eq!(deploy(&backend, "api-dev", script.path()).await.unwrap(), 2);
+
+        let worker = backend.get_worker("api-dev").await.unwrap();
+        assert_eq!(worker.environment.unwrap().name, "staging");
+
+        let deployment = backend.get_current_deployment("api-dev").await.unwrap();
+        let deployment = deployment.unwrap();
+        assert_eq!(deployment.code_type, "typescript");
+        assert_eq!(deployment.message.as_deref(), Some(DEV_MESSAGE));
+    }
+}
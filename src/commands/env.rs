@@ -1,21 +1,57 @@
 use crate::backend::{
-    Backend, BackendError, CreateEnvironmentInput, EnvironmentValueInput, UpdateEnvironmentInput,
+    ACCOUNT_SECRET_PREFIX, Backend, BackendError, CreateEnvironmentInput, EnvironmentValue,
+    EnvironmentValueChange, EnvironmentValueInput, UpdateEnvironmentInput, Worker,
 };
+use crate::config::project::{MANIFEST_FILE, Manifest};
+use crate::env_schema::{EnvSchema, SCHEMA_FILE};
+use crate::porcelain;
+use crate::quiet;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use clap::Subcommand;
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum EnvCommand {
     /// List all environments with their variable/binding counts
     #[command(alias = "ls")]
-    List,
+    List {
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
 
     /// Show environment details including all variables and bindings
-    #[command(after_help = "Example:\n  ow env get production")]
+    #[command(after_help = "Examples:\n  \
+        ow env get production\n  \
+        ow env get production --at 2026-06-01\n  \
+        ow env get production --at '2026-06-01 12:00:00' --diff\n\n\
+        --at rebuilds the bindings from the value history, which starts when \
+        history was enabled on the server.")]
     Get {
-        /// Environment name
-        name: String,
+        /// Environment name (default: ow.toml's `environment`)
+        name: Option<String>,
+
+        /// Only print the name (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+
+        /// Stable tab-separated output, one binding per line: key, type, value
+        /// (empty for secrets)
+        #[arg(long, conflicts_with = "ids")]
+        porcelain: bool,
+
+        /// Show the bindings as they were at this time (RFC 3339, or UTC
+        /// YYYY-MM-DD HH:MM:SS / YYYY-MM-DD)
+        #[arg(long, value_name = "TIMESTAMP", value_parser = parse_timestamp, conflicts_with = "ids")]
+        at: Option<DateTime<Utc>>,
+
+        /// With --at, show what changed between then and now instead
+        #[arg(long, requires = "at", conflicts_with = "porcelain")]
+        diff: bool,
     },
 
     /// Create a new environment for organizing variables and bindings
@@ -36,13 +72,18 @@ pub enum EnvCommand {
     Delete {
         /// Environment name to delete
         name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Set a variable or secret in an environment
     #[command(after_help = "Examples:\n  \
         ow env set prod API_URL https://api.example.com\n  \
         ow env set prod API_KEY --secret\n  \
-        ow env set prod DB_URL")]
+        ow env set prod DB_URL\n  \
+        ow env set prod API_URL https://api.example.com --dry-run")]
     Set {
         /// Environment name
         env: String,
@@ -56,24 +97,90 @@ pub enum EnvCommand {
         /// Store as secret (value is encrypted and masked in output)
         #[arg(short, long)]
         secret: bool,
+
+        /// Show the value row that would be written, without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Replace a secret with a new value and record the rotation
+    #[command(after_help = "Examples:\n  \
+        ow env rotate prod API_KEY                 Prompt for the new value\n  \
+        ow env rotate prod SESSION_KEY --generate  Random 32-character value\n  \
+        ow env rotate prod SESSION_KEY --generate 64 --redeploy")]
+    Rotate {
+        /// Environment name
+        env: String,
+
+        /// Secret name
+        key: String,
+
+        /// New value (prompted interactively if omitted and not generated)
+        #[arg(conflicts_with = "generate")]
+        value: Option<String>,
+
+        /// Generate a random alphanumeric value of this length (printed once)
+        #[arg(long, value_name = "LENGTH", num_args = 0..=1, default_missing_value = "32",
+              value_parser = clap::value_parser!(u16).range(16..))]
+        generate: Option<u16>,
+
+        /// Redeploy the workers linked to the environment so they pick up the new value
+        #[arg(long)]
+        redeploy: bool,
     },
 
     /// Remove a variable or secret from an environment
-    #[command(after_help = "Example:\n  ow env unset prod OLD_API_KEY")]
+    #[command(after_help = "Examples:\n  \
+        ow env unset prod OLD_API_KEY\n  \
+        ow env unset prod OLD_API_KEY --dry-run")]
     Unset {
         /// Environment name
         env: String,
 
         /// Variable name to remove
         key: String,
+
+        /// Show the value row that would be removed, without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show the past values of a variable or secret
+    #[command(after_help = "Example:\n  ow env history prod API_URL")]
+    History {
+        /// Environment name
+        env: String,
+
+        /// Variable name
+        key: String,
+    },
+
+    /// Restore the value a variable or secret had at a point in time
+    #[command(after_help = "Examples:\n  \
+        ow env revert prod API_URL --to '2026-03-14 09:30:00'\n  \
+        ow env revert prod API_URL --to 2026-03-14T09:30:00+01:00\n\n\
+        Times without an offset are UTC, a bare date means its midnight. \
+        Timestamps printed by 'ow env history' can be pasted as they are.")]
+    Revert {
+        /// Environment name
+        env: String,
+
+        /// Variable name
+        key: String,
+
+        /// Point in time to restore (RFC 3339, YYYY-MM-DD HH:MM:SS or YYYY-MM-DD)
+        #[arg(long, value_name = "TIMESTAMP", value_parser = parse_timestamp)]
+        to: DateTime<Utc>,
     },
 
-    /// Bind a resource (KV, database, storage) to an environment
+    /// Bind a resource (KV, database, storage) or an account secret to an environment
     #[command(after_help = "Examples:\n  \
         ow env bind prod KV my-cache --type kv\n  \
         ow env bind prod DB my-database --type database\n  \
         ow env bind prod ASSETS my-storage --type assets\n  \
-        ow env bind prod FILES my-storage --type storage")]
+        ow env bind prod FILES my-storage --type storage\n  \
+        ow env bind prod SENTRY_DSN @account/SENTRY_DSN\n  \
+        ow env bind prod KV my-cache --type kv --dry-run   Show the row, write nothing")]
     Bind {
         /// Environment name
         env: String,
@@ -81,27 +188,101 @@ pub enum EnvCommand {
         /// Binding name (accessed as env.NAME in worker code)
         key: String,
 
-        /// Resource name to bind (must exist)
+        /// Resource name to bind (must exist), or @account/KEY for an account secret
         resource: String,
 
-        /// Binding type: assets, storage, kv, or database
+        /// Binding type: assets, storage, kv, or database (not needed for account secrets)
         #[arg(short = 't', long = "type", value_parser = ["assets", "storage", "kv", "database"])]
-        binding_type: String,
+        binding_type: Option<String>,
+
+        /// Resolve the resource and show the value row that would be written, without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Link several workers to an environment at once
+    #[command(after_help = "Examples:\n  \
+        ow env link staging api web\n  \
+        ow env link staging 'api-*'     Every worker matching the glob")]
+    Link {
+        /// Environment name
+        env: String,
+
+        /// Worker names or globs (* and ?)
+        #[arg(required = true)]
+        workers: Vec<String>,
+    },
+
+    /// Check an environment against a schema of required keys, types and patterns
+    #[command(after_help = "Examples:\n  \
+        ow env validate prod\n  \
+        ow env validate staging --schema config/env.schema.json\n\n\
+        Schema format:\n  \
+        { \"keys\": { \"STRIPE_KEY\": { \"type\": \"secret\" },\n  \
+                    \"API_URL\": { \"type\": \"var\", \"pattern\": \"^https://\" },\n  \
+                    \"DEBUG\": { \"required\": false } } }")]
+    Validate {
+        /// Environment name
+        name: String,
+
+        /// Schema file
+        #[arg(long, default_value = SCHEMA_FILE)]
+        schema: PathBuf,
+    },
+
+    /// Write a schema requiring every key of an environment, as a starting point
+    #[command(after_help = "Examples:\n  \
+        ow env template prod                      Print to stdout\n  \
+        ow env template prod --out env.schema.json")]
+    Template {
+        /// Environment name
+        name: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Mark an environment as protected (deploys to linked workers require approval)
+    #[command(after_help = "Examples:\n  \
+        ow env protect production\n  \
+        ow env protect production --off")]
+    Protect {
+        /// Environment name
+        name: String,
+
+        /// Remove protection instead
+        #[arg(long)]
+        off: bool,
     },
 }
 
 impl EnvCommand {
     pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
         match self {
-            Self::List => cmd_list(backend).await,
-            Self::Get { name } => cmd_get(backend, &name).await,
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::Get {
+                name,
+                porcelain,
+                at: Some(at),
+                diff,
+                ..
+            } => cmd_get_at(backend, &project_environment(name)?, at, porcelain, diff).await,
+            Self::Get {
+                name,
+                ids,
+                porcelain,
+                at: None,
+                ..
+            } => cmd_get(backend, &project_environment(name)?, ids, porcelain).await,
             Self::Create { name, description } => cmd_create(backend, name, description).await,
-            Self::Delete { name } => cmd_delete(backend, &name).await,
+            Self::Delete { name, yes } => cmd_delete(backend, &name, yes).await,
             Self::Set {
                 env,
                 key,
                 value,
                 secret,
+                dry_run,
             } => {
                 let value = match value {
                     Some(v) => v,
@@ -123,24 +304,69 @@ impl EnvCommand {
                     }
                 };
 
-                cmd_set(backend, &env, &key, &value, secret).await
+                cmd_set(backend, &env, &key, &value, secret, dry_run).await
+            }
+            Self::Rotate {
+                env,
+                key,
+                value,
+                generate,
+                redeploy,
+            } => {
+                let value = match (value, generate) {
+                    (Some(v), _) => v,
+                    (None, Some(length)) => generate_secret(length as usize),
+                    (None, None) => {
+                        eprint!("{}: ", "Enter new secret value".dimmed());
+                        io::stderr().flush().ok();
+                        rpassword::read_password().map_err(|e| {
+                            BackendError::Api(format!("Failed to read input: {}", e))
+                        })?
+                    }
+                };
+
+                cmd_rotate(backend, &env, &key, &value, generate.is_some(), redeploy).await
             }
-            Self::Unset { env, key } => cmd_unset(backend, &env, &key).await,
+            Self::Unset { env, key, dry_run } => cmd_unset(backend, &env, &key, dry_run).await,
+            Self::History { env, key } => cmd_history(backend, &env, &key).await,
+            Self::Revert { env, key, to } => cmd_revert(backend, &env, &key, to).await,
             Self::Bind {
                 env,
                 key,
                 resource,
                 binding_type,
-            } => cmd_bind(backend, &env, &key, &resource, &binding_type).await,
+                dry_run,
+            } => match binding_type {
+                _ if resource.starts_with(ACCOUNT_SECRET_PREFIX) => {
+                    cmd_bind_account_secret(backend, &env, &key, &resource, dry_run).await
+                }
+                Some(binding_type) => {
+                    cmd_bind(backend, &env, &key, &resource, &binding_type, dry_run).await
+                }
+                None => Err(BackendError::Usage(
+                    "--type is required unless binding an @account/ secret".to_string(),
+                )),
+            },
+            Self::Link { env, workers } => cmd_link(backend, &env, &workers).await,
+            Self::Protect { name, off } => cmd_protect(backend, &name, !off).await,
+            Self::Validate { name, schema } => cmd_validate(backend, &name, &schema).await,
+            Self::Template { name, out } => cmd_template(backend, &name, out).await,
         }
     }
 }
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
     let environments = backend.list_environments().await?;
 
+    if ids {
+        for environment in &environments {
+            quiet::print(&environment.name);
+        }
+        return Ok(());
+    }
+
     if environments.is_empty() {
-        println!("No environments found.");
+        eprintln!("No environments found.");
         return Ok(());
     }
 
@@ -180,15 +406,39 @@ async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
             parts.join(", ")
         };
 
-        println!("  {:30} {}", env.name.bold(), summary);
+        if env.protected {
+            println!(
+                "  {:30} {} {}",
+                env.name.bold(),
+                summary,
+                "(protected)".yellow()
+            );
+        } else {
+            println!("  {:30} {}", env.name.bold(), summary);
+        }
     }
 
     Ok(())
 }
 
-async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_get<B: Backend>(
+    backend: &B,
+    name: &str,
+    ids: bool,
+    porcelain: bool,
+) -> Result<(), BackendError> {
     let env = backend.get_environment(name).await?;
 
+    if ids {
+        quiet::print(&env.name);
+        return Ok(());
+    }
+
+    if porcelain {
+        print_porcelain(&env.values);
+        return Ok(());
+    }
+
     println!("{:12} {}", "Name:".dimmed(), env.name.bold());
     println!("{:12} {}", "ID:".dimmed(), env.id);
 
@@ -196,6 +446,10 @@ async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError
         println!("{:12} {}", "Description:".dimmed(), desc);
     }
 
+    if env.protected {
+        println!("{:12} {}", "Protected:".dimmed(), "yes".yellow());
+    }
+
     println!(
         "{:12} {}",
         "Created:".dimmed(),
@@ -214,29 +468,176 @@ async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError
         println!("{}", "─".repeat(40));
 
         for val in &env.values {
-            let type_badge = match val.value_type.as_str() {
-                "secret" => "[secret]".yellow(),
-                "var" => "[var]".dimmed(),
-                "kv" => "[kv]".cyan(),
-                "assets" => "[assets]".green(),
-                "storage" => "[storage]".blue(),
-                "database" => "[database]".magenta(),
-                _ => format!("[{}]", val.value_type).dimmed(),
-            };
+            let rotated = val
+                .rotated_at
+                .map(|at| {
+                    format!(" (rotated {})", at.format("%Y-%m-%d"))
+                        .dimmed()
+                        .to_string()
+                })
+                .unwrap_or_default();
 
-            let display_value = if val.value_type == "secret" {
-                "****".to_string()
-            } else {
-                val.value.clone()
-            };
+            println!(
+                "  {} {} = {}{}",
+                type_badge(&val.value_type),
+                val.key.bold(),
+                display_value(val),
+                rotated
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_porcelain(values: &[EnvironmentValue]) {
+    for val in values {
+        let value = if val.value_type == "secret" && !val.value.starts_with(ACCOUNT_SECRET_PREFIX) {
+            ""
+        } else {
+            val.value.as_str()
+        };
+
+        porcelain::print(&[val.key.as_str(), val.value_type.as_str(), value]);
+    }
+}
+
+/// How a key differs between a past state of an environment and now
+#[derive(Debug)]
+enum ValueChange<'a> {
+    Added(&'a EnvironmentValue),
+    Removed(&'a EnvironmentValue),
+    Changed {
+        then: &'a EnvironmentValue,
+        now: &'a EnvironmentValue,
+    },
+}
+
+/// Keys added, removed or changed (value or type) going from `then` to
+/// `now`, sorted by key
+fn diff_values<'a>(
+    then: &'a [EnvironmentValue],
+    now: &'a [EnvironmentValue],
+) -> Vec<ValueChange<'a>> {
+    let then: BTreeMap<&str, &EnvironmentValue> =
+        then.iter().map(|v| (v.key.as_str(), v)).collect();
+    let now: BTreeMap<&str, &EnvironmentValue> = now.iter().map(|v| (v.key.as_str(), v)).collect();
+
+    let keys: BTreeSet<&str> = then.keys().chain(now.keys()).copied().collect();
+
+    keys.into_iter()
+        .filter_map(|key| match (then.get(key), now.get(key)) {
+            (None, Some(now)) => Some(ValueChange::Added(now)),
+            (Some(then), None) => Some(ValueChange::Removed(then)),
+            (Some(then), Some(now))
+                if then.value != now.value || then.value_type != now.value_type =>
+            {
+                Some(ValueChange::Changed { then, now })
+            }
+            _ => None,
+        })
+        .collect()
+}
 
-            println!("  {} {} = {}", type_badge, val.key.bold(), display_value);
+async fn cmd_get_at<B: Backend>(
+    backend: &B,
+    name: &str,
+    at: DateTime<Utc>,
+    porcelain: bool,
+    diff: bool,
+) -> Result<(), BackendError> {
+    let values = backend.environment_values_at(name, at).await?;
+    let at_label = at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    if porcelain {
+        print_porcelain(&values);
+        return Ok(());
+    }
+
+    if !diff {
+        if values.is_empty() {
+            eprintln!("No bindings in '{}' at {}.", name, at_label);
+            return Ok(());
+        }
+
+        println!("{}", format!("Bindings at {}", at_label).bold());
+        println!("{}", "─".repeat(40));
+
+        for val in &values {
+            println!(
+                "  {} {} = {}",
+                type_badge(&val.value_type),
+                val.key.bold(),
+                display_value(val)
+            );
+        }
+
+        return Ok(());
+    }
+
+    let env = backend.get_environment(name).await?;
+    let changes = diff_values(&values, &env.values);
+
+    if changes.is_empty() {
+        eprintln!("No changes in '{}' since {}.", name, at_label);
+        return Ok(());
+    }
+
+    println!("{}", format!("Changes: {} → now", at_label).bold());
+
+    for change in &changes {
+        match change {
+            ValueChange::Added(val) => println!(
+                "  {} {:24} {} {}",
+                "+".green(),
+                val.key,
+                type_badge(&val.value_type),
+                display_value(val)
+            ),
+            ValueChange::Removed(val) => println!(
+                "  {} {:24} {} {}",
+                "-".red(),
+                val.key,
+                type_badge(&val.value_type),
+                display_value(val)
+            ),
+            ValueChange::Changed { then, now } => println!(
+                "  {} {:24} {} {} → {} {}",
+                "~".yellow(),
+                then.key,
+                type_badge(&then.value_type),
+                display_value(then),
+                type_badge(&now.value_type),
+                display_value(now)
+            ),
         }
     }
 
     Ok(())
 }
 
+/// Colored `[type]` badge of an environment value
+pub fn type_badge(value_type: &str) -> ColoredString {
+    match value_type {
+        "secret" => "[secret]".yellow(),
+        "var" => "[var]".dimmed(),
+        "kv" => "[kv]".cyan(),
+        "assets" => "[assets]".green(),
+        "storage" => "[storage]".blue(),
+        "database" => "[database]".magenta(),
+        _ => format!("[{}]", value_type).dimmed(),
+    }
+}
+
+/// Value as shown to the user: secrets are masked, account references kept
+pub fn display_value(val: &EnvironmentValue) -> String {
+    if val.value_type == "secret" && !val.value.starts_with(ACCOUNT_SECRET_PREFIX) {
+        "****".to_string()
+    } else {
+        val.value.clone()
+    }
+}
+
 async fn cmd_create<B: Backend>(
     backend: &B,
     name: String,
@@ -249,7 +650,7 @@ async fn cmd_create<B: Backend>(
 
     let env = backend.create_environment(input).await?;
 
-    println!(
+    eprintln!(
         "{} Environment '{}' created.",
         "Created".green(),
         env.name.bold()
@@ -258,10 +659,12 @@ async fn cmd_create<B: Backend>(
     Ok(())
 }
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("environment '{}'", name), yes)?;
+
     backend.delete_environment(name).await?;
 
-    println!("{} Environment '{}' deleted.", "Deleted".red(), name.bold());
+    eprintln!("{} Environment '{}' deleted.", "Deleted".red(), name.bold());
 
     Ok(())
 }
@@ -272,6 +675,7 @@ async fn cmd_set<B: Backend>(
     key: &str,
     value: &str,
     secret: bool,
+    dry_run: bool,
 ) -> Result<(), BackendError> {
     // Get current environment to find existing value ID
     let env = backend.get_environment(env_name).await?;
@@ -293,15 +697,21 @@ async fn cmd_set<B: Backend>(
         },
     };
 
+    if dry_run {
+        print_dry_run(env_name, &value_input, None);
+        return Ok(());
+    }
+
     let input = UpdateEnvironmentInput {
         name: None,
         values: Some(vec![value_input]),
+        protected: None,
     };
 
     backend.update_environment(env_name, input).await?;
 
     let type_str = if secret { "Secret" } else { "Variable" };
-    println!(
+    eprintln!(
         "{} {} '{}' set in environment '{}'.",
         "Updated".green(),
         type_str,
@@ -312,7 +722,142 @@ async fn cmd_set<B: Backend>(
     Ok(())
 }
 
-async fn cmd_unset<B: Backend>(backend: &B, env_name: &str, key: &str) -> Result<(), BackendError> {
+/// Random alphanumeric secret of `length` characters
+fn generate_secret(length: usize) -> String {
+    rand::rng()
+        .sample_iter(&rand::distr::Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+async fn cmd_rotate<B: Backend>(
+    backend: &B,
+    env_name: &str,
+    key: &str,
+    value: &str,
+    generated: bool,
+    redeploy: bool,
+) -> Result<(), BackendError> {
+    let env = backend.get_environment(env_name).await?;
+
+    let existing = env.values.iter().find(|v| v.key == key).ok_or_else(|| {
+        BackendError::NotFound(format!(
+            "Secret '{}' not found in environment '{}'",
+            key, env_name
+        ))
+    })?;
+
+    if let Some(account_key) = existing.value.strip_prefix(ACCOUNT_SECRET_PREFIX) {
+        return Err(BackendError::Api(format!(
+            "'{}' follows account secret '{}'. Rotate it with `ow secrets set {}`",
+            key, account_key, account_key
+        )));
+    }
+
+    if existing.value_type != "secret" {
+        return Err(BackendError::Api(format!(
+            "'{}' is a {}, not a secret. Use `ow env set` instead",
+            key, existing.value_type
+        )));
+    }
+
+    let rotated = backend
+        .rotate_environment_secret(env_name, key, value)
+        .await?;
+
+    eprintln!(
+        "{} Secret '{}' rotated in environment '{}'.",
+        "Rotated".green(),
+        key.bold(),
+        env_name.bold()
+    );
+
+    if let Some(previous) = existing.rotated_at {
+        eprintln!(
+            "  {} {}",
+            "Previous rotation:".dimmed(),
+            previous.format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+
+    if generated {
+        println!("  {} {}", "New value:".dimmed(), rotated.value);
+        eprintln!("  {}", "It will not be shown again.".yellow());
+    }
+
+    if !redeploy {
+        return Ok(());
+    }
+
+    let workers: Vec<Worker> = backend
+        .list_workers()
+        .await?
+        .into_iter()
+        .filter(|w| w.environment.as_ref().is_some_and(|e| e.id == env.id))
+        .collect();
+
+    if workers.is_empty() {
+        eprintln!(
+            "  No workers linked to '{}', nothing to redeploy.",
+            env_name
+        );
+        return Ok(());
+    }
+
+    eprintln!();
+    eprintln!("Redeploying {} linked worker(s):", workers.len());
+
+    let message = format!("Rotated {}", key);
+    let mut failed = 0;
+
+    for worker in &workers {
+        // Protected workers need an approved deploy, even for the same code
+        if worker.is_protected() {
+            eprintln!(
+                "  {} {} {}",
+                "─".dimmed(),
+                worker.name,
+                "protected, redeploy with an approval token".dimmed()
+            );
+            continue;
+        }
+
+        match backend.redeploy_worker(&worker.name, Some(&message)).await {
+            Ok(deployment) => {
+                eprintln!("  {} {} v{}", "✓".green(), worker.name, deployment.version)
+            }
+            Err(BackendError::NotFound(_)) => {
+                eprintln!(
+                    "  {} {} {}",
+                    "─".dimmed(),
+                    worker.name,
+                    "not deployed".dimmed()
+                )
+            }
+            Err(e) => {
+                eprintln!("  {} {} {}", "✗".red(), worker.name, e.to_string().red());
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(BackendError::Partial(format!(
+            "{} worker(s) failed to redeploy",
+            failed
+        )));
+    }
+
+    Ok(())
+}
+
+async fn cmd_unset<B: Backend>(
+    backend: &B,
+    env_name: &str,
+    key: &str,
+    dry_run: bool,
+) -> Result<(), BackendError> {
     // Get current environment to find existing value ID
     let env = backend.get_environment(env_name).await?;
 
@@ -327,14 +872,20 @@ async fn cmd_unset<B: Backend>(backend: &B, env_name: &str, key: &str) -> Result
                 value_type: val.value_type.clone(),
             };
 
+            if dry_run {
+                print_dry_run(env_name, &value_input, None);
+                return Ok(());
+            }
+
             let input = UpdateEnvironmentInput {
                 name: None,
                 values: Some(vec![value_input]),
+                protected: None,
             };
 
             backend.update_environment(env_name, input).await?;
 
-            println!(
+            eprintln!(
                 "{} Variable '{}' removed from environment '{}'.",
                 "Removed".red(),
                 key.bold(),
@@ -352,34 +903,286 @@ async fn cmd_unset<B: Backend>(backend: &B, env_name: &str, key: &str) -> Result
     Ok(())
 }
 
-async fn cmd_bind<B: Backend>(
+/// Parse `--to` and `--at`: RFC 3339, or a UTC `YYYY-MM-DD HH:MM:SS` / `YYYY-MM-DD`
+fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+
+    if let Ok(at) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Ok(at.and_utc());
+    }
+
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| {
+            format!(
+                "invalid timestamp '{}', expected RFC 3339, YYYY-MM-DD HH:MM:SS or YYYY-MM-DD",
+                s
+            )
+        })
+}
+
+/// Value of a history entry as shown to the user, masked like `display_value`
+fn display_change(change: &EnvironmentValueChange) -> String {
+    match (&change.value, &change.value_type) {
+        (None, _) => "(unset)".dimmed().to_string(),
+        (Some(value), Some(value_type)) => display_value(&EnvironmentValue {
+            id: String::new(),
+            key: String::new(),
+            value: value.clone(),
+            value_type: value_type.clone(),
+            rotated_at: None,
+        }),
+        (Some(value), None) => value.clone(),
+    }
+}
+
+/// Environment values breaking a schema file, as an error listing each issue
+pub async fn check_schema<B: Backend>(
+    backend: &B,
+    env: &str,
+    schema: &Path,
+) -> Result<(), BackendError> {
+    let environment = backend.get_environment(env).await?;
+
+    check_values(env, &environment.values, schema)
+}
+
+/// Check values an environment has (or is about to have) against a schema
+pub fn check_values(
+    env: &str,
+    values: &[EnvironmentValue],
+    schema: &Path,
+) -> Result<(), BackendError> {
+    let schema = EnvSchema::load(schema).map_err(|e| BackendError::Api(e.to_string()))?;
+    let issues = schema.validate(values);
+
+    if issues.is_empty() {
+        return Ok(());
+    }
+
+    let lines: Vec<String> = issues.iter().map(|i| format!("\n  {}", i)).collect();
+
+    Err(BackendError::Api(format!(
+        "Environment '{}' does not match the schema ({} issue(s)):{}",
+        env,
+        issues.len(),
+        lines.concat()
+    )))
+}
+
+async fn cmd_validate<B: Backend>(
+    backend: &B,
+    name: &str,
+    schema: &Path,
+) -> Result<(), BackendError> {
+    check_schema(backend, name, schema).await?;
+
+    eprintln!(
+        "{} Environment '{}' matches {}",
+        "Valid".green(),
+        name.bold(),
+        schema.display()
+    );
+
+    Ok(())
+}
+
+async fn cmd_template<B: Backend>(
+    backend: &B,
+    name: &str,
+    out: Option<PathBuf>,
+) -> Result<(), BackendError> {
+    let environment = backend.get_environment(name).await?;
+    let schema = EnvSchema::from_values(&environment.values);
+
+    let json =
+        serde_json::to_string_pretty(&schema).map_err(|e| BackendError::Api(e.to_string()))?;
+
+    let Some(out) = out else {
+        println!("{}", json);
+        return Ok(());
+    };
+
+    std::fs::write(&out, json + "\n")
+        .map_err(|e| BackendError::Api(format!("Failed to write {}: {}", out.display(), e)))?;
+
+    eprintln!(
+        "{} {} ({} keys). Add patterns, or \"required\": false for optional keys.",
+        "Wrote".green(),
+        out.display(),
+        schema.keys.len()
+    );
+
+    Ok(())
+}
+
+async fn cmd_history<B: Backend>(
     backend: &B,
     env_name: &str,
     key: &str,
-    resource: &str,
-    binding_type: &str,
 ) -> Result<(), BackendError> {
-    // Get resource ID based on type
-    let resource_id = match binding_type {
+    let changes = backend.environment_value_history(env_name, key).await?;
+
+    if changes.is_empty() {
+        eprintln!("No history for '{}' in environment '{}'.", key, env_name);
+        return Ok(());
+    }
+
+    for change in &changes {
+        let badge = change
+            .value_type
+            .as_deref()
+            .map(|t| type_badge(t).to_string())
+            .unwrap_or_default();
+
+        println!(
+            "  {}  {:10} {}",
+            change
+                .changed_at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            badge,
+            display_change(change)
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_revert<B: Backend>(
+    backend: &B,
+    env_name: &str,
+    key: &str,
+    to: DateTime<Utc>,
+) -> Result<(), BackendError> {
+    let changes = backend.environment_value_history(env_name, key).await?;
+
+    let target = changes
+        .iter()
+        .rev()
+        .find(|c| c.changed_at <= to)
+        .ok_or_else(|| {
+            BackendError::NotFound(format!(
+                "No history for '{}' in environment '{}' at or before {}",
+                key,
+                env_name,
+                to.format("%Y-%m-%d %H:%M:%S")
+            ))
+        })?;
+
+    let env = backend.get_environment(env_name).await?;
+    let current = env.values.iter().find(|v| v.key == key);
+
+    let unchanged = match (current, &target.value) {
+        (Some(current), Some(value)) => {
+            &current.value == value && Some(&current.value_type) == target.value_type.as_ref()
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    if unchanged {
+        eprintln!(
+            "'{}' in environment '{}' already has that value.",
+            key, env_name
+        );
+        return Ok(());
+    }
+
+    let value_input = match (current, &target.value) {
+        // The key did not exist at that time
+        (Some(current), None) => EnvironmentValueInput {
+            id: Some(current.id.clone()),
+            key: key.to_string(),
+            value: None,
+            value_type: current.value_type.clone(),
+        },
+        (current, value) => EnvironmentValueInput {
+            id: current.map(|v| v.id.clone()),
+            key: key.to_string(),
+            value: value.clone(),
+            value_type: target
+                .value_type
+                .clone()
+                .unwrap_or_else(|| "var".to_string()),
+        },
+    };
+
+    let input = UpdateEnvironmentInput {
+        name: None,
+        values: Some(vec![value_input]),
+        protected: None,
+    };
+
+    backend.update_environment(env_name, input).await?;
+
+    eprintln!(
+        "{} '{}' in environment '{}' reverted to {} ({}).",
+        "Reverted".green(),
+        key.bold(),
+        env_name.bold(),
+        display_change(target),
+        target.changed_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    Ok(())
+}
+
+/// The given environment, or else the one ow.toml deploys to
+fn project_environment(name: Option<String>) -> Result<String, BackendError> {
+    if let Some(name) = name {
+        return Ok(name);
+    }
+
+    Manifest::find()
+        .map_err(|e| BackendError::Api(e.to_string()))?
+        .and_then(|m| m.environment)
+        .ok_or_else(|| {
+            BackendError::Api(format!(
+                "No environment given and no `environment` in {}",
+                MANIFEST_FILE
+            ))
+        })
+}
+
+/// ID of the resource a binding of `binding_type` points at
+pub async fn resource_id<B: Backend>(
+    backend: &B,
+    binding_type: &str,
+    resource: &str,
+) -> Result<String, BackendError> {
+    match binding_type {
         "assets" | "storage" => {
             let storage = backend.get_storage(resource).await?;
-            storage.id
+            Ok(storage.id)
         }
         "kv" => {
             let kv = backend.get_kv(resource).await?;
-            kv.id
+            Ok(kv.id)
         }
         "database" => {
             let db = backend.get_database(resource).await?;
-            db.id
-        }
-        _ => {
-            return Err(BackendError::Api(format!(
-                "Unknown binding type: {}",
-                binding_type
-            )));
+            Ok(db.id)
         }
-    };
+        _ => Err(BackendError::Api(format!(
+            "Unknown binding type: {}",
+            binding_type
+        ))),
+    }
+}
+
+async fn cmd_bind<B: Backend>(
+    backend: &B,
+    env_name: &str,
+    key: &str,
+    resource: &str,
+    binding_type: &str,
+    dry_run: bool,
+) -> Result<(), BackendError> {
+    let resource_id = resource_id(backend, binding_type, resource).await?;
 
     // Get current environment to find existing binding
     let env = backend.get_environment(env_name).await?;
@@ -397,14 +1200,20 @@ async fn cmd_bind<B: Backend>(
         value_type: binding_type.to_string(),
     };
 
+    if dry_run {
+        print_dry_run(env_name, &value_input, Some(resource));
+        return Ok(());
+    }
+
     let input = UpdateEnvironmentInput {
         name: None,
         values: Some(vec![value_input]),
+        protected: None,
     };
 
     backend.update_environment(env_name, input).await?;
 
-    println!(
+    eprintln!(
         "{} Binding '{}' ({}) added to environment '{}'.",
         "Bound".green(),
         key.bold(),
@@ -414,3 +1223,573 @@ async fn cmd_bind<B: Backend>(
 
     Ok(())
 }
+
+/// Bind an environment key to an account secret (`@account/KEY`)
+async fn cmd_bind_account_secret<B: Backend>(
+    backend: &B,
+    env_name: &str,
+    key: &str,
+    reference: &str,
+    dry_run: bool,
+) -> Result<(), BackendError> {
+    let secret_key = &reference[ACCOUNT_SECRET_PREFIX.len()..];
+
+    let secrets = backend.list_account_secrets().await?;
+
+    if !secrets.iter().any(|s| s.key == secret_key) {
+        return Err(BackendError::NotFound(format!(
+            "Account secret '{}' not found. Create it with `ow secrets set {}`",
+            secret_key, secret_key
+        )));
+    }
+
+    let env = backend.get_environment(env_name).await?;
+
+    let existing_id = env
+        .values
+        .iter()
+        .find(|v| v.key == key)
+        .map(|v| v.id.clone());
+
+    let value_input = EnvironmentValueInput {
+        id: existing_id,
+        key: key.to_string(),
+        value: Some(reference.to_string()),
+        value_type: "secret".to_string(),
+    };
+
+    if dry_run {
+        print_dry_run(env_name, &value_input, None);
+        return Ok(());
+    }
+
+    let input = UpdateEnvironmentInput {
+        name: None,
+        values: Some(vec![value_input]),
+        protected: None,
+    };
+
+    backend.update_environment(env_name, input).await?;
+
+    eprintln!(
+        "{} Secret '{}' in environment '{}' now follows {}.",
+        "Bound".green(),
+        key.bold(),
+        env_name.bold(),
+        reference
+    );
+
+    Ok(())
+}
+
+/// Print the value row a mutation would write. `resource` names the bound
+/// resource, whose ID is the row's value.
+fn print_dry_run(env_name: &str, input: &EnvironmentValueInput, resource: Option<&str>) {
+    let action = match (&input.id, &input.value) {
+        (_, None) => "delete",
+        (Some(_), Some(_)) => "update",
+        (None, Some(_)) => "create",
+    };
+
+    println!("{:12} {}", "Action:".dimmed(), action.bold());
+    println!("{:12} {}", "Environment:".dimmed(), env_name);
+    println!("{:12} {}", "Key:".dimmed(), input.key);
+    println!("{:12} {}", "Type:".dimmed(), input.value_type);
+
+    if let Some(id) = &input.id {
+        println!("{:12} {}", "Row ID:".dimmed(), id);
+    }
+
+    match (&input.value, resource) {
+        (Some(id), Some(name)) => println!("{:12} {} ({})", "Target:".dimmed(), name, id),
+        (Some(value), None) if value.starts_with(ACCOUNT_SECRET_PREFIX) => {
+            println!("{:12} {}", "Target:".dimmed(), value)
+        }
+        (Some(_), None) if input.value_type == "secret" => {
+            println!("{:12} {}", "Value:".dimmed(), "********".dimmed())
+        }
+        (Some(value), None) => println!("{:12} {}", "Value:".dimmed(), value),
+        (None, _) => {}
+    }
+
+    eprintln!();
+    eprintln!("{}", "Dry run, nothing was written.".yellow());
+}
+
+async fn cmd_protect<B: Backend>(
+    backend: &B,
+    name: &str,
+    protected: bool,
+) -> Result<(), BackendError> {
+    let input = UpdateEnvironmentInput {
+        protected: Some(protected),
+        ..Default::default()
+    };
+
+    backend.update_environment(name, input).await?;
+
+    if protected {
+        eprintln!(
+            "{} Environment '{}' is now protected.",
+            "Protected".yellow(),
+            name.bold()
+        );
+    } else {
+        eprintln!(
+            "{} Environment '{}' is no longer protected.",
+            "Unprotected".green(),
+            name.bold()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_link<B: Backend>(
+    backend: &B,
+    env: &str,
+    patterns: &[String],
+) -> Result<(), BackendError> {
+    let environment = backend.get_environment(env).await?;
+    let all_workers = backend.list_workers().await?;
+
+    let mut targets: Vec<&Worker> = Vec::new();
+
+    for pattern in patterns {
+        let matched: Vec<_> = all_workers
+            .iter()
+            .filter(|w| super::glob_match(pattern, &w.name))
+            .collect();
+
+        if matched.is_empty() {
+            let reason = if super::is_glob(pattern) {
+                format!("No workers match '{}'", pattern)
+            } else {
+                format!("Worker '{}' not found", pattern)
+            };
+            return Err(BackendError::NotFound(reason));
+        }
+
+        for worker in matched {
+            if !targets
+                .iter()
+                .any(|t: &&crate::backend::Worker| t.id == worker.id)
+            {
+                targets.push(worker);
+            }
+        }
+    }
+
+    let mut linked = Vec::new();
+    let mut unchanged = Vec::new();
+    let mut failed = Vec::new();
+
+    for worker in targets {
+        let current = worker.environment.as_ref().map(|e| e.id.as_str());
+
+        if current == Some(environment.id.as_str()) {
+            unchanged.push(worker.name.as_str());
+            continue;
+        }
+
+        match backend
+            .link_worker_environment(&worker.id, &environment.id)
+            .await
+        {
+            Ok(()) => linked.push(worker.name.as_str()),
+            Err(e) => failed.push((worker.name.as_str(), e)),
+        }
+    }
+
+    for name in &linked {
+        eprintln!("  {} {}", "✓".green(), name);
+    }
+
+    for name in &unchanged {
+        eprintln!(
+            "  {} {} {}",
+            "─".dimmed(),
+            name,
+            "(already linked)".dimmed()
+        );
+    }
+
+    for (name, error) in &failed {
+        eprintln!("  {} {} {}", "✗".red(), name, error.to_string().dimmed());
+    }
+
+    eprintln!();
+    eprintln!(
+        "{} {} worker(s) to environment '{}', {} already linked.",
+        "Linked".green(),
+        linked.len(),
+        env.bold(),
+        unchanged.len()
+    );
+
+    if !failed.is_empty() {
+        return Err(BackendError::Partial(format!(
+            "{} worker(s) could not be linked",
+            failed.len()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_link_globs() {
+        let backend = MockBackend::new()
+            .with_worker("api-users", None)
+            .with_worker("api-orders", None)
+            .with_worker("web", None);
+
+        let input = CreateEnvironmentInput {
+            name: "staging".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        EnvCommand::Link {
+            env: "staging".to_string(),
+            workers: vec!["api-*".to_string(), "api-users".to_string()],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        for (name, linked) in [("api-users", true), ("api-orders", true), ("web", false)] {
+            let worker = backend.get_worker(name).await.unwrap();
+            assert_eq!(worker.environment.is_some(), linked, "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_unmatched_pattern() {
+        let backend = MockBackend::new().with_worker("web", None);
+
+        let input = CreateEnvironmentInput {
+            name: "staging".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        let result = EnvCommand::Link {
+            env: "staging".to_string(),
+            workers: vec!["web".to_string(), "api-*".to_string()],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+        assert!(
+            backend
+                .get_worker("web")
+                .await
+                .unwrap()
+                .environment
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate_generate_and_redeploy() {
+        let backend = MockBackend::new()
+            .with_worker("api", None)
+            .with_worker("web", None);
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        cmd_set(&backend, "prod", "SESSION_KEY", "old", true, false)
+            .await
+            .unwrap();
+
+        let env = backend.get_environment("prod").await.unwrap();
+        backend
+            .link_worker_environment(&backend.get_worker("api").await.unwrap().id, &env.id)
+            .await
+            .unwrap();
+
+        for name in ["api", "web"] {
+            let input = crate::backend::DeployInput {
+                code: b"export default {}".to_vec(),
+                code_type: "javascript".to_string(),
+                message: None,
+                hash: None,
+                authorization: Default::default(),
+            };
+            backend.deploy_worker(name, input).await.unwrap();
+        }
+
+        EnvCommand::Rotate {
+            env: "prod".to_string(),
+            key: "SESSION_KEY".to_string(),
+            value: None,
+            generate: Some(40),
+            redeploy: true,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let env = backend.get_environment("prod").await.unwrap();
+        let secret = env.values.iter().find(|v| v.key == "SESSION_KEY").unwrap();
+        assert_eq!(secret.value.len(), 40);
+        assert!(secret.value.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(secret.rotated_at.is_some());
+
+        // Only the linked worker is redeployed
+        assert_eq!(backend.deployment_versions("api"), vec![1, 2]);
+        assert_eq!(backend.deployment_versions("web"), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_rejects_plain_variable() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+
+        let result = EnvCommand::Rotate {
+            env: "prod".to_string(),
+            key: "API_URL".to_string(),
+            value: Some("https://other.example.com".to_string()),
+            generate: None,
+            redeploy: false,
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = "2026-03-14T09:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(parse_timestamp("2026-03-14 09:30:00").unwrap(), expected);
+        assert_eq!(
+            parse_timestamp("2026-03-14T10:30:00+01:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp("2026-03-14").unwrap(),
+            "2026-03-14T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revert_restores_previous_value() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://good.example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+
+        let good = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://bad.example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+        cmd_set(&backend, "prod", "NEW_FLAG", "on", false, false)
+            .await
+            .unwrap();
+
+        let history = backend
+            .environment_value_history("prod", "API_URL")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+
+        cmd_revert(&backend, "prod", "API_URL", good).await.unwrap();
+        cmd_revert(&backend, "prod", "NEW_FLAG", good)
+            .await
+            .unwrap_err();
+
+        let env = backend.get_environment("prod").await.unwrap();
+        let value = env.values.iter().find(|v| v.key == "API_URL").unwrap();
+        assert_eq!(value.value, "https://good.example.com");
+
+        // The revert itself is recorded
+        let history = backend
+            .environment_value_history("prod", "API_URL")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_values_at() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://v1.example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+        cmd_set(&backend, "prod", "OLD_FLAG", "on", false, false)
+            .await
+            .unwrap();
+
+        let then = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://v2.example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+        cmd_unset(&backend, "prod", "OLD_FLAG", false)
+            .await
+            .unwrap();
+        cmd_set(&backend, "prod", "TOKEN", "abc", true, false)
+            .await
+            .unwrap();
+
+        let past = backend.environment_values_at("prod", then).await.unwrap();
+        let keys: Vec<(&str, &str)> = past
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![("API_URL", "https://v1.example.com"), ("OLD_FLAG", "on")]
+        );
+
+        let now = backend.get_environment("prod").await.unwrap();
+        let changes = diff_values(&past, &now.values);
+        let summary: Vec<String> = changes
+            .iter()
+            .map(|c| match c {
+                ValueChange::Added(v) => format!("+{}", v.key),
+                ValueChange::Removed(v) => format!("-{}", v.key),
+                ValueChange::Changed { now, .. } => format!("~{}={}", now.key, now.value),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec!["~API_URL=https://v2.example.com", "-OLD_FLAG", "+TOKEN"]
+        );
+
+        let before = then - chrono::Duration::days(1);
+        assert!(
+            backend
+                .environment_values_at("prod", before)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_writes_nothing() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        let input = crate::backend::CreateKvInput {
+            name: "cache".to_string(),
+            desc: None,
+        };
+        backend.create_kv(input).await.unwrap();
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://v1.example.com",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+        let before = backend.get_environment("prod").await.unwrap().values;
+
+        cmd_set(
+            &backend,
+            "prod",
+            "API_URL",
+            "https://v2.example.com",
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        cmd_unset(&backend, "prod", "API_URL", true).await.unwrap();
+        cmd_bind(&backend, "prod", "CACHE", "cache", "kv", true)
+            .await
+            .unwrap();
+
+        let after = backend.get_environment("prod").await.unwrap().values;
+        assert_eq!(after.len(), before.len());
+        assert_eq!(after[0].value, "https://v1.example.com");
+
+        // The resource is still resolved, so a typo fails the dry run
+        let result = cmd_bind(&backend, "prod", "CACHE", "cahce", "kv", true).await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
@@ -0,0 +1,362 @@
+use crate::backend::{Backend, BackendError, KvEntry};
+use clap::Subcommand;
+use colored::Colorize;
+use serde_json::Value;
+
+/// Key prefix of flags in a KV namespace (`flags:new-checkout`)
+const FLAG_PREFIX: &str = "flags:";
+
+#[derive(Subcommand)]
+pub enum FlagsCommand {
+    /// List flags and their state
+    #[command(
+        alias = "ls",
+        after_help = "Examples:\n  \
+        ow flags list my-api          (KV namespace bound to the worker)\n  \
+        ow flags list flags-kv        (KV namespace by name)"
+    )]
+    List {
+        /// Worker name or KV namespace name
+        target: String,
+
+        /// KV binding to use when the worker has several
+        #[arg(long)]
+        binding: Option<String>,
+    },
+
+    /// Turn a flag on, for everyone or a percentage of requests
+    #[command(after_help = "Examples:\n  \
+        ow flags enable my-api new-checkout\n  \
+        ow flags enable my-api new-checkout --percent 10")]
+    Enable {
+        /// Worker name or KV namespace name
+        target: String,
+
+        /// Flag name
+        flag: String,
+
+        /// Roll out to this percentage of requests (0-100)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        percent: Option<u8>,
+
+        /// KV binding to use when the worker has several
+        #[arg(long)]
+        binding: Option<String>,
+    },
+
+    /// Turn a flag off
+    #[command(after_help = "Example:\n  ow flags disable my-api new-checkout")]
+    Disable {
+        /// Worker name or KV namespace name
+        target: String,
+
+        /// Flag name
+        flag: String,
+
+        /// KV binding to use when the worker has several
+        #[arg(long)]
+        binding: Option<String>,
+    },
+}
+
+/// Flag state as stored in KV: `true`, `false` or a rollout percentage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlagValue {
+    On,
+    Off,
+    Percent(u8),
+}
+
+impl FlagValue {
+    fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(true) => Some(Self::On),
+            Value::Bool(false) => Some(Self::Off),
+            Value::Number(n) => n
+                .as_u64()
+                .filter(|p| *p <= 100)
+                .map(|p| Self::Percent(p as u8)),
+            _ => None,
+        }
+    }
+
+    fn to_json(self) -> Value {
+        match self {
+            Self::On => Value::Bool(true),
+            Self::Off => Value::Bool(false),
+            Self::Percent(p) => Value::from(p),
+        }
+    }
+}
+
+impl std::fmt::Display for FlagValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::On => write!(f, "on"),
+            Self::Off => write!(f, "off"),
+            Self::Percent(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+impl FlagsCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::List { target, binding } => cmd_list(backend, &target, binding).await,
+            Self::Enable {
+                target,
+                flag,
+                percent,
+                binding,
+            } => {
+                let value = match percent {
+                    Some(100) | None => FlagValue::On,
+                    Some(0) => FlagValue::Off,
+                    Some(p) => FlagValue::Percent(p),
+                };
+
+                cmd_set(backend, &target, &flag, value, binding).await
+            }
+            Self::Disable {
+                target,
+                flag,
+                binding,
+            } => cmd_set(backend, &target, &flag, FlagValue::Off, binding).await,
+        }
+    }
+}
+
+/// KV namespace holding the flags: `target` itself, or the namespace bound to
+/// the worker named `target`
+async fn resolve_namespace<B: Backend>(
+    backend: &B,
+    target: &str,
+    binding: Option<&str>,
+) -> Result<String, BackendError> {
+    match backend.get_kv(target).await {
+        Ok(namespace) => return Ok(namespace.name),
+        Err(BackendError::NotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let worker = match backend.get_worker(target).await {
+        Ok(worker) => worker,
+        Err(BackendError::NotFound(_)) => {
+            return Err(BackendError::NotFound(format!(
+                "No KV namespace or worker named '{}'",
+                target
+            )));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let Some(env_ref) = &worker.environment else {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' has no environment, so no KV binding. Pass a namespace name instead.",
+            target
+        )));
+    };
+
+    let environment = backend.get_environment(&env_ref.name).await?;
+
+    let bindings: Vec<_> = environment
+        .values
+        .iter()
+        .filter(|v| v.value_type == "kv")
+        .filter(|v| binding.is_none_or(|b| v.key == b))
+        .collect();
+
+    let kv_id = match bindings.as_slice() {
+        [one] => &one.value,
+        [] => {
+            return Err(BackendError::Api(match binding {
+                Some(b) => format!("Environment '{}' has no KV binding '{}'", env_ref.name, b),
+                None => format!("Environment '{}' has no KV binding", env_ref.name),
+            }));
+        }
+        several => {
+            let keys: Vec<&str> = several.iter().map(|v| v.key.as_str()).collect();
+            return Err(BackendError::Api(format!(
+                "Worker '{}' has several KV bindings ({}). Pick one with --binding.",
+                target,
+                keys.join(", ")
+            )));
+        }
+    };
+
+    backend
+        .list_kv()
+        .await?
+        .into_iter()
+        .find(|ns| &ns.id == kv_id)
+        .map(|ns| ns.name)
+        .ok_or_else(|| BackendError::NotFound(format!("KV namespace '{}' not found", kv_id)))
+}
+
+async fn cmd_list<B: Backend>(
+    backend: &B,
+    target: &str,
+    binding: Option<String>,
+) -> Result<(), BackendError> {
+    let namespace = resolve_namespace(backend, target, binding.as_deref()).await?;
+    let entries = backend.list_kv_entries(&namespace, FLAG_PREFIX).await?;
+
+    if entries.is_empty() {
+        eprintln!("No flags in KV namespace '{}'.", namespace.bold());
+        return Ok(());
+    }
+
+    println!(
+        "{} {}",
+        "Flags".bold(),
+        format!("(KV namespace {})", namespace).dimmed()
+    );
+    println!("{}", "─".repeat(60));
+
+    for entry in entries {
+        let name = &entry.key[FLAG_PREFIX.len()..];
+
+        let state = match FlagValue::from_json(&entry.value) {
+            Some(FlagValue::On) => "on".green(),
+            Some(FlagValue::Off) => "off".dimmed(),
+            Some(value) => value.to_string().yellow(),
+            None => format!("invalid: {}", entry.value).red(),
+        };
+
+        println!("  {:30} {}", name, state);
+    }
+
+    Ok(())
+}
+
+async fn cmd_set<B: Backend>(
+    backend: &B,
+    target: &str,
+    flag: &str,
+    value: FlagValue,
+    binding: Option<String>,
+) -> Result<(), BackendError> {
+    let namespace = resolve_namespace(backend, target, binding.as_deref()).await?;
+
+    let entry = KvEntry {
+        key: format!("{}{}", FLAG_PREFIX, flag),
+        value: value.to_json(),
+    };
+
+    backend.put_kv_entries(&namespace, vec![entry]).await?;
+
+    let state = match value {
+        FlagValue::On => "on".green(),
+        FlagValue::Off => "off".dimmed(),
+        FlagValue::Percent(_) => value.to_string().yellow(),
+    };
+
+    eprintln!(
+        "Flag '{}' is now {} (KV namespace {}).",
+        flag.bold(),
+        state,
+        namespace
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{
+        CreateEnvironmentInput, CreateKvInput, EnvironmentValueInput, UpdateEnvironmentInput,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_flag_value_json() {
+        assert_eq!(FlagValue::from_json(&json!(true)), Some(FlagValue::On));
+        assert_eq!(FlagValue::from_json(&json!(false)), Some(FlagValue::Off));
+        assert_eq!(
+            FlagValue::from_json(&json!(25)),
+            Some(FlagValue::Percent(25))
+        );
+        assert_eq!(FlagValue::from_json(&json!(250)), None);
+        assert_eq!(FlagValue::from_json(&json!("yes")), None);
+        assert_eq!(FlagValue::Percent(10).to_json(), json!(10));
+    }
+
+    #[tokio::test]
+    async fn test_enable_through_worker_binding() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        let kv = backend
+            .create_kv(CreateKvInput {
+                name: "api-flags".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let environment = backend
+            .create_environment(CreateEnvironmentInput {
+                name: "prod".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let input = UpdateEnvironmentInput {
+            name: None,
+            values: Some(vec![EnvironmentValueInput {
+                id: None,
+                key: "FLAGS".to_string(),
+                value: Some(kv.id),
+                value_type: "kv".to_string(),
+            }]),
+            protected: None,
+        };
+        backend.update_environment("prod", input).await.unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        backend
+            .link_worker_environment(&worker.id, &environment.id)
+            .await
+            .unwrap();
+
+        let result = FlagsCommand::Enable {
+            target: "api".to_string(),
+            flag: "new-checkout".to_string(),
+            percent: Some(10),
+            binding: None,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            backend.kv_entries("api-flags"),
+            vec![KvEntry {
+                key: "flags:new-checkout".to_string(),
+                value: json!(10),
+            }]
+        );
+
+        let result = FlagsCommand::Disable {
+            target: "api-flags".to_string(),
+            flag: "new-checkout".to_string(),
+            binding: None,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(backend.kv_entries("api-flags")[0].value, json!(false));
+
+        let result = FlagsCommand::List {
+            target: "nope".to_string(),
+            binding: None,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
@@ -0,0 +1,240 @@
+use crate::commands::latency::{self, LAYERS, LatencyError};
+use crate::commands::migrate;
+use crate::config::{AliasConfig, ConfigError, PlatformStorageConfig};
+use crate::s3::{S3Client, S3Config};
+use colored::Colorize;
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+use std::time::{Duration, Instant};
+
+/// Tables a self-hosted install can't run without
+const CRITICAL_TABLES: &[&str] = &[
+    "users",
+    "workers",
+    "worker_deployments",
+    "environments",
+    "projects",
+    "storage_configs",
+    "kv_configs",
+    "database_configs",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum HealthError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("{0}")]
+    Latency(#[from] LatencyError),
+
+    #[error("{0} check(s) degraded")]
+    Degraded(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Ok,
+    Degraded,
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub latency_ms: Option<f64>,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn ok(name: &str, start: Instant, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Ok,
+            latency_ms: Some(start.elapsed().as_secs_f64() * 1000.0),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn degraded(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Degraded,
+            latency_ms: None,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn skipped(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Skipped,
+            latency_ms: None,
+            detail: detail.into(),
+        }
+    }
+}
+
+pub async fn run(alias: Option<String>, timeout: u64) -> Result<(), HealthError> {
+    let (alias_name, alias_config) = latency::resolve_alias(&alias)?;
+
+    eprintln!("{} Checking '{}'", "→".cyan(), alias_name.green().bold());
+    eprintln!();
+
+    let checks = match alias_config {
+        AliasConfig::Api { url, insecure, .. } => check_api(&url, insecure, timeout).await?,
+        AliasConfig::Db {
+            database_url,
+            storage,
+            ..
+        } => check_db(&database_url, storage.as_ref(), timeout).await,
+    };
+
+    print_checks(&checks);
+
+    let degraded = checks
+        .iter()
+        .filter(|c| c.status == Status::Degraded)
+        .count();
+
+    if degraded > 0 {
+        return Err(HealthError::Degraded(degraded));
+    }
+
+    Ok(())
+}
+
+pub async fn check_api(url: &str, insecure: bool, timeout: u64) -> Result<Vec<Check>, HealthError> {
+    let client = latency::http_client(url, insecure, timeout)?;
+    let mut checks = Vec::new();
+
+    for layer in LAYERS {
+        let start = Instant::now();
+        let result = client
+            .get(latency::latency_url(url, layer.path))
+            .send()
+            .await;
+
+        let check = match result {
+            Ok(resp) if resp.status().as_u16() == 418 => {
+                Check::skipped(layer.name, "not configured")
+            }
+            Ok(resp) if resp.status().is_success() => Check::ok(layer.name, start, layer.label),
+            Ok(resp) => Check::degraded(layer.name, format!("HTTP {}", resp.status())),
+            Err(e) => Check::degraded(layer.name, e.to_string()),
+        };
+
+        checks.push(check);
+    }
+
+    Ok(checks)
+}
+
+pub async fn check_db(
+    database_url: &str,
+    storage: Option<&PlatformStorageConfig>,
+    timeout: u64,
+) -> Vec<Check> {
+    let start = Instant::now();
+
+    let pool = match PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(timeout))
+        .connect(database_url)
+        .await
+    {
+        Ok(pool) => pool,
+        Err(e) => return vec![Check::degraded("database", e.to_string())],
+    };
+
+    let mut checks = vec![Check::ok("database", start, "connected")];
+
+    for table in CRITICAL_TABLES {
+        checks.push(check_table(&pool, table).await);
+    }
+
+    checks.push(check_migrations(&pool).await);
+    checks.push(check_storage(storage).await);
+
+    checks
+}
+
+async fn check_table(pool: &PgPool, table: &str) -> Check {
+    let start = Instant::now();
+
+    // Table names come from CRITICAL_TABLES, never from input
+    let result: Result<i64, _> = sqlx::query_scalar(&format!("SELECT count(*) FROM {}", table))
+        .fetch_one(pool)
+        .await;
+
+    match result {
+        Ok(count) => Check::ok(table, start, format!("{} rows", count)),
+        Err(e) => Check::degraded(table, e.to_string()),
+    }
+}
+
+pub async fn check_migrations(pool: &PgPool) -> Check {
+    let start = Instant::now();
+
+    let migrations = match migrate::migration_status(pool).await {
+        Ok(migrations) => migrations,
+        Err(e) => return Check::degraded("migrations", e.to_string()),
+    };
+
+    let pending = migrations.iter().filter(|m| m.status == "pending").count();
+    let modified = migrations.iter().filter(|m| m.status == "modified").count();
+
+    match (pending, modified) {
+        (0, 0) => Check::ok("migrations", start, format!("{} applied", migrations.len())),
+        _ => Check::degraded(
+            "migrations",
+            format!("{} pending, {} modified", pending, modified),
+        ),
+    }
+}
+
+async fn check_storage(storage: Option<&PlatformStorageConfig>) -> Check {
+    let Some(storage) = storage else {
+        return Check::skipped("storage", "not configured");
+    };
+
+    let client = S3Client::new(S3Config {
+        bucket: storage.bucket.clone(),
+        endpoint: storage.endpoint.clone(),
+        access_key_id: storage.access_key_id.clone(),
+        secret_access_key: storage.secret_access_key.clone(),
+        region: storage.region.clone(),
+        prefix: storage.prefix.clone(),
+        session_token: storage.session_token.clone(),
+    });
+
+    let start = Instant::now();
+
+    match client.check_access().await {
+        Ok(()) => Check::ok("storage", start, storage.bucket.clone()),
+        Err(e) => Check::degraded("storage", e),
+    }
+}
+
+pub fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let (icon, name) = match check.status {
+            Status::Ok => ("✓".green(), check.name.normal()),
+            Status::Degraded => ("✗".red(), check.name.red()),
+            Status::Skipped => ("─".dimmed(), check.name.dimmed()),
+        };
+
+        let latency = check
+            .latency_ms
+            .map(|ms| format!("{:.1} ms", ms))
+            .unwrap_or_default();
+
+        println!(
+            "  {} {:20} {:>10}  {}",
+            icon,
+            name,
+            latency,
+            check.detail.dimmed()
+        );
+    }
+}
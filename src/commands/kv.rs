@@ -1,24 +1,41 @@
-use crate::backend::{Backend, BackendError, CreateKvInput};
+use crate::backend::{Backend, BackendError, CreateKvInput, KvEntry, UpdateKvInput};
+use crate::quiet;
 use clap::Subcommand;
 use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum KvCommand {
     /// List all KV namespaces
     #[command(alias = "ls")]
-    List,
+    List {
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
 
-    /// Show KV namespace details
-    #[command(after_help = "Example:\n  ow kv get my-cache")]
+    /// Show KV namespace details, or the value of a key
+    #[command(after_help = "Examples:\n  \
+        ow kv get my-cache                 Namespace details\n  \
+        ow kv get my-cache user:42         Value of a key (strings raw, anything else as JSON)")]
     Get {
         /// KV namespace name
         name: String,
+
+        /// Key to read instead of the namespace details
+        key: Option<String>,
+
+        /// Only print the name (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
     },
 
     /// Create a new KV namespace for key-value storage
     #[command(after_help = "Examples:\n  \
         ow kv create my-cache\n  \
-        ow kv create sessions -d \"User sessions\"")]
+        ow kv create sessions -d \"User sessions\"\n  \
+        ow kv create flags --seed flags.json   (JSON object of key/values)")]
     Create {
         /// KV namespace name
         name: String,
@@ -26,32 +43,167 @@ pub enum KvCommand {
         /// Description of what this namespace stores
         #[arg(short, long)]
         description: Option<String>,
+
+        /// Load initial entries from a JSON object file ({"key": value, ...})
+        #[arg(long)]
+        seed: Option<PathBuf>,
+    },
+
+    /// Set the value of a key
+    #[command(after_help = "Examples:\n  \
+        ow kv put my-cache greeting hello\n  \
+        ow kv put flags limits '{\"max\": 25}' --json\n  \
+        ow kv put sessions abc123 user-42 --ttl 1h")]
+    Put {
+        /// KV namespace name
+        name: String,
+
+        /// Key
+        key: String,
+
+        /// Value, stored as a string unless --json is given
+        value: String,
+
+        /// Parse the value as JSON (number, boolean, object...)
+        #[arg(long)]
+        json: bool,
+
+        /// Expire the key after this long (e.g. 30m, 1h, 7d)
+        #[arg(long)]
+        ttl: Option<String>,
+    },
+
+    /// List the keys of a KV namespace, one per line
+    #[command(after_help = "Examples:\n  \
+        ow kv keys my-cache\n  \
+        ow kv keys my-cache --prefix user:")]
+    Keys {
+        /// KV namespace name
+        name: String,
+
+        /// Only keys starting with this prefix
+        #[arg(long, default_value = "")]
+        prefix: String,
     },
 
-    /// Delete a KV namespace and all its data
-    #[command(alias = "rm", after_help = "Example:\n  ow kv delete old-cache")]
+    /// Update a KV namespace's details
+    #[command(after_help = "Examples:\n  \
+        ow kv update my-cache -d \"API response cache\"\n  \
+        ow kv update my-cache -d \"\"   (clear)")]
+    Update {
+        /// KV namespace name
+        name: String,
+
+        /// New description (empty to clear)
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Rename a KV namespace (bindings keep working)
+    #[command(
+        alias = "mv",
+        after_help = "Example:\n  ow kv rename cache sessions-cache"
+    )]
+    Rename {
+        /// Current name
+        name: String,
+
+        /// New name
+        new_name: String,
+    },
+
+    /// Delete a KV namespace and all its data, or a single key
+    #[command(
+        alias = "rm",
+        after_help = "Examples:\n  \
+        ow kv delete old-cache             Whole namespace (asks first)\n  \
+        ow kv delete my-cache user:42      One key"
+    )]
     Delete {
-        /// KV namespace name to delete
+        /// KV namespace name
         name: String,
+
+        /// Key to delete instead of the whole namespace
+        key: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Delete keys whose TTL has passed, in batches (DB aliases)
+    #[command(after_help = "Examples:\n  \
+        ow local kv expire-scan                 All namespaces\n  \
+        ow local kv expire-scan sessions --batch-size 5000\n  \
+        ow local kv expire-scan --dry-run       Only count expired keys\n\n\
+        Expired keys are never served, but stay in kv_data until deleted. \
+        Run this on a schedule to reclaim the space.")]
+    ExpireScan {
+        /// KV namespace (default: all)
+        name: Option<String>,
+
+        /// Keys deleted per statement
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u32).range(1..))]
+        batch_size: u32,
+
+        /// Count expired keys without deleting them
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
 impl KvCommand {
     pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
         match self {
-            Self::List => cmd_list(backend).await,
-            Self::Get { name } => cmd_get(backend, &name).await,
-            Self::Create { name, description } => cmd_create(backend, name, description).await,
-            Self::Delete { name } => cmd_delete(backend, &name).await,
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::Get {
+                name,
+                key: Some(key),
+                ..
+            } => cmd_get_key(backend, &name, &key).await,
+            Self::Get { name, ids, .. } => cmd_get(backend, &name, ids).await,
+            Self::Create {
+                name,
+                description,
+                seed,
+            } => cmd_create(backend, name, description, seed).await,
+            Self::Put {
+                name,
+                key,
+                value,
+                json,
+                ttl,
+            } => cmd_put(backend, &name, key, value, json, ttl.as_deref()).await,
+            Self::Keys { name, prefix } => cmd_keys(backend, &name, &prefix).await,
+            Self::Update { name, description } => cmd_update(backend, &name, description).await,
+            Self::Rename { name, new_name } => cmd_rename(backend, &name, new_name).await,
+            Self::Delete {
+                name,
+                key: Some(key),
+                ..
+            } => cmd_delete_key(backend, &name, &key).await,
+            Self::Delete { name, yes, .. } => cmd_delete(backend, &name, yes).await,
+            Self::ExpireScan {
+                name,
+                batch_size,
+                dry_run,
+            } => cmd_expire_scan(backend, name, batch_size, dry_run).await,
         }
     }
 }
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
     let namespaces = backend.list_kv().await?;
 
+    if ids {
+        for namespace in &namespaces {
+            quiet::print(&namespace.name);
+        }
+        return Ok(());
+    }
+
     if namespaces.is_empty() {
-        println!("No KV namespaces found.");
+        eprintln!("No KV namespaces found.");
         return Ok(());
     }
 
@@ -71,9 +223,14 @@ async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
     Ok(())
 }
 
-async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_get<B: Backend>(backend: &B, name: &str, ids: bool) -> Result<(), BackendError> {
     let ns = backend.get_kv(name).await?;
 
+    if ids {
+        quiet::print(&ns.name);
+        return Ok(());
+    }
+
     println!("{:12} {}", "Name:".dimmed(), ns.name.bold());
     println!("{:12} {}", "ID:".dimmed(), ns.id);
 
@@ -96,11 +253,74 @@ async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError
     Ok(())
 }
 
+async fn cmd_get_key<B: Backend>(backend: &B, name: &str, key: &str) -> Result<(), BackendError> {
+    let entry = backend.get_kv_entry(name, key).await?;
+    println!("{}", format_value(&entry.value));
+    Ok(())
+}
+
+/// Strings print as-is so they can be piped; other values as JSON
+fn format_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+async fn cmd_put<B: Backend>(
+    backend: &B,
+    name: &str,
+    key: String,
+    value: String,
+    json: bool,
+    ttl: Option<&str>,
+) -> Result<(), BackendError> {
+    let value = if json {
+        serde_json::from_str(&value)
+            .map_err(|e| BackendError::Usage(format!("Invalid JSON value: {}", e)))?
+    } else {
+        serde_json::Value::String(value)
+    };
+
+    let ttl = ttl
+        .map(super::parse_duration)
+        .transpose()
+        .map_err(BackendError::Api)?;
+
+    let entry = KvEntry { key, value };
+    let key = entry.key.clone();
+
+    backend.put_kv_entry(name, entry, ttl).await?;
+
+    eprintln!("{} '{}' in '{}'", "Set".green(), key.bold(), name);
+
+    Ok(())
+}
+
+async fn cmd_keys<B: Backend>(backend: &B, name: &str, prefix: &str) -> Result<(), BackendError> {
+    let entries = backend.list_kv_entries(name, prefix).await?;
+
+    if entries.is_empty() {
+        eprintln!("No keys found.");
+        return Ok(());
+    }
+
+    for entry in entries {
+        println!("{}", entry.key);
+    }
+
+    Ok(())
+}
+
 async fn cmd_create<B: Backend>(
     backend: &B,
     name: String,
     description: Option<String>,
+    seed: Option<PathBuf>,
 ) -> Result<(), BackendError> {
+    // Read the seed first so a bad file doesn't leave an empty namespace behind
+    let entries = seed.as_deref().map(read_seed).transpose()?;
+
     let input = CreateKvInput {
         name,
         desc: description,
@@ -108,19 +328,106 @@ async fn cmd_create<B: Backend>(
 
     let ns = backend.create_kv(input).await?;
 
-    println!(
+    eprintln!(
         "{} KV namespace '{}' created.",
         "Created".green(),
         ns.name.bold()
     );
 
+    let Some(entries) = entries else {
+        return Ok(());
+    };
+
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let count = backend.put_kv_entries(&ns.name, entries).await?;
+
+    eprintln!("{} Seeded {} key(s).", "Seeded".green(), count);
+
     Ok(())
 }
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+fn read_seed(path: &Path) -> Result<Vec<KvEntry>, BackendError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        BackendError::Api(format!(
+            "Failed to read seed file '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    parse_seed(&content)
+        .map_err(|e| BackendError::Api(format!("Invalid seed file '{}': {}", path.display(), e)))
+}
+
+/// Entries from a JSON object; values keep their JSON type
+fn parse_seed(content: &str) -> Result<Vec<KvEntry>, String> {
+    match serde_json::from_str(content).map_err(|e| e.to_string())? {
+        serde_json::Value::Object(map) => Ok(map
+            .into_iter()
+            .map(|(key, value)| KvEntry { key, value })
+            .collect()),
+        _ => Err("expected a JSON object of key/value pairs".to_string()),
+    }
+}
+
+async fn cmd_update<B: Backend>(
+    backend: &B,
+    name: &str,
+    description: Option<String>,
+) -> Result<(), BackendError> {
+    if description.is_none() {
+        return Err(BackendError::Usage(
+            "Nothing to update. Pass --description.".to_string(),
+        ));
+    }
+
+    let input = UpdateKvInput {
+        desc: description,
+        ..Default::default()
+    };
+
+    backend.update_kv(name, input).await?;
+
+    eprintln!(
+        "{} KV namespace '{}' updated.",
+        "Updated".green(),
+        name.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_rename<B: Backend>(
+    backend: &B,
+    name: &str,
+    new_name: String,
+) -> Result<(), BackendError> {
+    let input = UpdateKvInput {
+        name: Some(new_name),
+        ..Default::default()
+    };
+
+    let updated = backend.update_kv(name, input).await?;
+
+    eprintln!(
+        "{} KV namespace '{}' renamed to '{}'.",
+        "Renamed".green(),
+        name,
+        updated.name.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("KV namespace '{}'", name), yes)?;
+
     backend.delete_kv(name).await?;
 
-    println!(
+    eprintln!(
         "{} KV namespace '{}' deleted.",
         "Deleted".red(),
         name.bold()
@@ -128,3 +435,244 @@ async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendEr
 
     Ok(())
 }
+
+async fn cmd_delete_key<B: Backend>(
+    backend: &B,
+    name: &str,
+    key: &str,
+) -> Result<(), BackendError> {
+    backend.delete_kv_entry(name, key).await?;
+
+    eprintln!("{} '{}' from '{}'", "Deleted".red(), key.bold(), name);
+
+    Ok(())
+}
+
+async fn cmd_expire_scan<B: Backend>(
+    backend: &B,
+    name: Option<String>,
+    batch_size: u32,
+    dry_run: bool,
+) -> Result<(), BackendError> {
+    let names = match name {
+        Some(name) => vec![name],
+        None => backend
+            .list_kv()
+            .await?
+            .into_iter()
+            .map(|n| n.name)
+            .collect(),
+    };
+
+    let progress = io::stderr().is_terminal();
+    let mut total = 0;
+
+    for name in &names {
+        let expired = backend.count_expired_kv_entries(name).await?;
+
+        if dry_run || expired == 0 {
+            println!("  {:24} {} expired", name.bold(), expired);
+            total += expired;
+            continue;
+        }
+
+        let mut deleted = 0;
+
+        loop {
+            let batch = backend.delete_expired_kv_entries(name, batch_size).await?;
+            deleted += batch;
+
+            if progress {
+                eprint!(
+                    "\r\x1b[2K  {:24} {}",
+                    name.bold(),
+                    format!("{}/{} deleted", deleted, expired).dimmed()
+                );
+                io::stderr().flush().ok();
+            }
+
+            // Keys expiring during the scan are picked up too
+            if batch < batch_size as u64 {
+                break;
+            }
+        }
+
+        if progress {
+            eprint!("\r\x1b[2K");
+        }
+
+        println!("  {:24} {} deleted", name.bold(), deleted);
+        total += deleted;
+    }
+
+    eprintln!();
+
+    if dry_run {
+        eprintln!(
+            "{} expired keys in {} namespaces (dry run, nothing deleted)",
+            total.to_string().bold(),
+            names.len()
+        );
+    } else {
+        eprintln!(
+            "{} Deleted {} expired keys from {} namespaces",
+            "✓".green(),
+            total.to_string().bold(),
+            names.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_seed() {
+        let entries = parse_seed(r#"{"beta": true, "limit": 10, "greeting": "hi"}"#).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&KvEntry {
+            key: "limit".to_string(),
+            value: json!(10),
+        }));
+
+        assert!(parse_seed("[1, 2]").is_err());
+        assert!(parse_seed("{").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_with_seed() {
+        let backend = MockBackend::new();
+
+        let mut seed = NamedTempFile::with_suffix(".json").unwrap();
+        write!(seed, r#"{{"dark-mode": true, "max-items": 25}}"#).unwrap();
+
+        let result = KvCommand::Create {
+            name: "flags".to_string(),
+            description: None,
+            seed: Some(seed.path().to_path_buf()),
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(backend.kv_entries("flags").len(), 2);
+
+        // An invalid seed fails before anything is created
+        let result = KvCommand::Create {
+            name: "broken".to_string(),
+            description: None,
+            seed: Some(PathBuf::from("missing.json")),
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_err());
+        assert!(backend.kv_entries("broken").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_ids_print_names() {
+        let backend = MockBackend::new();
+        let input = CreateKvInput {
+            name: "cache".to_string(),
+            desc: None,
+        };
+        backend.create_kv(input).await.unwrap();
+
+        KvCommand::List { ids: true }.run(&backend).await.unwrap();
+        KvCommand::Get {
+            name: "cache".to_string(),
+            key: None,
+            ids: true,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        assert_eq!(quiet::take(), vec!["cache", "cache"]);
+    }
+
+    #[tokio::test]
+    async fn test_expire_scan() {
+        let backend = MockBackend::new().with_expired_kv_entries("sessions", 2500);
+
+        for name in ["sessions", "flags"] {
+            let input = CreateKvInput {
+                name: name.to_string(),
+                desc: None,
+            };
+            backend.create_kv(input).await.unwrap();
+        }
+
+        let scan = |dry_run| KvCommand::ExpireScan {
+            name: None,
+            batch_size: 1000,
+            dry_run,
+        };
+
+        scan(true).run(&backend).await.unwrap();
+        assert_eq!(
+            backend.count_expired_kv_entries("sessions").await.unwrap(),
+            2500
+        );
+
+        scan(false).run(&backend).await.unwrap();
+        assert_eq!(
+            backend.count_expired_kv_entries("sessions").await.unwrap(),
+            0
+        );
+
+        let result = KvCommand::ExpireScan {
+            name: Some("missing".to_string()),
+            batch_size: 1000,
+            dry_run: false,
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_key_operations() {
+        let backend = MockBackend::new();
+
+        let put = |key: &str, value: &str, json| KvCommand::Put {
+            name: "cache".to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            json,
+            ttl: Some("1h".to_string()),
+        };
+
+        put("user:1", "alice", false).run(&backend).await.unwrap();
+        put("limit", "25", true).run(&backend).await.unwrap();
+        assert!(put("bad", "{", true).run(&backend).await.is_err());
+
+        let entry = backend.get_kv_entry("cache", "limit").await.unwrap();
+        assert_eq!(entry.value, json!(25));
+
+        let entry = backend.get_kv_entry("cache", "user:1").await.unwrap();
+        assert_eq!(format_value(&entry.value), "alice");
+        assert_eq!(format_value(&json!({"a": 1})), "{\n  \"a\": 1\n}");
+
+        let keys = backend.list_kv_entries("cache", "user:").await.unwrap();
+        assert_eq!(keys.len(), 1);
+
+        let delete = || KvCommand::Delete {
+            name: "cache".to_string(),
+            key: Some("limit".to_string()),
+            yes: false,
+        };
+
+        delete().run(&backend).await.unwrap();
+        let result = delete().run(&backend).await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+        assert_eq!(backend.kv_entries("cache").len(), 1);
+    }
+}
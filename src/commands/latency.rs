@@ -1,3 +1,7 @@
+use crate::backend::api::ApiBackend;
+use crate::backend::db::DbBackend;
+use crate::backend::{Backend, BackendError};
+use crate::commands::workers::worker_url;
 use crate::config::{AliasConfig, Config, ConfigError};
 use colored::Colorize;
 use futures::stream::{self, StreamExt};
@@ -19,6 +23,12 @@ pub enum LatencyError {
     #[error("Database error: {0}")]
     Db(#[from] sqlx::Error),
 
+    #[error("{0}")]
+    Backend(#[from] BackendError),
+
+    #[error("No public URL known for worker '{0}'")]
+    NoWorkerUrl(String),
+
     #[error("No successful requests")]
     NoSuccess,
 }
@@ -42,7 +52,7 @@ fn parse_host_port(raw: &str) -> Result<(String, u16), LatencyError> {
     Ok((host, port))
 }
 
-fn resolve_alias(alias: &Option<String>) -> Result<(String, AliasConfig), LatencyError> {
+pub fn resolve_alias(alias: &Option<String>) -> Result<(String, AliasConfig), LatencyError> {
     let config = Config::load()?;
 
     let alias_name = alias
@@ -102,7 +112,7 @@ impl LiveProgress {
 
     fn render_status(&self) {
         if self.latencies.is_empty() {
-            print!(
+            eprint!(
                 "\r\x1b[2K  {}",
                 format!("{}/{}", self.completed, self.count).dimmed()
             );
@@ -114,7 +124,7 @@ impl LiveProgress {
                 .iter()
                 .cloned()
                 .fold(f64::NEG_INFINITY, f64::max);
-            print!(
+            eprint!(
                 "\r\x1b[2K  {}",
                 format!(
                     "{}/{} | min {:.2} / avg {:.2} / max {:.2} ms",
@@ -124,26 +134,26 @@ impl LiveProgress {
             );
         }
 
-        io::stdout().flush().unwrap();
+        io::stderr().flush().unwrap();
     }
 
     fn clear_status(&self) {
-        print!("\r\x1b[2K");
-        io::stdout().flush().unwrap();
+        eprint!("\r\x1b[2K");
+        io::stderr().flush().unwrap();
     }
 
     fn success(&mut self, i: usize, ms: f64) {
         self.completed += 1;
         self.latencies.push(ms);
-        print!("\r\x1b[2K");
-        println!("  {} {}/{}: {:.2} ms", "✓".green(), i, self.count, ms);
+        eprint!("\r\x1b[2K");
+        eprintln!("  {} {}/{}: {:.2} ms", "✓".green(), i, self.count, ms);
         self.render_status();
     }
 
     fn failure(&mut self, i: usize, msg: &str) {
         self.completed += 1;
-        print!("\r\x1b[2K");
-        println!("  {} {}/{}: {}", "✗".red(), i, self.count, msg.dimmed());
+        eprint!("\r\x1b[2K");
+        eprintln!("  {} {}/{}: {}", "✗".red(), i, self.count, msg.dimmed());
         self.render_status();
     }
 
@@ -153,8 +163,8 @@ impl LiveProgress {
     }
 
     fn finish(self) -> Vec<f64> {
-        print!("\r\x1b[2K");
-        io::stdout().flush().unwrap();
+        eprint!("\r\x1b[2K");
+        io::stderr().flush().unwrap();
         self.latencies
     }
 }
@@ -164,12 +174,17 @@ impl LiveProgress {
 pub async fn run(
     alias: Option<String>,
     connect: bool,
+    worker: Option<String>,
     count: usize,
     parallel: usize,
     timeout: u64,
 ) -> Result<(), LatencyError> {
     let (alias_name, alias_config) = resolve_alias(&alias)?;
 
+    if let Some(worker) = worker {
+        return run_worker(alias_config, &worker, count, parallel, timeout).await;
+    }
+
     match alias_config {
         AliasConfig::Db { database_url, .. } => {
             if connect {
@@ -199,7 +214,7 @@ async fn run_db_query(
 ) -> Result<(), LatencyError> {
     let (host, port) = parse_host_port(database_url)?;
 
-    println!(
+    eprintln!(
         "{} Connecting to database '{}' ({}:{})",
         "→".cyan(),
         alias_name.green().bold(),
@@ -213,11 +228,11 @@ async fn run_db_query(
         .connect(database_url)
         .await?;
 
-    println!(
+    eprintln!(
         "{} Connected. Measuring query latency (SELECT 1)...",
         "✓".green()
     );
-    println!();
+    eprintln!();
 
     let mut progress = LiveProgress::new(count);
 
@@ -270,14 +285,14 @@ async fn run_db_connect(
     let addr: Arc<str> = format!("{}:{}", host, port).into();
     let timeout_dur = Duration::from_secs(timeout);
 
-    println!(
+    eprintln!(
         "{} Testing connection latency to database '{}' ({}:{})",
         "→".cyan(),
         alias_name.green().bold(),
         host.cyan(),
         port.to_string().cyan(),
     );
-    println!();
+    eprintln!();
 
     let mut progress = LiveProgress::new(count);
 
@@ -323,20 +338,20 @@ async fn run_db_connect(
 
 // --- HTTP: reuse connection, measure layered latency ---
 
-fn latency_url(api_url: &str, layer: &str) -> String {
+pub fn latency_url(api_url: &str, layer: &str) -> String {
     // API URL is like https://host/api/v1 → we need https://host/api/health/latency/<layer>
     let base = api_url.trim_end_matches('/');
     let base = base.strip_suffix("/v1").unwrap_or(base);
     format!("{}/health/latency/{}", base, layer)
 }
 
-struct Layer {
-    name: &'static str,
-    path: &'static str,
-    label: &'static str,
+pub struct Layer {
+    pub name: &'static str,
+    pub path: &'static str,
+    pub label: &'static str,
 }
 
-const LAYERS: &[Layer] = &[
+pub const LAYERS: &[Layer] = &[
     Layer {
         name: "proxy",
         path: "proxy",
@@ -354,15 +369,12 @@ const LAYERS: &[Layer] = &[
     },
 ];
 
-async fn run_http_reuse(
-    alias_name: &str,
+pub fn http_client(
     url: &str,
     insecure: bool,
-    count: usize,
-    parallel: usize,
     timeout: u64,
-) -> Result<(), LatencyError> {
-    let (host, _) = parse_host_port(url)?;
+) -> Result<reqwest::Client, LatencyError> {
+    let (host, port) = parse_host_port(url)?;
 
     let mut builder = reqwest::Client::builder()
         .timeout(Duration::from_secs(timeout))
@@ -370,16 +382,27 @@ async fn run_http_reuse(
 
     // Resolve *.localhost domains to 127.0.0.1 (RFC 6761)
     if host.ends_with(".localhost") {
-        let port = parse_host_port(url)?.1;
         let addr: std::net::SocketAddr = ([127, 0, 0, 1], port).into();
         builder = builder.resolve(&host, addr);
     }
 
-    let client = builder
+    builder
         .build()
-        .map_err(|e| LatencyError::InvalidUrl(e.to_string()))?;
+        .map_err(|e| LatencyError::InvalidUrl(e.to_string()))
+}
 
-    println!(
+async fn run_http_reuse(
+    alias_name: &str,
+    url: &str,
+    insecure: bool,
+    count: usize,
+    parallel: usize,
+    timeout: u64,
+) -> Result<(), LatencyError> {
+    let (host, _) = parse_host_port(url)?;
+    let client = http_client(url, insecure, timeout)?;
+
+    eprintln!(
         "{} Testing latency to API '{}' ({})",
         "→".cyan(),
         alias_name.green().bold(),
@@ -397,7 +420,7 @@ async fn run_http_reuse(
     let _ = resp.bytes().await;
     let warmup_ms = start.elapsed().as_secs_f64() * 1000.0;
 
-    println!("{} Connected ({:.0} ms handshake)", "✓".green(), warmup_ms);
+    eprintln!("{} Connected ({:.0} ms handshake)", "✓".green(), warmup_ms);
 
     let mut any_success = false;
 
@@ -498,14 +521,14 @@ async fn run_http_connect(
     let addr: Arc<str> = format!("{}:{}", host, port).into();
     let timeout_dur = Duration::from_secs(timeout);
 
-    println!(
+    eprintln!(
         "{} Testing connection latency to API '{}' ({}:{})",
         "→".cyan(),
         alias_name.green().bold(),
         host.cyan(),
         port.to_string().cyan(),
     );
-    println!();
+    eprintln!();
 
     let mut progress = LiveProgress::new(count);
 
@@ -548,3 +571,252 @@ async fn run_http_connect(
 
     Ok(())
 }
+
+// --- Worker: end-to-end latency of a deployed worker ---
+
+/// Parse a `Server-Timing` header into (metric, duration in ms) pairs.
+/// Metrics without a `dur` parameter are skipped.
+pub fn parse_server_timing(header: &str) -> Vec<(String, f64)> {
+    header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let name = parts.next().filter(|n| !n.is_empty())?;
+
+            let dur = parts.find_map(|p| {
+                p.strip_prefix("dur=")
+                    .and_then(|d| d.trim_matches('"').parse::<f64>().ok())
+            })?;
+
+            Some((name.to_string(), dur))
+        })
+        .collect()
+}
+
+async fn find_worker_url<B: Backend>(backend: &B, name: &str) -> Result<String, LatencyError> {
+    let worker = backend.get_worker(name).await?;
+    worker_url(backend, &worker).ok_or_else(|| LatencyError::NoWorkerUrl(name.to_string()))
+}
+
+struct WorkerSample {
+    ttfb: f64,
+    total: f64,
+    server_timing: Vec<(String, f64)>,
+}
+
+async fn run_worker(
+    alias_config: AliasConfig,
+    name: &str,
+    count: usize,
+    parallel: usize,
+    timeout: u64,
+) -> Result<(), LatencyError> {
+    let (url, insecure) = match alias_config {
+        AliasConfig::Db {
+            database_url,
+            user,
+            storage,
+            organization,
+            ..
+        } => {
+            let pool = PgPoolOptions::new()
+                .max_connections(1)
+                .acquire_timeout(Duration::from_secs(timeout))
+                .connect(&database_url)
+                .await?;
+            let backend = DbBackend::new(pool, user, organization, storage).await?;
+            (find_worker_url(&backend, name).await?, false)
+        }
+        AliasConfig::Api {
+            url,
+            token,
+            insecure,
+            organization,
+            ..
+        } => {
+            let backend = ApiBackend::new(url, token, insecure).with_organization(organization);
+            (find_worker_url(&backend, name).await?, insecure)
+        }
+    };
+
+    let client = http_client(&url, insecure, timeout)?;
+
+    eprintln!(
+        "{} Testing latency to worker '{}' ({})",
+        "→".cyan(),
+        name.green().bold(),
+        url.cyan(),
+    );
+
+    // Warmup: establish TCP + TLS connection (and wake the worker)
+    let start = Instant::now();
+    let resp = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| LatencyError::InvalidUrl(e.to_string()))?;
+    let _ = resp.bytes().await;
+    let warmup_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    eprintln!(
+        "{} Connected ({:.0} ms first request)",
+        "✓".green(),
+        warmup_ms
+    );
+    eprintln!();
+
+    let endpoint: Arc<str> = url.into();
+    let mut progress = LiveProgress::new(count);
+    let mut samples = Vec::with_capacity(count);
+
+    let mut stream = stream::iter(1..=count)
+        .map(|i| {
+            let client = client.clone();
+            let endpoint = endpoint.clone();
+            async move {
+                let start = Instant::now();
+
+                let resp = match client.get(endpoint.as_ref()).send().await {
+                    Ok(resp) => resp,
+                    Err(e) => return (i, Err(e.to_string())),
+                };
+
+                let ttfb = start.elapsed().as_secs_f64() * 1000.0;
+                let status = resp.status();
+
+                let server_timing = resp
+                    .headers()
+                    .get_all("server-timing")
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .flat_map(parse_server_timing)
+                    .collect();
+
+                if let Err(e) = resp.bytes().await {
+                    return (i, Err(e.to_string()));
+                }
+
+                let total = start.elapsed().as_secs_f64() * 1000.0;
+
+                if !status.is_success() {
+                    return (i, Err(format!("HTTP {}", status)));
+                }
+
+                let sample = WorkerSample {
+                    ttfb,
+                    total,
+                    server_timing,
+                };
+
+                (i, Ok(sample))
+            }
+        })
+        .buffer_unordered(parallel);
+
+    while let Some((i, result)) = stream.next().await {
+        match result {
+            Ok(sample) => {
+                progress.success(i, sample.total);
+                samples.push(sample);
+            }
+            Err(e) => progress.failure(i, &e),
+        }
+    }
+
+    progress.finish();
+
+    if samples.is_empty() {
+        println!();
+        print_stats(&[], count);
+        return Err(LatencyError::NoSuccess);
+    }
+
+    let ttfb: Vec<f64> = samples.iter().map(|s| s.ttfb).collect();
+    let total: Vec<f64> = samples.iter().map(|s| s.total).collect();
+
+    println!();
+    println!("{}:", "Time to first byte".bold());
+    print_layer_stats(&ttfb);
+    println!("{}:", "Full response".bold());
+    print_layer_stats(&total);
+
+    print_server_timing(&samples, &ttfb);
+
+    println!();
+    println!("  Success: {}/{}", samples.len().to_string().green(), count);
+
+    Ok(())
+}
+
+/// Average each `Server-Timing` metric. When the worker reports a `worker`
+/// metric, the rest of the TTFB is network and platform overhead.
+fn print_server_timing(samples: &[WorkerSample], ttfb: &[f64]) {
+    let mut metrics: Vec<(String, Vec<f64>)> = Vec::new();
+
+    for sample in samples {
+        for (name, dur) in &sample.server_timing {
+            match metrics.iter_mut().find(|(n, _)| n == name) {
+                Some((_, durs)) => durs.push(*dur),
+                None => metrics.push((name.clone(), vec![*dur])),
+            }
+        }
+    }
+
+    if metrics.is_empty() {
+        eprintln!();
+        eprintln!(
+            "{}",
+            "No Server-Timing header; worker execution time can't be separated from overhead."
+                .dimmed()
+        );
+        return;
+    }
+
+    println!("{}:", "Server timing".bold());
+
+    for (name, durs) in &metrics {
+        let avg = durs.iter().sum::<f64>() / durs.len() as f64;
+        println!("  {} {:16} avg {:.2} ms", "→".dimmed(), name, avg);
+    }
+
+    if let Some((_, durs)) = metrics.iter().find(|(n, _)| n == "worker") {
+        let worker = durs.iter().sum::<f64>() / durs.len() as f64;
+        let ttfb = ttfb.iter().sum::<f64>() / ttfb.len() as f64;
+
+        println!(
+            "  {} {:16} avg {:.2} ms",
+            "→".dimmed(),
+            "overhead",
+            (ttfb - worker).max(0.0)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_timing() {
+        let metrics = parse_server_timing(r#"worker;dur=12.5, cache;desc="hit", db;dur="3""#);
+
+        assert_eq!(
+            metrics,
+            vec![("worker".to_string(), 12.5), ("db".to_string(), 3.0)]
+        );
+    }
+
+    #[test]
+    fn test_parse_server_timing_empty() {
+        assert!(parse_server_timing("").is_empty());
+        assert!(parse_server_timing("miss").is_empty());
+    }
+
+    #[test]
+    fn test_latency_url() {
+        assert_eq!(
+            latency_url("https://dash.openworkers.com/api/v1", "proxy"),
+            "https://dash.openworkers.com/api/health/latency/proxy"
+        );
+    }
+}
@@ -1,8 +1,19 @@
-use crate::config::{AliasConfig, Config, ConfigError};
+use crate::backend::api::ApiBackend;
+use crate::backend::{Backend, BackendError, CreateTokenInput};
+use crate::config::{AliasConfig, Config, ConfigError, TokenScope};
 use colored::Colorize;
 use std::io::{self, Write};
 
-pub fn run(alias_name: &str) -> Result<(), ConfigError> {
+#[derive(Debug, thiserror::Error)]
+pub enum LoginError {
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Could not issue a scoped token: {0}")]
+    Backend(#[from] BackendError),
+}
+
+pub async fn run(alias_name: &str, scope: Option<TokenScope>) -> Result<(), LoginError> {
     let mut config = Config::load()?;
 
     // Get existing alias
@@ -11,8 +22,13 @@ pub fn run(alias_name: &str) -> Result<(), ConfigError> {
         .ok_or_else(|| ConfigError::AliasNotFound(alias_name.to_string()))?;
 
     // Must be an API alias
-    let (url, insecure) = match existing {
-        AliasConfig::Api { url, insecure, .. } => (url.clone(), *insecure),
+    let (url, insecure, organization) = match existing {
+        AliasConfig::Api {
+            url,
+            insecure,
+            organization,
+            ..
+        } => (url.clone(), *insecure, organization.clone()),
         AliasConfig::Db { .. } => {
             eprintln!(
                 "{} Alias '{}' is a database alias, not an API alias.",
@@ -24,13 +40,13 @@ pub fn run(alias_name: &str) -> Result<(), ConfigError> {
     };
 
     // Prompt for token
-    println!(
+    eprintln!(
         "Logging into {} ({})",
         alias_name.cyan().bold(),
         url.dimmed()
     );
-    print!("Enter API token: ");
-    io::stdout().flush().unwrap();
+    eprint!("Enter API token: ");
+    io::stderr().flush().unwrap();
 
     let mut token = String::new();
     io::stdin().read_line(&mut token).unwrap();
@@ -41,16 +57,45 @@ pub fn run(alias_name: &str) -> Result<(), ConfigError> {
         return Ok(());
     }
 
+    // With --scope, the entered token is only used to mint a narrower one,
+    // which is what gets stored.
+    let token = match scope {
+        Some(scope) => {
+            let backend = ApiBackend::new(url.clone(), Some(token), insecure);
+            let input = CreateTokenInput {
+                name: format!("ow-cli ({})", alias_name),
+                scope,
+                expires_at: None,
+            };
+            let issued = backend.create_token(input).await?;
+
+            eprintln!(
+                "Issued {} token '{}'.",
+                issued.scope.to_string().cyan(),
+                issued.name
+            );
+
+            issued.token
+        }
+        None => token,
+    };
+
     // Update alias with token
     config.set_alias(
         alias_name,
-        AliasConfig::api(url, Some(token), insecure),
+        AliasConfig::Api {
+            url,
+            token: Some(token),
+            insecure,
+            scope,
+            organization,
+        },
         true,
     )?;
 
     config.save()?;
 
-    println!(
+    eprintln!(
         "{} Token saved for alias '{}'.",
         "Success:".green(),
         alias_name.bold()
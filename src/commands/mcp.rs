@@ -5,13 +5,17 @@ use rmcp::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
 
 use crate::backend::{
-    Backend, CreateDatabaseInput, CreateKvInput, CreateStorageInput, CreateWorkerInput,
-    DatabaseProvider, DeployInput, EnvironmentValueInput, UpdateEnvironmentInput, api::ApiBackend,
-    db::DbBackend,
+    Backend, CreateDatabaseInput, CreateEnvironmentInput, CreateKvInput, CreateStorageInput,
+    CreateWorkerInput, DatabaseProvider, DeployInput, Environment, EnvironmentValueInput,
+    UpdateEnvironmentInput,
+    api::ApiBackend,
+    db::{self, DbBackend},
 };
+use crate::commands::workers::{authorize_deploy, invoke_worker, worker_url};
+use crate::commands::{migrate, users};
 use crate::config::{AliasConfig, Config};
 
 // Wrapper enum to make Backend usable without dyn
@@ -44,6 +48,21 @@ macro_rules! tool_call {
     }};
 }
 
+// Helper macro for list tool calls that return a page of JSON results
+macro_rules! tool_list {
+    ($self:expr, $operation:expr, $page:expr, $method:ident) => {{
+        let backend = match $self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match backend_call!(backend, $method) {
+            Ok(items) => paginate(&items, &$page),
+            Err(e) => format!("Failed to {}: {}", $operation, e),
+        }
+    }};
+}
+
 // Helper macro for tool calls that return success messages
 macro_rules! tool_call_success {
     ($self:expr, $operation:expr, $item:expr, $method:ident $(, $arg:expr)*) => {{
@@ -68,8 +87,77 @@ pub struct McpHandler {
 
 // Request types
 
+/// Maximum number of items returned by a list tool when no limit is given
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Upper bound for `limit`, whatever the caller asks for
+const MAX_PAGE_SIZE: usize = 500;
+
+/// List results larger than this are cut short with a continuation cursor
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+struct PageParams {
+    /// Maximum number of items to return (default 50, max 500)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+
+    /// Cursor returned as `nextCursor` by a previous call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+/// Serialize one page of `items` as `{ items, total, nextCursor }`, shrinking
+/// the page further when the output would exceed `MAX_OUTPUT_BYTES`.
+fn paginate<T: Serialize>(items: &[T], page: &PageParams) -> String {
+    let offset = match page.cursor.as_deref().map(str::parse::<usize>) {
+        None => 0,
+        Some(Ok(offset)) => offset.min(items.len()),
+        Some(Err(_)) => return "Error: Invalid cursor".to_string(),
+    };
+
+    let limit = page
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut end = (offset + limit).min(items.len());
+    let mut truncated = false;
+
+    loop {
+        let next_cursor = (end < items.len()).then(|| end.to_string());
+
+        let mut result = serde_json::json!({
+            "items": &items[offset..end],
+            "total": items.len(),
+            "nextCursor": next_cursor,
+        });
+
+        if truncated {
+            result["truncated"] = serde_json::json!(true);
+            result["hint"] = serde_json::json!(format!(
+                "Output truncated to stay under {} KB; call again with cursor \"{}\" to continue",
+                MAX_OUTPUT_BYTES / 1024,
+                end
+            ));
+        }
+
+        let output = serde_json::to_string_pretty(&result).unwrap();
+
+        if output.len() <= MAX_OUTPUT_BYTES || end <= offset + 1 {
+            return output;
+        }
+
+        end = offset + (end - offset) / 2;
+        truncated = true;
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
-struct WorkersListRequest {}
+struct WorkersListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct WorkersGetRequest {
@@ -95,10 +183,39 @@ struct WorkersDeleteRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
-struct EnvListRequest {}
+struct EnvListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct EnvGetRequest {
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct EnvCreateRequest {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
-struct KvListRequest {}
+struct EnvDeleteRequest {
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct EnvUnsetRequest {
+    env_name: String,
+    key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct KvListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct KvCreateRequest {
@@ -118,6 +235,36 @@ struct WorkersDeployRequest {
     file_path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     message: Option<String>,
+    /// Approval token, required when the worker or its environment is protected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    approve_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct WorkersInvokeRequest {
+    name: String,
+    /// HTTP method (default: GET)
+    #[serde(default = "default_method")]
+    method: String,
+    /// Request path, e.g. "/api/users?limit=10" (default: "/")
+    #[serde(default = "default_path")]
+    path: String,
+    /// Request headers
+    #[serde(default)]
+    headers: std::collections::HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// Base URL override, for workers without a known public URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_path() -> String {
+    "/".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -144,7 +291,10 @@ struct EnvBindRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
-struct StorageListRequest {}
+struct StorageListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct StorageCreateRequest {
@@ -165,7 +315,10 @@ struct StorageDeleteRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
-struct DatabasesListRequest {}
+struct DatabasesListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct DatabasesCreateRequest {
@@ -180,6 +333,42 @@ struct DatabasesDeleteRequest {
     name: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct UsersListRequest {
+    #[serde(flatten)]
+    page: PageParams,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct UsersCreateRequest {
+    username: String,
+    /// Optional password for dashboard login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct UsersDeleteRequest {
+    username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct MigrateStatusRequest {}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct MigrateRunRequest {}
+
+/// Hide secret values, like `ow env get` does
+fn mask_secrets(mut env: Environment) -> Environment {
+    for value in &mut env.values {
+        if value.value_type == "secret" {
+            value.value = "****".to_string();
+        }
+    }
+
+    env
+}
+
 #[tool_router]
 impl McpHandler {
     fn new(config: Config, alias: Option<String>) -> Self {
@@ -207,16 +396,17 @@ impl McpHandler {
                 database_url,
                 user,
                 storage,
+                organization,
+                ..
             } => {
-                let pool = PgPoolOptions::new()
-                    .max_connections(1)
-                    .connect(database_url)
+                let pool = db::connect(database_url, None)
                     .await
                     .map_err(|e| format!("Database connection error: {}", e))?;
 
-                let backend = DbBackend::new(pool, user.clone(), storage.clone())
-                    .await
-                    .map_err(|e| format!("Backend error: {}", e))?;
+                let backend =
+                    DbBackend::new(pool, user.clone(), organization.clone(), storage.clone())
+                        .await
+                        .map_err(|e| format!("Backend error: {}", e))?;
 
                 Ok(BackendWrapper::Db(backend))
             }
@@ -225,16 +415,46 @@ impl McpHandler {
                 url,
                 token,
                 insecure,
+                organization,
+                ..
             } => {
-                let backend = ApiBackend::new(url.clone(), token.clone(), *insecure);
+                let backend = ApiBackend::new(url.clone(), token.clone(), *insecure)
+                    .with_organization(organization.clone());
                 Ok(BackendWrapper::Api(backend))
             }
         }
     }
 
+    /// Raw pool for admin tools that bypass the Backend trait (DB aliases only)
+    async fn get_db_pool(&self, tool: &str) -> Result<PgPool, String> {
+        let alias_name = self
+            .alias
+            .clone()
+            .or(self.config.default.clone())
+            .ok_or("No alias specified and no default configured")?;
+
+        let alias_config = self
+            .config
+            .get_alias(&alias_name)
+            .ok_or_else(|| format!("Alias '{}' not found", alias_name))?;
+
+        match alias_config {
+            AliasConfig::Db { database_url, .. } => db::connect(database_url, None)
+                .await
+                .map_err(|e| format!("Database connection error: {}", e)),
+            AliasConfig::Api { .. } => Err(format!(
+                "'{}' requires a database alias, but '{}' is an API alias",
+                tool, alias_name
+            )),
+        }
+    }
+
     #[tool(description = "List all workers")]
-    async fn workers_list(&self, Parameters(_params): Parameters<WorkersListRequest>) -> String {
-        tool_call!(self, "list workers", list_workers)
+    async fn workers_list(
+        &self,
+        Parameters(WorkersListRequest { page }): Parameters<WorkersListRequest>,
+    ) -> String {
+        tool_list!(self, "list workers", page, list_workers)
     }
 
     #[tool(description = "Get details of a specific worker")]
@@ -262,6 +482,7 @@ impl McpHandler {
                 name,
                 description,
                 language,
+                tags: Default::default(),
             }
         )
     }
@@ -274,14 +495,116 @@ impl McpHandler {
         tool_call_success!(self, "delete", &name, delete_worker, &name)
     }
 
-    #[tool(description = "List all environments")]
-    async fn env_list(&self, Parameters(_params): Parameters<EnvListRequest>) -> String {
-        tool_call!(self, "list environments", list_environments)
+    #[tool(description = "List all environments (secret values are masked)")]
+    async fn env_list(
+        &self,
+        Parameters(EnvListRequest { page }): Parameters<EnvListRequest>,
+    ) -> String {
+        let backend = match self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match backend_call!(backend, list_environments) {
+            Ok(envs) => {
+                let envs: Vec<Environment> = envs.into_iter().map(mask_secrets).collect();
+                paginate(&envs, &page)
+            }
+            Err(e) => format!("Failed to list environments: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Get an environment with its variables and bindings (secret values are masked)"
+    )]
+    async fn env_get(
+        &self,
+        Parameters(EnvGetRequest { name }): Parameters<EnvGetRequest>,
+    ) -> String {
+        let backend = match self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match backend_call!(backend, get_environment, &name) {
+            Ok(env) => serde_json::to_string_pretty(&mask_secrets(env)).unwrap(),
+            Err(e) => format!("Failed to get environment: {}", e),
+        }
+    }
+
+    #[tool(description = "Create a new environment")]
+    async fn env_create(
+        &self,
+        Parameters(EnvCreateRequest { name, description }): Parameters<EnvCreateRequest>,
+    ) -> String {
+        tool_call!(
+            self,
+            "create environment",
+            create_environment,
+            CreateEnvironmentInput {
+                name,
+                desc: description,
+            }
+        )
+    }
+
+    #[tool(description = "Delete an environment and all its variables/bindings")]
+    async fn env_delete(
+        &self,
+        Parameters(EnvDeleteRequest { name }): Parameters<EnvDeleteRequest>,
+    ) -> String {
+        tool_call_success!(self, "delete", &name, delete_environment, &name)
+    }
+
+    #[tool(description = "Remove a variable, secret or binding from an environment")]
+    async fn env_unset(
+        &self,
+        Parameters(EnvUnsetRequest { env_name, key }): Parameters<EnvUnsetRequest>,
+    ) -> String {
+        let backend = match self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        let env = match backend_call!(backend, get_environment, &env_name) {
+            Ok(e) => e,
+            Err(e) => return format!("Failed to get environment '{}': {}", env_name, e),
+        };
+
+        let Some(existing) = env.values.iter().find(|v| v.key == key) else {
+            return format!(
+                "Error: Variable '{}' not found in environment '{}'",
+                key, env_name
+            );
+        };
+
+        // Setting value to null deletes it (matching CLI)
+        match backend_call!(
+            backend,
+            update_environment,
+            &env_name,
+            UpdateEnvironmentInput {
+                name: None,
+                values: Some(vec![EnvironmentValueInput {
+                    id: Some(existing.id.clone()),
+                    key,
+                    value: None,
+                    value_type: existing.value_type.clone(),
+                }]),
+                protected: None,
+            }
+        ) {
+            Ok(result) => serde_json::to_string_pretty(&mask_secrets(result)).unwrap(),
+            Err(e) => format!("Failed to unset environment variable: {}", e),
+        }
     }
 
     #[tool(description = "List all KV namespaces")]
-    async fn kv_list(&self, Parameters(_params): Parameters<KvListRequest>) -> String {
-        tool_call!(self, "list KV namespaces", list_kv)
+    async fn kv_list(
+        &self,
+        Parameters(KvListRequest { page }): Parameters<KvListRequest>,
+    ) -> String {
+        tool_list!(self, "list KV namespaces", page, list_kv)
     }
 
     #[tool(description = "Create a new KV namespace")]
@@ -315,6 +638,7 @@ impl McpHandler {
             name,
             file_path,
             message,
+            approve_token,
         }): Parameters<WorkersDeployRequest>,
     ) -> String {
         use std::path::PathBuf;
@@ -345,17 +669,103 @@ impl McpHandler {
         }
         .to_string();
 
-        tool_call!(
-            self,
-            "deploy worker",
+        let hash = {
+            use sha2::{Digest, Sha256};
+            hex::encode(Sha256::digest(&code))
+        };
+
+        let backend = match self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        // MCP runs over stdio, so protected workers always need a token
+        let authorized = match &backend {
+            BackendWrapper::Api(b) => {
+                authorize_deploy(b, &name, approve_token.as_deref(), false).await
+            }
+            BackendWrapper::Db(b) => {
+                authorize_deploy(b, &name, approve_token.as_deref(), false).await
+            }
+        };
+
+        let authorization = match authorized {
+            Ok(authorization) => authorization,
+            Err(e) => return format!("Failed to deploy worker: {}", e),
+        };
+
+        match backend_call!(
+            backend,
             deploy_worker,
             &name,
             DeployInput {
                 code,
                 code_type,
                 message,
+                hash: Some(hash),
+                authorization,
             }
-        )
+        ) {
+            Ok(result) => serde_json::to_string_pretty(&result).unwrap(),
+            Err(e) => format!("Failed to deploy worker: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Send an HTTP request to a worker and return status, headers and body (use it to test a deployment)"
+    )]
+    async fn workers_invoke(
+        &self,
+        Parameters(WorkersInvokeRequest {
+            name,
+            method,
+            path,
+            headers,
+            body,
+            base_url,
+        }): Parameters<WorkersInvokeRequest>,
+    ) -> String {
+        let base_url = match base_url {
+            Some(url) => url,
+            None => {
+                let backend = match self.get_backend().await {
+                    Ok(b) => b,
+                    Err(e) => return format!("Error: {}", e),
+                };
+
+                let worker = match backend_call!(backend, get_worker, &name) {
+                    Ok(w) => w,
+                    Err(e) => return format!("Failed to get worker '{}': {}", name, e),
+                };
+
+                let url = match &backend {
+                    BackendWrapper::Api(b) => worker_url(b, &worker),
+                    BackendWrapper::Db(b) => worker_url(b, &worker),
+                };
+
+                match url {
+                    Some(url) => url,
+                    None => {
+                        return format!(
+                            "Error: No public URL known for worker '{}'. Pass base_url.",
+                            name
+                        );
+                    }
+                }
+            }
+        };
+
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        let headers: Vec<(String, String)> = headers.into_iter().collect();
+
+        match invoke_worker(&url, &method, &headers, body).await {
+            Ok(result) => serde_json::to_string_pretty(&result).unwrap(),
+            Err(e) => format!("Failed to invoke worker: {}", e),
+        }
     }
 
     #[tool(description = "Link an environment to a worker")]
@@ -372,8 +782,8 @@ impl McpHandler {
             update_worker,
             &worker_name,
             crate::backend::UpdateWorkerInput {
-                name: None,
                 environment: Some(env_name),
+                ..Default::default()
             }
         )
     }
@@ -390,9 +800,13 @@ impl McpHandler {
     ) -> String {
         let value_type = if is_secret { "secret" } else { "plain" }.to_string();
 
-        tool_call!(
-            self,
-            "set environment variable",
+        let backend = match self.get_backend().await {
+            Ok(b) => b,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match backend_call!(
+            backend,
             update_environment,
             &env_name,
             UpdateEnvironmentInput {
@@ -403,8 +817,12 @@ impl McpHandler {
                     value: Some(value),
                     value_type,
                 }]),
+                protected: None,
             }
-        )
+        ) {
+            Ok(result) => serde_json::to_string_pretty(&mask_secrets(result)).unwrap(),
+            Err(e) => format!("Failed to set environment variable: {}", e),
+        }
     }
 
     #[tool(description = "Bind a resource (KV, database, storage) to an environment")]
@@ -469,16 +887,20 @@ impl McpHandler {
                     value: Some(resource_id),
                     value_type: resource_type,
                 }]),
+                protected: None,
             }
         ) {
-            Ok(result) => serde_json::to_string_pretty(&result).unwrap(),
+            Ok(result) => serde_json::to_string_pretty(&mask_secrets(result)).unwrap(),
             Err(e) => format!("Failed to bind resource to environment: {}", e),
         }
     }
 
     #[tool(description = "List all storage configurations")]
-    async fn storage_list(&self, Parameters(_params): Parameters<StorageListRequest>) -> String {
-        tool_call!(self, "list storage", list_storage)
+    async fn storage_list(
+        &self,
+        Parameters(StorageListRequest { page }): Parameters<StorageListRequest>,
+    ) -> String {
+        tool_list!(self, "list storage", page, list_storage)
     }
 
     #[tool(description = "Create a new storage configuration")]
@@ -523,9 +945,9 @@ impl McpHandler {
     #[tool(description = "List all databases")]
     async fn databases_list(
         &self,
-        Parameters(_params): Parameters<DatabasesListRequest>,
+        Parameters(DatabasesListRequest { page }): Parameters<DatabasesListRequest>,
     ) -> String {
-        tool_call!(self, "list databases", list_databases)
+        tool_list!(self, "list databases", page, list_databases)
     }
 
     #[tool(description = "Create a new database")]
@@ -559,6 +981,102 @@ impl McpHandler {
     ) -> String {
         tool_call_success!(self, "delete", &name, delete_database, &name)
     }
+
+    #[tool(description = "List platform users (database aliases only)")]
+    async fn users_list(
+        &self,
+        Parameters(UsersListRequest { page }): Parameters<UsersListRequest>,
+    ) -> String {
+        let pool = match self.get_db_pool("users_list").await {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match users::list_users(&pool).await {
+            Ok(result) => paginate(&result, &page),
+            Err(e) => format!("Failed to list users: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Create a platform user, optionally with a password (database aliases only)"
+    )]
+    async fn users_create(
+        &self,
+        Parameters(UsersCreateRequest { username, password }): Parameters<UsersCreateRequest>,
+    ) -> String {
+        let pool = match self.get_db_pool("users_create").await {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        let user = match users::create_user(&pool, &username).await {
+            Ok(u) => u,
+            Err(e) => return format!("Failed to create user {}: {}", username, e),
+        };
+
+        if let Some(password) = password {
+            let hash = users::hash_password(&password);
+
+            if let Err(e) = users::set_password_hash(&pool, &username, &hash).await {
+                return format!("User created but failed to set password: {}", e);
+            }
+        }
+
+        serde_json::to_string_pretty(&user).unwrap()
+    }
+
+    #[tool(description = "Delete a platform user (database aliases only)")]
+    async fn users_delete(
+        &self,
+        Parameters(UsersDeleteRequest { username }): Parameters<UsersDeleteRequest>,
+    ) -> String {
+        let pool = match self.get_db_pool("users_delete").await {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match users::delete_user(&pool, &username).await {
+            Ok(()) => format!(
+                "{{\"success\": true, \"message\": \"{} deleted\"}}",
+                username
+            ),
+            Err(e) => format!("Failed to delete {}: {}", username, e),
+        }
+    }
+
+    #[tool(description = "Show applied and pending database migrations (database aliases only)")]
+    async fn migrate_status(
+        &self,
+        Parameters(_params): Parameters<MigrateStatusRequest>,
+    ) -> String {
+        let pool = match self.get_db_pool("migrate_status").await {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match migrate::migration_status(&pool).await {
+            Ok(result) => serde_json::to_string_pretty(&result).unwrap(),
+            Err(e) => format!("Failed to get migration status: {}", e),
+        }
+    }
+
+    #[tool(description = "Apply pending database migrations (database aliases only)")]
+    async fn migrate_run(&self, Parameters(_params): Parameters<MigrateRunRequest>) -> String {
+        let pool = match self.get_db_pool("migrate_run").await {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        match migrate::run_migrations(&pool).await {
+            Ok(applied) => serde_json::to_string_pretty(&serde_json::json!({
+                "success": true,
+                "applied": applied,
+            }))
+            .unwrap(),
+            Err(e) => format!("Failed to run migrations: {}", e),
+        }
+    }
 }
 
 #[tool_handler]
@@ -587,3 +1105,55 @@ pub async fn run(alias: Option<String>) -> Result<(), Box<dyn std::error::Error>
     service.waiting().await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(limit: Option<usize>, cursor: Option<&str>) -> serde_json::Value {
+        let items: Vec<usize> = (0..120).collect();
+        let params = PageParams {
+            limit,
+            cursor: cursor.map(str::to_string),
+        };
+
+        serde_json::from_str(&paginate(&items, &params)).unwrap()
+    }
+
+    #[test]
+    fn test_paginate_default_and_cursor() {
+        let first = page(None, None);
+        assert_eq!(first["items"].as_array().unwrap().len(), DEFAULT_PAGE_SIZE);
+        assert_eq!(first["total"], 120);
+        assert_eq!(first["nextCursor"], "50");
+
+        let last = page(Some(100), Some("50"));
+        assert_eq!(last["items"].as_array().unwrap().len(), 70);
+        assert_eq!(last["items"][0], 50);
+        assert!(last["nextCursor"].is_null());
+    }
+
+    #[test]
+    fn test_paginate_truncates_large_output() {
+        let items: Vec<String> = (0..100).map(|_| "x".repeat(4096)).collect();
+        let output = paginate(&items, &PageParams::default());
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert!(output.len() <= MAX_OUTPUT_BYTES);
+        assert_eq!(result["truncated"], true);
+
+        let returned = result["items"].as_array().unwrap().len();
+        assert_eq!(result["nextCursor"], returned.to_string());
+    }
+
+    #[test]
+    fn test_paginate_invalid_cursor() {
+        let items = vec![1, 2, 3];
+        let params = PageParams {
+            limit: None,
+            cursor: Some("abc".to_string()),
+        };
+
+        assert!(paginate(&items, &params).starts_with("Error"));
+    }
+}
@@ -0,0 +1,342 @@
+use super::bench::percentile;
+use crate::config::{self, Config, ConfigError};
+use chrono::{DateTime, Utc};
+use clap::{ArgMatches, Subcommand};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const METRICS_FILE: &str = "metrics.jsonl";
+
+/// Samples kept once the file is trimmed
+const MAX_SAMPLES: usize = 5000;
+
+/// The file is trimmed back to `MAX_SAMPLES` once it grows past this size
+const TRIM_AT_BYTES: u64 = 1024 * 1024;
+
+#[derive(Subcommand)]
+pub enum MetricsCommand {
+    /// Durations and errors of your own commands, slowest first
+    #[command(
+        name = "self",
+        after_help = "Only recorded with telemetry on (ow config set telemetry on).\n\
+        Samples stay in ~/.openworkers/metrics.jsonl and are never sent anywhere.\n\n\
+        Examples:\n  \
+        ow metrics self\n  \
+        ow metrics self --clear"
+    )]
+    Local {
+        /// Delete recorded samples
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+impl MetricsCommand {
+    pub fn run(self) -> Result<(), ConfigError> {
+        match self {
+            Self::Local { clear: true } => cmd_clear(),
+            Self::Local { clear: false } => cmd_show(),
+        }
+    }
+}
+
+/// One command run: no arguments, names or URLs, only what it was and how it went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sample {
+    command: String,
+    backend: String,
+    duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    at: DateTime<Utc>,
+}
+
+fn metrics_path() -> Result<PathBuf, ConfigError> {
+    Ok(Config::config_dir()?.join(METRICS_FILE))
+}
+
+/// `workers upload` from the parsed command line
+pub fn command_name(matches: &ArgMatches) -> Option<String> {
+    let (command, sub) = matches.subcommand()?;
+
+    match sub.subcommand_name() {
+        Some(action) => Some(format!("{} {}", command, action)),
+        None => Some(command.to_string()),
+    }
+}
+
+/// Error class from a failed command's message, without the message itself
+fn error_class(message: &str) -> &'static str {
+    const CLASSES: &[(&str, &str)] = &[
+        ("Database error", "database"),
+        ("API error", "api"),
+        ("HTTP error", "http"),
+        ("Not found", "not_found"),
+        ("Unauthorized", "unauthorized"),
+        ("IO error", "io"),
+    ];
+
+    CLASSES
+        .iter()
+        .find(|(prefix, _)| message.starts_with(*prefix))
+        .map_or("other", |(_, class)| *class)
+}
+
+/// Append a sample when telemetry is on (best effort, never fails the command)
+pub fn record(command: &str, duration: Duration, error: Option<&str>) {
+    let Ok(config) = Config::load() else {
+        return;
+    };
+
+    if !config.telemetry || command.starts_with("metrics") {
+        return;
+    }
+
+    let backend = config::active_alias()
+        .and_then(|alias| config.get_alias(alias))
+        .map_or("none", |alias| alias.type_name());
+
+    let sample = Sample {
+        command: command.to_string(),
+        backend: backend.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        error: error.map(|e| error_class(e).to_string()),
+        at: Utc::now(),
+    };
+
+    append(&sample).ok();
+}
+
+fn append(sample: &Sample) -> Result<(), ConfigError> {
+    let path = metrics_path()?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(sample)?)?;
+
+    if file.metadata()?.len() > TRIM_AT_BYTES {
+        let samples = load()?;
+        let keep = &samples[samples.len().saturating_sub(MAX_SAMPLES)..];
+        let content: String = keep
+            .iter()
+            .filter_map(|s| serde_json::to_string(s).ok())
+            .map(|line| line + "\n")
+            .collect();
+        std::fs::write(&path, content)?;
+    }
+
+    Ok(())
+}
+
+/// Recorded samples, oldest first. Unreadable lines are skipped.
+fn load() -> Result<Vec<Sample>, ConfigError> {
+    let path = metrics_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    durations: Vec<f64>,
+    errors: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    fn error_count(&self) -> usize {
+        self.errors.values().sum()
+    }
+}
+
+/// Samples grouped by command and backend, durations sorted ascending
+fn summarize(samples: &[Sample]) -> BTreeMap<(String, String), Summary> {
+    let mut summaries: BTreeMap<(String, String), Summary> = BTreeMap::new();
+
+    for sample in samples {
+        let summary = summaries
+            .entry((sample.command.clone(), sample.backend.clone()))
+            .or_default();
+
+        summary.durations.push(sample.duration_ms as f64);
+
+        if let Some(error) = &sample.error {
+            *summary.errors.entry(error.clone()).or_default() += 1;
+        }
+    }
+
+    for summary in summaries.values_mut() {
+        summary.durations.sort_by(f64::total_cmp);
+    }
+
+    summaries
+}
+
+fn format_ms(ms: f64) -> String {
+    if ms >= 1000.0 {
+        format!("{:.1}s", ms / 1000.0)
+    } else {
+        format!("{:.0}ms", ms)
+    }
+}
+
+fn cmd_show() -> Result<(), ConfigError> {
+    let telemetry = Config::load()?.telemetry;
+    let samples = load()?;
+
+    if samples.is_empty() {
+        if telemetry {
+            eprintln!("No commands recorded yet.");
+        } else {
+            eprintln!(
+                "Telemetry is off. Enable it with {} to record command durations locally.",
+                "ow config set telemetry on".cyan()
+            );
+        }
+
+        return Ok(());
+    }
+
+    let mut rows: Vec<_> = summarize(&samples).into_iter().collect();
+    rows.sort_by(|(_, a), (_, b)| {
+        let p50 = |s: &Summary| percentile(&s.durations, 50.0).unwrap_or(0.0);
+        p50(b).total_cmp(&p50(a))
+    });
+
+    let width = rows
+        .iter()
+        .map(|((command, _), _)| command.len())
+        .max()
+        .unwrap_or(0)
+        .max("COMMAND".len());
+
+    println!(
+        "{:width$}  {:7}  {:>5}  {:>7}  {:>7}  {:>7}  {}",
+        "COMMAND".dimmed(),
+        "BACKEND".dimmed(),
+        "RUNS".dimmed(),
+        "P50".dimmed(),
+        "P95".dimmed(),
+        "MAX".dimmed(),
+        "ERRORS".dimmed(),
+        width = width
+    );
+
+    for ((command, backend), summary) in &rows {
+        let stat = |p: f64| percentile(&summary.durations, p).map_or("-".to_string(), format_ms);
+
+        let errors = if summary.errors.is_empty() {
+            "-".dimmed().to_string()
+        } else {
+            let classes: Vec<String> = summary
+                .errors
+                .iter()
+                .map(|(class, count)| format!("{} {}", count, class))
+                .collect();
+            format!("{} ({})", summary.error_count(), classes.join(", "))
+                .red()
+                .to_string()
+        };
+
+        println!(
+            "{:width$}  {:7}  {:>5}  {:>7}  {:>7}  {:>7}  {}",
+            command,
+            backend,
+            summary.durations.len(),
+            stat(50.0),
+            stat(95.0),
+            stat(100.0),
+            errors,
+            width = width
+        );
+    }
+
+    let since = samples.first().map(|s| s.at.format("%Y-%m-%d").to_string());
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{} runs since {}{}",
+            samples.len(),
+            since.unwrap_or_default(),
+            if telemetry {
+                ""
+            } else {
+                " (telemetry is now off)"
+            }
+        )
+        .dimmed()
+    );
+
+    Ok(())
+}
+
+fn cmd_clear() -> Result<(), ConfigError> {
+    let path = metrics_path()?;
+
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+
+    eprintln!("{} recorded metrics", "Cleared".green());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(command: &str, duration_ms: u64, error: Option<&str>) -> Sample {
+        Sample {
+            command: command.to_string(),
+            backend: "api".to_string(),
+            duration_ms,
+            error: error.map(str::to_string),
+            at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_error_class() {
+        assert_eq!(error_class("HTTP error: connection refused"), "http");
+        assert_eq!(error_class("Not found: Worker 'api'"), "not_found");
+        assert_eq!(error_class("Unauthorized"), "unauthorized");
+        assert_eq!(error_class("Alias 'x' not found"), "other");
+    }
+
+    #[test]
+    fn test_summarize() {
+        let samples = vec![
+            sample("workers upload", 3000, None),
+            sample("workers list", 100, None),
+            sample("workers upload", 1000, Some("http")),
+            sample("workers upload", 2000, Some("http")),
+        ];
+
+        let summaries = summarize(&samples);
+        assert_eq!(summaries.len(), 2);
+
+        let upload = &summaries[&("workers upload".to_string(), "api".to_string())];
+        assert_eq!(upload.durations, vec![1000.0, 2000.0, 3000.0]);
+        assert_eq!(upload.error_count(), 2);
+        assert_eq!(percentile(&upload.durations, 50.0), Some(2000.0));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::config::{AliasConfig, Config, ConfigError};
+use crate::timings::{Phase, TimedExt};
 use clap::Subcommand;
 use colored::Colorize;
+use serde::Serialize;
 use sqlx::migrate::Migrator;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{PgPool, Row};
@@ -77,74 +79,107 @@ async fn connect(database_url: &str) -> Result<PgPool, MigrateError> {
     let pool = PgPoolOptions::new()
         .max_connections(1)
         .connect(database_url)
+        .timed(Phase::Connect)
         .await?;
 
     Ok(pool)
 }
 
-async fn cmd_run(pool: &PgPool) -> Result<(), MigrateError> {
-    // Get already applied migrations
-    let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations")
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
-
-    // Find pending migrations
-    let pending: Vec<_> = MIGRATOR
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+    /// "applied", "modified" (checksum mismatch) or "pending"
+    pub status: &'static str,
+}
+
+/// Status of every embedded migration against the database
+pub async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationInfo>, MigrateError> {
+    // Get applied migrations from DB
+    let applied: Vec<(i64, Vec<u8>)> =
+        sqlx::query("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|row| (row.get("version"), row.get("checksum")))
+            .collect();
+
+    let migrations = MIGRATOR
         .iter()
-        .filter(|m| !applied.contains(&m.version))
+        .map(|migration| {
+            let status = match applied.iter().find(|(v, _)| *v == migration.version) {
+                Some((_, db_checksum)) if *db_checksum == migration.checksum.to_vec() => "applied",
+                Some(_) => "modified",
+                None => "pending",
+            };
+
+            MigrationInfo {
+                version: migration.version,
+                description: migration.description.to_string(),
+                status,
+            }
+        })
+        .collect();
+
+    Ok(migrations)
+}
+
+/// Apply pending migrations, returning the ones that were applied
+pub async fn run_migrations(pool: &PgPool) -> Result<Vec<MigrationInfo>, MigrateError> {
+    let pending: Vec<MigrationInfo> = migration_status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| m.status == "pending")
+        .collect();
+
+    if !pending.is_empty() {
+        MIGRATOR.run(pool).await?;
+    }
+
+    Ok(pending)
+}
+
+async fn cmd_run(pool: &PgPool) -> Result<(), MigrateError> {
+    let pending: Vec<MigrationInfo> = migration_status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| m.status == "pending")
         .collect();
 
     if pending.is_empty() {
-        println!("{}", "No pending migrations.".green());
+        eprintln!("{}", "No pending migrations.".green());
         return Ok(());
     }
 
-    println!("Running {} migration(s)...\n", pending.len());
+    eprintln!("Running {} migration(s)...\n", pending.len());
 
     for migration in &pending {
-        println!("  {} {}", "Applying".blue(), migration.description);
+        eprintln!("  {} {}", "Applying".blue(), migration.description);
     }
 
-    println!();
+    eprintln!();
 
-    MIGRATOR.run(pool).await?;
+    run_migrations(pool).await?;
 
-    println!("\n{}", "Migrations complete.".green().bold());
+    eprintln!("\n{}", "Migrations complete.".green().bold());
 
     Ok(())
 }
 
 async fn cmd_status(pool: &PgPool) -> Result<(), MigrateError> {
-    // Get applied migrations from DB
-    let applied: Vec<(i64, Vec<u8>)> =
-        sqlx::query("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-            .fetch_all(pool)
-            .await
-            .unwrap_or_default()
-            .iter()
-            .map(|row| (row.get("version"), row.get("checksum")))
-            .collect();
+    let migrations = migration_status(pool).await?;
 
     println!("{}", "Migration Status".bold());
     println!("{}", "─".repeat(70));
 
     let mut pending_count = 0;
 
-    for migration in MIGRATOR.iter() {
-        let applied_entry = applied.iter().find(|(v, _)| *v == migration.version);
-
-        let (status, checksum_warn) = match applied_entry {
-            Some((_, db_checksum)) => {
-                let matches = db_checksum == &migration.checksum.to_vec();
-
-                if matches {
-                    ("applied".green(), "")
-                } else {
-                    ("modified".red(), " (checksum mismatch!)")
-                }
-            }
-            None => {
+    for migration in &migrations {
+        let (status, checksum_warn) = match migration.status {
+            "applied" => ("applied".green(), ""),
+            "modified" => ("modified".red(), " (checksum mismatch!)"),
+            _ => {
                 pending_count += 1;
                 ("pending".yellow(), "")
             }
@@ -196,16 +231,16 @@ async fn cmd_baseline(pool: &PgPool) -> Result<(), MigrateError> {
         .await?;
 
     if count > 0 {
-        println!(
+        eprintln!(
             "{} Database already has {} migration(s) recorded.",
             "Warning:".yellow().bold(),
             count
         );
-        println!("Use '{}' to check status.", "ow migrate status".cyan());
+        eprintln!("Use '{}' to check status.", "ow migrate status".cyan());
         return Ok(());
     }
 
-    println!("Marking all migrations as applied...\n");
+    eprintln!("Marking all migrations as applied...\n");
 
     for migration in MIGRATOR.iter() {
         sqlx::query(
@@ -220,7 +255,7 @@ async fn cmd_baseline(pool: &PgPool) -> Result<(), MigrateError> {
         .execute(pool)
         .await?;
 
-        println!("  {} {}", "Baseline".blue(), migration.description);
+        eprintln!("  {} {}", "Baseline".blue(), migration.description);
     }
 
     // Drop old _migrations table if it exists
@@ -228,7 +263,7 @@ async fn cmd_baseline(pool: &PgPool) -> Result<(), MigrateError> {
         .execute(pool)
         .await?;
 
-    println!("\n{}", "Baseline complete.".green().bold());
+    eprintln!("\n{}", "Baseline complete.".green().bold());
 
     Ok(())
 }
@@ -1,14 +1,216 @@
 pub mod alias;
+pub mod bench;
+pub mod bootstrap;
+pub mod ci;
+pub mod config;
+pub mod context;
+pub mod daemon;
 pub mod databases;
+pub mod db;
+pub mod deploy;
+pub mod dev;
 pub mod env;
+pub mod flags;
+pub mod health;
 pub mod kv;
 pub mod latency;
 pub mod login;
+pub mod metrics;
 pub mod migrate;
+pub mod notifications;
+pub mod orgs;
 pub mod projects;
+pub mod resources;
+pub mod search;
+pub mod secrets;
+pub mod session;
 pub mod storage;
+pub mod tokens;
+pub mod trace;
 pub mod users;
 pub mod workers;
 
 #[cfg(feature = "mcp")]
 pub mod mcp;
+
+use crate::backend::{BackendError, DeletePreview};
+use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+
+/// Whether a name contains glob wildcards
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Match a name against a shell-style glob (`*` any run, `?` one character)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name index it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` swallow one more character
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Parse a short duration like "30m", "1h", "7d" into seconds
+pub fn parse_duration(s: &str) -> Result<i64, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+
+    let n: i64 = num
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'. Use e.g. 30m, 1h, 7d", s))?;
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" | "" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => {
+            return Err(format!(
+                "Invalid duration unit '{}'. Use s, m, h or d",
+                unit
+            ));
+        }
+    };
+
+    Ok(n * multiplier)
+}
+
+/// Ask the user to type `expected` to confirm a dangerous action
+pub fn confirm_typed(prompt: &str, expected: &str) -> io::Result<bool> {
+    eprintln!("{}", prompt);
+    eprint!("{} '{}': ", "Type".dimmed(), expected.bold());
+    io::stderr().flush().ok();
+
+    let mut buf = String::new();
+    io::stdin().read_line(&mut buf)?;
+
+    Ok(buf.trim() == expected)
+}
+
+/// Ask for y/N confirmation before a delete, unless `--yes` was given.
+/// Non-interactive sessions must pass `--yes`.
+pub fn confirm_delete(what: &str, yes: bool) -> Result<(), BackendError> {
+    if yes {
+        return Ok(());
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(BackendError::Api(format!(
+            "Refusing to delete {} without --yes in a non-interactive session",
+            what
+        )));
+    }
+
+    eprint!("Delete {}? [y/N] ", what);
+    io::stderr().flush().ok();
+
+    let mut buf = String::new();
+    io::stdin()
+        .read_line(&mut buf)
+        .map_err(|e| BackendError::Api(format!("Failed to read input: {}", e)))?;
+
+    match buf.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(BackendError::Api("Delete cancelled.".to_string())),
+    }
+}
+
+/// Print what a cascade delete will remove
+pub fn print_delete_preview(preview: &DeletePreview) {
+    eprintln!("{}", "This will also delete:".yellow());
+
+    if !preview.workers.is_empty() {
+        eprintln!(
+            "  {:14} {}",
+            "Workers:".dimmed(),
+            preview.workers.join(", ")
+        );
+    }
+
+    eprintln!("  {:14} {}", "Deployments:".dimmed(), preview.deployments);
+
+    if preview.routes > 0 {
+        eprintln!("  {:14} {}", "Routes:".dimmed(), preview.routes);
+    }
+
+    if !preview.domains.is_empty() {
+        eprintln!(
+            "  {:14} {}",
+            "Domains:".dimmed(),
+            preview.domains.join(", ")
+        );
+    }
+
+    if preview.crons > 0 {
+        eprintln!("  {:14} {}", "Crons:".dimmed(), preview.crons);
+    }
+
+    if let Some(assets) = &preview.assets {
+        eprintln!(
+            "  {:14} {} {}",
+            "Assets:".dimmed(),
+            assets,
+            "(objects are not removed)".dimmed()
+        );
+    }
+
+    eprintln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45s"), Ok(45));
+        assert_eq!(parse_duration("30m"), Ok(1800));
+        assert_eq!(parse_duration("1h"), Ok(3600));
+        assert_eq!(parse_duration("7d"), Ok(604800));
+        assert_eq!(parse_duration("15"), Ok(900));
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("h").is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("api-*", "api-users"));
+        assert!(glob_match("api-*", "api-"));
+        assert!(glob_match("*-staging", "web-staging"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(glob_match("worker-?", "worker-1"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("api-*", "web-api"));
+        assert!(!glob_match("worker-?", "worker-10"));
+        assert!(!glob_match("a*b", "abc"));
+        assert!(is_glob("api-*"));
+        assert!(!is_glob("api"));
+    }
+}
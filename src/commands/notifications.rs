@@ -0,0 +1,365 @@
+use crate::backend::{Backend, BackendError, NotificationEvent};
+use crate::github;
+use chrono::Utc;
+use clap::Subcommand;
+use colored::Colorize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// A slow webhook must not hold up the command that fired it
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Subcommand)]
+pub enum NotificationsCommand {
+    /// Add a webhook, or change the events of an existing one
+    #[command(after_help = "Examples:\n  \
+        ow notifications set --url https://hooks.slack.com/services/T000/B000/XXX\n  \
+        ow notifications set --url https://ci.example.com/hook --events deploy\n\n\
+        Each event POSTs a JSON payload: {\"event\", \"worker\", \"version\", \"text\", \"at\"}.\n\
+        \"text\" makes it readable as-is by Slack and Mattermost incoming webhooks.")]
+    Set {
+        /// Webhook URL (http or https)
+        #[arg(long)]
+        url: String,
+
+        /// Events to notify, comma-separated. The CLI fires deploy and delete;
+        /// rollback is stored for the platform to fire
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "deploy,rollback,delete"
+        )]
+        events: Vec<NotificationEvent>,
+    },
+
+    /// List webhooks and the events they receive
+    #[command(alias = "ls")]
+    List,
+
+    /// Remove a webhook
+    #[command(alias = "rm")]
+    Remove {
+        /// Webhook URL
+        url: String,
+    },
+
+    /// Send a test payload to every webhook
+    Test,
+}
+
+impl NotificationsCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::Set { url, events } => cmd_set(backend, &url, &events).await,
+            Self::List => cmd_list(backend).await,
+            Self::Remove { url } => cmd_remove(backend, &url).await,
+            Self::Test => cmd_test(backend).await,
+        }
+    }
+}
+
+/// Body POSTed to webhooks
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    event: &'a str,
+    worker: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i32>,
+    /// One-line summary, rendered by chat incoming webhooks
+    text: String,
+    at: String,
+}
+
+fn payload<'a>(event: &'a str, worker: &'a str, version: Option<i32>) -> Payload<'a> {
+    let action = match event {
+        "deploy" => "Deployed",
+        "rollback" => "Rolled back",
+        "delete" => "Deleted",
+        _ => "Test notification for",
+    };
+
+    let text = match version {
+        Some(version) => format!("{} worker '{}' (v{})", action, worker, version),
+        None => format!("{} worker '{}'", action, worker),
+    };
+
+    Payload {
+        event,
+        worker,
+        version,
+        text,
+        at: crate::porcelain::time(&Utc::now()),
+    }
+}
+
+async fn post(client: &reqwest::Client, url: &str, payload: &Payload<'_>) -> Result<(), String> {
+    let response = client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    Ok(())
+}
+
+/// Scheme and host of a webhook URL, for messages that may end up in CI logs.
+/// Slack and Discord incoming webhooks carry their secret in the path.
+fn redact(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.path() == "/" && parsed.query().is_none() => {
+            format!(
+                "{}://{}",
+                parsed.scheme(),
+                parsed.host_str().unwrap_or_default()
+            )
+        }
+        Ok(parsed) => format!(
+            "{}://{}/…",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or_default()
+        ),
+        Err(_) => "<invalid URL>".to_string(),
+    }
+}
+
+/// Notify the webhooks subscribed to `event`. Best effort: a failing webhook
+/// only prints a warning, the operation itself already succeeded.
+pub async fn notify<B: Backend>(
+    backend: &B,
+    event: NotificationEvent,
+    worker: &str,
+    version: Option<i32>,
+) {
+    let webhooks = match backend.list_notification_webhooks().await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            eprintln!(
+                "{} Could not load notification webhooks: {}",
+                "!".yellow(),
+                e
+            );
+            return;
+        }
+    };
+
+    let targets: Vec<_> = webhooks
+        .iter()
+        .filter(|w| w.events.contains(&event))
+        .collect();
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let Ok(client) = reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() else {
+        return;
+    };
+
+    let payload = payload(event.as_str(), worker, version);
+
+    for webhook in targets {
+        if let Err(e) = post(&client, &webhook.url, &payload).await {
+            let message = format!("Notification to {} failed: {}", redact(&webhook.url), e);
+            eprintln!("{} {}", "!".yellow(), message);
+            github::warning(&message);
+        }
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), BackendError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| BackendError::Usage(format!("Invalid webhook URL '{}': {}", url, e)))?;
+
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(BackendError::Usage(format!(
+            "Invalid webhook URL '{}': expected http or https, got {}",
+            url, scheme
+        ))),
+    }
+}
+
+async fn cmd_set<B: Backend>(
+    backend: &B,
+    url: &str,
+    events: &[NotificationEvent],
+) -> Result<(), BackendError> {
+    validate_url(url)?;
+
+    let mut unique = Vec::new();
+
+    for event in events {
+        if !unique.contains(event) {
+            unique.push(*event);
+        }
+    }
+
+    let webhook = backend.set_notification_webhook(url, &unique).await?;
+
+    eprintln!(
+        "{} Webhook {} notified on: {}",
+        "Saved".green(),
+        webhook.url.bold(),
+        format_events(&webhook.events)
+    );
+
+    Ok(())
+}
+
+fn format_events(events: &[NotificationEvent]) -> String {
+    events
+        .iter()
+        .map(|e| e.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+    let webhooks = backend.list_notification_webhooks().await?;
+
+    if webhooks.is_empty() {
+        eprintln!("No notification webhooks.");
+        return Ok(());
+    }
+
+    println!("{}", "Notification Webhooks".bold());
+    println!("{}", "─".repeat(60));
+
+    for webhook in &webhooks {
+        println!(
+            "  {:50} {}",
+            webhook.url,
+            format_events(&webhook.events).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_remove<B: Backend>(backend: &B, url: &str) -> Result<(), BackendError> {
+    backend.delete_notification_webhook(url).await?;
+
+    eprintln!("{} Webhook {} removed.", "Removed".red(), url.bold());
+
+    Ok(())
+}
+
+async fn cmd_test<B: Backend>(backend: &B) -> Result<(), BackendError> {
+    let webhooks = backend.list_notification_webhooks().await?;
+
+    if webhooks.is_empty() {
+        eprintln!("No notification webhooks.");
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?;
+
+    let payload = payload("test", "example", None);
+    let mut failed = 0;
+
+    for webhook in &webhooks {
+        match post(&client, &webhook.url, &payload).await {
+            Ok(()) => eprintln!("  {} {}", "✓".green(), redact(&webhook.url)),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {} {}", "✗".red(), redact(&webhook.url), e.dimmed());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(BackendError::Partial(format!(
+            "{} of {} webhooks failed",
+            failed,
+            webhooks.len()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[test]
+    fn test_payload() {
+        let deploy = payload("deploy", "api", Some(3));
+        assert_eq!(deploy.text, "Deployed worker 'api' (v3)");
+
+        let json = serde_json::to_value(payload("delete", "api", None)).unwrap();
+        assert_eq!(json["event"], "delete");
+        assert_eq!(json["text"], "Deleted worker 'api'");
+        assert!(json.get("version").is_none());
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("https://hooks.slack.com/services/T000/B000/XXX"),
+            "https://hooks.slack.com/…"
+        );
+        assert_eq!(
+            redact("https://ci.example.com:8443/?token=secret"),
+            "https://ci.example.com/…"
+        );
+        assert_eq!(redact("https://ci.example.com"), "https://ci.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_set_replaces_events() {
+        let backend = MockBackend::new();
+        let url = "https://hooks.example.com/deploys";
+
+        let result = NotificationsCommand::Set {
+            url: "ftp://hooks.example.com".to_string(),
+            events: vec![NotificationEvent::Deploy],
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::Usage(_))));
+
+        for events in [
+            vec![NotificationEvent::Deploy, NotificationEvent::Delete],
+            vec![NotificationEvent::Rollback],
+        ] {
+            NotificationsCommand::Set {
+                url: url.to_string(),
+                events,
+            }
+            .run(&backend)
+            .await
+            .unwrap();
+        }
+
+        let webhooks = backend.list_notification_webhooks().await.unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].events, vec![NotificationEvent::Rollback]);
+
+        // No subscriber to deploys: nothing is sent
+        notify(&backend, NotificationEvent::Deploy, "api", Some(1)).await;
+
+        NotificationsCommand::Remove {
+            url: url.to_string(),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+        assert!(
+            backend
+                .list_notification_webhooks()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
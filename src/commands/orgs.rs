@@ -0,0 +1,268 @@
+use crate::backend::{Backend, BackendError, Organization};
+use crate::config::{self, Config};
+use clap::Subcommand;
+use colored::Colorize;
+
+#[derive(Subcommand)]
+pub enum OrgsCommand {
+    /// List the organizations you belong to (* marks the one this alias acts for)
+    #[command(alias = "ls")]
+    List,
+
+    /// Create an organization; you become its owner
+    #[command(after_help = "Example:\n  ow orgs create acme")]
+    Create {
+        /// Organization name (shares the namespace with usernames)
+        name: String,
+    },
+
+    /// Act for an organization with the current alias, or back as yourself
+    #[command(after_help = "Examples:\n  \
+        ow orgs switch acme\n  \
+        ow prod orgs switch acme       Only for the 'prod' alias\n  \
+        ow orgs switch --personal")]
+    Switch {
+        /// Organization name
+        #[arg(required_unless_present = "personal")]
+        name: Option<String>,
+
+        /// Act as your own account again
+        #[arg(long, conflicts_with = "name")]
+        personal: bool,
+    },
+
+    /// Add a user to an organization, or change their role (owners only)
+    #[command(after_help = "Examples:\n  \
+        ow orgs add-member acme alice\n  \
+        ow orgs add-member acme bob --role owner")]
+    AddMember {
+        /// Organization name
+        org: String,
+
+        /// Username to add
+        username: String,
+
+        #[arg(long, value_parser = ["owner", "member"], default_value = "member")]
+        role: String,
+    },
+
+    /// Remove a user from an organization (or leave it by removing yourself)
+    #[command(after_help = "Example:\n  ow orgs remove-member acme alice")]
+    RemoveMember {
+        /// Organization name
+        org: String,
+
+        /// Username to remove
+        username: String,
+    },
+}
+
+impl OrgsCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::List => cmd_list(backend).await,
+            Self::Create { name } => cmd_create(backend, &name).await,
+            Self::Switch { name, .. } => cmd_switch(backend, name).await,
+            Self::AddMember {
+                org,
+                username,
+                role,
+            } => cmd_add_member(backend, &org, &username, &role).await,
+            Self::RemoveMember { org, username } => {
+                cmd_remove_member(backend, &org, &username).await
+            }
+        }
+    }
+}
+
+/// Organization the active alias acts for, if any
+fn current_organization() -> Option<String> {
+    let config = Config::load().ok()?;
+    let alias = config.get_alias(config::active_alias()?)?;
+
+    alias.organization().map(str::to_string)
+}
+
+fn print_organizations(organizations: &[Organization], current: Option<&str>) {
+    for org in organizations {
+        let marker = if current == Some(org.name.as_str()) {
+            "*".green().bold().to_string()
+        } else {
+            " ".to_string()
+        };
+
+        println!(
+            "{} {:24} {:7} {}",
+            marker,
+            org.name.bold(),
+            org.role.cyan(),
+            format!("{} member(s)", org.members).dimmed()
+        );
+    }
+}
+
+async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+    let organizations = backend.list_organizations().await?;
+
+    if organizations.is_empty() {
+        eprintln!("You are not a member of any organization.");
+        return Ok(());
+    }
+
+    let current = current_organization();
+    print_organizations(&organizations, current.as_deref());
+
+    if current.is_none() {
+        println!();
+        println!("{}", "Acting as your personal account.".dimmed());
+    }
+
+    Ok(())
+}
+
+async fn cmd_create<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+    let org = backend.create_organization(name).await?;
+
+    eprintln!(
+        "{} Organization '{}' created.",
+        "Created".green(),
+        org.name.bold()
+    );
+    eprintln!(
+        "Run '{}' to manage its resources.",
+        format!("ow orgs switch {}", org.name).cyan()
+    );
+
+    Ok(())
+}
+
+async fn cmd_switch<B: Backend>(backend: &B, name: Option<String>) -> Result<(), BackendError> {
+    let alias =
+        config::active_alias().ok_or_else(|| BackendError::Api("No alias selected".to_string()))?;
+
+    if let Some(name) = &name {
+        let organizations = backend.list_organizations().await?;
+
+        if !organizations.iter().any(|o| &o.name == name) {
+            return Err(BackendError::NotFound(format!(
+                "You are not a member of organization '{}'",
+                name
+            )));
+        }
+    }
+
+    let config_error = |e: config::ConfigError| BackendError::Api(e.to_string());
+
+    let mut config = Config::load().map_err(config_error)?;
+    config
+        .set_organization(alias, name.clone())
+        .map_err(config_error)?;
+    config.save().map_err(config_error)?;
+
+    match name {
+        Some(name) => eprintln!(
+            "{} Alias '{}' now acts for organization '{}'.",
+            "Switched".green(),
+            alias.bold(),
+            name.bold()
+        ),
+        None => eprintln!(
+            "{} Alias '{}' now acts as your personal account.",
+            "Switched".green(),
+            alias.bold()
+        ),
+    }
+
+    Ok(())
+}
+
+async fn cmd_add_member<B: Backend>(
+    backend: &B,
+    org: &str,
+    username: &str,
+    role: &str,
+) -> Result<(), BackendError> {
+    backend.add_organization_member(org, username, role).await?;
+
+    eprintln!(
+        "{} '{}' is now {} of '{}'.",
+        "Added".green(),
+        username.bold(),
+        if role == "owner" {
+            "an owner"
+        } else {
+            "a member"
+        },
+        org.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_remove_member<B: Backend>(
+    backend: &B,
+    org: &str,
+    username: &str,
+) -> Result<(), BackendError> {
+    backend.remove_organization_member(org, username).await?;
+
+    eprintln!(
+        "{} '{}' removed from '{}'.",
+        "Removed".red(),
+        username.bold(),
+        org.bold()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_create_and_manage_members() {
+        let backend = MockBackend::new();
+
+        OrgsCommand::Create {
+            name: "acme".to_string(),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        // Names are unique
+        assert!(backend.create_organization("acme").await.is_err());
+
+        OrgsCommand::AddMember {
+            org: "acme".to_string(),
+            username: "alice".to_string(),
+            role: "member".to_string(),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let organizations = backend.list_organizations().await.unwrap();
+        assert_eq!(organizations.len(), 1);
+        assert_eq!(organizations[0].role, "owner");
+        assert_eq!(organizations[0].members, 2);
+
+        OrgsCommand::RemoveMember {
+            org: "acme".to_string(),
+            username: "alice".to_string(),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let result = OrgsCommand::RemoveMember {
+            org: "acme".to_string(),
+            username: "alice".to_string(),
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
@@ -1,35 +1,90 @@
-use crate::backend::{Backend, BackendError};
+use super::workers::{diff_keys, print_key_changes};
+use crate::backend::{Backend, BackendError, EnvironmentValue};
+use crate::quiet;
 use clap::Subcommand;
 use colored::Colorize;
+use std::io::{IsTerminal, Write};
 
 #[derive(Subcommand)]
 pub enum ProjectsCommand {
     /// List all projects
     #[command(alias = "ls")]
-    List,
+    List {
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
 
     /// Delete a project and all its workers
     #[command(alias = "rm")]
     Delete {
         /// Project name
         name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Add a standalone worker to a project
+    #[command(
+        after_help = "The worker is switched to the project's environment. When that changes\n\
+        its environment, the key changes are shown and confirmed first.\n\n\
+        Example:\n  \
+        ow projects add-worker my-app api"
+    )]
+    AddWorker {
+        /// Project name
+        project: String,
+
+        /// Worker name
+        worker: String,
+
+        /// Switch the worker's environment without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Detach a worker from a project (its routes are removed)
+    #[command(alias = "rm-worker")]
+    RemoveWorker {
+        /// Project name
+        project: String,
+
+        /// Worker name
+        worker: String,
     },
 }
 
 impl ProjectsCommand {
     pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
         match self {
-            Self::List => cmd_list(backend).await,
-            Self::Delete { name } => cmd_delete(backend, &name).await,
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::Delete { name, yes } => cmd_delete(backend, &name, yes).await,
+            Self::AddWorker {
+                project,
+                worker,
+                yes,
+            } => cmd_add_worker(backend, &project, &worker, yes).await,
+            Self::RemoveWorker { project, worker } => {
+                cmd_remove_worker(backend, &project, &worker).await
+            }
         }
     }
 }
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
     let projects = backend.list_projects().await?;
 
+    if ids {
+        for project in &projects {
+            quiet::print(&project.name);
+        }
+        return Ok(());
+    }
+
     if projects.is_empty() {
-        println!("No projects found.");
+        eprintln!("No projects found.");
         return Ok(());
     }
 
@@ -43,10 +98,17 @@ async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
     Ok(())
 }
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    if !yes {
+        let preview = backend.preview_delete_project(name).await?;
+        super::print_delete_preview(&preview);
+    }
+
+    super::confirm_delete(&format!("project '{}'", name), yes)?;
+
     backend.delete_project(name).await?;
 
-    println!(
+    eprintln!(
         "{} Project '{}' and all its workers deleted.",
         "Deleted".red(),
         name.bold()
@@ -54,3 +116,193 @@ async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendEr
 
     Ok(())
 }
+
+async fn cmd_add_worker<B: Backend>(
+    backend: &B,
+    project: &str,
+    worker: &str,
+    yes: bool,
+) -> Result<(), BackendError> {
+    let target = backend
+        .list_projects()
+        .await?
+        .into_iter()
+        .find(|p| p.name == project)
+        .ok_or_else(|| BackendError::NotFound(format!("Project '{}' not found", project)))?
+        .environment;
+
+    let current = backend
+        .get_worker(worker)
+        .await?
+        .environment
+        .map(|e| e.name);
+
+    if current != target {
+        confirm_environment_switch(backend, worker, current.as_deref(), target.as_deref(), yes)
+            .await?;
+    }
+
+    backend.add_project_worker(project, worker).await?;
+
+    eprintln!(
+        "{} Worker '{}' added to project '{}'.",
+        "Added".green(),
+        worker.bold(),
+        project.bold()
+    );
+
+    Ok(())
+}
+
+async fn environment_values<B: Backend>(
+    backend: &B,
+    environment: Option<&str>,
+) -> Result<Vec<EnvironmentValue>, BackendError> {
+    match environment {
+        Some(name) => Ok(backend.get_environment(name).await?.values),
+        None => Ok(vec![]),
+    }
+}
+
+/// Show the keys the worker gains and loses by taking the project's
+/// environment, and ask before going ahead
+async fn confirm_environment_switch<B: Backend>(
+    backend: &B,
+    worker: &str,
+    current: Option<&str>,
+    target: Option<&str>,
+    yes: bool,
+) -> Result<(), BackendError> {
+    let changes = diff_keys(
+        &environment_values(backend, current).await?,
+        &environment_values(backend, target).await?,
+    );
+    print_key_changes(current, target.unwrap_or("no environment"), &changes);
+
+    if yes {
+        return Ok(());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        return Err(BackendError::Api(format!(
+            "Adding '{}' changes its environment. Pass --yes to add it anyway.",
+            worker
+        )));
+    }
+
+    eprint!("Switch '{}' to the project's environment? [y/N] ", worker);
+    std::io::stderr().flush().ok();
+
+    let mut buf = String::new();
+    std::io::stdin()
+        .read_line(&mut buf)
+        .map_err(|e| BackendError::Api(format!("Failed to read input: {}", e)))?;
+
+    match buf.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(BackendError::Api("Add cancelled.".to_string())),
+    }
+}
+
+async fn cmd_remove_worker<B: Backend>(
+    backend: &B,
+    project: &str,
+    worker: &str,
+) -> Result<(), BackendError> {
+    backend.remove_project_worker(project, worker).await?;
+
+    eprintln!(
+        "{} Worker '{}' removed from project '{}'.",
+        "Removed".yellow(),
+        worker.bold(),
+        project.bold()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_add_then_remove_worker() {
+        let backend = MockBackend::new()
+            .with_project("my-app", None)
+            .with_worker("api", None);
+
+        cmd_add_worker(&backend, "my-app", "api", false)
+            .await
+            .unwrap();
+
+        let err = cmd_add_worker(&backend, "my-app", "api", false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already belongs"));
+
+        cmd_remove_worker(&backend, "my-app", "api").await.unwrap();
+
+        let err = cmd_remove_worker(&backend, "my-app", "api")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BackendError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_main_worker_cannot_be_removed() {
+        let backend = MockBackend::new().with_project("my-app", None);
+
+        let err = cmd_remove_worker(&backend, "my-app", "my-app")
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("is the main worker"));
+    }
+
+    /// Backend with the given environments created
+    async fn with_environments(names: &[&str]) -> MockBackend {
+        let backend = MockBackend::new();
+
+        for name in names {
+            let input = crate::backend::CreateEnvironmentInput {
+                name: name.to_string(),
+                desc: None,
+            };
+            backend.create_environment(input).await.unwrap();
+        }
+
+        backend
+    }
+
+    #[tokio::test]
+    async fn test_add_worker_switches_environment() {
+        let backend = with_environments(&["prod", "dev"])
+            .await
+            .with_project("my-app", Some("prod"))
+            .with_worker("api", None);
+
+        let worker = backend.get_worker("api").await.unwrap();
+        let dev = backend.get_environment("dev").await.unwrap();
+        backend
+            .link_worker_environment(&worker.id, &dev.id)
+            .await
+            .unwrap();
+
+        // Tests run without a terminal, so only --yes gets past the prompt
+        let err = cmd_add_worker(&backend, "my-app", "api", false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("--yes"));
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.environment.unwrap().name, "dev");
+
+        cmd_add_worker(&backend, "my-app", "api", true)
+            .await
+            .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.environment.unwrap().name, "prod");
+    }
+}
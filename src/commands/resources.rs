@@ -0,0 +1,146 @@
+use crate::backend::{Backend, BackendError, ResourceKind};
+use clap::Subcommand;
+use colored::Colorize;
+
+#[derive(Subcommand)]
+pub enum ResourcesCommand {
+    /// Show what references a resource before renaming or deleting it
+    #[command(after_help = "Examples:\n  \
+        ow resources where-used kv cache\n  \
+        ow resources where-used storage assets\n  \
+        ow resources where-used environment prod")]
+    WhereUsed {
+        /// Resource type
+        #[arg(value_enum)]
+        kind: ResourceKind,
+
+        /// Resource name
+        name: String,
+    },
+}
+
+impl ResourcesCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::WhereUsed { kind, name } => cmd_where_used(backend, kind, &name).await,
+        }
+    }
+}
+
+async fn cmd_where_used<B: Backend>(
+    backend: &B,
+    kind: ResourceKind,
+    name: &str,
+) -> Result<(), BackendError> {
+    let usage = backend.resource_usage(kind, name).await?;
+
+    if usage.is_empty() {
+        eprintln!(
+            "{} {} '{}' is not referenced anywhere.",
+            "Unused".green(),
+            kind.as_str(),
+            name.bold()
+        );
+        return Ok(());
+    }
+
+    println!("{} {}", kind.as_str().dimmed(), name.bold());
+    println!("{}", "─".repeat(60));
+
+    if kind != ResourceKind::Environment {
+        println!("{}", "Environments".bold());
+
+        if usage.bindings.is_empty() {
+            println!("  {}", "none".dimmed());
+        }
+
+        for binding in &usage.bindings {
+            println!(
+                "  {:24} {:24} {}",
+                binding.environment,
+                binding.key,
+                binding.binding_type.dimmed()
+            );
+        }
+    }
+
+    for (title, names) in [("Workers", &usage.workers), ("Projects", &usage.projects)] {
+        println!("{}", title.bold());
+
+        if names.is_empty() {
+            println!("  {}", "none".dimmed());
+        }
+
+        for name in names {
+            println!("  {}", name);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{CreateEnvironmentInput, CreateKvInput};
+    use crate::commands::env::EnvCommand;
+
+    #[tokio::test]
+    async fn test_where_used() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        let env = backend.create_environment(input).await.unwrap();
+
+        let input = CreateKvInput {
+            name: "cache".to_string(),
+            desc: None,
+        };
+        backend.create_kv(input).await.unwrap();
+
+        let usage = backend
+            .resource_usage(ResourceKind::Kv, "cache")
+            .await
+            .unwrap();
+        assert!(usage.is_empty());
+
+        EnvCommand::Bind {
+            env: "prod".to_string(),
+            key: "CACHE".to_string(),
+            resource: "cache".to_string(),
+            binding_type: Some("kv".to_string()),
+            dry_run: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        backend
+            .link_worker_environment(&worker.id, &env.id)
+            .await
+            .unwrap();
+
+        let usage = backend
+            .resource_usage(ResourceKind::Kv, "cache")
+            .await
+            .unwrap();
+        assert_eq!(usage.bindings.len(), 1);
+        assert_eq!(usage.bindings[0].environment, "prod");
+        assert_eq!(usage.bindings[0].key, "CACHE");
+        assert_eq!(usage.workers, vec!["api".to_string()]);
+
+        let usage = backend
+            .resource_usage(ResourceKind::Environment, "prod")
+            .await
+            .unwrap();
+        assert_eq!(usage.workers, vec!["api".to_string()]);
+
+        let result = backend.resource_usage(ResourceKind::Kv, "missing").await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
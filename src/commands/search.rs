@@ -0,0 +1,108 @@
+use crate::backend::{Backend, BackendError};
+use clap::Args;
+use colored::Colorize;
+
+#[derive(Args)]
+pub struct SearchArgs {
+    /// Part of a name or description (case-insensitive)
+    pub query: String,
+
+    /// Maximum number of results
+    #[arg(short = 'n', long, default_value_t = 50)]
+    pub limit: i64,
+
+    /// Only print `kind name`, one per line (for scripting)
+    #[arg(short = 'q', long = "ids")]
+    pub ids: bool,
+}
+
+impl SearchArgs {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        let query = self.query.trim();
+
+        if query.is_empty() {
+            return Err(BackendError::Api("Search query is empty".to_string()));
+        }
+
+        let results = backend.search(query, self.limit).await?;
+
+        if self.ids {
+            for result in &results {
+                println!("{} {}", result.kind, result.name);
+            }
+            return Ok(());
+        }
+
+        if results.is_empty() {
+            eprintln!("Nothing matches '{}'.", query);
+            return Ok(());
+        }
+
+        for result in &results {
+            println!(
+                "  {:12} {:30} {}",
+                result.kind.cyan(),
+                result.name.bold(),
+                result.description.as_deref().unwrap_or("").dimmed()
+            );
+        }
+
+        if results.len() as i64 == self.limit {
+            eprintln!();
+            eprintln!(
+                "{}",
+                format!(
+                    "First {} results, narrow the query or pass --limit.",
+                    self.limit
+                )
+                .dimmed()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{CreateEnvironmentInput, CreateKvInput};
+
+    #[tokio::test]
+    async fn test_search_ranking() {
+        let backend = MockBackend::new()
+            .with_worker("payments-api", Some("Charges cards"))
+            .with_worker("web", Some("Storefront, calls payments-api"));
+
+        let input = CreateEnvironmentInput {
+            name: "payments".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        let input = CreateKvInput {
+            name: "sessions".to_string(),
+            desc: None,
+        };
+        backend.create_kv(input).await.unwrap();
+
+        let results = backend.search("PAYMENTS", 50).await.unwrap();
+        let found: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r.kind.as_str(), r.name.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("environment", "payments"),
+                ("worker", "payments-api"),
+                ("worker", "web"),
+            ]
+        );
+
+        assert_eq!(backend.search("payments", 1).await.unwrap().len(), 1);
+        assert!(backend.search("nothing", 50).await.unwrap().is_empty());
+    }
+}
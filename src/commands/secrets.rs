@@ -0,0 +1,232 @@
+use crate::backend::{ACCOUNT_SECRET_PREFIX, Backend, BackendError};
+use crate::quiet;
+use clap::Subcommand;
+use colored::Colorize;
+use std::io::{self, Write};
+
+#[derive(Subcommand)]
+pub enum SecretsCommand {
+    /// List account secrets and the environments referencing them
+    #[command(alias = "ls")]
+    List {
+        /// Only print keys, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
+
+    /// Create or rotate an account secret
+    #[command(after_help = "Examples:\n  \
+        ow secrets set SENTRY_DSN                Prompt for the value (masked)\n  \
+        ow secrets set SENTRY_DSN https://...\n\n\
+        Reference it from environments with:\n  \
+        ow env bind prod SENTRY_DSN @account/SENTRY_DSN")]
+    Set {
+        /// Secret name (conventionally UPPER_SNAKE_CASE)
+        key: String,
+
+        /// Secret value (prompted interactively if omitted)
+        value: Option<String>,
+    },
+
+    /// Delete an account secret no environment references anymore
+    #[command(alias = "rm", after_help = "Example:\n  ow secrets rm OLD_API_KEY")]
+    Delete {
+        /// Secret name
+        key: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+impl SecretsCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::Set { key, value } => {
+                let value = match value {
+                    Some(v) => v,
+                    None => {
+                        eprint!("{}: ", "Enter secret value".dimmed());
+                        io::stderr().flush().ok();
+                        rpassword::read_password().map_err(|e| {
+                            BackendError::Api(format!("Failed to read input: {}", e))
+                        })?
+                    }
+                };
+
+                cmd_set(backend, &key, &value).await
+            }
+            Self::Delete { key, yes } => cmd_delete(backend, &key, yes).await,
+        }
+    }
+}
+
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
+    let secrets = backend.list_account_secrets().await?;
+
+    if ids {
+        for secret in &secrets {
+            quiet::print(&secret.key);
+        }
+        return Ok(());
+    }
+
+    if secrets.is_empty() {
+        eprintln!("No account secrets found.");
+        return Ok(());
+    }
+
+    println!("{}", "Account Secrets".bold());
+    println!("{}", "─".repeat(60));
+
+    for secret in secrets {
+        let used_by = if secret.environments.is_empty() {
+            "unused".dimmed().to_string()
+        } else {
+            secret.environments.join(", ")
+        };
+
+        println!(
+            "  {:30} {:24} {}",
+            secret.key.bold(),
+            used_by,
+            secret
+                .updated_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_set<B: Backend>(backend: &B, key: &str, value: &str) -> Result<(), BackendError> {
+    let secret = backend.set_account_secret(key, value).await?;
+
+    eprintln!("{} Account secret '{}' set.", "Updated".green(), key.bold());
+
+    if secret.environments.is_empty() {
+        eprintln!(
+            "  {} ow env bind <env> {} {}{}",
+            "Use it with:".dimmed(),
+            key,
+            ACCOUNT_SECRET_PREFIX,
+            key
+        );
+    } else {
+        eprintln!(
+            "  {} {}",
+            "Now used by:".dimmed(),
+            secret.environments.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_delete<B: Backend>(backend: &B, key: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("account secret '{}'", key), yes)?;
+
+    backend.delete_account_secret(key).await?;
+
+    eprintln!(
+        "{} Account secret '{}' deleted.",
+        "Deleted".red(),
+        key.bold()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CreateEnvironmentInput;
+    use crate::backend::mock::MockBackend;
+    use crate::commands::env::EnvCommand;
+
+    #[tokio::test]
+    async fn test_referenced_secret_cannot_be_deleted() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        SecretsCommand::Set {
+            key: "SENTRY_DSN".to_string(),
+            value: Some("https://key@sentry.example.com/1".to_string()),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        EnvCommand::Bind {
+            env: "prod".to_string(),
+            key: "SENTRY_DSN".to_string(),
+            resource: "@account/SENTRY_DSN".to_string(),
+            binding_type: None,
+            dry_run: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let secrets = backend.list_account_secrets().await.unwrap();
+        assert_eq!(secrets[0].environments, vec!["prod".to_string()]);
+
+        let result = SecretsCommand::Delete {
+            key: "SENTRY_DSN".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
+
+        EnvCommand::Unset {
+            env: "prod".to_string(),
+            key: "SENTRY_DSN".to_string(),
+            dry_run: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        SecretsCommand::Delete {
+            key: "SENTRY_DSN".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        assert!(backend.list_account_secrets().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bind_unknown_account_secret() {
+        let backend = MockBackend::new();
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        let result = EnvCommand::Bind {
+            env: "prod".to_string(),
+            key: "SENTRY_DSN".to_string(),
+            resource: "@account/SENTRY_DSN".to_string(),
+            binding_type: None,
+            dry_run: false,
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
@@ -0,0 +1,250 @@
+//! Recorded request/response sessions (`--record` / `--replay` on `workers
+//! bench` and `workers invoke`), for comparing a worker's answers before and
+//! after a change.
+
+use super::workers::{InvokeResponse, invoke_worker, replay_headers};
+use crate::backend::{BackendError, CapturedRequest};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const SESSION_VERSION: u32 = 1;
+
+/// Most exchanges kept in a recording: a bench sends far more identical requests
+pub const MAX_RECORDED: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub version: u32,
+    /// Worker the session was recorded against
+    pub worker: String,
+    pub recorded_at: DateTime<Utc>,
+    pub exchanges: Vec<Exchange>,
+}
+
+/// One request and what the worker answered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exchange {
+    pub request: CapturedRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<InvokeResponse>,
+    /// Why no response was received (timeout, connection error...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Session {
+    pub fn new(worker: &str, exchanges: Vec<Exchange>) -> Self {
+        Self {
+            version: SESSION_VERSION,
+            worker: worker.to_string(),
+            recorded_at: Utc::now(),
+            exchanges,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BackendError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BackendError::Api(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        let session: Self = serde_json::from_str(&content).map_err(|e| {
+            BackendError::Api(format!("Invalid session in {}: {}", path.display(), e))
+        })?;
+
+        if session.version != SESSION_VERSION {
+            return Err(BackendError::Api(format!(
+                "Unsupported session version {} in {}",
+                session.version,
+                path.display()
+            )));
+        }
+
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BackendError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| BackendError::Api(format!("Failed to encode session: {}", e)))?;
+
+        std::fs::write(path, content)
+            .map_err(|e| BackendError::Api(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+impl Exchange {
+    /// How the replayed answer differs from the recorded one: status, error,
+    /// then body. Headers are ignored, they carry dates and request IDs.
+    pub fn difference(&self, replayed: &Result<InvokeResponse, String>) -> Option<String> {
+        let recorded = match (&self.response, &self.error) {
+            (Some(response), _) => Ok(response),
+            (None, error) => Err(error.clone().unwrap_or_default()),
+        };
+
+        match (recorded, replayed) {
+            (Ok(then), Ok(now)) if then.status != now.status => {
+                Some(format!("status {} → {}", then.status, now.status))
+            }
+            (Ok(then), Ok(now)) if then.body != now.body => Some(format!(
+                "body differs ({} → {} bytes)",
+                then.body.len(),
+                now.body.len()
+            )),
+            (Ok(_), Ok(_)) => None,
+            (Ok(then), Err(e)) => Some(format!("status {} → {}", then.status, e)),
+            (Err(e), Ok(now)) => Some(format!("{} → status {}", e, now.status)),
+            (Err(_), Err(_)) => None,
+        }
+    }
+}
+
+/// Re-send a session's requests one at a time to `base_url`, printing each
+/// difference with the recording. Returns how many exchanges differed.
+pub async fn replay(base_url: &str, session: &Session) -> usize {
+    let mut differing = 0;
+    let mut durations = (0u128, 0u128, 0usize);
+
+    for exchange in &session.exchanges {
+        let request = &exchange.request;
+
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            request.path.trim_start_matches('/')
+        );
+
+        let replayed = invoke_worker(
+            &url,
+            &request.method,
+            &replay_headers(request),
+            request.body.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string());
+
+        if let (Some(then), Ok(now)) = (&exchange.response, &replayed) {
+            durations.0 += then.duration_ms;
+            durations.1 += now.duration_ms;
+            durations.2 += 1;
+        }
+
+        if let Some(difference) = exchange.difference(&replayed) {
+            differing += 1;
+            println!(
+                "  {} {} {}: {}",
+                "✗".red(),
+                request.method.to_uppercase().bold(),
+                request.path,
+                difference
+            );
+        }
+    }
+
+    let (then, now, count) = durations;
+    let total = session.exchanges.len();
+
+    println!();
+
+    if differing == 0 {
+        println!(
+            "{} {} responses match the recording",
+            "✓".green(),
+            total.to_string().bold()
+        );
+    } else {
+        println!(
+            "{} {} of {} responses differ from the recording",
+            "✗".red(),
+            differing.to_string().bold(),
+            total
+        );
+    }
+
+    if count > 0 {
+        println!(
+            "  {} {} ms recorded, {} ms now",
+            "Mean latency:".dimmed(),
+            then / count as u128,
+            now / count as u128
+        );
+    }
+
+    differing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn response(status: u16, body: &str) -> InvokeResponse {
+        InvokeResponse {
+            status,
+            headers: BTreeMap::new(),
+            body: body.to_string(),
+            truncated: false,
+            duration_ms: 10,
+        }
+    }
+
+    fn exchange(response: Option<InvokeResponse>, error: Option<&str>) -> Exchange {
+        Exchange {
+            request: CapturedRequest {
+                id: None,
+                method: "GET".to_string(),
+                path: "/api/orders".to_string(),
+                headers: BTreeMap::new(),
+                body: None,
+                status: None,
+                captured_at: None,
+            },
+            response,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_difference() {
+        let ok = exchange(Some(response(200, "[]")), None);
+
+        assert_eq!(ok.difference(&Ok(response(200, "[]"))), None);
+        assert_eq!(
+            ok.difference(&Ok(response(500, "[]"))),
+            Some("status 200 → 500".to_string())
+        );
+        assert_eq!(
+            ok.difference(&Ok(response(200, "[{}]"))),
+            Some("body differs (2 → 4 bytes)".to_string())
+        );
+        assert_eq!(
+            ok.difference(&Err("timeout".to_string())),
+            Some("status 200 → timeout".to_string())
+        );
+
+        let failed = exchange(None, Some("timeout"));
+        assert_eq!(
+            failed.difference(&Err("connection error".to_string())),
+            None
+        );
+        assert_eq!(
+            failed.difference(&Ok(response(200, ""))),
+            Some("timeout → status 200".to_string())
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let session = Session::new("my-api", vec![exchange(Some(response(200, "ok")), None)]);
+
+        let path = std::env::temp_dir().join(format!("ow-session-{}.json", uuid::Uuid::new_v4()));
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.worker, "my-api");
+        assert_eq!(loaded.exchanges.len(), 1);
+        assert_eq!(loaded.exchanges[0].response.as_ref().unwrap().body, "ok");
+    }
+}
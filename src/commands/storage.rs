@@ -1,18 +1,78 @@
-use crate::backend::{Backend, BackendError, CreateStorageInput};
+use crate::backend::{
+    Backend, BackendError, CreateStorageInput, StorageConfig, UpdateStorageInput,
+};
+use crate::config::PlatformStorageConfig;
+use crate::quiet;
+use crate::s3::{self, Asset, ObjectStorage, S3Client, S3Config};
 use clap::Subcommand;
 use colored::Colorize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Timeout for fetching the probe through the public URL
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Subcommand)]
 pub enum StorageCommand {
-    /// List all storage configurations
-    #[command(alias = "ls")]
-    List,
+    /// List all storage configurations, or the objects in one (DB aliases)
+    #[command(
+        alias = "ls",
+        after_help = "Examples:\n  \
+        ow storage ls                      Storage configurations\n  \
+        ow local storage ls assets         Objects in a bucket\n  \
+        ow local storage ls assets img/    Objects under a prefix"
+    )]
+    List {
+        /// Storage configuration whose objects to list
+        name: Option<String>,
+
+        /// Only objects whose key starts with this prefix
+        #[arg(requires = "name")]
+        prefix: Option<String>,
+
+        /// Only print names (or object keys), one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
+
+    /// Copy a file to or from a bucket; the bucket side starts with ':' (DB aliases)
+    #[command(after_help = "Examples:\n  \
+        ow local storage cp assets ./logo.png :img/logo.png    Upload\n  \
+        ow local storage cp assets ./logo.png :img/            Upload, keeping the file name\n  \
+        ow local storage cp assets :img/logo.png ./logo.png    Download")]
+    Cp {
+        /// Storage configuration name
+        name: String,
+
+        /// Source: a local file, or :key
+        from: String,
+
+        /// Destination: :key, or a local file or folder
+        to: String,
+    },
 
     /// Show storage configuration details
-    #[command(after_help = "Example:\n  ow storage get my-bucket")]
+    #[command(after_help = "Examples:\n  \
+        ow storage get my-bucket\n  \
+        ow storage get my-bucket --verify   (also check the public URL)")]
     Get {
         /// Storage configuration name
         name: String,
+
+        /// Only print the name (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+
+        /// Check that the public URL serves uploaded objects (DB aliases)
+        #[arg(long, conflicts_with = "ids")]
+        verify: bool,
+    },
+
+    /// Upload a probe object and fetch it back through the public URL (DB aliases)
+    #[command(after_help = "Example:\n  ow storage test my-assets")]
+    Test {
+        /// Storage configuration name
+        name: String,
     },
 
     /// Create a storage configuration for S3-compatible object storage
@@ -64,19 +124,117 @@ pub enum StorageCommand {
         description: Option<String>,
     },
 
-    /// Delete a storage configuration
-    #[command(alias = "rm", after_help = "Example:\n  ow storage delete old-bucket")]
+    /// Update a storage configuration's details
+    #[command(after_help = "Examples:\n  \
+        ow storage update assets -d \"Static site assets\"\n  \
+        ow storage update assets -d \"\"   (clear)")]
+    Update {
+        /// Storage configuration name
+        name: String,
+
+        /// New description (empty to clear)
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Rename a storage configuration (bindings keep working)
+    #[command(
+        alias = "mv",
+        after_help = "Example:\n  ow storage rename assets site-assets"
+    )]
+    Rename {
+        /// Current name
+        name: String,
+
+        /// New name
+        new_name: String,
+    },
+
+    /// Delete a storage configuration, or one object in it (DB aliases)
+    #[command(
+        alias = "rm",
+        after_help = "Examples:\n  \
+        ow storage delete old-bucket                 Configuration (asks first)\n  \
+        ow local storage rm assets img/old-logo.png  One object"
+    )]
     Delete {
-        /// Storage configuration name to delete
+        /// Storage configuration name
         name: String,
+
+        /// Object key to delete instead of the configuration
+        key: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show or set the bucket behind platform storage configs
+    Default {
+        #[command(subcommand)]
+        command: StorageDefaultCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageDefaultCommand {
+    /// Show the platform bucket
+    #[command(after_help = "Example:\n  ow storage default get")]
+    Get,
+
+    /// Set the platform bucket (alias config for DB aliases, server-side for API aliases)
+    #[command(after_help = "Example:\n  \
+        ow storage default set \\\n    \
+          --endpoint https://xxx.r2.cloudflarestorage.com \\\n    \
+          --bucket my-assets \\\n    \
+          --access-key-id AKIA... \\\n    \
+          --secret-access-key ...")]
+    Set {
+        /// S3-compatible endpoint URL (e.g., https://xxx.r2.cloudflarestorage.com)
+        #[arg(long)]
+        endpoint: String,
+
+        /// Bucket name
+        #[arg(long)]
+        bucket: String,
+
+        /// Access key ID
+        #[arg(long)]
+        access_key_id: String,
+
+        /// Secret access key
+        #[arg(long)]
+        secret_access_key: String,
+
+        /// Region (default: auto)
+        #[arg(long, default_value = "auto")]
+        region: String,
+
+        /// Optional key prefix for all uploads
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Session token for temporary (STS) credentials
+        #[arg(long)]
+        session_token: Option<String>,
     },
 }
 
 impl StorageCommand {
     pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
         match self {
-            Self::List => cmd_list(backend).await,
-            Self::Get { name } => cmd_get(backend, &name).await,
+            Self::List {
+                name: Some(name),
+                prefix,
+                ids,
+            } => cmd_objects(backend, &name, prefix.as_deref().unwrap_or(""), ids).await,
+            Self::List { ids, .. } => cmd_list(backend, ids).await,
+            Self::Cp { name, from, to } => cmd_cp(backend, &name, &from, &to).await,
+            Self::Get { name, ids, verify } => cmd_get(backend, &name, ids, verify).await,
+            Self::Test { name } => {
+                let config = backend.get_storage(&name).await?;
+                verify_public_url(backend, &config).await
+            }
             Self::Create {
                 name,
                 provider,
@@ -104,16 +262,87 @@ impl StorageCommand {
                 )
                 .await
             }
-            Self::Delete { name } => cmd_delete(backend, &name).await,
+            Self::Update { name, description } => cmd_update(backend, &name, description).await,
+            Self::Rename { name, new_name } => cmd_rename(backend, &name, new_name).await,
+            Self::Delete {
+                name,
+                key: Some(key),
+                ..
+            } => cmd_delete_object(backend, &name, &key).await,
+            Self::Delete { name, yes, .. } => cmd_delete(backend, &name, yes).await,
+            Self::Default {
+                command: StorageDefaultCommand::Get,
+            } => cmd_default_get(backend).await,
+            Self::Default {
+                command:
+                    StorageDefaultCommand::Set {
+                        endpoint,
+                        bucket,
+                        access_key_id,
+                        secret_access_key,
+                        region,
+                        prefix,
+                        session_token,
+                    },
+            } => {
+                let storage = PlatformStorageConfig {
+                    endpoint,
+                    bucket,
+                    access_key_id,
+                    secret_access_key,
+                    region,
+                    prefix,
+                    session_token,
+                };
+
+                cmd_default_set(backend, storage).await
+            }
         }
     }
 }
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
+async fn cmd_default_get<B: Backend>(backend: &B) -> Result<(), BackendError> {
+    let Some(storage) = backend.get_platform_storage().await? else {
+        eprintln!("No platform storage configured. Set one with 'ow storage default set'.");
+        return Ok(());
+    };
+
+    println!("{:12} {}", "Endpoint:".dimmed(), storage.endpoint);
+    println!("{:12} {}", "Bucket:".dimmed(), storage.bucket.bold());
+    println!("{:12} {}", "Region:".dimmed(), storage.region);
+
+    if let Some(prefix) = &storage.prefix {
+        println!("{:12} {}", "Prefix:".dimmed(), prefix);
+    }
+
+    Ok(())
+}
+
+async fn cmd_default_set<B: Backend>(
+    backend: &B,
+    storage: PlatformStorageConfig,
+) -> Result<(), BackendError> {
+    let summary = format!("{}/{}", storage.endpoint, storage.bucket);
+
+    backend.set_platform_storage(storage).await?;
+
+    eprintln!("{} platform storage to {}", "Set".green(), summary.cyan());
+
+    Ok(())
+}
+
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
     let configs = backend.list_storage().await?;
 
+    if ids {
+        for config in &configs {
+            quiet::print(&config.name);
+        }
+        return Ok(());
+    }
+
     if configs.is_empty() {
-        println!("No storage configs found.");
+        eprintln!("No storage configs found.");
         return Ok(());
     }
 
@@ -133,9 +362,222 @@ async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
     Ok(())
 }
 
-async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+/// Client for a storage config's bucket, with its stored credentials
+async fn storage_client<B: Backend>(backend: &B, name: &str) -> Result<S3Client, BackendError> {
+    let credentials = backend.get_storage_credentials(name).await?;
+
+    Ok(S3Client::new(S3Config {
+        bucket: credentials.bucket,
+        endpoint: credentials.endpoint,
+        access_key_id: credentials.access_key_id,
+        secret_access_key: credentials.secret_access_key,
+        region: credentials.region,
+        prefix: credentials.prefix,
+        session_token: credentials.session_token,
+    }))
+}
+
+async fn cmd_objects<B: Backend>(
+    backend: &B,
+    name: &str,
+    prefix: &str,
+    ids: bool,
+) -> Result<(), BackendError> {
+    let client = storage_client(backend, name).await?;
+
+    let objects = client
+        .list(prefix)
+        .await
+        .map_err(|e| BackendError::Api(format!("Failed to list '{}': {}", name, e)))?;
+
+    if ids {
+        for object in &objects {
+            quiet::print(&object.key);
+        }
+        return Ok(());
+    }
+
+    if objects.is_empty() {
+        eprintln!("No objects found.");
+        return Ok(());
+    }
+
+    for object in &objects {
+        let modified = object
+            .last_modified
+            .get(..19)
+            .unwrap_or_default()
+            .replace('T', " ");
+
+        println!(
+            "  {:>10}  {}  {}",
+            format_size(object.size),
+            modified.dimmed(),
+            object.key
+        );
+    }
+
+    let total: u64 = objects.iter().map(|o| o.size).sum();
+    eprintln!();
+    eprintln!("{} objects, {}", objects.len(), format_size(total));
+
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Which way a copy goes, from the ':' marking the bucket side
+#[derive(Debug, PartialEq)]
+enum Transfer {
+    Upload { local: PathBuf, key: String },
+    Download { key: String, local: PathBuf },
+}
+
+fn parse_copy(from: &str, to: &str) -> Result<Transfer, String> {
+    match (from.strip_prefix(':'), to.strip_prefix(':')) {
+        (None, Some(key)) => {
+            let local = PathBuf::from(from);
+
+            // A key ending in '/' (or empty) is a folder: keep the file name
+            let key = match key.is_empty() || key.ends_with('/') {
+                true => {
+                    let file_name = local
+                        .file_name()
+                        .ok_or_else(|| format!("'{}' is not a file", from))?;
+                    format!("{}{}", key, file_name.to_string_lossy())
+                }
+                false => key.to_string(),
+            };
+
+            Ok(Transfer::Upload { local, key })
+        }
+        (Some(key), None) if !key.is_empty() => Ok(Transfer::Download {
+            key: key.to_string(),
+            local: PathBuf::from(to),
+        }),
+        (Some(_), None) => Err("Missing object key after ':'".to_string()),
+        _ => Err(
+            "Exactly one side must be a bucket key starting with ':' (e.g. :img/logo.png)"
+                .to_string(),
+        ),
+    }
+}
+
+async fn cmd_cp<B: Backend>(
+    backend: &B,
+    name: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), BackendError> {
+    let transfer = parse_copy(from, to).map_err(BackendError::Api)?;
+    let client = storage_client(backend, name).await?;
+
+    match transfer {
+        Transfer::Upload { local, key } => {
+            if !local.is_file() {
+                return Err(BackendError::Api(format!(
+                    "'{}' is not a file",
+                    local.display()
+                )));
+            }
+
+            let content_type = s3::content_type(&key, &local);
+            let asset =
+                Asset::from_file(key.clone(), local.clone(), content_type).map_err(|e| {
+                    BackendError::Api(format!("Failed to read file '{}': {}", local.display(), e))
+                })?;
+
+            client
+                .put(&asset)
+                .await
+                .map_err(|e| BackendError::Api(format!("Upload failed: {}", e)))?;
+
+            eprintln!(
+                "{} {} → {}:{} ({})",
+                "Uploaded".green(),
+                local.display(),
+                name,
+                key.bold(),
+                format_size(asset.size)
+            );
+        }
+        Transfer::Download { key, local } => {
+            let dest = match local.is_dir() {
+                true => local.join(key.rsplit('/').next().unwrap_or(&key)),
+                false => local,
+            };
+
+            let size = client
+                .download(&key, &dest)
+                .await
+                .map_err(|e| BackendError::Api(format!("Download failed: {}", e)))?
+                .ok_or_else(|| {
+                    BackendError::NotFound(format!("Object '{}' not found in '{}'", key, name))
+                })?;
+
+            eprintln!(
+                "{} {}:{} → {} ({})",
+                "Downloaded".green(),
+                name,
+                key.bold(),
+                dest.display(),
+                format_size(size)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_delete_object<B: Backend>(
+    backend: &B,
+    name: &str,
+    key: &str,
+) -> Result<(), BackendError> {
+    let client = storage_client(backend, name).await?;
+
+    // DELETE succeeds on missing keys, so a typo would go unnoticed
+    let exists = client
+        .head(key)
+        .await
+        .map_err(|e| BackendError::Api(format!("Failed to check '{}': {}", key, e)))?;
+
+    if exists.is_none() {
+        return Err(BackendError::NotFound(format!(
+            "Object '{}' not found in '{}'",
+            key, name
+        )));
+    }
+
+    client
+        .delete(key)
+        .await
+        .map_err(|e| BackendError::Api(format!("Delete failed: {}", e)))?;
+
+    eprintln!("{} {}:{}", "Deleted".red(), name, key.bold());
+
+    Ok(())
+}
+
+async fn cmd_get<B: Backend>(
+    backend: &B,
+    name: &str,
+    ids: bool,
+    verify: bool,
+) -> Result<(), BackendError> {
     let config = backend.get_storage(name).await?;
 
+    if ids {
+        quiet::print(&config.name);
+        return Ok(());
+    }
+
     println!("{:12} {}", "Name:".dimmed(), config.name.bold());
     println!("{:12} {}", "ID:".dimmed(), config.id);
     println!("{:12} {}", "Provider:".dimmed(), config.provider);
@@ -172,9 +614,109 @@ async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError
         config.created_at.format("%Y-%m-%d %H:%M:%S")
     );
 
+    if verify {
+        eprintln!();
+        verify_public_url(backend, &config).await?;
+    }
+
     Ok(())
 }
 
+/// Upload a probe object with the bucket credentials, fetch it through the
+/// public URL and compare. Uploads can succeed while public reads fail (bucket
+/// not public, URL pointing at another bucket or prefix), which only shows up
+/// as broken assets in production.
+async fn verify_public_url<B: Backend>(
+    backend: &B,
+    config: &StorageConfig,
+) -> Result<(), BackendError> {
+    let Some(public_url) = &config.public_url else {
+        return Err(BackendError::Api(format!(
+            "Storage '{}' has no public URL to verify",
+            config.name
+        )));
+    };
+
+    let client = storage_client(backend, &config.name).await?;
+
+    let nonce = uuid::Uuid::new_v4().to_string();
+    let key = format!(".openworkers-probe-{}.txt", nonce);
+    let content = format!("openworkers probe {}", nonce);
+
+    eprintln!("{} Uploading probe {}", "→".blue(), key.dimmed());
+
+    let path = std::env::temp_dir().join(&key);
+    std::fs::write(&path, &content)
+        .map_err(|e| BackendError::Api(format!("Failed to write probe: {}", e)))?;
+
+    let uploaded = Asset::from_file(key.clone(), path.clone(), "text/plain".to_string())
+        .map_err(|e| e.to_string());
+    let uploaded = match uploaded {
+        Ok(asset) => client.put(&asset).await.map(|_| ()).map_err(String::from),
+        Err(e) => Err(e),
+    };
+    std::fs::remove_file(&path).ok();
+
+    uploaded.map_err(|e| BackendError::Api(format!("Probe upload failed: {}", e)))?;
+
+    let url = probe_url(public_url, &key);
+    eprintln!("{} Fetching {}", "→".blue(), url.dimmed());
+
+    let fetched = match reqwest::Client::new()
+        .get(&url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            probe_verdict(status, body == content)
+        }
+        Err(e) => Err(format!("request failed: {}", e)),
+    };
+
+    if let Err(e) = client.delete(&key).await {
+        eprintln!(
+            "{} Could not delete probe {}: {}",
+            "warning:".yellow(),
+            key,
+            e
+        );
+    }
+
+    match fetched {
+        Ok(()) => {
+            eprintln!("{} Public URL serves uploaded objects", "✓".green());
+            Ok(())
+        }
+        Err(reason) => Err(BackendError::Api(format!(
+            "Public URL check failed: {}",
+            reason
+        ))),
+    }
+}
+
+/// Where an object uploaded under `key` should be publicly readable
+fn probe_url(public_url: &str, key: &str) -> String {
+    format!("{}/{}", public_url.trim_end_matches('/'), key)
+}
+
+fn probe_verdict(status: u16, body_matches: bool) -> Result<(), String> {
+    match (status, body_matches) {
+        (200..=299, true) => Ok(()),
+        (200..=299, false) => Err(
+            "the URL answered with different content (another bucket or prefix, or a stale cache)"
+                .to_string(),
+        ),
+        (403, _) => Err("HTTP 403, the bucket is not publicly readable".to_string()),
+        (404, _) => {
+            Err("HTTP 404, the public URL doesn't serve this bucket and prefix".to_string())
+        }
+        (status, _) => Err(format!("HTTP {}", status)),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn cmd_create<B: Backend>(
     backend: &B,
@@ -191,7 +733,7 @@ async fn cmd_create<B: Backend>(
 ) -> Result<(), BackendError> {
     if provider == "s3" {
         if bucket.is_none() {
-            return Err(BackendError::Api(
+            return Err(BackendError::Usage(
                 "--bucket is required for s3 provider".to_string(),
             ));
         }
@@ -224,7 +766,7 @@ async fn cmd_create<B: Backend>(
 
     let config = backend.create_storage(input).await?;
 
-    println!(
+    eprintln!(
         "{} Storage '{}' created ({} provider).",
         "Created".green(),
         config.name.bold(),
@@ -234,10 +776,161 @@ async fn cmd_create<B: Backend>(
     Ok(())
 }
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+async fn cmd_update<B: Backend>(
+    backend: &B,
+    name: &str,
+    description: Option<String>,
+) -> Result<(), BackendError> {
+    if description.is_none() {
+        return Err(BackendError::Usage(
+            "Nothing to update. Pass --description.".to_string(),
+        ));
+    }
+
+    let input = UpdateStorageInput {
+        desc: description,
+        ..Default::default()
+    };
+
+    backend.update_storage(name, input).await?;
+
+    eprintln!("{} Storage '{}' updated.", "Updated".green(), name.bold());
+
+    Ok(())
+}
+
+async fn cmd_rename<B: Backend>(
+    backend: &B,
+    name: &str,
+    new_name: String,
+) -> Result<(), BackendError> {
+    let input = UpdateStorageInput {
+        name: Some(new_name),
+        ..Default::default()
+    };
+
+    let updated = backend.update_storage(name, input).await?;
+
+    eprintln!(
+        "{} Storage '{}' renamed to '{}'.",
+        "Renamed".green(),
+        name,
+        updated.name.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("storage '{}'", name), yes)?;
+
     backend.delete_storage(name).await?;
 
-    println!("{} Storage '{}' deleted.", "Deleted".red(), name.bold());
+    eprintln!("{} Storage '{}' deleted.", "Deleted".red(), name.bold());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_default_set_and_get() {
+        let backend = MockBackend::new();
+
+        assert!(backend.get_platform_storage().await.unwrap().is_none());
+
+        StorageCommand::Default {
+            command: StorageDefaultCommand::Set {
+                endpoint: "https://s3.example.com".to_string(),
+                bucket: "platform-assets".to_string(),
+                access_key_id: "key".to_string(),
+                secret_access_key: "secret".to_string(),
+                region: "auto".to_string(),
+                prefix: Some("ow".to_string()),
+                session_token: None,
+            },
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let storage = backend.get_platform_storage().await.unwrap().unwrap();
+        assert_eq!(storage.bucket, "platform-assets");
+        assert_eq!(storage.prefix.as_deref(), Some("ow"));
+
+        StorageCommand::Default {
+            command: StorageDefaultCommand::Get,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_probe_url() {
+        assert_eq!(
+            probe_url("https://assets.example.com/", ".probe.txt"),
+            "https://assets.example.com/.probe.txt"
+        );
+        assert_eq!(
+            probe_url("https://cdn.example.com/site", "a.txt"),
+            "https://cdn.example.com/site/a.txt"
+        );
+    }
+
+    #[test]
+    fn test_probe_verdict() {
+        assert!(probe_verdict(200, true).is_ok());
+        assert!(
+            probe_verdict(200, false)
+                .unwrap_err()
+                .contains("different content")
+        );
+        assert!(
+            probe_verdict(403, false)
+                .unwrap_err()
+                .contains("not publicly readable")
+        );
+        assert!(probe_verdict(404, false).unwrap_err().contains("404"));
+        assert_eq!(probe_verdict(500, false).unwrap_err(), "HTTP 500");
+    }
+
+    #[test]
+    fn test_parse_copy() {
+        assert_eq!(
+            parse_copy("./dist/logo.png", ":img/").unwrap(),
+            Transfer::Upload {
+                local: PathBuf::from("./dist/logo.png"),
+                key: "img/logo.png".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_copy("logo.png", ":brand.png").unwrap(),
+            Transfer::Upload {
+                local: PathBuf::from("logo.png"),
+                key: "brand.png".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_copy(":img/logo.png", ".").unwrap(),
+            Transfer::Download {
+                key: "img/logo.png".to_string(),
+                local: PathBuf::from("."),
+            }
+        );
+
+        assert!(parse_copy("a.png", "b.png").is_err());
+        assert!(parse_copy(":a.png", ":b.png").is_err());
+        assert!(parse_copy(":", "a.png").is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(5 * 1_048_576), "5.0 MB");
+    }
+}
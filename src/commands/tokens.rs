@@ -0,0 +1,243 @@
+use crate::backend::{Backend, BackendError, CreateTokenInput};
+use crate::config::TokenScope;
+use crate::quiet;
+use clap::Subcommand;
+use colored::Colorize;
+
+#[derive(Subcommand)]
+pub enum TokensCommand {
+    /// List API tokens
+    #[command(alias = "ls")]
+    List {
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+    },
+
+    /// Create a token for a CI system or other service account
+    #[command(after_help = "Examples:\n  \
+        ow tokens create-service github-actions\n  \
+        ow tokens create-service nightly-report --scope read --expires 90d")]
+    CreateService {
+        /// Token name (e.g. the CI system using it)
+        name: String,
+
+        /// What the token is allowed to do
+        #[arg(long, value_enum, default_value = "deploy")]
+        scope: TokenScope,
+
+        /// Token lifetime (e.g. 30d, 12h); never expires if omitted
+        #[arg(long)]
+        expires: Option<String>,
+    },
+
+    /// Revoke a token by name or ID
+    #[command(
+        alias = "rm",
+        after_help = "Example:\n  ow tokens revoke github-actions"
+    )]
+    Revoke {
+        /// Token name or ID
+        name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+impl TokensCommand {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::List { ids } => cmd_list(backend, ids).await,
+            Self::CreateService {
+                name,
+                scope,
+                expires,
+            } => cmd_create_service(backend, name, scope, expires.as_deref()).await,
+            Self::Revoke { name, yes } => cmd_revoke(backend, &name, yes).await,
+        }
+    }
+}
+
+async fn cmd_list<B: Backend>(backend: &B, ids: bool) -> Result<(), BackendError> {
+    let tokens = backend.list_tokens().await?;
+
+    if ids {
+        for token in &tokens {
+            quiet::print(&token.name);
+        }
+        return Ok(());
+    }
+
+    if tokens.is_empty() {
+        eprintln!("No tokens found.");
+        return Ok(());
+    }
+
+    println!("{}", "API Tokens".bold());
+    println!("{}", "─".repeat(60));
+
+    for token in tokens {
+        let expires = token
+            .expires_at
+            .map(|e| format!("expires {}", e.format("%Y-%m-%d")))
+            .unwrap_or_else(|| "no expiry".to_string());
+
+        let last_used = token
+            .last_used_at
+            .map(|u| format!("last used {}", u.format("%Y-%m-%d")))
+            .unwrap_or_else(|| "never used".to_string());
+
+        println!(
+            "  {:24} {:7} {}…  {}",
+            token.name.bold(),
+            token.scope.to_string().cyan(),
+            token.prefix,
+            format!("{}, {}", expires, last_used).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_create_service<B: Backend>(
+    backend: &B,
+    name: String,
+    scope: TokenScope,
+    expires: Option<&str>,
+) -> Result<(), BackendError> {
+    let expires_at = expires
+        .map(super::parse_duration)
+        .transpose()
+        .map_err(BackendError::Api)?
+        .map(|secs| chrono::Utc::now() + chrono::Duration::seconds(secs));
+
+    let input = CreateTokenInput {
+        name,
+        scope,
+        expires_at,
+    };
+
+    let issued = backend.create_token(input).await?;
+
+    eprintln!(
+        "{} {} token '{}'. It will not be shown again.",
+        "Created".green(),
+        issued.scope.to_string().cyan(),
+        issued.name.bold()
+    );
+    eprintln!();
+    println!("{:12} {}", "Token:".dimmed(), issued.token);
+
+    if let Some(expires_at) = issued.expires_at {
+        println!(
+            "{:12} {}",
+            "Expires:".dimmed(),
+            expires_at.format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_revoke<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    super::confirm_delete(&format!("token '{}'", name), yes)?;
+
+    backend.revoke_token(name).await?;
+
+    eprintln!("{} Token '{}' revoked.", "Revoked".red(), name.bold());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    #[tokio::test]
+    async fn test_create_service_token() {
+        let backend = MockBackend::new();
+
+        TokensCommand::CreateService {
+            name: "github-actions".to_string(),
+            scope: TokenScope::Deploy,
+            expires: Some("90d".to_string()),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let tokens = backend.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].scope, TokenScope::Deploy);
+        assert!(tokens[0].expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_create_service_token_duplicate_name() {
+        let backend = MockBackend::new();
+
+        for expected_ok in [true, false] {
+            let result = TokensCommand::CreateService {
+                name: "ci".to_string(),
+                scope: TokenScope::Read,
+                expires: None,
+            }
+            .run(&backend)
+            .await;
+
+            assert_eq!(result.is_ok(), expected_ok);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_service_token_invalid_expiry() {
+        let backend = MockBackend::new();
+
+        let result = TokensCommand::CreateService {
+            name: "ci".to_string(),
+            scope: TokenScope::Deploy,
+            expires: Some("soon".to_string()),
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::Api(_))));
+        assert!(backend.list_tokens().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_token() {
+        let backend = MockBackend::new();
+
+        TokensCommand::CreateService {
+            name: "ci".to_string(),
+            scope: TokenScope::Deploy,
+            expires: None,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        TokensCommand::Revoke {
+            name: "ci".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        assert!(backend.list_tokens().await.unwrap().is_empty());
+
+        let result = TokensCommand::Revoke {
+            name: "ci".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::backend::{Backend, BackendError};
+use clap::{ArgMatches, Args};
+use colored::Colorize;
+
+/// Command groups whose mutating actions get a request ID
+const TRACED_COMMANDS: &[&str] = &[
+    "workers",
+    "projects",
+    "env",
+    "storage",
+    "kv",
+    "flags",
+    "databases",
+    "tokens",
+];
+
+/// Actions that only read, per command group, so there is nothing to trace.
+/// Listed as pairs: the same action name can write elsewhere (`storage test`
+/// uploads a probe object).
+const READ_ONLY_ACTIONS: &[(&str, &[&str])] = &[
+    (
+        "workers",
+        &[
+            "list",
+            "get",
+            "verify-hash",
+            "bench",
+            "invoke",
+            "history",
+            "export",
+            "replay",
+            "runtime",
+            "versions",
+        ],
+    ),
+    ("projects", &["list"]),
+    ("env", &["list", "get", "history", "validate", "template"]),
+    ("storage", &["list", "get"]),
+    ("kv", &["list", "get", "keys"]),
+    ("flags", &["list"]),
+    ("databases", &["list", "get"]),
+    ("tokens", &["list"]),
+    ("users", &["list", "get"]),
+    ("orgs", &["list"]),
+    ("secrets", &["list"]),
+    ("notifications", &["list"]),
+    ("resources", &["where-used"]),
+];
+
+/// Top-level commands that only read
+const READ_ONLY_COMMANDS: &[&str] = &["search"];
+
+/// Top-level commands that write, traced under their own name
+const MUTATING_COMMANDS: &[&str] = &["deploy"];
+
+/// Name of the mutating operation selected on the command line (e.g.
+/// "workers deploy"), or None for reads and local-only commands.
+/// Uses the parsed matches so aliases like `w rm` resolve to canonical names.
+pub fn mutating_operation(matches: &ArgMatches) -> Option<String> {
+    let (command, sub) = matches.subcommand()?;
+
+    if MUTATING_COMMANDS.contains(&command) {
+        return Some(command.to_string());
+    }
+
+    if !TRACED_COMMANDS.contains(&command) {
+        return None;
+    }
+
+    let action = sub.subcommand_name()?;
+
+    (!is_read_only_action(command, action)).then(|| format!("{} {}", command, action))
+}
+
+fn is_read_only_action(command: &str, action: &str) -> bool {
+    READ_ONLY_ACTIONS
+        .iter()
+        .any(|(group, actions)| *group == command && actions.contains(&action))
+}
+
+/// Whether the selected action only reads (e.g. "workers list"), so a DB
+/// alias's read replica can serve it
+pub fn is_read_only(matches: &ArgMatches) -> bool {
+    let Some((command, sub)) = matches.subcommand() else {
+        return false;
+    };
+
+    READ_ONLY_COMMANDS.contains(&command)
+        || sub
+            .subcommand_name()
+            .is_some_and(|action| is_read_only_action(command, action))
+}
+
+#[derive(Args)]
+pub struct TraceArgs {
+    /// Request ID printed by a failed command (req_...)
+    pub id: String,
+}
+
+impl TraceArgs {
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        let record = backend.get_trace(&self.id).await?;
+
+        let status = match record.status.as_str() {
+            "ok" => record.status.green(),
+            _ => record.status.red(),
+        };
+
+        println!("{:12} {}", "Request:".dimmed(), record.request_id.bold());
+        println!("{:12} {}", "Operation:".dimmed(), record.operation);
+        println!("{:12} {}", "Status:".dimmed(), status);
+        println!(
+            "{:12} {}",
+            "Time:".dimmed(),
+            record.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+
+        if let Some(error) = &record.error {
+            println!("{:12} {}", "Error:".dimmed(), error);
+        }
+
+        if let Some(details) = &record.details {
+            println!();
+            println!("{}", "Details".bold());
+            println!(
+                "{}",
+                serde_json::to_string_pretty(details).unwrap_or_default()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use clap::{Command, Subcommand};
+
+    #[derive(clap::Parser)]
+    struct TestCli {
+        #[command(subcommand)]
+        command: TestCommands,
+    }
+
+    #[derive(Subcommand)]
+    enum TestCommands {
+        #[command(visible_alias = "w")]
+        Workers {
+            #[command(subcommand)]
+            command: crate::commands::workers::WorkersCommand,
+        },
+        Alias {
+            #[command(subcommand)]
+            command: crate::commands::alias::AliasCommand,
+        },
+        Storage {
+            #[command(subcommand)]
+            command: crate::commands::storage::StorageCommand,
+        },
+        Search(crate::commands::search::SearchArgs),
+        Deploy(crate::commands::deploy::DeployArgs),
+    }
+
+    fn matches(args: &[&str]) -> ArgMatches {
+        use clap::CommandFactory;
+
+        let command: Command = TestCli::command();
+        command.try_get_matches_from(args).unwrap()
+    }
+
+    fn operation(args: &[&str]) -> Option<String> {
+        mutating_operation(&matches(args))
+    }
+
+    #[test]
+    fn test_mutating_operation() {
+        assert_eq!(
+            operation(&["ow", "workers", "delete", "api"]),
+            Some("workers delete".to_string())
+        );
+        assert_eq!(
+            operation(&["ow", "w", "rm", "api", "-y"]),
+            Some("workers delete".to_string())
+        );
+        assert_eq!(operation(&["ow", "deploy"]), Some("deploy".to_string()));
+    }
+
+    #[test]
+    fn test_read_only_and_local_commands_are_not_traced() {
+        assert_eq!(operation(&["ow", "workers", "ls"]), None);
+        assert_eq!(operation(&["ow", "workers", "get", "api"]), None);
+        assert_eq!(operation(&["ow", "alias", "remove", "old"]), None);
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only(&matches(&["ow", "w", "ls"])));
+        assert!(is_read_only(&matches(&["ow", "workers", "get", "api"])));
+        assert!(!is_read_only(&matches(&["ow", "workers", "delete", "api"])));
+        assert!(!is_read_only(&matches(&["ow", "alias", "remove", "old"])));
+        assert!(is_read_only(&matches(&["ow", "search", "payments"])));
+    }
+
+    #[test]
+    fn test_read_only_is_scoped_to_the_command() {
+        assert!(is_read_only(&matches(&["ow", "workers", "invoke", "api"])));
+        assert!(!is_read_only(&matches(&[
+            "ow", "storage", "test", "assets"
+        ])));
+        assert_eq!(
+            operation(&["ow", "storage", "test", "assets"]),
+            Some("storage test".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trace_not_found() {
+        let backend = MockBackend::new();
+
+        let result = TraceArgs {
+            id: "req_missing".to_string(),
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+}
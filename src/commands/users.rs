@@ -1,10 +1,12 @@
 use crate::config::{AliasConfig, Config, ConfigError};
+use crate::timings::{Phase, TimedExt};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64;
 use clap::Subcommand;
 use colored::Colorize;
 use pbkdf2::hmac::Hmac;
 use rand::RngCore;
+use serde::Serialize;
 use sha2::Sha256;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{PgPool, Row};
@@ -117,12 +119,20 @@ async fn connect(database_url: &str) -> Result<PgPool, UsersError> {
     let pool = PgPoolOptions::new()
         .max_connections(1)
         .connect(database_url)
+        .timed(Phase::Connect)
         .await?;
 
     Ok(pool)
 }
 
-async fn cmd_list(pool: &PgPool) -> Result<(), UsersError> {
+#[derive(Debug, Clone, Serialize)]
+pub struct UserInfo {
+    pub id: uuid::Uuid,
+    pub username: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+pub async fn list_users(pool: &PgPool) -> Result<Vec<UserInfo>, UsersError> {
     let rows = sqlx::query(
         r#"
         SELECT id, username, created_at
@@ -133,24 +143,35 @@ async fn cmd_list(pool: &PgPool) -> Result<(), UsersError> {
     .fetch_all(pool)
     .await?;
 
-    if rows.is_empty() {
-        println!("No users found.");
+    let users = rows
+        .iter()
+        .map(|row| UserInfo {
+            id: row.get("id"),
+            username: row.get("username"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+    Ok(users)
+}
+
+async fn cmd_list(pool: &PgPool) -> Result<(), UsersError> {
+    let users = list_users(pool).await?;
+
+    if users.is_empty() {
+        eprintln!("No users found.");
         return Ok(());
     }
 
     println!("{}", "Users".bold());
     println!("{}", "─".repeat(60));
 
-    for row in rows {
-        let username: String = row.get("username");
-        let id: uuid::Uuid = row.get("id");
-        let created_at: chrono::NaiveDateTime = row.get("created_at");
-
+    for user in users {
         println!(
             "  {} {} {}",
-            username.bold(),
-            format!("({})", id).dimmed(),
-            format!("created {}", created_at.format("%Y-%m-%d")).dimmed()
+            user.username.bold(),
+            format!("({})", user.id).dimmed(),
+            format!("created {}", user.created_at.format("%Y-%m-%d")).dimmed()
         );
     }
 
@@ -191,7 +212,7 @@ async fn cmd_get(pool: &PgPool, username: &str) -> Result<(), UsersError> {
     Ok(())
 }
 
-fn hash_password(password: &str) -> String {
+pub fn hash_password(password: &str) -> String {
     const ITERATIONS: u32 = 100_000;
     const SALT_LEN: usize = 16;
     const KEY_LEN: usize = 32;
@@ -211,7 +232,7 @@ fn hash_password(password: &str) -> String {
     )
 }
 
-fn prompt_password() -> Result<String, UsersError> {
+pub fn prompt_password() -> Result<String, UsersError> {
     let password = rpassword::prompt_password("Password: ")
         .map_err(|e| UsersError::Password(e.to_string()))?;
 
@@ -239,69 +260,32 @@ async fn cmd_create(
     };
 
     if system {
-        // Rename the system user to claim it
-        let result = sqlx::query(
-            "UPDATE users SET username = $1 WHERE id = '00000000-0000-0000-0000-000000000000'",
-        )
-        .bind(&username)
-        .execute(pool)
-        .await?;
+        claim_system_user(pool, &username).await?;
 
-        if result.rows_affected() == 0 {
-            return Err(UsersError::UserNotFound("__system__".to_string()));
-        }
-
-        println!(
+        eprintln!(
             "{} System user renamed to '{}'.",
             "Updated".green().bold(),
             username.bold(),
         );
     } else {
-        // Check if user already exists
-        let exists: bool =
-            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
-                .bind(&username)
-                .fetch_one(pool)
-                .await?;
-
-        if exists {
-            return Err(UsersError::UserExists(username));
-        }
+        let user = create_user(pool, &username).await?;
 
-        // Insert new user
-        let row = sqlx::query(
-            r#"
-            INSERT INTO users (username)
-            VALUES ($1)
-            RETURNING id, username, created_at
-            "#,
-        )
-        .bind(&username)
-        .fetch_one(pool)
-        .await?;
-
-        let id: uuid::Uuid = row.get("id");
-
-        println!(
+        eprintln!(
             "{} User '{}' created (ID: {}).",
             "Created".green().bold(),
             username.bold(),
-            id.to_string().dimmed()
+            user.id.to_string().dimmed()
         );
     }
 
     if let Some(hash) = password_hash {
-        sqlx::query("UPDATE users SET password_hash = $1 WHERE username = $2")
-            .bind(&hash)
-            .bind(&username)
-            .execute(pool)
-            .await?;
+        set_password_hash(pool, &username, &hash).await?;
 
-        println!("{} Password set.", "Password".green().bold());
+        eprintln!("{} Password set.", "Password".green().bold());
     }
 
-    println!("\n{} Set this user as default with:", "Next:".cyan().bold());
-    println!(
+    eprintln!("\n{} Set this user as default with:", "Next:".cyan().bold());
+    eprintln!(
         "  {}",
         format!("ow alias set <alias> --db <url> --user {}", username).cyan()
     );
@@ -309,7 +293,67 @@ async fn cmd_create(
     Ok(())
 }
 
-async fn cmd_delete(pool: &PgPool, username: &str) -> Result<(), UsersError> {
+/// Rename the system user created by the migrations to claim it
+pub async fn claim_system_user(pool: &PgPool, username: &str) -> Result<(), UsersError> {
+    let result = sqlx::query(
+        "UPDATE users SET username = $1 WHERE id = '00000000-0000-0000-0000-000000000000'",
+    )
+    .bind(username)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(UsersError::UserNotFound("__system__".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn create_user(pool: &PgPool, username: &str) -> Result<UserInfo, UsersError> {
+    // Check if user already exists
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
+        .bind(username)
+        .fetch_one(pool)
+        .await?;
+
+    if exists {
+        return Err(UsersError::UserExists(username.to_string()));
+    }
+
+    // Insert new user
+    let row = sqlx::query(
+        r#"
+        INSERT INTO users (username)
+        VALUES ($1)
+        RETURNING id, username, created_at
+        "#,
+    )
+    .bind(username)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(UserInfo {
+        id: row.get("id"),
+        username: row.get("username"),
+        created_at: row.get("created_at"),
+    })
+}
+
+pub async fn set_password_hash(
+    pool: &PgPool,
+    username: &str,
+    hash: &str,
+) -> Result<(), UsersError> {
+    sqlx::query("UPDATE users SET password_hash = $1 WHERE username = $2")
+        .bind(hash)
+        .bind(username)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_user(pool: &PgPool, username: &str) -> Result<(), UsersError> {
     let result = sqlx::query("DELETE FROM users WHERE username = $1")
         .bind(username)
         .execute(pool)
@@ -319,7 +363,13 @@ async fn cmd_delete(pool: &PgPool, username: &str) -> Result<(), UsersError> {
         return Err(UsersError::UserNotFound(username.to_string()));
     }
 
-    println!(
+    Ok(())
+}
+
+async fn cmd_delete(pool: &PgPool, username: &str) -> Result<(), UsersError> {
+    delete_user(pool, username).await?;
+
+    eprintln!(
         "{} User '{}' deleted.",
         "Deleted".red().bold(),
         username.bold()
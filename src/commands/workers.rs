@@ -1,29 +1,80 @@
+use super::session::{self, Exchange, Session};
 use crate::backend::{
-    AssetManifestEntry, Backend, BackendError, CreateWorkerInput, DeployInput, Worker,
+    ACCOUNT_SECRET_PREFIX, AssetManifestEntry, Backend, BackendError, CapturedRequest,
+    CreateEnvironmentInput, CreateWorkerInput, DeployAuthorization, DeployInput, Deployment,
+    EnvironmentValue, EnvironmentValueInput, ExecResult, NotificationEvent, UpdateEnvironmentInput,
+    UpdateWorkerInput, Worker, WorkerEvent, WorkerRuntime, WorkerUrl,
 };
-use crate::s3::{self, PresignedClient, S3Client, S3Config, get_mime_type};
+use crate::bundle::{BUNDLE_FORMAT, Bundle, BundleEnvironment, BundleValue, BundleWorker};
+use crate::config::project::{AssetsSection, MANIFEST_FILE, Manifest};
+use crate::cron;
+use crate::github;
+use crate::logfmt::{self, LogFormat};
+use crate::porcelain;
+use crate::quiet;
+use crate::routes::{ROUTES_FILE, RoutesConfig};
+use crate::s3::{self, Asset, FailureKind, PresignedClient, S3Client, S3Config};
+use crate::steps::Steps;
+use crate::timings::{Phase, TimedExt};
+use chrono::Utc;
 use clap::Subcommand;
 use colored::Colorize;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum WorkersCommand {
     /// List all workers with their version and description
-    #[command(alias = "ls")]
-    List,
+    #[command(
+        alias = "ls",
+        after_help = "Examples:\n  \
+        ow workers list\n  \
+        ow workers list -l\n  \
+        ow workers list --tag team=payments --tag env"
+    )]
+    List {
+        /// Long format: URL, environment, last deploy, code type and message
+        #[arg(short, long)]
+        long: bool,
+
+        /// Only print names, one per line (for scripting)
+        #[arg(short = 'q', long = "ids", conflicts_with = "long")]
+        ids: bool,
+
+        /// Stable tab-separated output: name, id, version, environment, url
+        #[arg(long, conflicts_with_all = ["long", "ids"])]
+        porcelain: bool,
+
+        /// Only workers with this tag (KEY=VALUE, or KEY for any value); repeat to require several
+        #[arg(long = "tag", value_name = "KEY[=VALUE]")]
+        tags: Vec<String>,
+    },
 
     /// Show detailed information about a worker
     #[command(after_help = "Example:\n  ow workers get my-api")]
     Get {
         /// Worker name
         name: String,
+
+        /// Only print the name (for scripting)
+        #[arg(short = 'q', long = "ids")]
+        ids: bool,
+
+        /// Stable tab-separated output: name, id, version, environment, url,
+        /// deployed at, hash
+        #[arg(long, conflicts_with = "ids")]
+        porcelain: bool,
     },
 
     /// Create a new worker (available at https://<name>.workers.rocks)
     #[command(after_help = "Examples:\n  \
         ow workers create my-api\n  \
         ow workers create my-api -d \"REST API for users\"\n  \
-        ow workers create my-api --language javascript")]
+        ow workers create my-api --language javascript\n  \
+        ow workers create my-api --tag team=payments --tag tier=1\n  \
+        ow workers create my-api --file worker.ts   (create and deploy)")]
     Create {
         /// Worker name (becomes part of the URL)
         name: String,
@@ -35,6 +86,36 @@ pub enum WorkersCommand {
         /// Source language: javascript or typescript
         #[arg(short, long, default_value = "typescript")]
         language: String,
+
+        /// Deploy this source file (.js, .ts, or .wasm) right after creating
+        #[arg(short, long, visible_alias = "from-file")]
+        file: Option<PathBuf>,
+
+        /// Tag the worker (repeatable)
+        #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+    },
+
+    /// Update a worker's details
+    #[command(after_help = "Examples:\n  \
+        ow workers update my-api -d \"REST API for users\"\n  \
+        ow workers update my-api -d \"\"   (clear)\n  \
+        ow workers update my-api --tag team=payments --untag tier")]
+    Update {
+        /// Worker name
+        name: String,
+
+        /// New description (empty to clear)
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Add or change a tag (repeatable)
+        #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+
+        /// Remove a tag by key (repeatable)
+        #[arg(long, value_name = "KEY")]
+        untag: Vec<String>,
     },
 
     /// Delete a worker permanently
@@ -42,15 +123,24 @@ pub enum WorkersCommand {
     Delete {
         /// Worker name to delete
         name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Deploy a single source file to a worker
-    #[command(after_help = "Examples:\n  \
+    #[command(
+        allow_missing_positional = true,
+        after_help = "Examples:\n  \
         ow workers deploy my-api worker.ts\n  \
-        ow workers deploy my-api dist/worker.js -m \"Fix auth bug\"")]
+        ow workers deploy my-api dist/worker.js -m \"Fix auth bug\"\n  \
+        ow workers deploy worker.ts                  (last worker used in this directory)\n  \
+        ow workers deploy worker.ts --worker my-web"
+    )]
     Deploy {
-        /// Worker name to deploy to
-        name: String,
+        /// Worker name to deploy to (defaults to the last one used in this directory)
+        name: Option<String>,
 
         /// Source file (.js, .ts, or .wasm)
         file: PathBuf,
@@ -58,23 +148,106 @@ pub enum WorkersCommand {
         /// Deployment message (shown in version history)
         #[arg(short, long)]
         message: Option<String>,
+
+        /// Approval token for protected workers (see 'ow workers approve')
+        #[arg(long)]
+        approve_token: Option<String>,
+
+        /// Worker to deploy to (overrides the name and the remembered worker)
+        #[arg(long)]
+        worker: Option<String>,
+
+        /// Environment this deploy targets, checked against ow.toml's `environment`
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Stable tab-separated output: name, version, hash, type, deployed at
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Compare a local artifact's SHA-256 against the live deployment
+    #[command(after_help = "Example:\n  ow workers verify-hash my-api dist/worker.js")]
+    VerifyHash {
+        /// Worker name
+        name: String,
+
+        /// Local artifact to check
+        file: PathBuf,
     },
 
     /// Link an environment to a worker (for bindings and secrets)
-    #[command(after_help = "Example:\n  ow workers link my-api my-env")]
+    #[command(after_help = "Examples:\n  \
+        ow workers link my-api my-env\n  \
+        ow workers link my-api new-env --diff     Compare keys with the current environment first")]
     Link {
         /// Worker name
         name: String,
 
         /// Environment name to link
         env: String,
+
+        /// Show keys added, removed or retyped compared to the linked environment,
+        /// and confirm before switching if any are removed or retyped
+        #[arg(long)]
+        diff: bool,
+
+        /// Switch without confirmation even if keys are removed or retyped
+        #[arg(short, long, requires = "diff")]
+        yes: bool,
+    },
+
+    /// Remove the environment link from a worker
+    #[command(after_help = "Example:\n  ow workers unlink my-api")]
+    Unlink {
+        /// Worker name
+        name: String,
+    },
+
+    /// Show the effective runtime configuration (vars, secrets, bindings)
+    #[command(after_help = "Example:\n  ow workers env my-api")]
+    Env {
+        /// Worker name
+        name: String,
+    },
+
+    /// Set a variable or secret on one worker, overriding its environment
+    #[command(after_help = "Examples:\n  \
+        ow workers set-var my-api DEBUG true\n  \
+        ow workers set-var my-api API_KEY --secret    Prompt for the value (masked)")]
+    SetVar {
+        /// Worker name
+        name: String,
+
+        /// Variable name
+        key: String,
+
+        /// Value (prompted interactively for secrets if omitted)
+        value: Option<String>,
+
+        /// Store as a secret (masked in output)
+        #[arg(long)]
+        secret: bool,
+    },
+
+    /// Remove a worker-level variable, falling back to the environment value
+    #[command(after_help = "Example:\n  ow workers unset-var my-api DEBUG")]
+    UnsetVar {
+        /// Worker name
+        name: String,
+
+        /// Variable name
+        key: String,
     },
 
     /// Upload a folder with worker.js and static assets
     #[command(after_help = "Examples:\n  \
         ow workers upload my-app ./dist\n  \
-        ow workers upload my-app ./build.zip\n\n\
-        Note: Worker must have an ASSETS binding configured.\n\
+        ow workers upload my-app ./build.zip\n  \
+        ow workers upload my-app ./dist --assets ./public --assets ./static:/static\n  \
+        ow workers upload my-app ./dist --auto-bind my-storage\n\n\
+        Note: Assets need an ASSETS binding in the worker's environment. When it is\n\
+        missing, you are asked for a storage to bind (or pass --auto-bind).\n\
         The folder should contain worker.js at the root.")]
     Upload {
         /// Worker name to upload to
@@ -82,497 +255,5515 @@ pub enum WorkersCommand {
 
         /// Path to folder or .zip archive containing worker.js and assets
         path: PathBuf,
-    },
-}
-
-impl WorkersCommand {
-    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
-        match self {
-            Self::List => cmd_list(backend).await,
-            Self::Get { name } => cmd_get(backend, &name).await,
-            Self::Create {
-                name,
-                description,
-                language,
-            } => cmd_create(backend, name, description, language).await,
-            Self::Delete { name } => cmd_delete(backend, &name).await,
-            Self::Deploy {
-                name,
-                file,
-                message,
-            } => cmd_deploy(backend, &name, file, message).await,
-            Self::Link { name, env } => cmd_link(backend, &name, &env).await,
-            Self::Upload { name, path } => cmd_upload(backend, &name, path).await,
-        }
-    }
-}
 
-async fn cmd_list<B: Backend>(backend: &B) -> Result<(), BackendError> {
-    let workers = backend.list_workers().await?;
+        /// Approval token for protected workers (see 'ow workers approve')
+        #[arg(long)]
+        approve_token: Option<String>,
 
-    if workers.is_empty() {
-        println!("No workers found.");
-        return Ok(());
-    }
+        /// Extra asset directory, optionally mounted at a path (DIR or DIR:/MOUNT).
+        /// Replaces the default assets/ subfolder (or ow.toml's `assets.dir`); repeatable.
+        #[arg(long = "assets", value_name = "DIR[:/MOUNT]", value_parser = parse_asset_source)]
+        assets: Vec<AssetSource>,
 
-    println!("{}", "Workers".bold());
-    println!("{}", "─".repeat(60));
+        /// Add a content hash to asset filenames (app.css -> app.1a2b3c4d.css)
+        /// and upload /asset-manifest.json mapping original to hashed names
+        #[arg(long)]
+        fingerprint: bool,
 
-    for worker in workers {
-        let version = worker
-            .current_version
-            .map(|v| format!("v{}", v))
-            .unwrap_or_else(|| "no deploy".dimmed().to_string());
+        /// Follow symbolic links (skipped by default)
+        #[arg(long)]
+        follow_symlinks: bool,
 
-        println!(
-            "  {:30} {:10} {}",
-            worker.name.bold(),
-            version,
-            worker.description.as_deref().unwrap_or("").dimmed()
-        );
-    }
+        /// Include hidden files and directories (skipped by default, except .well-known)
+        #[arg(long)]
+        include_hidden: bool,
 
-    Ok(())
-}
+        /// Environment this deploy targets, checked against ow.toml's `environment`
+        #[arg(long)]
+        env: Option<String>,
 
-async fn cmd_get<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
-    let worker = backend.get_worker(name).await?;
+        /// Storage to bind as ASSETS in the linked environment if it has no assets binding
+        #[arg(long, value_name = "STORAGE")]
+        auto_bind: Option<String>,
 
-    print_worker(&worker);
+        /// After uploading, HEAD a random sample of assets (default 20) and fail if
+        /// their checksum, size, content type or encoding differ from the local files
+        #[arg(long, value_name = "SAMPLE", num_args = 0..=1, default_missing_value = "20")]
+        verify: Option<usize>,
+    },
 
-    Ok(())
-}
+    /// Mark a worker as protected (deploys require approval or confirmation)
+    #[command(after_help = "Examples:\n  \
+        ow workers protect my-api\n  \
+        ow workers protect my-api --off")]
+    Protect {
+        /// Worker name
+        name: String,
 
-async fn cmd_create<B: Backend>(
-    backend: &B,
-    name: String,
-    description: Option<String>,
-    language: String,
-) -> Result<(), BackendError> {
-    let input = CreateWorkerInput {
-        name,
-        description,
-        language,
-    };
-    let worker = backend.create_worker(input).await?;
+        /// Remove protection instead
+        #[arg(long)]
+        off: bool,
+    },
 
-    println!(
-        "{} Worker '{}' created.",
-        "Created".green(),
-        worker.name.bold()
-    );
-    println!();
+    /// Mint a single-use approval token for deploying to a protected worker
+    #[command(after_help = "Examples:\n  \
+        ow workers approve my-api\n  \
+        ow workers approve my-api --expires 30m\n  \
+        ow workers deploy my-api worker.ts --approve-token <token>")]
+    Approve {
+        /// Worker name
+        name: String,
 
-    print_worker(&worker);
+        /// Token lifetime (e.g. 30m, 1h, 7d)
+        #[arg(long, default_value = "1h")]
+        expires: String,
+    },
 
-    Ok(())
-}
+    /// Delete old deployments, keeping the newest ones plus the active and tagged versions
+    #[command(after_help = "Examples:\n  \
+        ow workers prune my-api --keep 20\n  \
+        ow workers prune my-api              Use the account retention policy")]
+    Prune {
+        /// Worker name
+        name: String,
 
-async fn cmd_delete<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
-    backend.delete_worker(name).await?;
+        /// Number of recent deployments to keep (default: account retention policy)
+        #[arg(long)]
+        keep: Option<u32>,
+    },
 
-    println!("{} Worker '{}' deleted.", "Deleted".red(), name.bold());
+    /// Tag a deployment so it is never pruned
+    #[command(after_help = "Examples:\n  \
+        ow workers tag my-api 12 stable\n  \
+        ow workers tag my-api 12 --remove")]
+    Tag {
+        /// Worker name
+        name: String,
 
-    Ok(())
-}
+        /// Deployment version
+        version: i32,
 
-fn print_worker(worker: &Worker) {
-    println!("{:12} {}", "Name:".dimmed(), worker.name.bold());
-    println!("{:12} {}", "ID:".dimmed(), worker.id);
+        /// Tag to set (e.g. stable, v1.4.0)
+        #[arg(required_unless_present = "remove")]
+        tag: Option<String>,
 
-    if let Some(desc) = &worker.description {
-        println!("{:12} {}", "Description:".dimmed(), desc);
-    }
+        /// Remove the tag instead
+        #[arg(long, conflicts_with = "tag")]
+        remove: bool,
+    },
 
-    if let Some(env) = &worker.environment {
-        println!("{:12} {}", "Environment:".dimmed(), env.name.cyan());
-    }
+    /// List past deployments of a worker, or export them with their code
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
 
-    println!(
-        "{:12} {}",
-        "Version:".dimmed(),
-        worker
-            .current_version
-            .map(|v| v.to_string())
-            .unwrap_or_else(|| "none".to_string())
-    );
+    /// List every deployment of a worker (same as `history list`)
+    #[command(after_help = "Examples:\n  \
+        ow workers versions my-api\n  \
+        ow workers versions my-api --porcelain")]
+    Versions {
+        /// Worker name
+        name: String,
 
-    println!(
-        "{:12} {}",
-        "Created:".dimmed(),
-        worker.created_at.format("%Y-%m-%d %H:%M:%S")
-    );
+        /// Stable tab-separated output: version, live (true/false), hash,
+        /// code type, deployed at, deployed by, tag, message
+        #[arg(long)]
+        porcelain: bool,
+    },
 
-    println!(
-        "{:12} {}",
-        "Updated:".dimmed(),
-        worker.updated_at.format("%Y-%m-%d %H:%M:%S")
-    );
-}
+    /// Freeform metadata (owner, repo, runbook, tags) shown in `get` and `list --long`
+    Meta {
+        #[command(subcommand)]
+        command: MetaCommand,
+    },
 
-async fn cmd_deploy<B: Backend>(
-    backend: &B,
-    name: &str,
-    file: PathBuf,
-    message: Option<String>,
-) -> Result<(), BackendError> {
-    // Read file
-    let code = std::fs::read(&file).map_err(|e| {
-        BackendError::Api(format!("Failed to read file '{}': {}", file.display(), e))
-    })?;
+    /// Show or set how many deployments are kept per worker, account-wide
+    #[command(after_help = "Examples:\n  \
+        ow workers retention          Show the current policy\n  \
+        ow workers retention 20       Keep 20 deployments per worker\n  \
+        ow workers retention --off    Keep all deployments\n\n\
+        The policy is applied after every deploy.")]
+    Retention {
+        /// Deployments to keep per worker
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        keep: Option<u32>,
+
+        /// Disable the policy and keep every deployment
+        #[arg(long, conflicts_with = "keep")]
+        off: bool,
+    },
 
-    // Determine code type from extension
-    let code_type = match file.extension().and_then(|e| e.to_str()) {
-        Some("js") => "javascript",
-        Some("ts") => "typescript",
-        Some("wasm") => "wasm",
-        _ => {
-            return Err(BackendError::Api(
-                "Unknown file type. Use .js, .ts, or .wasm".to_string(),
-            ));
-        }
-    };
+    /// List, add or remove extra domains and project routes pointing at a worker
+    #[command(after_help = "Examples:\n  \
+        ow workers urls my-api\n  \
+        ow workers urls my-api --add api.example.com\n  \
+        ow workers urls my-api --add '/api/*'          Route in the worker's project\n  \
+        ow workers urls my-api --remove api.example.com")]
+    Urls {
+        /// Worker name
+        name: String,
 
-    let input = DeployInput {
-        code,
-        code_type: code_type.to_string(),
-        message,
-    };
+        /// Domain (e.g. api.example.com) or route pattern (e.g. /api/*) to add
+        #[arg(long, value_name = "URL", value_parser = parse_worker_url)]
+        add: Vec<WorkerUrl>,
 
-    let deployment = backend.deploy_worker(name, input).await?;
+        /// Domain or route pattern to remove
+        #[arg(long, value_name = "URL", value_parser = parse_worker_url)]
+        remove: Vec<WorkerUrl>,
+    },
 
-    println!(
-        "{} Deployed '{}' v{}",
-        "Deployed".green(),
-        name.bold(),
-        deployment.version
-    );
+    /// Share a worker with another user, or list who it is shared with (DB aliases)
+    #[command(after_help = "Examples:\n  \
+        ow workers share my-api\n  \
+        ow workers share my-api --with alice\n  \
+        ow workers share my-api --with bob --role editor\n  \
+        ow workers share my-api --revoke alice\n\n\
+        The worker then shows up for them as <owner>/<name>, e.g. 'ow workers get you/my-api'.\n\
+        Viewers can read it, editors can also deploy and change its values and URLs.")]
+    Share {
+        /// Worker name
+        name: String,
 
-    println!();
-    println!("{:12} {}", "Version:".dimmed(), deployment.version);
-    println!("{:12} {}", "Hash:".dimmed(), &deployment.hash[..16]);
-    println!("{:12} {}", "Type:".dimmed(), deployment.code_type);
-    println!(
-        "{:12} {}",
-        "Deployed:".dimmed(),
-        deployment.deployed_at.format("%Y-%m-%d %H:%M:%S")
-    );
+        /// Username to share with (or whose role to change)
+        #[arg(long = "with", value_name = "USERNAME", conflicts_with = "revoke")]
+        with: Option<String>,
 
-    if let Some(msg) = &deployment.message {
-        println!("{:12} {}", "Message:".dimmed(), msg);
-    }
+        #[arg(long, value_parser = ["viewer", "editor"], default_value = "viewer")]
+        role: String,
 
-    Ok(())
-}
+        /// Username to stop sharing with
+        #[arg(long, value_name = "USERNAME")]
+        revoke: Option<String>,
+    },
 
-async fn cmd_link<B: Backend>(backend: &B, name: &str, env: &str) -> Result<(), BackendError> {
-    let worker = backend.get_worker(name).await?;
-    let environment = backend.get_environment(env).await?;
+    /// Generate load against a worker and report throughput, latency and errors
+    #[command(after_help = "Examples:\n  \
+        ow workers bench my-api\n  \
+        ow workers bench my-api --rps 200 --duration 1m --concurrency 50\n  \
+        ow workers bench my-api --path /api/health\n  \
+        ow workers bench my-api --duration 5s --record before.json\n  \
+        ow workers bench my-api-v2 --replay before.json   Same requests, responses compared\n\n\
+        A replay exits with an error when a status or body differs from the recording.")]
+    Bench {
+        /// Worker name
+        name: String,
 
-    backend
-        .link_worker_environment(&worker.id, &environment.id)
-        .await?;
+        /// Target requests per second (default: as fast as concurrency allows)
+        #[arg(long)]
+        rps: Option<u32>,
 
-    println!(
-        "{} Worker '{}' linked to environment '{}'.",
-        "Linked".green(),
-        name.bold(),
-        env.bold()
-    );
+        /// How long to run (e.g. 30s, 2m)
+        #[arg(short, long, default_value = "10s")]
+        duration: String,
 
-    Ok(())
-}
+        /// Maximum requests in flight
+        #[arg(short, long, default_value = "10")]
+        concurrency: usize,
 
-async fn cmd_upload<B: Backend>(
-    backend: &B,
-    name: &str,
-    path: PathBuf,
-) -> Result<(), BackendError> {
-    // Collect assets from folder (separate from zip)
-    let assets = if path.is_dir() {
-        collect_assets(&path)?
-    } else {
-        vec![]
-    };
+        /// Request path
+        #[arg(long, default_value = "/")]
+        path: String,
 
-    // Build asset manifest with SHA-256 hashes
-    let manifest: Vec<AssetManifestEntry> = assets
-        .iter()
-        .map(|(p, content, ct, hash)| AssetManifestEntry {
-            path: p.clone(),
-            size: content.len(),
-            content_type: ct.clone(),
-            hash: hash.clone(),
-        })
-        .collect();
+        /// Save requests and responses to a session file (the first 1000)
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<PathBuf>,
 
-    let zip_data = if path.is_dir() {
-        // Create zip from folder (code only, no assets)
-        println!("{} Creating archive from {}...", "→".blue(), path.display());
-        create_zip_from_folder(&path)?
-    } else if path.extension().and_then(|e| e.to_str()) == Some("zip") {
-        // Read existing zip file
-        std::fs::read(&path).map_err(|e| {
-            BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
-        })?
-    } else {
-        return Err(BackendError::Api(
-            "Path must be a .zip archive or a folder".to_string(),
-        ));
-    };
+        /// Re-send the requests of a recorded session, one at a time, and
+        /// compare the responses
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["rps", "path"])]
+        replay: Option<PathBuf>,
+    },
 
-    let size_kb = zip_data.len() / 1024;
+    /// Run a worker's scheduled or queue handler once, for testing cron and queue code
+    #[command(after_help = "Examples:\n  \
+        ow workers exec my-api --event scheduled\n  \
+        ow workers exec my-api --event scheduled --cron \"0 * * * *\"\n  \
+        ow workers exec my-api --event scheduled --cron \"30 9 * * MON-FRI\" --tz +02:00\n  \
+        ow workers exec my-api --event queue --message '{\"id\": 1}' --message '{\"id\": 2}'\n  \
+        ow workers exec my-api --event scheduled --format json | jq 'select(.level == \"error\")'\n\n\
+        --cron is checked before running and its next five execution times are printed.\n\
+        JSON log lines ({\"level\": \"info\", \"msg\": ...}) are shown as level, message and \
+        fields; --raw prints them untouched.")]
+    Exec {
+        /// Worker name
+        name: String,
+
+        /// Handler to run
+        #[arg(long, value_enum)]
+        event: EventKind,
+
+        /// Cron expression reported to the scheduled handler
+        #[arg(long, conflicts_with = "messages", value_parser = cron::validate)]
+        cron: Option<String>,
+
+        /// Time zone of the --cron preview: local, UTC or an offset like +02:00
+        #[arg(long, default_value = "local", requires = "cron")]
+        tz: cron::Tz,
+
+        /// Queue message body as JSON (repeatable, default: one empty object)
+        #[arg(long = "message", value_name = "JSON", value_parser = parse_json_message)]
+        messages: Vec<serde_json::Value>,
+
+        /// How console output is shown: JSON log lines are parsed and colorized
+        /// by level (pretty), or printed one object per line (json)
+        #[arg(long, value_enum, default_value = "pretty")]
+        format: LogFormat,
+
+        /// Print console output exactly as logged
+        #[arg(long, conflicts_with = "format")]
+        raw: bool,
+    },
+
+    /// Show live runtime state: isolates, in-flight requests, memory and cold starts
+    #[command(after_help = "Example:\n  ow workers runtime my-api\n\n\
+        Reported by the runners, so only available through API aliases.")]
+    Runtime {
+        /// Worker name
+        name: String,
+    },
+
+    /// Re-send a captured request to the current deployment, e.g. after a fix
+    #[command(after_help = "Examples:\n  \
+        ow workers replay my-api --log-id 3f2a9c1e-7d4b-4e8a-9f61-2b5c8d0e4a17\n  \
+        ow workers replay my-api --from-file request.json\n\n\
+        request.json: {\"method\": \"POST\", \"path\": \"/api/orders\", \
+        \"headers\": {\"content-type\": \"application/json\"}, \"body\": \"{}\"}\n\
+        Exits with an error when the worker answers with a 5xx.")]
+    Replay {
+        /// Worker name
+        name: String,
+
+        /// ID of a request captured by the runtime
+        #[arg(
+            long,
+            required_unless_present = "from_file",
+            conflicts_with = "from_file"
+        )]
+        log_id: Option<String>,
+
+        /// Request saved as JSON (method, path, headers, body)
+        #[arg(long, value_name = "FILE")]
+        from_file: Option<PathBuf>,
+    },
+
+    /// Send an HTTP request to a worker and print the status, headers, timing and body
+    #[command(
+        visible_alias = "curl",
+        after_help = "Examples:\n  \
+        ow workers invoke my-api\n  \
+        ow workers invoke my-api /api/orders?page=2\n  \
+        ow workers invoke my-api /api/orders --data @order.json --header content-type:application/json\n  \
+        ow workers invoke my-api /api/orders -X DELETE -H 'authorization: Bearer ...'\n  \
+        ow workers invoke my-api /api/orders --record orders.json\n  \
+        ow workers invoke my-api-v2 --replay orders.json   Same request, response compared\n\n\
+        The worker URL is its first custom domain, else <name>.workers.rocks.\n\
+        Status, headers and timing go to stderr, the body to stdout. \
+        Exits with an error when the worker answers with a 5xx."
+    )]
+    Invoke {
+        /// Worker name
+        name: String,
+
+        /// Path and query string
+        #[arg(default_value = "/")]
+        path: String,
+
+        /// HTTP method (default: GET, or POST with --data)
+        #[arg(short = 'X', long)]
+        method: Option<String>,
+
+        /// Request body: text, @FILE, or @- for stdin
+        #[arg(short, long)]
+        data: Option<String>,
+
+        /// Request header (repeatable)
+        #[arg(short = 'H', long = "header", value_name = "NAME:VALUE", value_parser = parse_header)]
+        headers: Vec<(String, String)>,
+
+        /// Save the request and response to a session file
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<PathBuf>,
+
+        /// Re-send the requests of a recorded session and compare the responses
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["method", "data", "headers"]
+        )]
+        replay: Option<PathBuf>,
+    },
+
+    /// Save a worker as a shareable bundle: live code, environment, bindings and routes
+    #[command(after_help = "Examples:\n  \
+        ow workers export my-api\n  \
+        ow workers export my-api --out my-api.tar.gz\n\n\
+        Secrets are redacted. Bindings are saved by resource name.")]
+    Export {
+        /// Worker name
+        name: String,
+
+        /// Output file (default: <name>.tar.gz)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Recreate a worker from a bundle made by 'ow workers export'
+    #[command(after_help = "Examples:\n  \
+        ow workers import my-api.tar.gz\n  \
+        ow workers import my-api.tar.gz --name my-api-copy --env my-api-copy-env\n\n\
+        Redacted secrets and bindings to missing resources are listed afterwards \
+        with the commands to set them.")]
+    Import {
+        /// Bundle file (.tar.gz)
+        file: PathBuf,
+
+        /// Worker name (default: the exported worker's name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Environment name (default: the exported environment's name)
+        #[arg(long)]
+        env: Option<String>,
+    },
+}
+
+/// Handler targeted by `workers exec`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventKind {
+    Scheduled,
+    Queue,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    /// Audit every deployment: version, hash, code type, tag, message, author and date
+    #[command(
+        alias = "ls",
+        after_help = "Examples:\n  \
+        ow workers history list my-api\n  \
+        ow workers history list my-api --porcelain\n\n\
+        Oldest first. The live version is marked with *. `ow workers versions` is the same."
+    )]
+    List {
+        /// Worker name
+        name: String,
+
+        /// Stable tab-separated output: version, live (true/false), hash,
+        /// code type, deployed at, deployed by, tag, message
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Write every deployment to a directory (v1/worker.js, v2/worker.js, metadata.json)
+    #[command(after_help = "Examples:\n  \
+        ow workers history export my-api\n  \
+        ow workers history export my-api --out archive/my-api")]
+    Export {
+        /// Worker name
+        name: String,
+
+        /// Output directory (default: <name>-history)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MetaCommand {
+    /// Set metadata fields; an empty value removes the field
+    #[command(after_help = "Examples:\n  \
+        ow workers meta set my-api owner=team-payments repo=https://github.com/acme/api\n  \
+        ow workers meta set my-api runbook=https://wiki.example.com/my-api tags=billing,public\n  \
+        ow workers meta set my-api runbook=          Remove the runbook")]
+    Set {
+        /// Worker name
+        name: String,
+
+        /// Fields to set
+        #[arg(required = true, value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+    },
+
+    /// Show the metadata of a worker, or the value of one field
+    #[command(after_help = "Examples:\n  \
+        ow workers meta get my-api\n  \
+        ow workers meta get my-api owner")]
+    Get {
+        /// Worker name
+        name: String,
+
+        /// Only print this field's value
+        key: Option<String>,
+    },
+}
+
+impl WorkersCommand {
+    /// Fill in the worker from ow.toml's `name`, or else the directory
+    /// context, when none was given
+    pub fn with_remembered_worker(self, remembered: impl FnOnce() -> Option<String>) -> Self {
+        match self {
+            Self::Deploy {
+                name: None,
+                file,
+                message,
+                approve_token,
+                worker: None,
+                env,
+                porcelain,
+            } => {
+                let project = Manifest::find().ok().flatten().and_then(|m| m.name);
+
+                let (worker, source) = match project {
+                    Some(worker) => (Some(worker), format!("from {}", MANIFEST_FILE)),
+                    None => (remembered(), "last used in this directory".to_string()),
+                };
+
+                if let Some(worker) = &worker {
+                    eprintln!(
+                        "{} Using worker '{}' ({})",
+                        "→".blue(),
+                        worker.bold(),
+                        source
+                    );
+                }
+
+                Self::Deploy {
+                    name: None,
+                    file,
+                    message,
+                    approve_token,
+                    worker,
+                    env,
+                    porcelain,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Worker targeted by this command, to remember for the directory
+    pub fn target_worker(&self) -> Option<&str> {
+        match self {
+            Self::Deploy { name, worker, .. } => worker.as_deref().or(name.as_deref()),
+            _ => None,
+        }
+    }
+
+    pub async fn run<B: Backend>(self, backend: &B) -> Result<(), BackendError> {
+        match self {
+            Self::List {
+                long,
+                ids,
+                porcelain,
+                tags,
+            } => cmd_list(backend, long, ids, porcelain, &tags).await,
+            Self::Get {
+                name,
+                ids,
+                porcelain,
+            } => cmd_get(backend, &name, ids, porcelain).await,
+            Self::Create {
+                name,
+                description,
+                language,
+                file,
+                tags,
+            } => cmd_create(backend, name, description, language, file, tags).await,
+            Self::Update {
+                name,
+                description,
+                tags,
+                untag,
+            } => cmd_update(backend, &name, description, tags, untag).await,
+            Self::Delete { name, yes } => cmd_delete(backend, &name, yes).await,
+            Self::Deploy {
+                name,
+                file,
+                message,
+                approve_token,
+                worker,
+                env,
+                porcelain,
+            } => {
+                let name = worker.or(name).ok_or_else(|| {
+                    BackendError::Usage(
+                        "No worker specified and none remembered for this directory. Pass a worker name or --worker.".to_string(),
+                    )
+                })?;
+
+                environment_gate(backend, &name, env.as_deref(), false).await?;
+                schema_gate(backend, &name).await?;
+
+                let authorization = authorize_deploy(
+                    backend,
+                    &name,
+                    approve_token.as_deref(),
+                    std::io::stdin().is_terminal(),
+                )
+                .await?;
+
+                cmd_deploy(backend, &name, file, message, authorization, porcelain).await
+            }
+            Self::VerifyHash { name, file } => cmd_verify_hash(backend, &name, file).await,
+            Self::Link {
+                name,
+                env,
+                diff,
+                yes,
+            } => cmd_link(backend, &name, &env, diff, yes).await,
+            Self::Unlink { name } => cmd_unlink(backend, &name).await,
+            Self::Env { name } => cmd_env(backend, &name).await,
+            Self::SetVar {
+                name,
+                key,
+                value,
+                secret,
+            } => {
+                let value = match value {
+                    Some(v) => v,
+                    None if secret => {
+                        eprint!("{}: ", "Enter secret value".dimmed());
+                        std::io::stderr().flush().ok();
+                        rpassword::read_password().map_err(|e| {
+                            BackendError::Api(format!("Failed to read input: {}", e))
+                        })?
+                    }
+                    None => {
+                        return Err(BackendError::Usage(format!(
+                            "Missing value for '{}'. Pass it after the key.",
+                            key
+                        )));
+                    }
+                };
+
+                cmd_set_var(backend, &name, &key, &value, secret).await
+            }
+            Self::UnsetVar { name, key } => cmd_unset_var(backend, &name, &key).await,
+            Self::Upload {
+                name,
+                path,
+                approve_token,
+                assets,
+                fingerprint,
+                follow_symlinks,
+                include_hidden,
+                env,
+                auto_bind,
+                verify,
+            } => {
+                environment_gate(backend, &name, env.as_deref(), false).await?;
+                schema_gate(backend, &name).await?;
+
+                let authorization = authorize_deploy(
+                    backend,
+                    &name,
+                    approve_token.as_deref(),
+                    std::io::stdin().is_terminal(),
+                )
+                .await?;
+
+                let assets = if assets.is_empty() {
+                    project_assets()?
+                } else {
+                    assets
+                };
+
+                let options = UploadOptions {
+                    assets,
+                    policy: UploadPolicy {
+                        follow_symlinks,
+                        include_hidden,
+                    },
+                    fingerprint,
+                    auto_bind,
+                    verify,
+                };
+
+                cmd_upload(backend, &name, path, authorization, options).await
+            }
+            Self::Protect { name, off } => cmd_protect(backend, &name, !off).await,
+            Self::Approve { name, expires } => cmd_approve(backend, &name, &expires).await,
+            Self::Prune { name, keep } => cmd_prune(backend, &name, keep).await,
+            Self::Tag {
+                name,
+                version,
+                tag,
+                remove: _,
+            } => cmd_tag(backend, &name, version, tag).await,
+            Self::History {
+                command: HistoryCommand::List { name, porcelain },
+            } => cmd_history(backend, &name, porcelain).await,
+            Self::History {
+                command: HistoryCommand::Export { name, out },
+            } => {
+                let out = out.unwrap_or_else(|| PathBuf::from(format!("{}-history", name)));
+                cmd_history_export(backend, &name, &out).await
+            }
+            Self::Versions { name, porcelain } => cmd_history(backend, &name, porcelain).await,
+            Self::Meta {
+                command: MetaCommand::Set { name, fields },
+            } => cmd_meta_set(backend, &name, &fields).await,
+            Self::Meta {
+                command: MetaCommand::Get { name, key },
+            } => cmd_meta_get(backend, &name, key.as_deref()).await,
+            Self::Retention { keep, off } => cmd_retention(backend, keep, off).await,
+            Self::Urls { name, add, remove } => cmd_urls(backend, &name, &add, &remove).await,
+            Self::Share {
+                name,
+                with,
+                role,
+                revoke,
+            } => cmd_share(backend, &name, with.as_deref(), &role, revoke.as_deref()).await,
+            Self::Bench {
+                name,
+                rps,
+                duration,
+                concurrency,
+                path,
+                record,
+                replay,
+            } => match replay {
+                Some(file) => cmd_session_replay(backend, &name, &file).await,
+                None => {
+                    cmd_bench(
+                        backend,
+                        &name,
+                        rps,
+                        &duration,
+                        concurrency,
+                        &path,
+                        record.as_deref(),
+                    )
+                    .await
+                }
+            },
+            Self::Exec {
+                name,
+                event,
+                cron,
+                tz,
+                messages,
+                format,
+                raw,
+            } => {
+                if let Some(cron) = &cron {
+                    print_upcoming(cron, tz);
+                }
+
+                let event = match event {
+                    EventKind::Scheduled => WorkerEvent::Scheduled {
+                        cron,
+                        scheduled_time: Utc::now(),
+                    },
+                    EventKind::Queue if messages.is_empty() => WorkerEvent::Queue {
+                        messages: vec![serde_json::json!({})],
+                    },
+                    EventKind::Queue => WorkerEvent::Queue { messages },
+                };
+
+                let format = if raw { LogFormat::Raw } else { format };
+
+                cmd_exec(backend, &name, event, format).await
+            }
+            Self::Runtime { name } => cmd_runtime(backend, &name).await,
+            Self::Replay {
+                name,
+                log_id,
+                from_file,
+            } => cmd_replay(backend, &name, log_id.as_deref(), from_file.as_deref()).await,
+            Self::Invoke {
+                name,
+                path,
+                method,
+                data,
+                headers,
+                record,
+                replay,
+            } => match replay {
+                Some(file) => cmd_session_replay(backend, &name, &file).await,
+                None => {
+                    let request = InvokeRequest {
+                        method,
+                        path,
+                        data,
+                        headers,
+                    };
+                    cmd_invoke(backend, &name, request, record.as_deref()).await
+                }
+            },
+            Self::Export { name, out } => {
+                let out = out.unwrap_or_else(|| PathBuf::from(format!("{}.tar.gz", name)));
+                cmd_export(backend, &name, &out).await
+            }
+            Self::Import { file, name, env } => cmd_import(backend, &file, name, env).await,
+        }
+    }
+}
+
+/// `team=payments` as a (key, value) pair
+fn parse_tag(tag: &str) -> Result<(String, String), String> {
+    match tag.split_once('=') {
+        Some((key, value)) if valid_tag_key(key) && !value.is_empty() => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "expected KEY=VALUE with a key of letters, digits, '-', '_', '.' or '/', got '{}'",
+            tag
+        )),
+    }
+}
+
+fn valid_tag_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+/// Whether a worker has every tag of the filters (`key=value`, or `key` for any value)
+fn matches_tags(worker: &Worker, filters: &[String]) -> bool {
+    filters.iter().all(|filter| match filter.split_once('=') {
+        Some((key, value)) => worker.tags.get(key).is_some_and(|v| v == value),
+        None => worker.tags.contains_key(filter),
+    })
+}
+
+fn format_tags(tags: &BTreeMap<String, String>) -> String {
+    tags.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn cmd_list<B: Backend>(
+    backend: &B,
+    long: bool,
+    ids: bool,
+    porcelain: bool,
+    tags: &[String],
+) -> Result<(), BackendError> {
+    let mut workers = backend.list_workers().await?;
+    workers.retain(|worker| matches_tags(worker, tags));
+
+    if porcelain {
+        for worker in &workers {
+            porcelain::print(&worker_fields(backend, worker));
+        }
+        return Ok(());
+    }
+
+    if ids {
+        for worker in &workers {
+            quiet::print(&worker.name);
+        }
+        return Ok(());
+    }
+
+    if workers.is_empty() {
+        if tags.is_empty() {
+            eprintln!("No workers found.");
+        } else {
+            eprintln!("No workers tagged {}.", tags.join(" and "));
+        }
+        return Ok(());
+    }
+
+    if long {
+        return print_workers_long(backend, &workers).await;
+    }
+
+    println!("{}", "Workers".bold());
+    println!("{}", "─".repeat(60));
+
+    for worker in workers {
+        let version = worker
+            .current_version
+            .map(|v| format!("v{}", v))
+            .unwrap_or_else(|| "no deploy".dimmed().to_string());
+
+        println!(
+            "  {:30} {:10} {}",
+            worker.name.bold(),
+            version,
+            worker.description.as_deref().unwrap_or("").dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn print_workers_long<B: Backend>(
+    backend: &B,
+    workers: &[Worker],
+) -> Result<(), BackendError> {
+    println!("{}", "Workers".bold());
+    println!("{}", "─".repeat(137));
     println!(
-        "{} Uploading {} ({} KB, {} assets)...",
-        "→".blue(),
-        path.display(),
-        size_kb,
-        assets.len()
+        "  {:24} {:8} {:16} {:16} {:11} {:17} {:36} {}",
+        "NAME".dimmed(),
+        "VERSION".dimmed(),
+        "ENVIRONMENT".dimmed(),
+        "OWNER".dimmed(),
+        "TYPE".dimmed(),
+        "DEPLOYED".dimmed(),
+        "URL".dimmed(),
+        "MESSAGE".dimmed()
     );
 
-    let result = backend
-        .upload_worker(name, &path, zip_data, &manifest)
-        .await?;
+    for [
+        name,
+        version,
+        env,
+        owner,
+        code_type,
+        deployed_at,
+        url,
+        message,
+    ] in long_rows(backend, workers).await?
+    {
+        println!(
+            "  {:24} {:8} {:16} {:16} {:11} {:17} {:36} {}",
+            name.bold(),
+            version,
+            env.cyan(),
+            owner,
+            code_type,
+            deployed_at,
+            url,
+            message.dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Rows of `workers list --long`, in listing order
+async fn long_rows<B: Backend>(
+    backend: &B,
+    workers: &[Worker],
+) -> Result<Vec<[String; 8]>, BackendError> {
+    let deployments = backend.list_current_deployments(workers).await?;
+
+    let rows = workers
+        .iter()
+        .zip(deployments)
+        .map(|(worker, deployment)| {
+            let version = worker
+                .current_version
+                .map(|v| format!("v{}", v))
+                .unwrap_or_else(|| "-".to_string());
+
+            let env = worker.environment.as_ref().map_or("-", |e| e.name.as_str());
+
+            let (code_type, deployed_at, message) = match deployment {
+                Some(d) => (
+                    d.code_type,
+                    d.deployed_at.format("%Y-%m-%d %H:%M").to_string(),
+                    d.message.unwrap_or_default(),
+                ),
+                None => ("-".to_string(), "-".to_string(), String::new()),
+            };
+
+            [
+                worker.name.clone(),
+                version,
+                env.to_string(),
+                worker
+                    .metadata
+                    .get("owner")
+                    .map_or("-", String::as_str)
+                    .to_string(),
+                code_type,
+                deployed_at,
+                worker_url(backend, worker).unwrap_or_else(|| "-".to_string()),
+                message,
+            ]
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Public URL of a worker: custom domain if any, else the default cloud subdomain
+pub fn worker_url<B: Backend>(backend: &B, worker: &Worker) -> Option<String> {
+    worker.url.clone().or_else(|| {
+        backend
+            .is_default_cloud()
+            .then(|| format!("https://{}.workers.rocks", worker.name))
+    })
+}
+
+/// Response of an HTTP request sent to a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvokeResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    pub truncated: bool,
+    pub duration_ms: u128,
+}
+
+/// Maximum response body kept by `invoke_worker` (bytes)
+pub const INVOKE_BODY_LIMIT: usize = 64 * 1024;
+
+/// Send an HTTP request to a worker URL and capture the response
+pub async fn invoke_worker(
+    url: &str,
+    method: &str,
+    headers: &[(String, String)],
+    body: Option<String>,
+) -> Result<InvokeResponse, BackendError> {
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| BackendError::Usage(format!("Invalid HTTP method '{}'", method)))?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    let mut request = client.request(method, url);
+
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+
+    let start = std::time::Instant::now();
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                String::from_utf8_lossy(v.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+
+    let bytes = response.bytes().await?;
+    let duration_ms = start.elapsed().as_millis();
+
+    let truncated = bytes.len() > INVOKE_BODY_LIMIT;
+    let body = String::from_utf8_lossy(&bytes[..bytes.len().min(INVOKE_BODY_LIMIT)]).to_string();
+
+    Ok(InvokeResponse {
+        status,
+        headers,
+        body,
+        truncated,
+        duration_ms,
+    })
+}
+
+/// Porcelain fields shared by `workers list` and `workers get`
+fn worker_fields<B: Backend>(backend: &B, worker: &Worker) -> Vec<String> {
+    vec![
+        worker.name.clone(),
+        worker.id.clone(),
+        worker
+            .current_version
+            .map(|v| v.to_string())
+            .unwrap_or_default(),
+        worker
+            .environment
+            .as_ref()
+            .map(|e| e.name.clone())
+            .unwrap_or_default(),
+        worker_url(backend, worker).unwrap_or_default(),
+    ]
+}
+
+async fn cmd_get<B: Backend>(
+    backend: &B,
+    name: &str,
+    ids: bool,
+    porcelain: bool,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    if ids {
+        quiet::print(&worker.name);
+        return Ok(());
+    }
+
+    let deployment = match worker.current_version {
+        Some(_) => backend.get_current_deployment(name).await?,
+        None => None,
+    };
+
+    if porcelain {
+        let mut fields = worker_fields(backend, &worker);
+        fields.push(
+            deployment
+                .as_ref()
+                .map(|d| porcelain::time(&d.deployed_at))
+                .unwrap_or_default(),
+        );
+        fields.push(deployment.map(|d| d.hash).unwrap_or_default());
+        porcelain::print(&fields);
+        return Ok(());
+    }
+
+    print_worker(&worker);
+
+    if let Some(deployment) = deployment {
+        print_live_deployment(&deployment);
+    }
+
+    Ok(())
+}
+
+/// What is serving right now: when the active version went live, who deployed it and why
+fn print_live_deployment(deployment: &Deployment) {
+    println!();
+    println!("{}", "Live deployment".bold());
+    println!("{}", "─".repeat(40));
+
+    let mut version = format!("v{}", deployment.version);
+
+    if let Some(tag) = &deployment.tag {
+        version.push_str(&format!(" ({})", tag));
+    }
+
+    println!("{:12} {}", "Version:".dimmed(), version.green());
+
+    let ago = format_age(Utc::now() - deployment.deployed_at);
+    println!(
+        "{:12} {} {}",
+        "Since:".dimmed(),
+        deployment.deployed_at.format("%Y-%m-%d %H:%M:%S"),
+        format!("({} ago)", ago).dimmed()
+    );
+
+    if let Some(deployer) = &deployment.deployed_by {
+        println!("{:12} {}", "Deployed by:".dimmed(), deployer);
+    }
+
+    if let Some(message) = &deployment.message {
+        println!("{:12} {}", "Message:".dimmed(), message);
+    }
+
+    println!(
+        "{:12} {} {}",
+        "Code:".dimmed(),
+        deployment.code_type,
+        deployment
+            .hash
+            .chars()
+            .take(12)
+            .collect::<String>()
+            .dimmed()
+    );
+}
+
+/// Coarse age like "5m", "3h" or "12d"
+fn format_age(age: chrono::Duration) -> String {
+    match age.num_seconds().max(0) {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+async fn cmd_create<B: Backend>(
+    backend: &B,
+    name: String,
+    description: Option<String>,
+    language: String,
+    file: Option<PathBuf>,
+    tags: Vec<(String, String)>,
+) -> Result<(), BackendError> {
+    let input = CreateWorkerInput {
+        name,
+        description,
+        language,
+        tags: tags.into_iter().collect(),
+    };
+    let worker = backend.create_worker(input).await?;
+
+    eprintln!(
+        "{} Worker '{}' created.",
+        "Created".green(),
+        worker.name.bold()
+    );
+    eprintln!();
+
+    let Some(file) = file else {
+        print_worker(&worker);
+        return print_linked_bindings(backend, &worker).await;
+    };
+
+    environment_gate(backend, &worker.name, None, false).await?;
+
+    // A new worker is neither protected nor linked to an environment yet
+    let authorization = DeployAuthorization::default();
+
+    if let Err(e) = cmd_deploy(
+        backend,
+        &worker.name,
+        file.clone(),
+        None,
+        authorization,
+        false,
+    )
+    .await
+    {
+        eprintln!(
+            "{} The worker was created, retry with: {}",
+            "hint:".yellow(),
+            format!("ow workers deploy {} {}", worker.name, file.display()).cyan()
+        );
+        return Err(e);
+    }
+
+    if let Some(url) = worker_url(backend, &worker) {
+        println!("{:12} {}", "URL:".dimmed(), url.cyan());
+    }
+
+    print_linked_bindings(backend, &worker).await
+}
+
+/// TypeScript type of each binding kind, with an example access
+fn binding_type(value_type: &str) -> (&'static str, &'static str) {
+    match value_type {
+        "kv" => ("KVNamespace", "await env.{}.get(\"key\")"),
+        "storage" => ("StorageBinding", "await env.{}.get(\"path/to/file\")"),
+        "database" => ("DatabaseBinding", "await env.{}.execute(\"SELECT 1\")"),
+        "assets" => ("AssetsBinding", "await env.{}.fetch(request)"),
+        _ => ("string", "env.{}"),
+    }
+}
+
+/// Ready-to-paste `Env` interface and access examples for an environment's
+/// values, or None when it has none
+fn env_snippet(values: &[EnvironmentValue]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut fields = String::new();
+    let mut examples = String::new();
+
+    for value in values {
+        let (ts_type, example) = binding_type(&value.value_type);
+        let comment = match value.value_type.as_str() {
+            "secret" => " // secret",
+            _ => "",
+        };
+
+        fields.push_str(&format!("  {}: {};{}\n", value.key, ts_type, comment));
+        examples.push_str(&example.replace("{}", &value.key));
+        examples.push('\n');
+    }
+
+    Some(format!("interface Env {{\n{}}}\n\n{}", fields, examples))
+}
+
+/// Print the `Env` snippet for the worker's linked environment, if any
+async fn print_linked_bindings<B: Backend>(
+    backend: &B,
+    worker: &Worker,
+) -> Result<(), BackendError> {
+    let Some(env_ref) = &worker.environment else {
+        return Ok(());
+    };
+
+    let environment = backend.get_environment(&env_ref.name).await?;
+    print_env_snippet(&environment.values);
+
+    Ok(())
+}
+
+fn print_env_snippet(values: &[EnvironmentValue]) {
+    let Some(snippet) = env_snippet(values) else {
+        return;
+    };
+
+    println!();
+    println!("{}", "Bindings (TypeScript):".bold());
+    println!();
+
+    for line in snippet.lines() {
+        println!("  {}", line.cyan());
+    }
+}
+
+async fn cmd_delete<B: Backend>(backend: &B, name: &str, yes: bool) -> Result<(), BackendError> {
+    if !yes {
+        let preview = backend.preview_delete_worker(name).await?;
+        super::print_delete_preview(&preview);
+    }
+
+    super::confirm_delete(&format!("worker '{}'", name), yes)?;
+
+    backend.delete_worker(name).await?;
+
+    eprintln!("{} Worker '{}' deleted.", "Deleted".red(), name.bold());
+
+    super::notifications::notify(backend, NotificationEvent::Delete, name, None).await;
+
+    Ok(())
+}
+
+fn print_worker(worker: &Worker) {
+    println!("{:12} {}", "Name:".dimmed(), worker.name.bold());
+    println!("{:12} {}", "ID:".dimmed(), worker.id);
+
+    if let Some(desc) = &worker.description {
+        println!("{:12} {}", "Description:".dimmed(), desc);
+    }
+
+    if let Some(env) = &worker.environment {
+        if env.protected {
+            println!(
+                "{:12} {} {}",
+                "Environment:".dimmed(),
+                env.name.cyan(),
+                "(protected)".yellow()
+            );
+        } else {
+            println!("{:12} {}", "Environment:".dimmed(), env.name.cyan());
+        }
+    }
+
+    if worker.protected {
+        println!("{:12} {}", "Protected:".dimmed(), "yes".yellow());
+    }
+
+    if !worker.tags.is_empty() {
+        println!("{:12} {}", "Tags:".dimmed(), format_tags(&worker.tags));
+    }
+
+    println!(
+        "{:12} {}",
+        "Version:".dimmed(),
+        worker
+            .current_version
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "none".to_string())
+    );
+
+    println!(
+        "{:12} {}",
+        "Created:".dimmed(),
+        worker.created_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    println!(
+        "{:12} {}",
+        "Updated:".dimmed(),
+        worker.updated_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    for (key, value) in &worker.metadata {
+        println!("{:12} {}", format!("{}:", key).dimmed(), value);
+    }
+}
+
+/// ow.toml's `assets.dir`, used by uploads given no --assets
+fn project_assets() -> Result<Vec<AssetSource>, BackendError> {
+    let manifest = Manifest::find().map_err(|e| BackendError::Api(e.to_string()))?;
+
+    Ok(manifest
+        .and_then(|m| m.assets.dir)
+        .map(|dir| AssetSource {
+            dir,
+            mount: String::new(),
+        })
+        .into_iter()
+        .collect())
+}
+
+/// Refuse to deploy when ow.toml pins the project to an environment the worker
+/// isn't linked to on this backend. `links` is set by `ow deploy`, which links
+/// an unlinked worker to the pinned environment itself.
+pub async fn environment_gate<B: Backend>(
+    backend: &B,
+    name: &str,
+    env: Option<&str>,
+    links: bool,
+) -> Result<(), BackendError> {
+    let manifest = Manifest::find().map_err(|e| BackendError::Api(e.to_string()))?;
+
+    let Some(required) = manifest.and_then(|m| m.environment) else {
+        return Ok(());
+    };
+
+    let worker = backend.get_worker(name).await?;
+    let linked = match worker.environment {
+        Some(environment) => Some(environment.name),
+        None if links => Some(required.clone()),
+        None => None,
+    };
+
+    check_environment(name, &required, env, linked.as_deref())
+}
+
+/// Refuse to deploy when the worker's environment breaks ow.toml's `env_schema`
+pub async fn schema_gate<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+    let manifest = Manifest::find().map_err(|e| BackendError::Api(e.to_string()))?;
+
+    let Some(schema) = manifest.and_then(|m| m.env_schema) else {
+        return Ok(());
+    };
+
+    let worker = backend.get_worker(name).await?;
+
+    let Some(environment) = worker.environment else {
+        return Err(BackendError::Api(format!(
+            "{} checks deploys against {}, but '{}' has no linked environment. \
+             Link one with 'ow workers link'.",
+            MANIFEST_FILE, schema, name
+        )));
+    };
+
+    super::env::check_schema(backend, &environment.name, Path::new(&schema)).await
+}
+
+/// The worker must be linked to the pinned environment; `--env`, when given,
+/// must name it too
+fn check_environment(
+    name: &str,
+    required: &str,
+    env: Option<&str>,
+    linked: Option<&str>,
+) -> Result<(), BackendError> {
+    if let Some(env) = env.filter(|env| *env != required) {
+        return Err(BackendError::Api(format!(
+            "{} pins this project to environment '{}', but this deploy targets '{}'",
+            MANIFEST_FILE, required, env
+        )));
+    }
+
+    match linked {
+        Some(linked) if linked == required => Ok(()),
+        linked => Err(BackendError::Api(format!(
+            "{} pins this project to environment '{}', but '{}' is linked to {}. \
+             Check the alias, or link it with 'ow workers link {} {}'.",
+            MANIFEST_FILE,
+            required,
+            name,
+            linked.map_or("no environment".to_string(), |l| format!("'{}'", l)),
+            name,
+            required
+        ))),
+    }
+}
+
+/// Code type of a worker script, from its extension
+pub fn code_type(file: &Path) -> Result<&'static str, BackendError> {
+    match file.extension().and_then(|e| e.to_str()) {
+        Some("js") => Ok("javascript"),
+        Some("ts") => Ok("typescript"),
+        Some("wasm") => Ok("wasm"),
+        _ => Err(BackendError::Api(
+            "Unknown file type. Use .js, .ts, or .wasm".to_string(),
+        )),
+    }
+}
+
+pub async fn cmd_deploy<B: Backend>(
+    backend: &B,
+    name: &str,
+    file: PathBuf,
+    message: Option<String>,
+    authorization: DeployAuthorization,
+    porcelain: bool,
+) -> Result<(), BackendError> {
+    // Read file
+    let code = std::fs::read(&file).map_err(|e| {
+        BackendError::Api(format!("Failed to read file '{}': {}", file.display(), e))
+    })?;
+
+    let code_type = code_type(&file)?;
+
+    let hash = sha256_hex(&code);
+
+    if !porcelain {
+        eprintln!("{} SHA-256 {}", "→".blue(), hash);
+    }
+
+    let input = DeployInput {
+        code,
+        code_type: code_type.to_string(),
+        message,
+        hash: Some(hash.clone()),
+        authorization,
+    };
+
+    let deployment = backend.deploy_worker(name, input).await?;
+
+    if !deployment.hash.eq_ignore_ascii_case(&hash) {
+        return Err(BackendError::Api(format!(
+            "Checksum mismatch: sent {}, backend stored {}",
+            hash, deployment.hash
+        )));
+    }
+
+    super::notifications::notify(
+        backend,
+        NotificationEvent::Deploy,
+        name,
+        Some(deployment.version),
+    )
+    .await;
+
+    if porcelain {
+        porcelain::print(&[
+            name.to_string(),
+            deployment.version.to_string(),
+            deployment.hash,
+            deployment.code_type,
+            porcelain::time(&deployment.deployed_at),
+        ]);
+        return Ok(());
+    }
+
+    eprintln!(
+        "{} Deployed '{}' v{}",
+        "Deployed".green(),
+        name.bold(),
+        deployment.version
+    );
+
+    eprintln!();
+    println!("{:12} {}", "Version:".dimmed(), deployment.version);
+    println!("{:12} {}", "Hash:".dimmed(), deployment.hash);
+    println!("{:12} {}", "Type:".dimmed(), deployment.code_type);
+    println!(
+        "{:12} {}",
+        "Deployed:".dimmed(),
+        deployment.deployed_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    if let Some(msg) = &deployment.message {
+        println!("{:12} {}", "Message:".dimmed(), msg);
+    }
+
+    Ok(())
+}
+
+async fn cmd_verify_hash<B: Backend>(
+    backend: &B,
+    name: &str,
+    file: PathBuf,
+) -> Result<(), BackendError> {
+    let code = std::fs::read(&file).map_err(|e| {
+        BackendError::Api(format!("Failed to read file '{}': {}", file.display(), e))
+    })?;
+
+    let local = sha256_hex(&code);
+
+    let deployment = backend.get_current_deployment(name).await?.ok_or_else(|| {
+        BackendError::NotFound(format!("Worker '{}' has no live deployment", name))
+    })?;
+
+    println!("{:12} {}", "Local:".dimmed(), local);
+    println!(
+        "{:12} {} (v{})",
+        "Live:".dimmed(),
+        deployment.hash,
+        deployment.version
+    );
+    eprintln!();
+
+    if !deployment.hash.eq_ignore_ascii_case(&local) {
+        return Err(BackendError::Api(format!(
+            "'{}' does not match the live deployment of '{}'",
+            file.display(),
+            name
+        )));
+    }
+
+    eprintln!(
+        "{} '{}' matches the live deployment.",
+        "Match".green(),
+        file.display()
+    );
+
+    Ok(())
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(data))
+}
+
+/// How a key differs between the linked environment and a candidate
+#[derive(Debug, PartialEq)]
+pub(super) enum KeyChange {
+    Added {
+        key: String,
+        value_type: String,
+    },
+    Removed {
+        key: String,
+        value_type: String,
+    },
+    Retyped {
+        key: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl KeyChange {
+    /// Removed and retyped keys break code that reads them
+    fn is_breaking(&self) -> bool {
+        !matches!(self, Self::Added { .. })
+    }
+}
+
+/// Keys that appear, disappear or change type going from `current` to
+/// `candidate`, sorted by key. Values themselves are not compared.
+pub(super) fn diff_keys(
+    current: &[EnvironmentValue],
+    candidate: &[EnvironmentValue],
+) -> Vec<KeyChange> {
+    let current: BTreeMap<&str, &str> = current
+        .iter()
+        .map(|v| (v.key.as_str(), v.value_type.as_str()))
+        .collect();
+    let candidate: BTreeMap<&str, &str> = candidate
+        .iter()
+        .map(|v| (v.key.as_str(), v.value_type.as_str()))
+        .collect();
+
+    let keys: std::collections::BTreeSet<&str> =
+        current.keys().chain(candidate.keys()).copied().collect();
+
+    keys.into_iter()
+        .filter_map(|key| match (current.get(key), candidate.get(key)) {
+            (None, Some(to)) => Some(KeyChange::Added {
+                key: key.to_string(),
+                value_type: to.to_string(),
+            }),
+            (Some(from), None) => Some(KeyChange::Removed {
+                key: key.to_string(),
+                value_type: from.to_string(),
+            }),
+            (Some(from), Some(to)) if from != to => Some(KeyChange::Retyped {
+                key: key.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(super) fn print_key_changes(current: Option<&str>, candidate: &str, changes: &[KeyChange]) {
+    let from = current.unwrap_or("no environment");
+
+    if changes.is_empty() {
+        eprintln!("Same keys in '{}' and '{}'.", from, candidate);
+        return;
+    }
+
+    println!("{}", format!("Keys: {} → {}", from, candidate).bold());
+
+    for change in changes {
+        match change {
+            KeyChange::Added { key, value_type } => {
+                println!("  {} {:24} {}", "+".green(), key, value_type.dimmed())
+            }
+            KeyChange::Removed { key, value_type } => {
+                println!("  {} {:24} {}", "-".red(), key, value_type.dimmed())
+            }
+            KeyChange::Retyped { key, from, to } => {
+                println!("  {} {:24} {} → {}", "~".yellow(), key, from, to)
+            }
+        }
+    }
+}
+
+async fn cmd_link<B: Backend>(
+    backend: &B,
+    name: &str,
+    env: &str,
+    diff: bool,
+    yes: bool,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+    let environment = backend.get_environment(env).await?;
+
+    if diff {
+        let current = match &worker.environment {
+            Some(linked) => Some(backend.get_environment(&linked.name).await?),
+            None => None,
+        };
+
+        let current_values = current.as_ref().map_or(&[][..], |e| &e.values[..]);
+        let changes = diff_keys(current_values, &environment.values);
+
+        print_key_changes(current.as_ref().map(|e| e.name.as_str()), env, &changes);
+
+        let breaking = changes.iter().filter(|c| c.is_breaking()).count();
+
+        if breaking > 0 && !yes {
+            if !std::io::stdin().is_terminal() {
+                return Err(BackendError::Api(format!(
+                    "{} keys removed or retyped. Pass --yes to link anyway.",
+                    breaking
+                )));
+            }
+
+            eprint!("{} keys removed or retyped. Link anyway? [y/N] ", breaking);
+            std::io::stderr().flush().ok();
+
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_line(&mut buf)
+                .map_err(|e| BackendError::Api(format!("Failed to read input: {}", e)))?;
+
+            if !matches!(buf.trim().to_lowercase().as_str(), "y" | "yes") {
+                return Err(BackendError::Api("Link cancelled.".to_string()));
+            }
+        }
+    }
+
+    backend
+        .link_worker_environment(&worker.id, &environment.id)
+        .await?;
+
+    eprintln!(
+        "{} Worker '{}' linked to environment '{}'.",
+        "Linked".green(),
+        name.bold(),
+        env.bold()
+    );
+
+    print_env_snippet(&environment.values);
+
+    Ok(())
+}
+
+async fn cmd_unlink<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    let Some(environment) = worker.environment else {
+        eprintln!("Worker '{}' is not linked to an environment.", name.bold());
+        return Ok(());
+    };
+
+    let input = UpdateWorkerInput {
+        environment: Some(String::new()),
+        ..Default::default()
+    };
+
+    backend.update_worker(name, input).await?;
+
+    eprintln!(
+        "{} Worker '{}' unlinked from environment '{}'.",
+        "Unlinked".yellow(),
+        name.bold(),
+        environment.name.bold()
+    );
+
+    Ok(())
+}
+
+/// One entry of a worker's runtime configuration
+#[derive(Debug)]
+struct EffectiveValue {
+    key: String,
+    value_type: String,
+    /// Masked secret, or binding resource name when known
+    display: String,
+    /// Where the value comes from: the environment name, or "worker"
+    source: String,
+}
+
+/// Resource ID -> name for the bindings among `values`. Only the resource
+/// kinds that are actually bound are listed.
+async fn resource_names<B: Backend>(
+    backend: &B,
+    values: &[EnvironmentValue],
+) -> Result<HashMap<String, String>, BackendError> {
+    let has_type = |types: &[&str]| {
+        values
+            .iter()
+            .any(|v| types.contains(&v.value_type.as_str()))
+    };
+
+    let mut resources: HashMap<String, String> = HashMap::new();
+
+    if has_type(&["kv"]) {
+        resources.extend(backend.list_kv().await?.into_iter().map(|r| (r.id, r.name)));
+    }
+
+    if has_type(&["storage", "assets"]) {
+        resources.extend(
+            backend
+                .list_storage()
+                .await?
+                .into_iter()
+                .map(|r| (r.id, r.name)),
+        );
+    }
+
+    if has_type(&["database"]) {
+        resources.extend(
+            backend
+                .list_databases()
+                .await?
+                .into_iter()
+                .map(|r| (r.id, r.name)),
+        );
+    }
+
+    Ok(resources)
+}
+
+/// Values the worker sees at runtime, with binding IDs resolved to resource
+/// names. Worker values take precedence over the linked environment.
+async fn effective_values<B: Backend>(
+    backend: &B,
+    worker: &Worker,
+) -> Result<Vec<EffectiveValue>, BackendError> {
+    let env_values = match &worker.environment {
+        Some(env_ref) => backend.get_environment(&env_ref.name).await?.values,
+        None => Vec::new(),
+    };
+    let env_name = worker.environment.as_ref().map(|e| e.name.as_str());
+
+    let resources = resource_names(backend, &env_values).await?;
+    let overrides = backend.list_worker_values(&worker.name).await?;
+
+    let mut values: Vec<EffectiveValue> = env_values
+        .iter()
+        .filter(|val| !overrides.iter().any(|o| o.key == val.key))
+        .map(|val| EffectiveValue {
+            key: val.key.clone(),
+            value_type: val.value_type.clone(),
+            display: resources
+                .get(&val.value)
+                .cloned()
+                .unwrap_or_else(|| super::env::display_value(val)),
+            source: env_name.unwrap_or_default().to_string(),
+        })
+        .collect();
+
+    for val in &overrides {
+        let source = match env_name {
+            Some(env) if env_values.iter().any(|v| v.key == val.key) => {
+                format!("worker, overrides {}", env)
+            }
+            _ => "worker".to_string(),
+        };
+
+        values.push(EffectiveValue {
+            key: val.key.clone(),
+            value_type: val.value_type.clone(),
+            display: super::env::display_value(val),
+            source,
+        });
+    }
+
+    values.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(values)
+}
+
+async fn cmd_env<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+    let values = effective_values(backend, &worker).await?;
+
+    let environment = worker
+        .environment
+        .as_ref()
+        .map(|e| e.name.as_str())
+        .unwrap_or("none");
+
+    println!(
+        "{} {} {}",
+        "Runtime configuration of".bold(),
+        worker.name.bold(),
+        format!("(environment: {})", environment).dimmed()
+    );
+    println!("{}", "─".repeat(60));
+
+    if values.is_empty() {
+        println!("  {}", "No variables or bindings.".dimmed());
+    }
+
+    for val in &values {
+        println!(
+            "  {} {} = {} {}",
+            super::env::type_badge(&val.value_type),
+            val.key.bold(),
+            val.display,
+            format!("({})", val.source).dimmed()
+        );
+    }
+
+    if worker.environment.is_none() {
+        eprintln!();
+        eprintln!(
+            "  {} ow workers link {} <env>",
+            "Link an environment with:".dimmed(),
+            name
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_set_var<B: Backend>(
+    backend: &B,
+    name: &str,
+    key: &str,
+    value: &str,
+    secret: bool,
+) -> Result<(), BackendError> {
+    let value_type = if secret { "secret" } else { "var" };
+    backend
+        .set_worker_value(name, key, value, value_type)
+        .await?;
+
+    eprintln!(
+        "{} {} '{}' set on worker '{}' (overrides its environment).",
+        "Updated".green(),
+        if secret { "Secret" } else { "Variable" },
+        key.bold(),
+        name.bold()
+    );
+
+    Ok(())
+}
+
+async fn cmd_unset_var<B: Backend>(backend: &B, name: &str, key: &str) -> Result<(), BackendError> {
+    backend.delete_worker_value(name, key).await?;
+
+    eprintln!(
+        "{} '{}' removed from worker '{}'.",
+        "Removed".yellow(),
+        key.bold(),
+        name.bold()
+    );
+
+    Ok(())
+}
+
+pub async fn cmd_upload<B: Backend>(
+    backend: &B,
+    name: &str,
+    path: PathBuf,
+    authorization: DeployAuthorization,
+    options: UploadOptions,
+) -> Result<(), BackendError> {
+    let UploadOptions {
+        assets: asset_sources,
+        policy,
+        fingerprint,
+        auto_bind,
+        verify,
+    } = options;
+
+    // Bad route patterns must fail before anything is deployed or uploaded
+    if let Some(content) = read_routes_file(&path)? {
+        let routes = RoutesConfig::parse(&content).map_err(|e| {
+            // Issues can only be pointed at a file that exists in the checkout
+            if path.is_dir() {
+                let file = path.join(ROUTES_FILE).display().to_string();
+
+                for issue in &e.0 {
+                    github::error_at(&file, issue.line, &issue.message);
+                }
+            }
+
+            BackendError::Api(e.to_string())
+        })?;
+
+        let missing = routes
+            .functions
+            .iter()
+            .find(|f| path.is_dir() && !path.join(f.script_path()).is_file());
+
+        if let Some(function) = missing {
+            return Err(BackendError::Api(format!(
+                "Function file '{}' for route '{}' not found in {}",
+                function.worker,
+                function.pattern,
+                path.display()
+            )));
+        }
+
+        eprintln!(
+            "{} {} OK ({} routes, {} functions)",
+            "→".blue(),
+            ROUTES_FILE,
+            routes.immutable.len()
+                + routes.static_routes.len()
+                + routes.prerendered.len()
+                + routes.ssr.len(),
+            routes.functions.len()
+        );
+    }
+
+    // Collect assets (separate from zip): --assets sources, or the folder's assets/
+    let asset_sources = if !asset_sources.is_empty() {
+        asset_sources
+    } else if path.join("assets").is_dir() {
+        vec![AssetSource {
+            dir: path.join("assets"),
+            mount: String::new(),
+        }]
+    } else {
+        vec![]
+    };
+
+    let mut assets = collect_assets(&asset_sources, policy)?;
+
+    if let Some(manifest) = Manifest::find().map_err(|e| BackendError::Api(e.to_string()))? {
+        let before = assets.len();
+        assets = apply_asset_rules(assets, &manifest.assets);
+
+        if assets.len() < before {
+            eprintln!(
+                "{} Excluded {} assets ({} rules)",
+                "→".blue(),
+                before - assets.len(),
+                MANIFEST_FILE
+            );
+        }
+    }
+
+    if !assets.is_empty() {
+        ensure_assets_binding(
+            backend,
+            name,
+            auto_bind.as_deref(),
+            std::io::stdin().is_terminal(),
+        )
+        .await?;
+    }
+
+    // Rename assets to include their content hash, plus a manifest for the worker
+    let fingerprint_manifest = if fingerprint && !assets.is_empty() {
+        let (renamed, manifest_asset) = fingerprint_assets(&mut assets)?;
+
+        eprintln!(
+            "{} Fingerprinted {} assets (manifest: /{})",
+            "→".blue(),
+            renamed,
+            FINGERPRINT_MANIFEST
+        );
+
+        assets.push(manifest_asset);
+        assets.last().map(|a| a.path.clone())
+    } else {
+        None
+    };
+
+    // Build asset manifest with SHA-256 hashes
+    let manifest: Vec<AssetManifestEntry> = assets
+        .iter()
+        .map(|asset| AssetManifestEntry {
+            path: asset.key.clone(),
+            size: asset.size as usize,
+            content_type: asset.content_type.clone(),
+            hash: asset.sha256_hex.clone(),
+            cache_control: asset.cache_control.clone(),
+        })
+        .collect();
+
+    let is_zip = path.extension().and_then(|e| e.to_str()) == Some("zip");
+
+    if !path.is_dir() && !is_zip {
+        return Err(BackendError::Usage(
+            "Path must be a .zip archive or a folder".to_string(),
+        ));
+    }
+
+    // Archive, upload, then asset sync and verification when there are assets
+    let mut steps = Steps::new(
+        2 + usize::from(!assets.is_empty()) + usize::from(verify.is_some() && !assets.is_empty()),
+    );
+
+    let zip_data = if is_zip {
+        steps.start(&format!("Reading {}", path.display()));
+        std::fs::read(&path).map_err(|e| {
+            BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
+        })?
+    } else {
+        // Create zip from folder (code only, no assets)
+        steps.start(&format!("Creating archive from {}", path.display()));
+        create_zip_from_folder(&path, policy, &asset_sources)?
+    };
+
+    steps.start(&format!(
+        "Uploading {} ({} KB, {} assets)",
+        path.display(),
+        zip_data.len() / 1024,
+        assets.len()
+    ));
+
+    let result = backend
+        .upload_worker(name, &path, zip_data, &manifest, &authorization)
+        .await?;
+
+    // Upload assets (presigned URLs from API, or direct S3 from DB backend)
+    let mut verify_issues = Vec::new();
+
+    let counts = if assets.is_empty() {
+        Ok((0, 0, 0))
+    } else if let Some(ref presigned) = result.assets {
+        steps.start(&format!("Syncing {} assets", presigned.len()));
+        let urls = presigned
+            .iter()
+            .map(|a| (a.path.clone(), (a.head_url.clone(), a.put_url.clone())))
+            .collect();
+        let client = PresignedClient::new(urls);
+        let _group = github::group(&format!("Upload {} assets", presigned.len()));
+        let counts = s3::upload_assets(&client, &assets)
+            .timed(Phase::Upload)
+            .await;
+
+        if let (Ok(_), Some(sample)) = (&counts, verify) {
+            steps.start(&format!(
+                "Verifying {} sampled assets",
+                sample.min(assets.len())
+            ));
+            verify_issues = s3::verify_assets(&client, &assets, sample).await;
+        }
+
+        counts
+    } else if let Some(ref config) = result.direct_upload {
+        steps.start(&format!("Syncing {} assets", assets.len()));
+        let client = S3Client::new(S3Config {
+            bucket: config.bucket.clone(),
+            endpoint: config.endpoint.clone(),
+            access_key_id: config.access_key_id.clone(),
+            secret_access_key: config.secret_access_key.clone(),
+            region: config.region.clone(),
+            prefix: config.prefix.clone(),
+            session_token: config.session_token.clone(),
+        });
+        let _group = github::group(&format!("Upload {} assets", assets.len()));
+        let counts = s3::upload_assets(&client, &assets)
+            .timed(Phase::Upload)
+            .await;
+
+        if let (Ok(_), Some(sample)) = (&counts, verify) {
+            steps.start(&format!(
+                "Verifying {} sampled assets",
+                sample.min(assets.len())
+            ));
+            verify_issues = s3::verify_assets(&client, &assets, sample).await;
+        }
+
+        counts
+    } else {
+        Ok((0, 0, 0))
+    };
+
+    if let Some(manifest_path) = fingerprint_manifest {
+        std::fs::remove_file(manifest_path).ok();
+    }
+
+    let (uploaded_assets, skipped_assets, failed_assets) = counts.map_err(|e| {
+        let message = format!(
+            "{}\nThe worker code was deployed, its assets were not synced.",
+            e
+        );
+
+        match e.kind {
+            FailureKind::Dns | FailureKind::Connect => BackendError::Unavailable(message),
+            _ => BackendError::Api(message),
+        }
+    })?;
+
+    steps.finish();
+
+    let version_str = result
+        .deployed
+        .as_ref()
+        .map(|d| format!("v{}", d.version))
+        .unwrap_or_else(|| "deployed".to_string());
+
+    eprintln!(
+        "{} Uploaded to '{}' ({})",
+        "Uploaded".green(),
+        result.worker.name.bold(),
+        version_str
+    );
+
+    eprintln!();
+
+    if result.worker.url.starts_with("http") {
+        println!("{:12} {}", "URL:".dimmed(), result.worker.url);
+    } else if backend.is_default_cloud() {
+        println!(
+            "{:12} https://{}.workers.rocks",
+            "URL:".dimmed(),
+            result.worker.url
+        );
+    } else {
+        println!("{:12} {}", "Worker:".dimmed(), result.worker.url);
+    }
+
+    github::notice(&format!(
+        "Deployed '{}' ({}) to {}",
+        result.worker.name, version_str, result.worker.url
+    ));
+
+    super::notifications::notify(
+        backend,
+        NotificationEvent::Deploy,
+        &result.worker.name,
+        result.deployed.as_ref().map(|d| d.version),
+    )
+    .await;
+
+    if let Some(deployed) = &result.deployed {
+        println!("{:12} {}", "Version:".dimmed(), deployed.version);
+
+        if deployed.functions > 0 {
+            println!("{:12} {}", "Functions:".dimmed(), deployed.functions);
+        }
+    }
+
+    if uploaded_assets > 0 || skipped_assets > 0 {
+        if skipped_assets > 0 {
+            println!(
+                "{:12} {} uploaded, {} unchanged",
+                "Assets:".dimmed(),
+                uploaded_assets,
+                skipped_assets
+            );
+        } else {
+            println!("{:12} {} uploaded", "Assets:".dimmed(), uploaded_assets);
+        }
+    }
+
+    if let Some(sample) = verify.filter(|_| !assets.is_empty()) {
+        let checked = sample.min(assets.len());
+
+        if verify_issues.is_empty() {
+            println!(
+                "{:12} {} sampled assets match",
+                "Verified:".dimmed(),
+                checked
+            );
+        } else {
+            eprintln!();
+            eprintln!(
+                "{} {} of {} sampled assets differ from the local files:",
+                "!".yellow(),
+                verify_issues.len(),
+                checked
+            );
+
+            for issue in &verify_issues {
+                eprintln!("  {} {}", "⎿".red(), issue);
+                github::warning(&format!("Asset verification: {}", issue));
+            }
+
+            return Err(BackendError::Api(
+                "Uploaded assets differ from the local files (a proxy or bucket may \
+                 transform them)"
+                    .to_string(),
+            ));
+        }
+    }
+
+    if failed_assets > 0 {
+        return Err(BackendError::Partial(format!(
+            "{} of {} assets failed to upload. The worker code was deployed; re-run to retry them.",
+            failed_assets,
+            assets.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Apply `KEY=VALUE` fields to metadata; an empty value removes the key
+fn apply_metadata(
+    metadata: &mut BTreeMap<String, String>,
+    fields: &[String],
+) -> Result<(), BackendError> {
+    for field in fields {
+        let (key, value) = field
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| {
+                BackendError::Usage(format!("Invalid field '{}'. Use KEY=VALUE.", field))
+            })?;
+
+        if value.is_empty() {
+            metadata.remove(key.trim());
+        } else {
+            metadata.insert(key.trim().to_string(), value.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_meta_set<B: Backend>(
+    backend: &B,
+    name: &str,
+    fields: &[String],
+) -> Result<(), BackendError> {
+    let mut metadata = backend.get_worker(name).await?.metadata;
+    apply_metadata(&mut metadata, fields)?;
+
+    let worker = backend.set_worker_metadata(name, &metadata).await?;
+
+    eprintln!(
+        "{} Metadata of worker '{}' updated.",
+        "Updated".green(),
+        name.bold()
+    );
+
+    for (key, value) in &worker.metadata {
+        println!("  {:12} {}", format!("{}:", key).dimmed(), value);
+    }
+
+    Ok(())
+}
+
+async fn cmd_meta_get<B: Backend>(
+    backend: &B,
+    name: &str,
+    key: Option<&str>,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    if let Some(key) = key {
+        let value = worker.metadata.get(key).ok_or_else(|| {
+            BackendError::NotFound(format!("Worker '{}' has no '{}' metadata", name, key))
+        })?;
+
+        println!("{}", value);
+        return Ok(());
+    }
+
+    if worker.metadata.is_empty() {
+        eprintln!(
+            "No metadata for worker '{}'. Add some with: ow workers meta set {} owner=<team>",
+            name, name
+        );
+        return Ok(());
+    }
+
+    for (key, value) in &worker.metadata {
+        println!("{:12} {}", format!("{}:", key).dimmed(), value);
+    }
+
+    Ok(())
+}
+
+async fn cmd_update<B: Backend>(
+    backend: &B,
+    name: &str,
+    description: Option<String>,
+    tags: Vec<(String, String)>,
+    untag: Vec<String>,
+) -> Result<(), BackendError> {
+    if description.is_none() && tags.is_empty() && untag.is_empty() {
+        return Err(BackendError::Usage(
+            "Nothing to update. Pass --description, --tag or --untag.".to_string(),
+        ));
+    }
+
+    let tags = if tags.is_empty() && untag.is_empty() {
+        None
+    } else {
+        let mut current = backend.get_worker(name).await?.tags;
+
+        for key in &untag {
+            current.remove(key);
+        }
+
+        current.extend(tags);
+        Some(current)
+    };
+
+    let input = UpdateWorkerInput {
+        desc: description,
+        tags,
+        ..Default::default()
+    };
+
+    backend.update_worker(name, input).await?;
+
+    eprintln!("{} Worker '{}' updated.", "Updated".green(), name.bold());
+
+    Ok(())
+}
+
+async fn cmd_protect<B: Backend>(
+    backend: &B,
+    name: &str,
+    protected: bool,
+) -> Result<(), BackendError> {
+    let input = UpdateWorkerInput {
+        protected: Some(protected),
+        ..Default::default()
+    };
+
+    backend.update_worker(name, input).await?;
+
+    if protected {
+        eprintln!(
+            "{} Worker '{}' is now protected.",
+            "Protected".yellow(),
+            name.bold()
+        );
+    } else {
+        eprintln!(
+            "{} Worker '{}' is no longer protected.",
+            "Unprotected".green(),
+            name.bold()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_approve<B: Backend>(
+    backend: &B,
+    name: &str,
+    expires: &str,
+) -> Result<(), BackendError> {
+    let ttl = super::parse_duration(expires).map_err(BackendError::Api)?;
+    let approval = backend.create_deploy_approval(name, ttl).await?;
+
+    eprintln!(
+        "{} Approval token for '{}' (single use).",
+        "Approved".green(),
+        name.bold()
+    );
+    eprintln!();
+    println!("{:12} {}", "Token:".dimmed(), approval.token);
+    println!(
+        "{:12} {}",
+        "Expires:".dimmed(),
+        approval.expires_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    Ok(())
+}
+
+async fn cmd_prune<B: Backend>(
+    backend: &B,
+    name: &str,
+    keep: Option<u32>,
+) -> Result<(), BackendError> {
+    let keep = match keep {
+        Some(keep) => keep,
+        None => backend.get_retention_policy().await?.ok_or_else(|| {
+            BackendError::Usage(
+                "No retention policy set. Pass --keep or set one with 'ow workers retention <n>'."
+                    .to_string(),
+            )
+        })?,
+    };
+
+    let deleted = backend.prune_deployments(name, keep).await?;
+
+    if deleted.is_empty() {
+        eprintln!("Nothing to prune for '{}'.", name.bold());
+        return Ok(());
+    }
+
+    let versions = deleted
+        .iter()
+        .map(|v| format!("v{}", v))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    eprintln!(
+        "{} {} deployment(s) of '{}' {}",
+        "Pruned".red(),
+        deleted.len(),
+        name.bold(),
+        format!("({})", versions).dimmed()
+    );
+
+    Ok(())
+}
+
+async fn cmd_tag<B: Backend>(
+    backend: &B,
+    name: &str,
+    version: i32,
+    tag: Option<String>,
+) -> Result<(), BackendError> {
+    let deployment = backend
+        .tag_deployment(name, version, tag.as_deref())
+        .await?;
+
+    match deployment.tag {
+        Some(tag) => eprintln!(
+            "{} v{} of '{}' as '{}'.",
+            "Tagged".green(),
+            version,
+            name.bold(),
+            tag.cyan()
+        ),
+        None => eprintln!(
+            "{} tag from v{} of '{}'.",
+            "Removed".yellow(),
+            version,
+            name.bold()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Next execution times of a cron expression, so a schedule that rarely
+/// fires where expected is caught before it ships
+fn print_upcoming(expr: &str, tz: cron::Tz) {
+    let Ok(schedule) = expr.parse::<cron::Schedule>() else {
+        return;
+    };
+
+    println!("{}", format!("Next runs of '{}' ({})", expr, tz).bold());
+
+    for at in schedule.upcoming(Utc::now(), 5, tz) {
+        println!("  {}", at.format("%Y-%m-%d %H:%M %a %:z"));
+    }
+}
+
+async fn cmd_exec<B: Backend>(
+    backend: &B,
+    name: &str,
+    event: WorkerEvent,
+    format: LogFormat,
+) -> Result<(), BackendError> {
+    eprintln!(
+        "{} Running {} handler of '{}'...",
+        "→".blue(),
+        event.kind(),
+        name.bold()
+    );
+
+    let result = backend.exec_worker(name, event).await?;
+    print_exec_result(&result, format);
+
+    if !result.success {
+        return Err(BackendError::Api(format!(
+            "Handler failed: {}",
+            result.error.as_deref().unwrap_or("unknown error")
+        )));
+    }
+
+    Ok(())
+}
+
+async fn cmd_runtime<B: Backend>(backend: &B, name: &str) -> Result<(), BackendError> {
+    let runtime = backend.worker_runtime(name).await?;
+    print_runtime(name, &runtime);
+    Ok(())
+}
+
+fn print_runtime(name: &str, runtime: &WorkerRuntime) {
+    println!("{}", name.bold());
+    println!("{}", "─".repeat(40));
+    println!("{:12} {}", "Isolates:".dimmed(), runtime.active_isolates);
+    println!(
+        "{:12} {}",
+        "In flight:".dimmed(),
+        runtime.in_flight_requests
+    );
+    println!(
+        "{:12} {:.1} MB",
+        "Memory:".dimmed(),
+        runtime.memory_bytes as f64 / (1024.0 * 1024.0)
+    );
+
+    println!();
+    println!("{}", "Recent cold starts".bold());
+
+    if runtime.cold_starts.is_empty() {
+        println!("  {}", "none".dimmed());
+    }
+
+    for cold_start in &runtime.cold_starts {
+        println!(
+            "  {}  {} ms",
+            cold_start
+                .at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            cold_start.duration_ms
+        );
+    }
+}
+
+fn print_exec_result(result: &ExecResult, format: LogFormat) {
+    for log in &result.logs {
+        match format {
+            LogFormat::Pretty => println!("  {}", logfmt::render(log, format)),
+            _ => println!("{}", logfmt::render(log, format)),
+        }
+    }
+
+    if result.success {
+        eprintln!("{} in {} ms", "Completed".green(), result.duration_ms);
+    } else {
+        eprintln!("{} after {} ms", "Failed".red(), result.duration_ms);
+    }
+}
+
+/// Headers set by the client or proxies for the original connection, not replayed
+const UNREPLAYED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "accept-encoding",
+];
+
+async fn load_captured_request<B: Backend>(
+    backend: &B,
+    name: &str,
+    log_id: Option<&str>,
+    from_file: Option<&Path>,
+) -> Result<CapturedRequest, BackendError> {
+    let Some(path) = from_file else {
+        let id = log_id
+            .ok_or_else(|| BackendError::Usage("Pass --log-id or --from-file".to_string()))?;
+        return backend.get_captured_request(name, id).await;
+    };
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| BackendError::Api(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| BackendError::Api(format!("Invalid request in {}: {}", path.display(), e)))
+}
+
+pub fn replay_headers(request: &CapturedRequest) -> Vec<(String, String)> {
+    request
+        .headers
+        .iter()
+        .filter(|(key, _)| !UNREPLAYED_HEADERS.contains(&key.to_ascii_lowercase().as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+async fn cmd_replay<B: Backend>(
+    backend: &B,
+    name: &str,
+    log_id: Option<&str>,
+    from_file: Option<&Path>,
+) -> Result<(), BackendError> {
+    let request = load_captured_request(backend, name, log_id, from_file).await?;
+    let worker = backend.get_worker(name).await?;
+
+    let base_url = worker_url(backend, &worker)
+        .ok_or_else(|| BackendError::Api(format!("No public URL known for worker '{}'", name)))?;
+
+    let url = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        request.path.trim_start_matches('/')
+    );
+
+    eprintln!(
+        "{} {} {}",
+        "→".cyan(),
+        request.method.to_uppercase().bold(),
+        url.cyan()
+    );
+
+    let response = invoke_worker(
+        &url,
+        &request.method,
+        &replay_headers(&request),
+        request.body.clone(),
+    )
+    .await?;
+
+    let status = format_status(response.status);
+
+    let was = request
+        .status
+        .map(|s| format!(" (was {})", s))
+        .unwrap_or_default();
+
+    eprintln!(
+        "{} {} in {} ms{}",
+        "←".cyan(),
+        status.bold(),
+        response.duration_ms,
+        was.dimmed()
+    );
+
+    if !response.body.is_empty() {
+        eprintln!();
+        println!("{}", response.body);
+
+        if response.truncated {
+            eprintln!("{}", "(body truncated)".dimmed());
+        }
+    }
+
+    if response.status >= 500 {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' answered {}",
+            name, response.status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Status code and reason, colored by class
+fn format_status(status: u16) -> colored::ColoredString {
+    let text = match reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+    {
+        Some(reason) => format!("{} {}", status, reason),
+        None => status.to_string(),
+    };
+
+    if status >= 500 {
+        text.red()
+    } else if status >= 400 {
+        text.yellow()
+    } else {
+        text.green()
+    }
+}
+
+/// `content-type: application/json` as a (name, value) pair
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    match header.split_once(':') {
+        Some((name, value))
+            if !name.trim().is_empty() && !name.trim().contains(char::is_whitespace) =>
+        {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected NAME:VALUE, got '{}'", header)),
+    }
+}
+
+/// Request given to `workers invoke`
+struct InvokeRequest {
+    method: Option<String>,
+    path: String,
+    data: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+/// Body given to `--data`: `@file`, `@-` for stdin, else the text itself
+fn read_data(data: &str) -> Result<String, BackendError> {
+    match data.strip_prefix('@') {
+        Some("-") => {
+            let mut content = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+                .map_err(|e| BackendError::Api(format!("Failed to read stdin: {}", e)))?;
+            Ok(content)
+        }
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| BackendError::Api(format!("Failed to read {}: {}", path, e))),
+        None => Ok(data.to_string()),
+    }
+}
+
+async fn cmd_invoke<B: Backend>(
+    backend: &B,
+    name: &str,
+    request: InvokeRequest,
+    record: Option<&Path>,
+) -> Result<(), BackendError> {
+    let body = request.data.as_deref().map(read_data).transpose()?;
+
+    let method = match (&request.method, &body) {
+        (Some(method), _) => method.to_uppercase(),
+        (None, Some(_)) => "POST".to_string(),
+        (None, None) => "GET".to_string(),
+    };
+
+    let worker = backend.get_worker(name).await?;
+
+    let base_url = worker_url(backend, &worker)
+        .ok_or_else(|| BackendError::Api(format!("No public URL known for worker '{}'", name)))?;
+
+    let url = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        request.path.trim_start_matches('/')
+    );
+
+    eprintln!("{} {} {}", "→".cyan(), method.bold(), url.cyan());
+
+    let result = invoke_worker(&url, &method, &request.headers, body.clone()).await;
+
+    if let Some(file) = record {
+        let (response, error) = match &result {
+            Ok(response) => (Some(response.clone()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let exchange = Exchange {
+            request: CapturedRequest {
+                id: None,
+                method: method.clone(),
+                path: request.path.clone(),
+                headers: request.headers.iter().cloned().collect(),
+                body,
+                status: None,
+                captured_at: Some(Utc::now()),
+            },
+            response,
+            error,
+        };
+
+        Session::new(name, vec![exchange]).save(file)?;
+    }
+
+    let response = result?;
+
+    eprintln!(
+        "{} {} in {} ms",
+        "←".cyan(),
+        format_status(response.status).bold(),
+        response.duration_ms
+    );
+
+    for (key, value) in &response.headers {
+        eprintln!("  {} {}", format!("{}:", key).dimmed(), value);
+    }
+
+    if !response.body.is_empty() {
+        eprintln!();
+        println!("{}", response.body);
+
+        if response.truncated {
+            eprintln!(
+                "{}",
+                format!("(body truncated to {} KB)", INVOKE_BODY_LIMIT / 1024).dimmed()
+            );
+        }
+    }
+
+    if let Some(file) = record {
+        eprintln!();
+        eprintln!(
+            "{} Recorded to {}",
+            "✓".green(),
+            file.display().to_string().bold()
+        );
+    }
+
+    if response.status >= 500 {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' answered {}",
+            name, response.status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Porcelain fields of `workers history list`
+fn version_fields(deployment: &Deployment, current: Option<i32>) -> Vec<String> {
+    vec![
+        deployment.version.to_string(),
+        (current == Some(deployment.version)).to_string(),
+        deployment.hash.clone(),
+        deployment.code_type.clone(),
+        porcelain::time(&deployment.deployed_at),
+        deployment.deployed_by.clone().unwrap_or_default(),
+        deployment.tag.clone().unwrap_or_default(),
+        deployment.message.clone().unwrap_or_default(),
+    ]
+}
+
+async fn cmd_history<B: Backend>(
+    backend: &B,
+    name: &str,
+    porcelain: bool,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+    let deployments = backend.list_deployments(name).await?;
+
+    if porcelain {
+        for deployment in &deployments {
+            porcelain::print(&version_fields(deployment, worker.current_version));
+        }
+
+        return Ok(());
+    }
+
+    if deployments.is_empty() {
+        eprintln!("Worker '{}' has no deployments.", name.bold());
+        return Ok(());
+    }
+
+    println!("{} {}", "History".bold(), format!("({})", name).dimmed());
+    println!("{}", "─".repeat(60));
+
+    for deployment in &deployments {
+        let live = worker.current_version == Some(deployment.version);
+        let version = format!("v{}", deployment.version);
+        let tag = deployment
+            .tag
+            .as_ref()
+            .map(|t| format!("[{}] ", t))
+            .unwrap_or_default();
+
+        println!(
+            "{} {:6} {}  {}  {:10} {:12} {}{}",
+            if live { "*".green() } else { " ".normal() },
+            if live {
+                version.green()
+            } else {
+                version.normal()
+            },
+            deployment
+                .hash
+                .get(..12)
+                .unwrap_or(&deployment.hash)
+                .dimmed(),
+            deployment.deployed_at.format("%Y-%m-%d %H:%M"),
+            deployment.code_type,
+            deployment.deployed_by.as_deref().unwrap_or("-"),
+            tag.cyan(),
+            deployment.message.as_deref().unwrap_or("").dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// File name of a deployment's code in an export, by code type
+fn code_file_name(code_type: &str) -> String {
+    let extension = match code_type {
+        "javascript" => "js",
+        "typescript" => "ts",
+        "wasm" => "wasm",
+        _ => "bin",
+    };
+
+    format!("worker.{}", extension)
+}
+
+async fn cmd_history_export<B: Backend>(
+    backend: &B,
+    name: &str,
+    out: &Path,
+) -> Result<(), BackendError> {
+    use sha2::{Digest, Sha256};
+
+    let deployments = backend.list_deployments(name).await?;
+
+    if deployments.is_empty() {
+        eprintln!("Worker '{}' has no deployments.", name.bold());
+        return Ok(());
+    }
+
+    let not_empty = std::fs::read_dir(out).is_ok_and(|mut entries| entries.next().is_some());
+
+    if not_empty {
+        return Err(BackendError::Api(format!(
+            "'{}' already exists and is not empty. Pick another --out.",
+            out.display()
+        )));
+    }
+
+    let write_error = |path: &Path, e: std::io::Error| {
+        BackendError::Api(format!("Failed to write {}: {}", path.display(), e))
+    };
+
+    for deployment in &deployments {
+        let code = backend
+            .get_deployment_code(name, deployment.version)
+            .await?;
+
+        let dir = out.join(format!("v{}", deployment.version));
+        std::fs::create_dir_all(&dir).map_err(|e| write_error(&dir, e))?;
+
+        let path = dir.join(code_file_name(&deployment.code_type));
+        std::fs::write(&path, &code).map_err(|e| write_error(&path, e))?;
+
+        let hash = hex::encode(Sha256::digest(&code));
+
+        if !hash.eq_ignore_ascii_case(&deployment.hash) {
+            eprintln!(
+                "{} v{} code hashes to {}, but the deployment records {}",
+                "Warning:".yellow(),
+                deployment.version,
+                hash,
+                deployment.hash
+            );
+        }
+
+        eprintln!("  {} {}", "✓".green(), path.display());
+    }
+
+    let metadata = serde_json::json!({
+        "worker": name,
+        "exportedAt": Utc::now(),
+        "deployments": deployments,
+    });
+
+    let path = out.join("metadata.json");
+    let content =
+        serde_json::to_string_pretty(&metadata).map_err(|e| BackendError::Api(e.to_string()))?;
+    std::fs::write(&path, content).map_err(|e| write_error(&path, e))?;
+
+    eprintln!(
+        "{} {} deployments of '{}' to {}",
+        "Exported".green(),
+        deployments.len(),
+        name.bold(),
+        out.display()
+    );
+
+    Ok(())
+}
+
+/// Value as saved in a bundle: secrets redacted (account references kept),
+/// bindings by resource name
+fn bundle_value(val: &EnvironmentValue, resources: &HashMap<String, String>) -> BundleValue {
+    let mut value = BundleValue {
+        key: val.key.clone(),
+        value_type: val.value_type.clone(),
+        value: None,
+        resource: None,
+    };
+
+    match val.value_type.as_str() {
+        "var" => value.value = Some(val.value.clone()),
+        "secret" if val.value.starts_with(ACCOUNT_SECRET_PREFIX) => {
+            value.value = Some(val.value.clone())
+        }
+        "secret" => {}
+        _ => value.resource = resources.get(&val.value).cloned(),
+    }
+
+    value
+}
+
+async fn cmd_export<B: Backend>(backend: &B, name: &str, out: &Path) -> Result<(), BackendError> {
+    use sha2::{Digest, Sha256};
+
+    if out.exists() {
+        return Err(BackendError::Api(format!(
+            "'{}' already exists. Pick another --out.",
+            out.display()
+        )));
+    }
+
+    let worker = backend.get_worker(name).await?;
+
+    let deployment = backend.get_current_deployment(name).await?.ok_or_else(|| {
+        BackendError::Api(format!("Worker '{}' has no deployment to export", name))
+    })?;
+
+    let code = backend
+        .get_deployment_code(name, deployment.version)
+        .await?;
+
+    let environment = match &worker.environment {
+        Some(env_ref) => Some(backend.get_environment(&env_ref.name).await?),
+        None => None,
+    };
+
+    let overrides = backend.list_worker_values(name).await?;
+
+    let all_values: Vec<EnvironmentValue> = environment
+        .iter()
+        .flat_map(|e| e.values.iter().cloned())
+        .chain(overrides.iter().cloned())
+        .collect();
+    let resources = resource_names(backend, &all_values).await?;
+
+    let bundle = Bundle {
+        format: BUNDLE_FORMAT,
+        exported_at: Utc::now(),
+        worker: BundleWorker {
+            name: worker.name.clone(),
+            description: worker.description.clone(),
+            code_type: deployment.code_type.clone(),
+            version: deployment.version,
+            hash: hex::encode(Sha256::digest(&code)),
+            code: code_file_name(&deployment.code_type),
+        },
+        environment: environment.map(|env| BundleEnvironment {
+            values: env
+                .values
+                .iter()
+                .map(|v| bundle_value(v, &resources))
+                .collect(),
+            name: env.name,
+            description: env.description,
+        }),
+        worker_values: overrides
+            .iter()
+            .map(|v| bundle_value(v, &resources))
+            .collect(),
+        urls: backend.list_worker_urls(name).await?,
+    };
+
+    bundle
+        .write(out, &code)
+        .map_err(|e| BackendError::Api(format!("Failed to write {}: {}", out.display(), e)))?;
+
+    let redacted = bundle
+        .environment
+        .iter()
+        .flat_map(|e| &e.values)
+        .chain(&bundle.worker_values)
+        .filter(|v| v.is_redacted())
+        .count();
+
+    eprintln!(
+        "{} '{}' v{} to {}",
+        "Exported".green(),
+        name.bold(),
+        deployment.version,
+        out.display()
+    );
+
+    if redacted > 0 {
+        eprintln!(
+            "  {} secret(s) redacted, set them again after importing.",
+            redacted
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_import<B: Backend>(
+    backend: &B,
+    file: &Path,
+    name: Option<String>,
+    env: Option<String>,
+) -> Result<(), BackendError> {
+    let (bundle, code) = Bundle::read(file)
+        .map_err(|e| BackendError::Api(format!("Failed to read {}: {}", file.display(), e)))?;
+
+    let name = name.unwrap_or_else(|| bundle.worker.name.clone());
+
+    if backend.get_worker(&name).await.is_ok() {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' already exists. Import under another name with --name.",
+            name
+        )));
+    }
+
+    let env_name = bundle
+        .environment
+        .as_ref()
+        .map(|e| env.unwrap_or_else(|| e.name.clone()));
+
+    let env_taken = match &env_name {
+        Some(env_name) => backend.get_environment(env_name).await.is_ok(),
+        None => false,
+    };
+
+    if env_taken {
+        return Err(BackendError::Api(format!(
+            "Environment '{}' already exists. Pick another name with --env.",
+            env_name.unwrap_or_default()
+        )));
+    }
+
+    // Commands left for the user: redacted secrets and unmatched bindings
+    let mut todo: Vec<String> = Vec::new();
+
+    let environment = match (&bundle.environment, &env_name) {
+        (Some(exported), Some(env_name)) => {
+            let input = CreateEnvironmentInput {
+                name: env_name.clone(),
+                desc: exported.description.clone(),
+            };
+            backend.create_environment(input).await?;
+
+            let mut values = Vec::new();
+
+            for val in &exported.values {
+                let value = match (&val.value, &val.resource) {
+                    (Some(value), _) => value.clone(),
+                    (None, Some(resource)) => {
+                        match super::env::resource_id(backend, &val.value_type, resource).await {
+                            Ok(id) => id,
+                            Err(BackendError::NotFound(_)) => {
+                                todo.push(format!(
+                                    "ow env bind {} {} <{}> --type {}   ('{}' not found)",
+                                    env_name, val.key, val.value_type, val.value_type, resource
+                                ));
+                                continue;
+                            }
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    (None, None) => {
+                        todo.push(format!("ow env set {} {} --secret", env_name, val.key));
+                        continue;
+                    }
+                };
+
+                values.push(EnvironmentValueInput {
+                    id: None,
+                    key: val.key.clone(),
+                    value: Some(value),
+                    value_type: val.value_type.clone(),
+                });
+            }
+
+            let input = UpdateEnvironmentInput {
+                name: None,
+                values: Some(values),
+                protected: None,
+            };
+
+            Some(backend.update_environment(env_name, input).await?)
+        }
+        _ => None,
+    };
+
+    let language = match bundle.worker.code_type.as_str() {
+        "typescript" => "typescript",
+        _ => "javascript",
+    };
+
+    let input = CreateWorkerInput {
+        name: name.clone(),
+        description: bundle.worker.description.clone(),
+        language: language.to_string(),
+        tags: BTreeMap::new(),
+    };
+    let worker = backend.create_worker(input).await?;
+
+    let input = DeployInput {
+        code,
+        code_type: bundle.worker.code_type.clone(),
+        message: Some(format!(
+            "Imported from {} (v{} of '{}')",
+            file.display(),
+            bundle.worker.version,
+            bundle.worker.name
+        )),
+        hash: Some(bundle.worker.hash.clone()),
+        authorization: Default::default(),
+    };
+    let deployment = backend.deploy_worker(&name, input).await?;
+
+    if let Some(environment) = &environment {
+        backend
+            .link_worker_environment(&worker.id, &environment.id)
+            .await?;
+    }
+
+    for val in &bundle.worker_values {
+        match &val.value {
+            Some(value) => {
+                backend
+                    .set_worker_value(&name, &val.key, value, &val.value_type)
+                    .await?;
+            }
+            None => todo.push(format!("ow workers set-var {} {} --secret", name, val.key)),
+        }
+    }
+
+    for url in &bundle.urls {
+        if let Err(e) = backend.add_worker_url(&name, url).await {
+            eprintln!("{} Could not add {}: {}", "Warning:".yellow(), url, e);
+        }
+    }
+
+    eprintln!(
+        "{} Worker '{}' imported and deployed (v{}).",
+        "Imported".green(),
+        name.bold(),
+        deployment.version
+    );
+
+    if let Some(environment) = &environment {
+        eprintln!(
+            "  Linked to new environment '{}' ({} values).",
+            environment.name.bold(),
+            environment.values.len()
+        );
+    }
+
+    if !todo.is_empty() {
+        eprintln!();
+        eprintln!("{}", "Still to set:".yellow().bold());
+
+        for command in &todo {
+            eprintln!("  {}", command.cyan());
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_retention<B: Backend>(
+    backend: &B,
+    keep: Option<u32>,
+    off: bool,
+) -> Result<(), BackendError> {
+    if keep.is_some() || off {
+        backend.set_retention_policy(keep).await?;
+    }
+
+    match backend.get_retention_policy().await? {
+        Some(keep) => println!(
+            "Keeping the {} most recent deployments per worker (plus active and tagged).",
+            keep.to_string().bold()
+        ),
+        None => println!("Keeping all deployments (no retention policy)."),
+    }
+
+    Ok(())
+}
+
+async fn cmd_urls<B: Backend>(
+    backend: &B,
+    name: &str,
+    add: &[WorkerUrl],
+    remove: &[WorkerUrl],
+) -> Result<(), BackendError> {
+    for url in remove {
+        backend.remove_worker_url(name, url).await?;
+        eprintln!("{} {}", "Removed".yellow(), url);
+    }
+
+    for url in add {
+        backend.add_worker_url(name, url).await?;
+        eprintln!("{} {}", "Added".green(), url);
+    }
+
+    if !add.is_empty() || !remove.is_empty() {
+        eprintln!();
+    }
+
+    let urls = backend.list_worker_urls(name).await?;
+
+    if urls.is_empty() {
+        eprintln!("No extra URLs for worker '{}'.", name);
+        return Ok(());
+    }
+
+    println!("{} {}", "URLs for".bold(), name.bold());
+    println!("{}", "─".repeat(60));
+
+    for url in &urls {
+        match url {
+            WorkerUrl::Domain { name } => {
+                println!("  {:8} https://{}", "domain".dimmed(), name)
+            }
+            WorkerUrl::Route { pattern } => println!("  {:8} {}", "route".dimmed(), pattern),
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_share<B: Backend>(
+    backend: &B,
+    name: &str,
+    with: Option<&str>,
+    role: &str,
+    revoke: Option<&str>,
+) -> Result<(), BackendError> {
+    if let Some(username) = revoke {
+        backend.unshare_worker(name, username).await?;
+        eprintln!(
+            "{} '{}' can no longer access '{}'.",
+            "Revoked".yellow(),
+            username.bold(),
+            name.bold()
+        );
+        eprintln!();
+    }
+
+    if let Some(username) = with {
+        backend.share_worker(name, username, role).await?;
+        eprintln!(
+            "{} '{}' with '{}' as {}.",
+            "Shared".green(),
+            name.bold(),
+            username.bold(),
+            role.cyan()
+        );
+        eprintln!();
+    }
+
+    let shares = backend.list_worker_shares(name).await?;
+
+    if shares.is_empty() {
+        eprintln!("Worker '{}' is not shared with anyone.", name);
+        return Ok(());
+    }
+
+    println!("{} {}", "Shared".bold(), name.bold());
+    println!("{}", "─".repeat(60));
+
+    for share in &shares {
+        println!(
+            "  {:24} {:7} {}",
+            share.username.bold(),
+            share.role.cyan(),
+            format!("since {}", share.created_at.format("%Y-%m-%d")).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_bench<B: Backend>(
+    backend: &B,
+    name: &str,
+    rps: Option<u32>,
+    duration: &str,
+    concurrency: usize,
+    path: &str,
+    record: Option<&Path>,
+) -> Result<(), BackendError> {
+    let duration = super::parse_duration(duration).map_err(BackendError::Api)?;
+    let worker = backend.get_worker(name).await?;
+
+    let base_url = worker_url(backend, &worker)
+        .ok_or_else(|| BackendError::Api(format!("No public URL known for worker '{}'", name)))?;
+
+    let url = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+
+    let rate = rps
+        .map(|r| format!("{} req/s", r))
+        .unwrap_or_else(|| "max rate".to_string());
+
+    eprintln!(
+        "{} Benchmarking {} for {}s ({}, {} concurrent)",
+        "→".cyan(),
+        url.cyan(),
+        duration,
+        rate,
+        concurrency
+    );
+    eprintln!();
+
+    let options = super::bench::BenchOptions {
+        rps,
+        duration: std::time::Duration::from_secs(duration.max(1) as u64),
+        concurrency,
+        record: record.is_some(),
+    };
+
+    let report = super::bench::run(&url, &options).await?;
+    super::bench::print_report(&report);
+
+    if let Some(file) = record {
+        let request = CapturedRequest {
+            id: None,
+            method: "GET".to_string(),
+            path: path.to_string(),
+            headers: BTreeMap::new(),
+            body: None,
+            status: None,
+            captured_at: None,
+        };
+
+        let exchanges = report
+            .recorded
+            .into_iter()
+            .map(|recorded| {
+                let (response, error) = match recorded {
+                    Ok(response) => (Some(response), None),
+                    Err(e) => (None, Some(e)),
+                };
+
+                Exchange {
+                    request: request.clone(),
+                    response,
+                    error,
+                }
+            })
+            .collect();
+
+        let session = Session::new(name, exchanges);
+        session.save(file)?;
+
+        eprintln!();
+        eprintln!(
+            "{} Recorded {} requests to {}",
+            "✓".green(),
+            session.exchanges.len(),
+            file.display().to_string().bold()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_session_replay<B: Backend>(
+    backend: &B,
+    name: &str,
+    file: &Path,
+) -> Result<(), BackendError> {
+    let session = Session::load(file)?;
+    let worker = backend.get_worker(name).await?;
+
+    let base_url = worker_url(backend, &worker)
+        .ok_or_else(|| BackendError::Api(format!("No public URL known for worker '{}'", name)))?;
+
+    eprintln!(
+        "{} Replaying {} requests recorded against {} on {}",
+        "→".cyan(),
+        session.exchanges.len(),
+        session.worker.bold(),
+        session.recorded_at.format("%Y-%m-%d %H:%M")
+    );
+    eprintln!();
+
+    let differing = session::replay(&base_url, &session).await;
+
+    if differing > 0 {
+        return Err(BackendError::Api(format!(
+            "{} responses differ from {}",
+            differing,
+            file.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Gate deploys to protected workers (or workers linked to a protected environment).
+///
+/// A token is passed along and consumed by the backend with the deploy. Without
+/// one, an interactive session must type the worker name to confirm;
+/// non-interactive sessions are refused.
+pub async fn authorize_deploy<B: Backend>(
+    backend: &B,
+    name: &str,
+    approve_token: Option<&str>,
+    interactive: bool,
+) -> Result<DeployAuthorization, BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    if !worker.is_protected() {
+        return Ok(DeployAuthorization::default());
+    }
+
+    if let Some(token) = approve_token {
+        return Ok(DeployAuthorization {
+            approve_token: Some(token.to_string()),
+            confirmed: false,
+        });
+    }
+
+    if !interactive {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' is protected. Pass --approve-token (see 'ow workers approve').",
+            name
+        )));
+    }
+
+    let reason = match &worker.environment {
+        Some(env) if !worker.protected => {
+            format!("is linked to protected environment '{}'", env.name)
+        }
+        _ => "is protected".to_string(),
+    };
+
+    let confirmed = super::confirm_typed(
+        &format!(
+            "{} Worker '{}' {}.",
+            "Warning:".yellow().bold(),
+            name.bold(),
+            reason
+        ),
+        name,
+    )
+    .map_err(|e| BackendError::Api(format!("Failed to read input: {}", e)))?;
+
+    if !confirmed {
+        return Err(BackendError::Api("Deploy cancelled.".to_string()));
+    }
+
+    Ok(DeployAuthorization {
+        approve_token: None,
+        confirmed: true,
+    })
+}
+
+/// Asset handling for `workers upload`
+#[derive(Debug, Clone, Default)]
+pub struct UploadOptions {
+    pub assets: Vec<AssetSource>,
+    pub policy: UploadPolicy,
+    pub fingerprint: bool,
+    /// Storage to bind as ASSETS when the linked environment has none
+    pub auto_bind: Option<String>,
+    /// Number of uploaded assets to check against the local files
+    pub verify: Option<usize>,
+}
+
+/// `_routes.json` at the root of an upload folder, or anywhere in an archive
+fn read_routes_file(path: &Path) -> Result<Option<String>, BackendError> {
+    let read_error = |e: &dyn std::fmt::Display| {
+        BackendError::Api(format!("Failed to read {}: {}", ROUTES_FILE, e))
+    };
+
+    if path.is_dir() {
+        let file = path.join(ROUTES_FILE);
+
+        if !file.is_file() {
+            return Ok(None);
+        }
+
+        return std::fs::read_to_string(&file)
+            .map(Some)
+            .map_err(|e| read_error(&e));
+    }
+
+    let Ok(file) = std::fs::File::open(path) else {
+        // Missing or unreadable archives are reported by the upload itself
+        return Ok(None);
+    };
+
+    let Ok(mut archive) = zip::ZipArchive::new(file) else {
+        return Ok(None);
+    };
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| read_error(&e))?;
+        let name = entry.name().replace('\\', "/");
+
+        if name == ROUTES_FILE || name.ends_with(&format!("/{}", ROUTES_FILE)) {
+            let mut content = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut content).map_err(|e| read_error(&e))?;
+            return Ok(Some(content));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Key of the binding created by `--auto-bind`
+const ASSETS_BINDING: &str = "ASSETS";
+
+/// Make sure the worker's environment has an assets binding before anything
+/// is zipped or uploaded. When it is missing, bind `auto_bind`, or ask for a
+/// storage in an interactive session.
+async fn ensure_assets_binding<B: Backend>(
+    backend: &B,
+    name: &str,
+    auto_bind: Option<&str>,
+    interactive: bool,
+) -> Result<(), BackendError> {
+    let worker = backend.get_worker(name).await?;
+
+    let Some(linked) = &worker.environment else {
+        return Err(BackendError::Api(format!(
+            "Worker '{}' has no environment, so no ASSETS binding. \
+             Link one first: ow workers link {} <env>",
+            name, name
+        )));
+    };
+
+    let environment = backend.get_environment(&linked.name).await?;
+
+    if environment.values.iter().any(|v| v.value_type == "assets") {
+        return Ok(());
+    }
+
+    let storage = match auto_bind {
+        Some(storage) => storage.to_string(),
+        None if interactive => {
+            let names: Vec<String> = backend
+                .list_storage()
+                .await?
+                .into_iter()
+                .map(|s| s.name)
+                .collect();
+
+            if names.is_empty() {
+                return Err(BackendError::Api(format!(
+                    "Environment '{}' has no ASSETS binding and there is no storage to bind. \
+                     Create one with 'ow storage create'.",
+                    environment.name
+                )));
+            }
+
+            eprintln!(
+                "{} Environment '{}' has no ASSETS binding. Storage: {}",
+                "Warning:".yellow(),
+                environment.name.bold(),
+                names.join(", ")
+            );
+            eprint!("Storage to bind as {} (empty to cancel): ", ASSETS_BINDING);
+            std::io::stderr().flush().ok();
+
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_line(&mut buf)
+                .map_err(|e| BackendError::Api(format!("Failed to read input: {}", e)))?;
+
+            match buf.trim() {
+                "" => return Err(BackendError::Api("Upload cancelled.".to_string())),
+                storage => storage.to_string(),
+            }
+        }
+        None => {
+            return Err(BackendError::Api(format!(
+                "Environment '{}' has no ASSETS binding. \
+                 Pass --auto-bind <storage> to create it.",
+                environment.name
+            )));
+        }
+    };
+
+    let storage = backend.get_storage(&storage).await?;
+
+    // Keep an existing non-assets value under the same key untouched
+    if environment.values.iter().any(|v| v.key == ASSETS_BINDING) {
+        return Err(BackendError::Api(format!(
+            "Environment '{}' already uses the key {} for something else. \
+             Bind the storage yourself: ow env bind {} <KEY> {} --type assets",
+            environment.name, ASSETS_BINDING, environment.name, storage.name
+        )));
+    }
+
+    let input = UpdateEnvironmentInput {
+        name: None,
+        values: Some(vec![EnvironmentValueInput {
+            id: None,
+            key: ASSETS_BINDING.to_string(),
+            value: Some(storage.id),
+            value_type: "assets".to_string(),
+        }]),
+        protected: None,
+    };
+
+    backend.update_environment(&environment.name, input).await?;
+
+    eprintln!(
+        "{} {} to storage '{}' in environment '{}'",
+        "Bound".green(),
+        ASSETS_BINDING.bold(),
+        storage.name,
+        environment.name.bold()
+    );
+
+    Ok(())
+}
+
+/// Which directory entries an upload picks up
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UploadPolicy {
+    /// Follow symbolic links (directory cycles are detected and skipped)
+    pub follow_symlinks: bool,
+
+    /// Include dotfiles and dot-directories (`.well-known` is always included)
+    pub include_hidden: bool,
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    let name = name.to_string_lossy();
+    name.starts_with('.') && name != ".well-known"
+}
+
+/// Files under `dir` allowed by `policy`, sorted. Directories in `skip` are
+/// not descended into.
+fn walk_files(
+    dir: &Path,
+    policy: UploadPolicy,
+    skip: &[PathBuf],
+) -> Result<Vec<PathBuf>, BackendError> {
+    let skip: Vec<PathBuf> = skip.iter().filter_map(|d| d.canonicalize().ok()).collect();
+    let mut files = Vec::new();
+    let mut ancestors = HashSet::new();
+
+    walk_dir(dir, dir, policy, &skip, &mut ancestors, &mut files)?;
+    files.sort();
+
+    Ok(files)
+}
+
+fn walk_dir(
+    dir: &Path,
+    base: &Path,
+    policy: UploadPolicy,
+    skip: &[PathBuf],
+    ancestors: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), BackendError> {
+    // Canonical paths of the directories being walked, to break symlink cycles
+    let canonical = dir.canonicalize().map_err(|e| {
+        BackendError::Api(format!(
+            "Failed to read directory '{}': {}",
+            dir.display(),
+            e
+        ))
+    })?;
+
+    if dir != base && skip.contains(&canonical) {
+        return Ok(());
+    }
+
+    if !ancestors.insert(canonical.clone()) {
+        eprintln!(
+            "  {} {} {}",
+            "⎿".dimmed(),
+            dir.display(),
+            "(skipped, symlink cycle)".dimmed()
+        );
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir).map_err(|e| {
+        BackendError::Api(format!(
+            "Failed to read directory '{}': {}",
+            dir.display(),
+            e
+        ))
+    })? {
+        let entry = entry.map_err(|e| BackendError::Api(format!("Failed to read entry: {}", e)))?;
+        let path = entry.path();
+
+        if !policy.include_hidden && is_hidden(&entry.file_name()) {
+            continue;
+        }
+
+        let file_type = entry
+            .file_type()
+            .map_err(|e| BackendError::Api(format!("Failed to read entry: {}", e)))?;
+
+        let is_dir = if file_type.is_symlink() {
+            if !policy.follow_symlinks {
+                eprintln!(
+                    "  {} {} {}",
+                    "⎿".dimmed(),
+                    path.display(),
+                    "(skipped, symlink; use --follow-symlinks)".dimmed()
+                );
+                continue;
+            }
+
+            match std::fs::metadata(&path) {
+                Ok(metadata) => metadata.is_dir(),
+                Err(_) => {
+                    eprintln!(
+                        "  {} {} {}",
+                        "⎿".dimmed(),
+                        path.display(),
+                        "(skipped, broken symlink)".dimmed()
+                    );
+                    continue;
+                }
+            }
+        } else {
+            file_type.is_dir()
+        };
+
+        if is_dir {
+            walk_dir(&path, base, policy, skip, ancestors, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    ancestors.remove(&canonical);
+
+    Ok(())
+}
+
+/// A local directory uploaded as assets, mounted at `mount` in the bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetSource {
+    pub dir: PathBuf,
+    pub mount: String,
+}
+
+/// Parse `DIR` or `DIR:/MOUNT` (e.g. `./static:/static`). A one-letter prefix
+/// before `:/` is a Windows drive (`C:/site/public`), not a directory.
+fn parse_asset_source(s: &str) -> Result<AssetSource, String> {
+    let is_drive = |dir: &str| dir.len() == 1 && dir.chars().all(|c| c.is_ascii_alphabetic());
+
+    let (dir, mount) = match s.rsplit_once(":/") {
+        Some((dir, mount)) if !is_drive(dir) => (dir, mount),
+        _ => (s, ""),
+    };
+
+    if dir.is_empty() {
+        return Err(format!(
+            "Invalid asset source '{}'. Use DIR or DIR:/MOUNT",
+            s
+        ));
+    }
+
+    Ok(AssetSource {
+        dir: PathBuf::from(dir),
+        mount: mount.trim_matches('/').to_string(),
+    })
+}
+
+/// Parse a route pattern (`/api/*`) or a bare hostname (`api.example.com`)
+fn parse_worker_url(s: &str) -> Result<WorkerUrl, String> {
+    if s.starts_with('/') {
+        return Ok(WorkerUrl::Route {
+            pattern: s.to_string(),
+        });
+    }
+
+    let name = s.trim_end_matches('.').to_lowercase();
+    let valid = name.contains('.')
+        && name.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+    if !valid {
+        return Err(format!(
+            "Invalid URL '{}'. Use a hostname (api.example.com) or a route pattern (/api/*)",
+            s
+        ));
+    }
+
+    Ok(WorkerUrl::Domain { name })
+}
+
+/// Parse a `--message` argument as a JSON value
+fn parse_json_message(s: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(s).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Collect assets from each source, keyed by mount point + relative path
+fn collect_assets(
+    sources: &[AssetSource],
+    policy: UploadPolicy,
+) -> Result<Vec<Asset>, BackendError> {
+    let mut assets: Vec<Asset> = Vec::new();
+    let mut owners: HashMap<String, PathBuf> = HashMap::new();
+
+    for source in sources {
+        if !source.dir.is_dir() {
+            return Err(BackendError::Api(format!(
+                "Asset directory '{}' not found",
+                source.dir.display()
+            )));
+        }
+
+        for path in walk_files(&source.dir, policy, &[])? {
+            let relative = path
+                .strip_prefix(&source.dir)
+                .map_err(|e| BackendError::Api(format!("Path error: {}", e)))?
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let key = if source.mount.is_empty() {
+                relative
+            } else {
+                format!("{}/{}", source.mount, relative)
+            };
+
+            if let Some(other) = owners.insert(key.clone(), source.dir.clone()) {
+                return Err(BackendError::Api(format!(
+                    "Asset '{}' is provided by both '{}' and '{}'",
+                    key,
+                    other.display(),
+                    source.dir.display()
+                )));
+            }
+
+            let content_type = s3::content_type(&key, &path);
+
+            let asset = Asset::from_file(key, path.clone(), content_type).map_err(|e| {
+                BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
+            })?;
+
+            assets.push(asset);
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Apply ow.toml `[assets.rules]`: drop excluded assets and override
+/// content types and cache headers
+fn apply_asset_rules(assets: Vec<Asset>, rules: &AssetsSection) -> Vec<Asset> {
+    assets
+        .into_iter()
+        .filter_map(|mut asset| {
+            let options = rules.resolve(&asset.key);
+
+            if options.exclude {
+                return None;
+            }
+
+            if let Some(content_type) = options.content_type {
+                asset.content_type = content_type;
+            }
+
+            asset.cache_control = options.cache_control;
+            Some(asset)
+        })
+        .collect()
+}
+
+/// Asset key of the original -> fingerprinted name mapping
+const FINGERPRINT_MANIFEST: &str = "asset-manifest.json";
+
+/// Insert the first 8 hex chars of the hash before the extension:
+/// `css/app.css` -> `css/app.1a2b3c4d.css`
+fn fingerprint_key(key: &str, sha256_hex: &str) -> String {
+    let hash = &sha256_hex[..8.min(sha256_hex.len())];
+
+    let (dir, file) = match key.rsplit_once('/') {
+        Some((dir, file)) => (Some(dir), file),
+        None => (None, key),
+    };
+
+    let name = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, hash, ext),
+        _ => format!("{}.{}", file, hash),
+    };
+
+    match dir {
+        Some(dir) => format!("{}/{}", dir, name),
+        None => name,
+    }
+}
+
+/// HTML pages and `.well-known/` are addressed by URL and keep their names
+fn should_fingerprint(key: &str) -> bool {
+    let lower = key.to_lowercase();
+
+    !(lower.ends_with(".html") || lower.ends_with(".htm") || lower.starts_with(".well-known/"))
+}
+
+/// Rename assets in place and write the manifest to a temp file.
+/// Returns the number of renamed assets and the manifest asset.
+fn fingerprint_assets(assets: &mut [Asset]) -> Result<(usize, Asset), BackendError> {
+    if assets.iter().any(|a| a.key == FINGERPRINT_MANIFEST) {
+        return Err(BackendError::Api(format!(
+            "An asset named '{}' already exists; it is reserved for --fingerprint",
+            FINGERPRINT_MANIFEST
+        )));
+    }
+
+    let mut mapping = BTreeMap::new();
+
+    for asset in assets.iter_mut().filter(|a| should_fingerprint(&a.key)) {
+        let hashed = fingerprint_key(&asset.key, &asset.sha256_hex);
+        mapping.insert(std::mem::replace(&mut asset.key, hashed.clone()), hashed);
+    }
+
+    let json =
+        serde_json::to_string_pretty(&mapping).map_err(|e| BackendError::Api(e.to_string()))?;
+
+    let path = std::env::temp_dir().join(format!(
+        "ow-{}-{}",
+        std::process::id(),
+        FINGERPRINT_MANIFEST
+    ));
+
+    std::fs::write(&path, json)
+        .map_err(|e| BackendError::Api(format!("Failed to write asset manifest: {}", e)))?;
+
+    let manifest = Asset::from_file(
+        FINGERPRINT_MANIFEST.to_string(),
+        path,
+        "application/json".to_string(),
+    )
+    .map_err(|e| BackendError::Api(format!("Failed to read asset manifest: {}", e)))?;
+
+    Ok((mapping.len(), manifest))
+}
+
+fn create_zip_from_folder(
+    folder: &Path,
+    policy: UploadPolicy,
+    assets: &[AssetSource],
+) -> Result<Vec<u8>, BackendError> {
+    use std::io::Cursor;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    let mut buffer = Cursor::new(Vec::new());
+    let mut zip = ZipWriter::new(&mut buffer);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    // Skip asset directories — assets are uploaded separately via presigned URLs
+    let mut skip: Vec<PathBuf> = assets.iter().map(|a| a.dir.clone()).collect();
+    skip.push(folder.join("assets"));
+
+    for path in walk_files(folder, policy, &skip)? {
+        let relative = path
+            .strip_prefix(folder)
+            .map_err(|e| BackendError::Api(format!("Path error: {}", e)))?;
+
+        let content = std::fs::read(&path).map_err(|e| {
+            BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
+        })?;
+
+        let relative_path = relative.to_string_lossy().replace('\\', "/");
+        zip.start_file(relative_path, options)
+            .map_err(|e| BackendError::Api(format!("Zip error: {}", e)))?;
+
+        zip.write_all(&content)
+            .map_err(|e| BackendError::Api(format!("Zip write error: {}", e)))?;
+    }
+
+    zip.finish()
+        .map_err(|e| BackendError::Api(format!("Zip finish error: {}", e)))?;
+
+    Ok(buffer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_effective_values() {
+        use crate::backend::{
+            CreateEnvironmentInput, EnvironmentValueInput, UpdateEnvironmentInput,
+        };
+
+        let backend = MockBackend::new();
+
+        backend
+            .create_worker(CreateWorkerInput {
+                name: "my-api".to_string(),
+                description: None,
+                language: "javascript".to_string(),
+                tags: BTreeMap::new(),
+            })
+            .await
+            .unwrap();
+
+        // Not linked yet
+        let worker = backend.get_worker("my-api").await.unwrap();
+        assert!(
+            effective_values(&backend, &worker)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        backend
+            .create_environment(CreateEnvironmentInput {
+                name: "prod".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let value = |key: &str, value: &str, value_type: &str| EnvironmentValueInput {
+            id: None,
+            key: key.to_string(),
+            value: Some(value.to_string()),
+            value_type: value_type.to_string(),
+        };
+
+        let input = UpdateEnvironmentInput {
+            name: None,
+            values: Some(vec![
+                value("DEBUG", "false", "var"),
+                value("API_KEY", "sk_live_123", "secret"),
+            ]),
+            protected: None,
+        };
+        backend.update_environment("prod", input).await.unwrap();
+
+        WorkersCommand::Link {
+            name: "my-api".to_string(),
+            env: "prod".to_string(),
+            diff: false,
+            yes: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("my-api").await.unwrap();
+        let values = effective_values(&backend, &worker).await.unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].key, "API_KEY");
+        assert_eq!(values[0].display, "****");
+        assert_eq!(values[1].key, "DEBUG");
+        assert_eq!(values[1].display, "false");
+        assert!(values.iter().all(|v| v.source == "prod"));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_assets_binding() {
+        use crate::backend::{CreateEnvironmentInput, CreateStorageInput};
+
+        let backend = MockBackend::new().with_worker("site", None);
+
+        // No environment linked
+        let result = ensure_assets_binding(&backend, "site", Some("assets"), false).await;
+        assert!(result.is_err());
+
+        backend
+            .create_environment(CreateEnvironmentInput {
+                name: "prod".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        WorkersCommand::Link {
+            name: "site".to_string(),
+            env: "prod".to_string(),
+            diff: false,
+            yes: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        // Non-interactive without --auto-bind
+        let result = ensure_assets_binding(&backend, "site", None, false).await;
+        assert!(result.is_err());
+
+        let storage = backend
+            .create_storage(CreateStorageInput {
+                name: "assets".to_string(),
+                desc: None,
+                provider: "platform".to_string(),
+                bucket: None,
+                prefix: None,
+                access_key_id: None,
+                secret_access_key: None,
+                endpoint: None,
+                region: None,
+                public_url: None,
+            })
+            .await
+            .unwrap();
+
+        ensure_assets_binding(&backend, "site", Some("assets"), false)
+            .await
+            .unwrap();
+
+        let environment = backend.get_environment("prod").await.unwrap();
+        assert_eq!(environment.values.len(), 1);
+        assert_eq!(environment.values[0].key, "ASSETS");
+        assert_eq!(environment.values[0].value, storage.id);
+
+        // Already bound: nothing to do, even without --auto-bind
+        ensure_assets_binding(&backend, "site", None, false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_worker_values_override_environment() {
+        use crate::backend::{
+            CreateEnvironmentInput, EnvironmentValueInput, UpdateEnvironmentInput,
+        };
+
+        let backend = MockBackend::new().with_worker("my-api", None);
+
+        backend
+            .create_environment(CreateEnvironmentInput {
+                name: "prod".to_string(),
+                desc: None,
+            })
+            .await
+            .unwrap();
+
+        let input = UpdateEnvironmentInput {
+            name: None,
+            values: Some(vec![EnvironmentValueInput {
+                id: None,
+                key: "DEBUG".to_string(),
+                value: Some("false".to_string()),
+                value_type: "var".to_string(),
+            }]),
+            protected: None,
+        };
+        backend.update_environment("prod", input).await.unwrap();
+
+        WorkersCommand::Link {
+            name: "my-api".to_string(),
+            env: "prod".to_string(),
+            diff: false,
+            yes: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        WorkersCommand::SetVar {
+            name: "my-api".to_string(),
+            key: "DEBUG".to_string(),
+            value: Some("true".to_string()),
+            secret: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("my-api").await.unwrap();
+        let values = effective_values(&backend, &worker).await.unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].display, "true");
+        assert_eq!(values[0].source, "worker, overrides prod");
+
+        WorkersCommand::UnsetVar {
+            name: "my-api".to_string(),
+            key: "DEBUG".to_string(),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let values = effective_values(&backend, &worker).await.unwrap();
+        assert_eq!(values[0].display, "false");
+        assert_eq!(values[0].source, "prod");
+
+        let result = WorkersCommand::UnsetVar {
+            name: "my-api".to_string(),
+            key: "DEBUG".to_string(),
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+
+        // Missing value for a plain variable is an error, not a prompt
+        let result = WorkersCommand::SetVar {
+            name: "my-api".to_string(),
+            key: "DEBUG".to_string(),
+            value: None,
+            secret: false,
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::Usage(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_empty() {
+        let backend = MockBackend::new();
+
+        let result = WorkersCommand::List {
+            long: false,
+            ids: false,
+            porcelain: false,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_with_workers() {
+        let backend = MockBackend::new()
+            .with_worker("api", Some("API worker"))
+            .with_deployed_worker("web", 3);
+
+        let result = WorkersCommand::List {
+            long: false,
+            ids: false,
+            porcelain: false,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_long() {
+        let backend = MockBackend::new()
+            .with_worker("api", Some("API worker"))
+            .with_deployed_worker("web", 3);
+
+        let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
+        writeln!(temp_file, "export default {{}}").unwrap();
+
+        WorkersCommand::Deploy {
+            name: Some("api".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: Some("Initial deploy".to_string()),
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let result = WorkersCommand::List {
+            long: true,
+            ids: false,
+            porcelain: false,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_long_rows_keep_listing_order() {
+        let backend = MockBackend::new()
+            .with_worker("web", None)
+            .with_worker("api", None)
+            .with_worker("cron", None);
+
+        for name in ["web", "api"] {
+            let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
+            writeln!(temp_file, "export default {{}}").unwrap();
+
+            WorkersCommand::Deploy {
+                name: Some(name.to_string()),
+                file: temp_file.path().to_path_buf(),
+                message: Some(format!("{} release", name)),
+                approve_token: None,
+                worker: None,
+                env: None,
+                porcelain: false,
+            }
+            .run(&backend)
+            .await
+            .unwrap();
+        }
+
+        let mut workers = vec![];
+        for name in ["web", "cron", "api"] {
+            workers.push(backend.get_worker(name).await.unwrap());
+        }
+
+        let rows = long_rows(&backend, &workers).await.unwrap();
+        let columns: Vec<(&str, &str, &str)> = rows
+            .iter()
+            .map(|r| (r[0].as_str(), r[1].as_str(), r[7].as_str()))
+            .collect();
+
+        assert_eq!(
+            columns,
+            vec![
+                ("web", "v1", "web release"),
+                ("cron", "-", ""),
+                ("api", "v1", "api release"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_ids_print_names() {
+        let backend = MockBackend::new()
+            .with_worker("api", None)
+            .with_worker("web", None);
+
+        WorkersCommand::List {
+            long: false,
+            ids: true,
+            porcelain: false,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let listed = quiet::take();
+        assert_eq!(listed, vec!["api", "web"]);
+
+        // `get -q` prints the same identifier, so list output pipes into get
+        for name in &listed {
+            WorkersCommand::Get {
+                name: name.clone(),
+                ids: true,
+                porcelain: false,
+            }
+            .run(&backend)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(quiet::take(), listed);
+    }
+
+    #[tokio::test]
+    async fn test_get_existing() {
+        let backend = MockBackend::new().with_worker("my-worker", Some("Test worker"));
+
+        let result = WorkersCommand::Get {
+            name: "my-worker".to_string(),
+            ids: false,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_worker_fields() {
+        let backend = MockBackend::new()
+            .with_worker("api", Some("API worker"))
+            .with_deployed_worker("web", 3);
+
+        let api = worker_fields(&backend, &backend.get_worker("api").await.unwrap());
+        let web = worker_fields(&backend, &backend.get_worker("web").await.unwrap());
+
+        assert_eq!(api[0], "api");
+        assert_eq!(api[2], "", "no version is an empty field");
+        assert_eq!(web[2], "3");
+        assert_eq!(web.len(), 5);
+    }
+
+    #[test]
+    fn test_apply_metadata() {
+        let mut metadata = BTreeMap::from([("runbook".to_string(), "https://wiki".to_string())]);
+
+        apply_metadata(
+            &mut metadata,
+            &[
+                "owner=team-payments".to_string(),
+                "repo=https://github.com/acme/api?tab=readme".to_string(),
+                "runbook=".to_string(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["owner"], "team-payments");
+        assert_eq!(metadata["repo"], "https://github.com/acme/api?tab=readme");
+
+        assert!(apply_metadata(&mut metadata, &["owner".to_string()]).is_err());
+        assert!(apply_metadata(&mut metadata, &["=x".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_meta_set() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        WorkersCommand::Meta {
+            command: MetaCommand::Set {
+                name: "api".to_string(),
+                fields: vec![
+                    "owner=team-payments".to_string(),
+                    "tags=billing".to_string(),
+                ],
+            },
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        WorkersCommand::Meta {
+            command: MetaCommand::Set {
+                name: "api".to_string(),
+                fields: vec!["tags=".to_string()],
+            },
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(
+            worker.metadata,
+            BTreeMap::from([("owner".to_string(), "team-payments".to_string())])
+        );
+
+        let result = WorkersCommand::Meta {
+            command: MetaCommand::Get {
+                name: "api".to_string(),
+                key: Some("runbook".to_string()),
+            },
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_not_found() {
+        let backend = MockBackend::new();
+
+        let result = WorkersCommand::Get {
+            name: "nonexistent".to_string(),
+            ids: false,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create() {
+        let backend = MockBackend::new();
+
+        let result = WorkersCommand::Create {
+            name: "new-worker".to_string(),
+            description: Some("A new worker".to_string()),
+            language: "typescript".to_string(),
+            file: None,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+
+        // Verify the worker was created
+        let worker = backend.get_worker("new-worker").await.unwrap();
+        assert_eq!(worker.name, "new-worker");
+        assert_eq!(worker.description, Some("A new worker".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_create_without_description() {
+        let backend = MockBackend::new();
+
+        let result = WorkersCommand::Create {
+            name: "simple-worker".to_string(),
+            description: None,
+            language: "javascript".to_string(),
+            file: None,
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+
+        let worker = backend.get_worker("simple-worker").await.unwrap();
+        assert!(worker.description.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_deploy() {
+        let backend = MockBackend::new();
+
+        let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
+        writeln!(
+            temp_file,
+            "export default {{ fetch() {{ return new Response('Hello') }} }}"
+        )
+        .unwrap();
+
+        let result = WorkersCommand::Create {
+            name: "fresh".to_string(),
+            description: None,
+            language: "javascript".to_string(),
+            file: Some(temp_file.path().to_path_buf()),
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+
+        let worker = backend.get_worker("fresh").await.unwrap();
+        assert_eq!(worker.current_version, Some(1));
+
+        // A bad file fails the deploy but keeps the worker
+        let result = WorkersCommand::Create {
+            name: "half".to_string(),
+            description: None,
+            language: "javascript".to_string(),
+            file: Some(PathBuf::from("worker.txt")),
+            tags: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_err());
+        assert!(backend.get_worker("half").await.is_ok());
+    }
+
+    #[test]
+    fn test_diff_keys() {
+        let value = |key: &str, value_type: &str| EnvironmentValue {
+            id: key.to_lowercase(),
+            key: key.to_string(),
+            value: "x".to_string(),
+            value_type: value_type.to_string(),
+            rotated_at: None,
+        };
+
+        let current = [
+            value("ASSETS", "assets"),
+            value("DB", "database"),
+            value("API_URL", "var"),
+        ];
+        let candidate = [
+            value("DB", "database"),
+            value("API_URL", "secret"),
+            value("CACHE", "kv"),
+        ];
+
+        let changes = diff_keys(&current, &candidate);
+
+        assert_eq!(
+            changes,
+            vec![
+                KeyChange::Retyped {
+                    key: "API_URL".to_string(),
+                    from: "var".to_string(),
+                    to: "secret".to_string(),
+                },
+                KeyChange::Removed {
+                    key: "ASSETS".to_string(),
+                    value_type: "assets".to_string(),
+                },
+                KeyChange::Added {
+                    key: "CACHE".to_string(),
+                    value_type: "kv".to_string(),
+                },
+            ]
+        );
+        assert_eq!(changes.iter().filter(|c| c.is_breaking()).count(), 2);
+        assert!(diff_keys(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("content-type: application/json"),
+            Ok(("content-type".to_string(), "application/json".to_string()))
+        );
+        assert_eq!(
+            parse_header("x-empty:"),
+            Ok(("x-empty".to_string(), String::new()))
+        );
+        assert!(parse_header("no-colon").is_err());
+        assert!(parse_header(": value").is_err());
+        assert!(parse_header("bad name: value").is_err());
+    }
+
+    #[test]
+    fn test_read_data() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{{\"id\": 1}}").unwrap();
+
+        let reference = format!("@{}", file.path().display());
+        assert_eq!(read_data(&reference).unwrap(), "{\"id\": 1}");
+        assert_eq!(read_data("plain=text").unwrap(), "plain=text");
+        assert!(read_data("@/nonexistent/body.json").is_err());
+    }
+
+    #[test]
+    fn test_env_snippet() {
+        let value = |key: &str, value_type: &str| EnvironmentValue {
+            id: key.to_lowercase(),
+            key: key.to_string(),
+            value: "x".to_string(),
+            value_type: value_type.to_string(),
+            rotated_at: None,
+        };
+
+        assert_eq!(env_snippet(&[]), None);
+
+        let snippet = env_snippet(&[
+            value("API_URL", "var"),
+            value("TOKEN", "secret"),
+            value("CACHE", "kv"),
+            value("DB", "database"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            snippet,
+            "interface Env {\n  API_URL: string;\n  TOKEN: string; // secret\n  CACHE: KVNamespace;\n  DB: DatabaseBinding;\n}\n\n\
+             env.API_URL\nenv.TOKEN\nawait env.CACHE.get(\"key\")\nawait env.DB.execute(\"SELECT 1\")\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_existing() {
+        let backend = MockBackend::new().with_worker("to-delete", None);
+
+        let result = WorkersCommand::Delete {
+            name: "to-delete".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
+
+        // Verify it's gone
+        let get_result = backend.get_worker("to-delete").await;
+        assert!(matches!(get_result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_not_found() {
+        let backend = MockBackend::new();
+
+        let result = WorkersCommand::Delete {
+            name: "nonexistent".to_string(),
+            yes: true,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_description() {
+        let backend = MockBackend::new().with_worker("api", Some("Tpyo"));
+
+        WorkersCommand::Update {
+            name: "api".to_string(),
+            description: Some("REST API".to_string()),
+            tags: vec![],
+            untag: vec![],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.description.as_deref(), Some("REST API"));
+
+        WorkersCommand::Update {
+            name: "api".to_string(),
+            description: Some(String::new()),
+            tags: vec![],
+            untag: vec![],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.description, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_requires_a_field() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        let result = WorkersCommand::Update {
+            name: "api".to_string(),
+            description: None,
+            tags: vec![],
+            untag: vec![],
+        }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::Usage(_))));
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(
+            parse_tag("team=payments"),
+            Ok(("team".to_string(), "payments".to_string()))
+        );
+        assert_eq!(
+            parse_tag("acme.io/tier=a=b"),
+            Ok(("acme.io/tier".to_string(), "a=b".to_string()))
+        );
+        assert!(parse_tag("team").is_err());
+        assert!(parse_tag("team=").is_err());
+        assert!(parse_tag("my team=x").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tags() {
+        let backend = MockBackend::new().with_worker("web", None);
+
+        WorkersCommand::Create {
+            name: "api".to_string(),
+            description: None,
+            language: "typescript".to_string(),
+            file: None,
+            tags: vec![
+                ("team".to_string(), "payments".to_string()),
+                ("tier".to_string(), "1".to_string()),
+            ],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        WorkersCommand::Update {
+            name: "api".to_string(),
+            description: None,
+            tags: vec![("team".to_string(), "billing".to_string())],
+            untag: vec!["tier".to_string()],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let api = backend.get_worker("api").await.unwrap();
+        let web = backend.get_worker("web").await.unwrap();
+
+        assert_eq!(
+            api.tags,
+            BTreeMap::from([("team".to_string(), "billing".to_string())])
+        );
+
+        let filter = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert!(matches_tags(&api, &filter(&["team=billing"])));
+        assert!(matches_tags(&api, &filter(&["team"])));
+        assert!(!matches_tags(&api, &filter(&["team=billing", "tier"])));
+        assert!(!matches_tags(&web, &filter(&["team"])));
+        assert!(matches_tags(&web, &[]));
+    }
+
+    #[tokio::test]
+    async fn test_link_and_unlink() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        let input = crate::backend::CreateEnvironmentInput {
+            name: "staging".to_string(),
+            desc: None,
+        };
+        backend.create_environment(input).await.unwrap();
+
+        WorkersCommand::Link {
+            name: "api".to_string(),
+            env: "staging".to_string(),
+            diff: false,
+            yes: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.environment.unwrap().name, "staging");
+
+        for _ in 0..2 {
+            WorkersCommand::Unlink {
+                name: "api".to_string(),
+            }
+            .run(&backend)
+            .await
+            .unwrap();
+
+            let worker = backend.get_worker("api").await.unwrap();
+            assert!(worker.environment.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_share_and_revoke() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        WorkersCommand::Share {
+            name: "api".to_string(),
+            with: Some("alice".to_string()),
+            role: "viewer".to_string(),
+            revoke: None,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        // Sharing again changes the role
+        WorkersCommand::Share {
+            name: "api".to_string(),
+            with: Some("alice".to_string()),
+            role: "editor".to_string(),
+            revoke: None,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let shares = backend.list_worker_shares("api").await.unwrap();
+        assert_eq!(shares.len(), 1);
+        assert_eq!(shares[0].role, "editor");
+
+        WorkersCommand::Share {
+            name: "api".to_string(),
+            with: None,
+            role: "viewer".to_string(),
+            revoke: Some("alice".to_string()),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        assert!(backend.list_worker_shares("api").await.unwrap().is_empty());
+
+        let result = backend.unshare_worker("api", "alice").await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_urls_add_and_remove() {
+        let backend = MockBackend::new()
+            .with_worker("api", None)
+            .with_worker("web", None);
+        let domain = parse_worker_url("API.example.com").unwrap();
+        let route = parse_worker_url("/api/*").unwrap();
+
+        WorkersCommand::Urls {
+            name: "api".to_string(),
+            add: vec![domain.clone(), route.clone()],
+            remove: vec![],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let urls = backend.list_worker_urls("api").await.unwrap();
+        assert_eq!(urls, vec![domain.clone(), route.clone()]);
+
+        // A domain can only point at one worker
+        let result = WorkersCommand::Urls {
+            name: "web".to_string(),
+            add: vec![domain.clone()],
+            remove: vec![],
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
+
+        WorkersCommand::Urls {
+            name: "api".to_string(),
+            add: vec![],
+            remove: vec![domain.clone()],
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let urls = backend.list_worker_urls("api").await.unwrap();
+        assert_eq!(urls, vec![route]);
+
+        let result = WorkersCommand::Urls {
+            name: "api".to_string(),
+            add: vec![],
+            remove: vec![domain],
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_typescript() {
+        let backend = MockBackend::new().with_worker("ts-worker", None);
+
+        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
+        writeln!(
+            temp_file,
+            "export default {{ fetch() {{ return new Response('Hello') }} }}"
+        )
+        .unwrap();
+
+        let result = WorkersCommand::Deploy {
+            name: Some("ts-worker".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: Some("Initial deploy".to_string()),
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
 
-    // Upload assets (presigned URLs from API, or direct S3 from DB backend)
-    let (uploaded_assets, skipped_assets) = if let Some(ref presigned) = result.assets {
-        println!("{} Checking {} assets...", "→".blue(), presigned.len());
-        let urls = presigned
-            .iter()
-            .map(|a| (a.path.clone(), (a.head_url.clone(), a.put_url.clone())))
-            .collect();
-        let client = PresignedClient::new(urls);
-        s3::upload_assets(&client, &assets).await
-    } else if let Some(ref config) = result.direct_upload {
-        println!("{} Checking {} assets...", "→".blue(), assets.len());
-        let client = S3Client::new(S3Config {
-            bucket: config.bucket.clone(),
-            endpoint: config.endpoint.clone(),
-            access_key_id: config.access_key_id.clone(),
-            secret_access_key: config.secret_access_key.clone(),
-            region: config.region.clone(),
-            prefix: config.prefix.clone(),
-        });
-        s3::upload_assets(&client, &assets).await
-    } else {
-        (0, 0)
-    };
+        // Verify version was updated
+        let worker = backend.get_worker("ts-worker").await.unwrap();
+        assert_eq!(worker.current_version, Some(1));
+    }
 
-    let version_str = result
-        .deployed
-        .as_ref()
-        .map(|d| format!("v{}", d.version))
-        .unwrap_or_else(|| "deployed".to_string());
+    #[tokio::test]
+    async fn test_deploy_javascript() {
+        let backend = MockBackend::new().with_worker("js-worker", None);
 
-    println!(
-        "{} Uploaded to '{}' ({})",
-        "Uploaded".green(),
-        result.worker.name.bold(),
-        version_str
-    );
+        let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
+        writeln!(
+            temp_file,
+            "export default {{ fetch() {{ return new Response('Hello') }} }}"
+        )
+        .unwrap();
 
-    println!();
+        let result = WorkersCommand::Deploy {
+            name: Some("js-worker".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await;
 
-    if result.worker.url.starts_with("http") {
-        println!("{:12} {}", "URL:".dimmed(), result.worker.url);
-    } else if backend.is_default_cloud() {
-        println!(
-            "{:12} https://{}.workers.rocks",
-            "URL:".dimmed(),
-            result.worker.url
-        );
-    } else {
-        println!("{:12} {}", "Worker:".dimmed(), result.worker.url);
+        assert!(result.is_ok());
     }
 
-    if let Some(deployed) = &result.deployed {
-        println!("{:12} {}", "Version:".dimmed(), deployed.version);
+    #[tokio::test]
+    async fn test_upload_rejects_invalid_routes() {
+        let backend = MockBackend::new().with_worker("site", None);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("worker.js"), "export default {}").unwrap();
+        std::fs::write(
+            dir.path().join(ROUTES_FILE),
+            "{\n  \"static\": [\"/ok\", \"no-slash\"]\n}",
+        )
+        .unwrap();
 
-        if deployed.functions > 0 {
-            println!("{:12} {}", "Functions:".dimmed(), deployed.functions);
+        let result = WorkersCommand::Upload {
+            name: "site".to_string(),
+            path: dir.path().to_path_buf(),
+            approve_token: None,
+            assets: vec![],
+            fingerprint: false,
+            follow_symlinks: false,
+            include_hidden: false,
+            env: None,
+            auto_bind: None,
+            verify: None,
+        }
+        .run(&backend)
+        .await;
+
+        let Err(BackendError::Api(message)) = result else {
+            panic!("expected a routes error");
+        };
+        assert!(message.contains("line 2"));
+        assert!(message.contains("no-slash"));
+
+        // Function routes must point at a script in the upload
+        std::fs::write(
+            dir.path().join(ROUTES_FILE),
+            r#"{ "functions": [{ "pattern": "/api/**", "worker": "functions/api.js" }] }"#,
+        )
+        .unwrap();
+
+        let result = WorkersCommand::Upload {
+            name: "site".to_string(),
+            path: dir.path().to_path_buf(),
+            approve_token: None,
+            assets: vec![],
+            fingerprint: false,
+            follow_symlinks: false,
+            include_hidden: false,
+            env: None,
+            auto_bind: None,
+            verify: None,
         }
+        .run(&backend)
+        .await;
+
+        let Err(BackendError::Api(message)) = result else {
+            panic!("expected a missing function error");
+        };
+        assert!(message.contains("functions/api.js"));
     }
 
-    if uploaded_assets > 0 || skipped_assets > 0 {
-        if skipped_assets > 0 {
-            println!(
-                "{:12} {} uploaded, {} unchanged",
-                "Assets:".dimmed(),
-                uploaded_assets,
-                skipped_assets
-            );
-        } else {
-            println!("{:12} {} uploaded", "Assets:".dimmed(), uploaded_assets);
+    #[tokio::test]
+    async fn test_deploy_increments_version() {
+        let backend = MockBackend::new().with_worker("versioned-worker", None);
+
+        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
+        writeln!(
+            temp_file,
+            "export default {{ fetch() {{ return new Response('v1') }} }}"
+        )
+        .unwrap();
+
+        // First deploy
+        WorkersCommand::Deploy {
+            name: Some("versioned-worker".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: Some("v1".to_string()),
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("versioned-worker").await.unwrap();
+        assert_eq!(worker.current_version, Some(1));
+
+        // Second deploy
+        writeln!(temp_file, "// v2").unwrap();
+        WorkersCommand::Deploy {
+            name: Some("versioned-worker".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: Some("v2".to_string()),
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        let worker = backend.get_worker("versioned-worker").await.unwrap();
+        assert_eq!(worker.current_version, Some(2));
     }
 
-    Ok(())
-}
+    async fn deploy_versions(backend: &MockBackend, name: &str, count: usize) {
+        for n in 0..count {
+            let input = DeployInput {
+                code: format!("// v{}", n).into_bytes(),
+                code_type: "javascript".to_string(),
+                message: None,
+                hash: None,
+                authorization: Default::default(),
+            };
+
+            backend.deploy_worker(name, input).await.unwrap();
+        }
+    }
 
-/// Asset: (path, content, content_type, sha256_base64)
-type Asset = (String, Vec<u8>, String, String);
+    #[tokio::test]
+    async fn test_versions() {
+        let backend = MockBackend::new().with_worker("api", None);
+        deploy_versions(&backend, "api", 2).await;
+        backend
+            .tag_deployment("api", 1, Some("stable"))
+            .await
+            .unwrap();
+
+        let deployments = backend.list_deployments("api").await.unwrap();
+        let fields: Vec<Vec<String>> = deployments
+            .iter()
+            .map(|d| version_fields(d, Some(2)))
+            .collect();
 
-/// Collect assets from the assets/ subdirectory of a folder
-fn collect_assets(folder: &PathBuf) -> Result<Vec<Asset>, BackendError> {
-    let assets_dir = folder.join("assets");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0][0], "1");
+        assert_eq!(fields[0][1], "false");
+        assert_eq!(fields[0][3], "javascript");
+        assert_eq!(fields[0][6], "stable");
+        assert_eq!(fields[1][1], "true");
+        assert_eq!(fields[1][2], deployments[1].hash);
+
+        WorkersCommand::Versions {
+            name: "api".to_string(),
+            porcelain: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
 
-    if !assets_dir.exists() {
-        return Ok(vec![]);
+        WorkersCommand::History {
+            command: HistoryCommand::List {
+                name: "api".to_string(),
+                porcelain: true,
+            },
+        }
+        .run(&backend)
+        .await
+        .unwrap();
     }
 
-    let mut assets = Vec::new();
-    collect_assets_recursive(&assets_dir, &assets_dir, &mut assets)?;
-    Ok(assets)
-}
+    #[tokio::test]
+    async fn test_runtime() {
+        let backend = MockBackend::new().with_worker("api", None);
 
-fn collect_assets_recursive(
-    dir: &PathBuf,
-    base: &PathBuf,
-    assets: &mut Vec<Asset>,
-) -> Result<(), BackendError> {
-    use sha2::{Digest, Sha256};
+        let runtime = backend.worker_runtime("api").await.unwrap();
+        assert_eq!(runtime.active_isolates, 1);
+        assert_eq!(runtime.cold_starts.len(), 1);
 
-    for entry in std::fs::read_dir(dir).map_err(|e| {
-        BackendError::Api(format!(
-            "Failed to read directory '{}': {}",
-            dir.display(),
-            e
-        ))
-    })? {
-        let entry = entry.map_err(|e| BackendError::Api(format!("Failed to read entry: {}", e)))?;
-        let path = entry.path();
+        let json = serde_json::to_value(&runtime).unwrap();
+        assert_eq!(json["inFlightRequests"], 0);
+        assert_eq!(json["coldStarts"][0]["durationMs"], 12);
 
-        if path.is_dir() {
-            collect_assets_recursive(&path, base, assets)?;
-        } else {
-            let relative = path
-                .strip_prefix(base)
-                .map_err(|e| BackendError::Api(format!("Path error: {}", e)))?
-                .to_string_lossy()
-                .replace('\\', "/");
+        let result = WorkersCommand::Runtime {
+            name: "missing".to_string(),
+        }
+        .run(&backend)
+        .await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+    }
 
-            let content = std::fs::read(&path).map_err(|e| {
-                BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
-            })?;
+    #[tokio::test]
+    async fn test_exec_scheduled() {
+        let backend = MockBackend::new().with_worker("cron-job", None);
+
+        let result = WorkersCommand::Exec {
+            name: "cron-job".to_string(),
+            event: EventKind::Scheduled,
+            cron: Some("0 * * * *".to_string()),
+            tz: cron::Tz::Local,
+            messages: vec![],
+            format: LogFormat::Pretty,
+            raw: false,
+        }
+        .run(&backend)
+        .await;
+
+        assert!(result.is_ok());
 
-            let hash_hex = hex::encode(Sha256::digest(&content));
+        let event = WorkerEvent::Scheduled {
+            cron: Some("0 * * * *".to_string()),
+            scheduled_time: "2026-01-01T00:00:00Z".parse().unwrap(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "type": "scheduled",
+                "cron": "0 * * * *",
+                "scheduledTime": "2026-01-01T00:00:00Z",
+            })
+        );
 
-            let content_type = get_mime_type(&relative);
-            assets.push((relative, content, content_type.to_string(), hash_hex));
+        let result = WorkersCommand::Exec {
+            name: "missing".to_string(),
+            event: EventKind::Queue,
+            cron: None,
+            tz: cron::Tz::Local,
+            messages: vec![],
+            format: LogFormat::Pretty,
+            raw: false,
         }
+        .run(&backend)
+        .await;
+
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
     }
 
-    Ok(())
-}
+    #[tokio::test]
+    async fn test_history_export() {
+        let backend = MockBackend::new().with_worker("api", None);
+        deploy_versions(&backend, "api", 2).await;
 
-fn create_zip_from_folder(folder: &PathBuf) -> Result<Vec<u8>, BackendError> {
-    use std::io::{Cursor, Write};
-    use zip::ZipWriter;
-    use zip::write::SimpleFileOptions;
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("api-history");
 
-    let mut buffer = Cursor::new(Vec::new());
-    let mut zip = ZipWriter::new(&mut buffer);
-    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let export = || WorkersCommand::History {
+            command: HistoryCommand::Export {
+                name: "api".to_string(),
+                out: Some(out.clone()),
+            },
+        };
 
-    fn add_directory(
-        zip: &mut ZipWriter<&mut Cursor<Vec<u8>>>,
-        folder: &PathBuf,
-        base: &PathBuf,
-        options: SimpleFileOptions,
-    ) -> Result<(), BackendError> {
-        for entry in std::fs::read_dir(folder).map_err(|e| {
-            BackendError::Api(format!(
-                "Failed to read directory '{}': {}",
-                folder.display(),
-                e
-            ))
-        })? {
-            let entry =
-                entry.map_err(|e| BackendError::Api(format!("Failed to read entry: {}", e)))?;
-            let path = entry.path();
-            let relative = path
-                .strip_prefix(base)
-                .map_err(|e| BackendError::Api(format!("Path error: {}", e)))?;
+        export().run(&backend).await.unwrap();
 
-            // Skip assets/ directory — assets are uploaded separately via presigned URLs
-            let relative_str = relative.to_string_lossy();
+        let code = std::fs::read_to_string(out.join("v2/worker.js")).unwrap();
+        assert_eq!(code, "// v1");
 
-            if relative_str == "assets" || relative_str.starts_with("assets/") {
-                continue;
-            }
+        let metadata: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out.join("metadata.json")).unwrap())
+                .unwrap();
+        assert_eq!(metadata["deployments"].as_array().unwrap().len(), 2);
+        assert_eq!(metadata["deployments"][0]["version"], 1);
 
-            if path.is_dir() {
-                add_directory(zip, &path, base, options)?;
-            } else {
-                let content = std::fs::read(&path).map_err(|e| {
-                    BackendError::Api(format!("Failed to read file '{}': {}", path.display(), e))
-                })?;
+        // Never writes over a previous export
+        assert!(export().run(&backend).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import() {
+        let backend = MockBackend::new().with_worker("api", None);
+        deploy_versions(&backend, "api", 2).await;
+
+        let input = CreateEnvironmentInput {
+            name: "prod".to_string(),
+            desc: None,
+        };
+        let env = backend.create_environment(input).await.unwrap();
+
+        let values = [
+            ("API_URL", "var", "https://example.com"),
+            ("API_KEY", "secret", "hunter2"),
+        ]
+        .iter()
+        .map(|(key, value_type, value)| EnvironmentValueInput {
+            id: None,
+            key: key.to_string(),
+            value: Some(value.to_string()),
+            value_type: value_type.to_string(),
+        })
+        .collect();
 
-                let relative_path = relative_str.replace('\\', "/");
-                zip.start_file(relative_path, options)
-                    .map_err(|e| BackendError::Api(format!("Zip error: {}", e)))?;
+        let input = UpdateEnvironmentInput {
+            name: None,
+            values: Some(values),
+            protected: None,
+        };
+        backend.update_environment("prod", input).await.unwrap();
+
+        let worker = backend.get_worker("api").await.unwrap();
+        backend
+            .link_worker_environment(&worker.id, &env.id)
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("api.tar.gz");
+
+        cmd_export(&backend, "api", &out).await.unwrap();
+
+        let (bundle, _) = Bundle::read(&out).unwrap();
+        let exported = bundle.environment.unwrap().values;
+        assert_eq!(exported.len(), 2);
+        assert!(
+            exported
+                .iter()
+                .any(|v| v.key == "API_KEY" && v.is_redacted())
+        );
 
-                zip.write_all(&content)
-                    .map_err(|e| BackendError::Api(format!("Zip write error: {}", e)))?;
-            }
-        }
+        // The names are taken in this account
+        assert!(cmd_import(&backend, &out, None, None).await.is_err());
+
+        cmd_import(
+            &backend,
+            &out,
+            Some("api-copy".to_string()),
+            Some("prod-copy".to_string()),
+        )
+        .await
+        .unwrap();
 
-        Ok(())
+        let copy = backend.get_worker("api-copy").await.unwrap();
+        assert_eq!(copy.environment.unwrap().name, "prod-copy");
+
+        let deployment = backend
+            .get_current_deployment("api-copy")
+            .await
+            .unwrap()
+            .unwrap();
+        let code = backend
+            .get_deployment_code("api-copy", deployment.version)
+            .await
+            .unwrap();
+        assert_eq!(code, b"// v1");
+
+        // Secrets are not carried over
+        let env = backend.get_environment("prod-copy").await.unwrap();
+        let keys: Vec<&str> = env.values.iter().map(|v| v.key.as_str()).collect();
+        assert_eq!(keys, vec!["API_URL"]);
     }
 
-    add_directory(&mut zip, folder, folder, options)?;
-    zip.finish()
-        .map_err(|e| BackendError::Api(format!("Zip finish error: {}", e)))?;
+    #[tokio::test]
+    async fn test_prune_keeps_recent_active_and_tagged() {
+        let backend = MockBackend::new().with_worker("api", None);
+        deploy_versions(&backend, "api", 6).await;
+
+        WorkersCommand::Tag {
+            name: "api".to_string(),
+            version: 1,
+            tag: Some("stable".to_string()),
+            remove: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
 
-    Ok(buffer.into_inner())
-}
+        WorkersCommand::Prune {
+            name: "api".to_string(),
+            keep: Some(2),
+        }
+        .run(&backend)
+        .await
+        .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::backend::mock::MockBackend;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
+        assert_eq!(backend.deployment_versions("api"), vec![1, 5, 6]);
+    }
 
     #[tokio::test]
-    async fn test_list_empty() {
-        let backend = MockBackend::new();
+    async fn test_prune_requires_keep_or_policy() {
+        let backend = MockBackend::new().with_worker("api", None);
 
-        let result = WorkersCommand::List.run(&backend).await;
+        let result = WorkersCommand::Prune {
+            name: "api".to_string(),
+            keep: None,
+        }
+        .run(&backend)
+        .await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(BackendError::Usage(_))));
     }
 
     #[tokio::test]
-    async fn test_list_with_workers() {
-        let backend = MockBackend::new()
-            .with_worker("api", Some("API worker"))
-            .with_deployed_worker("web", 3);
+    async fn test_retention_policy_applies_on_deploy() {
+        let backend = MockBackend::new().with_worker("api", None);
+
+        WorkersCommand::Retention {
+            keep: Some(3),
+            off: false,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
 
-        let result = WorkersCommand::List.run(&backend).await;
+        deploy_versions(&backend, "api", 5).await;
 
-        assert!(result.is_ok());
+        assert_eq!(backend.deployment_versions("api"), vec![3, 4, 5]);
+
+        let worker = backend.get_worker("api").await.unwrap();
+        assert_eq!(worker.current_version, Some(5));
     }
 
     #[tokio::test]
-    async fn test_get_existing() {
-        let backend = MockBackend::new().with_worker("my-worker", Some("Test worker"));
+    async fn test_deploy_invalid_extension() {
+        let backend = MockBackend::new().with_worker("worker", None);
 
-        let result = WorkersCommand::Get {
-            name: "my-worker".to_string(),
+        let temp_file = NamedTempFile::with_suffix(".txt").unwrap();
+
+        let result = WorkersCommand::Deploy {
+            name: Some("worker".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(BackendError::Api(_))));
     }
 
     #[tokio::test]
-    async fn test_get_not_found() {
+    async fn test_deploy_worker_not_found() {
         let backend = MockBackend::new();
 
-        let result = WorkersCommand::Get {
-            name: "nonexistent".to_string(),
+        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
+        writeln!(temp_file, "export default {{}}").unwrap();
+
+        let result = WorkersCommand::Deploy {
+            name: Some("nonexistent".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
@@ -581,170 +5772,247 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_create() {
-        let backend = MockBackend::new();
+    async fn test_deploy_file_not_found() {
+        let backend = MockBackend::new().with_worker("worker", None);
 
-        let result = WorkersCommand::Create {
-            name: "new-worker".to_string(),
-            description: Some("A new worker".to_string()),
-            language: "typescript".to_string(),
+        let result = WorkersCommand::Deploy {
+            name: Some("worker".to_string()),
+            file: PathBuf::from("/nonexistent/path/file.ts"),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
-        assert!(result.is_ok());
-
-        // Verify the worker was created
-        let worker = backend.get_worker("new-worker").await.unwrap();
-        assert_eq!(worker.name, "new-worker");
-        assert_eq!(worker.description, Some("A new worker".to_string()));
+        assert!(matches!(result, Err(BackendError::Api(_))));
     }
 
     #[tokio::test]
-    async fn test_create_without_description() {
-        let backend = MockBackend::new();
+    async fn test_protect_and_unprotect() {
+        let backend = MockBackend::new().with_worker("prod-api", None);
 
-        let result = WorkersCommand::Create {
-            name: "simple-worker".to_string(),
-            description: None,
-            language: "javascript".to_string(),
+        WorkersCommand::Protect {
+            name: "prod-api".to_string(),
+            off: false,
         }
         .run(&backend)
-        .await;
+        .await
+        .unwrap();
 
-        assert!(result.is_ok());
+        assert!(backend.get_worker("prod-api").await.unwrap().protected);
 
-        let worker = backend.get_worker("simple-worker").await.unwrap();
-        assert!(worker.description.is_none());
+        WorkersCommand::Protect {
+            name: "prod-api".to_string(),
+            off: true,
+        }
+        .run(&backend)
+        .await
+        .unwrap();
+
+        assert!(!backend.get_worker("prod-api").await.unwrap().protected);
     }
 
     #[tokio::test]
-    async fn test_delete_existing() {
-        let backend = MockBackend::new().with_worker("to-delete", None);
+    async fn test_deploy_protected_requires_approval() {
+        let backend = MockBackend::new().with_protected_worker("prod-api");
 
-        let result = WorkersCommand::Delete {
-            name: "to-delete".to_string(),
+        let result = authorize_deploy(&backend, "prod-api", None, false).await;
+
+        assert!(matches!(result, Err(BackendError::Api(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_protected_with_token() {
+        let backend = MockBackend::new().with_protected_worker("prod-api");
+
+        let approval = backend
+            .create_deploy_approval("prod-api", 3600)
+            .await
+            .unwrap();
+
+        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
+        writeln!(temp_file, "export default {{}}").unwrap();
+
+        let result = WorkersCommand::Deploy {
+            name: Some("prod-api".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: None,
+            approve_token: Some(approval.token.clone()),
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
         assert!(result.is_ok());
 
-        // Verify it's gone
-        let get_result = backend.get_worker("to-delete").await;
-        assert!(matches!(get_result, Err(BackendError::NotFound(_))));
-    }
-
-    #[tokio::test]
-    async fn test_delete_not_found() {
-        let backend = MockBackend::new();
-
-        let result = WorkersCommand::Delete {
-            name: "nonexistent".to_string(),
+        // Tokens are single use
+        let result = WorkersCommand::Deploy {
+            name: Some("prod-api".to_string()),
+            file: temp_file.path().to_path_buf(),
+            message: None,
+            approve_token: Some(approval.token),
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
-        assert!(matches!(result, Err(BackendError::NotFound(_))));
+        assert!(matches!(result, Err(BackendError::Api(_))));
     }
 
     #[tokio::test]
-    async fn test_deploy_typescript() {
-        let backend = MockBackend::new().with_worker("ts-worker", None);
+    async fn test_approval_token_bound_to_worker() {
+        let backend = MockBackend::new()
+            .with_protected_worker("prod-api")
+            .with_protected_worker("prod-web");
+
+        let approval = backend
+            .create_deploy_approval("prod-api", 3600)
+            .await
+            .unwrap();
 
         let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
-        writeln!(
-            temp_file,
-            "export default {{ fetch() {{ return new Response('Hello') }} }}"
-        )
-        .unwrap();
+        writeln!(temp_file, "export default {{}}").unwrap();
 
         let result = WorkersCommand::Deploy {
-            name: "ts-worker".to_string(),
+            name: Some("prod-web".to_string()),
             file: temp_file.path().to_path_buf(),
-            message: Some("Initial deploy".to_string()),
+            message: None,
+            approve_token: Some(approval.token),
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(BackendError::Api(_))));
+        assert_eq!(
+            backend
+                .get_worker("prod-web")
+                .await
+                .unwrap()
+                .current_version,
+            None
+        );
+    }
 
-        // Verify version was updated
-        let worker = backend.get_worker("ts-worker").await.unwrap();
-        assert_eq!(worker.current_version, Some(1));
+    #[tokio::test]
+    async fn test_backend_refuses_unauthorized_deploy() {
+        let backend = MockBackend::new().with_protected_worker("prod-api");
+
+        let input = DeployInput {
+            code: b"export default {}".to_vec(),
+            code_type: "javascript".to_string(),
+            message: None,
+            hash: None,
+            authorization: Default::default(),
+        };
+
+        let result = backend.deploy_worker("prod-api", input).await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
+
+        let result = backend
+            .upload_worker(
+                "prod-api",
+                std::path::Path::new("."),
+                Vec::new(),
+                &[],
+                &Default::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
     }
 
     #[tokio::test]
-    async fn test_deploy_javascript() {
-        let backend = MockBackend::new().with_worker("js-worker", None);
+    async fn test_failed_deploy_keeps_approval() {
+        let backend = MockBackend::new().with_protected_worker("prod-api");
 
-        let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
-        writeln!(
-            temp_file,
-            "export default {{ fetch() {{ return new Response('Hello') }} }}"
-        )
-        .unwrap();
+        let approval = backend
+            .create_deploy_approval("prod-api", 3600)
+            .await
+            .unwrap();
 
-        let result = WorkersCommand::Deploy {
-            name: "js-worker".to_string(),
-            file: temp_file.path().to_path_buf(),
+        let input = |hash: Option<String>| DeployInput {
+            code: b"export default {}".to_vec(),
+            code_type: "javascript".to_string(),
             message: None,
+            hash,
+            authorization: DeployAuthorization {
+                approve_token: Some(approval.token.clone()),
+                confirmed: false,
+            },
+        };
+
+        let result = backend
+            .deploy_worker("prod-api", input(Some("00".repeat(32))))
+            .await;
+        assert!(matches!(result, Err(BackendError::Api(_))));
+
+        let deployment = backend
+            .deploy_worker("prod-api", input(None))
+            .await
+            .unwrap();
+        assert_eq!(deployment.version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_approves() {
+        let backend = MockBackend::new()
+            .with_protected_worker("prod-api")
+            .with_member_login();
+
+        let result = WorkersCommand::Approve {
+            name: "prod-api".to_string(),
+            expires: "1h".to_string(),
         }
         .run(&backend)
         .await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(BackendError::Api(_))));
     }
 
     #[tokio::test]
-    async fn test_deploy_increments_version() {
-        let backend = MockBackend::new().with_worker("versioned-worker", None);
+    async fn test_verify_hash_matches_live_deployment() {
+        let backend = MockBackend::new().with_worker("worker", None);
 
-        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
-        writeln!(
-            temp_file,
-            "export default {{ fetch() {{ return new Response('v1') }} }}"
-        )
-        .unwrap();
+        let mut temp_file = NamedTempFile::with_suffix(".js").unwrap();
+        writeln!(temp_file, "export default {{}}").unwrap();
 
-        // First deploy
         WorkersCommand::Deploy {
-            name: "versioned-worker".to_string(),
+            name: Some("worker".to_string()),
             file: temp_file.path().to_path_buf(),
-            message: Some("v1".to_string()),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await
         .unwrap();
 
-        let worker = backend.get_worker("versioned-worker").await.unwrap();
-        assert_eq!(worker.current_version, Some(1));
-
-        // Second deploy
-        writeln!(temp_file, "// v2").unwrap();
-        WorkersCommand::Deploy {
-            name: "versioned-worker".to_string(),
+        let result = WorkersCommand::VerifyHash {
+            name: "worker".to_string(),
             file: temp_file.path().to_path_buf(),
-            message: Some("v2".to_string()),
         }
         .run(&backend)
-        .await
-        .unwrap();
-
-        let worker = backend.get_worker("versioned-worker").await.unwrap();
-        assert_eq!(worker.current_version, Some(2));
-    }
+        .await;
 
-    #[tokio::test]
-    async fn test_deploy_invalid_extension() {
-        let backend = MockBackend::new().with_worker("worker", None);
+        assert!(result.is_ok());
 
-        let temp_file = NamedTempFile::with_suffix(".txt").unwrap();
+        // Local change no longer matches
+        writeln!(temp_file, "// changed").unwrap();
 
-        let result = WorkersCommand::Deploy {
+        let result = WorkersCommand::VerifyHash {
             name: "worker".to_string(),
             file: temp_file.path().to_path_buf(),
-            message: None,
         }
         .run(&backend)
         .await;
@@ -753,35 +6021,408 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_deploy_worker_not_found() {
-        let backend = MockBackend::new();
+    async fn test_deploy_rejects_checksum_mismatch() {
+        let backend = MockBackend::new().with_worker("worker", None);
 
-        let mut temp_file = NamedTempFile::with_suffix(".ts").unwrap();
-        writeln!(temp_file, "export default {{}}").unwrap();
+        let input = DeployInput {
+            code: b"export default {}".to_vec(),
+            code_type: "javascript".to_string(),
+            message: None,
+            hash: Some("00".repeat(32)),
+            authorization: Default::default(),
+        };
 
-        let result = WorkersCommand::Deploy {
-            name: "nonexistent".to_string(),
-            file: temp_file.path().to_path_buf(),
+        let result = backend.deploy_worker("worker", input).await;
+
+        assert!(matches!(result, Err(BackendError::Api(_))));
+    }
+
+    #[test]
+    fn test_check_environment() {
+        assert!(check_environment("api", "prod", None, Some("prod")).is_ok());
+        assert!(check_environment("api", "prod", Some("prod"), Some("prod")).is_ok());
+
+        let err = check_environment("api", "staging", None, Some("prod")).unwrap_err();
+        assert!(err.to_string().contains("linked to 'prod'"));
+
+        // --env can't override the worker's actual link
+        let err = check_environment("api", "staging", Some("staging"), Some("prod")).unwrap_err();
+        assert!(err.to_string().contains("linked to 'prod'"));
+
+        assert!(check_environment("api", "prod", Some("staging"), Some("prod")).is_err());
+        assert!(check_environment("api", "prod", None, None).is_err());
+    }
+
+    #[test]
+    fn test_deploy_uses_remembered_worker() {
+        let command = WorkersCommand::Deploy {
+            name: None,
+            file: PathBuf::from("worker.ts"),
             message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
-        .run(&backend)
-        .await;
+        .with_remembered_worker(|| Some("my-api".to_string()));
 
-        assert!(matches!(result, Err(BackendError::NotFound(_))));
+        assert_eq!(command.target_worker(), Some("my-api"));
+
+        // Explicit names win over the remembered worker
+        let command = WorkersCommand::Deploy {
+            name: Some("my-web".to_string()),
+            file: PathBuf::from("worker.ts"),
+            message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
+        }
+        .with_remembered_worker(|| Some("my-api".to_string()));
+
+        assert_eq!(command.target_worker(), Some("my-web"));
+
+        let command = WorkersCommand::Deploy {
+            name: Some("my-web".to_string()),
+            file: PathBuf::from("worker.ts"),
+            message: None,
+            approve_token: None,
+            worker: Some("my-admin".to_string()),
+            env: None,
+            porcelain: false,
+        };
+
+        assert_eq!(command.target_worker(), Some("my-admin"));
     }
 
     #[tokio::test]
-    async fn test_deploy_file_not_found() {
-        let backend = MockBackend::new().with_worker("worker", None);
+    async fn test_deploy_without_worker() {
+        let backend = MockBackend::new();
 
         let result = WorkersCommand::Deploy {
-            name: "worker".to_string(),
-            file: PathBuf::from("/nonexistent/path/file.ts"),
+            name: None,
+            file: PathBuf::from("worker.ts"),
             message: None,
+            approve_token: None,
+            worker: None,
+            env: None,
+            porcelain: false,
         }
         .run(&backend)
         .await;
 
-        assert!(matches!(result, Err(BackendError::Api(_))));
+        assert!(matches!(result, Err(BackendError::Usage(_))));
+    }
+
+    fn relative_files(dir: &Path, policy: UploadPolicy, skip: &[PathBuf]) -> Vec<String> {
+        walk_files(dir, policy, skip)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                p.strip_prefix(dir)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_files_hidden() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".well-known")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("worker.js"), "").unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "").unwrap();
+        std::fs::write(dir.path().join(".well-known/security.txt"), "").unwrap();
+        std::fs::write(dir.path().join("assets/app.css"), "").unwrap();
+
+        assert_eq!(
+            relative_files(
+                dir.path(),
+                UploadPolicy::default(),
+                &[dir.path().join("assets")]
+            ),
+            vec![".well-known/security.txt", "worker.js"]
+        );
+
+        let policy = UploadPolicy {
+            include_hidden: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            relative_files(dir.path(), policy, &[]),
+            vec![
+                ".env",
+                ".git/HEAD",
+                ".well-known/security.txt",
+                "assets/app.css",
+                "worker.js"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_files_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("img")).unwrap();
+        std::fs::write(dir.path().join("img/logo.png"), "").unwrap();
+        std::fs::write(dir.path().join("index.html"), "").unwrap();
+        std::os::unix::fs::symlink("index.html", dir.path().join("home.html")).unwrap();
+        std::os::unix::fs::symlink("..", dir.path().join("img/loop")).unwrap();
+
+        assert_eq!(
+            relative_files(dir.path(), UploadPolicy::default(), &[]),
+            vec!["img/logo.png", "index.html"]
+        );
+
+        let policy = UploadPolicy {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            relative_files(dir.path(), policy, &[]),
+            vec!["home.html", "img/logo.png", "index.html"]
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(chrono::Duration::seconds(42)), "42s");
+        assert_eq!(format_age(chrono::Duration::minutes(90)), "1h");
+        assert_eq!(format_age(chrono::Duration::days(12)), "12d");
+        assert_eq!(format_age(chrono::Duration::seconds(-5)), "0s");
+    }
+
+    #[tokio::test]
+    async fn test_load_captured_request() {
+        let captured = CapturedRequest {
+            id: Some("c1".to_string()),
+            method: "POST".to_string(),
+            path: "/api/orders".to_string(),
+            headers: BTreeMap::from([
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Host".to_string(), "old.example.com".to_string()),
+            ]),
+            body: Some("{}".to_string()),
+            status: Some(500),
+            captured_at: None,
+        };
+
+        let backend = MockBackend::new()
+            .with_worker("api", None)
+            .with_captured_request("api", captured);
+
+        let request = load_captured_request(&backend, "api", Some("c1"), None)
+            .await
+            .unwrap();
+        assert_eq!(request.status, Some(500));
+
+        // The original host is not replayed
+        assert_eq!(
+            replay_headers(&request),
+            vec![("Content-Type".to_string(), "application/json".to_string())]
+        );
+
+        let result = load_captured_request(&backend, "api", Some("missing"), None).await;
+        assert!(matches!(result, Err(BackendError::NotFound(_))));
+
+        // Hand-written files only need a path
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("request.json");
+        std::fs::write(&file, r#"{"path": "/health"}"#).unwrap();
+
+        let request = load_captured_request(&backend, "api", None, Some(&file))
+            .await
+            .unwrap();
+        assert_eq!(request.method, "GET");
+        assert!(request.headers.is_empty());
+    }
+
+    #[test]
+    fn test_parse_worker_url() {
+        assert_eq!(
+            parse_worker_url("Api.Example.com.").unwrap(),
+            WorkerUrl::Domain {
+                name: "api.example.com".to_string()
+            }
+        );
+        assert_eq!(
+            parse_worker_url("/api/*").unwrap(),
+            WorkerUrl::Route {
+                pattern: "/api/*".to_string()
+            }
+        );
+        assert!(parse_worker_url("https://api.example.com").is_err());
+        assert!(parse_worker_url("localhost").is_err());
+        assert!(parse_worker_url("api..example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_asset_source() {
+        assert_eq!(
+            parse_asset_source("./public").unwrap(),
+            AssetSource {
+                dir: PathBuf::from("./public"),
+                mount: String::new(),
+            }
+        );
+        assert_eq!(
+            parse_asset_source("./static:/static/").unwrap(),
+            AssetSource {
+                dir: PathBuf::from("./static"),
+                mount: "static".to_string(),
+            }
+        );
+        assert!(parse_asset_source(":/static").is_err());
+    }
+
+    #[test]
+    fn test_parse_asset_source_windows_drive() {
+        assert_eq!(
+            parse_asset_source("C:/site/public").unwrap(),
+            AssetSource {
+                dir: PathBuf::from("C:/site/public"),
+                mount: String::new(),
+            }
+        );
+        assert_eq!(
+            parse_asset_source("C:/site/static:/static").unwrap(),
+            AssetSource {
+                dir: PathBuf::from("C:/site/static"),
+                mount: "static".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_collect_assets_mounts() {
+        let public = tempfile::tempdir().unwrap();
+        let static_dir = tempfile::tempdir().unwrap();
+        std::fs::write(public.path().join("favicon.ico"), "").unwrap();
+        std::fs::write(static_dir.path().join("app.css"), "").unwrap();
+
+        let sources = vec![
+            AssetSource {
+                dir: public.path().to_path_buf(),
+                mount: String::new(),
+            },
+            AssetSource {
+                dir: static_dir.path().to_path_buf(),
+                mount: "static".to_string(),
+            },
+        ];
+
+        let assets = collect_assets(&sources, UploadPolicy::default()).unwrap();
+        let keys: Vec<&str> = assets.iter().map(|a| a.key.as_str()).collect();
+        assert_eq!(keys, vec!["favicon.ico", "static/app.css"]);
+        assert_eq!(assets[1].content_type, "text/css");
+
+        // Same key from two sources is rejected
+        let duplicate = vec![sources[0].clone(), sources[0].clone()];
+        assert!(collect_assets(&duplicate, UploadPolicy::default()).is_err());
+    }
+
+    #[test]
+    fn test_collect_assets_sniffs_unknown_types() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("LICENSE"), "MIT License").unwrap();
+        std::fs::write(dir.path().join("blob"), [0u8, 1, 2, 3]).unwrap();
+
+        let sources = vec![AssetSource {
+            dir: dir.path().to_path_buf(),
+            mount: String::new(),
+        }];
+
+        let assets = collect_assets(&sources, UploadPolicy::default()).unwrap();
+        assert_eq!(assets[0].key, "LICENSE");
+        assert_eq!(assets[0].content_type, "text/plain; charset=utf-8");
+        assert_eq!(assets[1].content_type, "application/octet-stream");
+    }
+
+    #[test]
+    fn test_apply_asset_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["site.webmanifest", "app.js", "app.js.map"] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        let sources = vec![AssetSource {
+            dir: dir.path().to_path_buf(),
+            mount: String::new(),
+        }];
+
+        let rules = Manifest::parse(
+            r#"
+            [assets.rules]
+            "*.webmanifest" = { content_type = "application/manifest+json" }
+            "*.js" = { cache_control = "public, max-age=31536000, immutable" }
+            "*.map" = { exclude = true }
+            "#,
+        )
+        .unwrap()
+        .assets;
+
+        let assets = collect_assets(&sources, UploadPolicy::default()).unwrap();
+        let assets = apply_asset_rules(assets, &rules);
+
+        let keys: Vec<&str> = assets.iter().map(|a| a.key.as_str()).collect();
+        assert_eq!(keys, vec!["app.js", "site.webmanifest"]);
+        assert_eq!(
+            assets[0].cache_control.as_deref(),
+            Some("public, max-age=31536000, immutable")
+        );
+        assert_eq!(assets[1].content_type, "application/manifest+json");
+        assert_eq!(assets[1].cache_control, None);
+    }
+
+    #[test]
+    fn test_fingerprint_key() {
+        let hash = "1a2b3c4d5e6f";
+
+        assert_eq!(fingerprint_key("app.css", hash), "app.1a2b3c4d.css");
+        assert_eq!(
+            fingerprint_key("js/app.min.js", hash),
+            "js/app.min.1a2b3c4d.js"
+        );
+        assert_eq!(fingerprint_key("LICENSE", hash), "LICENSE.1a2b3c4d");
+        assert_eq!(fingerprint_key("img/.hidden", hash), "img/.hidden.1a2b3c4d");
+
+        assert!(should_fingerprint("css/app.css"));
+        assert!(!should_fingerprint("index.html"));
+        assert!(!should_fingerprint(".well-known/security.txt"));
+    }
+
+    #[test]
+    fn test_fingerprint_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.css"), "body {}").unwrap();
+        std::fs::write(dir.path().join("index.html"), "<html>").unwrap();
+
+        let sources = vec![AssetSource {
+            dir: dir.path().to_path_buf(),
+            mount: String::new(),
+        }];
+
+        let mut assets = collect_assets(&sources, UploadPolicy::default()).unwrap();
+        let css_hash = assets[0].sha256_hex.clone();
+
+        let (renamed, manifest) = fingerprint_assets(&mut assets).unwrap();
+
+        assert_eq!(renamed, 1);
+        assert_eq!(assets[0].key, format!("app.{}.css", &css_hash[..8]));
+        assert_eq!(assets[1].key, "index.html");
+        assert_eq!(manifest.key, FINGERPRINT_MANIFEST);
+
+        let mapping: BTreeMap<String, String> =
+            serde_json::from_str(&std::fs::read_to_string(&manifest.path).unwrap()).unwrap();
+        assert_eq!(mapping["app.css"], assets[0].key);
+
+        std::fs::remove_file(&manifest.path).ok();
     }
 }
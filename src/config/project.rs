@@ -0,0 +1,296 @@
+//! Project manifest (`ow.toml`, or `openworkers.toml`), read from the
+//! current directory.
+
+use crate::commands::glob_match;
+use serde::Deserialize;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const MANIFEST_FILE: &str = "ow.toml";
+
+/// Accepted manifest names, by precedence
+const MANIFEST_FILES: [&str; 2] = [MANIFEST_FILE, "openworkers.toml"];
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("Failed to read ow.toml: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid ow.toml: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Manifest {
+    /// Worker `ow deploy` deploys to (and the default of `workers deploy`)
+    pub name: Option<String>,
+
+    /// What `ow deploy` deploys: a script (.js, .ts, .wasm), or a built
+    /// folder or .zip holding worker.js
+    pub main: Option<PathBuf>,
+
+    /// Alias `ow deploy` runs against when no alias prefix is given
+    pub alias: Option<String>,
+
+    /// Environment this project deploys to. Deploys must target it through
+    /// `--env` or an alias of the same name.
+    pub environment: Option<String>,
+
+    /// Schema (`env.schema.json`) the worker's linked environment must
+    /// match before each deploy
+    pub env_schema: Option<String>,
+
+    #[serde(default)]
+    pub assets: AssetsSection,
+
+    /// `KEY = { type = "kv", resource = "my-cache" }`, set on `environment`
+    /// by `ow deploy`
+    #[serde(default)]
+    pub bindings: BTreeMap<String, Binding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Binding {
+    /// assets, storage, kv or database
+    #[serde(rename = "type")]
+    pub binding_type: String,
+    pub resource: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AssetsSection {
+    /// Asset directory uploaded with a folder deploy (replaces `assets/`)
+    pub dir: Option<PathBuf>,
+
+    /// `"glob" = { content_type, cache_control, exclude }`, in file order
+    #[serde(default, deserialize_with = "ordered_rules")]
+    pub rules: Vec<AssetRule>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOptions {
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    #[serde(default)]
+    pub exclude: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetRule {
+    pub pattern: String,
+    pub options: RuleOptions,
+}
+
+impl Manifest {
+    /// Load `ow.toml` (or `openworkers.toml`) from the current directory,
+    /// if there is one
+    pub fn find() -> Result<Option<Self>, ManifestError> {
+        match MANIFEST_FILES.iter().map(Path::new).find(|p| p.exists()) {
+            Some(path) => Self::load(path),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, ManifestError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+
+        Self::parse(&content).map(Some).inspect_err(|e| {
+            if let ManifestError::Toml(e) = e {
+                let line = e.span().map(|span| line_at(&content, span.start));
+                crate::github::error_at(&path.display().to_string(), line, e.message());
+            }
+        })
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ManifestError> {
+        Ok(toml::from_str(content)?)
+    }
+}
+
+impl AssetsSection {
+    /// Combine every rule matching an asset key. Rules apply in file order,
+    /// so a later rule overrides the fields an earlier one set.
+    /// `*` also matches across directories, and a leading `/` is ignored.
+    pub fn resolve(&self, key: &str) -> RuleOptions {
+        let mut resolved = RuleOptions::default();
+
+        for rule in &self.rules {
+            if !glob_match(rule.pattern.trim_start_matches('/'), key) {
+                continue;
+            }
+
+            let options = rule.options.clone();
+            resolved.content_type = options.content_type.or(resolved.content_type);
+            resolved.cache_control = options.cache_control.or(resolved.cache_control);
+            resolved.exclude |= options.exclude;
+        }
+
+        resolved
+    }
+}
+
+/// 1-based line of a byte offset
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Deserialize a table of rules without losing the order they were written in
+fn ordered_rules<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<AssetRule>, D::Error> {
+    struct RulesVisitor;
+
+    impl<'de> Visitor<'de> for RulesVisitor {
+        type Value = Vec<AssetRule>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a table of glob patterns to rules")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut rules = Vec::new();
+
+            while let Some((pattern, options)) = map.next_entry::<String, RuleOptions>()? {
+                rules.push(AssetRule { pattern, options });
+            }
+
+            Ok(rules)
+        }
+    }
+
+    deserializer.deserialize_map(RulesVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        [assets.rules]
+        "*.webmanifest" = { content_type = "application/manifest+json" }
+        "*.wasm.br" = { content_type = "application/wasm" }
+        "/immutable/*" = { cache_control = "public, max-age=31536000, immutable" }
+        "immutable/legacy/*" = { cache_control = "no-cache" }
+        "*.map" = { exclude = true }
+    "#;
+
+    #[test]
+    fn test_parse_keeps_rule_order() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        let patterns: Vec<_> = manifest
+            .assets
+            .rules
+            .iter()
+            .map(|r| r.pattern.as_str())
+            .collect();
+
+        assert_eq!(
+            patterns,
+            vec![
+                "*.webmanifest",
+                "*.wasm.br",
+                "/immutable/*",
+                "immutable/legacy/*",
+                "*.map"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let assets = Manifest::parse(MANIFEST).unwrap().assets;
+
+        let manifest = assets.resolve("icons/site.webmanifest");
+        assert_eq!(
+            manifest.content_type.as_deref(),
+            Some("application/manifest+json")
+        );
+        assert_eq!(manifest.cache_control, None);
+
+        let legacy = assets.resolve("immutable/legacy/app.js");
+        assert_eq!(legacy.cache_control.as_deref(), Some("no-cache"));
+
+        let sourcemap = assets.resolve("immutable/app.js.map");
+        assert!(sourcemap.exclude);
+        assert!(sourcemap.cache_control.is_some());
+
+        assert_eq!(assets.resolve("index.html"), RuleOptions::default());
+    }
+
+    #[test]
+    fn test_project() {
+        let manifest = Manifest::parse(
+            r#"
+            name = "my-api"
+            main = "dist"
+            alias = "prod"
+            environment = "production"
+
+            [assets]
+            dir = "dist/static"
+
+            [bindings]
+            CACHE = { type = "kv", resource = "my-cache" }
+            ASSETS = { type = "assets", resource = "my-storage" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("my-api"));
+        assert_eq!(manifest.main, Some(PathBuf::from("dist")));
+        assert_eq!(manifest.alias.as_deref(), Some("prod"));
+        assert_eq!(manifest.assets.dir, Some(PathBuf::from("dist/static")));
+        assert!(manifest.assets.rules.is_empty());
+        assert_eq!(
+            manifest.bindings.get("CACHE"),
+            Some(&Binding {
+                binding_type: "kv".to_string(),
+                resource: "my-cache".to_string(),
+            })
+        );
+
+        let result = Manifest::parse("[bindings]\nCACHE = { kv = \"my-cache\" }\n");
+        assert!(matches!(result, Err(ManifestError::Toml(_))));
+    }
+
+    #[test]
+    fn test_unknown_rule_field() {
+        let result = Manifest::parse("[assets.rules]\n\"*.js\" = { contenttype = \"x\" }\n");
+        assert!(matches!(result, Err(ManifestError::Toml(_))));
+    }
+
+    #[test]
+    fn test_error_line() {
+        let content =
+            "environment = \"prod\"\n\n[assets.rules]\n\"*.js\" = { contenttype = \"x\" }\n";
+        let Err(ManifestError::Toml(e)) = Manifest::parse(content) else {
+            panic!("expected a toml error");
+        };
+
+        assert_eq!(e.span().map(|span| line_at(content, span.start)), Some(4));
+    }
+
+    #[test]
+    fn test_empty_manifest() {
+        let manifest = Manifest::parse("").unwrap();
+        assert!(manifest.assets.rules.is_empty());
+        assert!(manifest.environment.is_none());
+    }
+
+    #[test]
+    fn test_environment() {
+        let manifest = Manifest::parse("environment = \"prod\"\n").unwrap();
+        assert_eq!(manifest.environment.as_deref(), Some("prod"));
+        assert!(manifest.env_schema.is_none());
+
+        let manifest = Manifest::parse("env_schema = \"env.schema.json\"\n").unwrap();
+        assert_eq!(manifest.env_schema.as_deref(), Some("env.schema.json"));
+    }
+}
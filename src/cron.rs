@@ -0,0 +1,430 @@
+//! Cron expressions: the five standard fields (minute, hour, day of month,
+//! month, day of week) plus the `@hourly`-style macros, and their next
+//! execution times.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+    Utc,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// How far ahead `Schedule::next_naive` looks before deciding a schedule
+/// never fires (covers Feb 29 on a leap year)
+const SEARCH_YEARS: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    /// Day of month and day of week both restricted: either may match
+    either_day: bool,
+}
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+
+const DAY: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ],
+};
+
+// 7 is accepted for Sunday and folded onto 0
+const WEEKDAY: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+};
+
+impl Field {
+    fn value(&self, s: &str) -> Result<u32, String> {
+        let upper = s.to_ascii_uppercase();
+
+        let value = match self.names.iter().position(|n| *n == upper) {
+            Some(index) => index as u32 + self.min,
+            None => s
+                .parse()
+                .map_err(|_| format!("invalid {} '{}'", self.name, s))?,
+        };
+
+        if value < self.min || value > self.max {
+            return Err(format!(
+                "{} {} out of range {}-{}",
+                self.name, value, self.min, self.max
+            ));
+        }
+
+        Ok(value)
+    }
+
+    /// Values matched by a field such as `*/15`, `1-5` or `MON,WED,FRI`,
+    /// indexed by value
+    fn parse(&self, s: &str) -> Result<Vec<bool>, String> {
+        let mut matched = vec![false; self.max as usize + 1];
+
+        for part in s.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .ok()
+                        .filter(|s| *s > 0)
+                        .ok_or_else(|| format!("invalid step '{}' in {}", step, self.name))?;
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = match range.split_once('-') {
+                _ if range == "*" => (self.min, self.max),
+                Some((start, end)) => (self.value(start)?, self.value(end)?),
+                // `5/10` runs from 5 to the end of the range
+                None if step > 1 => (self.value(range)?, self.max),
+                None => {
+                    let value = self.value(range)?;
+                    (value, value)
+                }
+            };
+
+            if start > end {
+                return Err(format!("invalid {} range '{}'", self.name, range));
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                matched[value as usize] = true;
+            }
+        }
+
+        Ok(matched)
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expanded = match s.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+
+        let mut weekdays = WEEKDAY.parse(weekday)?;
+
+        if weekdays[7] {
+            weekdays[0] = true;
+        }
+        weekdays.truncate(7);
+
+        let schedule = Self {
+            minutes: MINUTE.parse(minute)?,
+            hours: HOUR.parse(hour)?,
+            days: DAY.parse(day)?,
+            months: MONTH.parse(month)?,
+            weekdays,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        };
+
+        if !schedule.can_fire() {
+            return Err(format!("'{}' never fires", s.trim()));
+        }
+
+        Ok(schedule)
+    }
+}
+
+impl Schedule {
+    /// Some month is long enough for a matched day of month. Days of week
+    /// always come around, so only a day-of-month-only schedule can miss.
+    fn can_fire(&self) -> bool {
+        if self.either_day || self.days.iter().skip(1).all(|d| *d) {
+            return true;
+        }
+
+        (1..=12).filter(|m| self.months[*m]).any(|month| {
+            let longest = match month {
+                2 => 29,
+                4 | 6 | 9 | 11 => 30,
+                _ => 31,
+            };
+
+            (1..=longest).any(|day| self.days[day])
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days[date.day() as usize];
+        let weekday = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+
+        if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First wall-clock minute strictly after `after` matching the schedule
+    fn next_naive(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after.year() + SEARCH_YEARS;
+
+        while t.year() <= limit {
+            let midnight = t.date().and_hms_opt(0, 0, 0)?;
+
+            if !self.months[t.month() as usize] {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    month => (t.year(), month + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = midnight + Duration::days(1);
+            } else if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !self.minutes[t.minute() as usize] {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+
+        None
+    }
+
+    /// Next `count` execution times after `after`, in the time zone `tz`.
+    /// Wall-clock times skipped by a DST change are left out.
+    pub fn upcoming(
+        &self,
+        after: DateTime<Utc>,
+        count: usize,
+        tz: Tz,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let mut times = Vec::with_capacity(count);
+        let mut cursor = tz.to_naive(after);
+
+        while times.len() < count {
+            let Some(next) = self.next_naive(cursor) else {
+                break;
+            };
+
+            if let Some(at) = tz.resolve(next) {
+                times.push(at);
+            }
+
+            cursor = next;
+        }
+
+        times
+    }
+}
+
+/// Time zone of a schedule preview: the system one, or a fixed offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tz {
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl FromStr for Tz {
+    type Err = String;
+
+    /// `local`, `UTC`/`Z`, or an offset such as `+02:00`, `-0530` or `+9`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid time zone '{}', expected local, UTC or an offset like +02:00",
+                s
+            )
+        };
+
+        match s.to_ascii_uppercase().as_str() {
+            "LOCAL" => return Ok(Self::Local),
+            "UTC" | "Z" | "GMT" => return Ok(Self::Fixed(FixedOffset::east_opt(0).unwrap())),
+            _ => {}
+        }
+
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'+') => (1, &s[1..]),
+            Some(b'-') => (-1, &s[1..]),
+            _ => return Err(invalid()),
+        };
+
+        let digits = rest.replace(':', "");
+
+        let (hours, minutes) = match digits.len() {
+            1 | 2 => (digits.as_str(), "0"),
+            4 => digits.split_at(2),
+            _ => return Err(invalid()),
+        };
+
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+
+        if minutes >= 60 {
+            return Err(invalid());
+        }
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Self::Fixed)
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for Tz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local time"),
+            Self::Fixed(offset) if offset.local_minus_utc() == 0 => write!(f, "UTC"),
+            Self::Fixed(offset) => write!(f, "UTC{}", offset),
+        }
+    }
+}
+
+impl Tz {
+    fn to_naive(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Self::Local => at.with_timezone(&Local).naive_local(),
+            Self::Fixed(offset) => at.with_timezone(&offset).naive_local(),
+        }
+    }
+
+    fn resolve(self, at: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Local => Local
+                .from_local_datetime(&at)
+                .earliest()
+                .map(|at| at.fixed_offset()),
+            Self::Fixed(offset) => offset.from_local_datetime(&at).single(),
+        }
+    }
+}
+
+/// `value_parser` for arguments taking a cron expression: rejects malformed
+/// or never-firing expressions and keeps the text as written
+pub fn validate(s: &str) -> Result<String, String> {
+    s.parse::<Schedule>()?;
+    Ok(s.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc() -> Tz {
+        "UTC".parse().unwrap()
+    }
+
+    fn upcoming(expr: &str, after: &str, count: usize) -> Vec<String> {
+        let schedule: Schedule = expr.parse().unwrap();
+
+        schedule
+            .upcoming(after.parse().unwrap(), count, utc())
+            .iter()
+            .map(|at| at.format("%Y-%m-%d %H:%M %a").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_upcoming() {
+        assert_eq!(
+            upcoming("*/20 9-10 * * MON-FRI", "2026-01-02T10:30:00Z", 3),
+            vec![
+                "2026-01-02 10:40 Fri",
+                "2026-01-05 09:00 Mon",
+                "2026-01-05 09:20 Mon"
+            ]
+        );
+
+        assert_eq!(
+            upcoming("@monthly", "2026-01-31T23:59:30Z", 2),
+            vec!["2026-02-01 00:00 Sun", "2026-03-01 00:00 Sun"]
+        );
+
+        // Both days restricted: either matches
+        assert_eq!(
+            upcoming("0 12 13 * 5", "2026-02-10T00:00:00Z", 2),
+            vec!["2026-02-13 12:00 Fri", "2026-02-20 12:00 Fri"]
+        );
+
+        assert_eq!(
+            upcoming("0 0 29 2 *", "2026-01-01T00:00:00Z", 1),
+            vec!["2028-02-29 00:00 Tue"]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * FOO *",
+            "0 0 31 2,4 *",
+        ] {
+            assert!(expr.parse::<Schedule>().is_err(), "{}", expr);
+        }
+
+        assert_eq!(validate(" 0 * * * * ").unwrap(), "0 * * * *");
+        assert!(validate("0 0 30 2 *").unwrap_err().contains("never fires"));
+    }
+
+    #[test]
+    fn test_tz() {
+        let paris: Tz = "+02:00".parse().unwrap();
+        let schedule: Schedule = "0 9 * * *".parse().unwrap();
+        let next = schedule.upcoming("2026-06-01T08:00:00Z".parse().unwrap(), 1, paris);
+
+        assert_eq!(next[0].to_rfc3339(), "2026-06-02T09:00:00+02:00");
+        assert_eq!(paris.to_string(), "UTC+02:00");
+        assert_eq!("-0530".parse::<Tz>().unwrap().to_string(), "UTC-05:30");
+        assert_eq!("local".parse::<Tz>().unwrap(), Tz::Local);
+        assert!("Europe/Paris".parse::<Tz>().is_err());
+    }
+}
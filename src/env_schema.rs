@@ -0,0 +1,243 @@
+//! Environment schema (`env.schema.json`): the keys an environment must
+//! define, their type and a pattern their value must match.
+
+use crate::backend::EnvironmentValue;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+pub const SCHEMA_FILE: &str = "env.schema.json";
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("Failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+
+    #[error("Invalid schema: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid pattern for '{key}': {error}")]
+    Pattern { key: String, error: regex::Error },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EnvSchema {
+    #[serde(default)]
+    pub keys: BTreeMap<String, KeyRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRule {
+    /// var, secret, kv, storage, assets or database; any type when unset
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+
+    #[serde(default = "required_default")]
+    pub required: bool,
+
+    /// Regex the value must match (not checked for secrets, whose values
+    /// the CLI may not see)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn required_default() -> bool {
+    true
+}
+
+/// One way an environment breaks the schema
+#[derive(Debug, PartialEq)]
+pub struct SchemaIssue {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+impl EnvSchema {
+    pub fn load(path: &Path) -> Result<Self, SchemaError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SchemaError::Io(path.display().to_string(), e))?;
+
+        Self::parse(&content)
+    }
+
+    /// Parse a schema, rejecting patterns that don't compile
+    pub fn parse(content: &str) -> Result<Self, SchemaError> {
+        let schema: Self = serde_json::from_str(content)?;
+
+        for (key, rule) in &schema.keys {
+            if let Some(pattern) = &rule.pattern {
+                Regex::new(pattern).map_err(|error| SchemaError::Pattern {
+                    key: key.clone(),
+                    error,
+                })?;
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Schema requiring every key of an environment, with its current type
+    pub fn from_values(values: &[EnvironmentValue]) -> Self {
+        let keys = values
+            .iter()
+            .map(|value| {
+                let rule = KeyRule {
+                    value_type: Some(value.value_type.clone()),
+                    required: true,
+                    pattern: None,
+                    description: None,
+                };
+
+                (value.key.clone(), rule)
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Issues of an environment against the schema, in key order. Keys the
+    /// schema doesn't mention are allowed.
+    pub fn validate(&self, values: &[EnvironmentValue]) -> Vec<SchemaIssue> {
+        let mut issues = Vec::new();
+
+        for (key, rule) in &self.keys {
+            let issue = |message: String| SchemaIssue {
+                key: key.clone(),
+                message,
+            };
+
+            let Some(value) = values.iter().find(|v| v.key == *key) else {
+                if rule.required {
+                    issues.push(issue("missing".to_string()));
+                }
+                continue;
+            };
+
+            let expected = rule.value_type.as_deref();
+
+            if let Some(expected) = expected.filter(|t| *t != value.value_type) {
+                issues.push(issue(format!(
+                    "expected {}, found {}",
+                    expected, value.value_type
+                )));
+                continue;
+            }
+
+            if value.value_type == "secret" {
+                continue;
+            }
+
+            let pattern = rule.pattern.as_deref().and_then(|p| Regex::new(p).ok());
+
+            if let Some(pattern) = pattern.filter(|p| !p.is_match(&value.value)) {
+                issues.push(issue(format!(
+                    "'{}' does not match /{}/",
+                    value.value,
+                    pattern.as_str()
+                )));
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(key: &str, value: &str, value_type: &str) -> EnvironmentValue {
+        EnvironmentValue {
+            id: key.to_lowercase(),
+            key: key.to_string(),
+            value: value.to_string(),
+            value_type: value_type.to_string(),
+            rotated_at: None,
+        }
+    }
+
+    const SCHEMA: &str = r#"{
+        "keys": {
+            "STRIPE_KEY": { "type": "secret" },
+            "API_URL": { "type": "var", "pattern": "^https://" },
+            "CACHE": { "type": "kv", "required": false },
+            "DEBUG": { "required": false, "pattern": "^(on|off)$" }
+        }
+    }"#;
+
+    #[test]
+    fn test_validate() {
+        let schema = EnvSchema::parse(SCHEMA).unwrap();
+
+        let values = vec![
+            value("API_URL", "http://example.com", "var"),
+            value("CACHE", "cache-ns", "var"),
+            value("EXTRA", "1", "var"),
+        ];
+
+        let issues: Vec<String> = schema
+            .validate(&values)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            issues,
+            vec![
+                "API_URL: 'http://example.com' does not match /^https:///",
+                "CACHE: expected kv, found var",
+                "STRIPE_KEY: missing",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_environment() {
+        let schema = EnvSchema::parse(SCHEMA).unwrap();
+
+        let values = vec![
+            value("STRIPE_KEY", "****", "secret"),
+            value("API_URL", "https://api.example.com", "var"),
+            value("DEBUG", "off", "var"),
+        ];
+
+        assert!(schema.validate(&values).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let result = EnvSchema::parse(r#"{ "keys": { "A": { "pattern": "(" } } }"#);
+        assert!(matches!(result, Err(SchemaError::Pattern { key, .. }) if key == "A"));
+
+        let result = EnvSchema::parse(r#"{ "keys": { "A": { "typ": "var" } } }"#);
+        assert!(matches!(result, Err(SchemaError::Json(_))));
+    }
+
+    #[test]
+    fn test_from_values_roundtrip() {
+        let values = vec![
+            value("API_URL", "https://api.example.com", "var"),
+            value("STRIPE_KEY", "sk", "secret"),
+        ];
+
+        let json = serde_json::to_string(&EnvSchema::from_values(&values)).unwrap();
+        let schema = EnvSchema::parse(&json).unwrap();
+
+        assert_eq!(schema.keys.len(), 2);
+        assert!(schema.validate(&values).is_empty());
+        assert_eq!(schema.validate(&values[..1])[0].key, "STRIPE_KEY");
+    }
+}
@@ -0,0 +1,130 @@
+//! Process exit codes, one per error class, so scripts can tell a missing
+//! resource from an outage and retry only the latter. They are listed in the
+//! README: a code never changes meaning once released.
+
+use crate::backend::BackendError;
+
+/// Any failure not covered below
+pub const FAILURE: i32 = 1;
+/// Bad arguments or configuration (clap also exits with 2 on its own errors)
+pub const USAGE: i32 = 2;
+/// The worker, environment or other resource does not exist
+pub const NOT_FOUND: i32 = 3;
+/// Missing, invalid or insufficient credentials
+pub const UNAUTHORIZED: i32 = 4;
+/// Database or API unreachable, timed out or overloaded: worth retrying
+pub const UNAVAILABLE: i32 = 5;
+/// Some items of a batch failed, the others went through
+pub const PARTIAL: i32 = 6;
+/// Stopped with Ctrl-C
+pub const INTERRUPTED: i32 = 130;
+
+/// Why a command failed: the message printed on stderr and the exit code
+#[derive(Debug)]
+pub struct Failure {
+    pub code: i32,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self::new(FAILURE, message)
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Self::new(FAILURE, message)
+    }
+}
+
+impl From<BackendError> for Failure {
+    fn from(e: BackendError) -> Self {
+        let code = backend_code(&e);
+
+        let message = match e {
+            BackendError::NotFound(msg) => msg,
+            BackendError::Unauthorized => "Unauthorized. Check your token.".to_string(),
+            BackendError::Database(sqlx::Error::Database(ref db))
+                if db.message().contains("statement timeout") =>
+            {
+                format!(
+                    "{} (after {}s, see --statement-timeout)",
+                    e,
+                    crate::backend::db::statement_timeout().as_secs()
+                )
+            }
+            _ => e.to_string(),
+        };
+
+        Self::new(code, message)
+    }
+}
+
+/// Exit code for a backend error
+pub fn backend_code(e: &BackendError) -> i32 {
+    match e {
+        BackendError::NotFound(_) => NOT_FOUND,
+        BackendError::Unauthorized => UNAUTHORIZED,
+        BackendError::Unavailable(_) => UNAVAILABLE,
+        BackendError::Partial(_) => PARTIAL,
+        BackendError::Usage(_) => USAGE,
+        BackendError::Http(e) if e.is_connect() || e.is_timeout() => UNAVAILABLE,
+        BackendError::Http(e) if e.status().is_some_and(|s| s.is_server_error()) => UNAVAILABLE,
+        BackendError::Database(e) if is_unavailable(e) => UNAVAILABLE,
+        _ => FAILURE,
+    }
+}
+
+fn is_unavailable(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        // SQLSTATE classes 08 (connection exception), 53 (insufficient
+        // resources) and 57P (server shutting down or starting up)
+        sqlx::Error::Database(db) => db
+            .code()
+            .is_some_and(|c| c.starts_with("08") || c.starts_with("53") || c.starts_with("57P")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_code() {
+        let code = |e: BackendError| backend_code(&e);
+
+        assert_eq!(code(BackendError::NotFound("api".to_string())), NOT_FOUND);
+        assert_eq!(code(BackendError::Unauthorized), UNAUTHORIZED);
+        assert_eq!(
+            code(BackendError::Unavailable("503".to_string())),
+            UNAVAILABLE
+        );
+        assert_eq!(code(BackendError::Partial("1 of 3".to_string())), PARTIAL);
+        assert_eq!(code(BackendError::Usage("no worker".to_string())), USAGE);
+        assert_eq!(
+            code(BackendError::Database(sqlx::Error::PoolTimedOut)),
+            UNAVAILABLE
+        );
+        assert_eq!(
+            code(BackendError::Database(sqlx::Error::RowNotFound)),
+            FAILURE
+        );
+        assert_eq!(code(BackendError::Api("bad input".to_string())), FAILURE);
+    }
+}
@@ -0,0 +1,146 @@
+//! Run a command against every alias of a group (`ow all-prod workers list`).
+//!
+//! Like the pager, the command is re-run as child processes, one per member
+//! alias and all at once. Their output is printed in member order, each line
+//! prefixed with the alias it came from.
+
+use crate::exit;
+use colored::Colorize;
+use std::io::IsTerminal;
+use std::process::{Command, Output, Stdio};
+
+/// Prefix each line of `output` with a label padded to `width`
+fn label_lines(label: &str, width: usize, output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    let prefix = format!("{:width$} │", label, width = width);
+
+    text.lines()
+        .map(|line| format!("{} {}\n", prefix.cyan(), line))
+        .collect()
+}
+
+fn run_member(alias: &str, args: &[String], color: bool) -> std::io::Result<Output> {
+    let exe = std::env::current_exe()?;
+
+    let mut child = Command::new(exe);
+    child
+        .arg(alias)
+        .args(args)
+        // Children must not prompt or page: there's no terminal to answer
+        .stdin(Stdio::null())
+        .env("OW_PAGER", "");
+
+    if color {
+        child.env("CLICOLOR_FORCE", "1");
+    }
+
+    child.output()
+}
+
+/// Exit code of the group from its failed members' codes: partial failure
+/// when some members succeeded, else their common code if they agree
+fn group_code(failed: &[i32], members: usize) -> i32 {
+    match failed {
+        [] => 0,
+        _ if failed.len() < members => exit::PARTIAL,
+        [first, rest @ ..] if rest.iter().all(|c| c == first) => *first,
+        _ => exit::FAILURE,
+    }
+}
+
+/// Run `args` (without the program name) against each member alias.
+/// Returns the exit code: 0 when every member succeeded.
+pub fn run(group: &str, members: &[String], args: &[String]) -> i32 {
+    if members.is_empty() {
+        eprintln!("{} Group '{}' has no aliases", "error:".red().bold(), group);
+        return exit::USAGE;
+    }
+
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let width = members.iter().map(String::len).max().unwrap_or(0);
+
+    let handles: Vec<_> = members
+        .iter()
+        .map(|alias| {
+            let alias = alias.clone();
+            let args = args.to_vec();
+            std::thread::spawn(move || run_member(&alias, &args, color))
+        })
+        .collect();
+
+    let mut failed = Vec::new();
+
+    for (alias, handle) in members.iter().zip(handles) {
+        let result = handle
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("worker thread panicked")));
+
+        match result {
+            Ok(output) => {
+                print!("{}", label_lines(alias, width, &output.stdout));
+                eprint!("{}", label_lines(alias, width, &output.stderr));
+
+                if !output.status.success() {
+                    let code = output.status.code().unwrap_or(exit::FAILURE);
+                    failed.push((alias.as_str(), code));
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} {}",
+                    format!("{:width$} │", alias, width = width).cyan(),
+                    format!("failed to run: {}", e).red()
+                );
+                failed.push((alias.as_str(), exit::FAILURE));
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        return 0;
+    }
+
+    eprintln!(
+        "{} {} of {} aliases in '{}' failed: {}",
+        "error:".red().bold(),
+        failed.len(),
+        members.len(),
+        group,
+        failed
+            .iter()
+            .map(|(alias, _)| *alias)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let codes: Vec<i32> = failed.iter().map(|(_, code)| *code).collect();
+    group_code(&codes, members.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_lines() {
+        let labelled = label_lines("eu", 7, b"api\nweb\n");
+        let lines: Vec<&str> = labelled.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("eu      │") && lines[0].ends_with(" api"));
+        assert!(lines[1].ends_with(" web"));
+
+        assert_eq!(label_lines("eu", 2, b""), "");
+    }
+
+    #[test]
+    fn test_group_code() {
+        assert_eq!(group_code(&[], 3), 0);
+        assert_eq!(group_code(&[exit::NOT_FOUND], 3), exit::PARTIAL);
+        assert_eq!(group_code(&[exit::UNAVAILABLE; 2], 2), exit::UNAVAILABLE);
+        assert_eq!(
+            group_code(&[exit::UNAVAILABLE, exit::NOT_FOUND], 2),
+            exit::FAILURE
+        );
+    }
+}
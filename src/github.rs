@@ -0,0 +1,125 @@
+//! GitHub Actions workflow commands (`::error`, `::group::`), so deploy
+//! failures show up in the Checks UI instead of only in the raw log.
+//!
+//! Everything here is a no-op unless `GITHUB_ACTIONS=true`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once an error was annotated with its file, so the final error in
+/// `main` doesn't annotate the same failure twice.
+static ANNOTATED: AtomicBool = AtomicBool::new(false);
+
+pub fn enabled() -> bool {
+    std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true")
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// `::name file=...,line=...::message`
+fn command(name: &str, file: Option<&str>, line: Option<usize>, message: &str) -> String {
+    let mut properties = Vec::new();
+
+    if let Some(file) = file {
+        properties.push(format!("file={}", escape_property(file)));
+    }
+
+    if let Some(line) = line {
+        properties.push(format!("line={}", line));
+    }
+
+    if properties.is_empty() {
+        format!("::{}::{}", name, escape_data(message))
+    } else {
+        format!(
+            "::{} {}::{}",
+            name,
+            properties.join(","),
+            escape_data(message)
+        )
+    }
+}
+
+/// Annotate a failure, unless one was already annotated with its location
+pub fn error(message: &str) {
+    if enabled() && !ANNOTATED.load(Ordering::Relaxed) {
+        println!("{}", command("error", None, None, message));
+    }
+}
+
+/// Annotate a failure at a line of a project file
+pub fn error_at(file: &str, line: Option<usize>, message: &str) {
+    if enabled() {
+        ANNOTATED.store(true, Ordering::Relaxed);
+        println!("{}", command("error", Some(file), line, message));
+    }
+}
+
+pub fn warning(message: &str) {
+    if enabled() {
+        println!("{}", command("warning", None, None, message));
+    }
+}
+
+pub fn notice(message: &str) {
+    if enabled() {
+        println!("{}", command("notice", None, None, message));
+    }
+}
+
+/// Collapsible section of the log, closed when dropped
+pub struct Group(bool);
+
+pub fn group(title: &str) -> Group {
+    let enabled = enabled();
+
+    if enabled {
+        println!("::group::{}", escape_data(title));
+    }
+
+    Group(enabled)
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        if self.0 {
+            println!("::endgroup::");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command() {
+        assert_eq!(
+            command("error", None, None, "Worker 'api' not found"),
+            "::error::Worker 'api' not found"
+        );
+
+        assert_eq!(
+            command(
+                "error",
+                Some("dist/_routes.json"),
+                Some(3),
+                "100% bad\nroute"
+            ),
+            "::error file=dist/_routes.json,line=3::100%25 bad%0Aroute"
+        );
+
+        assert_eq!(
+            command("notice", Some("C:\\a,b"), None, "ok\r"),
+            "::notice file=C%3A\\a%2Cb::ok%0D"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Minimal HTTP/1.1 server side: read requests and write responses on a raw
+//! stream, enough for the local proxies of `ow daemon` and `ow dev`.
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Largest request body read into memory, well above what a deploy uploads
+pub const MAX_BODY_SIZE: usize = 128 * 1024 * 1024;
+
+/// Headers that only make sense on one hop
+pub const HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "transfer-encoding",
+    "content-length",
+    "host",
+    "upgrade",
+];
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn error(status: u16, message: String) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            body: message.into_bytes(),
+        }
+    }
+}
+
+/// Send a request on to `url` and read back the whole response, dropping
+/// hop-by-hop headers both ways. Upstream failures become a 502.
+pub async fn forward(client: &reqwest::Client, url: &str, request: Request) -> Response {
+    let Ok(method) = reqwest::Method::from_bytes(request.method.as_bytes()) else {
+        return Response::error(400, format!("Invalid method {}", request.method));
+    };
+
+    let mut upstream = client.request(method, url);
+
+    for (name, value) in &request.headers {
+        let name = name.to_ascii_lowercase();
+
+        if HOP_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+
+        upstream = upstream.header(name, value);
+    }
+
+    let response = match upstream.body(request.body).send().await {
+        Ok(response) => response,
+        Err(e) => return Response::error(502, e.to_string()),
+    };
+
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| !HOP_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+
+    match response.bytes().await {
+        Ok(body) => Response {
+            status,
+            headers,
+            body: body.to_vec(),
+        },
+        Err(e) => Response::error(502, e.to_string()),
+    }
+}
+
+/// Read one HTTP/1.1 request, or None when the client closed the connection.
+/// A body above [`MAX_BODY_SIZE`] is an error, see [`reject`].
+pub async fn read_request<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<Request>> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut headers = Vec::new();
+
+    loop {
+        line.clear();
+
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(invalid("connection closed in headers"));
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| invalid("bad header"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = Request {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+
+    let chunked = request
+        .header("transfer-encoding")
+        .is_some_and(|v| v.eq_ignore_ascii_case("chunked"));
+
+    if chunked {
+        request.body = read_chunked(reader).await?;
+    } else if let Some(length) = request.header("content-length") {
+        let length: usize = length.parse().map_err(|_| invalid("bad content-length"))?;
+
+        if length > MAX_BODY_SIZE {
+            return Err(too_large());
+        }
+
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body).await?;
+    }
+
+    Ok(Some(request))
+}
+
+async fn read_chunked<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        reader.read_line(&mut line).await?;
+
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16).map_err(|_| invalid("bad chunk size"))?;
+
+        if size == 0 {
+            // Trailers, up to the final empty line
+            loop {
+                line.clear();
+
+                if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                    return Ok(body);
+                }
+            }
+        }
+
+        if size > MAX_BODY_SIZE - body.len() {
+            return Err(too_large());
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..]).await?;
+
+        // CRLF after the chunk data
+        line.clear();
+        reader.read_line(&mut line).await?;
+    }
+}
+
+pub async fn write_response<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    response: &Response,
+) -> std::io::Result<()> {
+    let reason = reqwest::StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, reason);
+
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    head.push_str(&format!("content-length: {}\r\n\r\n", response.body.len()));
+
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(&response.body).await?;
+    writer.flush().await
+}
+
+/// Answer a request that failed to read: 413 for an oversized body, whose
+/// unread bytes leave the connection unusable, anything else is passed on
+pub async fn reject<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    error: std::io::Error,
+) -> std::io::Result<()> {
+    if error.kind() != std::io::ErrorKind::FileTooLarge {
+        return Err(error);
+    }
+
+    write_response(writer, &Response::error(413, error.to_string())).await
+}
+
+fn too_large() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::FileTooLarge,
+        format!("request body above {} MB", MAX_BODY_SIZE / 1024 / 1024),
+    )
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn test_read_request() {
+        let raw = b"POST /api/v1/workers HTTP/1.1\r\n\
+            x-ow-upstream: https://dash.openworkers.com\r\n\
+            content-length: 5\r\n\r\n\
+            hello\
+            GET /api/v1/workers HTTP/1.1\r\n\
+            transfer-encoding: chunked\r\n\r\n\
+            3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n";
+        let mut reader = BufReader::new(&raw[..]);
+
+        let request = read_request(&mut reader).await.unwrap().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/v1/workers");
+        assert_eq!(
+            request.header("X-Ow-Upstream"),
+            Some("https://dash.openworkers.com")
+        );
+        assert_eq!(request.body, b"hello");
+
+        let request = read_request(&mut reader).await.unwrap().unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.body, b"abcde");
+
+        assert!(read_request(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let raw = format!(
+            "POST /upload HTTP/1.1\r\ncontent-length: {}\r\n\r\n",
+            MAX_BODY_SIZE + 1
+        );
+        let err = read_request(&mut BufReader::new(raw.as_bytes()))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::FileTooLarge);
+
+        // Refused before the chunk is read
+        let raw = format!(
+            "POST /upload HTTP/1.1\r\ntransfer-encoding: chunked\r\n\r\n3\r\nabc\r\n{:x}\r\n",
+            MAX_BODY_SIZE
+        );
+        let err = read_request(&mut BufReader::new(raw.as_bytes()))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::FileTooLarge);
+
+        let mut written = Vec::new();
+        reject(&mut written, err).await.unwrap();
+        assert!(written.starts_with(b"HTTP/1.1 413 Payload Too Large\r\n"));
+
+        let err = reject(&mut Vec::new(), invalid("bad header")).await;
+        assert!(err.is_err());
+    }
+}
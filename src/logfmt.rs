@@ -0,0 +1,168 @@
+//! Rendering of `console.log` output captured from a worker.
+//!
+//! Worker code usually logs JSON lines (`{"level":"info","msg":"order placed",
+//! "id":42}`). `pretty` shows those as a colored level, the message, then the
+//! other fields as `key=value`; plain text lines keep the console level.
+//! `json` prints one object per line for `jq`, and raw passes messages through.
+
+use crate::backend::ExecLog;
+use colored::{ColoredString, Colorize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Colored level, message and fields
+    Pretty,
+    /// One JSON object per line
+    Json,
+    /// Messages exactly as logged (`--raw`)
+    #[value(skip)]
+    Raw,
+}
+
+/// Keys holding the level and the message in common structured loggers
+/// (pino, winston, bunyan-style and console wrappers)
+const LEVEL_KEYS: &[&str] = &["level", "severity", "lvl"];
+const MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+pub fn render(log: &ExecLog, format: LogFormat) -> String {
+    match format {
+        LogFormat::Raw => log.message.clone(),
+        LogFormat::Json => Value::Object(to_json(log)).to_string(),
+        LogFormat::Pretty => pretty(log),
+    }
+}
+
+/// The message's own fields when it is a JSON object, with the console level
+/// added if it has none
+fn to_json(log: &ExecLog) -> Map<String, Value> {
+    match structured(&log.message) {
+        Some(mut fields) => {
+            if !LEVEL_KEYS.iter().any(|k| fields.contains_key(*k)) {
+                fields.insert("level".to_string(), Value::String(log.level.clone()));
+            }
+            fields
+        }
+        None => {
+            let mut fields = Map::new();
+            fields.insert("level".to_string(), Value::String(log.level.clone()));
+            fields.insert("message".to_string(), Value::String(log.message.clone()));
+            fields
+        }
+    }
+}
+
+fn pretty(log: &ExecLog) -> String {
+    let Some(mut fields) = structured(&log.message) else {
+        return format!("{:6} {}", colorize(&log.level), log.message);
+    };
+
+    let level = take_first(&mut fields, LEVEL_KEYS)
+        .map(|v| level_name(&v))
+        .unwrap_or_else(|| log.level.clone());
+
+    let message = match take_first(&mut fields, MESSAGE_KEYS) {
+        Some(Value::String(s)) => s,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{}={}", key, s),
+            other => format!("{}={}", key, other),
+        })
+        .collect();
+
+    let mut line = format!("{:6} {}", colorize(&level), message);
+
+    if !fields.is_empty() {
+        line.push(' ');
+        line.push_str(&fields.join(" ").dimmed().to_string());
+    }
+
+    line
+}
+
+/// The message as a JSON object, if it is one
+fn structured(message: &str) -> Option<Map<String, Value>> {
+    let message = message.trim();
+
+    if !message.starts_with('{') {
+        return None;
+    }
+
+    match serde_json::from_str(message) {
+        Ok(Value::Object(fields)) => Some(fields),
+        _ => None,
+    }
+}
+
+fn take_first(fields: &mut Map<String, Value>, keys: &[&str]) -> Option<Value> {
+    keys.iter().find_map(|key| fields.remove(*key))
+}
+
+/// Level name from a string or a pino-style number (30 = info)
+fn level_name(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_lowercase(),
+        Value::Number(n) => match n.as_u64().unwrap_or(0) {
+            0..=10 => "trace",
+            11..=20 => "debug",
+            21..=30 => "info",
+            31..=40 => "warn",
+            41..=50 => "error",
+            _ => "fatal",
+        }
+        .to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn colorize(level: &str) -> ColoredString {
+    match level {
+        "error" | "fatal" => level.red(),
+        "warn" | "warning" => level.yellow(),
+        "debug" | "trace" => level.dimmed(),
+        _ => level.normal(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(level: &str, message: &str) -> ExecLog {
+        ExecLog {
+            level: level.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        colored::control::set_override(false);
+
+        let structured = log("log", r#"{"level":40,"msg":"slow query","ms":812}"#);
+        assert_eq!(
+            render(&structured, LogFormat::Pretty),
+            "warn   slow query ms=812"
+        );
+        assert_eq!(
+            render(&log("error", "boom"), LogFormat::Pretty),
+            "error  boom"
+        );
+
+        let json: Value =
+            serde_json::from_str(&render(&log("info", "hi"), LogFormat::Json)).unwrap();
+        assert_eq!(json["level"], "info");
+        assert_eq!(json["message"], "hi");
+
+        let json: Value = serde_json::from_str(&render(&structured, LogFormat::Json)).unwrap();
+        assert_eq!(json["level"], 40);
+
+        assert_eq!(render(&structured, LogFormat::Raw), structured.message);
+        assert!(super::structured("{not json").is_none());
+    }
+}
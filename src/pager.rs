@@ -0,0 +1,175 @@
+//! Page long `list`/`get` output through `$PAGER`, like git does.
+//!
+//! The command is re-run as a child process with its stdout captured, so the
+//! rest of the CLI can keep printing with `println!`. The pager only starts
+//! when the output is taller than the terminal; shorter output is printed as is.
+
+use clap::ArgMatches;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Overrides `$PAGER` for ow only; an empty value (or `cat`) disables paging
+const PAGER_ENV: &str = "OW_PAGER";
+
+/// Actions whose output is worth paging
+const PAGED_ACTIONS: &[&str] = &["list", "get"];
+
+/// Pager to run, from `OW_PAGER`, then `PAGER`, then `less`
+fn pager_command(ow_pager: Option<String>, pager: Option<String>) -> Option<String> {
+    let command = ow_pager.or(pager).unwrap_or_else(|| "less".to_string());
+    let command = command.trim();
+
+    if command.is_empty() || command == "cat" {
+        return None;
+    }
+
+    Some(command.to_string())
+}
+
+fn should_page(matches: &ArgMatches) -> bool {
+    let Some((_, sub)) = matches.subcommand() else {
+        return false;
+    };
+
+    let paged = sub
+        .subcommand_name()
+        .is_some_and(|action| PAGED_ACTIONS.contains(&action));
+
+    paged
+        && std::io::stdout().is_terminal()
+        && std::env::var("TERM").ok().is_none_or(|term| term != "dumb")
+}
+
+/// Terminal rows taken by `output`, counting long lines as wrapped at `width`
+/// and skipping ANSI color sequences
+fn rows(output: &str, width: usize) -> usize {
+    output
+        .lines()
+        .map(|line| {
+            let mut visible: usize = 0;
+            let mut escape = false;
+
+            for c in line.chars() {
+                match (escape, c) {
+                    (false, '\x1b') => escape = true,
+                    (true, c) if c.is_ascii_alphabetic() => escape = false,
+                    (true, _) => {}
+                    (false, _) => visible += 1,
+                }
+            }
+
+            visible.div_ceil(width.max(1)).max(1)
+        })
+        .sum()
+}
+
+/// Rows and columns of the terminal on stdout
+#[cfg(unix)]
+fn terminal_size() -> Option<(usize, usize)> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    // SAFETY: TIOCGWINSZ only writes into the winsize struct it is given
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+
+    (result == 0 && size.ws_row > 0 && size.ws_col > 0)
+        .then_some((size.ws_row as usize, size.ws_col as usize))
+}
+
+/// Re-run the current command with its output captured, then print it
+/// directly or through the pager when it doesn't fit on one screen.
+/// Returns the exit code to use, or None to run the command normally.
+#[cfg(unix)]
+pub fn run_paged(matches: &ArgMatches) -> Option<i32> {
+    if !should_page(matches) {
+        return None;
+    }
+
+    let pager = pager_command(std::env::var(PAGER_ENV).ok(), std::env::var("PAGER").ok())?;
+    let (height, width) = terminal_size()?;
+    let exe = std::env::current_exe().ok()?;
+
+    let mut child = Command::new(exe);
+    child
+        .args(std::env::args_os().skip(1))
+        .env(PAGER_ENV, "")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    // stdout is a pipe in the child, keep the colors of the terminal
+    if std::env::var_os("NO_COLOR").is_none() {
+        child.env("CLICOLOR_FORCE", "1");
+    }
+
+    let output = child.output().ok()?;
+    let code = output.status.code().unwrap_or(1);
+
+    // Keep a line free for the shell prompt, as less does
+    if rows(&String::from_utf8_lossy(&output.stdout), width) < height {
+        std::io::stdout().write_all(&output.stdout).ok();
+        return Some(code);
+    }
+
+    let mut pager_cmd = Command::new("sh");
+    pager_cmd.arg("-c").arg(&pager).stdin(Stdio::piped());
+
+    // Keep colors, don't clear the screen on exit
+    if std::env::var_os("LESS").is_none() {
+        pager_cmd.env("LESS", "RX");
+    }
+
+    let Ok(mut pager_process) = pager_cmd.spawn() else {
+        std::io::stdout().write_all(&output.stdout).ok();
+        return Some(code);
+    };
+
+    if let Some(mut stdin) = pager_process.stdin.take() {
+        // The pager may be quit before reading everything
+        stdin.write_all(&output.stdout).ok();
+    }
+
+    pager_process.wait().ok();
+
+    Some(code)
+}
+
+#[cfg(not(unix))]
+pub fn run_paged(_matches: &ArgMatches) -> Option<i32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None, None), Some("less".to_string()));
+        assert_eq!(
+            pager_command(None, Some("most".to_string())),
+            Some("most".to_string())
+        );
+        assert_eq!(
+            pager_command(Some("less -S".to_string()), Some("most".to_string())),
+            Some("less -S".to_string())
+        );
+        assert_eq!(
+            pager_command(Some(String::new()), Some("most".to_string())),
+            None
+        );
+        assert_eq!(pager_command(None, Some("cat".to_string())), None);
+    }
+
+    #[test]
+    fn test_rows() {
+        assert_eq!(rows("", 80), 0);
+        assert_eq!(rows("one\ntwo\n", 80), 2);
+        assert_eq!(rows("\n\n", 80), 2);
+        assert_eq!(rows(&"x".repeat(100), 80), 2);
+        assert_eq!(rows("\x1b[32mgreen\x1b[0m", 5), 1);
+    }
+}
@@ -0,0 +1,59 @@
+//! `--porcelain` output: tab-separated lines for scripts, kept stable across
+//! versions while the human output is free to change.
+//!
+//! The contract, like git's porcelain formats:
+//! - one record per line, fields separated by a tab;
+//! - a missing value is an empty field;
+//! - `\`, tab, newline and carriage return inside a field are written
+//!   `\\`, `\t`, `\n` and `\r`;
+//! - times are RFC 3339 in UTC;
+//! - new fields are only ever added at the end of a line.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+fn escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| escape(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+pub fn print<S: AsRef<str>>(fields: &[S]) {
+    println!("{}", line(fields));
+}
+
+pub fn time(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        assert_eq!(line(&["api", "", "v3"]), "api\t\tv3");
+        assert_eq!(
+            line(&["a\tb", "line\nbreak", "C:\\dir"]),
+            "a\\tb\tline\\nbreak\tC:\\\\dir"
+        );
+    }
+
+    #[test]
+    fn test_time() {
+        let at = DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(time(&at), "2024-01-02T01:04:05Z");
+    }
+}
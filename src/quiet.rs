@@ -0,0 +1,21 @@
+//! `-q`/`--ids` output: one resource name per line. `list -q` and `get -q`
+//! print the same identifier, so either can be piped into commands taking a
+//! name (`ow workers list -q | xargs -n1 ow workers delete -y`).
+
+#[cfg(test)]
+thread_local! {
+    static PRINTED: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+pub fn print(name: &str) {
+    #[cfg(test)]
+    PRINTED.with(|printed| printed.borrow_mut().push(name.to_string()));
+
+    println!("{}", name);
+}
+
+/// Lines printed on this thread since the last call
+#[cfg(test)]
+pub fn take() -> Vec<String> {
+    PRINTED.with(|printed| std::mem::take(&mut *printed.borrow_mut()))
+}
@@ -0,0 +1,282 @@
+//! Project routes (`_routes.json`), emitted by framework adapters next to
+//! the worker script.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+pub const ROUTES_FILE: &str = "_routes.json";
+
+/// Routes a single upload may create, all categories combined
+pub const MAX_ROUTES: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct RoutesConfig {
+    #[serde(default)]
+    pub immutable: Vec<String>,
+    #[serde(rename = "static", default)]
+    pub static_routes: Vec<String>,
+    #[serde(default)]
+    pub prerendered: Vec<String>,
+    #[serde(default)]
+    pub functions: Vec<FunctionRoute>,
+    /// Patterns rendered by the main worker, ranked above static files
+    #[serde(default)]
+    pub ssr: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionRoute {
+    pub pattern: String,
+    /// Script path in the upload (e.g. `functions/api.js`)
+    pub worker: String,
+}
+
+impl FunctionRoute {
+    /// Script path relative to the upload root, as adapters write it with or
+    /// without a leading `./` or `/`
+    pub fn script_path(&self) -> &str {
+        self.worker.trim_start_matches("./").trim_start_matches('/')
+    }
+}
+
+/// One problem in `_routes.json`, with the line it was found on when known
+#[derive(Debug, PartialEq)]
+pub struct RouteIssue {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for RouteIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RoutesError(pub Vec<RouteIssue>);
+
+impl fmt::Display for RoutesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {}:", ROUTES_FILE)?;
+
+        for issue in &self.0 {
+            write!(f, "\n  {}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for RoutesError {}
+
+impl RoutesConfig {
+    /// Parse and validate `_routes.json`, reporting every bad pattern at once
+    pub fn parse(content: &str) -> Result<Self, RoutesError> {
+        let config: Self = serde_json::from_str(content).map_err(|e| {
+            RoutesError(vec![RouteIssue {
+                line: Some(e.line()),
+                message: e.to_string(),
+            }])
+        })?;
+
+        let issues = config.validate(content);
+
+        if issues.is_empty() {
+            Ok(config)
+        } else {
+            Err(RoutesError(issues))
+        }
+    }
+
+    /// Every pattern with the list it came from, in priority order
+    fn patterns(&self) -> Vec<(&'static str, &str)> {
+        let mut patterns = Vec::new();
+
+        for pattern in &self.functions {
+            patterns.push(("functions", pattern.pattern.as_str()));
+        }
+
+        for pattern in &self.ssr {
+            patterns.push(("ssr", pattern.as_str()));
+        }
+
+        for pattern in &self.immutable {
+            patterns.push(("immutable", pattern.as_str()));
+        }
+
+        for pattern in &self.static_routes {
+            patterns.push(("static", pattern.as_str()));
+        }
+
+        for pattern in &self.prerendered {
+            patterns.push(("prerendered", pattern.as_str()));
+        }
+
+        patterns
+    }
+
+    fn validate(&self, content: &str) -> Vec<RouteIssue> {
+        let mut issues = Vec::new();
+        let patterns = self.patterns();
+
+        if patterns.len() > MAX_ROUTES {
+            issues.push(RouteIssue {
+                line: None,
+                message: format!(
+                    "{} routes, at most {} are allowed",
+                    patterns.len(),
+                    MAX_ROUTES
+                ),
+            });
+        }
+
+        // Occurrences seen so far, to point duplicates at the right line
+        let mut seen: HashMap<&str, (usize, &str)> = HashMap::new();
+
+        for (list, pattern) in patterns {
+            let count = seen.get(pattern).map_or(0, |(count, _)| *count);
+            let line = line_of(content, pattern, count);
+
+            if let Err(message) = check_pattern(pattern) {
+                issues.push(RouteIssue {
+                    line,
+                    message: format!("{} pattern \"{}\": {}", list, pattern, message),
+                });
+            }
+
+            if let Some((_, first)) = seen.get(pattern) {
+                issues.push(RouteIssue {
+                    line,
+                    message: format!(
+                        "{} pattern \"{}\" is already listed in {}",
+                        list, pattern, first
+                    ),
+                });
+            }
+
+            seen.entry(pattern).or_insert((0, list)).0 += 1;
+        }
+
+        for function in &self.functions {
+            if function.worker.trim().is_empty() {
+                issues.push(RouteIssue {
+                    line: line_of(content, &function.pattern, 0),
+                    message: format!("function \"{}\" has no worker file", function.pattern),
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/// Why a route pattern is invalid. Patterns are absolute paths where `*`
+/// matches within one segment and `**` (a whole segment) matches across them.
+fn check_pattern(pattern: &str) -> Result<(), String> {
+    if !pattern.starts_with('/') {
+        return Err("must start with /".to_string());
+    }
+
+    if let Some(c) = pattern
+        .chars()
+        .find(|c| c.is_whitespace() || matches!(c, '?' | '#'))
+    {
+        return Err(format!("contains {:?}", c));
+    }
+
+    if pattern.contains("***") {
+        return Err("*** is not a wildcard, use * or **".to_string());
+    }
+
+    let segments: Vec<&str> = pattern[1..].split('/').collect();
+
+    for (i, segment) in segments.iter().enumerate() {
+        // A trailing slash is fine (/about/), an empty segment in between is not
+        if segment.is_empty() && i + 1 < segments.len() {
+            return Err("has an empty segment (//)".to_string());
+        }
+
+        if segment.contains("**") && *segment != "**" {
+            return Err(format!("** must be a whole segment, not \"{}\"", segment));
+        }
+    }
+
+    Ok(())
+}
+
+/// 1-based line of the `nth` occurrence of `pattern` as a JSON string
+fn line_of(content: &str, pattern: &str, nth: usize) -> Option<usize> {
+    let needle = serde_json::to_string(pattern).ok()?;
+    let (offset, _) = content.match_indices(&needle).nth(nth)?;
+
+    Some(content[..offset].matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_pattern() {
+        assert!(check_pattern("/").is_ok());
+        assert!(check_pattern("/about/").is_ok());
+        assert!(check_pattern("/_app/immutable/*").is_ok());
+        assert!(check_pattern("/blog/*/comments").is_ok());
+        assert!(check_pattern("/api/**").is_ok());
+        assert!(check_pattern("/assets/*.js").is_ok());
+
+        assert!(check_pattern("_app/*").is_err());
+        assert!(check_pattern("/a b").is_err());
+        assert!(check_pattern("/search?q=*").is_err());
+        assert!(check_pattern("/a//b").is_err());
+        assert!(check_pattern("/api/v**").is_err());
+        assert!(check_pattern("/***").is_err());
+    }
+
+    #[test]
+    fn test_parse_reports_lines() {
+        let content = r#"{
+  "immutable": ["/_app/immutable/*"],
+  "static": ["/favicon.png", "robots.txt"],
+  "prerendered": ["/about", "/favicon.png"],
+  "functions": [{ "pattern": "/api/**", "worker": "functions/api.js" }]
+}"#;
+
+        let RoutesError(issues) = RoutesConfig::parse(content).unwrap_err();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].line, Some(3));
+        assert!(issues[0].message.contains("robots.txt"));
+        assert_eq!(issues[1].line, Some(4));
+        assert!(issues[1].message.contains("already listed in static"));
+    }
+
+    #[test]
+    fn test_parse_syntax_error() {
+        let content = "{\n  \"static\": [\"/a\",]\n}";
+
+        let RoutesError(issues) = RoutesConfig::parse(content).unwrap_err();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_parse_valid() {
+        let content = r#"{
+  "static": ["/a", "/b/*"],
+  "ssr": ["/blog/*"],
+  "functions": [{ "pattern": "/api/**", "worker": "./functions/api.js" }]
+}"#;
+
+        let routes = RoutesConfig::parse(content).unwrap();
+
+        assert_eq!(routes.static_routes, vec!["/a", "/b/*"]);
+        assert_eq!(routes.ssr, vec!["/blog/*"]);
+        assert_eq!(routes.functions[0].script_path(), "functions/api.js");
+    }
+}
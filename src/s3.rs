@@ -4,98 +4,608 @@
 use base64::Engine;
 use chrono::Utc;
 use hmac::{Hmac, Mac};
-use reqwest::{Client, Url};
+use md5::Md5;
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Read size for streamed upload bodies
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// A local file to upload. Size and hash are computed up front so the body can
+/// be streamed from disk instead of held in memory.
+#[derive(Debug, Clone)]
+pub struct Asset {
+    pub key: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub content_type: String,
+    /// `Cache-Control` stored with the object (from ow.toml asset rules)
+    pub cache_control: Option<String>,
+    pub sha256_hex: String,
+    pub md5_hex: String,
+    /// Big-endian CRC32, base64-encoded like `x-amz-checksum-crc32`
+    pub crc32_b64: String,
+}
+
+impl Asset {
+    /// Hash a file in a streaming pre-pass.
+    pub fn from_file(key: String, path: PathBuf, content_type: String) -> std::io::Result<Self> {
+        use std::io::Read;
+
+        let mut file = std::fs::File::open(&path)?;
+        let mut sha256 = Sha256::new();
+        let mut md5 = Md5::new();
+        let mut crc32 = crc32fast::Hasher::new();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let mut size = 0u64;
+
+        loop {
+            let n = file.read(&mut buf)?;
+
+            if n == 0 {
+                break;
+            }
+
+            sha256.update(&buf[..n]);
+            md5.update(&buf[..n]);
+            crc32.update(&buf[..n]);
+            size += n as u64;
+        }
+
+        Ok(Self {
+            key,
+            path,
+            size,
+            content_type,
+            cache_control: None,
+            sha256_hex: hex::encode(sha256.finalize()),
+            md5_hex: hex::encode(md5.finalize()),
+            crc32_b64: base64::engine::general_purpose::STANDARD
+                .encode(crc32.finalize().to_be_bytes()),
+        })
+    }
+}
+
+/// Checksums and headers reported by a HEAD on an existing object.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteObject {
+    pub checksum_sha256: Option<String>,
+    pub checksum_crc32: Option<String>,
+    pub etag: Option<String>,
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+}
+
+impl RemoteObject {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let get = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string())
+        };
+
+        Self {
+            checksum_sha256: get("x-amz-checksum-sha256"),
+            checksum_crc32: get("x-amz-checksum-crc32"),
+            etag: get("etag"),
+            content_length: get("content-length").and_then(|v| v.parse().ok()),
+            content_type: get("content-type"),
+            content_encoding: get("content-encoding"),
+        }
+    }
+
+    /// Compare against a local asset using the strongest checksum the provider
+    /// returned. Returns the name of the matching checksum, if any.
+    pub fn matches(&self, asset: &Asset) -> Option<&'static str> {
+        if let Some(sha256) = &self.checksum_sha256 {
+            return (*sha256 == hex_to_base64(&asset.sha256_hex)).then_some("checksum");
+        }
+
+        if let Some(crc32) = &self.checksum_crc32 {
+            return (*crc32 == asset.crc32_b64).then_some("crc32");
+        }
+
+        // Single-part ETags are the MD5 of the body; multipart ones end in "-<parts>"
+        let etag = self.etag.as_deref()?.trim_matches('"');
+
+        (!etag.contains('-') && etag.eq_ignore_ascii_case(&asset.md5_hex)).then_some("etag")
+    }
+
+    /// Ways the stored object differs from the asset as uploaded: checksum,
+    /// byte size, content type, or an encoding added on the way (auto-gzip).
+    pub fn differences(&self, asset: &Asset) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        // Multipart ETags without a checksum can't be compared
+        let comparable = self.checksum_sha256.is_some()
+            || self.checksum_crc32.is_some()
+            || self.etag.as_deref().is_some_and(|e| !e.contains('-'));
+
+        if comparable && self.matches(asset).is_none() {
+            differences.push("checksum differs".to_string());
+        }
+
+        if let Some(length) = self.content_length.filter(|l| *l != asset.size) {
+            differences.push(format!("{} bytes, uploaded {}", length, asset.size));
+        }
+
+        match &self.content_type {
+            Some(content_type) if !content_type.eq_ignore_ascii_case(&asset.content_type) => {
+                differences.push(format!(
+                    "content-type {}, uploaded {}",
+                    content_type, asset.content_type
+                ));
+            }
+            None => differences.push("no content-type".to_string()),
+            _ => {}
+        }
+
+        if let Some(encoding) = self
+            .content_encoding
+            .as_deref()
+            .filter(|e| !e.eq_ignore_ascii_case("identity"))
+        {
+            differences.push(format!("content-encoding {} added", encoding));
+        }
+
+        differences
+    }
+}
+
+/// What a failed storage request says about the setup. Everything but
+/// `Object` comes from the endpoint or the credentials, so every other asset
+/// would fail the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The endpoint host does not resolve
+    Dns,
+    /// The endpoint refused or dropped the connection
+    Connect,
+    /// TLS handshake failed (certificate, protocol)
+    Tls,
+    /// Signature rejected: wrong keys, region or clock, or expired presigned URLs
+    Signature,
+    /// Valid credentials without permission on the bucket
+    Denied,
+    /// The bucket does not exist at this endpoint
+    NoBucket,
+    /// Specific to one object (5xx after retries, bad request...)
+    Object,
+}
+
+impl FailureKind {
+    /// Whether the remaining assets are bound to fail the same way
+    pub fn is_fatal(self) -> bool {
+        self != FailureKind::Object
+    }
+}
+
+#[derive(Debug)]
+pub struct StorageError {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for StorageError {
+    fn from(message: String) -> Self {
+        Self {
+            kind: FailureKind::Object,
+            message,
+        }
+    }
+}
+
+impl From<&str> for StorageError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<StorageError> for String {
+    fn from(e: StorageError) -> Self {
+        e.message
+    }
+}
+
+/// The error with its sources: reqwest's own message rarely says whether DNS,
+/// TCP or TLS failed
+fn error_chain(e: &reqwest::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+
+    message
+}
+
+fn classify_error(e: &reqwest::Error, chain: &str) -> FailureKind {
+    let chain = chain.to_lowercase();
+
+    if [
+        "dns error",
+        "failed to lookup address",
+        "name or service not known",
+    ]
+    .iter()
+    .any(|m| chain.contains(m))
+    {
+        FailureKind::Dns
+    } else if ["certificate", "tls", "ssl", "handshake"]
+        .iter()
+        .any(|m| chain.contains(m))
+    {
+        FailureKind::Tls
+    } else if e.is_connect() {
+        FailureKind::Connect
+    } else {
+        FailureKind::Object
+    }
+}
+
+/// Classify an error response from its status and S3 error `<Code>`
+fn classify_status(status: StatusCode, code: Option<&str>) -> FailureKind {
+    match (status.as_u16(), code) {
+        (
+            _,
+            Some(
+                "SignatureDoesNotMatch"
+                | "InvalidAccessKeyId"
+                | "AuthorizationHeaderMalformed"
+                | "RequestTimeTooSkewed"
+                | "ExpiredToken"
+                | "InvalidToken"
+                | "PermanentRedirect",
+            ),
+        ) => FailureKind::Signature,
+        (_, Some("NoSuchBucket")) => FailureKind::NoBucket,
+        (401 | 403, _) => FailureKind::Denied,
+        _ => FailureKind::Object,
+    }
+}
+
+/// `<Code>` of an S3 XML error body
+fn error_code(body: &str) -> Option<&str> {
+    let start = body.find("<Code>")? + "<Code>".len();
+    let end = start + body[start..].find("</Code>")?;
+    Some(&body[start..end])
+}
+
+/// Error for an unsuccessful PUT response
+async fn status_error(response: Response, retries: u32) -> StorageError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let code = error_code(&body);
+
+    let message = match code {
+        Some(code) => format!("HTTP {} {}", status, code),
+        None => format!("HTTP {}", status),
+    };
+
+    StorageError {
+        kind: classify_status(status, code),
+        message: with_retries(message, retries),
+    }
+}
+
+/// What to check for a failure that stopped the upload
+fn diagnose(kind: FailureKind, target: &str, detail: &str) -> String {
+    let hint = match kind {
+        FailureKind::Dns => "The endpoint host does not resolve. Check the endpoint URL.",
+        FailureKind::Connect => {
+            "The endpoint is unreachable. Check the endpoint URL and port, and any proxy or firewall."
+        }
+        FailureKind::Tls => {
+            "The TLS handshake failed. Check the endpoint certificate, or use http:// for a \
+             local S3 without TLS."
+        }
+        FailureKind::Signature => {
+            "The request signature was rejected (a network problem would not get this far). \
+             Check the access key, secret key and region, and the system clock. Presigned \
+             URLs may also have expired: re-run the upload."
+        }
+        FailureKind::Denied => {
+            "The credentials were accepted but lack write permission on the bucket."
+        }
+        FailureKind::NoBucket => "The bucket does not exist. Check the bucket name and endpoint.",
+        FailureKind::Object => "",
+    };
+
+    format!("Asset upload to {} failed: {}\n{}", target, detail, hint)
+}
+
+/// Retries after the first attempt for transient S3 errors
+const MAX_RETRIES: u32 = 3;
+
+/// First backoff delay, doubled on each retry
+const RETRY_BASE_DELAY_MS: u64 = 250;
+
+/// Upper bound for any single wait, including `Retry-After`
+const RETRY_MAX_DELAY_MS: u64 = 10_000;
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn is_retryable_error(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_request()
+}
+
+/// Exponential backoff with a little jitter.
+fn backoff(retry: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY_MS.saturating_mul(1 << retry.min(16));
+    let jitter = rand::random_range(0..=RETRY_BASE_DELAY_MS / 2);
+
+    Duration::from_millis((delay + jitter).min(RETRY_MAX_DELAY_MS))
+}
+
+/// `Retry-After` in seconds, if the server sent one.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs: u64 = response
+        .headers()
+        .get("retry-after")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(Duration::from_millis(
+        secs.saturating_mul(1000).min(RETRY_MAX_DELAY_MS),
+    ))
+}
+
+/// Send a request with the shared retry policy (5xx, 429, timeouts, connection
+/// errors). The body is reopened from `body` on every attempt.
+/// Returns the final response and the number of retries.
+async fn send_with_retry(
+    request: RequestBuilder,
+    body: Option<&Path>,
+) -> Result<(Response, u32), StorageError> {
+    let mut retries = 0;
+
+    loop {
+        let mut attempt = request.try_clone().ok_or("Request cannot be retried")?;
+
+        if let Some(path) = body {
+            attempt = attempt.body(file_body(path).await?);
+        }
+
+        match attempt.send().await {
+            Ok(response) if retries < MAX_RETRIES && is_retryable_status(response.status()) => {
+                let delay = retry_after(&response).unwrap_or_else(|| backoff(retries));
+                tokio::time::sleep(delay).await;
+            }
+            Ok(response) => return Ok((response, retries)),
+            Err(e) if retries < MAX_RETRIES && is_retryable_error(&e) => {
+                tokio::time::sleep(backoff(retries)).await;
+            }
+            Err(e) => {
+                let chain = error_chain(&e);
+
+                return Err(StorageError {
+                    kind: classify_error(&e, &chain),
+                    message: with_retries(chain, retries),
+                });
+            }
+        }
+
+        retries += 1;
+    }
+}
+
+/// Append "after N retries" to an error message when retries happened.
+fn with_retries(message: String, retries: u32) -> String {
+    match retries {
+        0 => message,
+        1 => format!("{} after 1 retry", message),
+        n => format!("{} after {} retries", message, n),
+    }
+}
+
+/// Concurrent asset uploads, also the idle pool size per host
+const UPLOAD_CONCURRENCY: usize = 10;
+
+/// Pooled client shared by every storage client, so uploads reuse TLS
+/// connections (and HTTP/2 where the provider supports it).
+fn shared_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+
+    CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .pool_max_idle_per_host(UPLOAD_CONCURRENCY)
+                .pool_idle_timeout(Duration::from_secs(90))
+                .tcp_keepalive(Duration::from_secs(60))
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// Stream a file from disk as a request body.
+async fn file_body(path: &Path) -> Result<reqwest::Body, String> {
+    use tokio::io::AsyncReadExt;
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+
+    let stream = futures::stream::try_unfold(file, |mut file| async move {
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let n = file.read(&mut buf).await?;
+
+        if n == 0 {
+            return Ok::<_, std::io::Error>(None);
+        }
+
+        buf.truncate(n);
+        Ok(Some((buf, file)))
+    });
+
+    Ok(reqwest::Body::wrap_stream(stream))
+}
+
 // ============================================================================
 // ObjectStorage trait — shared interface for S3 and presigned uploads
 // ============================================================================
 
 pub trait ObjectStorage: Send + Sync {
-    /// HEAD check. Returns (checksum_sha256_b64, has_etag) if object exists.
+    /// HEAD check. Returns the remote checksums if the object exists.
     fn head(
         &self,
         key: &str,
-    ) -> impl std::future::Future<Output = Result<Option<(Option<String>, bool)>, String>> + Send;
+    ) -> impl std::future::Future<Output = Result<Option<RemoteObject>, String>> + Send;
 
-    /// PUT an object. Returns true on success.
+    /// PUT an asset, streaming its body from disk. Returns the number of retries.
     fn put(
         &self,
-        key: &str,
-        body: Vec<u8>,
-        content_type: &str,
-    ) -> impl std::future::Future<Output = Result<bool, String>> + Send;
+        asset: &Asset,
+    ) -> impl std::future::Future<Output = Result<u32, StorageError>> + Send;
+
+    /// Where objects go, for diagnostics (bucket and endpoint)
+    fn target(&self) -> String;
 }
 
-/// Upload assets with 10-way concurrency and HEAD-check deduplication.
-/// Each asset is (key, content, content_type, sha256_hex).
-/// Returns (uploaded, skipped).
+/// Upload assets concurrently with HEAD-check deduplication.
+/// Returns (uploaded, skipped, failed).
+///
+/// A failure pointing at the endpoint or credentials (DNS, TLS, signature...)
+/// stops the upload with a diagnostic instead of failing every asset in turn.
 pub async fn upload_assets(
     storage: &impl ObjectStorage,
-    assets: &[(String, Vec<u8>, String, String)],
-) -> (usize, usize) {
+    assets: &[Asset],
+) -> Result<(usize, usize, usize), StorageError> {
     use colored::Colorize;
     use futures::stream::{self, StreamExt};
+    use std::sync::Mutex;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
     let uploaded = AtomicUsize::new(0);
     let skipped = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let fatal: Mutex<Option<StorageError>> = Mutex::new(None);
+
+    stream::iter(assets)
+        .for_each_concurrent(UPLOAD_CONCURRENCY, |asset| {
+            let uploaded = &uploaded;
+            let skipped = &skipped;
+            let failed = &failed;
+            let fatal = &fatal;
+
+            async move {
+                if fatal.lock().unwrap().is_some() {
+                    return;
+                }
 
-    stream::iter(assets.iter().map(|(path, content, ct, hash_hex)| {
-        (
-            path.clone(),
-            content.clone(),
-            ct.clone(),
-            hex_to_base64(hash_hex),
-        )
-    }))
-    .for_each_concurrent(10, |(path, content, ct, hash_b64)| {
-        let uploaded = &uploaded;
-        let skipped = &skipped;
+                let path = &asset.key;
 
-        async move {
-            let mut should_upload = true;
-            let mut has_etag = false;
+                let mut should_upload = true;
+                let mut exists = false;
 
-            if let Ok(Some((remote_checksum, etag))) = storage.head(&path).await {
-                has_etag = etag;
+                if let Ok(Some(remote)) = storage.head(path).await {
+                    exists = true;
 
-                if let Some(ref remote_hash) = remote_checksum {
-                    if remote_hash == &hash_b64 {
-                        println!(
+                    if let Some(kind) = remote.matches(asset) {
+                        crate::steps::note(&format!(
                             "  {} {} {}",
                             "⎿".dimmed(),
                             path,
-                            "(skipped, checksum match)".dimmed()
-                        );
+                            format!("(skipped, {} match)", kind).dimmed()
+                        ));
                         skipped.fetch_add(1, Ordering::Relaxed);
                         should_upload = false;
                     }
                 }
-            }
 
-            if should_upload {
-                match storage.put(&path, content, &ct).await {
-                    Ok(true) => {
-                        let reason = if has_etag { "checksum changed" } else { "new" };
-                        println!("  {} {} ({})", "⎿".dimmed(), path, reason);
-                        uploaded.fetch_add(1, Ordering::Relaxed);
+                if should_upload {
+                    match storage.put(asset).await {
+                        Ok(retries) => {
+                            let reason = if exists { "checksum changed" } else { "new" };
+                            let reason = with_retries(reason.to_string(), retries);
+                            crate::steps::note(&format!(
+                                "  {} {} ({})",
+                                "⎿".dimmed(),
+                                path,
+                                reason
+                            ));
+                            uploaded.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(e) if e.kind.is_fatal() => {
+                            // Uploads already in flight fail the same way, keep the first
+                            fatal.lock().unwrap().get_or_insert(e);
+                        }
+                        Err(e) => {
+                            crate::steps::note(&format!("  {} {} ({})", "⎿".red(), path, e));
+                            crate::github::warning(&format!("Failed to upload {}: {}", path, e));
+                            failed.fetch_add(1, Ordering::Relaxed);
+                        }
                     }
-                    Ok(false) => eprintln!("  {} {} (upload failed)", "⎿".red(), path),
-                    Err(e) => eprintln!("  {} {} ({})", "⎿".red(), path, e),
                 }
             }
-        }
-    })
-    .await;
+        })
+        .await;
+
+    if let Some(e) = fatal.into_inner().unwrap() {
+        return Err(StorageError {
+            kind: e.kind,
+            message: diagnose(e.kind, &storage.target(), &e.message),
+        });
+    }
 
-    (
+    Ok((
         uploaded.load(Ordering::Relaxed),
         skipped.load(Ordering::Relaxed),
-    )
+        failed.load(Ordering::Relaxed),
+    ))
+}
+
+/// HEAD a random sample of uploaded assets and compare them with the local
+/// files. Returns one line per asset that differs, empty when all match.
+pub async fn verify_assets(
+    storage: &impl ObjectStorage,
+    assets: &[Asset],
+    sample: usize,
+) -> Vec<String> {
+    use futures::stream::{self, StreamExt};
+    use rand::seq::IndexedRandom;
+
+    let sampled: Vec<&Asset> = assets.choose_multiple(&mut rand::rng(), sample).collect();
+
+    let mut issues: Vec<String> = stream::iter(sampled)
+        .map(|asset| async move {
+            let differences = match storage.head(&asset.key).await {
+                Ok(Some(remote)) => remote.differences(asset),
+                Ok(None) => vec!["missing after upload".to_string()],
+                Err(e) => vec![e],
+            };
+
+            (!differences.is_empty()).then(|| format!("{}: {}", asset.key, differences.join(", ")))
+        })
+        .buffer_unordered(UPLOAD_CONCURRENCY)
+        .filter_map(|issue| async move { issue })
+        .collect()
+        .await;
+
+    issues.sort();
+    issues
 }
 
 fn hex_to_base64(hex_str: &str) -> String {
@@ -114,6 +624,8 @@ pub struct S3Config {
     pub secret_access_key: String,
     pub region: String,
     pub prefix: Option<String>,
+    /// Session token for temporary (STS) credentials
+    pub session_token: Option<String>,
 }
 
 pub struct S3Client {
@@ -124,7 +636,7 @@ pub struct S3Client {
 impl S3Client {
     pub fn new(config: S3Config) -> Self {
         Self {
-            client: Client::new(),
+            client: shared_client(),
             config,
         }
     }
@@ -147,44 +659,60 @@ impl S3Client {
         )
     }
 
-    /// Sign a string using AWS v4 signing.
-    fn sign(&self, date_stamp: &str, string_to_sign: &str) -> Result<String, String> {
-        let k_date = hmac_sha256(
-            format!("AWS4{}", self.config.secret_access_key).as_bytes(),
-            date_stamp.as_bytes(),
-        )?;
-        let k_region = hmac_sha256(&k_date, self.config.region.as_bytes())?;
-        let k_service = hmac_sha256(&k_region, b"s3")?;
-        let k_signing = hmac_sha256(&k_service, b"aws4_request")?;
+    /// Canonical header line and signed-header suffix for the session token, if any.
+    fn security_token_headers(&self) -> (String, &'static str) {
+        match &self.config.session_token {
+            Some(token) => (
+                format!("x-amz-security-token:{}\n", token),
+                ";x-amz-security-token",
+            ),
+            None => (String::new(), ""),
+        }
+    }
 
-        let signature = hmac_sha256(&k_signing, string_to_sign.as_bytes())?;
+    /// Signed HEAD request for a key.
+    fn head_request(&self, key: &str) -> Result<reqwest::RequestBuilder, String> {
+        self.empty_request(reqwest::Method::HEAD, key)
+    }
 
-        Ok(hex::encode(signature))
+    /// Signed request without a body (HEAD, GET, DELETE) for a key.
+    fn empty_request(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        self.signed_request(method, &self.url(key), &[])
     }
-}
 
-impl ObjectStorage for S3Client {
-    async fn head(&self, key: &str) -> Result<Option<(Option<String>, bool)>, String> {
-        let url = self.url(key);
+    /// Signed request without a body for a URL and query parameters.
+    fn signed_request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let canonical_query = canonical_query(query);
         let now = Utc::now();
         let date_stamp = now.format("%Y%m%d").to_string();
         let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
 
-        let parsed_url = Url::parse(&url).map_err(|e| e.to_string())?;
+        let parsed_url = Url::parse(url).map_err(|e| e.to_string())?;
         let host = parsed_url.host_str().ok_or("No host in URL")?;
         let path = parsed_url.path();
 
         let payload_hash = hex::encode(Sha256::digest(b""));
 
+        let (token_header, token_signed) = self.security_token_headers();
+
         let canonical_headers = format!(
-            "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
-            host, payload_hash, amz_date
+            "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n{}",
+            host, payload_hash, amz_date, token_header
         );
-        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let signed_headers = format!("host;x-amz-content-sha256;x-amz-date{}", token_signed);
 
         let canonical_request = format!(
-            "HEAD\n{}\n\n{}\n{}\n{}",
-            path, canonical_headers, signed_headers, payload_hash
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, path, canonical_query, canonical_headers, signed_headers, payload_hash
         );
 
         let algorithm = "AWS4-HMAC-SHA256";
@@ -202,34 +730,160 @@ impl ObjectStorage for S3Client {
             algorithm, self.config.access_key_id, credential_scope, signed_headers, signature
         );
 
-        let response = self
+        // Sent exactly as signed, so the server computes the same canonical query
+        let url = match canonical_query.is_empty() {
+            true => url.to_string(),
+            false => format!("{}?{}", url, canonical_query),
+        };
+
+        let mut request = self
             .client
-            .head(&url)
+            .request(method, &url)
             .header("Host", host)
             .header("x-amz-content-sha256", &payload_hash)
             .header("x-amz-date", &amz_date)
-            .header("Authorization", authorization)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .header("Authorization", authorization);
 
-        if !response.status().is_success() {
+        if let Some(token) = &self.config.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+
+        Ok(request)
+    }
+
+    /// Check that the bucket is reachable with these credentials.
+    /// A missing probe object (404) still proves access; 403 does not.
+    pub async fn check_access(&self) -> Result<(), String> {
+        let request = self.head_request(".openworkers-health")?;
+        let (response, _) = send_with_retry(request, None).await?;
+
+        match response.status().as_u16() {
+            200..=299 | 404 => Ok(()),
+            403 => Err("access denied (check credentials)".to_string()),
+            status => Err(format!("HTTP {}", status)),
+        }
+    }
+
+    /// List the objects whose key starts with `prefix`, following pagination.
+    /// Keys are returned without the storage prefix.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<ObjectInfo>, String> {
+        let url = format!("{}/{}", self.config.endpoint, self.config.bucket);
+        let full_prefix = self.full_key(prefix);
+        let strip = full_prefix.len() - prefix.len();
+
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", full_prefix.as_str())];
+
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.as_str()));
+            }
+
+            let request = self.signed_request(reqwest::Method::GET, &url, &query)?;
+            let (response, _) = send_with_retry(request, None).await?;
+            let status = response.status();
+            let body = response.text().await.map_err(|e| e.to_string())?;
+
+            if !status.is_success() {
+                return Err(match error_code(&body) {
+                    Some(code) => format!("HTTP {} {}", status, code),
+                    None => format!("HTTP {}", status),
+                });
+            }
+
+            let page = parse_list_page(&body);
+
+            objects.extend(page.objects.into_iter().map(|mut object| {
+                object.key = object.key.get(strip..).unwrap_or_default().to_string();
+                object
+            }));
+
+            match page.next_token {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Download an object to a file, returning its size, or None if there is
+    /// no such object.
+    pub async fn download(&self, key: &str, dest: &Path) -> Result<Option<u64>, String> {
+        use tokio::io::AsyncWriteExt;
+
+        let request = self.empty_request(reqwest::Method::GET, key)?;
+        let (mut response, _) = send_with_retry(request, None).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
             return Ok(None);
         }
 
-        let checksum = response
-            .headers()
-            .get("x-amz-checksum-sha256")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
+        if !response.status().is_success() {
+            return Err(status_error(response, 0).await.message);
+        }
+
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .map_err(|e| format!("Failed to create '{}': {}", dest.display(), e))?;
+        let mut size = 0u64;
+
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write '{}': {}", dest.display(), e))?;
+            size += chunk.len() as u64;
+        }
+
+        file.flush().await.map_err(|e| e.to_string())?;
+
+        Ok(Some(size))
+    }
+
+    /// Delete an object. A missing object counts as deleted.
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        let request = self.empty_request(reqwest::Method::DELETE, key)?;
+        let (response, _) = send_with_retry(request, None).await?;
+
+        match response.status().as_u16() {
+            200..=299 | 404 => Ok(()),
+            status => Err(format!("HTTP {}", status)),
+        }
+    }
+
+    /// Sign a string using AWS v4 signing.
+    fn sign(&self, date_stamp: &str, string_to_sign: &str) -> Result<String, String> {
+        let k_date = hmac_sha256(
+            format!("AWS4{}", self.config.secret_access_key).as_bytes(),
+            date_stamp.as_bytes(),
+        )?;
+        let k_region = hmac_sha256(&k_date, self.config.region.as_bytes())?;
+        let k_service = hmac_sha256(&k_region, b"s3")?;
+        let k_signing = hmac_sha256(&k_service, b"aws4_request")?;
+
+        let signature = hmac_sha256(&k_signing, string_to_sign.as_bytes())?;
+
+        Ok(hex::encode(signature))
+    }
+}
 
-        let has_etag = response.headers().get("etag").is_some();
+impl ObjectStorage for S3Client {
+    async fn head(&self, key: &str) -> Result<Option<RemoteObject>, String> {
+        let request = self.head_request(key)?;
+        let (response, _) = send_with_retry(request, None).await?;
 
-        Ok(Some((checksum, has_etag)))
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        Ok(Some(RemoteObject::from_headers(response.headers())))
     }
 
-    async fn put(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<bool, String> {
-        let url = self.url(key);
+    async fn put(&self, asset: &Asset) -> Result<u32, StorageError> {
+        let url = self.url(&asset.key);
+        let content_type = asset.content_type.as_str();
         let now = Utc::now();
         let date_stamp = now.format("%Y%m%d").to_string();
         let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
@@ -238,15 +892,25 @@ impl ObjectStorage for S3Client {
         let host = parsed_url.host_str().ok_or("No host in URL")?;
         let path = parsed_url.path();
 
-        let payload_hash = hex::encode(Sha256::digest(&body));
-        let checksum_b64 = base64_encode(&Sha256::digest(&body));
+        let payload_hash = asset.sha256_hex.clone();
+        let checksum_b64 = hex_to_base64(&asset.sha256_hex);
+
+        let (token_header, token_signed) = self.security_token_headers();
+
+        // Canonical headers are sorted, so cache-control goes first
+        let (cache_header, cache_signed) = match &asset.cache_control {
+            Some(value) => (format!("cache-control:{}\n", value), "cache-control;"),
+            None => (String::new(), ""),
+        };
 
         let canonical_headers = format!(
-            "content-type:{}\nhost:{}\nx-amz-checksum-sha256:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
-            content_type, host, checksum_b64, payload_hash, amz_date
+            "{}content-type:{}\nhost:{}\nx-amz-checksum-sha256:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n{}",
+            cache_header, content_type, host, checksum_b64, payload_hash, amz_date, token_header
+        );
+        let signed_headers = format!(
+            "{}content-type;host;x-amz-checksum-sha256;x-amz-content-sha256;x-amz-date{}",
+            cache_signed, token_signed
         );
-        let signed_headers =
-            "content-type;host;x-amz-checksum-sha256;x-amz-content-sha256;x-amz-date";
 
         let canonical_request = format!(
             "PUT\n{}\n\n{}\n{}\n{}",
@@ -268,24 +932,127 @@ impl ObjectStorage for S3Client {
             algorithm, self.config.access_key_id, credential_scope, signed_headers, signature
         );
 
-        let response = self
+        let mut request = self
             .client
             .put(&url)
             .header("Content-Type", content_type)
+            .header("Content-Length", asset.size)
             .header("Host", host)
             .header("x-amz-checksum-sha256", &checksum_b64)
             .header("x-amz-content-sha256", &payload_hash)
             .header("x-amz-date", &amz_date)
-            .header("Authorization", authorization)
-            .body(body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .header("Authorization", authorization);
+
+        if let Some(token) = &self.config.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+
+        if let Some(cache_control) = &asset.cache_control {
+            request = request.header("Cache-Control", cache_control);
+        }
+
+        let (response, retries) = send_with_retry(request, Some(&asset.path)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response, retries).await);
+        }
+
+        Ok(retries)
+    }
 
-        Ok(response.status().is_success())
+    fn target(&self) -> String {
+        format!(
+            "bucket '{}' at {}",
+            self.config.bucket, self.config.endpoint
+        )
     }
 }
 
+/// An object in a bucket listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectInfo {
+    pub key: String,
+    pub size: u64,
+    /// ISO 8601, as sent by the provider
+    pub last_modified: String,
+}
+
+/// One page of a ListObjectsV2 response
+struct ListPage {
+    objects: Vec<ObjectInfo>,
+    /// Set when the listing is truncated
+    next_token: Option<String>,
+}
+
+/// Text of the first `<tag>` element in `xml`, unescaped
+fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+
+    Some(
+        xml[start..end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// The few fields of a ListObjectsV2 response the CLI needs, without pulling
+/// in an XML parser
+fn parse_list_page(xml: &str) -> ListPage {
+    let objects = xml
+        .split("<Contents>")
+        .skip(1)
+        .filter_map(|contents| {
+            let contents = &contents[..contents.find("</Contents>")?];
+
+            Some(ObjectInfo {
+                key: xml_text(contents, "Key")?,
+                size: xml_text(contents, "Size")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0),
+                last_modified: xml_text(contents, "LastModified").unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    let truncated = xml_text(xml, "IsTruncated").is_some_and(|t| t == "true");
+
+    ListPage {
+        objects,
+        next_token: xml_text(xml, "NextContinuationToken").filter(|_| truncated),
+    }
+}
+
+/// SigV4 canonical query string: parameters sorted, names and values
+/// percent-encoded with only unreserved characters left as-is
+fn canonical_query(query: &[(&str, &str)]) -> String {
+    let encode = |s: &str| -> String {
+        s.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    };
+
+    let mut pairs: Vec<(String, String)> =
+        query.iter().map(|(k, v)| (encode(k), encode(v))).collect();
+    pairs.sort();
+
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 // ============================================================================
 // PresignedClient — raw HTTP to presigned URLs (for API backend)
 // ============================================================================
@@ -298,61 +1065,68 @@ pub struct PresignedClient {
 impl PresignedClient {
     pub fn new(urls: HashMap<String, (String, String)>) -> Self {
         Self {
-            client: Client::new(),
+            client: shared_client(),
             urls,
         }
     }
 }
 
 impl ObjectStorage for PresignedClient {
-    async fn head(&self, key: &str) -> Result<Option<(Option<String>, bool)>, String> {
+    async fn head(&self, key: &str) -> Result<Option<RemoteObject>, String> {
         let (head_url, _) = self
             .urls
             .get(key)
             .ok_or_else(|| format!("No URL for key '{}'", key))?;
 
-        let response = self
-            .client
-            .head(head_url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let (response, _) = send_with_retry(self.client.head(head_url), None).await?;
 
         if !response.status().is_success() {
             return Ok(None);
         }
 
-        let checksum = response
-            .headers()
-            .get("x-amz-checksum-sha256")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
-
-        let has_etag = response.headers().get("etag").is_some();
-
-        Ok(Some((checksum, has_etag)))
+        Ok(Some(RemoteObject::from_headers(response.headers())))
     }
 
-    async fn put(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<bool, String> {
+    async fn put(&self, asset: &Asset) -> Result<u32, StorageError> {
         let (_, put_url) = self
             .urls
-            .get(key)
-            .ok_or_else(|| format!("No URL for key '{}'", key))?;
+            .get(&asset.key)
+            .ok_or_else(|| format!("No URL for key '{}'", asset.key))?;
 
-        let checksum_b64 = base64_encode(&Sha256::digest(&body));
+        let checksum_b64 = hex_to_base64(&asset.sha256_hex);
 
-        let response = self
+        let mut request = self
             .client
             .put(put_url)
-            .header("Content-Type", content_type)
-            .header("Content-Length", body.len())
-            .header("x-amz-checksum-sha256", &checksum_b64)
-            .body(body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .header("Content-Type", &asset.content_type)
+            .header("Content-Length", asset.size)
+            .header("x-amz-checksum-sha256", &checksum_b64);
 
-        Ok(response.status().is_success())
+        if let Some(cache_control) = &asset.cache_control {
+            request = request.header("Cache-Control", cache_control);
+        }
+
+        let (response, retries) = send_with_retry(request, Some(&asset.path)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response, retries).await);
+        }
+
+        Ok(retries)
+    }
+
+    fn target(&self) -> String {
+        let host = self
+            .urls
+            .values()
+            .next()
+            .and_then(|(_, put_url)| Url::parse(put_url).ok())
+            .and_then(|url| url.host_str().map(|h| h.to_string()));
+
+        match host {
+            Some(host) => format!("presigned URLs on {}", host),
+            None => "presigned URLs".to_string(),
+        }
     }
 }
 
@@ -360,10 +1134,6 @@ impl ObjectStorage for PresignedClient {
 // Helpers
 // ============================================================================
 
-fn base64_encode(data: &[u8]) -> String {
-    base64::engine::general_purpose::STANDARD.encode(data)
-}
-
 fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
     let mut mac = HmacSha256::new_from_slice(key).map_err(|e| e.to_string())?;
     mac.update(data);
@@ -403,3 +1173,295 @@ pub fn get_mime_type(path: &str) -> &'static str {
         _ => "application/octet-stream",
     }
 }
+
+/// Content type for a file uploaded under `key`: from the extension, or
+/// sniffed from the content when the extension says nothing.
+pub fn content_type(key: &str, path: &Path) -> String {
+    match get_mime_type(key) {
+        "application/octet-stream" => sniff_mime_type(path),
+        known => Some(known),
+    }
+    .unwrap_or("application/octet-stream")
+    .to_string()
+}
+
+/// Bytes read from the start of a file to guess its type
+const SNIFF_LEN: usize = 8192;
+
+/// Guess a MIME type from file content, for files `get_mime_type` can't place.
+/// Known binary formats are detected from their magic bytes; anything else that
+/// decodes as UTF-8 without NUL bytes is served as plain text.
+pub fn sniff_mime_type(path: &Path) -> Option<&'static str> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    std::fs::File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+
+    if head.is_empty() {
+        return None;
+    }
+
+    if let Some(kind) = infer::get(&head) {
+        return Some(kind.mime_type());
+    }
+
+    // A multi-byte character may be cut off at the end of the sample
+    let is_text = match std::str::from_utf8(&head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+
+    (is_text && !head.contains(&0)).then_some("text/plain; charset=utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(content: &[u8]) -> Asset {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), content).unwrap();
+
+        Asset::from_file(
+            "index.html".to_string(),
+            file.path().to_path_buf(),
+            "text/html".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_asset_checksums() {
+        let a = asset(b"hello");
+
+        assert_eq!(a.size, 5);
+        assert_eq!(a.md5_hex, "5d41402abc4b2a76b9719d911017c592");
+        assert_eq!(a.crc32_b64, "NhCmhg==");
+    }
+
+    #[test]
+    fn test_sniff_mime_type() {
+        let sniff = |content: &[u8]| {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(file.path(), content).unwrap();
+            sniff_mime_type(file.path())
+        };
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(sniff(png), Some("image/png"));
+        assert_eq!(
+            sniff("MIT License\n\nCopyright © 2024".as_bytes()),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(sniff(&[0x00, 0x01, 0xfe, 0xff]), None);
+        assert_eq!(sniff(b""), None);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        assert!(backoff(0) >= Duration::from_millis(RETRY_BASE_DELAY_MS));
+        assert!(backoff(2) >= Duration::from_millis(RETRY_BASE_DELAY_MS * 4));
+        assert_eq!(backoff(20), Duration::from_millis(RETRY_MAX_DELAY_MS));
+    }
+
+    #[test]
+    fn test_with_retries() {
+        assert_eq!(with_retries("new".to_string(), 0), "new");
+        assert_eq!(with_retries("new".to_string(), 1), "new after 1 retry");
+        assert_eq!(
+            with_retries("HTTP 503".to_string(), 3),
+            "HTTP 503 after 3 retries"
+        );
+    }
+
+    #[test]
+    fn test_remote_object_matches() {
+        let a = asset(b"hello");
+
+        let sha256 = RemoteObject {
+            checksum_sha256: Some(hex_to_base64(&a.sha256_hex)),
+            etag: Some("\"stale\"".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(sha256.matches(&a), Some("checksum"));
+
+        let crc32 = RemoteObject {
+            checksum_crc32: Some("NhCmhg==".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(crc32.matches(&a), Some("crc32"));
+
+        let etag = RemoteObject {
+            etag: Some("\"5D41402ABC4B2A76B9719D911017C592\"".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(etag.matches(&a), Some("etag"));
+
+        let multipart = RemoteObject {
+            etag: Some("\"5d41402abc4b2a76b9719d911017c592-2\"".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(multipart.matches(&a), None);
+
+        let changed = RemoteObject {
+            checksum_sha256: Some("AAAA".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(changed.matches(&a), None);
+    }
+
+    #[test]
+    fn test_remote_object_differences() {
+        let a = asset(b"hello");
+
+        let served = RemoteObject {
+            etag: Some(format!("\"{}\"", a.md5_hex)),
+            content_length: Some(5),
+            content_type: Some("TEXT/HTML".to_string()),
+            content_encoding: Some("identity".to_string()),
+            ..Default::default()
+        };
+        assert!(served.differences(&a).is_empty());
+
+        // A proxy gzipped the object and rewrote its type
+        let gzipped = RemoteObject {
+            etag: Some("\"0123456789abcdef0123456789abcdef\"".to_string()),
+            content_length: Some(25),
+            content_type: Some("application/octet-stream".to_string()),
+            content_encoding: Some("gzip".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            gzipped.differences(&a),
+            vec![
+                "checksum differs",
+                "25 bytes, uploaded 5",
+                "content-type application/octet-stream, uploaded text/html",
+                "content-encoding gzip added",
+            ]
+        );
+
+        let multipart = RemoteObject {
+            etag: Some("\"abc-2\"".to_string()),
+            content_length: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(multipart.differences(&a), vec!["no content-type"]);
+    }
+
+    #[test]
+    fn test_parse_list_page() {
+        let xml = r#"<ListBucketResult>
+            <IsTruncated>true</IsTruncated>
+            <Contents><Key>ow/img/a&amp;b.png</Key><Size>2048</Size>
+              <LastModified>2026-10-01T12:00:00.000Z</LastModified></Contents>
+            <Contents><Key>ow/index.html</Key><Size>512</Size></Contents>
+            <NextContinuationToken>1ueGcxLPRx1Tr</NextContinuationToken>
+        </ListBucketResult>"#;
+
+        let page = parse_list_page(xml);
+        assert_eq!(page.objects.len(), 2);
+        assert_eq!(page.objects[0].key, "ow/img/a&b.png");
+        assert_eq!(page.objects[0].size, 2048);
+        assert_eq!(page.objects[1].last_modified, "");
+        assert_eq!(page.next_token.as_deref(), Some("1ueGcxLPRx1Tr"));
+
+        let last = parse_list_page("<IsTruncated>false</IsTruncated>");
+        assert!(last.objects.is_empty() && last.next_token.is_none());
+    }
+
+    #[test]
+    fn test_canonical_query() {
+        assert_eq!(canonical_query(&[]), "");
+        assert_eq!(
+            canonical_query(&[("prefix", "ow/img a"), ("list-type", "2")]),
+            "list-type=2&prefix=ow%2Fimg%20a"
+        );
+    }
+
+    #[test]
+    fn test_classify_status() {
+        let body = "<?xml version=\"1.0\"?><Error><Code>SignatureDoesNotMatch</Code>\
+                    <Message>...</Message></Error>";
+        assert_eq!(error_code(body), Some("SignatureDoesNotMatch"));
+        assert_eq!(error_code("Forbidden"), None);
+
+        let classify =
+            |status: u16, code| classify_status(StatusCode::from_u16(status).unwrap(), code);
+
+        assert_eq!(
+            classify(403, Some("SignatureDoesNotMatch")),
+            FailureKind::Signature
+        );
+        assert_eq!(
+            classify(400, Some("AuthorizationHeaderMalformed")),
+            FailureKind::Signature
+        );
+        assert_eq!(classify(403, Some("AccessDenied")), FailureKind::Denied);
+        assert_eq!(classify(403, None), FailureKind::Denied);
+        assert_eq!(classify(404, Some("NoSuchBucket")), FailureKind::NoBucket);
+        assert_eq!(classify(503, Some("SlowDown")), FailureKind::Object);
+        assert!(!FailureKind::Object.is_fatal());
+    }
+
+    /// Storage whose every PUT fails with the same error
+    struct FailingStorage {
+        kind: FailureKind,
+        puts: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ObjectStorage for FailingStorage {
+        async fn head(&self, _key: &str) -> Result<Option<RemoteObject>, String> {
+            Ok(None)
+        }
+
+        async fn put(&self, _asset: &Asset) -> Result<u32, StorageError> {
+            self.puts.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+            Err(StorageError {
+                kind: self.kind,
+                message: "HTTP 403 Forbidden SignatureDoesNotMatch".to_string(),
+            })
+        }
+
+        fn target(&self) -> String {
+            "bucket 'assets' at https://s3.example.com".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_stops_on_fatal_error() {
+        let assets: Vec<Asset> = (0..200).map(|_| asset(b"hello")).collect();
+
+        let storage = FailingStorage {
+            kind: FailureKind::Signature,
+            puts: Default::default(),
+        };
+
+        let e = upload_assets(&storage, &assets).await.unwrap_err();
+        assert_eq!(e.kind, FailureKind::Signature);
+        assert!(
+            e.message
+                .contains("bucket 'assets' at https://s3.example.com")
+        );
+        assert!(e.message.contains("secret key"));
+
+        // Only the uploads already in flight were attempted
+        assert!(storage.puts.into_inner() <= UPLOAD_CONCURRENCY);
+
+        let storage = FailingStorage {
+            kind: FailureKind::Object,
+            puts: Default::default(),
+        };
+
+        assert_eq!(
+            upload_assets(&storage, &assets[..3]).await.unwrap(),
+            (0, 0, 3)
+        );
+        assert_eq!(storage.puts.into_inner(), 3);
+    }
+}
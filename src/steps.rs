@@ -0,0 +1,167 @@
+//! Step indicator for multi-phase commands (`[2/3] Uploading ⠙ 3.1s`), so a
+//! slow or stuck phase is visible while it runs.
+//!
+//! On a terminal the running step gets a spinner and a live timer, and each
+//! finished step leaves a line with its duration. Elsewhere (CI logs, pipes)
+//! only a header is printed when a step starts.
+
+use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Spinner redraw interval
+const TICK: Duration = Duration::from_millis(80);
+
+/// The step shown on the spinner line, shared with the spinner thread and
+/// with [`note`]
+static CURRENT: Mutex<Option<Current>> = Mutex::new(None);
+
+struct Current {
+    header: String,
+    start: Instant,
+    frame: usize,
+}
+
+impl Current {
+    fn draw(&self) {
+        eprint!(
+            "\r\x1b[2K  {} {} {}",
+            FRAMES[self.frame % FRAMES.len()].cyan(),
+            self.header,
+            format_elapsed(self.start.elapsed()).dimmed()
+        );
+        io::stderr().flush().ok();
+    }
+}
+
+/// Running spinner thread; dropping `stop` wakes it up to exit
+struct Spinner {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+pub struct Steps {
+    total: usize,
+    index: usize,
+    tty: bool,
+    spinner: Option<Spinner>,
+}
+
+impl Steps {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            index: 0,
+            tty: io::stderr().is_terminal(),
+            spinner: None,
+        }
+    }
+
+    /// Finish the running step, if any, and start the next one
+    pub fn start(&mut self, label: &str) {
+        self.finish();
+        self.index += 1;
+
+        let header = format!(
+            "{} {}",
+            format!("[{}/{}]", self.index, self.total).dimmed(),
+            label
+        );
+
+        if !self.tty {
+            eprintln!("{} {}...", "→".blue(), header);
+            return;
+        }
+
+        let current = Current {
+            header,
+            start: Instant::now(),
+            frame: 0,
+        };
+        current.draw();
+        *CURRENT.lock().unwrap() = Some(current);
+
+        let (stop, ticks) = mpsc::channel::<()>();
+
+        let thread = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = ticks.recv_timeout(TICK) {
+                if let Some(current) = CURRENT.lock().unwrap().as_mut() {
+                    current.frame += 1;
+                    current.draw();
+                }
+            }
+        });
+
+        self.spinner = Some(Spinner { stop, thread });
+    }
+
+    /// Mark the running step as done
+    pub fn finish(&mut self) {
+        self.end("✓".green().to_string());
+    }
+
+    /// Stop the spinner and replace its line with the step's outcome
+    fn end(&mut self, mark: String) {
+        let Some(spinner) = self.spinner.take() else {
+            return;
+        };
+
+        drop(spinner.stop);
+        spinner.thread.join().ok();
+
+        if let Some(current) = CURRENT.lock().unwrap().take() {
+            eprintln!(
+                "\r\x1b[2K  {} {} {}",
+                mark,
+                current.header,
+                format_elapsed(current.start.elapsed()).dimmed()
+            );
+        }
+    }
+}
+
+impl Drop for Steps {
+    /// A step still running when dropped was cut short by an error
+    fn drop(&mut self) {
+        self.end("✗".red().to_string());
+    }
+}
+
+/// Print a line on stderr without garbling the spinner: the spinner line is
+/// cleared first and redrawn below the message.
+pub fn note(message: &str) {
+    let current = CURRENT.lock().unwrap();
+
+    match current.as_ref() {
+        Some(current) => {
+            eprintln!("\r\x1b[2K{}", message);
+            current.draw();
+        }
+        None => eprintln!("{}", message),
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{} ms", elapsed.as_millis())
+    } else {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(42)), "42 ms");
+        assert_eq!(format_elapsed(Duration::from_millis(1300)), "1.3s");
+        assert_eq!(format_elapsed(Duration::from_secs(75)), "75.0s");
+    }
+}
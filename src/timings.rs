@@ -0,0 +1,257 @@
+//! Opt-in timing report (`--timings`), to tell slow backends from slow networks.
+//!
+//! Backends tag the futures they await with a [`Phase`]; overlapping spans of the
+//! same phase are merged, so parallel requests count once in wall-clock time.
+
+use crate::commands::latency::parse_server_timing;
+use colored::Colorize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Opening the database connection
+    Connect,
+    /// API requests
+    Request,
+    /// Database queries
+    Query,
+    /// Worker archive and asset uploads
+    Upload,
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [Phase::Connect, Phase::Request, Phase::Query, Phase::Upload];
+
+    fn label(self) -> &'static str {
+        match self {
+            Phase::Connect => "Connect:",
+            Phase::Request => "Requests:",
+            Phase::Query => "Queries:",
+            Phase::Upload => "Upload:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    phase: Phase,
+    start: Instant,
+    end: Instant,
+    /// Time the server reported spending (`Server-Timing`), in ms
+    server_ms: f64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SPANS: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+
+/// Start recording spans (once, from `main`, when `--timings` is set)
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn record(phase: Phase, start: Instant, server_ms: f64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let span = Span {
+        phase,
+        start,
+        end: Instant::now(),
+        server_ms,
+    };
+
+    if let Ok(mut spans) = SPANS.lock() {
+        spans.push(span);
+    }
+}
+
+/// Future wrapper recording how long the inner future took
+pub struct Timed<F: Future> {
+    inner: Pin<Box<F>>,
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl<F: Future> Future for Timed<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let output = std::task::ready!(self.inner.as_mut().poll(cx));
+
+        record(self.phase, start, 0.0);
+        Poll::Ready(output)
+    }
+}
+
+pub trait TimedExt: Future + Sized {
+    fn timed(self, phase: Phase) -> Timed<Self> {
+        Timed {
+            inner: Box::pin(self),
+            phase,
+            start: None,
+        }
+    }
+}
+
+impl<F: Future> TimedExt for F {}
+
+pub trait TimedSend {
+    /// Send the request, recording its duration and the server's `Server-Timing`
+    fn timed_send(
+        self,
+        phase: Phase,
+    ) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl TimedSend for reqwest::RequestBuilder {
+    async fn timed_send(self, phase: Phase) -> reqwest::Result<reqwest::Response> {
+        let start = Instant::now();
+        let result = self.send().await;
+
+        let server_ms = match &result {
+            Ok(response) => server_time(response.headers()),
+            Err(_) => 0.0,
+        };
+
+        record(phase, start, server_ms);
+        result
+    }
+}
+
+/// Total server time from `Server-Timing` headers. A `total` metric wins over
+/// the sum of the individual ones, which may overlap.
+fn server_time(headers: &reqwest::header::HeaderMap) -> f64 {
+    let metrics: Vec<(String, f64)> = headers
+        .get_all("server-timing")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(parse_server_timing)
+        .collect();
+
+    match metrics.iter().find(|(name, _)| name == "total") {
+        Some((_, dur)) => *dur,
+        None => metrics.iter().map(|(_, dur)| dur).sum(),
+    }
+}
+
+/// Wall-clock time covered by the spans, counting overlaps once
+fn merged_duration(spans: &[Span]) -> Duration {
+    let mut intervals: Vec<(Instant, Instant)> = spans.iter().map(|s| (s.start, s.end)).collect();
+    intervals.sort_by_key(|(start, _)| *start);
+
+    let mut total = Duration::ZERO;
+    let mut current: Option<(Instant, Instant)> = None;
+
+    for (start, end) in intervals {
+        current = match current {
+            Some((cur_start, cur_end)) if start <= cur_end => Some((cur_start, cur_end.max(end))),
+            Some((cur_start, cur_end)) => {
+                total += cur_end - cur_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+
+    if let Some((start, end)) = current {
+        total += end - start;
+    }
+
+    total
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Print the timing report for the command started at `started` (on stderr,
+/// so piped output stays clean)
+pub fn print_report(started: Instant) {
+    let total = started.elapsed();
+    let spans = SPANS.lock().map(|s| s.clone()).unwrap_or_default();
+
+    eprintln!();
+    eprintln!("{}", "Timings:".bold());
+
+    for phase in Phase::ALL {
+        let phase_spans: Vec<Span> = spans.iter().filter(|s| s.phase == phase).copied().collect();
+
+        if phase_spans.is_empty() {
+            // API aliases connect lazily, the handshake is part of the first request
+            let first_request = spans
+                .iter()
+                .filter(|s| s.phase == Phase::Request)
+                .min_by_key(|s| s.start);
+
+            if let (Phase::Connect, Some(first)) = (phase, first_request) {
+                eprintln!(
+                    "  {:10} {}",
+                    phase.label(),
+                    format!(
+                        "in first request ({:.2} ms, incl. TLS handshake)",
+                        ms(first.end - first.start)
+                    )
+                    .dimmed()
+                );
+            }
+            continue;
+        }
+
+        let wall = ms(merged_duration(&phase_spans));
+        let server: f64 = phase_spans.iter().map(|s| s.server_ms).sum();
+
+        let detail = if server > 0.0 {
+            format!(
+                "({}, server {:.2} ms, network ~{:.2} ms)",
+                phase_spans.len(),
+                server,
+                (wall - server).max(0.0)
+            )
+        } else if phase == Phase::Connect {
+            String::new()
+        } else {
+            format!("({})", phase_spans.len())
+        };
+
+        eprintln!(
+            "  {:10} {:>10.2} ms {}",
+            phase.label(),
+            wall,
+            detail.dimmed()
+        );
+    }
+
+    eprintln!("  {:10} {:>10.2} ms", "Total:", ms(total));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_duration() {
+        let t0 = Instant::now();
+        let span = |start: u64, end: u64| Span {
+            phase: Phase::Request,
+            start: t0 + Duration::from_millis(start),
+            end: t0 + Duration::from_millis(end),
+            server_ms: 0.0,
+        };
+
+        assert_eq!(merged_duration(&[]), Duration::ZERO);
+
+        // Parallel requests overlap, sequential ones add up
+        let spans = [span(0, 100), span(50, 120), span(200, 250)];
+        assert_eq!(merged_duration(&spans), Duration::from_millis(170));
+
+        let spans = [span(10, 20), span(0, 100)];
+        assert_eq!(merged_duration(&spans), Duration::from_millis(100));
+    }
+}